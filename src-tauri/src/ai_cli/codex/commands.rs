@@ -6,6 +6,7 @@ use super::config::{
     ensure_cli_dir, get_codex_asset, get_codex_cli_path, get_embedded_cli_path, CODEX_RELEASES_API,
 };
 use crate::ai_cli::types::{AiCliAuthStatus, AiCliStatus};
+use crate::ai_cli::updates::extract_version;
 use flate2::read::GzDecoder;
 use serde::{Deserialize, Serialize};
 use std::io::Cursor;
@@ -61,13 +62,6 @@ fn emit_progress(app: &AppHandle, stage: &str, message: &str, percent: u8) {
     log::debug!("Codex install progress: {} - {} ({}%)", stage, message, percent);
 }
 
-/// Extract version number from tag name (e.g., "v0.1.0" -> "0.1.0")
-fn extract_version_number(tag: &str) -> String {
-    tag.trim_start_matches('v')
-        .trim_start_matches("codex-")
-        .to_string()
-}
-
 /// Check if Codex CLI is installed and get version info
 #[tauri::command]
 pub fn check_codex_cli_installed(app: AppHandle) -> AiCliStatus {
@@ -233,7 +227,9 @@ pub async fn get_available_codex_versions() -> Result<Vec<CodexReleaseInfo>, Str
 
     for r in releases {
         let info = CodexReleaseInfo {
-            version: extract_version_number(&r.tag_name),
+            version: extract_version(&r.tag_name)
+                .unwrap_or(&r.tag_name)
+                .to_string(),
             tag_name: r.tag_name,
             published_at: r.published_at,
             prerelease: r.prerelease,
@@ -349,6 +345,7 @@ pub async fn install_codex_cli(
     version: Option<String>,
 ) -> Result<String, String> {
     log::info!("Installing Codex CLI from GitHub releases");
+    let _install = crate::ai_cli::InstallLock::acquire("Codex CLI")?;
 
     // Check no running sessions (would be problematic to replace binary)
     if !crate::chat::registry::get_running_sessions().is_empty() {
@@ -569,6 +566,7 @@ pub async fn install_codex_cli(
 #[tauri::command]
 pub async fn uninstall_codex_cli(app: AppHandle) -> Result<String, String> {
     log::info!("Uninstalling Codex CLI");
+    let _install = crate::ai_cli::InstallLock::acquire("Codex CLI")?;
 
     let binary_path = get_embedded_cli_path(&app)?;
 
@@ -0,0 +1,149 @@
+//! Installed CLI detection in one call
+//!
+//! The per-CLI `check_*_installed` commands each spawn a `--version` probe,
+//! so checking every CLI at startup runs them one after another. This probes
+//! gh, glab and the AI CLIs concurrently, each with its own timeout, so one
+//! slow binary can't stall the rest.
+
+use serde::{Deserialize, Serialize};
+use std::path::{Path, PathBuf};
+use std::time::Duration;
+use tauri::{AppHandle, Manager};
+
+use super::codex::config::get_codex_cli_path;
+use super::gemini::config::get_gemini_cli_path;
+use super::health::run_with_timeout;
+use super::kimi::config::get_kimi_cli_path;
+use super::updates::extract_version;
+use crate::claude_cli::get_cli_binary_path;
+use crate::gh_cli::get_gh_cli_binary_path;
+use crate::glab_cli::get_glab_cli_binary_path;
+
+/// A `--version` probe that takes longer than this reports no version
+const VERSION_TIMEOUT: Duration = Duration::from_secs(5);
+
+/// Where an installed CLI comes from
+#[derive(Debug, Clone, Copy, Serialize, Deserialize, PartialEq, Eq)]
+#[serde(rename_all = "lowercase")]
+pub enum CliSource {
+    /// Installed by Jean into its app data directory
+    Embedded,
+    /// Found on the system (PATH or a common install location)
+    Global,
+}
+
+/// Installation status of a single CLI
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub struct CliStatus {
+    /// Whether the CLI is installed
+    pub installed: bool,
+    /// Installed version, if `--version` answered in time
+    pub version: Option<String>,
+    /// Path to the CLI binary (if installed)
+    pub path: Option<String>,
+    /// Where the binary comes from (if installed)
+    pub source: Option<CliSource>,
+}
+
+/// Installation status of every CLI Jean uses
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct AllCliStatus {
+    pub gh: CliStatus,
+    pub glab: CliStatus,
+    pub claude: CliStatus,
+    pub codex: CliStatus,
+    pub gemini: CliStatus,
+    pub kimi: CliStatus,
+}
+
+/// Check one CLI: whether its binary exists, its version and where it's from
+fn probe_cli(path: Result<PathBuf, String>, app_data_dir: Option<&Path>) -> CliStatus {
+    let path = match path {
+        Ok(path) if path.exists() => path,
+        _ => return CliStatus::default(),
+    };
+
+    let command = crate::platform::cli_command(&path, &["--version"]);
+    let version = match run_with_timeout(command, VERSION_TIMEOUT) {
+        // Unrecognized output is left out rather than passed on, since the
+        // frontend compares this against the latest release
+        Ok(stdout) => extract_version(&stdout).map(str::to_string),
+        Err(e) => {
+            log::warn!("Failed to get version of {}: {e}", path.display());
+            None
+        }
+    };
+    let source = match app_data_dir {
+        Some(dir) if path.starts_with(dir) => CliSource::Embedded,
+        _ => CliSource::Global,
+    };
+
+    CliStatus {
+        installed: true,
+        version,
+        path: Some(path.to_string_lossy().to_string()),
+        source: Some(source),
+    }
+}
+
+/// Check which CLIs are installed, probing them all concurrently
+#[tauri::command]
+pub async fn check_all_clis(app: AppHandle) -> Result<AllCliStatus, String> {
+    log::trace!("Checking all CLI installations");
+    let app_data_dir = app.path().app_data_dir().ok();
+
+    let probe = |resolve: fn(&AppHandle) -> Result<PathBuf, String>| {
+        let app = app.clone();
+        let app_data_dir = app_data_dir.clone();
+        tokio::task::spawn_blocking(move || probe_cli(resolve(&app), app_data_dir.as_deref()))
+    };
+    // Spawned tasks start right away, so awaiting them in turn still runs the probes in parallel
+    let gh = probe(get_gh_cli_binary_path);
+    let glab = probe(get_glab_cli_binary_path);
+    let claude = probe(get_cli_binary_path);
+    let codex = probe(get_codex_cli_path);
+    let gemini = probe(|_| get_gemini_cli_path());
+    let kimi = probe(|_| get_kimi_cli_path());
+
+    let joined = |e: tokio::task::JoinError| format!("CLI check task failed: {e}");
+    Ok(AllCliStatus {
+        gh: gh.await.map_err(joined)?,
+        glab: glab.await.map_err(joined)?,
+        claude: claude.await.map_err(joined)?,
+        codex: codex.await.map_err(joined)?,
+        gemini: gemini.await.map_err(joined)?,
+        kimi: kimi.await.map_err(joined)?,
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[cfg(unix)]
+    #[test]
+    fn test_probe_cli() {
+        use std::os::unix::fs::PermissionsExt;
+
+        let missing = probe_cli(Ok(PathBuf::from("/nonexistent/gh")), None);
+        assert!(!missing.installed);
+        assert!(missing.source.is_none());
+
+        let dir = tempfile::tempdir().unwrap();
+        let script = dir.path().join("glab");
+        std::fs::write(
+            &script,
+            "#!/bin/sh\necho 'glab version 1.36.0 (2024-01-15)'\n",
+        )
+        .unwrap();
+        std::fs::set_permissions(&script, std::fs::Permissions::from_mode(0o755)).unwrap();
+
+        let status = probe_cli(Ok(script.clone()), Some(dir.path()));
+        assert!(status.installed);
+        assert_eq!(status.version.as_deref(), Some("1.36.0"));
+        assert_eq!(status.source, Some(CliSource::Embedded));
+
+        let status = probe_cli(Ok(script), Some(Path::new("/nonexistent")));
+        assert_eq!(status.source, Some(CliSource::Global));
+    }
+}
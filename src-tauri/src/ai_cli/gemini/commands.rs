@@ -90,6 +90,7 @@ pub fn check_gemini_cli_auth() -> AiCliAuthStatus {
 #[tauri::command]
 pub async fn install_gemini_cli() -> Result<String, String> {
     log::info!("Installing Gemini CLI via npm");
+    let _install = crate::ai_cli::InstallLock::acquire("Gemini CLI")?;
 
     // Install via npm global
     let output = Command::new("npm")
@@ -0,0 +1,311 @@
+//! Provider health checks
+//!
+//! The `check_*_auth` commands only inspect config files and env vars. These
+//! checks make a minimal real request instead, so a revoked key or expired
+//! login is reported as a failure with the provider's own error rather than
+//! looking configured.
+
+use serde::{Deserialize, Serialize};
+use std::io::Read;
+use std::path::PathBuf;
+use std::process::{Command, Stdio};
+use std::sync::{mpsc, Arc, Mutex};
+use std::thread;
+use std::time::{Duration, Instant};
+use tauri::AppHandle;
+
+use super::codex::config::get_codex_cli_path;
+use super::gemini::config::get_gemini_cli_path;
+use super::kimi::config::get_kimi_cli_path;
+use super::types::AiCliProvider;
+use crate::claude_usage::api::fetch_usage_limits_uncached;
+
+/// A health check that takes longer than this is reported as failed
+const HEALTH_CHECK_TIMEOUT: Duration = Duration::from_secs(30);
+
+/// How often a running CLI is polled for exit
+const EXIT_POLL_INTERVAL: Duration = Duration::from_millis(50);
+
+/// How long to keep reading output after the CLI exits, in case something it
+/// spawned still holds the pipes open
+const OUTPUT_DRAIN_TIMEOUT: Duration = Duration::from_secs(2);
+
+/// Prompt for CLI pings; asks for the shortest possible reply
+const PING_PROMPT: &str = "Reply with just OK.";
+
+/// Result of a real request to a provider
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct ProviderHealth {
+    /// Provider ID (claude, gemini, codex, kimi)
+    pub provider: String,
+    /// Whether the request succeeded
+    pub ok: bool,
+    /// Time until the request finished or failed
+    pub latency_ms: u64,
+    /// The provider's error when the request failed
+    pub error: Option<String>,
+}
+
+impl ProviderHealth {
+    fn new(provider: &str, latency: Duration, result: Result<(), String>) -> Self {
+        Self {
+            provider: provider.to_string(),
+            ok: result.is_ok(),
+            latency_ms: latency.as_millis() as u64,
+            error: result.err(),
+        }
+    }
+}
+
+/// CLI arguments for a minimal non-interactive request
+fn ping_args(provider: &AiCliProvider) -> Vec<&'static str> {
+    match provider {
+        AiCliProvider::Claude => Vec::new(),
+        AiCliProvider::Gemini => vec!["-p", PING_PROMPT],
+        AiCliProvider::Codex => vec![
+            "exec",
+            "--sandbox",
+            "read-only",
+            "--skip-git-repo-check",
+            PING_PROMPT,
+        ],
+        AiCliProvider::Kimi => vec!["--print", "-p", PING_PROMPT],
+    }
+}
+
+/// The most useful line of a failed CLI's output
+///
+/// CLIs print progress before the error, so the last non-empty stderr line is
+/// preferred, then stdout, then the exit code.
+fn failure_message(code: Option<i32>, stdout: &str, stderr: &str) -> String {
+    let last_line = |s: &str| {
+        s.lines()
+            .map(str::trim)
+            .rfind(|line| !line.is_empty())
+            .map(str::to_string)
+    };
+    last_line(stderr)
+        .or_else(|| last_line(stdout))
+        .unwrap_or_else(|| match code {
+            Some(code) => format!("Command failed with exit code {code}"),
+            None => "Command failed".to_string(),
+        })
+}
+
+/// Output read from a pipe on a background thread
+struct Drain {
+    output: Arc<Mutex<Vec<u8>>>,
+    done: mpsc::Receiver<()>,
+}
+
+/// Read a pipe to the end on a background thread
+fn drain<R: Read + Send + 'static>(pipe: Option<R>) -> Drain {
+    let output = Arc::new(Mutex::new(Vec::new()));
+    let (done_tx, done) = mpsc::channel();
+    let sink = Arc::clone(&output);
+    thread::spawn(move || {
+        if let Some(mut pipe) = pipe {
+            let mut buf = [0u8; 8192];
+            loop {
+                match pipe.read(&mut buf) {
+                    Ok(0) => break,
+                    Ok(n) => sink.lock().unwrap().extend_from_slice(&buf[..n]),
+                    Err(e) if e.kind() == std::io::ErrorKind::Interrupted => continue,
+                    Err(_) => break,
+                }
+            }
+        }
+        let _ = done_tx.send(());
+    });
+    Drain { output, done }
+}
+
+impl Drain {
+    /// Everything read by `deadline`, even if the pipe is still open then
+    fn collect(self, deadline: Instant) -> String {
+        let _ = self
+            .done
+            .recv_timeout(deadline.saturating_duration_since(Instant::now()));
+        let output = self.output.lock().unwrap();
+        String::from_utf8_lossy(&output).into_owned()
+    }
+}
+
+/// Run a command to completion and return its stdout, killing it if it
+/// outlives the timeout
+///
+/// The command runs in its own process group, so a timeout also kills anything
+/// it spawned. Output is only awaited briefly after exit, so a leftover process
+/// holding the pipes can't stall the caller.
+pub fn run_with_timeout(mut command: Command, timeout: Duration) -> Result<String, String> {
+    #[cfg(unix)]
+    {
+        use std::os::unix::process::CommandExt;
+        command.process_group(0);
+    }
+
+    let mut child = command
+        .stdin(Stdio::null())
+        .stdout(Stdio::piped())
+        .stderr(Stdio::piped())
+        .spawn()
+        .map_err(|e| format!("Failed to start CLI: {e}"))?;
+
+    // Drain output on separate threads so a chatty CLI can't fill the pipe and stall
+    let stdout = drain(child.stdout.take());
+    let stderr = drain(child.stderr.take());
+
+    let deadline = Instant::now() + timeout;
+    let status = loop {
+        match child.try_wait() {
+            Ok(Some(status)) => break status,
+            Ok(None) if Instant::now() >= deadline => {
+                if let Err(e) = crate::platform::kill_process_tree(child.id()) {
+                    log::warn!("Failed to kill timed out CLI: {e}");
+                    let _ = child.kill();
+                }
+                let _ = child.wait();
+                return Err(format!("Timed out after {}s", timeout.as_secs()));
+            }
+            Ok(None) => thread::sleep(EXIT_POLL_INTERVAL),
+            Err(e) => return Err(format!("Failed to wait for CLI: {e}")),
+        }
+    };
+
+    let output_deadline = Instant::now() + OUTPUT_DRAIN_TIMEOUT;
+    let stdout = stdout.collect(output_deadline);
+    if status.success() {
+        return Ok(stdout);
+    }
+    let stderr = stderr.collect(output_deadline);
+    Err(failure_message(status.code(), &stdout, &stderr))
+}
+
+/// Send a ping prompt through a provider's CLI
+async fn ping_cli(
+    provider: &AiCliProvider,
+    cli_path: Result<PathBuf, String>,
+) -> Result<(), String> {
+    let path = cli_path.map_err(|e| format!("{} not installed: {e}", provider.display_name()))?;
+    let mut command = crate::platform::cli_command(&path, &ping_args(provider));
+    // Nothing should be read or written, so run outside any project
+    command.current_dir(std::env::temp_dir());
+
+    tokio::task::spawn_blocking(move || run_with_timeout(command, HEALTH_CHECK_TIMEOUT))
+        .await
+        .map_err(|e| format!("Health check task failed: {e}"))?
+        .map(|_| ())
+}
+
+/// Fetch Claude usage limits, bypassing the cache so stale data can't mask a revoked login
+async fn check_claude() -> Result<(), String> {
+    match tokio::time::timeout(HEALTH_CHECK_TIMEOUT, fetch_usage_limits_uncached()).await {
+        Ok(result) => result.map(|_| ()).map_err(|e| e.to_string()),
+        Err(_) => Err(format!(
+            "Timed out after {}s",
+            HEALTH_CHECK_TIMEOUT.as_secs()
+        )),
+    }
+}
+
+/// Test a provider end to end with a minimal real request
+///
+/// Claude fetches usage limits; Gemini, Codex and Kimi send a one-line prompt
+/// through their CLI (Codex in a read-only sandbox). A missing CLI, rejected
+/// credentials or a timeout is reported in the returned health rather than as
+/// an error; only an unknown provider fails the command.
+#[tauri::command]
+pub async fn test_provider(app: AppHandle, provider: String) -> Result<ProviderHealth, String> {
+    let provider_id = provider.to_lowercase();
+    let provider = AiCliProvider::from_str(&provider_id)
+        .ok_or_else(|| format!("Unknown provider: {provider}"))?;
+
+    let start = Instant::now();
+    let result = match provider {
+        AiCliProvider::Claude => check_claude().await,
+        AiCliProvider::Gemini => ping_cli(&provider, get_gemini_cli_path()).await,
+        AiCliProvider::Codex => ping_cli(&provider, get_codex_cli_path(&app)).await,
+        AiCliProvider::Kimi => ping_cli(&provider, get_kimi_cli_path()).await,
+    };
+    let health = ProviderHealth::new(&provider_id, start.elapsed(), result);
+
+    log::debug!(
+        "Provider health for {provider_id}: ok={} latency={}ms",
+        health.ok,
+        health.latency_ms
+    );
+    Ok(health)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_failure_message_prefers_last_stderr_line() {
+        let stderr = "Loading config...\nError: 401 Unauthorized: invalid API key\n\n";
+        assert_eq!(
+            failure_message(Some(1), "partial output", stderr),
+            "Error: 401 Unauthorized: invalid API key"
+        );
+        assert_eq!(
+            failure_message(Some(1), "auth failed\n", "  \n"),
+            "auth failed"
+        );
+        assert_eq!(
+            failure_message(Some(2), "", ""),
+            "Command failed with exit code 2"
+        );
+    }
+
+    #[cfg(unix)]
+    #[test]
+    fn test_run_with_timeout_kills_hung_command() {
+        let mut command = Command::new("sh");
+        command.args(["-c", "sleep 5"]);
+        let start = Instant::now();
+        let err = run_with_timeout(command, Duration::from_millis(100)).unwrap_err();
+        assert!(err.starts_with("Timed out"));
+        assert!(start.elapsed() < Duration::from_secs(5));
+    }
+
+    #[cfg(unix)]
+    #[test]
+    fn test_run_with_timeout_does_not_wait_on_leftover_children() {
+        // The backgrounded sleep inherits stdout and keeps it open
+        let mut command = Command::new("sh");
+        command.args(["-c", "sleep 10 & echo ok"]);
+        let start = Instant::now();
+        assert_eq!(
+            run_with_timeout(command, Duration::from_secs(5)).unwrap(),
+            "ok\n"
+        );
+        assert!(start.elapsed() < Duration::from_secs(5));
+
+        // A timeout takes the whole process group down
+        let mut command = Command::new("sh");
+        command.args(["-c", "sleep 10 & sleep 10"]);
+        let start = Instant::now();
+        assert!(run_with_timeout(command, Duration::from_millis(100)).is_err());
+        assert!(start.elapsed() < Duration::from_secs(5));
+    }
+
+    #[cfg(unix)]
+    #[test]
+    fn test_run_with_timeout_reports_real_error() {
+        let mut command = Command::new("sh");
+        command.args(["-c", "echo 'Error: key revoked' >&2; exit 1"]);
+        assert_eq!(
+            run_with_timeout(command, Duration::from_secs(5)).unwrap_err(),
+            "Error: key revoked"
+        );
+
+        let mut command = Command::new("sh");
+        command.args(["-c", "echo OK"]);
+        assert_eq!(
+            run_with_timeout(command, Duration::from_secs(5)).unwrap(),
+            "OK\n"
+        );
+    }
+}
@@ -113,6 +113,7 @@ pub fn check_kimi_cli_auth() -> AiCliAuthStatus {
 #[tauri::command]
 pub async fn install_kimi_cli() -> Result<String, String> {
     log::info!("Installing Kimi CLI");
+    let _install = crate::ai_cli::InstallLock::acquire("Kimi CLI")?;
 
     // Check if uv is installed (required for Kimi CLI)
     if !is_uv_installed() {
@@ -179,6 +180,7 @@ pub async fn install_kimi_cli() -> Result<String, String> {
 #[allow(dead_code)]
 pub async fn install_kimi_cli_via_uv() -> Result<String, String> {
     log::info!("Installing Kimi CLI via uv");
+    let _install = crate::ai_cli::InstallLock::acquire("Kimi CLI")?;
 
     if !is_uv_installed() {
         return Err("uv is not installed. Please install uv first.".to_string());
@@ -4,6 +4,79 @@
 //! (Claude, Gemini, Codex, Kimi) with a unified interface.
 
 pub mod codex;
+pub mod detect;
 pub mod gemini;
+pub mod health;
 pub mod kimi;
+pub mod models;
 pub mod types;
+pub mod updates;
+
+use once_cell::sync::Lazy;
+use std::collections::HashSet;
+use std::sync::{Mutex, PoisonError};
+
+/// CLIs with an install (or uninstall) in progress
+static INSTALLS_IN_PROGRESS: Lazy<Mutex<HashSet<&'static str>>> =
+    Lazy::new(|| Mutex::new(HashSet::new()));
+
+/// Exclusive claim on installing a CLI, released when dropped
+///
+/// Two installs of the same CLI would race on its temp directory and final
+/// copy, so the second one fails fast instead of waiting. Releasing on drop
+/// covers every early `?` return as well as success.
+pub struct InstallLock {
+    cli: &'static str,
+}
+
+impl InstallLock {
+    /// Claim the install of `cli` (e.g. "Codex CLI")
+    pub fn acquire(cli: &'static str) -> Result<Self, String> {
+        let mut installs = INSTALLS_IN_PROGRESS
+            .lock()
+            .unwrap_or_else(PoisonError::into_inner);
+        if !installs.insert(cli) {
+            return Err(format!("{cli} install already in progress"));
+        }
+        Ok(Self { cli })
+    }
+}
+
+impl Drop for InstallLock {
+    fn drop(&mut self) {
+        INSTALLS_IN_PROGRESS
+            .lock()
+            .unwrap_or_else(PoisonError::into_inner)
+            .remove(self.cli);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_install_lock_rejects_concurrent_install_until_released() {
+        let first = InstallLock::acquire("Test CLI").unwrap();
+        assert_eq!(
+            InstallLock::acquire("Test CLI").err().as_deref(),
+            Some("Test CLI install already in progress")
+        );
+        // Other CLIs are independent
+        assert!(InstallLock::acquire("Other Test CLI").is_ok());
+
+        drop(first);
+        assert!(InstallLock::acquire("Test CLI").is_ok());
+    }
+
+    #[test]
+    fn test_install_lock_released_on_error_path() {
+        fn failing_install() -> Result<(), String> {
+            let _install = InstallLock::acquire("Failing Test CLI")?;
+            Err("Download failed".to_string())
+        }
+
+        assert_eq!(failing_install().unwrap_err(), "Download failed");
+        assert_eq!(failing_install().unwrap_err(), "Download failed");
+    }
+}
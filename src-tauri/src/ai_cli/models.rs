@@ -0,0 +1,126 @@
+//! Model catalog
+//!
+//! Known models per AI CLI provider, so the model picker doesn't hard-code
+//! them. Adding a model is a one-line edit to `MODELS`; its cost tier comes
+//! from the shared price table in `provider_usage::types`, and its vision
+//! support from the provider's chat backend.
+
+use serde::{Deserialize, Serialize};
+
+use crate::chat::model_supports_vision;
+use crate::provider_usage::types::model_pricing;
+
+/// Relative price of a model compared to others
+#[derive(Debug, Clone, Copy, Serialize, Deserialize, PartialEq, Eq)]
+#[serde(rename_all = "snake_case")]
+pub enum CostTier {
+    Low,
+    Medium,
+    High,
+}
+
+impl CostTier {
+    /// Tier for a model, by its input price per million tokens
+    fn for_model(model: &str) -> Self {
+        let input_per_1m = model_pricing(model).input_per_1m;
+        if input_per_1m < 1.5 {
+            CostTier::Low
+        } else if input_per_1m < 4.0 {
+            CostTier::Medium
+        } else {
+            CostTier::High
+        }
+    }
+}
+
+/// A model the app knows how to run
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
+#[serde(rename_all = "camelCase")]
+pub struct ModelInfo {
+    /// Value passed to the CLI's `--model` flag
+    pub id: String,
+    /// Provider ID (claude, gemini, codex, kimi)
+    pub provider: String,
+    pub display_name: String,
+    /// Context window size in tokens
+    pub context_window: u64,
+    /// Whether pasted images are sent to the model
+    pub supports_vision: bool,
+    pub cost_tier: CostTier,
+}
+
+/// (provider, id, display name, context window)
+#[rustfmt::skip]
+const MODELS: &[(&str, &str, &str, u64)] = &[
+    ("claude", "opus", "Claude Opus", 200_000),
+    ("claude", "sonnet", "Claude Sonnet", 200_000),
+    ("claude", "haiku", "Claude Haiku", 200_000),
+    ("gemini", "gemini-3-flash-preview", "Gemini 3 Flash", 1_048_576),
+    ("gemini", "gemini-3-pro-preview", "Gemini 3 Pro", 1_048_576),
+    ("codex", "gpt-5.2-codex", "GPT-5.2 Codex", 400_000),
+    ("kimi", "kimi-code/kimi-for-coding", "Kimi Code", 262_144),
+];
+
+/// Known models for a provider, in picker order
+pub fn models_for_provider(provider: &str) -> Vec<ModelInfo> {
+    MODELS
+        .iter()
+        .filter(|(p, ..)| *p == provider)
+        .map(|&(provider, id, display_name, context_window)| ModelInfo {
+            id: id.to_string(),
+            provider: provider.to_string(),
+            display_name: display_name.to_string(),
+            context_window,
+            supports_vision: model_supports_vision(provider, Some(id)),
+            cost_tier: CostTier::for_model(id),
+        })
+        .collect()
+}
+
+/// List the known models for a provider (claude, gemini, codex, kimi)
+#[tauri::command]
+pub async fn list_models(provider: String) -> Result<Vec<ModelInfo>, String> {
+    let models = models_for_provider(&provider.to_lowercase());
+    if models.is_empty() {
+        return Err(format!("Unknown provider: {provider}"));
+    }
+    Ok(models)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_every_provider_has_models() {
+        for provider in ["claude", "gemini", "codex", "kimi"] {
+            assert!(
+                !models_for_provider(provider).is_empty(),
+                "{provider} has no models"
+            );
+        }
+        assert!(models_for_provider("other").is_empty());
+    }
+
+    #[test]
+    fn test_claude_catalog_tiers_follow_pricing() {
+        let models = models_for_provider("claude");
+        let ids: Vec<&str> = models.iter().map(|m| m.id.as_str()).collect();
+        assert_eq!(ids, ["opus", "sonnet", "haiku"]);
+
+        let tiers: Vec<CostTier> = models.iter().map(|m| m.cost_tier).collect();
+        assert_eq!(tiers, [CostTier::High, CostTier::Medium, CostTier::Low]);
+    }
+
+    #[test]
+    fn test_vision_support_comes_from_chat_backends() {
+        let vision = |provider| -> Vec<bool> {
+            models_for_provider(provider)
+                .iter()
+                .map(|m| m.supports_vision)
+                .collect()
+        };
+        assert_eq!(vision("claude"), [true, true, true]);
+        assert_eq!(vision("kimi"), [false]);
+    }
+}
@@ -0,0 +1,353 @@
+//! CLI update checks
+//!
+//! Compares each installed AI CLI's version against the latest published
+//! release. Latest versions are cached in `{app_data}/cli-latest-versions.json`
+//! with a daily TTL so startup checks don't hit registry rate limits.
+
+use serde::{Deserialize, Serialize};
+use std::collections::HashMap;
+use std::fs;
+use std::path::PathBuf;
+use std::time::{SystemTime, UNIX_EPOCH};
+use tauri::{AppHandle, Manager};
+
+/// How long a fetched latest version stays fresh (one day)
+const LATEST_VERSION_TTL_SECS: u64 = 24 * 60 * 60;
+
+/// Update status for one installed CLI
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct CliUpdateStatus {
+    /// Provider ID (claude, codex, gemini, kimi)
+    pub provider: String,
+    /// Installed version (normalized, e.g. "1.0.28")
+    pub installed_version: String,
+    /// Latest published version, if known
+    pub latest_version: Option<String>,
+    /// Whether the latest version is newer than the installed one
+    pub update_available: bool,
+}
+
+/// Cached latest versions, keyed by provider ID
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+struct LatestVersionCache {
+    entries: HashMap<String, CachedLatestVersion>,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+struct CachedLatestVersion {
+    version: String,
+    /// Unix timestamp (seconds) when the version was fetched
+    fetched_at: u64,
+}
+
+fn get_cache_path(app: &AppHandle) -> Result<PathBuf, String> {
+    let app_data_dir = app
+        .path()
+        .app_data_dir()
+        .map_err(|e| format!("Failed to get app data directory: {e}"))?;
+    Ok(app_data_dir.join("cli-latest-versions.json"))
+}
+
+fn load_cache(app: &AppHandle) -> LatestVersionCache {
+    get_cache_path(app)
+        .ok()
+        .and_then(|path| fs::read_to_string(path).ok())
+        .and_then(|content| serde_json::from_str(&content).ok())
+        .unwrap_or_default()
+}
+
+/// Save the cache (atomic write: temp file + rename)
+fn save_cache(app: &AppHandle, cache: &LatestVersionCache) -> Result<(), String> {
+    let path = get_cache_path(app)?;
+    if let Some(parent) = path.parent() {
+        fs::create_dir_all(parent)
+            .map_err(|e| format!("Failed to create app data directory: {e}"))?;
+    }
+
+    let content = serde_json::to_string_pretty(cache)
+        .map_err(|e| format!("Failed to serialize version cache: {e}"))?;
+    let temp_path = path.with_extension("tmp");
+    fs::write(&temp_path, content).map_err(|e| format!("Failed to write version cache: {e}"))?;
+    fs::rename(&temp_path, &path).map_err(|e| format!("Failed to finalize version cache: {e}"))
+}
+
+fn now_secs() -> u64 {
+    SystemTime::now()
+        .duration_since(UNIX_EPOCH)
+        .map(|d| d.as_secs())
+        .unwrap_or(0)
+}
+
+/// Whether the cached latest version for a provider is missing or expired
+fn needs_refresh(cache: &LatestVersionCache, provider: &str, now: u64) -> bool {
+    cache
+        .entries
+        .get(provider)
+        .is_none_or(|entry| now.saturating_sub(entry.fetched_at) >= LATEST_VERSION_TTL_SECS)
+}
+
+/// The first version-like token in CLI output or a release tag
+///
+/// Handles "1.0.28", "v1.0.28", "codex-cli 0.46.0", "kimi, version 0.5.1",
+/// "1.0.28 (Claude Code)" and tags like "rust-v0.46.0". Pre-release suffixes
+/// ("1.2.0-beta.1") are kept.
+pub fn extract_version(text: &str) -> Option<&str> {
+    text.split(|c: char| c.is_whitespace() || c == ',')
+        .map(|word| {
+            word.trim_start_matches(|c: char| !c.is_ascii_digit())
+                .trim_end_matches(|c: char| !c.is_ascii_alphanumeric())
+        })
+        .find(|word| !word.is_empty() && word.contains('.'))
+}
+
+/// Parse the numeric components of the first version-like token
+///
+/// See `extract_version`; pre-release suffixes are ignored.
+pub fn parse_version(version_str: &str) -> Option<Vec<u32>> {
+    extract_version(version_str).map(|word| {
+        let core = word.split(['-', '+']).next().unwrap_or(word);
+        core.split('.')
+            .filter_map(|part| part.parse().ok())
+            .collect()
+    })
+}
+
+/// Build the update status for one provider
+fn update_status(provider: &str, installed_raw: &str, latest_raw: Option<&str>) -> CliUpdateStatus {
+    let installed = parse_version(installed_raw);
+    let latest = latest_raw.and_then(parse_version);
+
+    let update_available = match (&installed, &latest) {
+        (Some(installed), Some(latest)) => latest > installed,
+        _ => false,
+    };
+    let join = |parts: &Vec<u32>| {
+        parts
+            .iter()
+            .map(|p| p.to_string())
+            .collect::<Vec<_>>()
+            .join(".")
+    };
+
+    CliUpdateStatus {
+        provider: provider.to_string(),
+        installed_version: installed
+            .as_ref()
+            .map(join)
+            .unwrap_or_else(|| installed_raw.to_string()),
+        latest_version: latest.as_ref().map(join),
+        update_available,
+    }
+}
+
+/// Installed version strings for each provider whose CLI is installed
+async fn installed_versions(app: &AppHandle) -> Vec<(&'static str, String)> {
+    let claude = crate::claude_cli::check_claude_cli_installed(app.clone())
+        .await
+        .ok()
+        .and_then(|status| status.version);
+    let codex = super::codex::commands::check_codex_cli_installed(app.clone()).version;
+    let gemini = super::gemini::commands::check_gemini_cli_installed().version;
+    let kimi = super::kimi::commands::check_kimi_cli_installed().version;
+
+    [
+        ("claude", claude),
+        ("codex", codex),
+        ("gemini", gemini),
+        ("kimi", kimi),
+    ]
+    .into_iter()
+    .filter_map(|(provider, version)| version.map(|v| (provider, v)))
+    .collect()
+}
+
+/// Fetch the latest published version for a provider
+async fn fetch_latest_version(provider: &str) -> Result<String, String> {
+    match provider {
+        "claude" => crate::claude_cli::fetch_latest_version().await,
+        "codex" => super::codex::commands::get_available_codex_versions()
+            .await?
+            .into_iter()
+            .find(|v| !v.prerelease)
+            .map(|v| v.version)
+            .ok_or_else(|| "No Codex releases found".to_string()),
+        "gemini" => {
+            fetch_json_field(
+                "https://registry.npmjs.org/@google/gemini-cli/latest",
+                &["version"],
+            )
+            .await
+        }
+        "kimi" => {
+            fetch_json_field("https://pypi.org/pypi/kimi-cli/json", &["info", "version"]).await
+        }
+        _ => Err(format!("Unknown provider: {provider}")),
+    }
+}
+
+/// Fetch a JSON document and read a string at the given path
+async fn fetch_json_field(url: &str, path: &[&str]) -> Result<String, String> {
+    let client = reqwest::Client::builder()
+        .user_agent("Jean-App/1.0")
+        .build()
+        .map_err(|e| format!("Failed to create HTTP client: {e}"))?;
+
+    let response = client
+        .get(url)
+        .send()
+        .await
+        .map_err(|e| format!("Failed to fetch {url}: {e}"))?;
+    if !response.status().is_success() {
+        return Err(format!("{url} returned status: {}", response.status()));
+    }
+
+    let json: serde_json::Value = response
+        .json()
+        .await
+        .map_err(|e| format!("Failed to parse response from {url}: {e}"))?;
+    path.iter()
+        .try_fold(&json, |value, key| value.get(key))
+        .and_then(|value| value.as_str())
+        .map(|s| s.to_string())
+        .ok_or_else(|| format!("No version in response from {url}"))
+}
+
+/// Check every installed AI CLI for available updates
+///
+/// Only installed CLIs are returned. Latest versions come from the daily cache,
+/// refreshed from the provider's release source when expired. If a refresh
+/// fails, the stale cached version (if any) is used.
+#[tauri::command]
+pub async fn check_all_cli_updates(app: AppHandle) -> Vec<CliUpdateStatus> {
+    let installed = installed_versions(&app).await;
+    let mut cache = load_cache(&app);
+    let now = now_secs();
+
+    let mut refreshed = false;
+    for (provider, _) in &installed {
+        if !needs_refresh(&cache, provider, now) {
+            continue;
+        }
+        match fetch_latest_version(provider).await {
+            Ok(version) => {
+                cache.entries.insert(
+                    provider.to_string(),
+                    CachedLatestVersion {
+                        version,
+                        fetched_at: now,
+                    },
+                );
+                refreshed = true;
+            }
+            Err(e) => log::warn!("Failed to fetch latest {provider} CLI version: {e}"),
+        }
+    }
+
+    if refreshed {
+        if let Err(e) = save_cache(&app, &cache) {
+            log::warn!("Failed to save CLI version cache: {e}");
+        }
+    }
+
+    installed
+        .iter()
+        .map(|(provider, version)| {
+            let latest = cache.entries.get(*provider).map(|e| e.version.as_str());
+            update_status(provider, version, latest)
+        })
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn cache_with(entries: &[(&str, &str, u64)]) -> LatestVersionCache {
+        LatestVersionCache {
+            entries: entries
+                .iter()
+                .map(|(provider, version, fetched_at)| {
+                    (
+                        provider.to_string(),
+                        CachedLatestVersion {
+                            version: version.to_string(),
+                            fetched_at: *fetched_at,
+                        },
+                    )
+                })
+                .collect(),
+        }
+    }
+
+    #[test]
+    fn test_update_flags_from_cached_versions() {
+        let now = 1_700_000_000;
+        let cache = cache_with(&[
+            ("claude", "1.0.30", now),
+            ("codex", "0.46.0", now),
+            ("kimi", "0.5.1", now),
+        ]);
+        let installed = [
+            ("claude", "1.0.28 (Claude Code)"),
+            ("codex", "codex-cli 0.46.0"),
+            ("gemini", "0.9.0"),
+            ("kimi", "kimi, version 0.10.0"),
+        ];
+
+        let statuses: Vec<CliUpdateStatus> = installed
+            .iter()
+            .map(|(provider, version)| {
+                let latest = cache.entries.get(*provider).map(|e| e.version.as_str());
+                update_status(provider, version, latest)
+            })
+            .collect();
+
+        // Older than latest
+        assert!(statuses[0].update_available);
+        assert_eq!(statuses[0].installed_version, "1.0.28");
+        assert_eq!(statuses[0].latest_version.as_deref(), Some("1.0.30"));
+        // Up to date
+        assert!(!statuses[1].update_available);
+        assert_eq!(statuses[1].installed_version, "0.46.0");
+        // Latest unknown
+        assert!(!statuses[2].update_available);
+        assert!(statuses[2].latest_version.is_none());
+        // Numeric (not lexicographic) comparison: 0.10.0 > 0.5.1
+        assert!(!statuses[3].update_available);
+    }
+
+    #[test]
+    fn test_cache_ttl() {
+        let now = 1_700_000_000;
+        let cache = cache_with(&[
+            ("claude", "1.0.30", now - 60),
+            ("codex", "0.46.0", now - LATEST_VERSION_TTL_SECS),
+        ]);
+
+        assert!(!needs_refresh(&cache, "claude", now));
+        assert!(needs_refresh(&cache, "codex", now));
+        assert!(needs_refresh(&cache, "gemini", now));
+    }
+
+    #[test]
+    fn test_parse_version() {
+        assert_eq!(parse_version("v1.2.3"), Some(vec![1, 2, 3]));
+        assert_eq!(parse_version("1.2.0-beta.1"), Some(vec![1, 2, 0]));
+        assert_eq!(parse_version("unknown"), None);
+    }
+
+    #[test]
+    fn test_extract_version() {
+        let gh =
+            "gh version 2.40.0 (2024-01-15)\nhttps://github.com/cli/cli/releases/tag/v2.40.0\n";
+        assert_eq!(extract_version(gh), Some("2.40.0"));
+        assert_eq!(extract_version("codex-cli 0.1.0\n"), Some("0.1.0"));
+        assert_eq!(extract_version("1.0.28 (Claude Code)"), Some("1.0.28"));
+        assert_eq!(extract_version("kimi, version v0.45"), Some("0.45"));
+        assert_eq!(extract_version("rust-v0.46.0"), Some("0.46.0"));
+        assert_eq!(extract_version("v1.2.0-beta.1"), Some("1.2.0-beta.1"));
+        assert_eq!(extract_version("dev build"), None);
+        assert_eq!(extract_version("\n"), None);
+    }
+}
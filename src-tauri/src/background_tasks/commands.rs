@@ -7,6 +7,7 @@ use super::{
     MIN_REMOTE_POLL_INTERVAL,
 };
 use crate::projects::git_status::ActiveWorktreeInfo;
+use crate::projects::pr_status::{get_rate_limit_budget, RateLimitBudget};
 
 /// Set the application focus state
 ///
@@ -118,3 +119,11 @@ pub fn trigger_immediate_remote_poll(
     state.trigger_immediate_remote_poll();
     Ok(())
 }
+
+/// Get the GitHub REST rate limit budget last seen by PR status polling
+///
+/// Returns None until the first API response has been received.
+#[tauri::command]
+pub fn get_github_rate_limit() -> Result<Option<RateLimitBudget>, String> {
+    Ok(get_rate_limit_budget())
+}
@@ -1,6 +1,12 @@
+use std::path::{Path, PathBuf};
+
+use base64::{engine::general_purpose::STANDARD, Engine};
 use tauri::{Emitter, Manager};
 
+use super::error::ChatError;
+use super::run_log::{events_from_json, ChatEvent, RunFormat, Transcript};
 use super::types::{ContentBlock, ThinkingLevel, ToolCall, UsageData};
+use crate::ai_cli::types::AiCliProvider;
 use crate::projects::github_issues::{
     get_github_contexts_dir, get_worktree_issue_refs, get_worktree_pr_refs,
 };
@@ -59,6 +65,18 @@ pub struct ErrorEvent {
     pub session_id: String,
     pub worktree_id: String, // Kept for backward compatibility
     pub error: String,
+    /// `ChatError` code, for errors raised through the structured error path
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub code: Option<&'static str>,
+}
+
+/// Payload for heartbeat events sent while a CLI is alive but silent
+#[derive(serde::Serialize, Clone)]
+pub struct HeartbeatEvent {
+    pub session_id: String,
+    pub worktree_id: String, // Kept for backward compatibility
+    /// Seconds since the CLI last produced output (or was started)
+    pub silent_secs: u64,
 }
 
 /// Payload for cancelled events sent to frontend
@@ -94,6 +112,8 @@ pub struct ToolResultEvent {
     pub worktree_id: String, // Kept for backward compatibility
     pub tool_use_id: String,
     pub output: String,
+    /// False when the tool reported a failure (always true for providers that don't report it)
+    pub success: bool,
 }
 
 /// A single permission denial from Claude CLI
@@ -113,19 +133,98 @@ struct PermissionDeniedEvent {
     denials: Vec<PermissionDenial>,
 }
 
+// =============================================================================
+// Image input
+// =============================================================================
+
+/// Largest image the API accepts as a base64 content block (5MB)
+const MAX_INLINE_IMAGE_SIZE: u64 = 5 * 1024 * 1024;
+
+/// Whether a Claude model accepts image input
+///
+/// Every current model does except Claude 3.5 Haiku.
+pub fn supports_vision(model: Option<&str>) -> bool {
+    !model.is_some_and(|m| m.contains("3-5-haiku"))
+}
+
+/// Media type of a pasted image, from its file extension
+fn image_media_type(path: &Path) -> Option<&'static str> {
+    let extension = path.extension()?.to_str()?.to_ascii_lowercase();
+    match extension.as_str() {
+        "png" => Some("image/png"),
+        "jpg" | "jpeg" => Some("image/jpeg"),
+        "gif" => Some("image/gif"),
+        "webp" => Some("image/webp"),
+        _ => None,
+    }
+}
+
+/// Build the `content` of a stream-json user message
+///
+/// Without images this is the plain message string. Images are sent as base64
+/// blocks ahead of the text; unreadable, unsupported or oversized images are
+/// skipped with a warning (the message still references them for the Read tool).
+pub fn build_user_content(message: &str, images: &[PathBuf]) -> serde_json::Value {
+    let mut blocks = Vec::new();
+
+    for image in images {
+        let Some(media_type) = image_media_type(image) else {
+            log::warn!("Skipping image with unsupported type: {image:?}");
+            continue;
+        };
+        match std::fs::metadata(image) {
+            Ok(metadata) if metadata.len() > MAX_INLINE_IMAGE_SIZE => {
+                log::warn!(
+                    "Skipping image over {MAX_INLINE_IMAGE_SIZE} bytes: {image:?} ({} bytes)",
+                    metadata.len()
+                );
+                continue;
+            }
+            Ok(_) => {}
+            Err(e) => {
+                log::warn!("Skipping unreadable image {image:?}: {e}");
+                continue;
+            }
+        }
+        match std::fs::read(image) {
+            Ok(data) => blocks.push(serde_json::json!({
+                "type": "image",
+                "source": {
+                    "type": "base64",
+                    "media_type": media_type,
+                    "data": STANDARD.encode(data),
+                }
+            })),
+            Err(e) => log::warn!("Skipping unreadable image {image:?}: {e}"),
+        }
+    }
+
+    if blocks.is_empty() {
+        return serde_json::Value::String(message.to_string());
+    }
+
+    blocks.push(serde_json::json!({ "type": "text", "text": message }));
+    serde_json::Value::Array(blocks)
+}
+
 // =============================================================================
 // Detached Claude CLI execution
 // =============================================================================
 
+/// CLI args and (key, value) env vars for a Claude CLI invocation
+type ClaudeArgs = (Vec<String>, Vec<(String, String)>);
+
 /// Build CLI arguments for Claude CLI.
 ///
 /// Returns a tuple of (args, env_vars) where env_vars are (key, value) pairs.
+/// Fails if the session's MCP config file is missing.
 #[allow(clippy::too_many_arguments)]
 fn build_claude_args(
     app: &tauri::AppHandle,
     session_id: &str,
     worktree_id: &str,
     existing_claude_session_id: Option<&str>,
+    fork_session: bool,
     model: Option<&str>,
     execution_mode: Option<&str>,
     thinking_level: Option<&ThinkingLevel>,
@@ -133,7 +232,8 @@ fn build_claude_args(
     disable_thinking_in_non_plan_modes: bool,
     parallel_execution_prompt_enabled: bool,
     ai_language: Option<&str>,
-) -> (Vec<String>, Vec<(String, String)>) {
+    mcp_config_path: Option<&std::path::Path>,
+) -> Result<ClaudeArgs, String> {
     let mut args = Vec::new();
     let mut env_vars = Vec::new();
 
@@ -223,6 +323,11 @@ fn build_claude_args(
         }
     }
 
+    // MCP servers
+    if let Some(path) = mcp_config_path {
+        args.extend(super::mcp::claude_mcp_args(path)?);
+    }
+
     // Allowed tools
     if let Some(tools) = allowed_tools {
         for tool in tools {
@@ -423,6 +528,10 @@ fn build_claude_args(
     if let Some(claude_sid) = existing_claude_session_id {
         args.push("--resume".to_string());
         args.push(claude_sid.to_string());
+        // Continue the conversation under a new session ID, leaving the original intact
+        if fork_session {
+            args.push("--fork-session".to_string());
+        }
     }
 
     // Debug env vars
@@ -440,7 +549,20 @@ fn build_claude_args(
         env_vars.push(("JEAN_CLAUDE_SESSION_ID".to_string(), claude_sid.to_string()));
     }
 
-    (args, env_vars)
+    Ok((args, env_vars))
+}
+
+/// Whether a Claude CLI error means the `--resume` session ID was rejected
+///
+/// Claude reports an unknown or expired session as "No conversation found with
+/// session ID: ..." and exits without output.
+pub fn is_resume_rejected(error: &str) -> bool {
+    let error = error.to_lowercase();
+    error.contains("no conversation found")
+        || (error.contains("session")
+            && (error.contains("not found")
+                || error.contains("invalid")
+                || error.contains("expired")))
 }
 
 /// Execute Claude CLI in detached mode.
@@ -456,7 +578,9 @@ pub fn execute_claude_detached(
     input_file: &std::path::Path,
     output_file: &std::path::Path,
     working_dir: &std::path::Path,
+    worktree_env: &[(String, String)],
     existing_claude_session_id: Option<&str>,
+    fork_session: bool,
     model: Option<&str>,
     execution_mode: Option<&str>,
     thinking_level: Option<&ThinkingLevel>,
@@ -464,8 +588,11 @@ pub fn execute_claude_detached(
     disable_thinking_in_non_plan_modes: bool,
     parallel_execution_prompt_enabled: bool,
     ai_language: Option<&str>,
-) -> Result<(u32, ClaudeResponse), String> {
-    use super::detached::spawn_detached_claude;
+    mcp_config_path: Option<&std::path::Path>,
+    wsl_distro: Option<&str>,
+) -> Result<(u32, ClaudeResponse), ChatError> {
+    use super::detached::{spawn_detached_claude, DetachedProcess};
+    use super::error::emit_chat_error;
     use crate::claude_cli::get_cli_binary_path;
 
     log::trace!("Executing Claude CLI (detached) for session: {session_id}");
@@ -475,29 +602,19 @@ pub fn execute_claude_detached(
 
     // Get CLI path
     let cli_path = get_cli_binary_path(app).map_err(|e| {
-        let error_msg =
-            format!("Failed to get CLI path: {e}. Please complete setup in Settings > Advanced.");
-        log::error!("{error_msg}");
-        let error_event = ErrorEvent {
-            session_id: session_id.to_string(),
-            worktree_id: worktree_id.to_string(),
-            error: error_msg.clone(),
-        };
-        let _ = app.emit("chat:error", &error_event);
-        error_msg
+        let error = ChatError::Io(format!(
+            "Failed to get CLI path: {e}. Please complete setup in Settings > Advanced."
+        ));
+        emit_chat_error(app, session_id, worktree_id, &error);
+        error
     })?;
 
     if !cli_path.exists() {
-        let error_msg =
-            "Claude CLI not installed. Please complete setup in Settings > Advanced.".to_string();
-        log::error!("{error_msg}");
-        let error_event = ErrorEvent {
-            session_id: session_id.to_string(),
-            worktree_id: worktree_id.to_string(),
-            error: error_msg.clone(),
+        let error = ChatError::CliNotInstalled {
+            cli: "Claude".to_string(),
         };
-        let _ = app.emit("chat:error", &error_event);
-        return Err(error_msg);
+        emit_chat_error(app, session_id, worktree_id, &error);
+        return Err(error);
     }
 
     // Build args
@@ -506,6 +623,7 @@ pub fn execute_claude_detached(
         session_id,
         worktree_id,
         existing_claude_session_id,
+        fork_session,
         model,
         execution_mode,
         thinking_level,
@@ -513,7 +631,13 @@ pub fn execute_claude_detached(
         disable_thinking_in_non_plan_modes,
         parallel_execution_prompt_enabled,
         ai_language,
-    );
+        mcp_config_path,
+    )
+    .map_err(|error_msg| {
+        let error = ChatError::Io(error_msg);
+        emit_chat_error(app, session_id, worktree_id, &error);
+        error
+    })?;
 
     // Log the full Claude CLI command for debugging
     log::debug!(
@@ -522,39 +646,47 @@ pub fn execute_claude_detached(
         args.join(" ")
     );
 
-    // Convert env_vars to &str references for spawn_detached_claude
-    let env_refs: Vec<(&str, &str)> = env_vars
+    // Convert env_vars to &str references for spawn_detached_claude.
+    // Worktree env goes first so Jean's own variables win on conflicts.
+    let env_refs: Vec<(&str, &str)> = worktree_env
         .iter()
+        .chain(&env_vars)
         .map(|(k, v)| (k.as_str(), v.as_str()))
         .collect();
 
+    // The output file is the run log, which already holds the header and, when a
+    // rejected --resume is retried, the failed attempt's output: tail past them
+    let start_offset = std::fs::metadata(output_file).map(|m| m.len()).unwrap_or(0);
+
     // Spawn detached process
-    let pid = spawn_detached_claude(
+    let DetachedProcess { pid, pgid } = spawn_detached_claude(
         &cli_path,
         &args,
         input_file,
         output_file,
         working_dir,
         &env_refs,
+        wsl_distro,
     )?;
 
     log::trace!("Detached Claude CLI spawned with PID: {pid}");
 
     // Register the process for cancellation
-    super::registry::register_process(session_id.to_string(), pid);
+    super::registry::register_process(session_id.to_string(), worktree_id, pid, pgid, output_file);
 
     // Tail the output file for real-time updates
     // Use match to ensure unregister_process is always called, even on error
-    let response = match tail_claude_output(app, session_id, worktree_id, output_file, pid) {
-        Ok(resp) => {
-            super::registry::unregister_process(session_id);
-            resp
-        }
-        Err(e) => {
-            super::registry::unregister_process(session_id);
-            return Err(e);
-        }
-    };
+    let response =
+        match tail_claude_output(app, session_id, worktree_id, output_file, start_offset, pid) {
+            Ok(resp) => {
+                super::registry::unregister_process(session_id, output_file);
+                resp
+            }
+            Err(e) => {
+                super::registry::unregister_process(session_id, output_file);
+                return Err(ChatError::from_cli_failure("Claude", e));
+            }
+        };
 
     Ok((pid, response))
 }
@@ -572,11 +704,15 @@ pub fn execute_claude_detached(
 /// - A "result" message is received (completion)
 /// - The process is no longer running and no new output (timeout)
 /// - An error occurs
+///
+/// Reading starts `start_offset` bytes into the file, so output already there
+/// from an earlier attempt isn't parsed again.
 pub fn tail_claude_output(
     app: &tauri::AppHandle,
     session_id: &str,
     worktree_id: &str,
     output_file: &std::path::Path,
+    start_offset: u64,
     pid: u32,
 ) -> Result<ClaudeResponse, String> {
     use super::detached::is_process_alive;
@@ -586,17 +722,15 @@ pub fn tail_claude_output(
     log::trace!("Starting to tail NDJSON output for session: {session_id}");
     log::trace!("Output file: {output_file:?}, PID: {pid}");
 
-    // Create tailer starting from beginning (we want all content)
-    let mut tailer = NdjsonTailer::new_from_start(output_file)?;
+    let mut tailer = NdjsonTailer::new_from_offset(output_file, start_offset)?;
 
-    let mut full_content = String::new();
+    let mut transcript = Transcript::default();
     let mut claude_session_id = String::new();
-    let mut tool_calls: Vec<ToolCall> = Vec::new();
-    let mut content_blocks: Vec<ContentBlock> = Vec::new();
-    let mut current_parent_tool_use_id: Option<String> = None;
     let mut completed = false;
     let mut cancelled = false;
     let mut usage: Option<UsageData> = None;
+    // Non-JSON lines are the CLI's stderr (redirected into the output file)
+    let mut cli_errors: Vec<String> = Vec::new();
 
     // Timeout configuration:
     // - Startup timeout: Wait up to 120 seconds for first Claude output (API connection time)
@@ -638,6 +772,7 @@ pub fn tail_claude_output(
                 Ok(m) => m,
                 Err(e) => {
                     log::trace!("Failed to parse line: {e}");
+                    cli_errors.push(line.trim().to_string());
                     continue;
                 }
             };
@@ -649,295 +784,147 @@ pub fn tail_claude_output(
                 }
             }
 
-            // Track parent_tool_use_id for sub-agent tool calls
-            if let Some(parent_id) = msg.get("parent_tool_use_id").and_then(|v| v.as_str()) {
-                current_parent_tool_use_id = Some(parent_id.to_string());
-            }
+            for event in events_from_json(&RunFormat::new(AiCliProvider::Claude, None), &msg) {
+                event.emit(app, session_id, worktree_id);
+                transcript.apply(&event);
 
-            let msg_type = msg.get("type").and_then(|v| v.as_str()).unwrap_or("");
-
-            match msg_type {
-                "assistant" => {
-                    if let Some(message) = msg.get("message") {
-                        if let Some(blocks) = message.get("content").and_then(|c| c.as_array()) {
-                            for block in blocks {
-                                let block_type =
-                                    block.get("type").and_then(|v| v.as_str()).unwrap_or("");
-
-                                match block_type {
-                                    "text" => {
-                                        if let Some(text) =
-                                            block.get("text").and_then(|v| v.as_str())
-                                        {
-                                            // Skip CLI placeholder text emitted when extended
-                                            // thinking starts before any real text content
-                                            if text == "(no content)" {
-                                                continue;
-                                            }
-                                            full_content.push_str(text);
-                                            content_blocks.push(ContentBlock::Text {
-                                                text: text.to_string(),
-                                            });
-
-                                            // Emit chunk event
-                                            let event = ChunkEvent {
-                                                session_id: session_id.to_string(),
-                                                worktree_id: worktree_id.to_string(),
-                                                content: text.to_string(),
-                                            };
-                                            if let Err(e) = app.emit("chat:chunk", &event) {
-                                                log::error!("Failed to emit chunk: {e}");
-                                            }
-                                        }
-                                    }
-                                    "tool_use" => {
-                                        let id = block
-                                            .get("id")
-                                            .and_then(|v| v.as_str())
-                                            .unwrap_or("")
-                                            .to_string();
-                                        let name = block
-                                            .get("name")
-                                            .and_then(|v| v.as_str())
-                                            .unwrap_or("")
-                                            .to_string();
-                                        let input = block
-                                            .get("input")
-                                            .cloned()
-                                            .unwrap_or(serde_json::Value::Null);
-
-                                        tool_calls.push(ToolCall {
-                                            id: id.clone(),
-                                            name: name.clone(),
-                                            input: input.clone(),
-                                            output: None,
-                                            parent_tool_use_id: current_parent_tool_use_id.clone(),
-                                        });
-
-                                        content_blocks.push(ContentBlock::ToolUse {
-                                            tool_call_id: id.clone(),
-                                        });
-
-                                        // Emit tool_use event
-                                        let event = ToolUseEvent {
-                                            session_id: session_id.to_string(),
-                                            worktree_id: worktree_id.to_string(),
-                                            id: id.clone(),
-                                            name: name.clone(),
-                                            input: input.clone(),
-                                            parent_tool_use_id: current_parent_tool_use_id.clone(),
-                                        };
-                                        if let Err(e) = app.emit("chat:tool_use", &event) {
-                                            log::error!("Failed to emit tool_use: {e}");
-                                        }
+                let ChatEvent::ToolUse { id, name, .. } = &event else {
+                    continue;
+                };
+
+                // Emit tool_block event
+                let block_event = ToolBlockEvent {
+                    session_id: session_id.to_string(),
+                    worktree_id: worktree_id.to_string(),
+                    tool_call_id: id.clone(),
+                };
+                if let Err(e) = app.emit("chat:tool_block", &block_event) {
+                    log::error!("Failed to emit tool_block: {e}");
+                }
 
-                                        // Emit tool_block event
-                                        let block_event = ToolBlockEvent {
-                                            session_id: session_id.to_string(),
-                                            worktree_id: worktree_id.to_string(),
-                                            tool_call_id: id.clone(),
-                                        };
-                                        if let Err(e) = app.emit("chat:tool_block", &block_event) {
-                                            log::error!("Failed to emit tool_block: {e}");
-                                        }
+                // Check for blocking tools - kill process and return
+                if name == "AskUserQuestion" || name == "ExitPlanMode" {
+                    log::trace!("Detected blocking tool {name}, killing detached process");
 
-                                        // Check for blocking tools - kill process and return
-                                        if name == "AskUserQuestion" || name == "ExitPlanMode" {
-                                            log::trace!("Detected blocking tool {name}, killing detached process");
-
-                                            // Kill the detached process
-                                            #[cfg(unix)]
-                                            unsafe {
-                                                libc::kill(pid as i32, libc::SIGKILL);
-                                            }
-                                            #[cfg(windows)]
-                                            {
-                                                let _ = std::process::Command::new("taskkill")
-                                                    .args(["/F", "/PID", &pid.to_string()])
-                                                    .output();
-                                            }
-
-                                            // Emit done event so frontend knows streaming is complete
-                                            let done_event = DoneEvent {
-                                                session_id: session_id.to_string(),
-                                                worktree_id: worktree_id.to_string(),
-                                            };
-                                            if let Err(e) = app.emit("chat:done", &done_event) {
-                                                log::error!("Failed to emit done event: {e}");
-                                            }
-
-                                            // Return partial response (blocking tool is already in tool_calls)
-                                            return Ok(ClaudeResponse {
-                                                content: full_content,
-                                                session_id: claude_session_id,
-                                                tool_calls,
-                                                content_blocks,
-                                                cancelled: false,
-                                                usage: None, // No usage for partial responses
-                                            });
-                                        }
-                                    }
-                                    "thinking" => {
-                                        if let Some(thinking) =
-                                            block.get("thinking").and_then(|v| v.as_str())
-                                        {
-                                            content_blocks.push(ContentBlock::Thinking {
-                                                thinking: thinking.to_string(),
-                                            });
-
-                                            let event = ThinkingEvent {
-                                                session_id: session_id.to_string(),
-                                                worktree_id: worktree_id.to_string(),
-                                                content: thinking.to_string(),
-                                            };
-                                            if let Err(e) = app.emit("chat:thinking", &event) {
-                                                log::error!("Failed to emit thinking: {e}");
-                                            }
-                                        }
-                                    }
-                                    _ => {}
-                                }
-                            }
-                        }
+                    // Kill the detached process
+                    #[cfg(unix)]
+                    unsafe {
+                        libc::kill(pid as i32, libc::SIGKILL);
+                    }
+                    #[cfg(windows)]
+                    {
+                        let _ = std::process::Command::new("taskkill")
+                            .args(["/F", "/PID", &pid.to_string()])
+                            .output();
                     }
-                }
-                "user" => {
-                    // User messages contain tool results
-                    if let Some(message) = msg.get("message") {
-                        if let Some(blocks) = message.get("content").and_then(|c| c.as_array()) {
-                            for block in blocks {
-                                let block_type =
-                                    block.get("type").and_then(|v| v.as_str()).unwrap_or("");
-
-                                if block_type == "tool_result" {
-                                    let tool_id = block
-                                        .get("tool_use_id")
-                                        .and_then(|v| v.as_str())
-                                        .unwrap_or("");
-                                    let output =
-                                        block.get("content").and_then(|v| v.as_str()).unwrap_or("");
-
-                                    // Update matching tool call's output
-                                    if let Some(tc) =
-                                        tool_calls.iter_mut().find(|t| t.id == tool_id)
-                                    {
-                                        tc.output = Some(output.to_string());
-                                    }
 
-                                    // Emit tool_result event
-                                    let event = ToolResultEvent {
-                                        session_id: session_id.to_string(),
-                                        worktree_id: worktree_id.to_string(),
-                                        tool_use_id: tool_id.to_string(),
-                                        output: output.to_string(),
-                                    };
-                                    if let Err(e) = app.emit("chat:tool_result", &event) {
-                                        log::error!("Failed to emit tool_result: {e}");
-                                    }
-                                }
-                            }
-                        }
+                    // Emit done event so frontend knows streaming is complete
+                    let done_event = DoneEvent {
+                        session_id: session_id.to_string(),
+                        worktree_id: worktree_id.to_string(),
+                    };
+                    if let Err(e) = app.emit("chat:done", &done_event) {
+                        log::error!("Failed to emit done event: {e}");
                     }
+
+                    // Return partial response (blocking tool is already in tool_calls)
+                    return Ok(ClaudeResponse {
+                        content: transcript.content,
+                        session_id: claude_session_id,
+                        tool_calls: transcript.tool_calls,
+                        content_blocks: transcript.content_blocks,
+                        cancelled: false,
+                        usage: None, // No usage for partial responses
+                    });
                 }
-                "result" => {
-                    // Final result - Claude CLI completed
-                    if full_content.is_empty() {
-                        if let Some(result) = msg.get("result").and_then(|v| v.as_str()) {
-                            full_content = result.to_string();
-                        }
-                    }
+            }
 
-                    // Extract token usage data
-                    if let Some(usage_obj) = msg.get("usage") {
-                        usage = Some(UsageData {
-                            input_tokens: usage_obj
-                                .get("input_tokens")
-                                .and_then(|v| v.as_u64())
-                                .unwrap_or(0),
-                            output_tokens: usage_obj
-                                .get("output_tokens")
-                                .and_then(|v| v.as_u64())
-                                .unwrap_or(0),
-                            cache_read_input_tokens: usage_obj
-                                .get("cache_read_input_tokens")
-                                .and_then(|v| v.as_u64())
-                                .unwrap_or(0),
-                            cache_creation_input_tokens: usage_obj
-                                .get("cache_creation_input_tokens")
-                                .and_then(|v| v.as_u64())
-                                .unwrap_or(0),
-                        });
-                        log::trace!(
-                            "Token usage: input={}, output={}, cache_read={}, cache_create={}",
-                            usage.as_ref().map(|u| u.input_tokens).unwrap_or(0),
-                            usage.as_ref().map(|u| u.output_tokens).unwrap_or(0),
-                            usage
-                                .as_ref()
-                                .map(|u| u.cache_read_input_tokens)
-                                .unwrap_or(0),
-                            usage
-                                .as_ref()
-                                .map(|u| u.cache_creation_input_tokens)
-                                .unwrap_or(0),
-                        );
-                    }
+            if msg.get("type").and_then(|v| v.as_str()) == Some("result") {
+                // Final result - Claude CLI completed
+                // Extract token usage data
+                if let Some(usage_obj) = msg.get("usage") {
+                    usage = Some(UsageData {
+                        input_tokens: usage_obj
+                            .get("input_tokens")
+                            .and_then(|v| v.as_u64())
+                            .unwrap_or(0),
+                        output_tokens: usage_obj
+                            .get("output_tokens")
+                            .and_then(|v| v.as_u64())
+                            .unwrap_or(0),
+                        cache_read_input_tokens: usage_obj
+                            .get("cache_read_input_tokens")
+                            .and_then(|v| v.as_u64())
+                            .unwrap_or(0),
+                        cache_creation_input_tokens: usage_obj
+                            .get("cache_creation_input_tokens")
+                            .and_then(|v| v.as_u64())
+                            .unwrap_or(0),
+                    });
+                    log::trace!(
+                        "Token usage: input={}, output={}, cache_read={}, cache_create={}",
+                        usage.as_ref().map(|u| u.input_tokens).unwrap_or(0),
+                        usage.as_ref().map(|u| u.output_tokens).unwrap_or(0),
+                        usage
+                            .as_ref()
+                            .map(|u| u.cache_read_input_tokens)
+                            .unwrap_or(0),
+                        usage
+                            .as_ref()
+                            .map(|u| u.cache_creation_input_tokens)
+                            .unwrap_or(0),
+                    );
+                }
 
-                    // Check for permission denials and emit event
-                    if let Some(denials) = msg.get("permission_denials").and_then(|v| v.as_array())
-                    {
-                        if !denials.is_empty() {
-                            let denial_events: Vec<PermissionDenial> = denials
-                                .iter()
-                                .filter_map(|d| {
-                                    let tool_name = d.get("tool_name")?.as_str()?;
-                                    let tool_input = d.get("tool_input")?;
-
-                                    // Skip plan file cleanup denials (benign Claude housekeeping)
-                                    if tool_name == "Bash" {
-                                        if let Some(cmd) =
-                                            tool_input.get("command").and_then(|c| c.as_str())
+                // Check for permission denials and emit event
+                if let Some(denials) = msg.get("permission_denials").and_then(|v| v.as_array()) {
+                    if !denials.is_empty() {
+                        let denial_events: Vec<PermissionDenial> = denials
+                            .iter()
+                            .filter_map(|d| {
+                                let tool_name = d.get("tool_name")?.as_str()?;
+                                let tool_input = d.get("tool_input")?;
+
+                                // Skip plan file cleanup denials (benign Claude housekeeping)
+                                if tool_name == "Bash" {
+                                    if let Some(cmd) =
+                                        tool_input.get("command").and_then(|c| c.as_str())
+                                    {
+                                        if cmd.contains(".claude/plans/") && cmd.starts_with("rm ")
                                         {
-                                            if cmd.contains(".claude/plans/")
-                                                && cmd.starts_with("rm ")
-                                            {
-                                                log::trace!(
-                                                    "Ignoring plan cleanup denial: {}",
-                                                    cmd
-                                                );
-                                                return None;
-                                            }
+                                            log::trace!("Ignoring plan cleanup denial: {}", cmd);
+                                            return None;
                                         }
                                     }
+                                }
 
-                                    Some(PermissionDenial {
-                                        tool_name: tool_name.to_string(),
-                                        tool_use_id: d.get("tool_use_id")?.as_str()?.to_string(),
-                                        tool_input: tool_input.clone(),
-                                    })
+                                Some(PermissionDenial {
+                                    tool_name: tool_name.to_string(),
+                                    tool_use_id: d.get("tool_use_id")?.as_str()?.to_string(),
+                                    tool_input: tool_input.clone(),
                                 })
-                                .collect();
-
-                            if !denial_events.is_empty() {
-                                log::trace!(
-                                    "Emitting permission_denied event with {} denials",
-                                    denial_events.len()
-                                );
-                                let event = PermissionDeniedEvent {
-                                    session_id: session_id.to_string(),
-                                    worktree_id: worktree_id.to_string(),
-                                    denials: denial_events,
-                                };
-                                if let Err(e) = app.emit("chat:permission_denied", &event) {
-                                    log::error!("Failed to emit permission_denied: {e}");
-                                }
+                            })
+                            .collect();
+
+                        if !denial_events.is_empty() {
+                            log::trace!(
+                                "Emitting permission_denied event with {} denials",
+                                denial_events.len()
+                            );
+                            let event = PermissionDeniedEvent {
+                                session_id: session_id.to_string(),
+                                worktree_id: worktree_id.to_string(),
+                                denials: denial_events,
+                            };
+                            if let Err(e) = app.emit("chat:permission_denied", &event) {
+                                log::error!("Failed to emit permission_denied: {e}");
                             }
                         }
                     }
-
-                    completed = true;
-                    log::trace!("Received result message - Claude CLI completed");
                 }
-                _ => {}
+
+                completed = true;
+                log::trace!("Received result message - Claude CLI completed");
             }
         }
 
@@ -961,6 +948,15 @@ pub fn tail_claude_output(
         if received_claude_output {
             // After receiving output, use shorter timeout for detecting dead process
             if !process_alive && last_output_time.elapsed() > dead_process_timeout {
+                // Exited with only an error (e.g. a rejected --resume): report it so
+                // the caller can react instead of saving an empty response
+                if transcript.content.is_empty()
+                    && transcript.tool_calls.is_empty()
+                    && !cli_errors.is_empty()
+                {
+                    return Err(format!("Claude CLI failed: {}", cli_errors.join("\n")));
+                }
+
                 log::trace!(
                     "Process {pid} is no longer running and no new output after receiving content"
                 );
@@ -1008,16 +1004,61 @@ pub fn tail_claude_output(
 
     log::trace!(
         "Tailing complete: {} chars, {} tool calls, cancelled: {cancelled}",
-        full_content.len(),
-        tool_calls.len()
+        transcript.content.len(),
+        transcript.tool_calls.len()
     );
 
     Ok(ClaudeResponse {
-        content: full_content,
+        content: transcript.content,
         session_id: claude_session_id,
-        tool_calls,
-        content_blocks,
+        tool_calls: transcript.tool_calls,
+        content_blocks: transcript.content_blocks,
         cancelled,
         usage,
     })
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_is_resume_rejected() {
+        assert!(is_resume_rejected(
+            "Claude CLI failed: No conversation found with session ID: 1b2c3d"
+        ));
+        assert!(is_resume_rejected("Error: session expired"));
+        assert!(!is_resume_rejected("Claude CLI failed: Invalid API key"));
+    }
+
+    #[test]
+    fn test_supports_vision() {
+        assert!(supports_vision(None));
+        assert!(supports_vision(Some("opus")));
+        assert!(!supports_vision(Some("claude-3-5-haiku-20241022")));
+    }
+
+    #[test]
+    fn test_build_user_content_with_images() {
+        assert_eq!(build_user_content("hello", &[]), serde_json::json!("hello"));
+
+        let dir = tempfile::tempdir().unwrap();
+        let png = dir.path().join("image-1.png");
+        std::fs::write(&png, [0x89, b'P', b'N', b'G']).unwrap();
+        let missing = dir.path().join("missing.png");
+        let unsupported = dir.path().join("image.bmp");
+        std::fs::write(&unsupported, [0u8]).unwrap();
+
+        let content = build_user_content("what is this?", &[png, missing, unsupported]);
+        assert_eq!(
+            content,
+            serde_json::json!([
+                {
+                    "type": "image",
+                    "source": { "type": "base64", "media_type": "image/png", "data": "iVBORw==" }
+                },
+                { "type": "text", "text": "what is this?" }
+            ])
+        );
+    }
+}
@@ -0,0 +1,408 @@
+//! Import sessions started in the Claude CLI
+//!
+//! Claude CLI keeps a transcript per session in
+//! `~/.claude/projects/<project>/<session_id>.jsonl`. Its assistant and tool
+//! result lines use the same shape as `--output-format stream-json`, so each
+//! user prompt and the lines after it become one Jean run, stored and replayed
+//! like any other. The imported session keeps the Claude session ID, so the
+//! next message resumes the same conversation.
+
+use std::fs;
+use std::io::Write;
+use std::path::{Path, PathBuf};
+
+use tauri::AppHandle;
+use uuid::Uuid;
+
+use crate::ai_cli::types::AiCliProvider;
+
+use super::run_log::{parse_run_events, RunFormat};
+use super::storage::{
+    delete_session_data, get_session_dir, load_sessions, with_metadata_mut, with_sessions_mut,
+};
+use super::types::{RunEntry, RunStatus, Session, WorktreeSessions};
+
+/// Maximum characters of the first prompt used as the session name
+const SESSION_NAME_LIMIT: usize = 40;
+
+/// One user prompt and the transcript lines that answered it
+#[derive(Debug, Clone, PartialEq)]
+struct ImportedTurn {
+    user_message: String,
+    timestamp: u64,
+    lines: Vec<String>,
+}
+
+/// Find a Claude CLI session transcript by session ID
+///
+/// The project directory name is derived from the working directory the CLI
+/// ran in, so every project directory is searched.
+pub fn find_claude_transcript(claude_session_id: &str) -> Option<PathBuf> {
+    let claude_projects = dirs::home_dir()?.join(".claude").join("projects");
+    fs::read_dir(&claude_projects)
+        .ok()?
+        .flatten()
+        .map(|entry| entry.path().join(format!("{claude_session_id}.jsonl")))
+        .find(|path| path.is_file())
+}
+
+/// Working directory the Claude CLI ran in, from the first transcript line that records it
+fn transcript_cwd(jsonl: &str) -> Option<String> {
+    jsonl
+        .lines()
+        .filter_map(|line| serde_json::from_str::<serde_json::Value>(line).ok())
+        .find_map(|msg| msg.get("cwd")?.as_str().map(str::to_string))
+}
+
+/// Name of the `~/.claude/projects` directory Claude CLI keeps a working
+/// directory's sessions in: every character but ASCII letters and digits becomes `-`
+fn claude_project_dir_name(path: &str) -> String {
+    path.chars()
+        .map(|c| if c.is_ascii_alphanumeric() { c } else { '-' })
+        .collect()
+}
+
+/// Check that a transcript was recorded in `worktree_path`
+///
+/// Claude CLI looks up `--resume` sessions by its working directory, so a
+/// session started anywhere else couldn't be continued from this worktree.
+/// Uses the transcript's `cwd`, or its project directory name if no line has one.
+fn check_transcript_location(
+    transcript_path: &Path,
+    jsonl: &str,
+    worktree_path: &str,
+) -> Result<(), String> {
+    let canonical = |path: &str| fs::canonicalize(path).unwrap_or_else(|_| PathBuf::from(path));
+
+    let (matches, location) = match transcript_cwd(jsonl) {
+        Some(cwd) => (canonical(&cwd) == canonical(worktree_path), cwd),
+        None => {
+            let project_dir = transcript_path
+                .parent()
+                .and_then(|p| p.file_name())
+                .map(|n| n.to_string_lossy().to_string())
+                .unwrap_or_default();
+            (
+                project_dir == claude_project_dir_name(worktree_path),
+                project_dir,
+            )
+        }
+    };
+
+    if matches {
+        Ok(())
+    } else {
+        Err(format!(
+            "Claude session was started in {location}, not in this worktree ({worktree_path})"
+        ))
+    }
+}
+
+/// Text of a user prompt, or None for tool results and other non-prompt user lines
+fn prompt_text(msg: &serde_json::Value) -> Option<String> {
+    if msg.get("isMeta").and_then(|v| v.as_bool()) == Some(true) {
+        return None;
+    }
+    match msg.get("message")?.get("content")? {
+        serde_json::Value::String(text) => Some(text.clone()),
+        serde_json::Value::Array(blocks) => {
+            let texts: Vec<&str> = blocks
+                .iter()
+                .filter(|b| b.get("type").and_then(|t| t.as_str()) == Some("text"))
+                .filter_map(|b| b.get("text").and_then(|t| t.as_str()))
+                .collect();
+            (!texts.is_empty()).then(|| texts.join("\n"))
+        }
+        _ => None,
+    }
+}
+
+/// Unix timestamp of a transcript line (RFC 3339 `timestamp` field)
+fn line_timestamp(msg: &serde_json::Value) -> Option<u64> {
+    let timestamp = msg.get("timestamp")?.as_str()?;
+    chrono::DateTime::parse_from_rfc3339(timestamp)
+        .ok()
+        .map(|t| t.timestamp().max(0) as u64)
+}
+
+/// Split a Claude CLI transcript into turns, one per user prompt
+///
+/// Sub-agent (sidechain) lines and lines before the first prompt are dropped.
+/// Fails if the transcript has no user/assistant messages at all, which means
+/// the file isn't in a format this parser knows.
+fn split_transcript(jsonl: &str, fallback_timestamp: u64) -> Result<Vec<ImportedTurn>, String> {
+    let mut turns: Vec<ImportedTurn> = Vec::new();
+    let mut recognized = false;
+
+    for line in jsonl.lines() {
+        let Ok(msg) = serde_json::from_str::<serde_json::Value>(line) else {
+            continue;
+        };
+        let msg_type = msg.get("type").and_then(|t| t.as_str()).unwrap_or_default();
+        if msg_type != "user" && msg_type != "assistant" {
+            continue;
+        }
+        recognized = true;
+        if msg.get("isSidechain").and_then(|v| v.as_bool()) == Some(true) {
+            continue;
+        }
+
+        if msg_type == "user" {
+            if let Some(text) = prompt_text(&msg) {
+                turns.push(ImportedTurn {
+                    user_message: text,
+                    timestamp: line_timestamp(&msg).unwrap_or(fallback_timestamp),
+                    lines: Vec::new(),
+                });
+                continue;
+            }
+        }
+        if let Some(turn) = turns.last_mut() {
+            turn.lines.push(line.to_string());
+        }
+    }
+
+    if !recognized {
+        return Err("Unrecognized Claude transcript format".to_string());
+    }
+    Ok(turns)
+}
+
+/// Session name from the first prompt, falling back to the Claude session ID
+fn imported_session_name(turns: &[ImportedTurn], claude_session_id: &str) -> String {
+    let first_line = turns.first().and_then(|t| {
+        t.user_message
+            .lines()
+            .map(str::trim)
+            .find(|l| !l.is_empty())
+    });
+    match first_line {
+        Some(line) if line.chars().count() > SESSION_NAME_LIMIT => {
+            let truncated: String = line.chars().take(SESSION_NAME_LIMIT).collect();
+            format!("{}...", truncated.trim_end())
+        }
+        Some(line) => line.to_string(),
+        None => format!(
+            "Claude {}",
+            claude_session_id.chars().take(8).collect::<String>()
+        ),
+    }
+}
+
+/// Import a Claude CLI session into a worktree as a new Jean session
+///
+/// Each prompt in the transcript becomes a completed run, and the session is
+/// linked to the Claude session so the next message resumes it. Fails if the
+/// transcript can't be found, isn't in Claude's format, was recorded in another
+/// directory, or the session was already imported into this worktree.
+///
+/// The run logs and metadata are written before the session is added to the
+/// worktree index, so a failed import never leaves a listed but empty session.
+#[tauri::command]
+pub async fn import_claude_session(
+    app: AppHandle,
+    worktree_id: String,
+    claude_session_id: String,
+) -> Result<Session, String> {
+    log::trace!("Importing Claude session {claude_session_id} into worktree {worktree_id}");
+
+    // The ID becomes part of a file name, so only accept what Claude generates
+    if claude_session_id.is_empty()
+        || !claude_session_id
+            .chars()
+            .all(|c| c.is_ascii_alphanumeric() || c == '-')
+    {
+        return Err(format!("Invalid Claude session ID: {claude_session_id}"));
+    }
+
+    let transcript_path = find_claude_transcript(&claude_session_id)
+        .ok_or_else(|| format!("Claude session not found: {claude_session_id}"))?;
+    let jsonl = fs::read_to_string(&transcript_path)
+        .map_err(|e| format!("Failed to read Claude transcript: {e}"))?;
+
+    let projects = crate::projects::storage::load_projects_data(&app)?;
+    let worktree = projects
+        .find_worktree(&worktree_id)
+        .ok_or_else(|| format!("Worktree not found: {worktree_id}"))?;
+    check_transcript_location(&transcript_path, &jsonl, &worktree.path)?;
+
+    let now = std::time::SystemTime::now()
+        .duration_since(std::time::UNIX_EPOCH)
+        .unwrap_or_default()
+        .as_secs();
+    let turns =
+        split_transcript(&jsonl, now).map_err(|e| format!("{e}: {}", transcript_path.display()))?;
+    let has_events = turns.iter().any(|turn| {
+        !parse_run_events(
+            &RunFormat::new(AiCliProvider::Claude, None),
+            &turn.lines.join("\n"),
+        )
+        .is_empty()
+    });
+    if !has_events {
+        return Err(format!(
+            "Claude session {claude_session_id} has no messages to import"
+        ));
+    }
+
+    let already_imported = |sessions: &WorktreeSessions| {
+        sessions
+            .sessions
+            .iter()
+            .find(|s| s.claude_session_id.as_deref() == Some(claude_session_id.as_str()))
+            .map(|existing| format!("Claude session already imported as '{}'", existing.name))
+    };
+    let existing_sessions = load_sessions(&app, "", &worktree_id)?;
+    if let Some(error) = already_imported(&existing_sessions) {
+        return Err(error);
+    }
+
+    let session_name = imported_session_name(&turns, &claude_session_id);
+    let mut session = Session::new(session_name, existing_sessions.sessions.len() as u32);
+    session.claude_session_id = Some(claude_session_id.clone());
+    session.selected_provider = Some("claude".to_string());
+    session.message_count = Some(turns.len() as u32 * 2);
+
+    // Write each turn as a completed run, with the same header live runs get
+    let session_dir = get_session_dir(&app, &session.id)?;
+    let mut runs = Vec::with_capacity(turns.len());
+    for turn in &turns {
+        let run_id = Uuid::new_v4().to_string();
+        let user_message_id = Uuid::new_v4().to_string();
+        let meta = serde_json::json!({
+            "_run_meta": true,
+            "run_id": run_id,
+            "session_id": session.id,
+            "worktree_id": worktree_id,
+            "user_message_id": user_message_id,
+            "started_at": turn.timestamp,
+        });
+
+        let mut file = fs::File::create(session_dir.join(format!("{run_id}.jsonl")))
+            .map_err(|e| format!("Failed to create run log file: {e}"))?;
+        writeln!(file, "{meta}").map_err(|e| format!("Failed to write run log: {e}"))?;
+        for line in &turn.lines {
+            writeln!(file, "{line}").map_err(|e| format!("Failed to write run log: {e}"))?;
+        }
+
+        runs.push(RunEntry {
+            provider: Some(AiCliProvider::Claude),
+            run_id,
+            user_message_id,
+            user_message: turn.user_message.clone(),
+            model: None,
+            execution_mode: None,
+            thinking_level: None,
+            started_at: turn.timestamp,
+            ended_at: Some(turn.timestamp),
+            status: RunStatus::Completed,
+            assistant_message_id: Some(Uuid::new_v4().to_string()),
+            cancelled: false,
+            recovered: false,
+            claude_session_id: Some(claude_session_id.clone()),
+            pid: None,
+            usage: None,
+            start_head: None,
+            start_tree: None,
+            end_tree: None,
+        });
+    }
+
+    with_metadata_mut(
+        &app,
+        &session.id,
+        &worktree_id,
+        &session.name,
+        session.order,
+        |metadata| {
+            metadata.update_from_session(&session);
+            metadata.runs = runs;
+            Ok(())
+        },
+    )?;
+
+    // Only list the session once its data is complete
+    let added = with_sessions_mut(&app, "", &worktree_id, |sessions| {
+        if let Some(error) = already_imported(sessions) {
+            return Err(error);
+        }
+        sessions.sessions.push(session.clone());
+        sessions.active_session_id = Some(session.id.clone());
+        Ok(())
+    });
+    if let Err(e) = added {
+        if let Err(cleanup) = delete_session_data(&app, &session.id) {
+            log::warn!(
+                "Failed to remove data of unimported session {}: {cleanup}",
+                session.id
+            );
+        }
+        return Err(e);
+    }
+
+    session.messages = super::run_log::load_session_messages(&app, &session.id)?;
+    log::debug!(
+        "Imported Claude session {claude_session_id} as {} ({} turns)",
+        session.id,
+        turns.len()
+    );
+    Ok(session)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_split_transcript_groups_lines_by_prompt() {
+        let jsonl = [
+            r#"{"type":"summary","summary":"Fix tests"}"#,
+            r#"{"type":"user","message":{"role":"user","content":"Fix the tests"},"timestamp":"2025-06-01T12:00:00.000Z"}"#,
+            r#"{"type":"assistant","message":{"content":[{"type":"tool_use","id":"t1","name":"Bash","input":{"command":"cargo test"}}]}}"#,
+            r#"{"type":"user","message":{"content":[{"type":"tool_result","tool_use_id":"t1","content":"ok"}]}}"#,
+            r#"{"type":"assistant","message":{"content":[{"type":"text","text":"Tests pass."}]},"isSidechain":true}"#,
+            r#"{"type":"assistant","message":{"content":[{"type":"text","text":"All green."}]}}"#,
+            r#"{"type":"user","message":{"content":"<command-name>/clear</command-name>"},"isMeta":true}"#,
+            r#"{"type":"user","message":{"content":[{"type":"text","text":"Thanks"}]}}"#,
+        ]
+        .join("\n");
+
+        let turns = split_transcript(&jsonl, 42).unwrap();
+        assert_eq!(turns.len(), 2);
+        assert_eq!(turns[0].user_message, "Fix the tests");
+        assert_eq!(turns[0].timestamp, 1748779200);
+        assert_eq!(turns[0].lines.len(), 4);
+        assert_eq!(turns[1].user_message, "Thanks");
+        assert_eq!(turns[1].timestamp, 42);
+
+        let events = parse_run_events(
+            &RunFormat::new(AiCliProvider::Claude, None),
+            &turns[0].lines.join("\n"),
+        );
+        assert_eq!(events.len(), 3);
+
+        assert_eq!(imported_session_name(&turns, "abc"), "Fix the tests");
+    }
+
+    #[test]
+    fn test_check_transcript_location() {
+        let transcript = Path::new("/home/me/.claude/projects/-work-app/abc.jsonl");
+        let jsonl = r#"{"type":"user","cwd":"/work/app","message":{"content":"hi"}}"#;
+        assert!(check_transcript_location(transcript, jsonl, "/work/app").is_ok());
+        assert!(check_transcript_location(transcript, jsonl, "/work/app/").is_ok());
+        let err = check_transcript_location(transcript, jsonl, "/work/other").unwrap_err();
+        assert!(err.contains("/work/app"), "{err}");
+
+        // Without a cwd, the project directory name is compared
+        let jsonl = r#"{"type":"user","message":{"content":"hi"}}"#;
+        assert!(check_transcript_location(transcript, jsonl, "/work/app").is_ok());
+        assert!(check_transcript_location(transcript, jsonl, "/work/app.v2").is_err());
+        assert_eq!(claude_project_dir_name("/work/app.v2"), "-work-app-v2");
+    }
+
+    #[test]
+    fn test_split_transcript_rejects_unknown_format() {
+        let err = split_transcript(r#"{"role":"user","content":"hi"}"#, 0).unwrap_err();
+        assert!(err.starts_with("Unrecognized"));
+    }
+}
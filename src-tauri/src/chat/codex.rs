@@ -4,14 +4,21 @@
 //! Uses detached process execution + JSONL tailing for robustness.
 
 use crate::ai_cli::codex::config::get_codex_cli_path;
-use std::path::Path;
+use crate::ai_cli::types::AiCliProvider;
+use std::path::{Path, PathBuf};
 use std::thread;
 use std::time::{Duration, Instant};
 use tauri::Emitter;
 
-use super::claude::{ChunkEvent, ClaudeResponse, ErrorEvent, ThinkingEvent, ToolResultEvent, ToolUseEvent};
-use super::detached::{is_process_alive, spawn_detached_codex};
-use super::tail::{NdjsonTailer, POLL_INTERVAL};
+use super::claude::{ClaudeResponse, ErrorEvent, HeartbeatEvent, ToolUseEvent};
+use super::detached::{
+    detect_startup_crash, has_output, is_process_alive, spawn_detached_codex,
+    startup_crash_message, STARTUP_CRASH_WINDOW,
+};
+use super::error::{emit_chat_error, is_auth_failure, ChatError};
+use super::run_log::{events_from_json, plain_text_events, RunFormat, Transcript};
+use super::tail::{Heartbeat, NdjsonTailer, PollBackoff, HEARTBEAT_INTERVAL};
+use super::types::ToolKind;
 
 /// Timeout for waiting for first output from Codex
 const STARTUP_TIMEOUT: Duration = Duration::from_secs(120);
@@ -19,6 +26,43 @@ const STARTUP_TIMEOUT: Duration = Duration::from_secs(120);
 /// Timeout after process dies to wait for final output
 const DEAD_PROCESS_GRACE_PERIOD: Duration = Duration::from_secs(2);
 
+/// Appended to the prompt in plan mode so Codex proposes its edits as patches
+const PLAN_MODE_INSTRUCTIONS: &str = "This is a dry run in a read-only sandbox. Do not try to \
+work around it. Propose every file change you would make as a patch so it can be reviewed and \
+applied later.";
+
+/// A file change Codex proposed during a read-only (plan mode) run
+#[derive(Debug, Clone, PartialEq, serde::Serialize)]
+pub struct PlannedChange {
+    pub file_path: String,
+    /// `create`, `edit`, `delete` or `rename`
+    pub change_type: String,
+    /// Source path of a rename
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub old_path: Option<String>,
+    /// Unified diff of the change, when Codex provided one
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub diff: Option<String>,
+}
+
+/// Payload for `chat:plan`, emitted at turn completion of a plan mode run
+#[derive(serde::Serialize, Clone)]
+pub struct PlanEvent {
+    pub session_id: String,
+    pub worktree_id: String,
+    pub changes: Vec<PlannedChange>,
+}
+
+/// Whether a Codex model accepts image input (the open-weight gpt-oss models are text-only)
+pub fn supports_vision(model: Option<&str>) -> bool {
+    !model.is_some_and(|m| m.contains("gpt-oss"))
+}
+
+/// Whether the execution mode runs Codex in a read-only sandbox
+pub fn is_read_only_mode(execution_mode: Option<&str>) -> bool {
+    execution_mode == Some("plan")
+}
+
 /// Get Codex sandbox and approval flags based on execution mode
 fn get_codex_sandbox_args(execution_mode: Option<&str>) -> Vec<&'static str> {
     match execution_mode {
@@ -39,13 +83,104 @@ fn get_codex_reasoning_effort(thinking_level: Option<&str>) -> &'static str {
     }
 }
 
+/// Extract output and success from a completed `command_execution` item
+///
+/// Codex reports `aggregated_output` (older versions: `output`), an `exit_code`
+/// and a `status` of `completed`/`failed`. Failed commands get a trailing
+/// "exit code" line so the failure is visible in the rendered output.
+pub fn parse_command_execution_result(item: &serde_json::Value) -> (String, bool) {
+    let output = item
+        .get("aggregated_output")
+        .or_else(|| item.get("output"))
+        .and_then(|v| v.as_str())
+        .unwrap_or("");
+    let exit_code = item.get("exit_code").and_then(|v| v.as_i64());
+    let status = item.get("status").and_then(|v| v.as_str());
+
+    let success = match (exit_code, status) {
+        (Some(code), _) => code == 0,
+        (None, Some(status)) => status != "failed",
+        (None, None) => true,
+    };
+
+    if success {
+        return (output.to_string(), true);
+    }
+
+    let indicator = match exit_code {
+        Some(code) => format!("Command failed with exit code {code}"),
+        None => "Command failed".to_string(),
+    };
+    let output = if output.is_empty() {
+        indicator
+    } else {
+        format!("{}\n{indicator}", output.trim_end())
+    };
+    (output, false)
+}
+
+/// Build the tool name and input for a `file_change` item
+///
+/// `change_type` is one of `create`/`edit`/`delete`/`rename`. The patch
+/// (`diff` or `unified_diff`) is passed through as `diff` so the frontend can
+/// render it, and renames include the previous path as `old_path`.
+pub fn parse_file_change(item: &serde_json::Value) -> (ToolKind, serde_json::Value) {
+    let str_field = |key: &str| item.get(key).and_then(|v| v.as_str());
+
+    let file_path = str_field("file_path").unwrap_or("");
+    let change_type = str_field("change_type").unwrap_or("edit");
+
+    // Unknown change types are shown as edits
+    let tool_name = match ToolKind::normalize(AiCliProvider::Codex, change_type) {
+        ToolKind::Other(_) => ToolKind::Edit,
+        kind => kind,
+    };
+
+    let mut input = serde_json::json!({
+        "file_path": file_path,
+        "change_type": change_type,
+    });
+    if change_type == "rename" {
+        // Codex reports the destination as new_path/move_path, file_path is the source
+        if let Some(new_path) = str_field("new_path").or_else(|| str_field("move_path")) {
+            input["old_path"] = file_path.into();
+            input["file_path"] = new_path.into();
+        }
+    }
+    if let Some(diff) = str_field("diff").or_else(|| str_field("unified_diff")) {
+        input["diff"] = diff.into();
+    }
+
+    (tool_name, input)
+}
+
+/// Build a planned change from a `file_change` item of a read-only run
+pub fn parse_planned_change(item: &serde_json::Value) -> PlannedChange {
+    let (_, input) = parse_file_change(item);
+    let str_field = |key: &str| input.get(key).and_then(|v| v.as_str()).map(str::to_string);
+
+    PlannedChange {
+        file_path: str_field("file_path").unwrap_or_default(),
+        change_type: str_field("change_type").unwrap_or_default(),
+        old_path: str_field("old_path"),
+        diff: str_field("diff"),
+    }
+}
+
 /// Process a single Codex JSONL event and emit appropriate frontend events
+///
+/// Content events come from the shared run log parser, so the streamed message
+/// matches the one rebuilt from the log later; only lifecycle events are
+/// handled here. `planned_changes` is set for read-only runs: file changes are
+/// collected there instead of being shown as applied edits, and emitted as
+/// `chat:plan` when the turn completes.
 fn process_codex_event(
     app: &tauri::AppHandle,
     session_id: &str,
     worktree_id: &str,
     line: &str,
-    full_content: &mut String,
+    transcript: &mut Transcript,
+    mut planned_changes: Option<&mut Vec<PlannedChange>>,
 ) -> Option<bool> {
     // Skip empty lines
     if line.trim().is_empty() {
@@ -57,158 +192,34 @@ fn process_codex_event(
         Ok(m) => m,
         Err(_) => {
             // Not JSON, treat as plain text content
-            full_content.push_str(line);
-            full_content.push('\n');
-            let _ = app.emit(
-                "chat:chunk",
-                ChunkEvent {
-                    session_id: session_id.to_string(),
-                    worktree_id: worktree_id.to_string(),
-                    content: format!("{line}\n"),
-                },
-            );
+            for event in plain_text_events(&AiCliProvider::Codex, line) {
+                event.emit(app, session_id, worktree_id);
+                transcript.apply(&event);
+            }
             return None;
         }
     };
 
     let event_type = msg.get("type").and_then(|v| v.as_str()).unwrap_or("");
+    // Same rule as replay: file changes of a read-only run aren't shown as edits
+    let format = RunFormat {
+        provider: AiCliProvider::Codex,
+        read_only: planned_changes.is_some(),
+    };
 
     match event_type {
         "item.completed" => {
-            if let Some(item) = msg.get("item") {
-                let item_type = item.get("type").and_then(|v| v.as_str()).unwrap_or("");
-
-                match item_type {
-                    "agent_message" => {
-                        if let Some(text) = item.get("text").and_then(|v| v.as_str()) {
-                            if !text.is_empty() {
-                                full_content.push_str(text);
-                                full_content.push('\n');
-                                let _ = app.emit(
-                                    "chat:chunk",
-                                    ChunkEvent {
-                                        session_id: session_id.to_string(),
-                                        worktree_id: worktree_id.to_string(),
-                                        content: format!("{text}\n"),
-                                    },
-                                );
-                            }
-                        }
-                    }
-                    "reasoning" => {
-                        if let Some(text) = item.get("text").and_then(|v| v.as_str()) {
-                            let _ = app.emit(
-                                "chat:thinking",
-                                ThinkingEvent {
-                                    session_id: session_id.to_string(),
-                                    worktree_id: worktree_id.to_string(),
-                                    content: text.to_string(),
-                                },
-                            );
-                        }
-                    }
-                    "command_execution" => {
-                        let command = item.get("command").and_then(|v| v.as_str()).unwrap_or("");
-                        let output = item.get("output").and_then(|v| v.as_str()).unwrap_or("");
-                        let tool_id = item
-                            .get("id")
-                            .and_then(|v| v.as_str())
-                            .unwrap_or("")
-                            .to_string();
-
-                        let _ = app.emit(
-                            "chat:tool_use",
-                            ToolUseEvent {
-                                session_id: session_id.to_string(),
-                                worktree_id: worktree_id.to_string(),
-                                id: tool_id.clone(),
-                                name: "Bash".to_string(),
-                                input: serde_json::json!({ "command": command }),
-                                parent_tool_use_id: None,
-                            },
-                        );
-
-                        let _ = app.emit(
-                            "chat:tool_result",
-                            ToolResultEvent {
-                                session_id: session_id.to_string(),
-                                worktree_id: worktree_id.to_string(),
-                                tool_use_id: tool_id,
-                                output: output.to_string(),
-                            },
-                        );
-                    }
-                    "file_change" => {
-                        let file_path = item.get("file_path").and_then(|v| v.as_str()).unwrap_or("");
-                        let change_type = item
-                            .get("change_type")
-                            .and_then(|v| v.as_str())
-                            .unwrap_or("edit");
-                        let tool_id = item
-                            .get("id")
-                            .and_then(|v| v.as_str())
-                            .unwrap_or("")
-                            .to_string();
-
-                        let tool_name = match change_type {
-                            "create" => "Write",
-                            "delete" => "Bash",
-                            _ => "Edit",
-                        };
-
-                        let _ = app.emit(
-                            "chat:tool_use",
-                            ToolUseEvent {
-                                session_id: session_id.to_string(),
-                                worktree_id: worktree_id.to_string(),
-                                id: tool_id,
-                                name: tool_name.to_string(),
-                                input: serde_json::json!({ "file_path": file_path }),
-                                parent_tool_use_id: None,
-                            },
-                        );
-                    }
-                    "mcp_tool_call" => {
-                        let tool_name = item.get("tool_name").and_then(|v| v.as_str()).unwrap_or("");
-                        let tool_id = item
-                            .get("id")
-                            .and_then(|v| v.as_str())
-                            .unwrap_or("")
-                            .to_string();
-                        let arguments = item.get("arguments").cloned().unwrap_or(serde_json::Value::Null);
-
-                        let _ = app.emit(
-                            "chat:tool_use",
-                            ToolUseEvent {
-                                session_id: session_id.to_string(),
-                                worktree_id: worktree_id.to_string(),
-                                id: tool_id,
-                                name: tool_name.to_string(),
-                                input: arguments,
-                                parent_tool_use_id: None,
-                            },
-                        );
-                    }
-                    _ => {
-                        if let Some(text) = item.get("text").and_then(|v| v.as_str()) {
-                            if !text.is_empty() {
-                                full_content.push_str(text);
-                                full_content.push('\n');
-                                let _ = app.emit(
-                                    "chat:chunk",
-                                    ChunkEvent {
-                                        session_id: session_id.to_string(),
-                                        worktree_id: worktree_id.to_string(),
-                                        content: format!("{text}\n"),
-                                    },
-                                );
-                            }
-                        }
-                    }
+            if let (Some(item), Some(planned_changes)) =
+                (msg.get("item"), planned_changes.as_deref_mut())
+            {
+                if item.get("type").and_then(|v| v.as_str()) == Some("file_change") {
+                    planned_changes.push(parse_planned_change(item));
                 }
             }
         }
         "item.started" => {
+            // Show commands as soon as they start; the completed item repeats
+            // the call with its result, so this isn't part of the transcript
             if let Some(item) = msg.get("item") {
                 let item_type = item.get("type").and_then(|v| v.as_str()).unwrap_or("");
 
@@ -226,7 +237,7 @@ fn process_codex_event(
                             session_id: session_id.to_string(),
                             worktree_id: worktree_id.to_string(),
                             id: tool_id,
-                            name: "Bash".to_string(),
+                            name: ToolKind::Bash.to_string(),
                             input: serde_json::json!({ "command": command }),
                             parent_tool_use_id: None,
                         },
@@ -240,6 +251,16 @@ fn process_codex_event(
                 let output_tokens = usage.get("output_tokens").and_then(|v| v.as_u64()).unwrap_or(0);
                 log::debug!("Codex turn completed: {input_tokens} in, {output_tokens} out");
             }
+            if let Some(planned_changes) = planned_changes.filter(|c| !c.is_empty()) {
+                let _ = app.emit(
+                    "chat:plan",
+                    PlanEvent {
+                        session_id: session_id.to_string(),
+                        worktree_id: worktree_id.to_string(),
+                        changes: std::mem::take(planned_changes),
+                    },
+                );
+            }
             return Some(true); // Signal completion
         }
         "turn.failed" | "error" => {
@@ -249,6 +270,15 @@ fn process_codex_event(
                 .or_else(|| msg.get("message").and_then(|m| m.as_str()))
                 .unwrap_or("Unknown error");
 
+            if is_auth_failure(error_msg) {
+                let error = ChatError::NotAuthenticated {
+                    cli: "Codex".to_string(),
+                    message: error_msg.to_string(),
+                };
+                emit_chat_error(app, session_id, worktree_id, &error);
+                return None;
+            }
+
             log::error!("Codex error: {error_msg}");
             let _ = app.emit(
                 "chat:error",
@@ -256,38 +286,27 @@ fn process_codex_event(
                     session_id: session_id.to_string(),
                     worktree_id: worktree_id.to_string(),
                     error: error_msg.to_string(),
+                    code: None,
                 },
             );
         }
         "thread.started" | "turn.started" => {
             log::trace!("Codex lifecycle event: {event_type}");
         }
-        _ => {
-            // Try common content fields
-            if let Some(text) = msg
-                .get("text")
-                .and_then(|v| v.as_str())
-                .or_else(|| msg.get("content").and_then(|v| v.as_str()))
-                .or_else(|| msg.get("output").and_then(|v| v.as_str()))
-            {
-                full_content.push_str(text);
-                full_content.push('\n');
-                let _ = app.emit(
-                    "chat:chunk",
-                    ChunkEvent {
-                        session_id: session_id.to_string(),
-                        worktree_id: worktree_id.to_string(),
-                        content: format!("{text}\n"),
-                    },
-                );
-            }
-        }
+        _ => {}
+    }
+
+    for event in events_from_json(&format, &msg) {
+        event.emit(app, session_id, worktree_id);
+        transcript.apply(&event);
     }
 
     None
 }
 
 /// Execute Codex CLI as a detached process and tail output
+///
+/// `poll_interval` is the base sleep between output polls (see `PollBackoff`).
 pub fn execute_codex_detached(
     app: &tauri::AppHandle,
     session_id: &str,
@@ -295,42 +314,32 @@ pub fn execute_codex_detached(
     _input_file: &Path,
     output_file: &Path,
     working_dir: &Path,
+    worktree_env: &[(String, String)],
     model: Option<&str>,
     execution_mode: Option<&str>,
     thinking_level: Option<&str>,
     prompt: &str,
-) -> Result<(u32, ClaudeResponse), String> {
+    images: &[PathBuf],
+    mcp_config_path: Option<&Path>,
+    poll_interval: Duration,
+) -> Result<(u32, ClaudeResponse), ChatError> {
     log::trace!("Executing Codex CLI (detached) for session: {session_id}");
     log::trace!("Output file: {output_file:?}");
     log::trace!("Working directory: {working_dir:?}");
 
     // Get CLI path
     let cli_path = get_codex_cli_path(app).map_err(|e| {
-        let error_msg = format!("Failed to get Codex CLI path: {e}");
-        log::error!("{error_msg}");
-        let _ = app.emit(
-            "chat:error",
-            ErrorEvent {
-                session_id: session_id.to_string(),
-                worktree_id: worktree_id.to_string(),
-                error: error_msg.clone(),
-            },
-        );
-        error_msg
+        let error = ChatError::Io(format!("Failed to get Codex CLI path: {e}"));
+        emit_chat_error(app, session_id, worktree_id, &error);
+        error
     })?;
 
     if !cli_path.exists() {
-        let error_msg = "Codex CLI not installed. Please install it from Settings.".to_string();
-        log::error!("{error_msg}");
-        let _ = app.emit(
-            "chat:error",
-            ErrorEvent {
-                session_id: session_id.to_string(),
-                worktree_id: worktree_id.to_string(),
-                error: error_msg.clone(),
-            },
-        );
-        return Err(error_msg);
+        let error = ChatError::CliNotInstalled {
+            cli: "Codex".to_string(),
+        };
+        emit_chat_error(app, session_id, worktree_id, &error);
+        return Err(error);
     }
 
     // Build args
@@ -342,6 +351,13 @@ pub fn execute_codex_detached(
         args.push(m.to_string());
     }
 
+    // Attached images (--image takes multiple values, so these must precede
+    // another flag rather than the positional prompt)
+    for image in images {
+        args.push("--image".to_string());
+        args.push(image.to_string_lossy().to_string());
+    }
+
     // Enable JSON streaming output
     args.push("--json".to_string());
 
@@ -355,8 +371,24 @@ pub fn execute_codex_detached(
     args.push("--config".to_string());
     args.push(format!("model_reasoning_effort=\"{reasoning_effort}\""));
 
+    // MCP servers, translated from the session's MCP config file
+    let mut mcp_env = Vec::new();
+    if let Some(path) = mcp_config_path {
+        let mcp = super::mcp::codex_mcp_args(path).map_err(|error| {
+            emit_chat_error(app, session_id, worktree_id, &error);
+            error
+        })?;
+        args.extend(mcp.args);
+        mcp_env = mcp.env;
+    }
+
     // Add the prompt as the last argument
-    args.push(prompt.to_string());
+    let read_only = is_read_only_mode(execution_mode);
+    if read_only {
+        args.push(format!("{prompt}\n\n{PLAN_MODE_INSTRUCTIONS}"));
+    } else {
+        args.push(prompt.to_string());
+    }
 
     log::debug!(
         "Codex CLI command: {} {}",
@@ -372,29 +404,53 @@ pub fn execute_codex_detached(
     let stderr_file = output_file.with_extension("stderr.log");
 
     // Ensure output file exists (for tailing)
-    std::fs::write(output_file, "").map_err(|e| format!("Failed to create output file: {e}"))?;
+    super::run_log::create_output_file(output_file)?;
 
     // Spawn detached process
+    let env_refs: Vec<(&str, &str)> = worktree_env
+        .iter()
+        .chain(&mcp_env)
+        .map(|(k, v)| (k.as_str(), v.as_str()))
+        .collect();
     let pid = spawn_detached_codex(
         &cli_path,
         &args,
         output_file,
         &stderr_file,
         working_dir,
-        &[],
+        &env_refs,
     )?;
 
     // Register process for cancellation
-    super::registry::register_process(session_id.to_string(), pid);
+    super::registry::register_process(session_id.to_string(), worktree_id, pid, None, output_file);
+
+    // Fail fast if the CLI dies right away (bad flag, missing library), unless
+    // it died because the user cancelled (cancel_process unregisters it first)
+    if let Some(error_msg) = detect_startup_crash(
+        "Codex",
+        pid,
+        output_file,
+        &stderr_file,
+        STARTUP_CRASH_WINDOW,
+        || !super::registry::is_process_running(session_id),
+    ) {
+        super::registry::unregister_process(session_id, output_file);
+        let error = ChatError::from_cli_failure("Codex", error_msg);
+        emit_chat_error(app, session_id, worktree_id, &error);
+        return Err(error);
+    }
 
     // Create tailer for output file
     let mut tailer =
         NdjsonTailer::new_from_start(output_file).map_err(|e| format!("Failed to create tailer: {e}"))?;
 
     // Tail loop
-    let mut full_content = String::new();
+    let mut transcript = Transcript::default();
     let start_time = Instant::now();
     let mut last_output_time = Instant::now();
+    let mut heartbeat = Heartbeat::new(HEARTBEAT_INTERVAL);
+    let mut backoff = PollBackoff::new(poll_interval);
+    let mut planned_changes = Vec::new();
     let mut got_first_output = false;
     let mut completed = false;
 
@@ -406,9 +462,11 @@ pub fn execute_codex_detached(
         }
 
         // Poll for new lines
+        let mut got_lines = false;
         match tailer.poll() {
             Ok(lines) => {
                 if !lines.is_empty() {
+                    got_lines = true;
                     got_first_output = true;
                     last_output_time = Instant::now();
 
@@ -418,7 +476,8 @@ pub fn execute_codex_detached(
                             session_id,
                             worktree_id,
                             &line,
-                            &mut full_content,
+                            &mut transcript,
+                            read_only.then_some(&mut planned_changes),
                         ) {
                             completed = true;
                             break;
@@ -439,18 +498,36 @@ pub fn execute_codex_detached(
         let process_alive = is_process_alive(pid);
 
         if !process_alive {
+            // Died without writing anything: report stderr now rather than
+            // waiting out the startup timeout
+            if !got_first_output && !has_output(output_file) {
+                let error = ChatError::from_cli_failure(
+                    "Codex",
+                    startup_crash_message("Codex", &stderr_file),
+                );
+                emit_chat_error(app, session_id, worktree_id, &error);
+                break;
+            }
+
             // Process died - give it a grace period to flush output
             if last_output_time.elapsed() > DEAD_PROCESS_GRACE_PERIOD {
                 log::trace!("Process {} died and no new output, ending tail", pid);
                 break;
             }
+        } else if let Some(silent_secs) = heartbeat.due(last_output_time) {
+            // Alive but silent (long reasoning): let the UI know it isn't hung
+            let _ = app.emit(
+                "chat:heartbeat",
+                HeartbeatEvent {
+                    session_id: session_id.to_string(),
+                    worktree_id: worktree_id.to_string(),
+                    silent_secs,
+                },
+            );
         }
 
         // Check startup timeout
         if !got_first_output && start_time.elapsed() > STARTUP_TIMEOUT {
-            let error_msg = "Codex CLI startup timeout - no output received";
-            log::error!("{error_msg}");
-
             // Read stderr for more info
             if let Ok(stderr) = std::fs::read_to_string(&stderr_file) {
                 if !stderr.is_empty() {
@@ -458,29 +535,25 @@ pub fn execute_codex_detached(
                 }
             }
 
-            let _ = app.emit(
-                "chat:error",
-                ErrorEvent {
-                    session_id: session_id.to_string(),
-                    worktree_id: worktree_id.to_string(),
-                    error: error_msg.to_string(),
-                },
-            );
+            let error = ChatError::StartupTimeout {
+                cli: "Codex".to_string(),
+            };
+            emit_chat_error(app, session_id, worktree_id, &error);
             break;
         }
 
-        thread::sleep(POLL_INTERVAL);
+        thread::sleep(backoff.next(got_lines));
     }
 
     // Unregister process
-    super::registry::unregister_process(session_id);
+    super::registry::unregister_process(session_id, output_file);
 
     log::info!(
         "Codex CLI completed, content length: {} chars",
-        full_content.len()
+        transcript.content.len()
     );
 
-    let response_text = full_content.trim().to_string();
+    let response_text = transcript.content.trim().to_string();
 
     // Emit done event
     let _ = app.emit(
@@ -498,10 +571,116 @@ pub fn execute_codex_detached(
         ClaudeResponse {
             content: response_text,
             session_id: session_id.to_string(),
-            tool_calls: Vec::new(),
-            content_blocks: Vec::new(),
+            tool_calls: transcript.tool_calls,
+            content_blocks: transcript.content_blocks,
             cancelled: false,
             usage: None,
         },
     ))
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_file_change_types() {
+        let change = |change_type: &str| {
+            parse_file_change(&serde_json::json!({
+                "type": "file_change",
+                "file_path": "src/lib.rs",
+                "change_type": change_type,
+            }))
+        };
+
+        assert_eq!(change("create").0, ToolKind::Write);
+        assert_eq!(change("edit").0, ToolKind::Edit);
+        assert_eq!(change("delete").0, ToolKind::Bash);
+        assert_eq!(change("rename").0, ToolKind::Edit);
+        assert_eq!(change("chmod").0, ToolKind::Edit);
+        assert_eq!(change("delete").1["file_path"], "src/lib.rs");
+        assert!(change("edit").1.get("diff").is_none());
+    }
+
+    #[test]
+    fn test_file_change_diff_and_rename() {
+        let (kind, input) = parse_file_change(&serde_json::json!({
+            "type": "file_change",
+            "file_path": "src/old.rs",
+            "new_path": "src/new.rs",
+            "change_type": "rename",
+            "unified_diff": "--- a/src/old.rs\n+++ b/src/new.rs\n",
+        }));
+        assert_eq!(kind, ToolKind::Edit);
+        assert_eq!(input["file_path"], "src/new.rs");
+        assert_eq!(input["old_path"], "src/old.rs");
+        assert_eq!(input["diff"], "--- a/src/old.rs\n+++ b/src/new.rs\n");
+
+        let (_, input) = parse_file_change(&serde_json::json!({
+            "file_path": "src/lib.rs",
+            "change_type": "edit",
+            "diff": "@@ -1 +1 @@\n-a\n+b\n",
+        }));
+        assert_eq!(input["diff"], "@@ -1 +1 @@\n-a\n+b\n");
+    }
+
+    #[test]
+    fn test_planned_change_from_file_change() {
+        let change = parse_planned_change(&serde_json::json!({
+            "type": "file_change",
+            "file_path": "src/old.rs",
+            "new_path": "src/new.rs",
+            "change_type": "rename",
+            "diff": "--- a/src/old.rs\n+++ b/src/new.rs\n",
+        }));
+        assert_eq!(
+            change,
+            PlannedChange {
+                file_path: "src/new.rs".to_string(),
+                change_type: "rename".to_string(),
+                old_path: Some("src/old.rs".to_string()),
+                diff: Some("--- a/src/old.rs\n+++ b/src/new.rs\n".to_string()),
+            }
+        );
+
+        assert!(is_read_only_mode(Some("plan")));
+        assert!(!is_read_only_mode(Some("build")));
+    }
+
+    #[test]
+    fn test_failed_command_execution_is_error() {
+        let item: serde_json::Value = serde_json::from_str(
+            r#"{"id":"item_3","type":"command_execution","command":"bash -lc 'cargo test'","aggregated_output":"error: could not compile\n","exit_code":101,"status":"failed"}"#,
+        )
+        .unwrap();
+
+        let (output, success) = parse_command_execution_result(&item);
+        assert!(!success);
+        assert_eq!(
+            output,
+            "error: could not compile\nCommand failed with exit code 101"
+        );
+    }
+
+    #[test]
+    fn test_successful_command_execution() {
+        let item = serde_json::json!({
+            "type": "command_execution",
+            "command": "ls",
+            "aggregated_output": "Cargo.toml\n",
+            "exit_code": 0,
+            "status": "completed"
+        });
+        assert_eq!(
+            parse_command_execution_result(&item),
+            ("Cargo.toml\n".to_string(), true)
+        );
+
+        // Status alone is enough to detect a failure
+        let item = serde_json::json!({ "type": "command_execution", "status": "failed" });
+        assert_eq!(
+            parse_command_execution_result(&item),
+            ("Command failed".to_string(), false)
+        );
+    }
+}
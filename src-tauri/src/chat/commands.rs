@@ -1,3 +1,4 @@
+use std::collections::HashSet;
 use std::io::Write;
 use std::path::PathBuf;
 use std::process::{Command, Stdio};
@@ -10,16 +11,17 @@ use tokio::sync::Semaphore;
 use tauri::{AppHandle, Manager};
 use uuid::Uuid;
 
+use super::error::ChatError;
 use super::naming::{spawn_naming_task, NamingRequest};
 use super::registry::cancel_process;
 use super::run_log;
 use super::storage::{
     delete_session_data, get_data_dir, get_index_path, get_session_dir, load_metadata,
-    load_sessions, with_sessions_mut,
+    load_sessions, with_index_mut, with_sessions_mut,
 };
 use super::types::{
-    AllSessionsEntry, AllSessionsResponse, ChatMessage, ClaudeContext, MessageRole, RunStatus,
-    Session, ThinkingLevel, WorktreeSessions,
+    AllSessionsEntry, AllSessionsResponse, ChatMessage, ClaudeContext, MessageRole,
+    ReviewReadyEvent, RunStatus, Session, StopReport, ThinkingLevel, WorktreeSessions,
 };
 use crate::claude_cli::get_cli_binary_path;
 use crate::projects::storage::load_projects_data;
@@ -173,9 +175,14 @@ pub async fn create_session(
         let session_number = sessions.next_session_number();
         let session_name = name.unwrap_or_else(|| format!("Session {session_number}"));
 
-        let session = Session::new(session_name, sessions.sessions.len() as u32);
+        let mut session = Session::new(session_name, sessions.sessions.len() as u32);
         let session_id = session.id.clone();
 
+        // Start with the worktree's default model. Unset values fall back to the
+        // app preferences, as for any session without its own selection.
+        session.selected_model = sessions.default_model.clone();
+        session.selected_thinking_level = sessions.default_thinking_level.clone();
+
         sessions.sessions.push(session.clone());
         sessions.active_session_id = Some(session_id);
 
@@ -184,6 +191,126 @@ pub async fn create_session(
     })
 }
 
+/// Duplicate a session to branch the conversation
+///
+/// The copy is placed right after the source and becomes the active session.
+/// It gets the source's finished runs and settings, but not runtime state
+/// like review or pending permission flags. It keeps the Claude session ID so
+/// context carries over; the first message forks it (see `send_chat_message`).
+///
+/// The run logs and metadata are written before the copy is added to the
+/// worktree index, so a failed duplicate never leaves a listed but empty session.
+#[tauri::command]
+pub async fn duplicate_session(
+    app: AppHandle,
+    worktree_id: String,
+    session_id: String,
+) -> Result<Session, String> {
+    log::trace!("Duplicating session: {session_id}");
+
+    let source_metadata = load_metadata(&app, &session_id)?
+        .ok_or_else(|| format!("Session not found: {session_id}"))?;
+    // A run still in progress belongs to the source only
+    let runs: Vec<_> = source_metadata
+        .runs
+        .into_iter()
+        .filter(|run| !matches!(run.status, RunStatus::Running | RunStatus::Resumable))
+        .collect();
+
+    let mut session = {
+        let sessions = load_sessions(&app, "", &worktree_id)?;
+        let source = sessions
+            .find_session(&session_id)
+            .ok_or_else(|| format!("Session not found: {session_id}"))?;
+
+        let mut copy = Session::new(format!("{} (copy)", source.name), source.order + 1);
+        copy.claude_session_id = source.claude_session_id.clone();
+        copy.selected_provider = source.selected_provider.clone();
+        copy.selected_model = source.selected_model.clone();
+        copy.selected_thinking_level = source.selected_thinking_level.clone();
+        copy.mcp_config_path = source.mcp_config_path.clone();
+        copy.session_naming_completed = true;
+        copy.message_count = source.message_count;
+        copy.answered_questions = source.answered_questions.clone();
+        copy.submitted_answers = source.submitted_answers.clone();
+        copy.fixed_findings = source.fixed_findings.clone();
+        copy.approved_plan_message_ids = source.approved_plan_message_ids.clone();
+        copy
+    };
+
+    run_log::copy_run_logs(&app, &session_id, &session.id, &runs)?;
+    // The copy's run snapshots need their own pins, or deleting the source
+    // would let `git gc` prune them
+    if let Some(worktree) = load_projects_data(&app)?.find_worktree(&worktree_id) {
+        for run in &runs {
+            let trees = [("start", &run.start_tree), ("end", &run.end_tree)];
+            for (which, tree) in trees {
+                let Some(tree) = tree else { continue };
+                if let Err(e) = crate::projects::git_status::pin_run_snapshot(
+                    &worktree.path,
+                    &session.id,
+                    &run.run_id,
+                    which,
+                    tree,
+                ) {
+                    log::warn!("Failed to pin {which} snapshot of run {}: {e}", run.run_id);
+                }
+            }
+        }
+    }
+    super::storage::with_metadata_mut(
+        &app,
+        &session.id,
+        &worktree_id,
+        &session.name,
+        session.order,
+        |metadata| {
+            metadata.update_from_session(&session);
+            metadata.runs = runs;
+            Ok(())
+        },
+    )?;
+
+    // Only list the copy once its data is complete
+    let added = with_sessions_mut(&app, "", &worktree_id, |sessions| {
+        // Re-read the source's position, which may have moved meanwhile
+        let source_order = sessions
+            .find_session(&session_id)
+            .map(|source| source.order)
+            .ok_or_else(|| format!("Session not found: {session_id}"))?;
+        session.order = source_order + 1;
+
+        for other in sessions
+            .sessions
+            .iter_mut()
+            .filter(|s| s.order > source_order)
+        {
+            other.order += 1;
+        }
+        let position = sessions
+            .sessions
+            .iter()
+            .position(|s| s.id == session_id)
+            .map_or(sessions.sessions.len(), |i| i + 1);
+        sessions.sessions.insert(position, session.clone());
+        sessions.active_session_id = Some(session.id.clone());
+        Ok(())
+    });
+    if let Err(e) = added {
+        if let Err(cleanup) = delete_session_data(&app, &session.id) {
+            log::warn!(
+                "Failed to remove data of unduplicated session {}: {cleanup}",
+                session.id
+            );
+        }
+        return Err(e);
+    }
+
+    session.messages = run_log::load_session_messages(&app, &session.id)?;
+    log::trace!("Duplicated session {session_id} as {}", session.id);
+    Ok(session)
+}
+
 /// Rename a session tab
 #[tauri::command]
 pub async fn rename_session(
@@ -205,6 +332,30 @@ pub async fn rename_session(
     })
 }
 
+/// Regenerate a session's name from its transcript
+///
+/// For when a conversation's topic has drifted from its first message.
+/// Returns the new name ("Empty Session" if the session has no messages).
+#[tauri::command]
+pub async fn regenerate_session_name(
+    app: AppHandle,
+    worktree_id: String,
+    session_id: String,
+) -> Result<String, String> {
+    log::trace!("Regenerating name for session: {session_id}");
+
+    let model = crate::load_preferences(app.clone())
+        .await
+        .map(|prefs| prefs.session_naming_model)
+        .unwrap_or_else(|_| crate::AppPreferences::default().session_naming_model);
+
+    spawn_blocking(move || {
+        super::naming::regenerate_session_name(&app, &worktree_id, &session_id, &model)
+    })
+    .await
+    .map_err(|e| format!("Failed to regenerate session name: {e}"))?
+}
+
 /// Update session-specific UI state (answered questions, fixed findings, etc.)
 /// All fields are optional - only provided fields are updated
 #[tauri::command]
@@ -254,6 +405,30 @@ pub async fn update_session_state(
     })
 }
 
+/// Discard a session's pending permission denials
+///
+/// Used when the user abandons a denied action instead of approving it, so the
+/// session doesn't stay stuck waiting for an answer that will never come.
+#[tauri::command]
+pub async fn clear_pending_denials(
+    app: AppHandle,
+    worktree_id: String,
+    session_id: String,
+) -> Result<(), String> {
+    log::trace!("Clearing pending permission denials for: {session_id}");
+
+    with_sessions_mut(&app, "", &worktree_id, |sessions| {
+        if let Some(session) = sessions.find_session_mut(&session_id) {
+            session.pending_permission_denials.clear();
+            session.denied_message_context = None;
+            session.waiting_for_input = false;
+            Ok(())
+        } else {
+            Err(format!("Session not found: {session_id}"))
+        }
+    })
+}
+
 /// Extract pasted image paths from message content
 /// Matches: [Image attached: /path/to/image.png - Use the Read tool to view this image]
 fn extract_image_paths(content: &str) -> Vec<String> {
@@ -266,6 +441,41 @@ fn extract_image_paths(content: &str) -> Vec<String> {
         .collect()
 }
 
+/// Whether a provider's model accepts image input (provider: claude, gemini, codex, kimi)
+pub fn model_supports_vision(provider: &str, model: Option<&str>) -> bool {
+    match provider {
+        "claude" => super::claude::supports_vision(model),
+        "gemini" => super::gemini::supports_vision(model),
+        "codex" => super::codex::supports_vision(model),
+        "kimi" => super::kimi::supports_vision(model),
+        _ => false,
+    }
+}
+
+/// Pasted images referenced in message content that still exist on disk
+///
+/// Returns nothing (logging a warning) if the selected model is text-only;
+/// the message keeps its image references either way.
+fn vision_images(content: &str, provider: &str, supports_vision: bool) -> Vec<PathBuf> {
+    let mut images: Vec<PathBuf> = extract_image_paths(content)
+        .into_iter()
+        .map(PathBuf::from)
+        .filter(|path| path.is_file())
+        .collect();
+    // The same image can recur anywhere in resumed history
+    let mut seen = HashSet::new();
+    images.retain(|path| seen.insert(path.clone()));
+
+    if !supports_vision && !images.is_empty() {
+        log::warn!(
+            "{provider} model does not accept images, dropping {} attached image(s)",
+            images.len()
+        );
+        return Vec::new();
+    }
+    images
+}
+
 /// Extract pasted text file paths from message content
 /// Matches: [Text file attached: /path/to/file.txt - Use the Read tool to view this file]
 fn extract_text_file_paths(content: &str) -> Vec<String> {
@@ -674,7 +884,14 @@ pub async fn delete_archived_session(
         sessions.sessions.remove(session_idx);
         log::trace!("Archived session permanently deleted: {session_id}");
         Ok(())
-    })
+    })?;
+
+    // Run logs and attached file copies (after the index update, so a failure
+    // here leaves an orphaned directory rather than a session without data)
+    if let Err(e) = delete_session_data(&app, &session_id) {
+        log::warn!("Failed to delete session data: {e}");
+    }
+    Ok(())
 }
 
 /// List archived sessions for a worktree
@@ -826,7 +1043,8 @@ pub async fn send_chat_message(
     parallel_execution_prompt_enabled: Option<bool>,
     ai_language: Option<String>,
     allowed_tools: Option<Vec<String>>,
-) -> Result<ChatMessage, String> {
+    kimi_max_iterations: Option<i32>,
+) -> Result<ChatMessage, ChatError> {
     let provider_str = provider.as_deref().unwrap_or("claude");
     log::info!("=== CHAT MESSAGE DEBUG ===");
     log::info!("Provider param received: {:?}", provider);
@@ -836,13 +1054,26 @@ pub async fn send_chat_message(
 
     // Validate inputs
     if message.trim().is_empty() {
-        return Err("Message cannot be empty".to_string());
+        return Err(ChatError::InvalidRequest(
+            "Message cannot be empty".to_string(),
+        ));
     }
 
     if worktree_path.is_empty() {
-        return Err("Worktree path cannot be empty".to_string());
+        return Err(ChatError::InvalidRequest(
+            "Worktree path cannot be empty".to_string(),
+        ));
     }
 
+    super::kimi::validate_max_iterations(kimi_max_iterations).map_err(ChatError::InvalidRequest)?;
+
+    // Read once for the whole send; the fields used below fall back to their
+    // defaults if preferences can't be loaded
+    let prefs = crate::load_preferences(app.clone())
+        .await
+        .map_err(|e| log::warn!("Failed to load preferences, using defaults: {e}"))
+        .ok();
+
     // Load sessions
     let mut sessions = load_sessions(&app, &worktree_path, &worktree_id)?;
 
@@ -883,7 +1114,7 @@ pub async fn send_chat_message(
 
     // Spawn unified naming task if either condition is met
     if is_first_worktree_message || is_first_session_message {
-        if let Ok(prefs) = crate::load_preferences(app.clone()).await {
+        if let Some(prefs) = &prefs {
             // Check if this is a base session - don't rename the default branch
             let is_base_session = load_projects_data(&app)
                 .ok()
@@ -959,12 +1190,13 @@ pub async fn send_chat_message(
                 worktree_id: worktree_id.clone(),
                 error: "Session not found. Please refresh the page or create a new session."
                     .to_string(),
+                code: None,
             };
             if let Err(e) = app.emit("chat:error", &error_event) {
                 log::error!("Failed to emit chat:error event: {e}");
             }
 
-            return Err(error_msg);
+            return Err(ChatError::InvalidRequest(error_msg));
         }
     };
 
@@ -986,6 +1218,21 @@ pub async fn send_chat_message(
         .find_session(&session_id)
         .and_then(|s| s.claude_session_id.clone());
 
+    // A duplicated session shares its Claude session with the original until one
+    // of them sends a message, so resume it as a fork instead of appending to both
+    let fork_claude_session = claude_session_id.as_deref().is_some_and(|sid| {
+        sessions
+            .sessions
+            .iter()
+            .any(|s| s.id != session_id && s.claude_session_id.as_deref() == Some(sid))
+    });
+
+    // MCP config chosen for this session, kept across resumes
+    let mcp_config_path = sessions
+        .find_session(&session_id)
+        .and_then(|s| s.mcp_config_path.clone())
+        .map(PathBuf::from);
+
     // Start NDJSON run log for crash recovery
     let mut run_log_writer = run_log::start_run(
         &app,
@@ -995,6 +1242,7 @@ pub async fn send_chat_message(
         session_order,
         &user_message_id,
         &message,
+        &crate::ai_cli::types::AiCliProvider::from_str(provider_str).unwrap_or_default(),
         model.as_deref(),
         execution_mode.as_deref(),
         thinking_level
@@ -1008,8 +1256,58 @@ pub async fn send_chat_message(
     let output_file = run_log_writer.output_file_path()?;
     let run_id = run_log_writer.run_id().to_string();
 
+    // Snapshot the worktree so get_run_diff can show what this run changed
+    match crate::projects::git_status::snapshot_worktree_tree(&worktree_path) {
+        Ok(tree) => {
+            if let Err(e) = crate::projects::git_status::pin_run_snapshot(
+                &worktree_path,
+                &session_id,
+                &run_id,
+                "start",
+                &tree,
+            ) {
+                log::warn!("Failed to pin run start snapshot: {e}");
+            }
+            let head = crate::projects::git_status::get_head_sha(&worktree_path);
+            if let Err(e) = run_log_writer.set_start_snapshot(head, tree) {
+                log::warn!("Failed to record run start snapshot: {e}");
+            }
+        }
+        Err(e) => log::warn!("Failed to snapshot worktree at run start: {e}"),
+    }
+
+    // Files attached since the last message are sent once, ahead of the message.
+    // They stay attached until the CLI has actually run with them.
+    let attachments = load_metadata(&app, &session_id)
+        .map(|metadata| metadata.map(|m| m.attached_files).unwrap_or_default())
+        .unwrap_or_else(|e| {
+            log::warn!("Failed to load attached files: {e}");
+            Vec::new()
+        });
+    let attached_context = format_attached_files(&attachments);
+
+    // Execute the appropriate CLI based on provider
+    // Default to Claude if no provider specified
+    let effective_provider = provider.as_deref().unwrap_or("claude");
+
+    // Claude reads pasted images from the input file; other CLIs get them as args
+    let input_images = match effective_provider {
+        "gemini" | "codex" | "kimi" => Vec::new(),
+        _ => vision_images(
+            &message,
+            "Claude",
+            super::claude::supports_vision(model.as_deref()),
+        ),
+    };
+
     // Write input file with the user message
-    run_log::write_input_file(&app, &session_id, &run_id, &message)?;
+    run_log::write_input_file(
+        &app,
+        &session_id,
+        &run_id,
+        &format!("{attached_context}{message}"),
+        &input_images,
+    )?;
 
     // Use passed parameter for thinking override (computed by frontend based on preference + manual override)
     let disable_thinking_in_non_plan_modes = disable_thinking_for_mode.unwrap_or(false);
@@ -1017,13 +1315,29 @@ pub async fn send_chat_message(
     // Use passed parameter for parallel execution prompt (default false - experimental)
     let parallel_execution_prompt = parallel_execution_prompt_enabled.unwrap_or(false);
 
-    // Execute the appropriate CLI based on provider
-    // Default to Claude if no provider specified
-    let effective_provider = provider.as_deref().unwrap_or("claude");
+    // Base poll interval for the Codex/Kimi tail loops
+    let poll_interval = prefs
+        .as_ref()
+        .map(|prefs| super::tail::poll_interval_from_ms(prefs.tail_poll_interval_ms))
+        .unwrap_or(super::tail::POLL_INTERVAL);
+
+    // Env vars from the worktree's .jean.env and .env, if enabled, for the CLI
+    let (load_jean_env, load_project_dotenv) = prefs
+        .as_ref()
+        .map(|prefs| (prefs.load_jean_env, prefs.load_project_dotenv))
+        .unwrap_or((false, false));
+    let worktree_env = super::env_file::load_worktree_env(
+        context.worktree_path.as_ref(),
+        load_jean_env,
+        load_project_dotenv,
+    );
 
     let (pid, claude_response) = match effective_provider {
         "gemini" => {
             log::trace!("Using Gemini CLI for provider: {effective_provider}");
+            let plain_text_output = prefs
+                .as_ref()
+                .is_some_and(|prefs| prefs.gemini_plain_text_output);
             super::gemini::execute_gemini_detached(
                 &app,
                 &session_id,
@@ -1031,8 +1345,15 @@ pub async fn send_chat_message(
                 &input_file,
                 &output_file,
                 context.worktree_path.as_ref(),
+                &worktree_env,
                 model.as_deref(),
                 execution_mode.as_deref(),
+                plain_text_output,
+                &vision_images(
+                    &message,
+                    "Gemini",
+                    super::gemini::supports_vision(model.as_deref()),
+                ),
             )?
         }
         "codex" => {
@@ -1042,15 +1363,10 @@ pub async fn send_chat_message(
             // Codex CLI is stateless, so we must provide the full conversation history
             let history = run_log::load_session_messages(&app, &session_id)
                 .unwrap_or_default();
-
-            let mut full_prompt = String::new();
-            for msg in history {
-                let role = match msg.role {
-                    MessageRole::User => "User",
-                    MessageRole::Assistant => "Assistant",
-                };
-                full_prompt.push_str(&format!("{}: {}\n\n", role, msg.content));
-            }
+            let full_prompt = history_prompt(
+                history.iter().map(|msg| (&msg.role, msg.content.as_str())),
+                &attached_context,
+            );
 
             // Overwrite the input file with the full history
             if let Err(e) = std::fs::write(&input_file, &full_prompt) {
@@ -1066,10 +1382,19 @@ pub async fn send_chat_message(
                 &input_file,
                 &output_file,
                 context.worktree_path.as_ref(),
+                &worktree_env,
                 model.as_deref(),
                 execution_mode.as_deref(),
                 thinking_level.as_ref().map(|t| t.as_str()),
                 &full_prompt,
+                // Images from the whole history, since Codex replays it every turn
+                &vision_images(
+                    &full_prompt,
+                    "Codex",
+                    super::codex::supports_vision(model.as_deref()),
+                ),
+                mcp_config_path.as_deref(),
+                poll_interval,
             )?
         }
         "kimi" => {
@@ -1079,15 +1404,10 @@ pub async fn send_chat_message(
             // Kimi CLI is stateless, so we must provide the full conversation history
             let history = run_log::load_session_messages(&app, &session_id)
                 .unwrap_or_default();
-
-            let mut full_prompt = String::new();
-            for msg in history {
-                let role = match msg.role {
-                    MessageRole::User => "User",
-                    MessageRole::Assistant => "Assistant",
-                };
-                full_prompt.push_str(&format!("{}: {}\n\n", role, msg.content));
-            }
+            let full_prompt = history_prompt(
+                history.iter().map(|msg| (&msg.role, msg.content.as_str())),
+                &attached_context,
+            );
 
             // Overwrite the input file with the full history
             if let Err(e) = std::fs::write(&input_file, &full_prompt) {
@@ -1096,6 +1416,13 @@ pub async fn send_chat_message(
                 log::trace!("Wrote full history to input file for Kimi ({} bytes)", full_prompt.len());
             }
 
+            // Kimi takes text only; this just logs any images being dropped
+            vision_images(
+                &full_prompt,
+                "Kimi",
+                super::kimi::supports_vision(model.as_deref()),
+            );
+
             super::kimi::execute_kimi_detached(
                 &app,
                 &session_id,
@@ -1103,14 +1430,19 @@ pub async fn send_chat_message(
                 &input_file,
                 &output_file,
                 context.worktree_path.as_ref(),
+                &worktree_env,
                 model.as_deref(),
                 execution_mode.as_deref(),
                 thinking_level.as_ref().map(|t| t.as_str()),
+                kimi_max_iterations,
                 &full_prompt,
+                poll_interval,
             )?
         }
         _ => {
             // Default to Claude CLI
+            let wsl_distro = prefs.as_ref().and_then(|prefs| prefs.wsl_distro.clone());
+
             // If resume fails with "session not found", retry without the session ID
             let mut claude_session_id_for_call = claude_session_id.clone();
             loop {
@@ -1123,7 +1455,9 @@ pub async fn send_chat_message(
                     &input_file,
                     &output_file,
                     context.worktree_path.as_ref(),
+                    &worktree_env,
                     claude_session_id_for_call.as_deref(),
+                    fork_claude_session,
                     model.as_deref(),
                     execution_mode.as_deref(),
                     thinking_level.as_ref(),
@@ -1131,21 +1465,23 @@ pub async fn send_chat_message(
                     disable_thinking_in_non_plan_modes,
                     parallel_execution_prompt,
                     ai_language.as_deref(),
+                    mcp_config_path.as_deref(),
+                    wsl_distro.as_deref(),
                 ) {
                     Ok((pid, response)) => {
                         log::trace!("execute_claude_detached succeeded (PID: {pid})");
                         break (pid, response);
                     }
                     Err(e) => {
-                        // Check if this is a session not found error and we were trying to resume
-                        let is_session_not_found = e.to_lowercase().contains("session")
-                            && (e.to_lowercase().contains("not found")
-                                || e.to_lowercase().contains("invalid")
-                                || e.to_lowercase().contains("expired"));
-
-                        if is_session_not_found && claude_session_id_for_call.is_some() {
+                        // Claude rejected the stored session (expired/invalid): start fresh
+                        let resume_rejected = matches!(
+                            &e,
+                            ChatError::ProcessCrashed { message }
+                                if super::claude::is_resume_rejected(message)
+                        );
+                        if resume_rejected && claude_session_id_for_call.is_some() {
                             log::warn!(
-                                "Session not found, clearing stored session ID and retrying: {}",
+                                "Claude rejected session {}, clearing stored session ID and starting a new conversation: {e}",
                                 claude_session_id_for_call.as_deref().unwrap_or("")
                             );
 
@@ -1178,6 +1514,25 @@ pub async fn send_chat_message(
         log::warn!("Failed to delete input file: {e}");
     }
 
+    // Snapshot the worktree again so the run's diff stays stable after later edits
+    match crate::projects::git_status::snapshot_worktree_tree(&worktree_path) {
+        Ok(tree) => {
+            if let Err(e) = crate::projects::git_status::pin_run_snapshot(
+                &worktree_path,
+                &session_id,
+                &run_id,
+                "end",
+                &tree,
+            ) {
+                log::warn!("Failed to pin run end snapshot: {e}");
+            }
+            if let Err(e) = run_log_writer.set_end_snapshot(tree) {
+                log::warn!("Failed to record run end snapshot: {e}");
+            }
+        }
+        Err(e) => log::warn!("Failed to snapshot worktree at run end: {e}"),
+    }
+
     // Handle cancellation: only save if there's meaningful content (>10 chars) or tool calls
     // This avoids cluttering history with empty cancelled messages from instant cancellations
     let has_meaningful_content = claude_response.content.len() >= 10;
@@ -1210,6 +1565,7 @@ pub async fn send_chat_message(
             Ok(())
         })?;
 
+        // The attachments stay attached too, to go out with the restored message
         log::trace!("Chat cancelled with no meaningful content for session: {session_id}");
         // Return a minimal cancelled message (not persisted, just for UI)
         return Ok(ChatMessage {
@@ -1230,6 +1586,29 @@ pub async fn send_chat_message(
         });
     }
 
+    // The CLI ran with the attached files: detach them and delete their copies
+    if !attachments.is_empty() {
+        if let Err(e) = with_metadata_mut(
+            &app,
+            &session_id,
+            &worktree_id,
+            &session_name,
+            session_order,
+            |metadata| {
+                // Files attached while the run was going are kept for the next message
+                metadata
+                    .attached_files
+                    .retain(|a| !attachments.iter().any(|s| s.stored_path == a.stored_path));
+                Ok(())
+            },
+        ) {
+            log::warn!("Failed to detach sent files: {e}");
+        }
+        for attachment in &attachments {
+            delete_pasted_file(&attachment.stored_path);
+        }
+    }
+
     // Create assistant message with tool calls and content blocks
     let assistant_msg_id = Uuid::new_v4().to_string();
     let assistant_msg = ChatMessage {
@@ -1284,10 +1663,142 @@ pub async fn send_chat_message(
         log::trace!("Chat message cancelled but partial response saved for session: {session_id}");
     } else {
         log::trace!("Chat message sent and response received for session: {session_id}");
+
+        // Post-run step: signal that the run left changes to review
+        if let Some(event) = check_review_ready(&worktree_path, &session_id, &worktree_id) {
+            use tauri::Emitter;
+            if let Err(e) = app.emit("session:review_ready", &event) {
+                log::error!("Failed to emit session:review_ready event: {e}");
+            }
+        }
     }
     Ok(assistant_msg)
 }
 
+/// Build a review_ready event if the worktree has uncommitted changes after a run
+fn check_review_ready(
+    worktree_path: &str,
+    session_id: &str,
+    worktree_id: &str,
+) -> Option<ReviewReadyEvent> {
+    let changed_files = match crate::projects::git::get_uncommitted_count(worktree_path) {
+        Ok(count) => count,
+        Err(e) => {
+            log::warn!("Failed to check git status after run: {e}");
+            return None;
+        }
+    };
+
+    if changed_files == 0 {
+        return None;
+    }
+
+    Some(ReviewReadyEvent {
+        session_id: session_id.to_string(),
+        worktree_id: worktree_id.to_string(),
+        changed_files,
+    })
+}
+
+/// Get the diff of everything a run changed in the worktree
+///
+/// Compares the snapshot taken at run start with the one taken at run end
+/// (or the current working directory if the run is still in progress).
+#[tauri::command]
+pub async fn get_run_diff(
+    app: AppHandle,
+    session_id: String,
+    run_id: String,
+) -> Result<crate::projects::git_status::GitDiff, String> {
+    log::trace!("Getting diff for run {run_id} in session {session_id}");
+
+    let metadata = load_metadata(&app, &session_id)?
+        .ok_or_else(|| format!("Session not found: {session_id}"))?;
+    let run = metadata
+        .find_run(&run_id)
+        .ok_or_else(|| format!("Run not found: {run_id}"))?;
+    let start_tree = run
+        .start_tree
+        .as_deref()
+        .ok_or_else(|| "No worktree snapshot was recorded for this run".to_string())?;
+
+    let projects_data = load_projects_data(&app)?;
+    let worktree = projects_data
+        .find_worktree(&metadata.worktree_id)
+        .ok_or_else(|| format!("Worktree not found: {}", metadata.worktree_id))?;
+
+    let resolve = |which: &str, tree: &str| {
+        crate::projects::git_status::resolve_run_snapshot(
+            &worktree.path,
+            &session_id,
+            &run_id,
+            which,
+            tree,
+        )
+    };
+    let start_tree = resolve("start", start_tree)?;
+    let end_tree = run
+        .end_tree
+        .as_deref()
+        .map(|tree| resolve("end", tree))
+        .transpose()?;
+
+    crate::projects::git_status::get_snapshot_diff(&worktree.path, &start_tree, end_tree.as_deref())
+}
+
+/// Revert everything a run changed in the worktree
+///
+/// Restores the working directory to the run's start snapshot. Requires
+/// `force` as confirmation, and refuses if files changed after the run ended.
+#[tauri::command]
+pub async fn revert_run_changes(
+    app: AppHandle,
+    session_id: String,
+    run_id: String,
+    force: bool,
+) -> Result<(), String> {
+    log::trace!("Reverting changes of run {run_id} in session {session_id}");
+
+    if !force {
+        return Err(
+            "Reverting discards all changes made by this run. Pass force to confirm.".to_string(),
+        );
+    }
+
+    let metadata = load_metadata(&app, &session_id)?
+        .ok_or_else(|| format!("Session not found: {session_id}"))?;
+    let run = metadata
+        .find_run(&run_id)
+        .ok_or_else(|| format!("Run not found: {run_id}"))?;
+    let (Some(start_tree), Some(end_tree)) = (run.start_tree.as_deref(), run.end_tree.as_deref())
+    else {
+        return Err("This run has no complete worktree snapshot to revert to".to_string());
+    };
+
+    let projects_data = load_projects_data(&app)?;
+    let worktree = projects_data
+        .find_worktree(&metadata.worktree_id)
+        .ok_or_else(|| format!("Worktree not found: {}", metadata.worktree_id))?;
+
+    // Errors clearly if a snapshot was pruned, rather than failing on a bad object
+    let resolve = |which: &str, tree: &str| {
+        crate::projects::git_status::resolve_run_snapshot(
+            &worktree.path,
+            &session_id,
+            &run_id,
+            which,
+            tree,
+        )
+    };
+    let start_tree = resolve("start", start_tree)?;
+    let end_tree = resolve("end", end_tree)?;
+
+    crate::projects::git_status::revert_to_snapshot(&worktree.path, &start_tree, &end_tree)?;
+
+    log::trace!("Reverted changes of run {run_id}");
+    Ok(())
+}
+
 /// Clear chat history for a session
 /// This also clears the Claude session ID, starting a fresh conversation
 /// Preserves the selected model and thinking level preferences
@@ -1357,6 +1868,33 @@ pub async fn set_session_model(
     })
 }
 
+/// Set or clear the MCP config file passed to Claude/Codex for a session
+#[tauri::command]
+pub async fn set_session_mcp_config(
+    app: AppHandle,
+    worktree_id: String,
+    worktree_path: String,
+    session_id: String,
+    mcp_config_path: Option<String>,
+) -> Result<(), String> {
+    log::trace!("Setting MCP config for session {session_id}: {mcp_config_path:?}");
+
+    // Reject a bad path now rather than on the next message
+    if let Some(path) = &mcp_config_path {
+        super::mcp::validate_mcp_config(std::path::Path::new(path))?;
+    }
+
+    with_sessions_mut(&app, &worktree_path, &worktree_id, |sessions| {
+        if let Some(session) = sessions.find_session_mut(&session_id) {
+            session.mcp_config_path = mcp_config_path.clone();
+            log::trace!("MCP config selection saved");
+            Ok(())
+        } else {
+            Err(format!("Session not found: {session_id}"))
+        }
+    })
+}
+
 /// Infers AI provider from model string
 fn infer_provider_from_model(model: &str) -> String {
     let model_lower = model.to_lowercase();
@@ -1399,6 +1937,27 @@ pub async fn set_session_thinking_level(
     })
 }
 
+/// Set the model and thinking level new sessions in a worktree start with
+///
+/// Pass None to clear a value, so new sessions fall back to the app preferences.
+#[tauri::command]
+pub async fn set_worktree_default_model(
+    app: AppHandle,
+    worktree_id: String,
+    model: Option<String>,
+    thinking_level: Option<ThinkingLevel>,
+) -> Result<(), String> {
+    log::trace!(
+        "Setting default model for worktree {worktree_id}: {model:?}, thinking: {thinking_level:?}"
+    );
+
+    with_index_mut(&app, &worktree_id, |index| {
+        index.default_model = model;
+        index.default_thinking_level = thinking_level;
+        Ok(())
+    })
+}
+
 /// Cancel a running Claude chat request for a session
 /// Returns true if a process was found and cancelled, false if no process was running
 #[tauri::command]
@@ -1411,6 +1970,60 @@ pub async fn cancel_chat_message(
     cancel_process(&app, &session_id, &worktree_id)
 }
 
+/// Cancel a running chat request, giving the CLI `grace_ms` to exit cleanly
+/// before it is force-killed (defaults to 3s)
+#[tauri::command]
+pub async fn cancel_session(
+    app: AppHandle,
+    session_id: String,
+    worktree_id: String,
+    grace_ms: Option<u64>,
+) -> Result<bool, String> {
+    log::trace!("Cancel session requested for session: {session_id}");
+    let grace = grace_ms
+        .map(std::time::Duration::from_millis)
+        .unwrap_or(super::registry::DEFAULT_CANCEL_GRACE);
+    spawn_blocking(move || super::registry::cancel_session(&app, &session_id, &worktree_id, grace))
+        .await
+        .map_err(|e| format!("Failed to cancel session: {e}"))?
+}
+
+/// Cancel every running chat session of a worktree, e.g. when closing it
+///
+/// Each CLI gets `grace_ms` to exit cleanly before it is force-killed (defaults
+/// to 3s). Returns the number of sessions stopped.
+#[tauri::command]
+pub async fn cancel_worktree_sessions(
+    app: AppHandle,
+    worktree_id: String,
+    grace_ms: Option<u64>,
+) -> Result<usize, String> {
+    log::trace!("Cancel sessions requested for worktree: {worktree_id}");
+    let grace = grace_ms
+        .map(std::time::Duration::from_millis)
+        .unwrap_or(super::registry::DEFAULT_CANCEL_GRACE);
+    spawn_blocking(move || super::registry::cancel_worktree_sessions(&app, &worktree_id, grace))
+        .await
+        .map_err(|e| format!("Failed to cancel worktree sessions: {e}"))
+}
+
+/// Stop every running chat session, e.g. before quitting or installing a CLI
+///
+/// Each CLI gets `grace_ms` to exit cleanly before it is force-killed (defaults to 3s).
+#[tauri::command]
+pub async fn stop_all_sessions(
+    app: AppHandle,
+    grace_ms: Option<u64>,
+) -> Result<StopReport, String> {
+    log::trace!("Stop all sessions requested");
+    let grace = grace_ms
+        .map(std::time::Duration::from_millis)
+        .unwrap_or(super::registry::DEFAULT_CANCEL_GRACE);
+    spawn_blocking(move || super::registry::stop_all_sessions(&app, grace))
+        .await
+        .map_err(|e| format!("Failed to stop sessions: {e}"))
+}
+
 /// Check if any sessions have running Claude processes
 /// Used for quit confirmation dialog to prevent accidental closure during active sessions
 #[tauri::command]
@@ -1928,30 +2541,224 @@ pub async fn open_file_in_default_app(
             _ => std::process::Command::new("code").arg(&path).spawn(),
         };
 
-        result.map_err(|e| format!("Failed to open {editor_app}: {e}"))?;
+        result.map_err(|e| format!("Failed to open {editor_app}: {e}"))?;
+    }
+
+    #[cfg(target_os = "windows")]
+    {
+        let result = match editor_app.as_str() {
+            "cursor" => std::process::Command::new("cursor").arg(&path).spawn(),
+            _ => std::process::Command::new("code").arg(&path).spawn(),
+        };
+
+        result.map_err(|e| format!("Failed to open {editor_app}: {e}"))?;
+    }
+
+    #[cfg(target_os = "linux")]
+    {
+        let result = match editor_app.as_str() {
+            "cursor" => std::process::Command::new("cursor").arg(&path).spawn(),
+            _ => std::process::Command::new("code").arg(&path).spawn(),
+        };
+
+        result.map_err(|e| format!("Failed to open {editor_app}: {e}"))?;
+    }
+
+    Ok(())
+}
+
+// ============================================================================
+// File Attachment Commands (local files as additional chat context)
+// ============================================================================
+
+use super::storage::with_metadata_mut;
+use super::types::FileAttachment;
+use crate::provider_usage::types::{estimate_text_tokens, TokenEstimate};
+
+/// Maximum size of a file that can be attached as context (1MB)
+const MAX_ATTACHMENT_SIZE: u64 = 1024 * 1024;
+
+/// Maximum bytes of each attached file included in the prompt
+const ATTACHMENT_PROMPT_LIMIT: usize = 32 * 1024;
+
+/// Number of leading bytes scanned for null bytes when detecting binary files
+const BINARY_SNIFF_LEN: usize = 8 * 1024;
+
+/// Whether file content looks binary (has a null byte near the start)
+fn is_binary_content(bytes: &[u8]) -> bool {
+    bytes.iter().take(BINARY_SNIFF_LEN).any(|b| *b == 0)
+}
+
+/// Attach a local file to a session as additional context
+///
+/// The file is copied into the session's data directory, so later edits to the
+/// original don't change what was attached. Its path and (truncated) content
+/// are included in the next message sent in this session.
+#[tauri::command]
+pub async fn attach_file_context(
+    app: AppHandle,
+    session_id: String,
+    file_path: String,
+) -> Result<(), String> {
+    log::trace!("Attaching file context to session {session_id}: {file_path}");
+
+    let file_metadata = std::fs::metadata(&file_path)
+        .map_err(|e| format!("Failed to read file {file_path}: {e}"))?;
+    if !file_metadata.is_file() {
+        return Err(format!("Not a file: {file_path}"));
+    }
+
+    let size = file_metadata.len();
+    if size > MAX_ATTACHMENT_SIZE {
+        return Err(format!(
+            "File too large: {size} bytes. Maximum size: {MAX_ATTACHMENT_SIZE} bytes (1MB)"
+        ));
+    }
+
+    let content =
+        std::fs::read(&file_path).map_err(|e| format!("Failed to read file {file_path}: {e}"))?;
+    if is_binary_content(&content) {
+        return Err(format!("Cannot attach binary file: {file_path}"));
+    }
+
+    let session = load_metadata(&app, &session_id)?
+        .ok_or_else(|| format!("Session not found: {session_id}"))?;
+
+    let attachments_dir = get_session_dir(&app, &session_id)?.join("attachments");
+    std::fs::create_dir_all(&attachments_dir)
+        .map_err(|e| format!("Failed to create attachments directory: {e}"))?;
+
+    // Copy the file atomically (temp file + rename)
+    let timestamp = now();
+    let short_uuid = &Uuid::new_v4().to_string()[..8];
+    let stored_path = attachments_dir.join(format!("attachment-{timestamp}-{short_uuid}.txt"));
+    let temp_path = stored_path.with_extension("tmp");
+    std::fs::write(&temp_path, &content)
+        .map_err(|e| format!("Failed to write attachment file: {e}"))?;
+    std::fs::rename(&temp_path, &stored_path)
+        .map_err(|e| format!("Failed to finalize attachment file: {e}"))?;
+
+    let attachment = FileAttachment {
+        path: file_path,
+        stored_path: stored_path.to_string_lossy().to_string(),
+        size,
+        attached_at: timestamp,
+    };
+
+    with_metadata_mut(
+        &app,
+        &session_id,
+        &session.worktree_id,
+        &session.name,
+        session.order,
+        |metadata| {
+            // Attaching the same file again replaces the earlier copy
+            let (replaced, kept) = std::mem::take(&mut metadata.attached_files)
+                .into_iter()
+                .partition(|a| a.path == attachment.path);
+            metadata.attached_files = kept;
+            for old in replaced {
+                delete_pasted_file(&old.stored_path);
+            }
+            metadata.attached_files.push(attachment);
+            Ok(())
+        },
+    )
+}
+
+/// Render attached files as a context block to prepend to the next prompt
+///
+/// Returns an empty string when nothing is attached.
+fn format_attached_files(attachments: &[FileAttachment]) -> String {
+    let mut context = String::new();
+    for attachment in attachments {
+        match std::fs::read(&attachment.stored_path) {
+            Ok(bytes) => context.push_str(&format_attached_file(
+                &attachment.path,
+                &String::from_utf8_lossy(&bytes),
+            )),
+            Err(e) => log::warn!("Failed to read attachment {}: {e}", attachment.stored_path),
+        }
     }
+    context
+}
 
-    #[cfg(target_os = "windows")]
-    {
-        let result = match editor_app.as_str() {
-            "cursor" => std::process::Command::new("cursor").arg(&path).spawn(),
-            _ => std::process::Command::new("code").arg(&path).spawn(),
+/// Render a conversation as the prompt for a stateless CLI (Codex, Kimi)
+///
+/// `attached_context` goes with the last message, the turn being sent, so
+/// the model reads the files as part of the current request rather than as
+/// the start of the conversation.
+fn history_prompt<'a>(
+    history: impl IntoIterator<Item = (&'a MessageRole, &'a str)>,
+    attached_context: &str,
+) -> String {
+    let history: Vec<_> = history.into_iter().collect();
+    let mut prompt = String::new();
+    for (i, (role, content)) in history.iter().enumerate() {
+        let role = match role {
+            MessageRole::User => "User",
+            MessageRole::Assistant => "Assistant",
         };
-
-        result.map_err(|e| format!("Failed to open {editor_app}: {e}"))?;
+        let context = if i + 1 == history.len() {
+            attached_context
+        } else {
+            ""
+        };
+        prompt.push_str(&format!("{role}: {context}{content}\n\n"));
     }
+    prompt
+}
 
-    #[cfg(target_os = "linux")]
-    {
-        let result = match editor_app.as_str() {
-            "cursor" => std::process::Command::new("cursor").arg(&path).spawn(),
-            _ => std::process::Command::new("code").arg(&path).spawn(),
-        };
+/// Render a single attached file, truncating its content to ATTACHMENT_PROMPT_LIMIT
+fn format_attached_file(path: &str, content: &str) -> String {
+    let mut end = content.len().min(ATTACHMENT_PROMPT_LIMIT);
+    while !content.is_char_boundary(end) {
+        end -= 1;
+    }
 
-        result.map_err(|e| format!("Failed to open {editor_app}: {e}"))?;
+    let mut block = format!("<attached_file path=\"{path}\">\n{}\n", &content[..end]);
+    if end < content.len() {
+        block.push_str(&format!(
+            "[Truncated to the first {end} of {} bytes - Use the Read tool to view the rest of this file]\n",
+            content.len()
+        ));
     }
+    block.push_str("</attached_file>\n\n");
+    block
+}
 
-    Ok(())
+/// Estimate a message's input tokens and cost before sending it
+///
+/// Counts the typed text (~4 characters per token), pasted images it
+/// references, and, when `session_id` is given, the files attached to that
+/// session, which are sent along with the next message.
+#[tauri::command]
+pub async fn estimate_prompt_tokens(
+    app: AppHandle,
+    text: String,
+    model: String,
+    session_id: Option<String>,
+) -> Result<TokenEstimate, String> {
+    let attachment_tokens = match session_id {
+        Some(session_id) => load_metadata(&app, &session_id)?
+            .map(|metadata| estimate_text_tokens(&format_attached_files(&metadata.attached_files)))
+            .unwrap_or(0),
+        None => 0,
+    };
+
+    let mut images: Vec<String> = extract_image_paths(&text)
+        .into_iter()
+        .filter(|path| std::path::Path::new(path).is_file())
+        .collect();
+    let mut seen = HashSet::new();
+    images.retain(|path| seen.insert(path.clone()));
+
+    Ok(TokenEstimate::new(
+        &model,
+        estimate_text_tokens(&text),
+        attachment_tokens,
+        images.len() as u32,
+    ))
 }
 
 // ============================================================================
@@ -1961,7 +2768,13 @@ pub async fn open_file_in_default_app(
 use super::storage::{
     get_saved_contexts_dir, load_saved_contexts_metadata, save_saved_contexts_metadata,
 };
-use super::types::{SaveContextResponse, SavedContext, SavedContextsResponse};
+use super::types::{ContextSearchHit, SaveContextResponse, SavedContext, SavedContextsResponse};
+
+/// Upper bound on bytes read by a single saved-context search
+const MAX_CONTEXT_SEARCH_BYTES: u64 = 32 * 1024 * 1024;
+
+/// Bytes of surrounding text kept on each side of the first match in a snippet
+const CONTEXT_SNIPPET_RADIUS: usize = 80;
 
 /// Sanitize a string for use as a filename component
 /// Keeps only alphanumeric characters and hyphens, converts to lowercase
@@ -2256,6 +3069,238 @@ pub async fn rename_saved_context(
     Ok(())
 }
 
+/// Match count, snippet and highlight ranges for one saved context
+type ContextMatch = (usize, String, Vec<(usize, usize)>);
+
+/// Find case-insensitive (ASCII) matches of `query` in `content`
+///
+/// Returns the match count, a snippet around the first match, and the match
+/// ranges within that snippet as UTF-16 offsets (for slicing in JS).
+/// Returns None if there is no match.
+fn search_context_content(content: &str, query: &str) -> Option<ContextMatch> {
+    // ASCII lowercasing keeps byte offsets identical between the two strings
+    let haystack = content.to_ascii_lowercase();
+    let needle = query.to_ascii_lowercase();
+    let matches: Vec<usize> = haystack.match_indices(&needle).map(|(i, _)| i).collect();
+    let first = *matches.first()?;
+
+    let mut start = first.saturating_sub(CONTEXT_SNIPPET_RADIUS);
+    while !content.is_char_boundary(start) {
+        start -= 1;
+    }
+    let mut end = (first + needle.len() + CONTEXT_SNIPPET_RADIUS).min(content.len());
+    while !content.is_char_boundary(end) {
+        end += 1;
+    }
+
+    let snippet = &content[start..end];
+    let utf16_offset = |i: usize| snippet[..i - start].encode_utf16().count();
+    let highlights = matches
+        .iter()
+        .filter(|&&i| i >= start && i + needle.len() <= end)
+        .map(|&i| (utf16_offset(i), utf16_offset(i + needle.len())))
+        .collect();
+
+    Some((matches.len(), snippet.to_string(), highlights))
+}
+
+/// Search saved context files for a case-insensitive substring
+///
+/// Files are read one at a time, newest first, within a budget of
+/// `MAX_CONTEXT_SEARCH_BYTES`. A file that doesn't fit in what's left of the
+/// budget is skipped, so smaller older files are still searched.
+#[tauri::command]
+pub async fn search_saved_contexts(
+    app: AppHandle,
+    query: String,
+) -> Result<Vec<ContextSearchHit>, String> {
+    let query = query.trim().to_string();
+    if query.is_empty() {
+        return Ok(vec![]);
+    }
+    log::trace!("Searching saved contexts for: {query}");
+
+    let contexts_dir = get_saved_contexts_dir(&app)?;
+    let metadata = load_saved_contexts_metadata(&app);
+
+    let entries = std::fs::read_dir(&contexts_dir)
+        .map_err(|e| format!("Failed to read contexts directory: {e}"))?;
+    let mut contexts: Vec<SavedContext> = entries
+        .flatten()
+        .map(|entry| entry.path())
+        .filter(|path| path.extension().is_some_and(|ext| ext == "md"))
+        .filter_map(|path| parse_context_filename(&path))
+        .collect();
+    contexts.sort_by_key(|c| std::cmp::Reverse(c.created_at));
+
+    let mut scanned_bytes = 0u64;
+    let mut skipped = 0usize;
+    let mut hits = Vec::new();
+    for context in contexts {
+        if scanned_bytes >= MAX_CONTEXT_SEARCH_BYTES {
+            break;
+        }
+        if scanned_bytes + context.size > MAX_CONTEXT_SEARCH_BYTES {
+            skipped += 1;
+            continue;
+        }
+        scanned_bytes += context.size;
+
+        let content = match std::fs::read_to_string(&context.path) {
+            Ok(content) => content,
+            Err(e) => {
+                log::warn!("Failed to read context file {}: {e}", context.path);
+                continue;
+            }
+        };
+
+        if let Some((match_count, snippet, highlights)) = search_context_content(&content, &query) {
+            hits.push(ContextSearchHit {
+                name: metadata.names.get(&context.filename).cloned(),
+                filename: context.filename,
+                path: context.path,
+                match_count,
+                snippet,
+                highlights,
+            });
+        }
+    }
+
+    if skipped > 0 {
+        log::warn!(
+            "Saved context search skipped {skipped} files over the {MAX_CONTEXT_SEARCH_BYTES} byte budget"
+        );
+    }
+    log::trace!("Found {} saved contexts matching search", hits.len());
+    Ok(hits)
+}
+
+// ============================================================================
+// Transcript Search
+// ============================================================================
+
+use super::types::{RunEntry, TranscriptHit};
+
+/// Upper bound on run logs read by a single transcript search
+const MAX_TRANSCRIPT_SEARCH_FILES: usize = 5000;
+
+/// Upper bound on bytes of run logs read by a single transcript search
+const MAX_TRANSCRIPT_SEARCH_BYTES: usize = 64 * 1024 * 1024;
+
+/// Sessions returned by a transcript search when no limit is given
+const DEFAULT_TRANSCRIPT_SEARCH_LIMIT: usize = 50;
+
+/// Searchable text of a run: the user's prompt and the assistant's reply,
+/// without tool calls, tool output or thinking
+fn run_search_text(run: &RunEntry, jsonl: &str) -> String {
+    let format = run_log::RunFormat::of_run(run, jsonl);
+    let mut transcript = run_log::Transcript::default();
+    for event in run_log::parse_run_events(&format, jsonl) {
+        transcript.apply(&event);
+    }
+    format!("{}\n\n{}", run.user_message, transcript.content)
+}
+
+/// Scan session transcripts, most recently active session first
+fn scan_transcripts(
+    app: &AppHandle,
+    query: &str,
+    limit: usize,
+) -> Result<Vec<TranscriptHit>, String> {
+    let mut sessions: Vec<_> = super::storage::list_all_session_ids(app)?
+        .iter()
+        .filter_map(|id| load_metadata(app, id).ok().flatten())
+        .collect();
+    sessions.sort_by_key(|m| std::cmp::Reverse(m.runs.last().map(|r| r.started_at)));
+
+    let mut scanned_files = 0;
+    let mut scanned_bytes = 0;
+    let mut hits = Vec::new();
+    'sessions: for metadata in sessions {
+        let mut hit: Option<TranscriptHit> = None;
+        for run in metadata.runs.iter().rev() {
+            // Instantly cancelled sends aren't shown in the chat either
+            if run.status == RunStatus::Cancelled && run.assistant_message_id.is_none() {
+                continue;
+            }
+            if scanned_files >= MAX_TRANSCRIPT_SEARCH_FILES
+                || scanned_bytes >= MAX_TRANSCRIPT_SEARCH_BYTES
+            {
+                log::warn!(
+                    "Transcript search stopped after {scanned_files} run logs ({scanned_bytes} bytes)"
+                );
+                hits.extend(hit);
+                break 'sessions;
+            }
+
+            let jsonl = match run_log::read_run_log(app, &metadata.id, &run.run_id) {
+                Ok(jsonl) => jsonl,
+                Err(e) => {
+                    log::warn!("Failed to read run log {}: {e}", run.run_id);
+                    continue;
+                }
+            };
+            scanned_files += 1;
+            scanned_bytes += jsonl.len();
+
+            let text = run_search_text(run, &jsonl);
+            let Some((match_count, snippet, highlights)) = search_context_content(&text, query)
+            else {
+                continue;
+            };
+            match &mut hit {
+                Some(hit) => hit.match_count += match_count,
+                None => {
+                    hit = Some(TranscriptHit {
+                        session_id: metadata.id.clone(),
+                        worktree_id: metadata.worktree_id.clone(),
+                        session_name: metadata.name.clone(),
+                        run_id: run.run_id.clone(),
+                        timestamp: run.started_at,
+                        match_count,
+                        snippet,
+                        highlights,
+                    })
+                }
+            }
+        }
+
+        hits.extend(hit);
+        if hits.len() >= limit {
+            break;
+        }
+    }
+
+    Ok(hits)
+}
+
+/// Search every session's transcript for a case-insensitive substring
+///
+/// Returns at most `limit` sessions (default 50), most recently active first,
+/// each with a snippet from its latest matching run. Only prompts and
+/// assistant text are searched. Scanning stops after
+/// `MAX_TRANSCRIPT_SEARCH_FILES` run logs or `MAX_TRANSCRIPT_SEARCH_BYTES`.
+#[tauri::command]
+pub async fn search_transcripts(
+    app: AppHandle,
+    query: String,
+    limit: Option<usize>,
+) -> Result<Vec<TranscriptHit>, String> {
+    let query = query.trim().to_string();
+    if query.is_empty() {
+        return Ok(vec![]);
+    }
+    log::trace!("Searching transcripts for: {query}");
+
+    let limit = limit.unwrap_or(DEFAULT_TRANSCRIPT_SEARCH_LIMIT);
+    let hits = spawn_blocking(move || scan_transcripts(&app, &query, limit))
+        .await
+        .map_err(|e| format!("Transcript search failed: {e}"))??;
+
+    log::trace!("Found {} sessions matching transcript search", hits.len());
+    Ok(hits)
+}
+
 // ============================================================================
 // Background Context Generation
 // ============================================================================
@@ -2675,25 +3720,9 @@ pub async fn get_session_debug_info(
 
     // Try to find Claude CLI's JSONL file
     let claude_jsonl_file = claude_session_id.as_ref().and_then(|sid| {
-        // Claude CLI stores sessions in ~/.claude/projects/<project-hash>/<session-id>.jsonl
-        let home = dirs::home_dir()?;
-        let claude_projects = home.join(".claude").join("projects");
-
-        // We need to search for the session file in the projects directory
-        // The project hash is based on the worktree path
-        if claude_projects.exists() {
-            for entry in std::fs::read_dir(&claude_projects).ok()? {
-                let entry = entry.ok()?;
-                let project_dir = entry.path();
-                if project_dir.is_dir() {
-                    let session_file = project_dir.join(format!("{sid}.jsonl"));
-                    if session_file.exists() {
-                        return session_file.to_str().map(|s| s.to_string());
-                    }
-                }
-            }
-        }
-        None
+        super::claude_import::find_claude_transcript(sid)?
+            .to_str()
+            .map(|s| s.to_string())
     });
 
     // Get session directory and metadata file path (was manifest)
@@ -2712,8 +3741,12 @@ pub async fn get_session_debug_info(
     let mut run_log_files = Vec::new();
     if let Some(metadata) = metadata {
         for run in &metadata.runs {
-            let jsonl_path = session_dir.join(format!("{}.jsonl", run.run_id));
-            if jsonl_path.exists() {
+            // Old runs may have been gzipped by run log compression
+            let jsonl_path = [".jsonl", ".jsonl.gz"]
+                .iter()
+                .map(|ext| session_dir.join(format!("{}{ext}", run.run_id)))
+                .find(|path| path.exists());
+            if let Some(jsonl_path) = jsonl_path {
                 // Truncate user message preview to 50 chars
                 let preview = if run.user_message.len() > 50 {
                     format!("{}...", &run.user_message[..47])
@@ -2780,7 +3813,6 @@ pub async fn resume_session(
     session_id: String,
     worktree_id: String,
 ) -> Result<ResumeSessionResponse, String> {
-    use super::run_log::RunLogWriter;
     use super::storage::save_metadata;
 
     log::trace!("Attempting to resume session: {session_id}");
@@ -2839,81 +3871,171 @@ pub async fn resume_session(
         }
         save_metadata(&app, &metadata)?;
 
-        // Clone values for the async task
-        let app_clone = app.clone();
-        let session_id_clone = session_id.clone();
-        let worktree_id_clone = worktree_id.clone();
-        let run_id_clone = run_id.clone();
-
-        // Spawn a task to tail the output file
-        tauri::async_runtime::spawn(async move {
-            log::trace!("Starting tail task for run: {run_id_clone}, session: {session_id_clone}");
-
-            // Tail the output file
-            let result = super::claude::tail_claude_output(
-                &app_clone,
-                &session_id_clone,
-                &worktree_id_clone,
-                &output_file,
-                pid,
-            );
+        spawn_resumed_tail(&app, &session_id, &worktree_id, &run_id, pid, output_file);
+    }
 
-            match result {
-                Ok(response) => {
-                    log::trace!(
-                        "Resume completed for run: {run_id_clone}, session_id: {:?}",
-                        response.session_id
-                    );
+    Ok(ResumeSessionResponse {
+        resumed: true,
+        run_count,
+    })
+}
 
-                    // Create a RunLogWriter to update the manifest
-                    if let Ok(mut writer) =
-                        RunLogWriter::resume(&app_clone, &session_id_clone, &run_id_clone)
-                    {
-                        // Mark as completed
-                        let assistant_message_id = uuid::Uuid::new_v4().to_string();
-                        let claude_session_id = if response.session_id.is_empty() {
-                            None
-                        } else {
-                            Some(response.session_id.as_str())
-                        };
-                        if let Err(e) = writer.complete(
-                            &assistant_message_id,
-                            claude_session_id,
-                            response.usage.clone(),
-                        ) {
-                            log::error!("Failed to mark run as completed: {e}");
-                        }
+/// Re-attach to a detached run that is still running and stream its output.
+///
+/// Looks up the session's latest running run, replays its output file from the
+/// start to rebuild UI state, then keeps tailing until the process exits. The
+/// worktree is taken from the session metadata. Does nothing if a tail is
+/// already active for the session, or if the process has already finished.
+#[tauri::command]
+pub async fn resume_session_stream(app: AppHandle, session_id: String) -> Result<(), String> {
+    use super::detached::is_process_alive;
+    use super::storage::save_metadata;
 
-                        // Clean up input file if it exists
-                        if let Err(e) = super::run_log::delete_input_file(
-                            &app_clone,
-                            &session_id_clone,
-                            &run_id_clone,
-                        ) {
-                            log::trace!("Could not delete input file (may not exist): {e}");
-                        }
+    if super::registry::is_process_running(&session_id) {
+        log::trace!("Session {session_id} is already streaming, not resuming");
+        return Ok(());
+    }
+
+    let Some(mut metadata) = load_metadata(&app, &session_id)? else {
+        log::trace!("No metadata found for session: {session_id}");
+        return Ok(());
+    };
+
+    if metadata
+        .selected_provider
+        .as_deref()
+        .is_some_and(|provider| provider != "claude")
+    {
+        log::trace!("Session {session_id} is not a Claude session, not resuming");
+        return Ok(());
+    }
+
+    let Some((run_id, pid)) = metadata
+        .runs
+        .iter()
+        .rev()
+        .find(|r| matches!(r.status, RunStatus::Running | RunStatus::Resumable))
+        .and_then(|r| Some((r.run_id.clone(), r.pid?)))
+    else {
+        log::trace!("No running run found for session: {session_id}");
+        return Ok(());
+    };
+
+    if !is_process_alive(pid) {
+        log::trace!("Process {pid} for run {run_id} has finished, nothing to resume");
+        return Ok(());
+    }
+
+    let output_file = get_session_dir(&app, &session_id)?.join(format!("{run_id}.jsonl"));
+    if !output_file.exists() {
+        log::trace!("Output file for run {run_id} not found, nothing to resume");
+        return Ok(());
+    }
+
+    if let Some(run) = metadata.find_run_mut(&run_id) {
+        run.status = RunStatus::Running;
+    }
+    save_metadata(&app, &metadata)?;
+
+    log::trace!("Resuming stream for run: {run_id}, PID: {pid}");
+    spawn_resumed_tail(
+        &app,
+        &session_id,
+        &metadata.worktree_id,
+        &run_id,
+        pid,
+        output_file,
+    );
+    Ok(())
+}
+
+/// Tail a resumed run's output file in the background, then finalize the run.
+///
+/// The process is registered for the duration so it can be cancelled and is
+/// not tailed twice.
+fn spawn_resumed_tail(
+    app: &AppHandle,
+    session_id: &str,
+    worktree_id: &str,
+    run_id: &str,
+    pid: u32,
+    output_file: PathBuf,
+) {
+    use super::run_log::RunLogWriter;
+
+    // Clone values for the async task
+    let app_clone = app.clone();
+    let session_id_clone = session_id.to_string();
+    let worktree_id_clone = worktree_id.to_string();
+    let run_id_clone = run_id.to_string();
+
+    super::registry::register_process(session_id.to_string(), worktree_id, pid, None, &output_file);
+
+    // Spawn a task to tail the output file
+    tauri::async_runtime::spawn(async move {
+        log::trace!("Starting tail task for run: {run_id_clone}, session: {session_id_clone}");
+
+        // Tail the output file
+        let result = super::claude::tail_claude_output(
+            &app_clone,
+            &session_id_clone,
+            &worktree_id_clone,
+            &output_file,
+            0, // Resumed after a restart: replay everything the run wrote
+            pid,
+        );
+        super::registry::unregister_process(&session_id_clone, &output_file);
+
+        match result {
+            Ok(response) => {
+                log::trace!(
+                    "Resume completed for run: {run_id_clone}, session_id: {:?}",
+                    response.session_id
+                );
+
+                // Create a RunLogWriter to update the manifest
+                if let Ok(mut writer) =
+                    RunLogWriter::resume(&app_clone, &session_id_clone, &run_id_clone)
+                {
+                    // Mark as completed
+                    let assistant_message_id = uuid::Uuid::new_v4().to_string();
+                    let claude_session_id = if response.session_id.is_empty() {
+                        None
+                    } else {
+                        Some(response.session_id.as_str())
+                    };
+                    if let Err(e) = writer.complete(
+                        &assistant_message_id,
+                        claude_session_id,
+                        response.usage.clone(),
+                    ) {
+                        log::error!("Failed to mark run as completed: {e}");
                     }
-                }
-                Err(e) => {
-                    log::error!("Resume failed for run: {run_id_clone}, error: {e}");
 
-                    // Mark as crashed
-                    if let Ok(mut writer) =
-                        RunLogWriter::resume(&app_clone, &session_id_clone, &run_id_clone)
-                    {
-                        if let Err(e) = writer.crash() {
-                            log::error!("Failed to mark run as crashed: {e}");
-                        }
+                    // Clean up input file if it exists
+                    if let Err(e) = super::run_log::delete_input_file(
+                        &app_clone,
+                        &session_id_clone,
+                        &run_id_clone,
+                    ) {
+                        log::trace!("Could not delete input file (may not exist): {e}");
                     }
                 }
             }
-        });
-    }
+            Err(e) => {
+                log::error!("Resume failed for run: {run_id_clone}, error: {e}");
 
-    Ok(ResumeSessionResponse {
-        resumed: true,
-        run_count,
-    })
+                // Mark as crashed
+                if let Ok(mut writer) =
+                    RunLogWriter::resume(&app_clone, &session_id_clone, &run_id_clone)
+                {
+                    if let Err(e) = writer.crash() {
+                        log::error!("Failed to mark run as crashed: {e}");
+                    }
+                }
+            }
+        }
+    });
 }
 
 /// Check for resumable sessions on startup and return their info.
@@ -2936,6 +4058,47 @@ pub async fn check_resumable_sessions(
     Ok(resumable)
 }
 
+/// Re-link sessions whose worktree index entry was lost.
+///
+/// Called by frontend on app startup, alongside `check_resumable_sessions`.
+#[tauri::command]
+pub async fn recover_orphaned_sessions(
+    app: AppHandle,
+) -> Result<Vec<super::storage::RecoveredSession>, String> {
+    log::trace!("Scanning for orphaned sessions");
+    super::storage::recover_orphaned_sessions(&app)
+}
+
+/// Permanently delete sessions archived more than `older_than_days` ago.
+///
+/// With `dry_run`, reports what would be deleted without deleting anything.
+#[tauri::command]
+pub async fn cleanup_archived_sessions(
+    app: AppHandle,
+    older_than_days: u64,
+    dry_run: Option<bool>,
+) -> Result<super::storage::CleanupReport, String> {
+    log::trace!("Cleaning up sessions archived more than {older_than_days} days ago");
+    super::storage::cleanup_archived_sessions(&app, older_than_days, dry_run.unwrap_or(false))
+}
+
+/// Archive sessions with no activity in the last `idle_days`.
+///
+/// Activity is the newest run, or creation for sessions without runs. The
+/// active session and running sessions are never archived. Returns the IDs of
+/// the archived sessions.
+#[tauri::command]
+pub async fn auto_archive_idle_sessions(
+    app: AppHandle,
+    idle_days: u64,
+) -> Result<Vec<String>, String> {
+    log::trace!("Archiving sessions idle for more than {idle_days} days");
+    if idle_days == 0 {
+        return Err("Idle days must be at least 1".to_string());
+    }
+    super::storage::auto_archive_idle_sessions(&app, idle_days, super::registry::is_process_running)
+}
+
 // ============================================================================
 // Session Digest Commands (for context recall after switching)
 // ============================================================================
@@ -3984,6 +5147,87 @@ Execute this task now. Focus only on the work described above. Do not ask for cl
 mod tests {
     use super::*;
 
+    #[test]
+    fn test_vision_images_from_resumed_history() {
+        let dir = tempfile::tempdir().unwrap();
+        let image = dir.path().join("image-1.png");
+        std::fs::write(&image, [0u8]).unwrap();
+        let missing = dir.path().join("image-2.png");
+
+        // History replayed on resume still carries earlier image references
+        let history = format!(
+            "User: [Image attached: {} - Use the Read tool to view this image] what is this?\n\n\
+             Assistant: A logo.\n\n\
+             User: [Image attached: {} - Use the Read tool to view this image] and this?\n\n",
+            image.display(),
+            missing.display()
+        );
+
+        assert_eq!(vision_images(&history, "Codex", true), vec![image]);
+        assert!(vision_images(&history, "Kimi", false).is_empty());
+    }
+
+    #[test]
+    fn test_vision_images_drops_repeated_images() {
+        let dir = tempfile::tempdir().unwrap();
+        let first = dir.path().join("image-1.png");
+        let second = dir.path().join("image-2.png");
+        std::fs::write(&first, [0u8]).unwrap();
+        std::fs::write(&second, [0u8]).unwrap();
+
+        let content = [&first, &second, &first]
+            .iter()
+            .map(|path| {
+                format!(
+                    "[Image attached: {} - Use the Read tool to view this image]\n",
+                    path.display()
+                )
+            })
+            .collect::<String>();
+
+        assert_eq!(vision_images(&content, "Codex", true), vec![first, second]);
+    }
+
+    #[test]
+    fn test_is_binary_content_detects_null_bytes() {
+        assert!(!is_binary_content(b"fn main() {}\n"));
+        assert!(is_binary_content(b"\x89PNG\r\n\x1a\n\0\0\0\rIHDR"));
+    }
+
+    #[test]
+    fn test_format_attached_file_truncates_on_char_boundary() {
+        let block = format_attached_file("/repo/notes.md", "short");
+        assert_eq!(
+            block,
+            "<attached_file path=\"/repo/notes.md\">\nshort\n</attached_file>\n\n"
+        );
+
+        // Multi-byte characters straddling the limit must not be split
+        let content = format!("a{}", "é".repeat(ATTACHMENT_PROMPT_LIMIT));
+        let block = format_attached_file("/repo/big.txt", &content);
+        let expected_end = ATTACHMENT_PROMPT_LIMIT - 1;
+        assert!(block.contains(&format!(
+            "[Truncated to the first {expected_end} of {} bytes",
+            content.len()
+        )));
+    }
+
+    #[test]
+    fn test_history_prompt_attaches_files_to_current_turn() {
+        let history = [
+            (MessageRole::User, "first"),
+            (MessageRole::Assistant, "reply"),
+            (MessageRole::User, "now this"),
+        ];
+        let attached = format_attached_file("/repo/a.txt", "data");
+        let prompt = history_prompt(history.iter().map(|(r, c)| (r, *c)), &attached);
+        assert_eq!(
+            prompt,
+            format!("User: first\n\nAssistant: reply\n\nUser: {attached}now this\n\n")
+        );
+        assert_eq!(history_prompt([], &attached), "");
+    }
+
     #[test]
     fn test_extract_text_from_stream_json_text_only() {
         let output =
@@ -4070,4 +5314,74 @@ also not json"#;
         assert!(result.is_ok());
         assert_eq!(result.unwrap(), "After tool");
     }
+
+    #[test]
+    fn test_check_review_ready_only_with_changes() {
+        let dir = tempfile::tempdir().unwrap();
+        let path = dir.path().to_str().unwrap();
+        let status = Command::new("git")
+            .args(["init", "-q"])
+            .current_dir(path)
+            .status()
+            .unwrap();
+        assert!(status.success());
+
+        // Clean worktree: no signal
+        assert_eq!(check_review_ready(path, "session-1", "worktree-1"), None);
+
+        // Run left changes behind: signal with file count
+        std::fs::write(dir.path().join("a.txt"), "a").unwrap();
+        std::fs::write(dir.path().join("b.txt"), "b").unwrap();
+        assert_eq!(
+            check_review_ready(path, "session-1", "worktree-1"),
+            Some(ReviewReadyEvent {
+                session_id: "session-1".to_string(),
+                worktree_id: "worktree-1".to_string(),
+                changed_files: 2,
+            })
+        );
+    }
+
+    #[test]
+    fn test_search_context_content() {
+        let content = "# Auth refactor\n\nMoved AUTH tokens to the keychain. Auth is done.";
+
+        let (count, snippet, highlights) = search_context_content(content, "auth").unwrap();
+        assert_eq!(count, 3);
+        assert_eq!(snippet, content);
+        let matched: Vec<&str> = highlights.iter().map(|&(s, e)| &snippet[s..e]).collect();
+        assert_eq!(matched, vec!["Auth", "AUTH", "Auth"]);
+
+        assert!(search_context_content(content, "oauth").is_none());
+    }
+
+    #[test]
+    fn test_run_search_text_skips_tool_noise() {
+        let jsonl = r#"{"_run_meta":true,"run_id":"r1"}
+{"type":"assistant","message":{"content":[{"type":"thinking","thinking":"check the token code"},{"type":"tool_use","id":"t1","name":"Grep","input":{"pattern":"token"}}]}}
+{"type":"user","message":{"content":[{"type":"tool_result","tool_use_id":"t1","content":"src/token.rs"}]}}
+{"type":"assistant","message":{"content":[{"type":"text","text":"Fixed the auth bug."}]}}"#;
+
+        let run: RunEntry = serde_json::from_value(serde_json::json!({
+            "run_id": "r1", "user_message_id": "u", "user_message": "Why does login fail?",
+            "started_at": 0, "status": "completed", "provider": "claude",
+        }))
+        .unwrap();
+        let text = run_search_text(&run, jsonl);
+        assert!(search_context_content(&text, "login").is_some());
+        assert!(search_context_content(&text, "AUTH BUG").is_some());
+        assert!(search_context_content(&text, "token").is_none());
+    }
+
+    #[test]
+    fn test_search_context_snippet_respects_char_boundaries() {
+        let content = format!("{}needle{}", "é".repeat(100), "ü".repeat(100));
+        let (count, snippet, highlights) = search_context_content(&content, "NEEDLE").unwrap();
+        assert_eq!(count, 1);
+
+        // Offsets are UTF-16 units, so each accented char counts once
+        let utf16: Vec<u16> = snippet.encode_utf16().collect();
+        let (start, end) = highlights[0];
+        assert_eq!(String::from_utf16(&utf16[start..end]).unwrap(), "needle");
+    }
 }
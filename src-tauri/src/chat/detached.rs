@@ -4,25 +4,158 @@
 //! survives Jean quitting. The process writes directly to a JSONL file,
 //! which Jean tails for real-time updates.
 
+use std::fs;
 use std::io::{BufRead, BufReader};
 use std::path::Path;
 use std::process::{Command, Stdio};
+#[cfg(windows)]
+use std::sync::Mutex;
+use std::thread;
+use std::time::{Duration, Instant};
+
+#[cfg(windows)]
+use once_cell::sync::Lazy;
+
+use super::tail::POLL_INTERVAL;
 
 // Re-export is_process_alive from platform module
 pub use crate::platform::is_process_alive;
 
+/// How long after spawn a CLI exiting without output counts as a startup crash
+pub const STARTUP_CRASH_WINDOW: Duration = Duration::from_secs(2);
+
+/// Longest stderr tail included in a startup crash message
+const MAX_CRASH_STDERR_CHARS: usize = 2000;
+
+/// Check whether the CLI has written anything to its output file
+///
+/// The run log starts with our own `_run_meta` header, written before the CLI
+/// spawns, so that line (and blank lines) don't count as output.
+pub fn has_output(output_file: &Path) -> bool {
+    let Ok(file) = fs::File::open(output_file) else {
+        return false;
+    };
+    BufReader::new(file)
+        .lines()
+        .map_while(Result::ok)
+        .any(|line| !line.trim().is_empty() && !line.contains("\"_run_meta\""))
+}
+
+/// Build an error for a CLI that exited without output, from its stderr file
+pub fn startup_crash_message(cli_name: &str, stderr_file: &Path) -> String {
+    let stderr = fs::read_to_string(stderr_file).unwrap_or_default();
+    let stderr = stderr.trim();
+    if stderr.is_empty() {
+        return format!("{cli_name} CLI exited immediately without any output");
+    }
+
+    // Keep the end of stderr, where the actual error usually is
+    let start = stderr
+        .char_indices()
+        .rev()
+        .nth(MAX_CRASH_STDERR_CHARS - 1)
+        .map(|(i, _)| i)
+        .unwrap_or(0);
+    format!("{cli_name} CLI crashed on startup: {}", &stderr[start..])
+}
+
+/// Wait up to `window` after spawn for the CLI to fail fast
+///
+/// Returns the startup crash message if the process exits without writing any
+/// output (bad flag, missing library), or None once output appears or the
+/// window passes with the process still running. A process that exits because
+/// it was cancelled (`is_cancelled` returns true) didn't crash, so that also
+/// returns None.
+pub fn detect_startup_crash(
+    cli_name: &str,
+    pid: u32,
+    output_file: &Path,
+    stderr_file: &Path,
+    window: Duration,
+    is_cancelled: impl Fn() -> bool,
+) -> Option<String> {
+    let started_at = Instant::now();
+    loop {
+        if has_output(output_file) {
+            return None;
+        }
+        if !is_process_alive(pid) {
+            // It may have written its output just before exiting
+            if has_output(output_file) || is_cancelled() {
+                return None;
+            }
+            return Some(startup_crash_message(cli_name, stderr_file));
+        }
+        if started_at.elapsed() >= window {
+            return None;
+        }
+        thread::sleep(POLL_INTERVAL);
+    }
+}
+
+/// Check that a name is a portable shell variable name (`[A-Za-z_][A-Za-z0-9_]*`).
+pub(super) fn is_valid_env_name(name: &str) -> bool {
+    let mut chars = name.chars();
+    chars
+        .next()
+        .is_some_and(|c| c.is_ascii_alphabetic() || c == '_')
+        && chars.all(|c| c.is_ascii_alphanumeric() || c == '_')
+}
+
+/// Build `KEY='value' ...` assignments to prefix a shell command with.
+///
+/// Values are shell-escaped; names can't be escaped, so any name that isn't a
+/// plain variable name is rejected instead of being interpolated.
+fn build_env_exports(env_vars: &[(&str, &str)]) -> Result<String, String> {
+    env_vars
+        .iter()
+        .map(|(k, v)| {
+            if is_valid_env_name(k) {
+                shell_escape(v).map(|v| format!("{k}={v}"))
+            } else {
+                Err(format!("Invalid environment variable name: {k:?}"))
+            }
+        })
+        .collect::<Result<Vec<_>, _>>()
+        .map(|exports| exports.join(" "))
+}
+
+/// A spawned detached CLI process
+#[derive(Debug, Clone, Copy)]
+pub struct DetachedProcess {
+    /// PID of the CLI process (or of wsl.exe when Claude runs in WSL on Windows)
+    pub pid: u32,
+    /// Process group containing the CLI and its children (MCP servers, subshells).
+    /// Unix only; on Windows killing wsl.exe already terminates its children.
+    pub pgid: Option<u32>,
+}
+
 /// Escape a string for safe use in a shell command.
-fn shell_escape(s: &str) -> String {
+///
+/// Single quotes preserve everything else literally, including newlines.
+/// NUL bytes are rejected since they can't be passed in a process argument.
+pub fn shell_escape(s: &str) -> Result<String, String> {
+    // Don't echo the argument: it may be a prompt or carry secrets
+    if let Some(offset) = s.find('\0') {
+        return Err(format!(
+            "Shell argument contains a NUL byte at offset {offset} (argument is {} bytes)",
+            s.len()
+        ));
+    }
     // Use single quotes and escape any single quotes within
-    format!("'{}'", s.replace('\'', "'\\''"))
+    Ok(format!("'{}'", s.replace('\'', "'\\''")))
 }
 
 /// Spawn Claude CLI as a detached process that survives Jean quitting (Unix).
 ///
 /// Uses `nohup` and shell backgrounding to fully detach the process.
 /// The process reads input from a file and writes output to the NDJSON file.
+/// The shell is started in a new process group, which the backgrounded pipeline
+/// inherits, so cancellation can signal Claude together with everything it spawned.
 ///
-/// Returns the PID of the detached Claude CLI process.
+/// `_wsl_distro` only applies to the Windows (WSL) spawner.
+///
+/// Returns the PID of the detached Claude CLI process and its process group.
 #[cfg(unix)]
 #[allow(clippy::too_many_arguments)]
 pub fn spawn_detached_claude(
@@ -32,7 +165,10 @@ pub fn spawn_detached_claude(
     output_file: &Path,
     working_dir: &Path,
     env_vars: &[(&str, &str)],
-) -> Result<u32, String> {
+    _wsl_distro: Option<&str>,
+) -> Result<DetachedProcess, String> {
+    use std::os::unix::process::CommandExt;
+
     // Build the shell command:
     // cat input.jsonl | nohup /path/to/claude [args] >> output.jsonl 2>&1 & echo $!
     //
@@ -48,31 +184,27 @@ pub fn spawn_detached_claude(
 
     // Escape ALL paths for safe shell usage (paths may contain spaces like "Application Support")
     let cli_path_escaped =
-        shell_escape(cli_path.to_str().ok_or("CLI path contains invalid UTF-8")?);
+        shell_escape(cli_path.to_str().ok_or("CLI path contains invalid UTF-8")?)?;
     let input_path_escaped = shell_escape(
         input_file
             .to_str()
             .ok_or("Input file path contains invalid UTF-8")?,
-    );
+    )?;
     let output_path_escaped = shell_escape(
         output_file
             .to_str()
             .ok_or("Output file path contains invalid UTF-8")?,
-    );
+    )?;
 
     // Build args string with proper escaping
     let args_str = args
         .iter()
         .map(|arg| shell_escape(arg))
-        .collect::<Vec<_>>()
+        .collect::<Result<Vec<_>, _>>()?
         .join(" ");
 
     // Build environment variable exports
-    let env_exports = env_vars
-        .iter()
-        .map(|(k, v)| format!("{}={}", k, shell_escape(v)))
-        .collect::<Vec<_>>()
-        .join(" ");
+    let env_exports = build_env_exports(env_vars)?;
 
     // The full shell command - use cat pipe instead of file redirection
     // Claude CLI with --print requires piped stdin, not file redirection
@@ -91,7 +223,8 @@ pub fn spawn_detached_claude(
     log::trace!("Shell command: {shell_cmd}");
     log::trace!("Working directory: {working_dir:?}");
 
-    // Spawn the shell command
+    // Spawn the shell command as the leader of a new process group.
+    // The background pipeline stays in that group after the shell exits.
     let mut child = Command::new("sh")
         .arg("-c")
         .arg(&shell_cmd)
@@ -99,8 +232,10 @@ pub fn spawn_detached_claude(
         .stdin(Stdio::null())
         .stdout(Stdio::piped())
         .stderr(Stdio::piped())
+        .process_group(0)
         .spawn()
         .map_err(|e| format!("Failed to spawn shell: {e}"))?;
+    let pgid = child.id();
 
     // Read the PID from stdout (the `echo $!` part)
     let stdout = child
@@ -152,17 +287,156 @@ pub fn spawn_detached_claude(
         .parse()
         .map_err(|e| format!("Failed to parse PID '{pid_str}': {e}"))?;
 
-    log::trace!("Detached Claude CLI spawned with PID: {pid}");
+    log::trace!("Detached Claude CLI spawned with PID: {pid}, PGID: {pgid}");
 
-    Ok(pid)
+    Ok(DetachedProcess {
+        pid,
+        pgid: Some(pgid),
+    })
+}
+
+/// Native Windows Claude CLI found on PATH by an earlier lookup
+///
+/// Only hits are cached, so a CLI installed while Jean runs is still found.
+#[cfg(windows)]
+static NATIVE_CLAUDE_ON_PATH: Lazy<Mutex<Option<std::path::PathBuf>>> =
+    Lazy::new(|| Mutex::new(None));
+
+/// Find the native Windows Claude CLI to run
+///
+/// The managed `cli_path` is used when it is a Windows executable; PATH is
+/// only searched when it isn't (e.g. a Linux binary meant for WSL).
+#[cfg(windows)]
+fn find_native_claude(cli_path: &Path) -> Option<std::path::PathBuf> {
+    let is_windows_binary = cli_path
+        .extension()
+        .and_then(|ext| ext.to_str())
+        .is_some_and(|ext| ext.eq_ignore_ascii_case("exe") || ext.eq_ignore_ascii_case("cmd"));
+    if is_windows_binary && cli_path.exists() {
+        return Some(cli_path.to_path_buf());
+    }
+
+    let mut cached = NATIVE_CLAUDE_ON_PATH.lock().unwrap();
+    if let Some(path) = cached.as_ref().filter(|path| path.exists()) {
+        return Some(path.clone());
+    }
+    *cached = find_native_claude_on_path();
+    cached.clone()
+}
+
+/// Find a native Windows Claude CLI on PATH
+///
+/// npm installs create a `claude.cmd` wrapper, so that is tried before `claude.exe`.
+#[cfg(windows)]
+fn find_native_claude_on_path() -> Option<std::path::PathBuf> {
+    use std::os::windows::process::CommandExt;
+
+    const CREATE_NO_WINDOW: u32 = 0x08000000;
+
+    ["claude.cmd", "claude.exe"].into_iter().find_map(|name| {
+        let output = Command::new("where")
+            .arg(name)
+            .creation_flags(CREATE_NO_WINDOW)
+            .output()
+            .ok()?;
+        if !output.status.success() {
+            return None;
+        }
+        // `where` prints every match; the first one is what PATH resolves to
+        let path_str = String::from_utf8_lossy(&output.stdout)
+            .lines()
+            .next()
+            .unwrap_or("")
+            .trim()
+            .to_string();
+        let path = std::path::PathBuf::from(&path_str);
+        (!path_str.is_empty() && path.exists()).then(|| {
+            log::trace!("Found native Claude CLI via 'where {name}': {path_str}");
+            path
+        })
+    })
 }
 
-/// Spawn Claude CLI as a detached process via WSL (Windows).
+/// Spawn a native Windows Claude CLI as a detached process.
 ///
-/// On Windows, Claude CLI requires WSL. We invoke `wsl` to run the command
-/// inside the Linux environment, with paths translated to WSL format.
+/// Like the Windows Codex spawner, stdout and stderr go straight to the output
+/// file. The input is written through a pipe rather than handing Claude the
+/// file as stdin, since `--print` only reads piped input (see the Unix spawner).
 ///
-/// Returns the PID of the wsl.exe process (killing it terminates WSL children).
+/// Returns the PID of the Claude CLI process; cancellation kills its whole
+/// tree with `taskkill /T`, so no PGID is recorded.
+#[cfg(windows)]
+fn spawn_native_claude(
+    cli_path: &Path,
+    args: &[String],
+    input_file: &Path,
+    output_file: &Path,
+    working_dir: &Path,
+    env_vars: &[(&str, &str)],
+) -> Result<DetachedProcess, String> {
+    use std::fs::OpenOptions;
+    use std::io::Write;
+    use std::os::windows::process::CommandExt;
+
+    const CREATE_NEW_PROCESS_GROUP: u32 = 0x00000200;
+    const CREATE_NO_WINDOW: u32 = 0x08000000;
+
+    let input = fs::read(input_file).map_err(|e| format!("Failed to read input file: {e}"))?;
+
+    let stdout_file = OpenOptions::new()
+        .create(true)
+        .append(true)
+        .open(output_file)
+        .map_err(|e| format!("Failed to open output file: {e}"))?;
+    let stderr_file = stdout_file
+        .try_clone()
+        .map_err(|e| format!("Failed to open output file: {e}"))?;
+
+    log::trace!("Spawning detached native Claude CLI on Windows");
+    log::trace!("CLI path: {cli_path:?}");
+    log::trace!("Working directory: {working_dir:?}");
+
+    let mut cmd = Command::new(cli_path);
+    cmd.args(args)
+        .current_dir(working_dir)
+        .stdin(Stdio::piped())
+        .stdout(stdout_file)
+        .stderr(stderr_file)
+        .creation_flags(CREATE_NEW_PROCESS_GROUP | CREATE_NO_WINDOW);
+
+    for (key, value) in env_vars {
+        cmd.env(key, value);
+    }
+
+    let mut child = cmd
+        .spawn()
+        .map_err(|e| format!("Failed to spawn Claude CLI: {e}"))?;
+
+    // Write the input on a separate thread so a slow reader can't block the
+    // caller; dropping the handle afterwards closes Claude's stdin
+    let mut stdin = child.stdin.take().ok_or("Failed to capture Claude stdin")?;
+    thread::spawn(move || {
+        if let Err(e) = stdin.write_all(&input) {
+            log::warn!("Failed to write input to Claude CLI: {e}");
+        }
+    });
+
+    let pid = child.id();
+    log::trace!("Detached native Claude CLI spawned with PID: {pid}");
+
+    Ok(DetachedProcess { pid, pgid: None })
+}
+
+/// Spawn Claude CLI as a detached process (Windows).
+///
+/// A native Claude CLI on PATH (`claude.cmd` or `claude.exe`) is run directly.
+/// Otherwise Claude runs inside WSL: we invoke `wsl` to run the command in the
+/// Linux environment, with paths translated to WSL format.
+/// `wsl_distro` selects the distribution (`wsl -d`); `None` uses the default one.
+///
+/// Returns the PID of the native CLI or of the wsl.exe process (killing it
+/// terminates WSL children, the equivalent of signalling the process group on
+/// Unix, so no PGID is recorded).
 #[cfg(windows)]
 #[allow(clippy::too_many_arguments)]
 pub fn spawn_detached_claude(
@@ -172,7 +446,8 @@ pub fn spawn_detached_claude(
     output_file: &Path,
     working_dir: &Path,
     env_vars: &[(&str, &str)],
-) -> Result<u32, String> {
+    wsl_distro: Option<&str>,
+) -> Result<DetachedProcess, String> {
     use crate::platform::shell::{is_wsl_available, windows_to_wsl_path};
     use std::os::windows::process::CommandExt;
 
@@ -180,45 +455,52 @@ pub fn spawn_detached_claude(
     const CREATE_NEW_PROCESS_GROUP: u32 = 0x00000200;
     const CREATE_NO_WINDOW: u32 = 0x08000000;
 
-    // Check WSL availability
+    if let Some(native_cli) = find_native_claude(cli_path) {
+        return spawn_native_claude(
+            &native_cli,
+            args,
+            input_file,
+            output_file,
+            working_dir,
+            env_vars,
+        );
+    }
+
+    // No native CLI, so fall back to WSL
     if !is_wsl_available() {
         return Err(
-            "WSL is required on Windows to run Claude CLI. Install with: wsl --install".to_string(),
+            "Claude CLI for Windows was not found and WSL is not available. Install Claude CLI for Windows (npm install -g @anthropic-ai/claude-code) or WSL (wsl --install)".to_string(),
         );
     }
 
     // Convert Windows paths to WSL paths
     let wsl_cli_path =
-        windows_to_wsl_path(cli_path.to_str().ok_or("CLI path contains invalid UTF-8")?);
+        windows_to_wsl_path(cli_path.to_str().ok_or("CLI path contains invalid UTF-8")?)?;
     let wsl_input_path = windows_to_wsl_path(
         input_file
             .to_str()
             .ok_or("Input file path contains invalid UTF-8")?,
-    );
+    )?;
     let wsl_output_path = windows_to_wsl_path(
         output_file
             .to_str()
             .ok_or("Output file path contains invalid UTF-8")?,
-    );
+    )?;
     let wsl_working_dir = windows_to_wsl_path(
         working_dir
             .to_str()
             .ok_or("Working directory path contains invalid UTF-8")?,
-    );
+    )?;
 
     // Build args string with proper escaping
     let args_str = args
         .iter()
         .map(|arg| shell_escape(arg))
-        .collect::<Vec<_>>()
+        .collect::<Result<Vec<_>, _>>()?
         .join(" ");
 
     // Build environment variable exports
-    let env_exports = env_vars
-        .iter()
-        .map(|(k, v)| format!("{}={}", k, shell_escape(v)))
-        .collect::<Vec<_>>()
-        .join(" ");
+    let env_exports = build_env_exports(env_vars)?;
 
     // Build the shell command to run inside WSL
     // Same structure as Unix, but with WSL paths
@@ -238,7 +520,11 @@ pub fn spawn_detached_claude(
     log::trace!("WSL shell command: {shell_cmd}");
 
     // Spawn wsl.exe with the shell command
-    let mut child = Command::new("wsl")
+    let mut wsl = Command::new("wsl");
+    if let Some(distro) = wsl_distro {
+        wsl.args(["-d", distro]);
+    }
+    let mut child = wsl
         .args(["-e", "bash", "-c", &shell_cmd])
         .stdin(Stdio::null())
         .stdout(Stdio::piped())
@@ -301,13 +587,15 @@ pub fn spawn_detached_claude(
 
     log::trace!("Detached Claude CLI spawned via WSL with PID: {pid}");
 
-    Ok(pid)
+    Ok(DetachedProcess { pid, pgid: None })
 }
 
 /// Spawn Codex CLI as a detached process that survives Jean quitting (Unix).
 ///
 /// Unlike Claude, Codex takes the prompt as an argument rather than stdin.
 /// Uses `nohup` and shell backgrounding to fully detach the process.
+/// `env_vars` are set on the shell's environment rather than written into the
+/// command line, since they can carry MCP server secrets.
 ///
 /// Returns the PID of the detached Codex CLI process.
 #[cfg(unix)]
@@ -330,51 +618,43 @@ pub fn spawn_detached_codex(
     // - echo $!: Print the PID of the background process
 
     let cli_path_escaped =
-        shell_escape(cli_path.to_str().ok_or("CLI path contains invalid UTF-8")?);
+        shell_escape(cli_path.to_str().ok_or("CLI path contains invalid UTF-8")?)?;
     let output_path_escaped = shell_escape(
         output_file
             .to_str()
             .ok_or("Output file path contains invalid UTF-8")?,
-    );
+    )?;
     let stderr_path_escaped = shell_escape(
         stderr_file
             .to_str()
             .ok_or("Stderr file path contains invalid UTF-8")?,
-    );
+    )?;
 
     // Build args string with proper escaping
     let args_str = args
         .iter()
         .map(|arg| shell_escape(arg))
-        .collect::<Vec<_>>()
+        .collect::<Result<Vec<_>, _>>()?
         .join(" ");
 
-    // Build environment variable exports
-    let env_exports = env_vars
-        .iter()
-        .map(|(k, v)| format!("{}={}", k, shell_escape(v)))
-        .collect::<Vec<_>>()
-        .join(" ");
+    if let Some((key, _)) = env_vars.iter().find(|(k, _)| !is_valid_env_name(k)) {
+        return Err(format!("Invalid environment variable name: {key:?}"));
+    }
 
     // The full shell command - Codex doesn't need stdin piping
-    let shell_cmd = if env_exports.is_empty() {
-        format!(
-            "nohup {cli_path_escaped} {args_str} >> {output_path_escaped} 2>> {stderr_path_escaped} & echo $!"
-        )
-    } else {
-        format!(
-            "{env_exports} nohup {cli_path_escaped} {args_str} >> {output_path_escaped} 2>> {stderr_path_escaped} & echo $!"
-        )
-    };
+    let shell_cmd = format!(
+        "nohup {cli_path_escaped} {args_str} >> {output_path_escaped} 2>> {stderr_path_escaped} & echo $!"
+    );
 
     log::trace!("Spawning detached Codex CLI");
     log::trace!("Shell command: {shell_cmd}");
     log::trace!("Working directory: {working_dir:?}");
 
-    // Spawn the shell command
+    // Spawn the shell command; the backgrounded Codex inherits its environment
     let mut child = Command::new("sh")
         .arg("-c")
         .arg(&shell_cmd)
+        .envs(env_vars.iter().copied())
         .current_dir(working_dir)
         .stdin(Stdio::null())
         .stdout(Stdio::piped())
@@ -445,7 +725,7 @@ pub fn spawn_detached_kimi(
     output_file: &Path,
     stderr_file: &Path,
     working_dir: &Path,
-    _env_vars: &[(&str, &str)],
+    env_vars: &[(&str, &str)],
 ) -> Result<u32, String> {
     // Build the shell command without nohup:
     // /path/to/kimi [args] >> output.jsonl 2>> stderr.log & echo $!
@@ -454,29 +734,38 @@ pub fn spawn_detached_kimi(
     // during the request, we don't need nohup for crash survival.
 
     let cli_path_escaped =
-        shell_escape(cli_path.to_str().ok_or("CLI path contains invalid UTF-8")?);
+        shell_escape(cli_path.to_str().ok_or("CLI path contains invalid UTF-8")?)?;
     let output_path_escaped = shell_escape(
         output_file
             .to_str()
             .ok_or("Output file path contains invalid UTF-8")?,
-    );
+    )?;
     let stderr_path_escaped = shell_escape(
         stderr_file
             .to_str()
             .ok_or("Stderr file path contains invalid UTF-8")?,
-    );
+    )?;
 
     // Build args string with proper escaping
     let args_str = args
         .iter()
         .map(|arg| shell_escape(arg))
-        .collect::<Vec<_>>()
+        .collect::<Result<Vec<_>, _>>()?
         .join(" ");
 
+    // Build environment variable exports
+    let env_exports = build_env_exports(env_vars)?;
+
     // Simple background execution without nohup
-    let shell_cmd = format!(
-        "{cli_path_escaped} {args_str} >> {output_path_escaped} 2>> {stderr_path_escaped} & echo $!"
-    );
+    let shell_cmd = if env_exports.is_empty() {
+        format!(
+            "{cli_path_escaped} {args_str} >> {output_path_escaped} 2>> {stderr_path_escaped} & echo $!"
+        )
+    } else {
+        format!(
+            "{env_exports} {cli_path_escaped} {args_str} >> {output_path_escaped} 2>> {stderr_path_escaped} & echo $!"
+        )
+    };
 
     log::trace!("Spawning Kimi CLI (without nohup)");
     log::trace!("Shell command: {shell_cmd}");
@@ -611,10 +900,125 @@ mod tests {
 
     #[test]
     fn test_shell_escape() {
-        assert_eq!(shell_escape("hello"), "'hello'");
-        assert_eq!(shell_escape("hello world"), "'hello world'");
-        assert_eq!(shell_escape("it's"), "'it'\\''s'");
-        assert_eq!(shell_escape(""), "''");
+        assert_eq!(shell_escape("hello").unwrap(), "'hello'");
+        assert_eq!(shell_escape("hello world").unwrap(), "'hello world'");
+        assert_eq!(shell_escape("it's").unwrap(), "'it'\\''s'");
+        assert_eq!(shell_escape("").unwrap(), "''");
+        let err = shell_escape("secret\0token").unwrap_err();
+        assert!(err.contains("offset 6"), "{err}");
+        assert!(!err.contains("secret"), "{err}");
+    }
+
+    #[cfg(unix)]
+    #[test]
+    fn test_shell_escape_multiline_round_trip() {
+        let value = "line one\nit's line two\n\n  $HOME `id` \\n";
+        let output = Command::new("sh")
+            .arg("-c")
+            .arg(format!("printf '%s' {}", shell_escape(value).unwrap()))
+            .output()
+            .unwrap();
+        assert!(output.status.success());
+        assert_eq!(String::from_utf8(output.stdout).unwrap(), value);
+    }
+
+    #[test]
+    fn test_build_env_exports_rejects_invalid_names() {
+        assert_eq!(
+            build_env_exports(&[("FOO", "a b"), ("_BAR2", "it's")]).unwrap(),
+            "FOO='a b' _BAR2='it'\\''s'"
+        );
+        assert_eq!(build_env_exports(&[]).unwrap(), "");
+
+        assert!(build_env_exports(&[("FOO; rm -rf", "x")]).is_err());
+        assert!(build_env_exports(&[("FOO", "ok"), ("$(id)", "x")]).is_err());
+        assert!(build_env_exports(&[("1FOO", "x")]).is_err());
+        assert!(build_env_exports(&[("", "x")]).is_err());
+    }
+
+    #[test]
+    fn test_startup_crash_message_uses_stderr_tail() {
+        let dir = tempfile::tempdir().unwrap();
+        let stderr_file = dir.path().join("run.stderr.log");
+
+        assert_eq!(
+            startup_crash_message("Codex", &stderr_file),
+            "Codex CLI exited immediately without any output"
+        );
+
+        fs::write(&stderr_file, "error: unexpected argument '--bogus'\n").unwrap();
+        assert_eq!(
+            startup_crash_message("Codex", &stderr_file),
+            "Codex CLI crashed on startup: error: unexpected argument '--bogus'"
+        );
+
+        let long = format!("{}END", "x".repeat(5000));
+        fs::write(&stderr_file, &long).unwrap();
+        let message = startup_crash_message("Codex", &stderr_file);
+        assert!(message.ends_with("END"));
+        assert!(message.len() < 2100);
+    }
+
+    #[test]
+    fn test_detect_startup_crash_for_dead_process() {
+        let dir = tempfile::tempdir().unwrap();
+        let output_file = dir.path().join("run.jsonl");
+        let stderr_file = dir.path().join("run.stderr.log");
+        fs::write(&output_file, "").unwrap();
+        fs::write(&stderr_file, "libfoo.so: cannot open shared object file").unwrap();
+
+        let detect = |cancelled: bool| {
+            detect_startup_crash(
+                "Codex",
+                999999,
+                &output_file,
+                &stderr_file,
+                Duration::ZERO,
+                || cancelled,
+            )
+        };
+        assert!(detect(false).unwrap().contains("libfoo.so"));
+
+        // Killed by a cancel, not a crash
+        assert!(detect(true).is_none());
+
+        // Output was written, so it's not a startup crash
+        fs::write(&output_file, "{}\n").unwrap();
+        assert!(detect(false).is_none());
+    }
+
+    #[test]
+    fn test_has_output_ignores_run_meta_header() {
+        let dir = tempfile::tempdir().unwrap();
+        let output_file = dir.path().join("run.jsonl");
+        assert!(!has_output(&output_file));
+
+        fs::write(&output_file, "").unwrap();
+        assert!(!has_output(&output_file));
+
+        let header = "{\"_run_meta\":true,\"run_id\":\"r1\"}\n";
+        fs::write(&output_file, header).unwrap();
+        assert!(!has_output(&output_file));
+
+        // A CLI that dies after only our header was written is a startup crash
+        let stderr_file = dir.path().join("run.stderr.log");
+        fs::write(&stderr_file, "error: unexpected argument '--bogus'").unwrap();
+        let crash = detect_startup_crash(
+            "Codex",
+            999999,
+            &output_file,
+            &stderr_file,
+            Duration::ZERO,
+            || false,
+        );
+        assert!(crash.unwrap().contains("--bogus"));
+
+        fs::write(
+            &output_file,
+            format!("{header}{{\"type\":\"thread.started\"}}\n"),
+        )
+        .unwrap();
+        assert!(has_output(&output_file));
     }
 
     #[test]
@@ -0,0 +1,223 @@
+//! Worktree environment files for CLI runs
+//!
+//! Variables from `{worktree}/.jean.env` are passed to every CLI Jean spawns
+//! in that worktree, so tools the AI runs see the right environment without
+//! shell wrappers. Both files come from the repository, which may not be
+//! trusted, so each needs its own preference: `load_jean_env` for
+//! `.jean.env` and `load_project_dotenv` for the project's `.env`, which
+//! usually holds secrets. `.jean.env` overrides `.env` where both set a
+//! variable.
+//!
+//! Variables that change how the CLIs themselves run (the dynamic loader,
+//! Node options and CA certificates, shell startup files, `PATH`, proxies,
+//! API base URLs, CLI config directories) are never taken from these files.
+
+use std::fs;
+use std::path::Path;
+
+use super::detached::is_valid_env_name;
+
+/// Jean-specific env file, always loaded when present
+pub const JEAN_ENV_FILE: &str = ".jean.env";
+
+/// The project's env file, loaded only when enabled in preferences
+pub const PROJECT_ENV_FILE: &str = ".env";
+
+/// Variables a repository could use to run its own code inside the CLI,
+/// redirect or intercept its API traffic (and credentials), or swap its config
+const DENIED_ENV_NAMES: &[&str] = &[
+    "PATH",
+    "NODE_OPTIONS",
+    "NODE_EXTRA_CA_CERTS",
+    "BASH_ENV",
+    "ENV",
+    "HTTP_PROXY",
+    "HTTPS_PROXY",
+    "ALL_PROXY",
+    "CLAUDE_CONFIG_DIR",
+    "CODEX_HOME",
+];
+
+/// Whether a variable must not be loaded from a repository env file.
+/// Names are compared case-insensitively, so `https_proxy` is denied too.
+pub(super) fn is_denied_env_name(name: &str) -> bool {
+    let name = name.to_ascii_uppercase();
+    DENIED_ENV_NAMES.contains(&name.as_str())
+        || name.starts_with("LD_")
+        || name.starts_with("DYLD_")
+        || name.ends_with("_BASE_URL")
+}
+
+/// Parse a value: `"double"` quotes process `\n`, `\t`, `\"` and `\\` escapes,
+/// `'single'` quotes are literal, and unquoted values end at a ` #` comment
+fn parse_value(raw: &str) -> Option<String> {
+    if let Some(rest) = raw.strip_prefix('"') {
+        let mut value = String::new();
+        let mut chars = rest.chars();
+        while let Some(c) = chars.next() {
+            match c {
+                '"' => return Some(value),
+                '\\' => match chars.next()? {
+                    'n' => value.push('\n'),
+                    't' => value.push('\t'),
+                    other => value.push(other),
+                },
+                c => value.push(c),
+            }
+        }
+        // Unterminated quote
+        return None;
+    }
+    if let Some(rest) = raw.strip_prefix('\'') {
+        return rest.find('\'').map(|end| rest[..end].to_string());
+    }
+
+    let value = match raw.find(" #") {
+        Some(comment) => &raw[..comment],
+        None => raw,
+    };
+    Some(value.trim_end().to_string())
+}
+
+/// Parse dotenv content into (key, value) pairs, in file order
+///
+/// Supports `KEY=VALUE`, an optional `export ` prefix, `#` comment lines and
+/// quoted values. Malformed lines are skipped with a warning naming `source`.
+pub fn parse_env_file(content: &str, source: &str) -> Vec<(String, String)> {
+    let mut vars = Vec::new();
+    for (i, line) in content.lines().enumerate() {
+        let line = line.trim();
+        if line.is_empty() || line.starts_with('#') {
+            continue;
+        }
+        let line = line.strip_prefix("export ").unwrap_or(line);
+
+        let parsed = line.split_once('=').and_then(|(key, raw)| {
+            let key = key.trim();
+            if !is_valid_env_name(key) {
+                return None;
+            }
+            parse_value(raw.trim_start()).map(|value| (key.to_string(), value))
+        });
+        match parsed {
+            Some(var) => vars.push(var),
+            None => log::warn!("Skipping malformed line {} in {source}", i + 1),
+        }
+    }
+    vars
+}
+
+/// Load the env vars CLIs should run with in a worktree
+///
+/// Reads `.env` (only with `include_project_env`) and then `.jean.env` (only
+/// with `include_jean_env`), so later entries override earlier ones when
+/// applied in order. Missing files are skipped; unreadable ones are logged
+/// and skipped. Denied variables are dropped with a warning.
+pub fn load_worktree_env(
+    worktree_path: &Path,
+    include_jean_env: bool,
+    include_project_env: bool,
+) -> Vec<(String, String)> {
+    let files = [
+        (PROJECT_ENV_FILE, include_project_env),
+        (JEAN_ENV_FILE, include_jean_env),
+    ];
+
+    let mut vars = Vec::new();
+    for (name, _) in files.into_iter().filter(|(_, enabled)| *enabled) {
+        let path = worktree_path.join(name);
+        if !path.is_file() {
+            continue;
+        }
+        match fs::read_to_string(&path) {
+            Ok(content) => {
+                let (denied, parsed): (Vec<_>, Vec<_>) =
+                    parse_env_file(&content, &path.display().to_string())
+                        .into_iter()
+                        .partition(|(key, _)| is_denied_env_name(key));
+                for (key, _) in denied {
+                    log::warn!(
+                        "Ignoring {key} from {}: it could change how the AI CLI runs",
+                        path.display()
+                    );
+                }
+                log::trace!(
+                    "Loaded {} variable(s) from {}",
+                    parsed.len(),
+                    path.display()
+                );
+                vars.extend(parsed);
+            }
+            Err(e) => log::warn!("Failed to read {}: {e}", path.display()),
+        }
+    }
+    vars
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_parse_env_file() {
+        let content = r#"
+# Database
+DATABASE_URL=postgres://localhost/dev
+export API_HOST = api.local # trailing comment
+GREETING="hello \"world\"\nbye"
+LITERAL='$HOME # not a comment'
+EMPTY=
+PASSWORD=abc#123
+not a var
+1BAD=x
+UNTERMINATED="oops
+"#;
+        let vars = parse_env_file(content, ".jean.env");
+        assert_eq!(
+            vars,
+            vec![
+                ("DATABASE_URL".into(), "postgres://localhost/dev".into()),
+                ("API_HOST".into(), "api.local".into()),
+                ("GREETING".into(), "hello \"world\"\nbye".into()),
+                ("LITERAL".into(), "$HOME # not a comment".into()),
+                ("EMPTY".into(), String::new()),
+                ("PASSWORD".into(), "abc#123".into()),
+            ]
+        );
+    }
+
+    #[test]
+    fn test_load_worktree_env_only_reads_enabled_files() {
+        let dir = tempfile::tempdir().unwrap();
+        fs::write(dir.path().join(".env"), "SECRET=1\nSHARED=project\n").unwrap();
+        fs::write(dir.path().join(".jean.env"), "SHARED=jean\n").unwrap();
+
+        assert!(load_worktree_env(dir.path(), false, false).is_empty());
+
+        let vars = load_worktree_env(dir.path(), true, false);
+        assert_eq!(vars, vec![("SHARED".into(), "jean".into())]);
+
+        // .jean.env comes last so it wins when applied in order
+        let vars = load_worktree_env(dir.path(), true, true);
+        assert_eq!(vars.len(), 3);
+        assert_eq!(vars[0], ("SECRET".into(), "1".into()));
+        assert_eq!(vars[2], ("SHARED".into(), "jean".into()));
+    }
+
+    #[test]
+    fn test_load_worktree_env_drops_denied_variables() {
+        let dir = tempfile::tempdir().unwrap();
+        fs::write(
+            dir.path().join(".jean.env"),
+            "LD_PRELOAD=/tmp/x.so\nDYLD_INSERT_LIBRARIES=x\nNODE_OPTIONS=--require=x\n\
+             PATH=/tmp\nANTHROPIC_BASE_URL=http://proxy\nDATABASE_URL=postgres://db\n\
+             HTTPS_PROXY=http://proxy\nhttp_proxy=http://proxy\nALL_PROXY=socks5://proxy\n\
+             NODE_EXTRA_CA_CERTS=/tmp/ca.pem\nBASH_ENV=/tmp/x.sh\nENV=/tmp/x.sh\n\
+             CLAUDE_CONFIG_DIR=/tmp/claude\n",
+        )
+        .unwrap();
+
+        let vars = load_worktree_env(dir.path(), true, false);
+        assert_eq!(vars, vec![("DATABASE_URL".into(), "postgres://db".into())]);
+    }
+}
@@ -0,0 +1,185 @@
+//! Structured errors for chat execution
+//!
+//! Serialized as `{ "code": "...", "message": "..." }` so the frontend can
+//! branch on the kind of failure instead of matching on message text. Every
+//! provider's execute function returns them, and `send_chat_message` passes
+//! them through to the frontend.
+
+use serde::ser::SerializeStruct;
+use serde::{Serialize, Serializer};
+use tauri::Emitter;
+
+use super::claude::ErrorEvent;
+
+/// Phrases CLIs print when a request fails for lack of (valid) credentials
+///
+/// A bare "401" isn't one: it shows up in paths, IDs and token counts.
+const AUTH_FAILURE_MARKERS: &[&str] = &[
+    "401 unauthorized",
+    "status 401",
+    "status: 401",
+    "status code 401",
+    "\"status\":401",
+    "unauthorized",
+    "not logged in",
+    "please log in",
+    "please login",
+    "authentication failed",
+    "invalid api key",
+];
+
+#[derive(Debug, Clone, PartialEq)]
+pub enum ChatError {
+    /// The provider's CLI binary is missing
+    CliNotInstalled { cli: String },
+    /// The CLI rejected the request because the user is not logged in
+    NotAuthenticated { cli: String, message: String },
+    /// The CLI produced no output within the startup timeout
+    StartupTimeout { cli: String },
+    /// The CLI exited before completing; `message` includes its stderr
+    ProcessCrashed { message: String },
+    /// The installed CLI can't run the request as asked (e.g. too old for the mode)
+    Unsupported(String),
+    /// The request itself is invalid (empty message, unknown session)
+    InvalidRequest(String),
+    /// Filesystem or process spawn failure
+    Io(String),
+}
+
+impl ChatError {
+    /// Stable identifier sent to the frontend
+    pub fn code(&self) -> &'static str {
+        match self {
+            Self::CliNotInstalled { .. } => "cli_not_installed",
+            Self::NotAuthenticated { .. } => "not_authenticated",
+            Self::StartupTimeout { .. } => "startup_timeout",
+            Self::ProcessCrashed { .. } => "process_crashed",
+            Self::Unsupported(_) => "unsupported",
+            Self::InvalidRequest(_) => "invalid_request",
+            Self::Io(_) => "io",
+        }
+    }
+
+    /// Classify a CLI that exited early, so a missing login is reported as
+    /// such rather than as a crash
+    pub fn from_cli_failure(cli: &str, message: String) -> Self {
+        if is_auth_failure(&message) {
+            Self::NotAuthenticated {
+                cli: cli.to_string(),
+                message,
+            }
+        } else {
+            Self::ProcessCrashed { message }
+        }
+    }
+}
+
+impl std::fmt::Display for ChatError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Self::CliNotInstalled { cli } => {
+                write!(
+                    f,
+                    "{cli} CLI not installed. Please install it from Settings."
+                )
+            }
+            Self::NotAuthenticated { cli, message } => {
+                write!(
+                    f,
+                    "{cli} CLI is not authenticated. Please log in again. {message}"
+                )
+            }
+            Self::StartupTimeout { cli } => {
+                write!(f, "{cli} CLI startup timeout - no output received")
+            }
+            Self::ProcessCrashed { message } => write!(f, "{message}"),
+            Self::Unsupported(message) | Self::InvalidRequest(message) | Self::Io(message) => {
+                write!(f, "{message}")
+            }
+        }
+    }
+}
+
+impl Serialize for ChatError {
+    fn serialize<S: Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        let mut state = serializer.serialize_struct("ChatError", 2)?;
+        state.serialize_field("code", self.code())?;
+        state.serialize_field("message", &self.to_string())?;
+        state.end()
+    }
+}
+
+impl From<String> for ChatError {
+    fn from(message: String) -> Self {
+        Self::Io(message)
+    }
+}
+
+/// Whether a CLI error message indicates missing or rejected credentials
+pub fn is_auth_failure(message: &str) -> bool {
+    let message = message.to_lowercase();
+    AUTH_FAILURE_MARKERS
+        .iter()
+        .any(|marker| message.contains(marker))
+}
+
+/// Log and emit a `chat:error` event carrying the error's code
+pub fn emit_chat_error(
+    app: &tauri::AppHandle,
+    session_id: &str,
+    worktree_id: &str,
+    error: &ChatError,
+) {
+    log::error!("{error}");
+    let _ = app.emit(
+        "chat:error",
+        ErrorEvent {
+            session_id: session_id.to_string(),
+            worktree_id: worktree_id.to_string(),
+            error: error.to_string(),
+            code: Some(error.code()),
+        },
+    );
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_serializes_code_and_message() {
+        let error = ChatError::CliNotInstalled {
+            cli: "Codex".to_string(),
+        };
+        assert_eq!(
+            serde_json::to_value(&error).unwrap(),
+            serde_json::json!({
+                "code": "cli_not_installed",
+                "message": "Codex CLI not installed. Please install it from Settings.",
+            })
+        );
+
+        let error = ChatError::Io("Failed to create output file".to_string());
+        assert_eq!(serde_json::to_value(&error).unwrap()["code"], "io");
+    }
+
+    #[test]
+    fn test_from_cli_failure_detects_auth_errors() {
+        let error = ChatError::from_cli_failure("Codex", "Error: 401 Unauthorized".to_string());
+        assert_eq!(error.code(), "not_authenticated");
+
+        let error = ChatError::from_cli_failure("Codex", "error: unexpected argument".to_string());
+        assert_eq!(
+            error,
+            ChatError::ProcessCrashed {
+                message: "error: unexpected argument".to_string()
+            }
+        );
+
+        // A 401 that isn't an HTTP status
+        let error = ChatError::from_cli_failure("Claude", "ENOENT: /tmp/run-4012/out".to_string());
+        assert_eq!(error.code(), "process_crashed");
+        let error = ChatError::from_cli_failure("Kimi", "API error (status code 401)".to_string());
+        assert_eq!(error.code(), "not_authenticated");
+    }
+}
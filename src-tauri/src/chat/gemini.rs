@@ -3,16 +3,113 @@
 //! Handles executing Gemini CLI for chat messages with streaming support.
 
 use crate::ai_cli::gemini::config::get_gemini_cli_path;
+use crate::ai_cli::types::AiCliProvider;
+use once_cell::sync::Lazy;
+use std::collections::HashMap;
 use std::io::{BufRead, BufReader, Write};
-use std::path::Path;
+use std::path::{Path, PathBuf};
 use std::process::Stdio;
+use std::sync::Mutex;
+use std::time::SystemTime;
 use tauri::Emitter;
 
-use super::claude::{ChunkEvent, ClaudeResponse, ErrorEvent, ToolBlockEvent, ToolUseEvent};
-use super::types::{ContentBlock, ToolCall};
+use super::claude::{ChunkEvent, ClaudeResponse, ToolBlockEvent, ToolUseEvent};
+use super::error::{emit_chat_error, ChatError};
+use super::types::{ContentBlock, ToolCall, ToolKind};
+
+/// Whether a Gemini model accepts image input (all Gemini models are multimodal)
+pub fn supports_vision(_model: Option<&str>) -> bool {
+    true
+}
+
+/// Reference images in the prompt with Gemini CLI's `@path` syntax
+///
+/// Spaces are escaped since `@` references end at whitespace.
+fn append_image_references(prompt: &mut String, images: &[PathBuf]) {
+    for image in images {
+        let path = image.to_string_lossy().replace(' ', "\\ ");
+        prompt.push_str(&format!("\n@{path}"));
+    }
+}
+
+/// First Gemini CLI version with `--approval-mode`
+const APPROVAL_MODE_MIN_VERSION: [u32; 3] = [0, 1, 18];
+
+/// A Gemini CLI binary: its path and modification time, so an updated
+/// binary is probed again
+type CliBinaryKey = (PathBuf, Option<SystemTime>);
+
+/// Probed Gemini CLI versions
+static GEMINI_VERSION_CACHE: Lazy<Mutex<HashMap<CliBinaryKey, Vec<u32>>>> =
+    Lazy::new(|| Mutex::new(HashMap::new()));
+
+/// Get the installed Gemini CLI version, if it can be determined
+///
+/// Successful probes are cached per binary; failed ones are retried next time.
+fn get_gemini_cli_version(cli_path: &Path) -> Option<Vec<u32>> {
+    let modified = std::fs::metadata(cli_path).and_then(|m| m.modified()).ok();
+    let key = (cli_path.to_path_buf(), modified);
+    if let Some(version) = GEMINI_VERSION_CACHE.lock().unwrap().get(&key) {
+        return Some(version.clone());
+    }
+
+    let output = std::process::Command::new(cli_path)
+        .arg("--version")
+        .output()
+        .ok()?;
+    if !output.status.success() {
+        return None;
+    }
+    let version = crate::ai_cli::updates::parse_version(&String::from_utf8_lossy(&output.stdout))?;
+    GEMINI_VERSION_CACHE
+        .lock()
+        .unwrap()
+        .insert(key, version.clone());
+    Some(version)
+}
+
+/// Map execution mode to Gemini approval args
+///
+/// - plan: `--approval-mode default` (headless Gemini drops tools that need
+///   confirmation, so edits and shell commands are unavailable: read-only)
+/// - build: `--approval-mode auto_edit` (auto-approve edit tools)
+/// - yolo: `--yolo` (auto-approve all actions)
+///
+/// Versions without `--approval-mode` (or unknown versions) can't run read-only,
+/// so plan mode is refused rather than escalated; build falls back to `--yolo`.
+fn get_gemini_approval_args(
+    execution_mode: Option<&str>,
+    version: Option<&[u32]>,
+) -> Result<Vec<&'static str>, String> {
+    let supports_approval_mode = version.is_some_and(|v| v >= &APPROVAL_MODE_MIN_VERSION[..]);
+
+    match execution_mode {
+        Some("plan") if !supports_approval_mode => {
+            let installed = version
+                .map(|v| v.iter().map(u32::to_string).collect::<Vec<_>>().join("."))
+                .unwrap_or_else(|| "unknown".to_string());
+            Err(format!(
+                "Plan mode needs Gemini CLI 0.1.18 or newer (installed: {installed}). Update Gemini CLI or switch to build mode."
+            ))
+        }
+        Some("build") if !supports_approval_mode => {
+            log::warn!(
+                "Gemini CLI {version:?} doesn't support --approval-mode, falling back to --yolo for build mode"
+            );
+            Ok(vec!["--yolo"])
+        }
+        Some("plan") => Ok(vec!["--approval-mode", "default"]),
+        Some("build") => Ok(vec!["--approval-mode", "auto_edit"]),
+        _ => Ok(vec!["--yolo"]),
+    }
+}
 
 /// Execute Gemini CLI with streaming output
 /// Returns (process_id, response with content)
+///
+/// `plain_text_output` runs Gemini with `-o text` instead of `-o stream-json`,
+/// for Gemini versions whose stream format we can't parse.
+#[allow(clippy::too_many_arguments)]
 pub fn execute_gemini_detached(
     app: &tauri::AppHandle,
     session_id: &str,
@@ -20,47 +117,39 @@ pub fn execute_gemini_detached(
     input_file: &Path,
     output_file: &Path,
     working_dir: &Path,
+    worktree_env: &[(String, String)],
     model: Option<&str>,
     execution_mode: Option<&str>,
-) -> Result<(u32, ClaudeResponse), String> {
+    plain_text_output: bool,
+    images: &[PathBuf],
+) -> Result<(u32, ClaudeResponse), ChatError> {
     log::trace!("Executing Gemini CLI for session: {session_id}");
-    log::trace!("Execution mode: {execution_mode:?}");
+    log::trace!("Execution mode: {execution_mode:?}, plain text output: {plain_text_output}");
     log::trace!("Input file: {input_file:?}");
     log::trace!("Output file: {output_file:?}");
     log::trace!("Working directory: {working_dir:?}");
 
     // Get CLI path
     let cli_path = get_gemini_cli_path().map_err(|e| {
-        let error_msg = format!(
+        let error = ChatError::Io(format!(
             "Failed to get Gemini CLI path: {e}. Please install Gemini CLI via 'npm install -g @google/gemini-cli'."
-        );
-        log::error!("{error_msg}");
-        let error_event = ErrorEvent {
-            session_id: session_id.to_string(),
-            worktree_id: worktree_id.to_string(),
-            error: error_msg.clone(),
-        };
-        let _ = app.emit("chat:error", &error_event);
-        error_msg
+        ));
+        emit_chat_error(app, session_id, worktree_id, &error);
+        error
     })?;
 
     if !cli_path.exists() {
-        let error_msg =
-            "Gemini CLI not installed. Please install via 'npm install -g @google/gemini-cli'."
-                .to_string();
-        log::error!("{error_msg}");
-        let error_event = ErrorEvent {
-            session_id: session_id.to_string(),
-            worktree_id: worktree_id.to_string(),
-            error: error_msg.clone(),
+        let error = ChatError::CliNotInstalled {
+            cli: "Gemini".to_string(),
         };
-        let _ = app.emit("chat:error", &error_event);
-        return Err(error_msg);
+        emit_chat_error(app, session_id, worktree_id, &error);
+        return Err(error);
     }
 
     // Read input message for the prompt
-    let input_message = std::fs::read_to_string(input_file)
+    let mut input_message = std::fs::read_to_string(input_file)
         .map_err(|e| format!("Failed to read input file: {e}"))?;
+    append_image_references(&mut input_message, images);
 
     // Build args for Gemini CLI
     let mut args = Vec::new();
@@ -71,30 +160,35 @@ pub fn execute_gemini_detached(
         args.push(m.to_string());
     }
 
-    // Execution mode handling:
-    // - plan: --sandbox (read-only, no file modifications)
-    // - build: --approval-mode auto_edit (auto-approve edit tools)
-    // - yolo: --yolo (auto-approve all actions)
-    match execution_mode {
-        Some("plan") => {
-            // Sandbox mode - read-only, no file modifications
-            args.push("--sandbox".to_string());
-            args.push("--yolo".to_string()); // Still need yolo for non-interactive
-        }
-        Some("build") => {
-            // Auto-approve edits but confirm destructive actions
-            args.push("--approval-mode".to_string());
-            args.push("auto_edit".to_string());
-        }
-        _ => {
-            // yolo mode - auto-approve everything
-            args.push("--yolo".to_string());
-        }
+    // Execution mode handling (see get_gemini_approval_args)
+    let version = match execution_mode {
+        Some("plan") | Some("build") => get_gemini_cli_version(&cli_path),
+        _ => None,
+    };
+    let approval_args =
+        get_gemini_approval_args(execution_mode, version.as_deref()).map_err(|message| {
+            let error = ChatError::Unsupported(message);
+            emit_chat_error(app, session_id, worktree_id, &error);
+            error
+        })?;
+    args.extend(approval_args.into_iter().map(String::from));
+
+    // Pasted images live outside the worktree, so allow `@` references to read them
+    let mut image_dirs: Vec<&Path> = images.iter().filter_map(|i| i.parent()).collect();
+    image_dirs.dedup();
+    for dir in image_dirs {
+        args.push("--include-directories".to_string());
+        args.push(dir.to_string_lossy().to_string());
     }
 
-    // Use stream-json output format for real-time streaming
+    // Use stream-json output format for real-time streaming (or plain text if forced)
     args.push("-o".to_string());
-    args.push("stream-json".to_string());
+    let output_format = if plain_text_output {
+        "text"
+    } else {
+        "stream-json"
+    };
+    args.push(output_format.to_string());
 
     // Add the prompt as positional argument
     args.push(input_message.clone());
@@ -111,6 +205,7 @@ pub fn execute_gemini_detached(
     let mut child = std::process::Command::new(&cli_path)
         .args(&args)
         .current_dir(working_dir)
+        .envs(worktree_env.iter().map(|(k, v)| (k, v)))
         .stdin(Stdio::null())
         .stdout(Stdio::piped())
         .stderr(Stdio::piped())
@@ -120,10 +215,13 @@ pub fn execute_gemini_detached(
     let pid = child.id();
 
     // Register the process for cancellation
-    super::registry::register_process(session_id.to_string(), pid);
+    super::registry::register_process(session_id.to_string(), worktree_id, pid, None, output_file);
 
     // Get stdout handle for streaming
-    let stdout = child.stdout.take().ok_or("Failed to capture stdout")?;
+    let stdout = child
+        .stdout
+        .take()
+        .ok_or_else(|| ChatError::Io("Failed to capture stdout".to_string()))?;
     let reader = BufReader::new(stdout);
 
     // Accumulate content from streaming response
@@ -131,6 +229,11 @@ pub fn execute_gemini_detached(
     let mut tool_calls: Vec<ToolCall> = Vec::new();
     let mut content_blocks: Vec<ContentBlock> = Vec::new();
 
+    // Raw JSON lines (minus user echoes), used as the response if none of them
+    // turn out to be events we understand (Gemini's stream format has shifted before)
+    let mut raw_stdout = String::new();
+    let mut parsed_response_event = false;
+
     // Process each line as it comes (JSONL format)
     for line_result in reader.lines() {
         // Check for cancellation
@@ -154,6 +257,21 @@ pub fn execute_gemini_detached(
 
         log::trace!("Gemini stream line: {}", &line[..std::cmp::min(200, line.len())]);
 
+        // Plain text mode: every line is response content
+        if plain_text_output {
+            full_content.push_str(&line);
+            full_content.push('\n');
+            let _ = app.emit(
+                "chat:chunk",
+                ChunkEvent {
+                    session_id: session_id.to_string(),
+                    worktree_id: worktree_id.to_string(),
+                    content: format!("{line}\n"),
+                },
+            );
+            continue;
+        }
+
         // Strip user message JSON prefix if present (Gemini echoes user messages)
         // Pattern: {"message":{"content":"...","role":"user"},"type":"user"} followed by actual response
         let clean_line = if line.contains(r#""type":"user""#) || line.contains(r#""role":"user""#) {
@@ -225,6 +343,14 @@ pub fn execute_gemini_detached(
 
         let msg_type = msg.get("type").and_then(|v| v.as_str()).unwrap_or("");
 
+        if msg_type != "user" {
+            raw_stdout.push_str(&clean_line);
+            raw_stdout.push('\n');
+        }
+        if matches!(msg_type, "message" | "assistant" | "result") {
+            parsed_response_event = true;
+        }
+
         match msg_type {
             // Skip user messages - they're just echoed back by Gemini CLI
             "user" => {
@@ -287,10 +413,9 @@ pub fn execute_gemini_detached(
                                         .and_then(|v| v.as_str())
                                         .unwrap_or("")
                                         .to_string();
-                                    let name = block
-                                        .get("name")
-                                        .and_then(|v| v.as_str())
-                                        .unwrap_or("")
+                                    let raw_name =
+                                        block.get("name").and_then(|v| v.as_str()).unwrap_or("");
+                                    let name = ToolKind::normalize(AiCliProvider::Gemini, raw_name)
                                         .to_string();
                                     let input = block
                                         .get("input")
@@ -393,15 +518,8 @@ pub fn execute_gemini_detached(
             // Handle error events
             "error" => {
                 if let Some(error) = msg.get("error").and_then(|v| v.as_str()) {
-                    log::error!("Gemini error event: {error}");
-                    let _ = app.emit(
-                        "chat:error",
-                        ErrorEvent {
-                            session_id: session_id.to_string(),
-                            worktree_id: worktree_id.to_string(),
-                            error: error.to_string(),
-                        },
-                    );
+                    let error = ChatError::from_cli_failure("Gemini", error.to_string());
+                    emit_chat_error(app, session_id, worktree_id, &error);
                 }
             }
             // Handle other event types we might encounter
@@ -415,36 +533,50 @@ pub fn execute_gemini_detached(
     let status = child.wait().map_err(|e| format!("Failed to wait for Gemini CLI: {e}"))?;
 
     // Read any remaining stderr
+    let mut stderr_lines = Vec::new();
     if let Some(stderr) = child.stderr.take() {
         let stderr_reader = BufReader::new(stderr);
-        for line in stderr_reader.lines().flatten() {
+        for line in stderr_reader.lines().map_while(Result::ok) {
             if !line.is_empty() {
                 log::warn!("Gemini CLI stderr: {line}");
+                stderr_lines.push(line);
             }
         }
     }
 
-    super::registry::unregister_process(session_id);
+    super::registry::unregister_process(session_id, output_file);
 
-    log::info!("Gemini CLI completed with status: {status}, content length: {} chars", full_content.len());
-
-    // Check for errors
-    if !status.success() && full_content.is_empty() {
-        let error_msg = format!("Gemini CLI exited with status: {status}");
+    // Fallback: the stream had output but no events we recognize, so show it raw
+    // rather than returning an empty response
+    if !parsed_response_event && full_content.trim().is_empty() && !raw_stdout.trim().is_empty() {
+        log::warn!("No recognizable Gemini stream events, falling back to raw output");
+        full_content = raw_stdout;
         let _ = app.emit(
-            "chat:error",
-            ErrorEvent {
+            "chat:chunk",
+            ChunkEvent {
                 session_id: session_id.to_string(),
                 worktree_id: worktree_id.to_string(),
-                error: error_msg.clone(),
+                content: full_content.clone(),
             },
         );
-        return Err(error_msg);
+    }
+
+    log::info!("Gemini CLI completed with status: {status}, content length: {} chars", full_content.len());
+
+    // Check for errors
+    if !status.success() && full_content.is_empty() {
+        let mut error_msg = format!("Gemini CLI exited with status: {status}");
+        if !stderr_lines.is_empty() {
+            error_msg.push_str(&format!("\n{}", stderr_lines.join("\n")));
+        }
+        let error = ChatError::from_cli_failure("Gemini", error_msg);
+        emit_chat_error(app, session_id, worktree_id, &error);
+        return Err(error);
     }
 
     let response_text = full_content.trim().to_string();
 
-    // Note: Gemini now supports execution modes via --sandbox and --approval-mode
+    // Note: Gemini execution modes map to --approval-mode (see get_gemini_approval_args)
 
     // Build message.content from content_blocks (includes both text and tool_use blocks)
     let message_content: Vec<serde_json::Value> = content_blocks
@@ -521,3 +653,51 @@ pub fn execute_gemini_detached(
         },
     ))
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_append_image_references_escapes_spaces() {
+        let mut prompt = "describe this".to_string();
+        append_image_references(
+            &mut prompt,
+            &[PathBuf::from(
+                "/Users/me/Application Support/jean/pasted-images/a.png",
+            )],
+        );
+        assert_eq!(
+            prompt,
+            "describe this\n@/Users/me/Application\\ Support/jean/pasted-images/a.png"
+        );
+    }
+
+    #[test]
+    fn test_gemini_approval_args() {
+        let supported: &[u32] = &[0, 20, 0];
+        assert_eq!(
+            get_gemini_approval_args(Some("plan"), Some(supported)).unwrap(),
+            vec!["--approval-mode", "default"]
+        );
+        assert_eq!(
+            get_gemini_approval_args(Some("build"), Some(supported)).unwrap(),
+            vec!["--approval-mode", "auto_edit"]
+        );
+        assert_eq!(
+            get_gemini_approval_args(Some("yolo"), Some(supported)).unwrap(),
+            vec!["--yolo"]
+        );
+
+        // Old or unknown versions fall back to --yolo for build only
+        assert_eq!(
+            get_gemini_approval_args(Some("build"), None).unwrap(),
+            vec!["--yolo"]
+        );
+
+        // Plan mode is never escalated to --yolo
+        let err = get_gemini_approval_args(Some("plan"), Some(&[0, 1, 9])).unwrap_err();
+        assert!(err.contains("0.1.9"), "{err}");
+        assert!(get_gemini_approval_args(Some("plan"), None).is_err());
+    }
+}
@@ -4,14 +4,17 @@
 //! Uses detached process execution + NDJSON tailing for robustness.
 
 use crate::ai_cli::kimi::config::get_kimi_cli_path;
+use crate::ai_cli::types::AiCliProvider;
 use std::path::Path;
 use std::thread;
 use std::time::{Duration, Instant};
 use tauri::Emitter;
 
-use super::claude::{ChunkEvent, ClaudeResponse, ErrorEvent, ThinkingEvent, ToolResultEvent, ToolUseEvent};
+use super::claude::{ClaudeResponse, HeartbeatEvent};
 use super::detached::{is_process_alive, spawn_detached_kimi};
-use super::tail::{NdjsonTailer, POLL_INTERVAL};
+use super::error::{emit_chat_error, ChatError};
+use super::run_log::{events_from_json, plain_text_events, RunFormat, Transcript};
+use super::tail::{Heartbeat, NdjsonTailer, PollBackoff, HEARTBEAT_INTERVAL};
 
 /// Timeout for waiting for first output from Kimi
 const STARTUP_TIMEOUT: Duration = Duration::from_secs(120);
@@ -19,13 +22,114 @@ const STARTUP_TIMEOUT: Duration = Duration::from_secs(120);
 /// Timeout after process dies to wait for final output
 const DEAD_PROCESS_GRACE_PERIOD: Duration = Duration::from_secs(2);
 
+/// Payload for Ralph-loop progress events sent to frontend
+#[derive(serde::Serialize, Clone)]
+pub struct ProgressEvent {
+    pub session_id: String,
+    pub worktree_id: String,
+    pub completed_iterations: u32,
+    /// Iteration cap passed to Kimi (-1 = unlimited, None = Kimi's default)
+    pub max_iterations: Option<i32>,
+}
+
+/// Ralph-loop iteration tracking while tailing Kimi output
+///
+/// Kimi re-prompts itself with a user message at the start of each loop
+/// iteration, so a user message after assistant output marks a completed one.
+#[derive(Default)]
+struct IterationTracker {
+    completed: u32,
+    saw_assistant_output: bool,
+}
+
+impl IterationTracker {
+    /// Record a message's role, returning the new count if it completed an iteration
+    fn record(&mut self, role: &str) -> Option<u32> {
+        match role {
+            "user" if self.saw_assistant_output => {
+                self.completed += 1;
+                self.saw_assistant_output = false;
+                Some(self.completed)
+            }
+            "assistant" => {
+                self.saw_assistant_output = true;
+                None
+            }
+            _ => None,
+        }
+    }
+}
+
+/// Check a Ralph-loop iteration cap: -1 (unlimited) or at least one iteration
+pub fn validate_max_iterations(max_iterations: Option<i32>) -> Result<(), String> {
+    match max_iterations {
+        Some(max) if max != -1 && max < 1 => Err(format!(
+            "Invalid Kimi iteration cap {max}: use -1 for unlimited or a positive number"
+        )),
+        _ => Ok(()),
+    }
+}
+
+/// Whether a Kimi model accepts image input
+///
+/// Kimi CLI's print mode only takes a text prompt, so images are never passed.
+pub fn supports_vision(_model: Option<&str>) -> bool {
+    false
+}
+
+/// Map thinking level (and an optional iteration cap) to Kimi CLI args
+///
+/// | thinking level | Kimi mode | args                                                   |
+/// |----------------|-----------|--------------------------------------------------------|
+/// | off            | Instant   | `--no-thinking`                                        |
+/// | think          | Thinking  | `--thinking`                                           |
+/// | megathink      | Agent     | `--thinking --agent okabe`                             |
+/// | ultrathink     | Swarm     | `--thinking --agent okabe --max-ralph-iterations -1`   |
+/// | (none)         | default   | Kimi config file settings                              |
+///
+/// `max_iterations` caps the Ralph loop, replacing the unlimited (-1) default
+/// of swarm mode, or enabling a bounded loop for the other modes.
+fn get_kimi_thinking_args(
+    thinking_level: Option<&str>,
+    max_iterations: Option<i32>,
+) -> Vec<String> {
+    let mut args: Vec<String> = match thinking_level {
+        Some("off") => vec!["--no-thinking"],
+        Some("think") => vec!["--thinking"],
+        Some("megathink") | Some("ultrathink") => vec!["--thinking", "--agent", "okabe"],
+        _ => vec![],
+    }
+    .into_iter()
+    .map(String::from)
+    .collect();
+
+    let iterations = match (max_iterations, thinking_level) {
+        (Some(max), _) => Some(max),
+        // Swarm mode loops until the task is complete
+        (None, Some("ultrathink")) => Some(-1),
+        (None, _) => None,
+    };
+    if let Some(iterations) = iterations {
+        args.push("--max-ralph-iterations".to_string());
+        args.push(iterations.to_string());
+    }
+
+    args
+}
+
 /// Process a single Kimi NDJSON event and emit appropriate frontend events
+///
+/// Content events come from the shared run log parser, so the streamed message
+/// matches the one rebuilt from the log later; iteration progress and errors
+/// are handled here.
 fn process_kimi_event(
     app: &tauri::AppHandle,
     session_id: &str,
     worktree_id: &str,
     line: &str,
-    full_content: &mut String,
+    transcript: &mut Transcript,
+    iterations: &mut IterationTracker,
+    max_iterations: Option<i32>,
 ) -> Option<bool> {
     // Skip empty lines
     if line.trim().is_empty() {
@@ -37,159 +141,32 @@ fn process_kimi_event(
         Ok(m) => m,
         Err(_) => {
             // Not JSON, treat as plain text content
-            full_content.push_str(line);
-            full_content.push('\n');
-            let _ = app.emit(
-                "chat:chunk",
-                ChunkEvent {
-                    session_id: session_id.to_string(),
-                    worktree_id: worktree_id.to_string(),
-                    content: format!("{line}\n"),
-                },
-            );
+            for event in plain_text_events(&AiCliProvider::Kimi, line) {
+                event.emit(app, session_id, worktree_id);
+                transcript.apply(&event);
+            }
             return None;
         }
     };
 
     let role = msg.get("role").and_then(|v| v.as_str()).unwrap_or("");
 
-    match role {
-        "assistant" => {
-            // Content can be either an array (with thinking) or a string (without thinking)
-            if let Some(content_str) = msg.get("content").and_then(|v| v.as_str()) {
-                // Simple string content (--no-thinking mode)
-                if !content_str.is_empty() {
-                    full_content.push_str(content_str);
-                    full_content.push('\n');
-                    let _ = app.emit(
-                        "chat:chunk",
-                        ChunkEvent {
-                            session_id: session_id.to_string(),
-                            worktree_id: worktree_id.to_string(),
-                            content: format!("{content_str}\n"),
-                        },
-                    );
-                }
-            } else if let Some(content_arr) = msg.get("content").and_then(|v| v.as_array()) {
-                // Array content (with thinking enabled)
-                for item in content_arr {
-                    let item_type = item.get("type").and_then(|v| v.as_str()).unwrap_or("");
-
-                    match item_type {
-                        "think" => {
-                            if let Some(think_text) = item.get("think").and_then(|v| v.as_str()) {
-                                if !think_text.is_empty() {
-                                    let _ = app.emit(
-                                        "chat:thinking",
-                                        ThinkingEvent {
-                                            session_id: session_id.to_string(),
-                                            worktree_id: worktree_id.to_string(),
-                                            content: think_text.to_string(),
-                                        },
-                                    );
-                                }
-                            }
-                        }
-                        "text" => {
-                            if let Some(text) = item.get("text").and_then(|v| v.as_str()) {
-                                if !text.is_empty() {
-                                    full_content.push_str(text);
-                                    full_content.push('\n');
-                                    let _ = app.emit(
-                                        "chat:chunk",
-                                        ChunkEvent {
-                                            session_id: session_id.to_string(),
-                                            worktree_id: worktree_id.to_string(),
-                                            content: format!("{text}\n"),
-                                        },
-                                    );
-                                }
-                            }
-                        }
-                        _ => {}
-                    }
-                }
-            }
-
-            // Process tool_calls if present
-            if let Some(tool_calls) = msg.get("tool_calls").and_then(|v| v.as_array()) {
-                for tool_call in tool_calls {
-                    let tool_id = tool_call
-                        .get("id")
-                        .and_then(|v| v.as_str())
-                        .unwrap_or("")
-                        .to_string();
-
-                    if let Some(function) = tool_call.get("function") {
-                        let tool_name = function
-                            .get("name")
-                            .and_then(|v| v.as_str())
-                            .unwrap_or("")
-                            .to_string();
-
-                        let arguments = function
-                            .get("arguments")
-                            .and_then(|v| v.as_str())
-                            .unwrap_or("{}");
-
-                        // Parse arguments as JSON
-                        let input: serde_json::Value =
-                            serde_json::from_str(arguments).unwrap_or(serde_json::json!({}));
-
-                        // Map Kimi tool names to our standard names
-                        let mapped_name = match tool_name.as_str() {
-                            "WriteFile" | "CreateFile" => "Write",
-                            "ReadFile" => "Read",
-                            "EditFile" | "PatchFile" => "Edit",
-                            "RunCommand" | "Bash" | "Shell" => "Bash",
-                            "ListDirectory" | "ListDir" => "Bash",
-                            "DeleteFile" => "Bash",
-                            "SearchFiles" | "GlobTool" => "Glob",
-                            "GrepTool" | "SearchContent" => "Grep",
-                            _ => &tool_name,
-                        };
-
-                        let _ = app.emit(
-                            "chat:tool_use",
-                            ToolUseEvent {
-                                session_id: session_id.to_string(),
-                                worktree_id: worktree_id.to_string(),
-                                id: tool_id,
-                                name: mapped_name.to_string(),
-                                input,
-                                parent_tool_use_id: None,
-                            },
-                        );
-                    }
-                }
-            }
-
-            // Don't try to detect completion from content - just let the process finish
-            // The tail loop will exit when the process dies
-        }
-        "tool" => {
-            // Tool result
-            let tool_call_id = msg
-                .get("tool_call_id")
-                .and_then(|v| v.as_str())
-                .unwrap_or("")
-                .to_string();
-            let output = msg
-                .get("content")
-                .and_then(|v| v.as_str())
-                .unwrap_or("")
-                .to_string();
+    if let Some(completed_iterations) = iterations.record(role) {
+        let _ = app.emit(
+            "chat:progress",
+            ProgressEvent {
+                session_id: session_id.to_string(),
+                worktree_id: worktree_id.to_string(),
+                completed_iterations,
+                max_iterations,
+            },
+        );
+    }
 
-            let _ = app.emit(
-                "chat:tool_result",
-                ToolResultEvent {
-                    session_id: session_id.to_string(),
-                    worktree_id: worktree_id.to_string(),
-                    tool_use_id: tool_call_id,
-                    output,
-                },
-            );
-        }
+    match role {
+        // Don't try to detect completion from assistant content - just let the
+        // process finish. The tail loop will exit when the process dies
+        "user" | "assistant" | "tool" => {}
         "error" => {
             let error_msg = msg
                 .get("content")
@@ -197,25 +174,27 @@ fn process_kimi_event(
                 .or_else(|| msg.get("message").and_then(|v| v.as_str()))
                 .unwrap_or("Unknown error");
 
-            log::error!("Kimi error: {error_msg}");
-            let _ = app.emit(
-                "chat:error",
-                ErrorEvent {
-                    session_id: session_id.to_string(),
-                    worktree_id: worktree_id.to_string(),
-                    error: error_msg.to_string(),
-                },
-            );
+            let error = ChatError::from_cli_failure("Kimi", error_msg.to_string());
+            emit_chat_error(app, session_id, worktree_id, &error);
         }
         _ => {
             log::trace!("Kimi unknown role: {role}");
         }
     }
 
+    for event in events_from_json(&RunFormat::new(AiCliProvider::Kimi, None), &msg) {
+        event.emit(app, session_id, worktree_id);
+        transcript.apply(&event);
+    }
+
     None
 }
 
 /// Execute Kimi CLI as a detached process and tail output
+///
+/// `max_iterations` caps the Ralph loop (see `get_kimi_thinking_args`).
+/// `poll_interval` is the base sleep between output polls (see `PollBackoff`).
+#[allow(clippy::too_many_arguments)]
 pub fn execute_kimi_detached(
     app: &tauri::AppHandle,
     session_id: &str,
@@ -223,42 +202,31 @@ pub fn execute_kimi_detached(
     _input_file: &Path,
     output_file: &Path,
     working_dir: &Path,
+    worktree_env: &[(String, String)],
     model: Option<&str>,
     execution_mode: Option<&str>,
     thinking_level: Option<&str>,
+    max_iterations: Option<i32>,
     prompt: &str,
-) -> Result<(u32, ClaudeResponse), String> {
+    poll_interval: Duration,
+) -> Result<(u32, ClaudeResponse), ChatError> {
     log::trace!("Executing Kimi CLI (detached) for session: {session_id}");
     log::trace!("Output file: {output_file:?}");
     log::trace!("Working directory: {working_dir:?}");
 
     // Get CLI path
     let cli_path = get_kimi_cli_path().map_err(|e| {
-        let error_msg = format!("Failed to get Kimi CLI path: {e}");
-        log::error!("{error_msg}");
-        let _ = app.emit(
-            "chat:error",
-            ErrorEvent {
-                session_id: session_id.to_string(),
-                worktree_id: worktree_id.to_string(),
-                error: error_msg.clone(),
-            },
-        );
-        error_msg
+        let error = ChatError::Io(format!("Failed to get Kimi CLI path: {e}"));
+        emit_chat_error(app, session_id, worktree_id, &error);
+        error
     })?;
 
     if !cli_path.exists() {
-        let error_msg = "Kimi CLI not installed. Please install it from Settings.".to_string();
-        log::error!("{error_msg}");
-        let _ = app.emit(
-            "chat:error",
-            ErrorEvent {
-                session_id: session_id.to_string(),
-                worktree_id: worktree_id.to_string(),
-                error: error_msg.clone(),
-            },
-        );
-        return Err(error_msg);
+        let error = ChatError::CliNotInstalled {
+            cli: "Kimi".to_string(),
+        };
+        emit_chat_error(app, session_id, worktree_id, &error);
+        return Err(error);
     }
 
     // Build args
@@ -279,38 +247,8 @@ pub fn execute_kimi_detached(
         args.push(m.to_string());
     }
 
-    // Kimi execution mode based on thinking_level:
-    // - off: Instant mode (--no-thinking) - quick responses
-    // - think: Thinking mode (--thinking) - deep reasoning
-    // - megathink: Agent mode (--thinking --agent okabe) - single task execution
-    // - ultrathink: Swarm mode (--thinking --agent okabe --max-ralph-iterations -1) - continuous loop
-    match thinking_level {
-        Some("off") => {
-            // Instant mode - no thinking
-            args.push("--no-thinking".to_string());
-        }
-        Some("think") => {
-            // Thinking mode - enable thinking
-            args.push("--thinking".to_string());
-        }
-        Some("megathink") => {
-            // Agent mode - thinking + explicit agent, single task
-            args.push("--thinking".to_string());
-            args.push("--agent".to_string());
-            args.push("okabe".to_string());
-        }
-        Some("ultrathink") => {
-            // Swarm mode - agent with Ralph loop enabled (continuous iterations)
-            args.push("--thinking".to_string());
-            args.push("--agent".to_string());
-            args.push("okabe".to_string());
-            args.push("--max-ralph-iterations".to_string());
-            args.push("-1".to_string()); // -1 = unlimited iterations until task complete
-        }
-        _ => {
-            // Use default (config file setting)
-        }
-    }
+    // Kimi execution mode based on thinking_level (see get_kimi_thinking_args)
+    args.extend(get_kimi_thinking_args(thinking_level, max_iterations));
 
     // Approval mode: --print implies --yolo but we can be explicit
     // For plan mode, we might want different behavior, but Kimi doesn't have read-only sandbox
@@ -342,29 +280,36 @@ pub fn execute_kimi_detached(
     let stderr_file = output_file.with_extension("stderr.log");
 
     // Ensure output file exists (for tailing)
-    std::fs::write(output_file, "").map_err(|e| format!("Failed to create output file: {e}"))?;
+    super::run_log::create_output_file(output_file)?;
 
     // Spawn process (Kimi doesn't work with nohup, so we use a simpler approach)
+    let env_refs: Vec<(&str, &str)> = worktree_env
+        .iter()
+        .map(|(k, v)| (k.as_str(), v.as_str()))
+        .collect();
     let pid = spawn_detached_kimi(
         &cli_path,
         &args,
         output_file,
         &stderr_file,
         working_dir,
-        &[],
+        &env_refs,
     )?;
 
     // Register process for cancellation
-    super::registry::register_process(session_id.to_string(), pid);
+    super::registry::register_process(session_id.to_string(), worktree_id, pid, None, output_file);
 
     // Create tailer for output file
     let mut tailer =
         NdjsonTailer::new_from_start(output_file).map_err(|e| format!("Failed to create tailer: {e}"))?;
 
     // Tail loop
-    let mut full_content = String::new();
+    let mut transcript = Transcript::default();
+    let mut iterations = IterationTracker::default();
     let start_time = Instant::now();
     let mut last_output_time = Instant::now();
+    let mut heartbeat = Heartbeat::new(HEARTBEAT_INTERVAL);
+    let mut backoff = PollBackoff::new(poll_interval);
     let mut got_first_output = false;
     let mut completed = false;
 
@@ -376,9 +321,11 @@ pub fn execute_kimi_detached(
         }
 
         // Poll for new lines
+        let mut got_lines = false;
         match tailer.poll() {
             Ok(lines) => {
                 if !lines.is_empty() {
+                    got_lines = true;
                     got_first_output = true;
                     last_output_time = Instant::now();
 
@@ -388,7 +335,9 @@ pub fn execute_kimi_detached(
                             session_id,
                             worktree_id,
                             &line,
-                            &mut full_content,
+                            &mut transcript,
+                            &mut iterations,
+                            max_iterations,
                         ) {
                             completed = true;
                             break;
@@ -414,13 +363,20 @@ pub fn execute_kimi_detached(
                 log::trace!("Process {} died and no new output, ending tail", pid);
                 break;
             }
+        } else if let Some(silent_secs) = heartbeat.due(last_output_time) {
+            // Alive but silent (long reasoning): let the UI know it isn't hung
+            let _ = app.emit(
+                "chat:heartbeat",
+                HeartbeatEvent {
+                    session_id: session_id.to_string(),
+                    worktree_id: worktree_id.to_string(),
+                    silent_secs,
+                },
+            );
         }
 
         // Check startup timeout
         if !got_first_output && start_time.elapsed() > STARTUP_TIMEOUT {
-            let error_msg = "Kimi CLI startup timeout - no output received";
-            log::error!("{error_msg}");
-
             // Read stderr for more info
             if let Ok(stderr) = std::fs::read_to_string(&stderr_file) {
                 if !stderr.is_empty() {
@@ -428,29 +384,25 @@ pub fn execute_kimi_detached(
                 }
             }
 
-            let _ = app.emit(
-                "chat:error",
-                ErrorEvent {
-                    session_id: session_id.to_string(),
-                    worktree_id: worktree_id.to_string(),
-                    error: error_msg.to_string(),
-                },
-            );
+            let error = ChatError::StartupTimeout {
+                cli: "Kimi".to_string(),
+            };
+            emit_chat_error(app, session_id, worktree_id, &error);
             break;
         }
 
-        thread::sleep(POLL_INTERVAL);
+        thread::sleep(backoff.next(got_lines));
     }
 
     // Unregister process
-    super::registry::unregister_process(session_id);
+    super::registry::unregister_process(session_id, output_file);
 
     log::info!(
         "Kimi CLI completed, content length: {} chars",
-        full_content.len()
+        transcript.content.len()
     );
 
-    let response_text = full_content.trim().to_string();
+    let response_text = transcript.content.trim().to_string();
 
     // Emit done event
     let _ = app.emit(
@@ -468,10 +420,70 @@ pub fn execute_kimi_detached(
         ClaudeResponse {
             content: response_text,
             session_id: session_id.to_string(),
-            tool_calls: Vec::new(),
-            content_blocks: Vec::new(),
+            tool_calls: transcript.tool_calls,
+            content_blocks: transcript.content_blocks,
             cancelled: false,
             usage: None,
         },
     ))
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_kimi_thinking_args_iteration_cap() {
+        assert_eq!(
+            get_kimi_thinking_args(Some("think"), None),
+            vec!["--thinking"]
+        );
+        assert_eq!(
+            get_kimi_thinking_args(Some("ultrathink"), None),
+            vec![
+                "--thinking",
+                "--agent",
+                "okabe",
+                "--max-ralph-iterations",
+                "-1"
+            ]
+        );
+        assert_eq!(
+            get_kimi_thinking_args(Some("ultrathink"), Some(10)),
+            vec![
+                "--thinking",
+                "--agent",
+                "okabe",
+                "--max-ralph-iterations",
+                "10"
+            ]
+        );
+        assert!(get_kimi_thinking_args(None, None).is_empty());
+    }
+
+    #[test]
+    fn test_validate_max_iterations() {
+        assert!(validate_max_iterations(None).is_ok());
+        assert!(validate_max_iterations(Some(-1)).is_ok());
+        assert!(validate_max_iterations(Some(1)).is_ok());
+        assert!(validate_max_iterations(Some(50)).is_ok());
+        assert!(validate_max_iterations(Some(0)).is_err());
+        assert!(validate_max_iterations(Some(-2)).is_err());
+    }
+
+    #[test]
+    fn test_iteration_tracker_counts_loop_restarts() {
+        let mut tracker = IterationTracker::default();
+        // The initial prompt doesn't complete an iteration
+        assert_eq!(tracker.record("user"), None);
+        assert_eq!(tracker.record("assistant"), None);
+        assert_eq!(tracker.record("tool"), None);
+        assert_eq!(tracker.record("assistant"), None);
+        assert_eq!(tracker.record("user"), Some(1));
+        // Back-to-back user messages without output count once
+        assert_eq!(tracker.record("user"), None);
+        assert_eq!(tracker.record("assistant"), None);
+        assert_eq!(tracker.record("user"), Some(2));
+        assert_eq!(tracker.completed, 2);
+    }
+}
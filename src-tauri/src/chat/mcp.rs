@@ -0,0 +1,238 @@
+//! MCP server config passthrough
+//!
+//! Sessions can point at an MCP config file in Claude's `.mcp.json` format
+//! (`{"mcpServers": {"name": {...}}}`). Claude reads the file directly via
+//! `--mcp-config`; Codex has no file flag, so each server is passed as a
+//! `--config mcp_servers.<name>=...` override instead. Server `env` values
+//! often hold tokens, so for Codex they travel in the process environment and
+//! the override only names them (`env_vars`), keeping them out of argv.
+
+use std::path::Path;
+
+use super::detached::is_valid_env_name;
+use super::env_file::is_denied_env_name;
+use super::error::ChatError;
+
+/// Server fields Codex understands; anything else (e.g. Claude's `type`) is dropped
+const CODEX_SERVER_KEYS: &[&str] = &["command", "args", "env", "cwd", "url"];
+
+/// Codex CLI args for an MCP config, and the env vars its servers read
+#[derive(Debug, Default)]
+pub struct CodexMcpConfig {
+    /// `--config` overrides, one per server
+    pub args: Vec<String>,
+    /// Server env values, to set on the Codex process
+    pub env: Vec<(String, String)>,
+}
+
+/// Check that an MCP config file exists before handing it to a CLI
+pub fn validate_mcp_config(path: &Path) -> Result<(), String> {
+    if !path.is_file() {
+        return Err(format!("MCP config file not found: {}", path.display()));
+    }
+    Ok(())
+}
+
+/// Claude CLI args for an MCP config file
+pub fn claude_mcp_args(path: &Path) -> Result<Vec<String>, String> {
+    validate_mcp_config(path)?;
+    Ok(vec![
+        "--mcp-config".to_string(),
+        path.to_string_lossy().to_string(),
+    ])
+}
+
+/// Codex CLI args for an MCP config file, one `--config` override per server
+///
+/// A missing or malformed config is an invalid request; only failing to read
+/// an existing file is an I/O error.
+pub fn codex_mcp_args(path: &Path) -> Result<CodexMcpConfig, ChatError> {
+    validate_mcp_config(path).map_err(ChatError::InvalidRequest)?;
+    let content = std::fs::read_to_string(path)
+        .map_err(|e| ChatError::Io(format!("Failed to read MCP config {}: {e}", path.display())))?;
+    let config: serde_json::Value = serde_json::from_str(&content).map_err(|e| {
+        ChatError::InvalidRequest(format!("Invalid MCP config {}: {e}", path.display()))
+    })?;
+
+    let servers = config
+        .get("mcpServers")
+        .and_then(|s| s.as_object())
+        .ok_or_else(|| {
+            ChatError::InvalidRequest(format!(
+                "MCP config {} has no mcpServers object",
+                path.display()
+            ))
+        })?;
+
+    let mut mcp = CodexMcpConfig::default();
+    for (name, server) in servers {
+        let server_override =
+            codex_server_override(name, server, &mut mcp.env).map_err(ChatError::InvalidRequest)?;
+        mcp.args.push("--config".to_string());
+        mcp.args.push(server_override);
+    }
+    Ok(mcp)
+}
+
+/// A single `mcp_servers.<name>={...}` override with the server as an inline TOML table
+///
+/// Env values are moved into `env` and listed by name in `env_vars`, so Codex
+/// forwards them from its own environment. Names that would change how Codex
+/// itself runs (see `is_denied_env_name`) stay inline instead.
+fn codex_server_override(
+    name: &str,
+    server: &serde_json::Value,
+    env: &mut Vec<(String, String)>,
+) -> Result<String, String> {
+    // The name becomes a bare TOML key, so it can't contain dots or quotes
+    if name.is_empty()
+        || !name
+            .chars()
+            .all(|c| c.is_ascii_alphanumeric() || c == '_' || c == '-')
+    {
+        return Err(format!(
+            "MCP server name '{name}' must only contain letters, digits, '_' or '-'"
+        ));
+    }
+
+    let fields = server
+        .as_object()
+        .ok_or_else(|| format!("MCP server '{name}' must be an object"))?;
+
+    let mut table = toml::value::Table::new();
+    for key in CODEX_SERVER_KEYS {
+        if *key == "env" {
+            if let Some(value) = fields.get("env") {
+                codex_server_env(name, value, &mut table, env)?;
+            }
+            continue;
+        }
+        if let Some(value) = fields.get(*key) {
+            let value = toml::Value::try_from(value)
+                .map_err(|e| format!("Invalid '{key}' for MCP server '{name}': {e}"))?;
+            table.insert(key.to_string(), value);
+        }
+    }
+    if !table.contains_key("command") && !table.contains_key("url") {
+        return Err(format!("MCP server '{name}' needs a command or url"));
+    }
+
+    Ok(format!("mcp_servers.{name}={}", toml::Value::Table(table)))
+}
+
+/// Split a server's `env` object between the process environment and the table
+fn codex_server_env(
+    name: &str,
+    value: &serde_json::Value,
+    table: &mut toml::value::Table,
+    env: &mut Vec<(String, String)>,
+) -> Result<(), String> {
+    let vars = value
+        .as_object()
+        .ok_or_else(|| format!("Invalid 'env' for MCP server '{name}': expected an object"))?;
+
+    let mut inline = toml::value::Table::new();
+    let mut forwarded = Vec::new();
+    for (key, value) in vars {
+        let value = value.as_str().ok_or_else(|| {
+            format!("Invalid 'env' for MCP server '{name}': {key} must be a string")
+        })?;
+        if !is_valid_env_name(key) {
+            return Err(format!(
+                "Invalid 'env' for MCP server '{name}': {key:?} is not a valid variable name"
+            ));
+        }
+        if is_denied_env_name(key) {
+            inline.insert(key.clone(), toml::Value::String(value.to_string()));
+            continue;
+        }
+
+        // All servers share Codex's environment, so they must agree on values
+        match env.iter().find(|(existing, _)| existing == key) {
+            Some((_, existing)) if existing != value => {
+                return Err(format!(
+                    "MCP servers set {key} to different values; Codex can only pass one"
+                ));
+            }
+            Some(_) => {}
+            None => env.push((key.clone(), value.to_string())),
+        }
+        forwarded.push(toml::Value::String(key.clone()));
+    }
+
+    if !inline.is_empty() {
+        table.insert("env".to_string(), toml::Value::Table(inline));
+    }
+    if !forwarded.is_empty() {
+        table.insert("env_vars".to_string(), toml::Value::Array(forwarded));
+    }
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::io::Write;
+
+    #[test]
+    fn test_codex_mcp_args_translates_servers() {
+        let mut file = tempfile::NamedTempFile::new().unwrap();
+        write!(
+            file,
+            r#"{{"mcpServers": {{"docs": {{"type": "stdio", "command": "npx", "args": ["-y", "docs-mcp"], "env": {{"TOKEN": "abc"}}}}}}}}"#
+        )
+        .unwrap();
+
+        let mcp = codex_mcp_args(file.path()).unwrap();
+        assert_eq!(mcp.args.len(), 2);
+        assert_eq!(mcp.args[0], "--config");
+        let value: toml::Table = toml::from_str(&mcp.args[1]).unwrap();
+        let docs = &value["mcp_servers"]["docs"];
+        assert_eq!(docs["command"].as_str(), Some("npx"));
+        assert_eq!(docs["args"][1].as_str(), Some("docs-mcp"));
+        assert!(docs.get("type").is_none());
+
+        // The token is passed through the environment, never on the command line
+        assert!(!mcp.args[1].contains("abc"));
+        assert!(docs.get("env").is_none());
+        assert_eq!(docs["env_vars"][0].as_str(), Some("TOKEN"));
+        assert_eq!(mcp.env, vec![("TOKEN".to_string(), "abc".to_string())]);
+    }
+
+    #[test]
+    fn test_codex_mcp_env_conflicts_are_invalid_requests() {
+        let mut file = tempfile::NamedTempFile::new().unwrap();
+        write!(
+            file,
+            r#"{{"mcpServers": {{"a": {{"command": "x", "env": {{"TOKEN": "1"}}}}, "b": {{"command": "y", "env": {{"TOKEN": "2"}}}}}}}}"#
+        )
+        .unwrap();
+        assert!(matches!(
+            codex_mcp_args(file.path()),
+            Err(ChatError::InvalidRequest(_))
+        ));
+    }
+
+    #[test]
+    fn test_mcp_args_reject_missing_and_invalid_config() {
+        let missing = Path::new("/nonexistent/mcp.json");
+        assert!(claude_mcp_args(missing)
+            .unwrap_err()
+            .starts_with("MCP config file not found"));
+
+        let mut file = tempfile::NamedTempFile::new().unwrap();
+        write!(
+            file,
+            r#"{{"mcpServers": {{"bad.name": {{"command": "x"}}}}}}"#
+        )
+        .unwrap();
+        assert!(matches!(
+            codex_mcp_args(file.path()),
+            Err(ChatError::InvalidRequest(_))
+        ));
+        assert!(matches!(
+            codex_mcp_args(missing),
+            Err(ChatError::InvalidRequest(_))
+        ));
+    }
+}
@@ -1,14 +1,19 @@
 mod claude;
+pub mod claude_import;
 mod codex;
 mod commands;
 pub mod detached;
+pub mod env_file;
+mod error;
 mod gemini;
 mod kimi;
+mod mcp;
 mod naming;
 pub mod registry;
 pub mod run_log;
 pub mod storage;
 pub mod tail;
+pub mod transfer;
 pub mod types;
 
 pub use commands::*;
@@ -1,13 +1,16 @@
 //! Unified automatic naming for sessions and branches
 //!
 //! Uses a single Claude CLI call to generate both session and branch names
-//! based on the first message in a session.
+//! based on the first message in a session. Session names can also be
+//! regenerated on demand from the whole transcript.
 
 use crate::claude_cli::get_cli_binary_path;
 use crate::projects::git;
 use crate::projects::storage::{load_projects_data, save_projects_data};
 
+use super::run_log;
 use super::storage::with_sessions_mut;
+use super::types::{ChatMessage, MessageRole};
 use serde::{Deserialize, Serialize};
 use std::io::Write;
 use std::path::PathBuf;
@@ -734,6 +737,149 @@ fn execute_naming(app: &AppHandle, request: &NamingRequest) {
     }
 }
 
+// ============================================================================
+// Regenerating session names
+// ============================================================================
+
+/// Name given to a session with no messages to name it from
+const EMPTY_SESSION_NAME: &str = "Empty Session";
+
+/// Maximum characters of a single message included in the naming transcript
+const TRANSCRIPT_MESSAGE_LIMIT: usize = 600;
+
+/// Maximum characters of transcript sent for naming (most recent messages win,
+/// since regeneration is for conversations whose topic has drifted)
+const TRANSCRIPT_LIMIT: usize = 4000;
+
+/// Truncate to at most `limit` bytes on a char boundary
+fn truncate_chars(text: &str, limit: usize) -> &str {
+    let mut end = text.len().min(limit);
+    while !text.is_char_boundary(end) {
+        end -= 1;
+    }
+    &text[..end]
+}
+
+/// Build a condensed "User: ... / Assistant: ..." transcript for naming
+fn transcript_excerpt(messages: &[ChatMessage]) -> String {
+    let mut parts = Vec::new();
+    let mut total = 0;
+
+    for message in messages.iter().rev() {
+        let content = message.content.trim();
+        if content.is_empty() {
+            continue;
+        }
+        let role = match message.role {
+            MessageRole::User => "User",
+            MessageRole::Assistant => "Assistant",
+        };
+        let part = format!(
+            "{role}: {}",
+            truncate_chars(content, TRANSCRIPT_MESSAGE_LIMIT)
+        );
+        total += part.len();
+        if total > TRANSCRIPT_LIMIT && !parts.is_empty() {
+            break;
+        }
+        parts.push(part);
+    }
+
+    parts.reverse();
+    parts.join("\n\n")
+}
+
+/// Fallback name: the first few words of the first user message
+fn heuristic_session_name(messages: &[ChatMessage]) -> Option<String> {
+    let first = messages
+        .iter()
+        .find(|m| m.role == MessageRole::User && !m.content.trim().is_empty())?;
+
+    // Drop attachment markers like "[Image attached: ...]"
+    let text: String = first
+        .content
+        .split_inclusive(']')
+        .map(|chunk| match chunk.find('[') {
+            Some(start) if chunk.contains(" attached: ") => &chunk[..start],
+            _ => chunk,
+        })
+        .collect();
+
+    validate_session_name(&text).ok()
+}
+
+/// Regenerate a session's name from its transcript
+///
+/// Runs the same Claude CLI naming as the automatic first-message flow, over a
+/// condensed transcript; falls back to the first words of the first user
+/// message if that fails. Marks naming as completed and emits `session-renamed`.
+pub fn regenerate_session_name(
+    app: &AppHandle,
+    worktree_id: &str,
+    session_id: &str,
+    model: &str,
+) -> Result<String, String> {
+    let messages = run_log::load_session_messages(app, session_id)?;
+
+    let new_name = if messages.iter().all(|m| m.content.trim().is_empty()) {
+        EMPTY_SESSION_NAME.to_string()
+    } else {
+        let worktree_path = load_projects_data(app)
+            .ok()
+            .and_then(|data| data.find_worktree(worktree_id).map(|w| w.path.clone()))
+            .map(PathBuf::from)
+            .unwrap_or_default();
+
+        let request = NamingRequest {
+            session_id: session_id.to_string(),
+            worktree_id: worktree_id.to_string(),
+            worktree_path,
+            first_message: transcript_excerpt(&messages),
+            model: model.to_string(),
+            existing_branch_names: Vec::new(),
+            generate_session_name: true,
+            generate_branch_name: false,
+        };
+
+        generate_names(app, &request)
+            .and_then(|output| {
+                output
+                    .session_name
+                    .ok_or_else(|| "No session name in response".to_string())
+            })
+            .and_then(|name| validate_session_name(&name))
+            .or_else(|e| {
+                log::warn!("Session name regeneration failed, using first message: {e}");
+                heuristic_session_name(&messages).ok_or(e)
+            })?
+    };
+
+    let result = with_sessions_mut(app, "", worktree_id, |sessions| {
+        let session = sessions
+            .find_session_mut(session_id)
+            .ok_or_else(|| format!("Session not found: {session_id}"))?;
+
+        let old_name = std::mem::replace(&mut session.name, new_name.clone());
+        session.session_naming_completed = true;
+
+        Ok(SessionNameResult {
+            session_id: session_id.to_string(),
+            worktree_id: worktree_id.to_string(),
+            old_name,
+            new_name: new_name.clone(),
+        })
+    })?;
+
+    log::trace!(
+        "Session renamed from '{}' to '{}'",
+        result.old_name,
+        result.new_name
+    );
+    let _ = app.emit("session-renamed", &result);
+
+    Ok(new_name)
+}
+
 /// Spawn a background task to generate and apply names
 ///
 /// This function returns immediately and performs the work in a background thread.
@@ -755,3 +901,48 @@ pub fn spawn_naming_task(app: AppHandle, request: NamingRequest) {
         execute_naming(&app, &request);
     });
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn message(role: MessageRole, content: &str) -> ChatMessage {
+        ChatMessage {
+            role,
+            content: content.to_string(),
+            ..Default::default()
+        }
+    }
+
+    #[test]
+    fn test_transcript_excerpt_keeps_most_recent_messages() {
+        let long = "x".repeat(TRANSCRIPT_MESSAGE_LIMIT * 2);
+        let mut messages = vec![message(MessageRole::User, "old topic")];
+        for _ in 0..10 {
+            messages.push(message(MessageRole::Assistant, &long));
+        }
+        messages.push(message(MessageRole::User, "new topic"));
+
+        let excerpt = transcript_excerpt(&messages);
+        assert!(excerpt.ends_with("User: new topic"));
+        assert!(!excerpt.contains("old topic"));
+        assert!(excerpt.len() <= TRANSCRIPT_LIMIT);
+    }
+
+    #[test]
+    fn test_heuristic_session_name_skips_attachments() {
+        let messages = vec![
+            message(MessageRole::Assistant, "Hello"),
+            message(
+                MessageRole::User,
+                "[Image attached: /tmp/a.png - Use the Read tool to view this image] \
+                 Fix the login button alignment on mobile",
+            ),
+        ];
+        assert_eq!(
+            heuristic_session_name(&messages).as_deref(),
+            Some("Fix the login button")
+        );
+        assert_eq!(heuristic_session_name(&[]), None);
+    }
+}
@@ -1,40 +1,91 @@
 use std::collections::HashMap;
+use std::path::{Path, PathBuf};
 use std::sync::Mutex;
+use std::thread;
+use std::time::{Duration, Instant};
 
 use once_cell::sync::Lazy;
 use tauri::{AppHandle, Emitter};
 
 use super::claude::CancelledEvent;
 use super::run_log;
-use super::storage;
+use super::types::StopReport;
+
+/// How long a cancelled process gets to exit cleanly before it is force-killed
+pub const DEFAULT_CANCEL_GRACE: Duration = Duration::from_secs(3);
+
+/// How long a force-killed process gets to disappear before it is reported as refusing to die
+const KILL_CONFIRM_TIMEOUT: Duration = Duration::from_secs(1);
+
+/// A running CLI process and the output file of the run it belongs to
+#[derive(Debug)]
+struct ActiveProcess {
+    /// Worktree the session belongs to, so a whole worktree can be stopped at once
+    worktree_id: String,
+    pid: u32,
+    /// Process group holding the CLI and everything it spawned (Unix detached Claude)
+    pgid: Option<u32>,
+    output_file: PathBuf,
+}
 
 /// Global registry of running Claude process PIDs by session_id
 /// Allows cancellation of in-progress chat requests via SIGKILL
 /// Key is session_id (not worktree_id) to support multiple concurrent sessions per worktree
-static PROCESS_REGISTRY: Lazy<Mutex<HashMap<String, u32>>> =
+static PROCESS_REGISTRY: Lazy<Mutex<HashMap<String, ActiveProcess>>> =
     Lazy::new(|| Mutex::new(HashMap::new()));
 
 /// Register a running Claude process PID for a session
-pub fn register_process(session_id: String, pid: u32) {
+///
+/// `output_file` is the run's own output file, so a stale run finishing after
+/// a retry can't unregister the process of the run that replaced it. `pgid` is
+/// the process group to signal on cancellation, when the CLI runs in its own.
+pub fn register_process(
+    session_id: String,
+    worktree_id: &str,
+    pid: u32,
+    pgid: Option<u32>,
+    output_file: &Path,
+) {
     let mut registry = PROCESS_REGISTRY.lock().unwrap();
     log::trace!("Registering Claude process pid={pid} for session: {session_id}");
     log::trace!(
         "Registry state before insert: {:?}",
         registry.keys().collect::<Vec<_>>()
     );
-    registry.insert(session_id, pid);
+    registry.insert(
+        session_id,
+        ActiveProcess {
+            worktree_id: worktree_id.to_string(),
+            pid,
+            pgid,
+            output_file: output_file.to_path_buf(),
+        },
+    );
 }
 
 /// Remove a process from the registry (called after completion or cancellation)
-pub fn unregister_process(session_id: &str) {
+///
+/// Only removes the entry if it still belongs to the run writing `output_file`.
+pub fn unregister_process(session_id: &str, output_file: &Path) {
     let mut registry = PROCESS_REGISTRY.lock().unwrap();
-    if let Some(pid) = registry.remove(session_id) {
-        log::trace!("Unregistered Claude process {pid} for session: {session_id}");
+    match registry.get(session_id) {
+        Some(active) if active.output_file == output_file => {
+            let pid = active.pid;
+            registry.remove(session_id);
+            log::trace!("Unregistered Claude process {pid} for session: {session_id}");
+        }
+        Some(active) => {
+            log::trace!(
+                "Not unregistering session {session_id}: active run writes to {:?}, not {:?}",
+                active.output_file,
+                output_file
+            );
+        }
+        None => {}
     }
 }
 
 /// Check if a session has a running process
-#[allow(dead_code)]
 pub fn is_process_running(session_id: &str) -> bool {
     PROCESS_REGISTRY.lock().unwrap().contains_key(session_id)
 }
@@ -44,104 +95,309 @@ pub fn get_running_sessions() -> Vec<String> {
     PROCESS_REGISTRY.lock().unwrap().keys().cloned().collect()
 }
 
-/// Cancel a running Claude process for a session by sending SIGKILL to the process group
+/// Cancel a running Claude process for a session without waiting for it to exit
 /// Returns true if a process was found and signal sent, false otherwise
 ///
-/// SAFETY: We kill the entire process group (negative PID) to ensure all child processes
-/// spawned by Claude CLI are also terminated. This is safe because:
-/// 1. Claude is spawned with process_group(0), creating a NEW group separate from Jean
-/// 2. We guard against dangerous PIDs (0, 1) that could affect system processes
+/// The process gets SIGTERM right away; escalation to SIGKILL after
+/// `DEFAULT_CANCEL_GRACE` happens in the background, so async commands
+/// (close, archive, cancel) return immediately.
 pub fn cancel_process(
     app: &AppHandle,
     session_id: &str,
     worktree_id: &str,
 ) -> Result<bool, String> {
-    let mut registry = PROCESS_REGISTRY.lock().unwrap();
-    log::trace!("cancel_process called for session: {session_id}");
-    log::trace!("Registry state: {:?}", registry.iter().collect::<Vec<_>>());
+    let Some(active) = take_process(session_id)? else {
+        log::trace!("No running process found for session: {session_id}");
+        return Ok(false);
+    };
 
-    if let Some(pid) = registry.remove(session_id) {
-        // SAFETY: Never kill PID 0 (would kill our own process group) or PID 1 (init/launchd)
-        if pid == 0 || pid == 1 {
-            log::error!("Refusing to kill dangerous PID: {pid}");
-            return Err(format!("Invalid PID: {pid}"));
-        }
+    log::trace!(
+        "Cancelling Claude process {} for session: {session_id}",
+        active.pid
+    );
+    crate::platform::terminate_processes_in_background(
+        vec![(active.pid, active.pgid)],
+        DEFAULT_CANCEL_GRACE,
+    );
+    report_cancelled(app, session_id, worktree_id);
+    Ok(true)
+}
 
-        log::trace!("Cancelling Claude process group {pid} for session: {session_id}");
+/// Cancel a running Claude process for a session, escalating from graceful to forceful
+/// Returns true if a process was found and signal sent, false otherwise
+///
+/// The process group first gets SIGTERM (CTRL_BREAK on Windows) so the CLI can finish
+/// any in-progress file write; it is only SIGKILLed if still alive after `grace`.
+/// Blocks until then, so only call it from a blocking task.
+///
+/// SAFETY: We signal the entire process group (negative PGID) to ensure all child processes
+/// spawned by Claude CLI (MCP servers, subshells) are also terminated. This is safe because:
+/// 1. Detached Claude runs in its own process group (see `DetachedProcess`), separate from Jean
+/// 2. We guard against dangerous PIDs/PGIDs (0, 1) that could affect system processes
+pub fn cancel_session(
+    app: &AppHandle,
+    session_id: &str,
+    worktree_id: &str,
+    grace: Duration,
+) -> Result<bool, String> {
+    let Some(ActiveProcess { pid, pgid, .. }) = take_process(session_id)? else {
+        log::trace!("No running process found for session: {session_id}");
+        return Ok(false);
+    };
 
-        // Kill the entire process tree to ensure child processes are also terminated
-        // Uses platform-specific implementation from the platform module
-        use crate::platform::{is_process_alive, kill_process, kill_process_tree};
+    log::trace!("Cancelling Claude process group {pid} for session: {session_id}");
 
-        log::trace!("Killing process tree for pid={pid}");
+    use crate::platform::{is_process_alive, terminate_process_tree_with_grace};
 
-        // First, check if the process exists
-        if !is_process_alive(pid) {
-            log::warn!("Process {pid} check failed (may have exited)");
-        } else {
-            log::trace!("Process {pid} exists, proceeding with kill");
-        }
+    if !is_process_alive(pid) {
+        log::warn!("Process {pid} check failed (may have exited)");
+    }
 
-        // Kill the process tree (process group on Unix, taskkill /T on Windows)
-        if let Err(e) = kill_process_tree(pid) {
-            log::error!("Failed to kill process tree for pid={pid}: {e}");
-        } else {
-            log::trace!("Successfully sent kill to process tree pid={pid}");
+    match terminate_process_tree_with_grace(pid, pgid, grace) {
+        Ok(true) => {
+            log::warn!("Process {pid} ignored termination, force-killed after {grace:?}")
         }
+        Ok(false) => log::trace!("Process {pid} exited gracefully"),
+        Err(e) => log::error!("Failed to kill process tree for pid={pid}: {e}"),
+    }
 
-        // Also try killing the process directly as fallback
-        if let Err(e) = kill_process(pid) {
-            log::trace!("Direct kill of pid={pid} failed (may be redundant): {e}");
-        } else {
-            log::trace!("Direct kill of pid={pid} succeeded");
-        }
+    report_cancelled(app, session_id, worktree_id);
+    Ok(true)
+}
 
-        // Update manifest SYNCHRONOUSLY before emitting event
-        // This ensures any frontend refetch sees "Cancelled" status, not "Running"
-        if let Err(e) = run_log::mark_running_run_cancelled(app, session_id) {
-            log::warn!("Failed to mark run as cancelled in manifest: {e}");
-        }
+/// Remove a session's process from the registry so it can be signalled
+///
+/// Fails for PIDs/PGIDs that must never be signalled: 0 (our own process
+/// group) and 1 (init/launchd).
+fn take_process(session_id: &str) -> Result<Option<ActiveProcess>, String> {
+    let mut registry = PROCESS_REGISTRY.lock().unwrap();
+    log::trace!("Registry state: {:?}", registry.iter().collect::<Vec<_>>());
+    let Some(active) = registry.remove(session_id) else {
+        return Ok(None);
+    };
 
-        // Emit cancelled event for responsive UI
-        let event = CancelledEvent {
-            session_id: session_id.to_string(),
-            worktree_id: worktree_id.to_string(),
-            undo_send: false, // Process was running, may have partial content
-        };
-        if let Err(e) = app.emit("chat:cancelled", &event) {
-            log::error!("Failed to emit chat:cancelled event: {e}");
-        }
+    if active.pid == 0 || active.pid == 1 {
+        log::error!("Refusing to kill dangerous PID: {}", active.pid);
+        return Err(format!("Invalid PID: {}", active.pid));
+    }
+    if active.pgid == Some(0) || active.pgid == Some(1) {
+        log::error!(
+            "Refusing to kill dangerous process group: {:?}",
+            active.pgid
+        );
+        return Err(format!("Invalid process group: {:?}", active.pgid));
+    }
+    Ok(Some(active))
+}
 
-        Ok(true)
-    } else {
-        log::trace!("No running process found for session: {session_id}");
-        Ok(false)
+/// Mark a cancelled session's run as cancelled and tell the frontend
+fn report_cancelled(app: &AppHandle, session_id: &str, worktree_id: &str) {
+    // Update manifest SYNCHRONOUSLY before emitting event
+    // This ensures any frontend refetch sees "Cancelled" status, not "Running"
+    if let Err(e) = run_log::mark_running_run_cancelled(app, session_id) {
+        log::warn!("Failed to mark run as cancelled in manifest: {e}");
+    }
+
+    // Emit cancelled event for responsive UI
+    let event = CancelledEvent {
+        session_id: session_id.to_string(),
+        worktree_id: worktree_id.to_string(),
+        undo_send: false, // Process was running, may have partial content
+    };
+    if let Err(e) = app.emit("chat:cancelled", &event) {
+        log::error!("Failed to emit chat:cancelled event: {e}");
     }
 }
 
-/// Cancel all running Claude processes for a given worktree
+/// Cancel all running Claude processes for a given worktree without waiting
 /// Called before worktree deletion to clean up orphaned processes
+///
+/// Every process is signalled first, then a single background wait escalates
+/// the ones still alive after `DEFAULT_CANCEL_GRACE`.
 pub fn cancel_processes_for_worktree(app: &AppHandle, worktree_id: &str) {
     log::trace!("Cancelling all Claude processes for worktree: {worktree_id}");
 
-    // Load sessions for this worktree from app data directory
-    match storage::load_sessions_by_id(app, worktree_id) {
-        Ok(sessions) => {
-            let mut cancelled_count = 0;
-            for session in &sessions.sessions {
-                if let Ok(true) = cancel_process(app, &session.id, worktree_id) {
-                    cancelled_count += 1;
-                }
+    let mut processes = Vec::new();
+    for (session_id, _, _) in running_processes(|active| active.worktree_id == worktree_id) {
+        match take_process(&session_id) {
+            Ok(Some(active)) => {
+                processes.push((active.pid, active.pgid));
+                report_cancelled(app, &session_id, worktree_id);
             }
-            if cancelled_count > 0 {
-                log::trace!(
-                    "Cancelled {cancelled_count} Claude process(es) for worktree: {worktree_id}"
-                );
+            // Finished on its own in the meantime
+            Ok(None) => {}
+            Err(e) => log::warn!("Failed to cancel session {session_id}: {e}"),
+        }
+    }
+
+    if !processes.is_empty() {
+        log::trace!(
+            "Cancelled {} Claude process(es) for worktree: {worktree_id}",
+            processes.len()
+        );
+        crate::platform::terminate_processes_in_background(processes, DEFAULT_CANCEL_GRACE);
+    }
+}
+
+/// Cancel every running session of a worktree in parallel, escalating from
+/// graceful to forceful
+///
+/// Returns the number of sessions stopped. Sessions that refuse to die are
+/// logged but not counted.
+pub fn cancel_worktree_sessions(app: &AppHandle, worktree_id: &str, grace: Duration) -> usize {
+    let running = running_processes(|active| active.worktree_id == worktree_id);
+    stop_sessions(app, running, grace).stopped as usize
+}
+
+/// Cancel every running session in parallel, escalating from graceful to forceful
+///
+/// Each session gets `grace` to exit before it is force-killed, so this takes
+/// roughly `grace` overall rather than per session. Safe to call when nothing
+/// is running.
+pub fn stop_all_sessions(app: &AppHandle, grace: Duration) -> StopReport {
+    stop_sessions(app, running_processes(|_| true), grace)
+}
+
+/// (session ID, worktree ID, PID) of each registered process matching `filter`
+fn running_processes(filter: impl Fn(&ActiveProcess) -> bool) -> Vec<(String, String, u32)> {
+    PROCESS_REGISTRY
+        .lock()
+        .unwrap()
+        .iter()
+        .filter(|(_, active)| filter(active))
+        .map(|(session_id, active)| (session_id.clone(), active.worktree_id.clone(), active.pid))
+        .collect()
+}
+
+/// Cancel the given sessions in parallel and confirm each process is gone
+fn stop_sessions(
+    app: &AppHandle,
+    running: Vec<(String, String, u32)>,
+    grace: Duration,
+) -> StopReport {
+    if running.is_empty() {
+        return StopReport::default();
+    }
+    log::trace!("Stopping {} running session(s)", running.len());
+
+    let outcomes: Vec<(String, Result<bool, String>, u32)> = thread::scope(|scope| {
+        let handles: Vec<_> = running
+            .into_iter()
+            .map(|(session_id, worktree_id, pid)| {
+                scope.spawn(move || {
+                    let result = cancel_session(app, &session_id, &worktree_id, grace);
+                    (session_id, result, pid)
+                })
+            })
+            .collect();
+        handles.into_iter().filter_map(|h| h.join().ok()).collect()
+    });
+
+    let mut report = StopReport::default();
+    for (session_id, result, pid) in outcomes {
+        match result {
+            // Finished on its own before we got to it
+            Ok(false) => {}
+            Ok(true) if wait_for_exit(pid, KILL_CONFIRM_TIMEOUT) => report.stopped += 1,
+            Ok(true) => {
+                log::warn!("Process {pid} for session {session_id} survived being killed");
+                report.refused.push(session_id);
             }
+            Err(e) => {
+                log::warn!("Failed to stop session {session_id}: {e}");
+                report.refused.push(session_id);
+            }
+        }
+    }
+    report
+}
+
+/// Wait up to `timeout` for a process to exit; returns true if it is gone
+fn wait_for_exit(pid: u32, timeout: Duration) -> bool {
+    use crate::platform::is_process_alive;
+
+    let deadline = Instant::now() + timeout;
+    while is_process_alive(pid) {
+        if Instant::now() >= deadline {
+            return false;
         }
-        Err(e) => {
-            // Not an error - worktree may have no sessions yet
-            log::trace!("No sessions found for worktree {worktree_id}: {e}");
+        thread::sleep(Duration::from_millis(50));
+    }
+    true
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_stale_run_does_not_unregister_replacement() {
+        let session_id = "registry-test-session";
+        let old_run = Path::new("/tmp/sessions/s/old-run.jsonl");
+        let new_run = Path::new("/tmp/sessions/s/new-run.jsonl");
+
+        register_process(session_id.to_string(), "wt", 1001, None, old_run);
+        // Retry replaces the registration before the old run finishes
+        register_process(session_id.to_string(), "wt", 1002, None, new_run);
+
+        unregister_process(session_id, old_run);
+        assert!(is_process_running(session_id));
+
+        unregister_process(session_id, new_run);
+        assert!(!is_process_running(session_id));
+    }
+
+    #[test]
+    fn test_running_processes_filters_by_worktree() {
+        let output = Path::new("/tmp/sessions/s/run.jsonl");
+        register_process(
+            "registry-wt-a-1".to_string(),
+            "registry-wt-a",
+            2001,
+            None,
+            output,
+        );
+        register_process(
+            "registry-wt-a-2".to_string(),
+            "registry-wt-a",
+            2002,
+            None,
+            output,
+        );
+        register_process(
+            "registry-wt-b-1".to_string(),
+            "registry-wt-b",
+            2003,
+            None,
+            output,
+        );
+
+        let mut sessions: Vec<String> = running_processes(|p| p.worktree_id == "registry-wt-a")
+            .into_iter()
+            .map(|(session_id, worktree_id, _)| {
+                assert_eq!(worktree_id, "registry-wt-a");
+                session_id
+            })
+            .collect();
+        sessions.sort();
+        assert_eq!(sessions, ["registry-wt-a-1", "registry-wt-a-2"]);
+
+        for session_id in ["registry-wt-a-1", "registry-wt-a-2", "registry-wt-b-1"] {
+            unregister_process(session_id, output);
         }
     }
+
+    #[cfg(unix)]
+    #[test]
+    fn test_wait_for_exit() {
+        let mut child = std::process::Command::new("sleep")
+            .arg("30")
+            .spawn()
+            .unwrap();
+        assert!(!wait_for_exit(child.id(), Duration::from_millis(100)));
+
+        child.kill().unwrap();
+        child.wait().unwrap();
+        assert!(wait_for_exit(child.id(), Duration::from_millis(100)));
+    }
 }
@@ -1,8 +1,10 @@
-use std::collections::HashMap;
+use std::collections::hash_map::DefaultHasher;
+use std::collections::{HashMap, HashSet};
 use std::fs::{self, File};
-use std::io::{BufReader, BufWriter};
-use std::path::PathBuf;
-use std::sync::{Arc, Mutex};
+use std::hash::{Hash, Hasher};
+use std::io::{BufReader, BufWriter, Write};
+use std::path::{Path, PathBuf};
+use std::sync::Mutex;
 
 use once_cell::sync::Lazy;
 use tauri::{AppHandle, Manager};
@@ -11,40 +13,75 @@ use super::types::{
     SavedContextsMetadata, Session, SessionIndexEntry, SessionMetadata, WorktreeIndex,
     WorktreeSessions,
 };
+use crate::util::parallel_map;
 
 // ============================================================================
 // Locking
 // ============================================================================
 
-/// Per-worktree mutex to prevent concurrent read-modify-write races on index files.
-/// Each worktree gets its own mutex so different worktrees don't block each other.
-static INDEX_LOCKS: Lazy<Mutex<HashMap<String, Arc<Mutex<()>>>>> =
-    Lazy::new(|| Mutex::new(HashMap::new()));
+/// Number of mutex stripes per lock table
+const LOCK_STRIPES: usize = 64;
 
-/// Per-session mutex to prevent concurrent read-modify-write races on metadata files.
-/// Each session gets its own mutex so different sessions don't block each other.
-static METADATA_LOCKS: Lazy<Mutex<HashMap<String, Arc<Mutex<()>>>>> =
-    Lazy::new(|| Mutex::new(HashMap::new()));
+/// Striped mutexes preventing concurrent read-modify-write races on index files.
+/// A worktree always maps to the same stripe, so its index updates are serialized
+/// without keeping a lock per worktree alive for the lifetime of the app.
+static INDEX_LOCKS: [Mutex<()>; LOCK_STRIPES] = [const { Mutex::new(()) }; LOCK_STRIPES];
+
+/// Striped mutexes preventing concurrent read-modify-write races on metadata files.
+/// A session always maps to the same stripe, like `INDEX_LOCKS`.
+static METADATA_LOCKS: [Mutex<()>; LOCK_STRIPES] = [const { Mutex::new(()) }; LOCK_STRIPES];
+
+/// Upper bound on threads used to load session metadata in parallel
+const MAX_METADATA_LOAD_THREADS: usize = 8;
+
+/// Worktrees whose index was already compacted during this app run.
+/// Compaction only runs on the first load of each index to keep loads cheap.
+static COMPACTED_INDEXES: Lazy<Mutex<HashSet<String>>> = Lazy::new(|| Mutex::new(HashSet::new()));
 
 /// Global mutex to prevent concurrent read-modify-write races on session-context-metadata.json.
 static SAVED_CONTEXTS_LOCK: Lazy<Mutex<()>> = Lazy::new(|| Mutex::new(()));
 
-/// Get or create a mutex for a specific worktree index
-fn get_index_lock(worktree_id: &str) -> Arc<Mutex<()>> {
-    let mut locks = INDEX_LOCKS.lock().unwrap();
-    locks
-        .entry(worktree_id.to_string())
-        .or_insert_with(|| Arc::new(Mutex::new(())))
-        .clone()
+/// Pick the stripe guarding `id`
+///
+/// Two ids that hash to the same stripe share a mutex, so one may briefly wait
+/// on the other (roughly a 1 in 64 chance for any pair). Never hold two locks
+/// from the same table at once (ids sharing a stripe would deadlock), except
+/// through `lock_index_pair`.
+fn lock_stripe(id: &str) -> usize {
+    let mut hasher = DefaultHasher::new();
+    id.hash(&mut hasher);
+    (hasher.finish() % LOCK_STRIPES as u64) as usize
+}
+
+/// Get the mutex for a specific worktree index
+fn get_index_lock(worktree_id: &str) -> &'static Mutex<()> {
+    &INDEX_LOCKS[lock_stripe(worktree_id)]
 }
 
-/// Get or create a mutex for a specific session metadata
-fn get_metadata_lock(session_id: &str) -> Arc<Mutex<()>> {
-    let mut locks = METADATA_LOCKS.lock().unwrap();
-    locks
-        .entry(session_id.to_string())
-        .or_insert_with(|| Arc::new(Mutex::new(())))
-        .clone()
+/// Lock key of a project's preserved base index
+fn base_index_lock_key(project_id: &str) -> String {
+    format!("base-{project_id}")
+}
+
+/// Hold the index locks of two ids at once
+///
+/// Stripes are taken in a fixed order, and only once when both ids share one,
+/// so two callers can't deadlock on each other or on themselves.
+fn lock_index_pair(a: &str, b: &str) -> Vec<std::sync::MutexGuard<'static, ()>> {
+    let (first, second) = {
+        let (a, b) = (lock_stripe(a), lock_stripe(b));
+        (a.min(b), a.max(b))
+    };
+    let mut guards = vec![INDEX_LOCKS[first].lock().unwrap()];
+    if second != first {
+        guards.push(INDEX_LOCKS[second].lock().unwrap());
+    }
+    guards
+}
+
+/// Get the mutex for a specific session metadata
+fn get_metadata_lock(session_id: &str) -> &'static Mutex<()> {
+    &METADATA_LOCKS[lock_stripe(session_id)]
 }
 
 // ============================================================================
@@ -141,6 +178,59 @@ pub fn get_base_index_path(app: &AppHandle, project_id: &str) -> Result<PathBuf,
     Ok(index_dir.join(format!("base-{safe_id}.json")))
 }
 
+/// Flush a temp file's contents to disk before it is renamed into place.
+/// Failures are logged, not returned: some filesystems don't support fsync.
+fn sync_file(file: &File, path: &Path) {
+    if let Err(e) = file.sync_all() {
+        log::warn!("Failed to fsync {path:?}: {e}");
+    }
+}
+
+/// Flush the parent directory so a completed rename survives power loss (Unix only).
+/// Failures are logged, not returned: some filesystems don't support directory fsync.
+fn sync_parent_dir(path: &Path) {
+    #[cfg(unix)]
+    if let Some(parent) = path.parent() {
+        if let Err(e) = File::open(parent).and_then(|dir| dir.sync_all()) {
+            log::warn!("Failed to fsync directory {parent:?}: {e}");
+        }
+    }
+    #[cfg(not(unix))]
+    let _ = path;
+}
+
+/// Move a file that failed to parse to `{name}.corrupt-{timestamp}`
+///
+/// Keeps the data around for manual recovery and diagnosis instead of letting
+/// the next save overwrite it. Returns the backup path if the rename succeeded.
+fn back_up_corrupt_file(path: &Path, error: &str) -> Option<PathBuf> {
+    let timestamp = std::time::SystemTime::now()
+        .duration_since(std::time::UNIX_EPOCH)
+        .unwrap_or_default()
+        .as_secs();
+    let file_name = path.file_name()?.to_string_lossy();
+    let backup_path = path.with_file_name(format!("{file_name}.corrupt-{timestamp}"));
+
+    match fs::rename(path, &backup_path) {
+        Ok(()) => {
+            log::error!("Corrupt file {path:?} ({error}), moved to {backup_path:?}");
+            Some(backup_path)
+        }
+        Err(e) => {
+            log::error!("Corrupt file {path:?} ({error}), failed to back it up: {e}");
+            None
+        }
+    }
+}
+
+/// Write `contents` to `temp_path` and flush it to disk
+fn write_synced(temp_path: &Path, contents: &[u8]) -> std::io::Result<()> {
+    let mut file = File::create(temp_path)?;
+    file.write_all(contents)?;
+    sync_file(&file, temp_path);
+    Ok(())
+}
+
 // ============================================================================
 // Index Operations (WorktreeIndex)
 // ============================================================================
@@ -155,12 +245,16 @@ fn load_index_internal(app: &AppHandle, worktree_id: &str) -> Result<WorktreeInd
             format!("Failed to read index: {e}")
         })?;
 
-        let index: WorktreeIndex = serde_json::from_str(&contents).map_err(|e| {
-            log::error!("Failed to parse index JSON: {e}");
-            format!("Failed to parse index: {e}")
-        })?;
-
-        return Ok(index);
+        match serde_json::from_str::<WorktreeIndex>(&contents) {
+            Ok(index) => return Ok(index),
+            Err(e) => {
+                // Keep the corrupt file for recovery, then start over with a fresh index.
+                // Its sessions are re-linked by recover_orphaned_sessions on next startup.
+                if back_up_corrupt_file(&path, &e.to_string()).is_none() {
+                    return Err(format!("Failed to parse index: {e}"));
+                }
+            }
+        }
     }
 
     // No data exists - create new index with default session
@@ -172,6 +266,18 @@ fn load_index_internal(app: &AppHandle, worktree_id: &str) -> Result<WorktreeInd
 fn save_index_internal(app: &AppHandle, index: &WorktreeIndex) -> Result<(), String> {
     log::trace!("Saving index for worktree: {}", index.worktree_id);
     let path = get_index_path(app, &index.worktree_id)?;
+    write_index_file(&path, index)?;
+
+    log::trace!(
+        "Saved {} sessions in index for worktree {}",
+        index.sessions.len(),
+        index.worktree_id
+    );
+    Ok(())
+}
+
+/// Atomically write an index to `path` (temp file + rename)
+fn write_index_file(path: &Path, index: &WorktreeIndex) -> Result<(), String> {
     let temp_path = path.with_extension("tmp");
 
     let json_content = serde_json::to_string_pretty(index).map_err(|e| {
@@ -179,34 +285,80 @@ fn save_index_internal(app: &AppHandle, index: &WorktreeIndex) -> Result<(), Str
         format!("Failed to serialize index: {e}")
     })?;
 
-    fs::write(&temp_path, &json_content).map_err(|e| {
+    write_synced(&temp_path, json_content.as_bytes()).map_err(|e| {
         log::error!("Failed to write index file: {e}");
         format!("Failed to write index: {e}")
     })?;
 
-    fs::rename(&temp_path, &path).map_err(|e| {
+    fs::rename(&temp_path, path).map_err(|e| {
         log::error!("Failed to finalize index file: {e}");
         format!("Failed to finalize index: {e}")
     })?;
-
-    log::trace!(
-        "Saved {} sessions in index for worktree {}",
-        index.sessions.len(),
-        index.worktree_id
-    );
+    sync_parent_dir(path);
     Ok(())
 }
 
+/// Drop duplicate and dangling entries from an index
+///
+/// Duplicates (same session id, e.g. after a crash mid-write) keep their first
+/// entry. An entry is dangling when its metadata is gone but it has messages;
+/// entries without messages may legitimately have no metadata yet (new sessions
+/// are indexed before their metadata is written). Returns the number pruned.
+fn compact_index<F>(index: &mut WorktreeIndex, metadata_exists: F) -> usize
+where
+    F: Fn(&str) -> bool,
+{
+    let before = index.sessions.len();
+    let mut seen = HashSet::new();
+    index.sessions.retain(|entry| {
+        seen.insert(entry.id.clone()) && (entry.message_count == 0 || metadata_exists(&entry.id))
+    });
+
+    let is_active_missing = index
+        .active_session_id
+        .as_ref()
+        .is_some_and(|id| !index.sessions.iter().any(|s| &s.id == id));
+    if is_active_missing {
+        index.active_session_id = index.sessions.first().map(|s| s.id.clone());
+    }
+
+    before - index.sessions.len()
+}
+
+/// Compact an index the first time it is loaded during this app run
+///
+/// Returns true if entries were pruned and the index should be saved.
+fn compact_index_once(app: &AppHandle, index: &mut WorktreeIndex) -> Result<bool, String> {
+    if !COMPACTED_INDEXES
+        .lock()
+        .unwrap()
+        .insert(index.worktree_id.clone())
+    {
+        return Ok(false);
+    }
+
+    let data_dir = get_data_dir(app)?;
+    let pruned = compact_index(index, |id| data_dir.join(id).join("metadata.json").exists());
+    if pruned > 0 {
+        log::info!(
+            "Pruned {pruned} stale entries from index for worktree {}",
+            index.worktree_id
+        );
+    }
+    Ok(pruned > 0)
+}
+
 /// Load a worktree index (with locking for thread safety)
 pub fn load_index(app: &AppHandle, worktree_id: &str) -> Result<WorktreeIndex, String> {
     let lock = get_index_lock(worktree_id);
     let _guard = lock.lock().unwrap();
 
-    let index = load_index_internal(app, worktree_id)?;
+    let mut index = load_index_internal(app, worktree_id)?;
+    let compacted = compact_index_once(app, &mut index)?;
 
-    // If this was a new index file, save it
+    // If this was a new index file (or it was just compacted), save it
     let index_path = get_index_path(app, worktree_id)?;
-    if !index_path.exists() {
+    if compacted || !index_path.exists() {
         save_index_internal(app, &index)?;
     }
 
@@ -223,6 +375,7 @@ where
     let _guard = lock.lock().unwrap();
 
     let mut index = load_index_internal(app, worktree_id)?;
+    compact_index_once(app, &mut index)?;
     let result = f(&mut index)?;
     save_index_internal(app, &index)?;
 
@@ -248,10 +401,16 @@ fn load_metadata_internal(
         File::open(&path).map_err(|e| format!("Failed to open metadata file {path:?}: {e}"))?;
 
     let reader = BufReader::new(file);
-    let metadata: SessionMetadata = serde_json::from_reader(reader)
-        .map_err(|e| format!("Failed to parse metadata file {path:?}: {e}"))?;
-
-    Ok(Some(metadata))
+    match serde_json::from_reader(reader) {
+        Ok(metadata) => Ok(Some(metadata)),
+        Err(e) => {
+            // Keep the corrupt file for recovery, then treat the metadata as missing
+            match back_up_corrupt_file(&path, &e.to_string()) {
+                Some(_) => Ok(None),
+                None => Err(format!("Failed to parse metadata file {path:?}: {e}")),
+            }
+        }
+    }
 }
 
 /// Save session metadata (internal, no locking - atomic write)
@@ -262,11 +421,16 @@ fn save_metadata_internal(app: &AppHandle, metadata: &SessionMetadata) -> Result
     let file = File::create(&temp_path)
         .map_err(|e| format!("Failed to create temp metadata file: {e}"))?;
 
-    let writer = BufWriter::new(file);
-    serde_json::to_writer_pretty(writer, metadata)
+    let mut writer = BufWriter::new(file);
+    serde_json::to_writer_pretty(&mut writer, metadata)
+        .map_err(|e| format!("Failed to write metadata: {e}"))?;
+    let file = writer
+        .into_inner()
         .map_err(|e| format!("Failed to write metadata: {e}"))?;
+    sync_file(&file, &temp_path);
 
     fs::rename(&temp_path, &path).map_err(|e| format!("Failed to rename metadata file: {e}"))?;
+    sync_parent_dir(&path);
 
     log::trace!("Saved metadata for session: {}", metadata.id);
     Ok(())
@@ -317,11 +481,49 @@ where
     Ok(result)
 }
 
+/// Delete the git refs keeping a session's run snapshots alive (best-effort)
+///
+/// The refs live in the repository shared by all of a project's worktrees, so
+/// the project path is used when the worktree directory is already gone.
+fn unpin_run_snapshots(app: &AppHandle, metadata: &SessionMetadata) {
+    let projects = match crate::projects::storage::load_projects_data(app) {
+        Ok(projects) => projects,
+        Err(e) => {
+            log::warn!("Failed to load projects to unpin run snapshots: {e}");
+            return;
+        }
+    };
+    let repo_path = match projects.find_worktree(&metadata.worktree_id) {
+        Some(worktree) if Path::new(&worktree.path).exists() => Some(worktree.path.clone()),
+        Some(worktree) => projects
+            .find_project(&worktree.project_id)
+            .map(|p| p.path.clone()),
+        None => projects
+            .find_project(&metadata.worktree_id)
+            .map(|p| p.path.clone()),
+    };
+    let Some(repo_path) = repo_path else {
+        return;
+    };
+    if let Err(e) = crate::projects::git_status::delete_run_snapshots(&repo_path, &metadata.id) {
+        log::warn!(
+            "Failed to unpin run snapshots of session {}: {e}",
+            metadata.id
+        );
+    }
+}
+
 /// Delete a session's metadata and all data files (with locking)
 pub fn delete_session_data(app: &AppHandle, session_id: &str) -> Result<(), String> {
     let lock = get_metadata_lock(session_id);
     let _guard = lock.lock().unwrap();
 
+    if let Ok(Some(metadata)) = load_metadata_internal(app, session_id) {
+        if metadata.runs.iter().any(|run| run.start_tree.is_some()) {
+            unpin_run_snapshots(app, &metadata);
+        }
+    }
+
     let data_dir = get_data_dir(app)?;
     let session_dir = data_dir.join(session_id);
 
@@ -334,6 +536,63 @@ pub fn delete_session_data(app: &AppHandle, session_id: &str) -> Result<(), Stri
     Ok(())
 }
 
+/// Replace a session's data directory with `source` (with locking)
+///
+/// The current directory is moved aside first and only removed once `source`
+/// is in place; if the move in fails, it is put back. `source` must be on the
+/// same filesystem as the data directory.
+pub fn replace_session_data(
+    app: &AppHandle,
+    session_id: &str,
+    source: &Path,
+) -> Result<(), String> {
+    let lock = get_metadata_lock(session_id);
+    let _guard = lock.lock().unwrap();
+
+    let previous = load_metadata_internal(app, session_id).ok().flatten();
+    let data_dir = get_data_dir(app)?;
+    let session_dir = data_dir.join(session_id);
+    let aside = data_dir
+        .parent()
+        .unwrap_or(&data_dir)
+        .join(format!("replaced-session-{session_id}"));
+
+    let moved_aside = session_dir.exists();
+    if moved_aside {
+        if aside.exists() {
+            fs::remove_dir_all(&aside)
+                .map_err(|e| format!("Failed to clear old replaced session data: {e}"))?;
+        }
+        fs::rename(&session_dir, &aside)
+            .map_err(|e| format!("Failed to move session {session_id} aside: {e}"))?;
+    }
+
+    if let Err(e) = fs::rename(source, &session_dir) {
+        if moved_aside {
+            if let Err(restore) = fs::rename(&aside, &session_dir) {
+                log::error!(
+                    "Failed to restore session {session_id} after a failed replace: {restore}"
+                );
+            }
+        }
+        return Err(format!("Failed to replace session {session_id}: {e}"));
+    }
+
+    if moved_aside {
+        if let Err(e) = fs::remove_dir_all(&aside) {
+            log::warn!("Failed to remove replaced data of session {session_id}: {e}");
+        }
+    }
+    if let Some(metadata) = previous {
+        if metadata.runs.iter().any(|run| run.start_tree.is_some()) {
+            unpin_run_snapshots(app, &metadata);
+        }
+    }
+
+    log::trace!("Replaced session data for: {session_id}");
+    Ok(())
+}
+
 /// List all session IDs in the data directory (for recovery scanning)
 pub fn list_all_session_ids(app: &AppHandle) -> Result<Vec<String>, String> {
     let data_dir = get_data_dir(app)?;
@@ -356,6 +615,364 @@ pub fn list_all_session_ids(app: &AppHandle) -> Result<Vec<String>, String> {
     Ok(session_ids)
 }
 
+/// A session re-linked to its worktree index by `recover_orphaned_sessions`
+#[derive(Debug, Clone, serde::Serialize)]
+pub struct RecoveredSession {
+    pub session_id: String,
+    pub worktree_id: String,
+    pub name: String,
+    /// True if the worktree's index file was missing and had to be rebuilt
+    pub index_rebuilt: bool,
+}
+
+/// Collect the session IDs referenced by any index file: worktree indexes and
+/// the `base-*.json` indexes preserved for closed base sessions
+///
+/// Only `sessions[].id` is read, so an index that no longer matches
+/// `WorktreeIndex` still keeps its sessions from being re-linked elsewhere.
+fn referenced_session_ids(app: &AppHandle) -> Result<HashSet<String>, String> {
+    let index_dir = get_index_dir(app)?;
+    let entries =
+        fs::read_dir(&index_dir).map_err(|e| format!("Failed to read index directory: {e}"))?;
+
+    let mut ids = HashSet::new();
+    for entry in entries.flatten() {
+        let path = entry.path();
+        if path.extension().and_then(|e| e.to_str()) != Some("json") {
+            continue;
+        }
+        match fs::read_to_string(&path)
+            .map_err(|e| e.to_string())
+            .and_then(|c| serde_json::from_str::<serde_json::Value>(&c).map_err(|e| e.to_string()))
+        {
+            Ok(index) => ids.extend(index_session_ids(&index)),
+            Err(e) => log::warn!("Skipping unreadable index {path:?}: {e}"),
+        }
+    }
+
+    Ok(ids)
+}
+
+/// Session IDs listed in a parsed index file
+fn index_session_ids(index: &serde_json::Value) -> impl Iterator<Item = String> + '_ {
+    index
+        .get("sessions")
+        .and_then(|s| s.as_array())
+        .into_iter()
+        .flatten()
+        .filter_map(|entry| entry.get("id")?.as_str().map(str::to_string))
+}
+
+/// Whether an unreferenced session is worth re-linking
+///
+/// Archived sessions are dropped from their index on purpose when they are
+/// cleaned up, and a session that never ran has nothing to recover; either
+/// may just be left over from an interrupted delete.
+fn is_recoverable(metadata: &SessionMetadata) -> bool {
+    metadata.archived_at.is_none() && !metadata.runs.is_empty()
+}
+
+/// Add index entries for sessions missing from `index`
+///
+/// When `rebuild` is set the index was just recreated, so its placeholder
+/// default session is dropped first. Returns the IDs that were added.
+pub fn relink_sessions(
+    index: &mut WorktreeIndex,
+    sessions: &[SessionMetadata],
+    rebuild: bool,
+) -> Vec<String> {
+    if rebuild {
+        index.sessions.clear();
+        index.active_session_id = None;
+    }
+
+    let mut added = Vec::new();
+    for metadata in sessions {
+        if index.find_session_mut(&metadata.id).is_none() {
+            index.sessions.push(metadata.to_index_entry());
+            added.push(metadata.id.clone());
+        }
+    }
+    index.sessions.sort_by_key(|s| s.order);
+
+    if index.active_session_id.is_none() {
+        index.active_session_id = index
+            .sessions
+            .iter()
+            .find(|s| s.archived_at.is_none())
+            .map(|s| s.id.clone());
+    }
+
+    added
+}
+
+/// Re-link sessions whose data is on disk but which no index file references
+///
+/// Happens when an index file is lost (corruption, a crash during
+/// `save_index_internal`, partial delete). Each orphan is added back to the
+/// index of the worktree recorded in its metadata, rebuilding the index if it
+/// is missing. Sessions of worktrees that no longer exist, archived sessions
+/// and sessions without runs are left alone.
+pub fn recover_orphaned_sessions(app: &AppHandle) -> Result<Vec<RecoveredSession>, String> {
+    let referenced = referenced_session_ids(app)?;
+    let projects = crate::projects::storage::load_projects_data(app)?;
+
+    let mut orphans: HashMap<String, Vec<SessionMetadata>> = HashMap::new();
+    for session_id in list_all_session_ids(app)? {
+        if referenced.contains(&session_id) {
+            continue;
+        }
+        let Some(metadata) = load_metadata(app, &session_id)? else {
+            continue;
+        };
+        if !is_recoverable(&metadata) {
+            log::trace!("Skipping orphaned session {session_id}: archived or never run");
+            continue;
+        }
+        if projects.find_worktree(&metadata.worktree_id).is_none() {
+            log::trace!(
+                "Skipping orphaned session {session_id}: worktree {} no longer exists",
+                metadata.worktree_id
+            );
+            continue;
+        }
+        orphans
+            .entry(metadata.worktree_id.clone())
+            .or_default()
+            .push(metadata);
+    }
+
+    let mut recovered = Vec::new();
+    for (worktree_id, sessions) in orphans {
+        let rebuild = !get_index_path(app, &worktree_id)?.exists();
+        let added = with_index_mut(app, &worktree_id, |index| {
+            Ok(relink_sessions(index, &sessions, rebuild))
+        })?;
+
+        for metadata in sessions.iter().filter(|m| added.contains(&m.id)) {
+            recovered.push(RecoveredSession {
+                session_id: metadata.id.clone(),
+                worktree_id: worktree_id.clone(),
+                name: metadata.name.clone(),
+                index_rebuilt: rebuild,
+            });
+        }
+    }
+
+    if !recovered.is_empty() {
+        log::info!("Re-linked {} orphaned session(s)", recovered.len());
+    }
+
+    Ok(recovered)
+}
+
+/// Result of `cleanup_archived_sessions`
+#[derive(Debug, Clone, Default, serde::Serialize)]
+pub struct CleanupReport {
+    /// True if nothing was deleted (counts describe what would be deleted)
+    pub dry_run: bool,
+    /// Archived sessions deleted
+    pub sessions_deleted: usize,
+    /// Worktree indexes that had at least one session deleted
+    pub worktrees_affected: usize,
+    /// Bytes of session data freed
+    pub bytes_reclaimed: u64,
+    /// IDs of the deleted sessions
+    pub session_ids: Vec<String>,
+}
+
+/// IDs of sessions archived before `cutoff` (Unix seconds), never including the active session
+fn expired_archived_sessions(index: &WorktreeIndex, cutoff: u64) -> Vec<String> {
+    index
+        .sessions
+        .iter()
+        .filter(|s| s.archived_at.is_some_and(|t| t < cutoff))
+        .filter(|s| index.active_session_id.as_deref() != Some(s.id.as_str()))
+        .map(|s| s.id.clone())
+        .collect()
+}
+
+/// Total size in bytes of all files under `path`
+fn dir_size(path: &Path) -> u64 {
+    let Ok(entries) = fs::read_dir(path) else {
+        return 0;
+    };
+    entries
+        .flatten()
+        .map(|entry| match entry.metadata() {
+            Ok(meta) if meta.is_dir() => dir_size(&entry.path()),
+            Ok(meta) => meta.len(),
+            Err(_) => 0,
+        })
+        .sum()
+}
+
+/// Read every live worktree index, skipping preserved base indexes and unreadable files
+fn load_live_indexes(app: &AppHandle) -> Result<Vec<WorktreeIndex>, String> {
+    let index_dir = get_index_dir(app)?;
+    let entries =
+        fs::read_dir(&index_dir).map_err(|e| format!("Failed to read index directory: {e}"))?;
+
+    let mut indexes = Vec::new();
+    for entry in entries.flatten() {
+        let path = entry.path();
+        if path.extension().and_then(|e| e.to_str()) != Some("json") {
+            continue;
+        }
+        let index: WorktreeIndex = match fs::read_to_string(&path)
+            .map_err(|e| e.to_string())
+            .and_then(|c| serde_json::from_str(&c).map_err(|e| e.to_string()))
+        {
+            Ok(index) => index,
+            Err(e) => {
+                log::warn!("Skipping unreadable index {path:?}: {e}");
+                continue;
+            }
+        };
+        if get_index_path(app, &index.worktree_id)? != path {
+            // Preserved base index (base-{project_id}.json)
+            continue;
+        }
+        indexes.push(index);
+    }
+    Ok(indexes)
+}
+
+/// Permanently delete sessions archived more than `older_than_days` ago
+///
+/// Scans every live worktree index. Preserved base indexes are left alone so
+/// their sessions can still be restored with the base session. With `dry_run`
+/// the report lists what would be deleted without touching anything.
+pub fn cleanup_archived_sessions(
+    app: &AppHandle,
+    older_than_days: u64,
+    dry_run: bool,
+) -> Result<CleanupReport, String> {
+    let now = std::time::SystemTime::now()
+        .duration_since(std::time::UNIX_EPOCH)
+        .unwrap_or_default()
+        .as_secs();
+    let cutoff = now.saturating_sub(older_than_days.saturating_mul(24 * 60 * 60));
+
+    let data_dir = get_data_dir(app)?;
+    let mut report = CleanupReport {
+        dry_run,
+        ..Default::default()
+    };
+    for index in load_live_indexes(app)? {
+        let expired = if dry_run {
+            expired_archived_sessions(&index, cutoff)
+        } else {
+            // Re-check under the index lock in case sessions changed since the scan
+            with_index_mut(app, &index.worktree_id, |index| {
+                let expired = expired_archived_sessions(index, cutoff);
+                index.sessions.retain(|s| !expired.contains(&s.id));
+                Ok(expired)
+            })?
+        };
+        if expired.is_empty() {
+            continue;
+        }
+
+        for session_id in &expired {
+            report.bytes_reclaimed += dir_size(&data_dir.join(session_id));
+            if !dry_run {
+                if let Err(e) = delete_session_data(app, session_id) {
+                    log::warn!("Failed to delete data for archived session {session_id}: {e}");
+                }
+            }
+        }
+        report.sessions_deleted += expired.len();
+        report.worktrees_affected += 1;
+        report.session_ids.extend(expired);
+    }
+
+    log::info!(
+        "{} {} archived session(s) older than {older_than_days} days ({} bytes)",
+        if dry_run { "Would delete" } else { "Deleted" },
+        report.sessions_deleted,
+        report.bytes_reclaimed
+    );
+    Ok(report)
+}
+
+/// Unix timestamp of a session's last activity: its newest run, or its creation if it has none
+pub fn session_last_activity(metadata: &SessionMetadata) -> u64 {
+    metadata
+        .runs
+        .iter()
+        .map(|run| run.ended_at.unwrap_or(run.started_at))
+        .max()
+        .unwrap_or(metadata.created_at)
+}
+
+/// IDs of unarchived sessions last active before `cutoff` (Unix seconds),
+/// never including the active session or one that is running
+fn idle_sessions(
+    sessions: &WorktreeSessions,
+    cutoff: u64,
+    last_activity: impl Fn(&Session) -> u64,
+    is_running: impl Fn(&str) -> bool,
+) -> Vec<String> {
+    sessions
+        .sessions
+        .iter()
+        .filter(|s| s.archived_at.is_none())
+        .filter(|s| sessions.active_session_id.as_deref() != Some(s.id.as_str()))
+        .filter(|s| !is_running(&s.id))
+        .filter(|s| last_activity(s) < cutoff)
+        .map(|s| s.id.clone())
+        .collect()
+}
+
+/// Archive sessions with no activity in the last `idle_days`
+///
+/// Scans every live worktree index; the active session and sessions for which
+/// `is_running` returns true are left alone. Returns the archived session IDs.
+pub fn auto_archive_idle_sessions(
+    app: &AppHandle,
+    idle_days: u64,
+    is_running: impl Fn(&str) -> bool,
+) -> Result<Vec<String>, String> {
+    let now = std::time::SystemTime::now()
+        .duration_since(std::time::UNIX_EPOCH)
+        .unwrap_or_default()
+        .as_secs();
+    let cutoff = now.saturating_sub(idle_days.saturating_mul(24 * 60 * 60));
+
+    let last_activity = |session: &Session| {
+        load_metadata(app, &session.id)
+            .ok()
+            .flatten()
+            .map(|metadata| session_last_activity(&metadata))
+            .unwrap_or(session.created_at)
+    };
+
+    let mut archived = Vec::new();
+    for index in load_live_indexes(app)? {
+        let ids = with_sessions_mut(app, "", &index.worktree_id, |sessions| {
+            let ids = idle_sessions(sessions, cutoff, last_activity, &is_running);
+            for session in sessions.sessions.iter_mut().filter(|s| ids.contains(&s.id)) {
+                session.archived_at = Some(now);
+            }
+            // Same as archiving by hand: a worktree always keeps one open session
+            if !ids.is_empty() && sessions.sessions.iter().all(|s| s.archived_at.is_some()) {
+                let default_session = Session::default_session();
+                sessions.active_session_id = Some(default_session.id.clone());
+                sessions.sessions.push(default_session);
+            }
+            Ok(ids)
+        })?;
+        archived.extend(ids);
+    }
+
+    log::info!(
+        "Archived {} session(s) idle for more than {idle_days} days",
+        archived.len()
+    );
+    Ok(archived)
+}
+
 // ============================================================================
 // High-Level Session API (Backward Compatibility)
 // ============================================================================
@@ -369,53 +986,59 @@ pub fn load_sessions(
 ) -> Result<WorktreeSessions, String> {
     let index = load_index(app, worktree_id)?;
 
-    // Load metadata for each session to build full Session objects
-    let mut sessions = Vec::new();
-    for entry in &index.sessions {
-        let session = if let Ok(Some(metadata)) = load_metadata(app, &entry.id) {
-            metadata.to_session()
-        } else {
-            // No metadata found - create minimal session from index entry
-            Session {
-                id: entry.id.clone(),
-                name: entry.name.clone(),
-                order: entry.order,
-                created_at: std::time::SystemTime::now()
-                    .duration_since(std::time::UNIX_EPOCH)
-                    .unwrap_or_default()
-                    .as_secs(),
-                messages: vec![],
-                message_count: Some(entry.message_count),
-                claude_session_id: None,
-                task_list_id: None,
-                selected_provider: None,
-                selected_model: None,
-                selected_thinking_level: None,
-                session_naming_completed: false,
-                archived_at: entry.archived_at,
-                answered_questions: vec![],
-                submitted_answers: std::collections::HashMap::new(),
-                fixed_findings: vec![],
-                pending_permission_denials: vec![],
-                denied_message_context: None,
-                is_reviewing: false,
-                waiting_for_input: false,
-                approved_plan_message_ids: vec![],
-            }
-        };
-        sessions.push(session);
-    }
+    // Load metadata for each session to build full Session objects.
+    // Each load is an independent file read under its own per-session lock.
+    let sessions = parallel_map(&index.sessions, MAX_METADATA_LOAD_THREADS, |entry| {
+        session_from_index_entry(app, entry)
+    });
 
     Ok(WorktreeSessions {
         worktree_id: index.worktree_id,
         sessions,
         active_session_id: index.active_session_id,
-        default_model: None,
+        default_model: index.default_model,
+        default_thinking_level: index.default_thinking_level,
         version: index.version,
         branch_naming_completed: index.branch_naming_completed,
     })
 }
 
+/// Build a full Session from its metadata, falling back to the index entry
+fn session_from_index_entry(app: &AppHandle, entry: &SessionIndexEntry) -> Session {
+    if let Ok(Some(metadata)) = load_metadata(app, &entry.id) {
+        return metadata.to_session();
+    }
+
+    // No metadata found - create minimal session from index entry
+    Session {
+        id: entry.id.clone(),
+        name: entry.name.clone(),
+        order: entry.order,
+        created_at: std::time::SystemTime::now()
+            .duration_since(std::time::UNIX_EPOCH)
+            .unwrap_or_default()
+            .as_secs(),
+        messages: vec![],
+        message_count: Some(entry.message_count),
+        claude_session_id: None,
+        task_list_id: None,
+        selected_provider: None,
+        selected_model: None,
+        selected_thinking_level: None,
+        mcp_config_path: None,
+        session_naming_completed: false,
+        archived_at: entry.archived_at,
+        answered_questions: vec![],
+        submitted_answers: std::collections::HashMap::new(),
+        fixed_findings: vec![],
+        pending_permission_denials: vec![],
+        denied_message_context: None,
+        is_reviewing: false,
+        waiting_for_input: false,
+        approved_plan_message_ids: vec![],
+    }
+}
+
 /// Atomically modify sessions (backward compatible with old with_sessions_mut).
 /// Updates both index and metadata files.
 pub fn with_sessions_mut<F, T>(
@@ -497,11 +1120,6 @@ pub fn get_sessions_path(app: &AppHandle, worktree_id: &str) -> Result<PathBuf,
     get_index_path(app, worktree_id)
 }
 
-/// Load sessions by worktree_id only (for cleanup when worktree path may not exist)
-pub fn load_sessions_by_id(app: &AppHandle, worktree_id: &str) -> Result<WorktreeSessions, String> {
-    load_sessions(app, "", worktree_id)
-}
-
 /// Get the path for a closed base session's preserved index file
 /// (Backward compatible with old get_closed_base_sessions_path)
 pub fn get_closed_base_sessions_path(app: &AppHandle, project_id: &str) -> Result<PathBuf, String> {
@@ -519,8 +1137,8 @@ pub fn preserve_base_sessions(
     worktree_id: &str,
     project_id: &str,
 ) -> Result<(), String> {
-    let lock = get_index_lock(worktree_id);
-    let _guard = lock.lock().unwrap();
+    // Same lock as `with_base_index_mut`, plus the worktree's own
+    let _guards = lock_index_pair(worktree_id, &base_index_lock_key(project_id));
 
     let current_path = get_index_path(app, worktree_id)?;
     let preserved_path = get_base_index_path(app, project_id)?;
@@ -536,6 +1154,35 @@ pub fn preserve_base_sessions(
     Ok(())
 }
 
+/// Run `f` on a project's preserved base index (with locking), then save it
+///
+/// Starts from an empty index when the project has none. Lets sessions be
+/// added to a base session while it is closed.
+pub fn with_base_index_mut<F, T>(app: &AppHandle, project_id: &str, f: F) -> Result<T, String>
+where
+    F: FnOnce(&mut WorktreeIndex) -> Result<T, String>,
+{
+    let lock = get_index_lock(&base_index_lock_key(project_id));
+    let _guard = lock.lock().unwrap();
+
+    let path = get_base_index_path(app, project_id)?;
+    let mut index = if path.exists() {
+        let contents = fs::read_to_string(&path)
+            .map_err(|e| format!("Failed to read preserved index: {e}"))?;
+        serde_json::from_str(&contents)
+            .map_err(|e| format!("Failed to parse preserved index: {e}"))?
+    } else {
+        let mut index = WorktreeIndex::new(project_id.to_string());
+        index.sessions.clear();
+        index.active_session_id = None;
+        index
+    };
+    let result = f(&mut index)?;
+    write_index_file(&path, &index)?;
+
+    Ok(result)
+}
+
 /// Restore preserved sessions when reopening a base session
 /// Loads from base-{project_id}.json and updates worktree_id
 pub fn restore_base_sessions(
@@ -543,8 +1190,8 @@ pub fn restore_base_sessions(
     project_id: &str,
     new_worktree_id: &str,
 ) -> Result<Option<WorktreeIndex>, String> {
-    let lock = get_index_lock(new_worktree_id);
-    let _guard = lock.lock().unwrap();
+    // Same lock as `with_base_index_mut`, plus the worktree's own
+    let _guards = lock_index_pair(new_worktree_id, &base_index_lock_key(project_id));
 
     let preserved_path = get_base_index_path(app, project_id)?;
 
@@ -648,7 +1295,10 @@ pub fn load_saved_contexts_metadata(app: &AppHandle) -> SavedContextsMetadata {
     }
 
     match fs::read_to_string(&path) {
-        Ok(contents) => serde_json::from_str(&contents).unwrap_or_default(),
+        Ok(contents) => serde_json::from_str(&contents).unwrap_or_else(|e| {
+            back_up_corrupt_file(&path, &e.to_string());
+            SavedContextsMetadata::default()
+        }),
         Err(_) => SavedContextsMetadata::default(),
     }
 }
@@ -666,9 +1316,11 @@ pub fn save_saved_contexts_metadata(
     let json = serde_json::to_string_pretty(metadata)
         .map_err(|e| format!("Failed to serialize metadata: {e}"))?;
 
-    fs::write(&temp_path, &json).map_err(|e| format!("Failed to write metadata file: {e}"))?;
+    write_synced(&temp_path, json.as_bytes())
+        .map_err(|e| format!("Failed to write metadata file: {e}"))?;
 
     fs::rename(&temp_path, &path).map_err(|e| format!("Failed to finalize metadata file: {e}"))?;
+    sync_parent_dir(&path);
 
     Ok(())
 }
@@ -697,6 +1349,224 @@ mod tests {
         );
     }
 
+    #[test]
+    fn test_parallel_map_preserves_index_order() {
+        // One metadata file per session, read back in parallel
+        let dir = tempfile::tempdir().unwrap();
+        let mut index = WorktreeIndex::new("test".to_string());
+        for n in 2..=20 {
+            index.sessions.push(SessionIndexEntry {
+                id: format!("sess-{n}"),
+                name: format!("Session {n}"),
+                order: n - 1,
+                message_count: 0,
+                archived_at: None,
+            });
+        }
+        for entry in &index.sessions {
+            let metadata = SessionMetadata::new(
+                entry.id.clone(),
+                "test".to_string(),
+                entry.name.clone(),
+                entry.order,
+            );
+            let path = dir.path().join(format!("{}.json", entry.id));
+            fs::write(path, serde_json::to_string(&metadata).unwrap()).unwrap();
+        }
+
+        let load = |entry: &SessionIndexEntry| {
+            // Stagger reads so threads finish out of order
+            std::thread::sleep(std::time::Duration::from_millis(
+                (20 - entry.order as u64) % 5,
+            ));
+            let path = dir.path().join(format!("{}.json", entry.id));
+            let metadata: SessionMetadata =
+                serde_json::from_str(&fs::read_to_string(path).unwrap()).unwrap();
+            (metadata.id, metadata.name)
+        };
+
+        let serial: Vec<_> = index.sessions.iter().map(load).collect();
+        let parallel = parallel_map(&index.sessions, 4, load);
+
+        assert_eq!(parallel, serial);
+        let ids: Vec<&str> = parallel.iter().map(|(id, _)| id.as_str()).collect();
+        let expected: Vec<&str> = index.sessions.iter().map(|e| e.id.as_str()).collect();
+        assert_eq!(ids, expected);
+    }
+
+    #[test]
+    fn test_compact_index_drops_dangling_and_duplicate_entries() {
+        let dir = tempfile::tempdir().unwrap();
+        let entry = |id: &str, message_count: u32| SessionIndexEntry {
+            id: id.to_string(),
+            name: id.to_string(),
+            order: 0,
+            message_count,
+            archived_at: None,
+        };
+
+        let mut index = WorktreeIndex::new("test".to_string());
+        index.sessions = vec![
+            entry("kept", 3),
+            entry("dangling", 5),
+            entry("kept", 3),
+            entry("new-empty", 0),
+        ];
+        index.active_session_id = Some("dangling".to_string());
+
+        // Only "kept" has metadata on disk
+        fs::create_dir_all(dir.path().join("kept")).unwrap();
+        fs::write(dir.path().join("kept").join("metadata.json"), "{}").unwrap();
+        let metadata_exists = |id: &str| dir.path().join(id).join("metadata.json").exists();
+
+        assert_eq!(compact_index(&mut index, metadata_exists), 2);
+        let ids: Vec<&str> = index.sessions.iter().map(|e| e.id.as_str()).collect();
+        assert_eq!(ids, vec!["kept", "new-empty"]);
+        assert_eq!(index.active_session_id.as_deref(), Some("kept"));
+
+        // Already compact: nothing to prune
+        assert_eq!(compact_index(&mut index, metadata_exists), 0);
+    }
+
+    #[test]
+    fn test_index_session_ids_and_recoverable() {
+        let base_index = serde_json::json!({
+            "worktree_id": "old-base",
+            "sessions": [{ "id": "a" }, { "id": "b", "unknown_field": 1 }],
+        });
+        let ids: Vec<String> = index_session_ids(&base_index).collect();
+        assert_eq!(ids, ["a", "b"]);
+        assert_eq!(index_session_ids(&serde_json::json!({})).count(), 0);
+
+        let mut metadata =
+            SessionMetadata::new("s".to_string(), "wt".to_string(), "S".to_string(), 0);
+        assert!(!is_recoverable(&metadata), "never ran");
+        metadata.runs.push(
+            serde_json::from_value(serde_json::json!({
+                "run_id": "r", "user_message_id": "u", "user_message": "hi",
+                "started_at": 0, "status": "completed",
+            }))
+            .unwrap(),
+        );
+        assert!(is_recoverable(&metadata));
+        metadata.archived_at = Some(1);
+        assert!(!is_recoverable(&metadata), "archived");
+    }
+
+    #[test]
+    fn test_relink_sessions() {
+        let metadata = |id: &str, order: u32| {
+            SessionMetadata::new(id.to_string(), "wt".to_string(), id.to_string(), order)
+        };
+
+        // Missing entries are appended to an existing index, keeping tab order
+        let mut index = WorktreeIndex::new("wt".to_string());
+        let existing = index.sessions[0].id.clone();
+        let orphans = vec![metadata("orphan-b", 2), metadata("orphan-a", 1)];
+        let added = relink_sessions(&mut index, &orphans, false);
+        assert_eq!(added, vec!["orphan-b", "orphan-a"]);
+        let ids: Vec<&str> = index.sessions.iter().map(|s| s.id.as_str()).collect();
+        assert_eq!(ids, vec![existing.as_str(), "orphan-a", "orphan-b"]);
+
+        // Already linked sessions are not added twice
+        assert!(relink_sessions(&mut index, &orphans, false).is_empty());
+
+        // A rebuilt index drops its placeholder session
+        let mut index = WorktreeIndex::new("wt".to_string());
+        relink_sessions(&mut index, &orphans, true);
+        let ids: Vec<&str> = index.sessions.iter().map(|s| s.id.as_str()).collect();
+        assert_eq!(ids, vec!["orphan-a", "orphan-b"]);
+        assert_eq!(index.active_session_id.as_deref(), Some("orphan-a"));
+    }
+
+    #[test]
+    fn test_lock_stripe_is_stable() {
+        let id = "4f9c1a52-7d1e-4b8a-9c3e-2a6f0d8b1e47";
+        assert_eq!(lock_stripe(id), lock_stripe(id));
+        assert!(std::ptr::eq(get_index_lock(id), get_index_lock(id)));
+
+        // Ids spread across stripes rather than piling onto one
+        let stripes: HashSet<usize> = (0..1_000)
+            .map(|i| lock_stripe(&format!("session-{i}")))
+            .collect();
+        assert!(stripes.iter().all(|&s| s < LOCK_STRIPES));
+        assert!(stripes.len() > LOCK_STRIPES / 2);
+    }
+
+    #[test]
+    fn test_lock_index_pair_shares_a_stripe_once() {
+        let a = "session-0";
+        let b = (1..)
+            .map(|i| format!("session-{i}"))
+            .find(|id| lock_stripe(id) == lock_stripe(a))
+            .unwrap();
+        // Would deadlock if the shared stripe were locked twice
+        assert_eq!(lock_index_pair(a, &b).len(), 1);
+        assert_eq!(lock_index_pair(&b, a).len(), 1);
+    }
+
+    #[test]
+    fn test_expired_archived_sessions() {
+        let mut index = WorktreeIndex::new("wt".to_string());
+        index.sessions.clear();
+        for (id, archived_at) in [("old", Some(100)), ("recent", Some(500)), ("live", None)] {
+            let mut metadata =
+                SessionMetadata::new(id.to_string(), "wt".to_string(), id.to_string(), 0);
+            metadata.archived_at = archived_at;
+            index.sessions.push(metadata.to_index_entry());
+        }
+        index.active_session_id = Some("live".to_string());
+
+        assert_eq!(expired_archived_sessions(&index, 200), vec!["old"]);
+        assert!(expired_archived_sessions(&index, 100).is_empty());
+
+        // The active session is never cleaned up, even if archived
+        index.active_session_id = Some("old".to_string());
+        assert_eq!(expired_archived_sessions(&index, 1_000), vec!["recent"]);
+    }
+
+    #[test]
+    fn test_idle_sessions() {
+        let mut sessions = WorktreeSessions::default();
+        sessions.sessions.clear();
+        for (i, name) in ["stale", "fresh", "running", "archived", "active"]
+            .into_iter()
+            .enumerate()
+        {
+            let mut session = Session::new(name.to_string(), i as u32);
+            session.id = name.to_string();
+            session.created_at = if name == "fresh" { 500 } else { 100 };
+            sessions.sessions.push(session);
+        }
+        sessions.sessions[3].archived_at = Some(150);
+        sessions.active_session_id = Some("active".to_string());
+
+        let created_at = |s: &Session| s.created_at;
+        let is_running = |id: &str| id == "running";
+        assert_eq!(
+            idle_sessions(&sessions, 200, created_at, is_running),
+            vec!["stale"]
+        );
+        assert!(idle_sessions(&sessions, 100, created_at, is_running).is_empty());
+
+        // Recent activity keeps an old session out
+        let recently_used = |s: &Session| if s.id == "stale" { 300 } else { s.created_at };
+        assert!(idle_sessions(&sessions, 200, recently_used, is_running).is_empty());
+    }
+
+    #[test]
+    fn test_back_up_corrupt_file() {
+        let dir = tempfile::tempdir().unwrap();
+        let path = dir.path().join("wt-1.json");
+        fs::write(&path, "{ truncated").unwrap();
+
+        let backup = back_up_corrupt_file(&path, "EOF while parsing").unwrap();
+        assert!(!path.exists());
+        assert_eq!(fs::read_to_string(&backup).unwrap(), "{ truncated");
+        let backup_name = backup.file_name().unwrap().to_str().unwrap();
+        assert!(backup_name.starts_with("wt-1.json.corrupt-"));
+    }
+
     #[test]
     fn test_worktree_index_new() {
         let index = WorktreeIndex::new("test-worktree".to_string());
@@ -6,11 +6,28 @@
 use std::fs::File;
 use std::io::{BufRead, BufReader, Seek, SeekFrom};
 use std::path::Path;
-use std::time::Duration;
+use std::time::{Duration, Instant};
 
 /// Polling interval for tailing NDJSON files (50ms)
 pub const POLL_INTERVAL: Duration = Duration::from_millis(50);
 
+/// Shortest configurable poll interval; anything lower is busy-waiting
+pub const MIN_POLL_INTERVAL: Duration = Duration::from_millis(10);
+
+/// Longest poll interval, also the ceiling for idle backoff
+pub const MAX_POLL_INTERVAL: Duration = Duration::from_millis(200);
+
+/// Empty polls in a row before `PollBackoff` starts widening the interval
+const EMPTY_POLLS_BEFORE_BACKOFF: u32 = 20;
+
+/// Poll interval from the `tail_poll_interval_ms` preference, clamped to 10-200ms
+pub fn poll_interval_from_ms(ms: u64) -> Duration {
+    Duration::from_millis(ms).clamp(MIN_POLL_INTERVAL, MAX_POLL_INTERVAL)
+}
+
+/// Interval between `chat:heartbeat` events while a CLI produces no output
+pub const HEARTBEAT_INTERVAL: Duration = Duration::from_secs(15);
+
 /// Tailer for reading new lines from an NDJSON file.
 ///
 /// Maintains position in the file and returns only new complete lines
@@ -48,9 +65,21 @@ impl NdjsonTailer {
     /// This is used when resuming a session where we need to read
     /// all existing content first.
     pub fn new_from_start(path: &Path) -> Result<Self, String> {
+        Self::new_from_offset(path, 0)
+    }
+
+    /// Create a new tailer, starting `offset` bytes into the file.
+    ///
+    /// This is used when a file already holds output of an earlier attempt
+    /// (e.g. a retried CLI call appending to the same run log) that must not
+    /// be read again.
+    pub fn new_from_offset(path: &Path, offset: u64) -> Result<Self, String> {
         let file = File::open(path).map_err(|e| format!("Failed to open file for tailing: {e}"))?;
 
-        let reader = BufReader::new(file);
+        let mut reader = BufReader::new(file);
+        reader
+            .seek(SeekFrom::Start(offset))
+            .map_err(|e| format!("Failed to seek in file: {e}"))?;
 
         Ok(Self {
             reader,
@@ -101,12 +130,141 @@ impl NdjsonTailer {
     }
 }
 
+/// Paces heartbeat events during silent stretches of a tail loop
+///
+/// A heartbeat is due once output has been silent for a full interval, then
+/// every interval after that. New output restarts the countdown.
+pub struct Heartbeat {
+    interval: Duration,
+    last_beat: Option<Instant>,
+}
+
+impl Heartbeat {
+    pub fn new(interval: Duration) -> Self {
+        Self {
+            interval,
+            last_beat: None,
+        }
+    }
+
+    /// Seconds since the last output, if a heartbeat is due now
+    pub fn due(&mut self, last_output: Instant) -> Option<u64> {
+        self.due_at(last_output, Instant::now())
+    }
+
+    fn due_at(&mut self, last_output: Instant, now: Instant) -> Option<u64> {
+        // Beats from before the latest output don't count
+        let since = self
+            .last_beat
+            .filter(|beat| *beat > last_output)
+            .unwrap_or(last_output);
+        if now.duration_since(since) < self.interval {
+            return None;
+        }
+        self.last_beat = Some(now);
+        Some(now.duration_since(last_output).as_secs())
+    }
+}
+
+/// Adaptive sleep between polls of a tail loop
+///
+/// Polls at the configured interval while output is flowing. After a run of
+/// empty polls (e.g. the model is thinking) the interval doubles up to
+/// `MAX_POLL_INTERVAL`, and it snaps back as soon as new lines arrive.
+pub struct PollBackoff {
+    base: Duration,
+    current: Duration,
+    empty_polls: u32,
+}
+
+impl PollBackoff {
+    pub fn new(base: Duration) -> Self {
+        Self {
+            base,
+            current: base,
+            empty_polls: 0,
+        }
+    }
+
+    /// Record whether the last poll returned lines; returns how long to sleep
+    pub fn next(&mut self, got_output: bool) -> Duration {
+        if got_output {
+            self.empty_polls = 0;
+            self.current = self.base;
+        } else {
+            self.empty_polls = self.empty_polls.saturating_add(1);
+            if self.empty_polls > EMPTY_POLLS_BEFORE_BACKOFF {
+                self.current = (self.current * 2).min(MAX_POLL_INTERVAL.max(self.base));
+            }
+        }
+        self.current
+    }
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
     use std::io::Write;
     use tempfile::NamedTempFile;
 
+    #[test]
+    fn test_heartbeat_paces_silent_stretches() {
+        let secs = Duration::from_secs;
+        let start = Instant::now();
+        let mut heartbeat = Heartbeat::new(secs(15));
+
+        assert_eq!(heartbeat.due_at(start, start + secs(10)), None);
+        assert_eq!(heartbeat.due_at(start, start + secs(15)), Some(15));
+        // Not again until another full interval has passed
+        assert_eq!(heartbeat.due_at(start, start + secs(20)), None);
+        assert_eq!(heartbeat.due_at(start, start + secs(30)), Some(30));
+
+        // Output resumed: the countdown restarts from it
+        let output = start + secs(32);
+        assert_eq!(heartbeat.due_at(output, start + secs(45)), None);
+        assert_eq!(heartbeat.due_at(output, start + secs(47)), Some(15));
+    }
+
+    #[test]
+    fn test_tailer_from_offset_skips_earlier_output() {
+        let mut file = NamedTempFile::new().unwrap();
+        writeln!(file, "{{\"_run_meta\":true}}").unwrap();
+        writeln!(file, "No conversation found with session ID: abc").unwrap();
+        file.flush().unwrap();
+        let offset = file.as_file().metadata().unwrap().len();
+
+        let mut tailer = NdjsonTailer::new_from_offset(file.path(), offset).unwrap();
+        assert!(tailer.poll().unwrap().is_empty());
+
+        writeln!(file, "{{\"type\":\"result\"}}").unwrap();
+        file.flush().unwrap();
+        assert_eq!(tailer.poll().unwrap(), vec![r#"{"type":"result"}"#]);
+    }
+
+    #[test]
+    fn test_poll_interval_from_ms_clamps_to_range() {
+        assert_eq!(poll_interval_from_ms(50), POLL_INTERVAL);
+        assert_eq!(poll_interval_from_ms(20), Duration::from_millis(20));
+        assert_eq!(poll_interval_from_ms(0), MIN_POLL_INTERVAL);
+        assert_eq!(poll_interval_from_ms(5_000), MAX_POLL_INTERVAL);
+    }
+
+    #[test]
+    fn test_poll_backoff_widens_when_idle_and_snaps_back() {
+        let mut backoff = PollBackoff::new(POLL_INTERVAL);
+
+        for _ in 0..EMPTY_POLLS_BEFORE_BACKOFF {
+            assert_eq!(backoff.next(false), POLL_INTERVAL);
+        }
+        assert_eq!(backoff.next(false), Duration::from_millis(100));
+        assert_eq!(backoff.next(false), MAX_POLL_INTERVAL);
+        assert_eq!(backoff.next(false), MAX_POLL_INTERVAL);
+
+        // New output: back to the configured interval, and the count restarts
+        assert_eq!(backoff.next(true), POLL_INTERVAL);
+        assert_eq!(backoff.next(false), POLL_INTERVAL);
+    }
+
     #[test]
     fn test_tailer_new_lines() {
         let mut file = NamedTempFile::new().unwrap();
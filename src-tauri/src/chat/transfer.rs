@@ -0,0 +1,988 @@
+//! Session export/import for backup or machine migration
+//!
+//! Sessions are bundled into a `.tar.gz` mirroring the store layout:
+//! `manifest.json`, `index/{worktree_id}.json` and `data/{session_id}/*`.
+//! Worktree IDs differ between machines, so the manifest also records which
+//! project, branch and path each index came from, and import maps every index
+//! to the matching local worktree.
+//! Export and import walk every session in a batch, which can take a while
+//! for large histories. These helpers emit `session:export-progress` /
+//! `session:import-progress` events so the UI can render a progress bar,
+//! mirroring the CLI install-progress events.
+
+use std::collections::HashMap;
+use std::fs::{self, File};
+use std::io::{Read, Write};
+use std::path::{Component, Path, PathBuf};
+use std::time::{SystemTime, UNIX_EPOCH};
+
+use flate2::read::GzDecoder;
+use flate2::write::GzEncoder;
+use flate2::Compression;
+use serde::{Deserialize, Serialize};
+use tauri::{AppHandle, Emitter, Manager};
+
+use super::storage::{
+    get_base_index_path, get_data_dir, get_index_dir, get_index_path, relink_sessions,
+    replace_session_data, save_metadata, with_base_index_mut, with_index_mut,
+};
+use super::types::{SessionMetadata, WorktreeIndex};
+use crate::projects::types::{ProjectsData, SessionType};
+
+/// Event emitted while exporting sessions
+pub const EXPORT_PROGRESS_EVENT: &str = "session:export-progress";
+
+/// Event emitted while importing sessions
+pub const IMPORT_PROGRESS_EVENT: &str = "session:import-progress";
+
+/// Progress payload for session export/import events
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq, Eq)]
+pub struct TransferProgress {
+    /// Number of sessions processed so far
+    pub processed: usize,
+    /// Total number of sessions in the batch
+    pub total: usize,
+    /// Name of the session that was just processed (None at start/finish)
+    pub current_session: Option<String>,
+}
+
+/// Tracks progress through a batch of sessions and reports every step
+///
+/// The reporter is a closure so the tracker can be driven without an
+/// `AppHandle` (see `app_reporter` for the event-emitting version).
+pub struct ProgressTracker<F: FnMut(&TransferProgress)> {
+    processed: usize,
+    total: usize,
+    report: F,
+}
+
+impl<F: FnMut(&TransferProgress)> ProgressTracker<F> {
+    /// Create a tracker and report the initial `0 / total` state
+    pub fn new(total: usize, report: F) -> Self {
+        let mut tracker = Self {
+            processed: 0,
+            total,
+            report,
+        };
+        tracker.emit(None);
+        tracker
+    }
+
+    /// Mark one session as processed
+    pub fn advance(&mut self, session_name: &str) {
+        self.processed = (self.processed + 1).min(self.total);
+        self.emit(Some(session_name.to_string()));
+    }
+
+    /// Report completion, even if some sessions were skipped
+    pub fn finish(mut self) {
+        self.processed = self.total;
+        self.emit(None);
+    }
+
+    fn emit(&mut self, current_session: Option<String>) {
+        let progress = TransferProgress {
+            processed: self.processed,
+            total: self.total,
+            current_session,
+        };
+        (self.report)(&progress);
+    }
+}
+
+/// Build a reporter that emits progress as a Tauri event
+pub fn app_reporter<'a>(
+    app: &'a AppHandle,
+    event: &'static str,
+) -> impl FnMut(&TransferProgress) + 'a {
+    move |progress| {
+        if let Err(e) = app.emit(event, progress) {
+            log::warn!("Failed to emit {event}: {e}");
+        }
+    }
+}
+
+// ============================================================================
+// Session Archives
+// ============================================================================
+
+/// Archive format version, bumped on incompatible layout changes
+const ARCHIVE_VERSION: u32 = 1;
+
+/// Name of the manifest entry at the archive root
+const MANIFEST_NAME: &str = "manifest.json";
+
+/// Describes the contents of a session archive
+#[derive(Debug, Clone, Serialize, Deserialize)]
+struct ArchiveManifest {
+    version: u32,
+    exported_at: u64,
+    session_ids: Vec<String>,
+    /// Where each exported index came from (missing in older archives)
+    #[serde(default)]
+    indexes: Vec<IndexOrigin>,
+}
+
+/// The project and worktree an exported index belonged to
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
+struct IndexOrigin {
+    /// Index file name in the archive
+    index_file: String,
+    project_name: String,
+    project_path: String,
+    /// A base session's index, open or preserved while the base session is closed
+    is_base: bool,
+    /// Worktree branch (None for preserved base indexes)
+    branch: Option<String>,
+    /// Worktree path (None for preserved base indexes)
+    path: Option<String>,
+}
+
+/// Where an archived index's sessions are imported to
+#[derive(Debug, Clone, PartialEq)]
+enum ImportTarget {
+    /// A worktree's live index
+    Worktree(String),
+    /// A project's preserved base index, while its base session is closed
+    PreservedBase(String),
+}
+
+/// Result of importing a session archive
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq, Eq)]
+pub struct ImportSummary {
+    /// Sessions written into the store
+    pub imported: usize,
+    /// Sessions left alone because they already exist (and overwrite was off)
+    pub skipped: usize,
+    /// Worktree indexes that received sessions
+    pub worktrees: usize,
+    /// Sessions left out because no local worktree matches the one they came from
+    pub unmatched: usize,
+}
+
+/// A validated archive extracted into a staging directory
+struct StagedArchive {
+    /// Indexes with their file names in the archive
+    indexes: Vec<(String, WorktreeIndex)>,
+    sessions: HashMap<String, SessionMetadata>,
+    origins: Vec<IndexOrigin>,
+}
+
+/// Where an archive entry belongs in the store
+#[derive(Debug, PartialEq)]
+enum EntryKind {
+    Manifest,
+    Index,
+    SessionFile { session_id: String },
+}
+
+/// A path component that is safe to use as a file name in the store
+fn is_safe_name(name: &str) -> bool {
+    !name.starts_with('.')
+        && name
+            .chars()
+            .all(|c| c.is_alphanumeric() || matches!(c, '-' | '_' | '.'))
+}
+
+/// Classify an archive entry path, rejecting anything outside the expected layout
+fn classify_entry(path: &Path) -> Result<EntryKind, String> {
+    let parts: Vec<&str> = path
+        .components()
+        .map(|c| match c {
+            Component::Normal(part) => part.to_str().ok_or(()),
+            _ => Err(()),
+        })
+        .collect::<Result<_, _>>()
+        .map_err(|_| format!("Invalid path in archive: {}", path.display()))?;
+
+    match parts.as_slice() {
+        [MANIFEST_NAME] => Ok(EntryKind::Manifest),
+        ["index", file] if file.ends_with(".json") && is_safe_name(file) => Ok(EntryKind::Index),
+        ["data", session_id, file] if is_safe_name(session_id) && is_safe_name(file) => {
+            Ok(EntryKind::SessionFile {
+                session_id: session_id.to_string(),
+            })
+        }
+        _ => Err(format!("Unexpected path in archive: {}", path.display())),
+    }
+}
+
+/// Append a file from disk to the archive under `name`
+fn append_file<W: Write>(
+    builder: &mut tar::Builder<W>,
+    path: &Path,
+    name: &str,
+) -> Result<(), String> {
+    builder
+        .append_path_with_name(path, name)
+        .map_err(|e| format!("Failed to add {name} to archive: {e}"))
+}
+
+/// Write index files and session data directories into a gzipped tarball
+///
+/// `on_session` is called after each session directory is added.
+fn write_archive<W: Write>(
+    writer: W,
+    index_files: &[PathBuf],
+    origins: Vec<IndexOrigin>,
+    session_dirs: &[(String, PathBuf)],
+    mut on_session: impl FnMut(&str),
+) -> Result<(), String> {
+    let mut builder = tar::Builder::new(GzEncoder::new(writer, Compression::default()));
+
+    let manifest = ArchiveManifest {
+        version: ARCHIVE_VERSION,
+        exported_at: SystemTime::now()
+            .duration_since(UNIX_EPOCH)
+            .unwrap_or_default()
+            .as_secs(),
+        session_ids: session_dirs.iter().map(|(id, _)| id.clone()).collect(),
+        indexes: origins,
+    };
+    let manifest_json = serde_json::to_vec_pretty(&manifest)
+        .map_err(|e| format!("Failed to serialize manifest: {e}"))?;
+    let mut header = tar::Header::new_gnu();
+    header.set_size(manifest_json.len() as u64);
+    header.set_mode(0o644);
+    header.set_cksum();
+    builder
+        .append_data(&mut header, MANIFEST_NAME, manifest_json.as_slice())
+        .map_err(|e| format!("Failed to add manifest to archive: {e}"))?;
+
+    for path in index_files {
+        let Some(file_name) = path.file_name().and_then(|n| n.to_str()) else {
+            continue;
+        };
+        append_file(&mut builder, path, &format!("index/{file_name}"))?;
+    }
+
+    for (session_id, dir) in session_dirs {
+        let entries =
+            fs::read_dir(dir).map_err(|e| format!("Failed to read session {session_id}: {e}"))?;
+        for entry in entries.flatten() {
+            let path = entry.path();
+            let Some(file_name) = path.file_name().and_then(|n| n.to_str()) else {
+                continue;
+            };
+            // Skip in-progress temp files and anything that isn't a plain file
+            if !path.is_file() || file_name.ends_with(".tmp") {
+                continue;
+            }
+            append_file(
+                &mut builder,
+                &path,
+                &format!("data/{session_id}/{file_name}"),
+            )?;
+        }
+        on_session(session_id);
+    }
+
+    builder
+        .into_inner()
+        .and_then(|encoder| encoder.finish())
+        .and_then(|mut writer| writer.flush())
+        .map_err(|e| format!("Failed to finalize archive: {e}"))
+}
+
+/// Extract an archive into `staging` and validate its contents
+///
+/// Every entry path is checked before it is written, so nothing escapes the
+/// staging directory. The manifest, indexes and session metadata must parse.
+fn stage_archive<R: Read>(reader: R, staging: &Path) -> Result<StagedArchive, String> {
+    let mut archive = tar::Archive::new(GzDecoder::new(reader));
+    let entries = archive
+        .entries()
+        .map_err(|e| format!("Failed to read archive: {e}"))?;
+
+    for entry in entries {
+        let mut entry = entry.map_err(|e| format!("Failed to read archive entry: {e}"))?;
+        let path = entry
+            .path()
+            .map_err(|e| format!("Invalid path in archive: {e}"))?
+            .into_owned();
+
+        match entry.header().entry_type() {
+            tar::EntryType::Directory => continue,
+            tar::EntryType::Regular => {}
+            other => {
+                return Err(format!(
+                    "Unsupported entry type {other:?} in archive: {}",
+                    path.display()
+                ))
+            }
+        }
+        classify_entry(&path)?;
+
+        let target = staging.join(&path);
+        if let Some(parent) = target.parent() {
+            fs::create_dir_all(parent)
+                .map_err(|e| format!("Failed to create staging directory: {e}"))?;
+        }
+        let mut file =
+            File::create(&target).map_err(|e| format!("Failed to stage archive entry: {e}"))?;
+        std::io::copy(&mut entry, &mut file)
+            .map_err(|e| format!("Failed to stage archive entry: {e}"))?;
+    }
+
+    let manifest: ArchiveManifest = fs::read_to_string(staging.join(MANIFEST_NAME))
+        .map_err(|_| "Archive is missing its manifest".to_string())
+        .and_then(|c| serde_json::from_str(&c).map_err(|e| format!("Invalid manifest: {e}")))?;
+    if manifest.version != ARCHIVE_VERSION {
+        return Err(format!(
+            "Unsupported archive version {} (expected {ARCHIVE_VERSION})",
+            manifest.version
+        ));
+    }
+
+    let mut indexes = Vec::new();
+    if let Ok(entries) = fs::read_dir(staging.join("index")) {
+        for entry in entries.flatten() {
+            let path = entry.path();
+            let index: WorktreeIndex = fs::read_to_string(&path)
+                .map_err(|e| e.to_string())
+                .and_then(|c| serde_json::from_str(&c).map_err(|e| e.to_string()))
+                .map_err(|e| format!("Invalid index {}: {e}", path.display()))?;
+            indexes.push((entry.file_name().to_string_lossy().to_string(), index));
+        }
+    }
+
+    let mut sessions = HashMap::new();
+    if let Ok(entries) = fs::read_dir(staging.join("data")) {
+        for entry in entries.flatten() {
+            let session_id = entry.file_name().to_string_lossy().to_string();
+            let metadata_path = entry.path().join("metadata.json");
+            let metadata: SessionMetadata = fs::read_to_string(&metadata_path)
+                .map_err(|e| e.to_string())
+                .and_then(|c| serde_json::from_str(&c).map_err(|e| e.to_string()))
+                .map_err(|e| format!("Invalid metadata for session {session_id}: {e}"))?;
+            if metadata.id != session_id {
+                return Err(format!(
+                    "Session {session_id} has metadata for a different session ({})",
+                    metadata.id
+                ));
+            }
+            sessions.insert(session_id, metadata);
+        }
+    }
+
+    Ok(StagedArchive {
+        indexes,
+        sessions,
+        origins: manifest.indexes,
+    })
+}
+
+/// Describe where an index file in the store belongs
+///
+/// None for indexes of worktrees (or projects) that no longer exist.
+fn index_origin(
+    app: &AppHandle,
+    projects: &ProjectsData,
+    path: &Path,
+    index: &WorktreeIndex,
+) -> Result<Option<IndexOrigin>, String> {
+    let Some(index_file) = path.file_name().and_then(|n| n.to_str()) else {
+        return Ok(None);
+    };
+
+    if let Some(worktree) = projects.find_worktree(&index.worktree_id) {
+        return Ok(projects
+            .find_project(&worktree.project_id)
+            .map(|project| IndexOrigin {
+                index_file: index_file.to_string(),
+                project_name: project.name.clone(),
+                project_path: project.path.clone(),
+                is_base: worktree.session_type == SessionType::Base,
+                branch: Some(worktree.branch.clone()),
+                path: Some(worktree.path.clone()),
+            }));
+    }
+
+    // Preserved base index (base-{project_id}.json)
+    for project in &projects.projects {
+        if get_base_index_path(app, &project.id)? == path {
+            return Ok(Some(IndexOrigin {
+                index_file: index_file.to_string(),
+                project_name: project.name.clone(),
+                project_path: project.path.clone(),
+                is_base: true,
+                branch: None,
+                path: None,
+            }));
+        }
+    }
+    Ok(None)
+}
+
+/// Find the local home of an archived index
+///
+/// `target_worktree_id` wins when given. Otherwise an index whose worktree
+/// exists locally (restoring a backup on the same machine) keeps it, and one
+/// from another machine is matched through its manifest entry: the project
+/// with the same path or name, then its base session, or its worktree with the
+/// same path or branch. Returns None when nothing matches.
+fn resolve_import_target(
+    index_file: &str,
+    index: &WorktreeIndex,
+    origins: &[IndexOrigin],
+    projects: &ProjectsData,
+    target_worktree_id: Option<&str>,
+) -> Option<ImportTarget> {
+    if let Some(target) = target_worktree_id {
+        return Some(ImportTarget::Worktree(target.to_string()));
+    }
+
+    let origin = origins.iter().find(|o| o.index_file == index_file);
+    let is_preserved_base = origin.is_some_and(|o| o.is_base && o.path.is_none());
+    if !is_preserved_base && projects.find_worktree(&index.worktree_id).is_some() {
+        return Some(ImportTarget::Worktree(index.worktree_id.clone()));
+    }
+    let origin = origin?;
+
+    let live_projects = || projects.projects.iter().filter(|p| !p.is_folder);
+    let project = live_projects()
+        .find(|p| p.path == origin.project_path)
+        .or_else(|| live_projects().find(|p| p.name == origin.project_name))?;
+    let worktrees = projects
+        .worktrees
+        .iter()
+        .filter(|w| w.project_id == project.id);
+
+    if origin.is_base {
+        return Some(
+            worktrees
+                .clone()
+                .find(|w| w.session_type == SessionType::Base)
+                .map(|w| ImportTarget::Worktree(w.id.clone()))
+                .unwrap_or_else(|| ImportTarget::PreservedBase(project.id.clone())),
+        );
+    }
+
+    let worktrees: Vec<_> = worktrees
+        .filter(|w| w.session_type == SessionType::Worktree)
+        .collect();
+    worktrees
+        .iter()
+        .find(|w| Some(&w.path) == origin.path.as_ref())
+        .or_else(|| {
+            worktrees
+                .iter()
+                .find(|w| Some(&w.branch) == origin.branch.as_ref())
+        })
+        .map(|w| ImportTarget::Worktree(w.id.clone()))
+}
+
+/// Get the directory exported archives are written to (creates if not exists)
+fn get_exports_dir(app: &AppHandle) -> Result<PathBuf, String> {
+    let app_data_dir = app
+        .path()
+        .app_data_dir()
+        .map_err(|e| format!("Failed to get app data directory: {e}"))?;
+    let exports_dir = app_data_dir.join("session-exports");
+    fs::create_dir_all(&exports_dir)
+        .map_err(|e| format!("Failed to create exports directory: {e}"))?;
+    Ok(exports_dir)
+}
+
+/// Bundle sessions into a `.tar.gz` archive for backup or migration
+///
+/// Exports the given worktrees' indexes and session data, or every index in
+/// the store when `worktree_ids` is None. Returns the archive path.
+#[tauri::command]
+pub async fn export_sessions_archive(
+    app: AppHandle,
+    worktree_ids: Option<Vec<String>>,
+) -> Result<PathBuf, String> {
+    tauri::async_runtime::spawn_blocking(move || {
+        export_sessions_archive_blocking(&app, worktree_ids)
+    })
+    .await
+    .map_err(|e| format!("Export task failed: {e}"))?
+}
+
+fn export_sessions_archive_blocking(
+    app: &AppHandle,
+    worktree_ids: Option<Vec<String>>,
+) -> Result<PathBuf, String> {
+    let index_files: Vec<PathBuf> = match worktree_ids {
+        Some(ids) => ids
+            .iter()
+            .map(|id| get_index_path(app, id))
+            .collect::<Result<Vec<_>, _>>()?
+            .into_iter()
+            .filter(|path| path.exists())
+            .collect(),
+        None => fs::read_dir(get_index_dir(app)?)
+            .map_err(|e| format!("Failed to read index directory: {e}"))?
+            .flatten()
+            .map(|entry| entry.path())
+            .filter(|path| path.extension().is_some_and(|ext| ext == "json"))
+            .collect(),
+    };
+
+    // Sessions referenced by the exported indexes that have data on disk
+    let data_dir = get_data_dir(app)?;
+    let projects = crate::projects::storage::load_projects_data(app)?;
+    let mut session_dirs = Vec::new();
+    let mut session_names = HashMap::new();
+    let mut origins = Vec::new();
+    for path in &index_files {
+        let index: WorktreeIndex = fs::read_to_string(path)
+            .map_err(|e| e.to_string())
+            .and_then(|c| serde_json::from_str(&c).map_err(|e| e.to_string()))
+            .map_err(|e| format!("Failed to read index {}: {e}", path.display()))?;
+        origins.extend(index_origin(app, &projects, path, &index)?);
+        for entry in index.sessions {
+            let dir = data_dir.join(&entry.id);
+            if dir.join("metadata.json").exists() && !session_names.contains_key(&entry.id) {
+                session_names.insert(entry.id.clone(), entry.name);
+                session_dirs.push((entry.id, dir));
+            }
+        }
+    }
+
+    let timestamp = SystemTime::now()
+        .duration_since(UNIX_EPOCH)
+        .unwrap_or_default()
+        .as_secs();
+    let archive_path = get_exports_dir(app)?.join(format!("jean-sessions-{timestamp}.tar.gz"));
+    let file =
+        File::create(&archive_path).map_err(|e| format!("Failed to create archive file: {e}"))?;
+
+    let mut progress =
+        ProgressTracker::new(session_dirs.len(), app_reporter(app, EXPORT_PROGRESS_EVENT));
+    let result = write_archive(file, &index_files, origins, &session_dirs, |id| {
+        progress.advance(session_names.get(id).map(String::as_str).unwrap_or(id));
+    });
+    progress.finish();
+
+    if let Err(e) = result {
+        let _ = fs::remove_file(&archive_path);
+        return Err(e);
+    }
+
+    log::info!(
+        "Exported {} sessions from {} indexes to {archive_path:?}",
+        session_dirs.len(),
+        index_files.len()
+    );
+    Ok(archive_path)
+}
+
+/// Import a session archive created by `export_sessions_archive`
+///
+/// The archive is extracted into a staging directory and validated before
+/// anything touches the live store. Sessions that already exist are skipped
+/// unless `overwrite` is set. Sessions are merged into the local worktree
+/// matching the one they were exported from (see `resolve_import_target`), or
+/// all into `target_worktree_id` when given.
+#[tauri::command]
+pub async fn import_sessions_archive(
+    app: AppHandle,
+    path: String,
+    overwrite: Option<bool>,
+    target_worktree_id: Option<String>,
+) -> Result<ImportSummary, String> {
+    tauri::async_runtime::spawn_blocking(move || {
+        import_sessions_archive_blocking(
+            &app,
+            Path::new(&path),
+            overwrite.unwrap_or(false),
+            target_worktree_id.as_deref(),
+        )
+    })
+    .await
+    .map_err(|e| format!("Import task failed: {e}"))?
+}
+
+fn import_sessions_archive_blocking(
+    app: &AppHandle,
+    path: &Path,
+    overwrite: bool,
+    target_worktree_id: Option<&str>,
+) -> Result<ImportSummary, String> {
+    let projects = crate::projects::storage::load_projects_data(app)?;
+    if let Some(target) = target_worktree_id {
+        if projects.find_worktree(target).is_none() {
+            return Err(format!("Worktree not found: {target}"));
+        }
+    }
+
+    let file = File::open(path).map_err(|e| format!("Failed to open archive: {e}"))?;
+
+    // Staging lives next to the store so sessions can be moved in with a rename
+    let data_dir = get_data_dir(app)?;
+    let staging = data_dir
+        .parent()
+        .unwrap_or(&data_dir)
+        .join(format!("import-staging-{}", uuid::Uuid::new_v4()));
+    fs::create_dir_all(&staging).map_err(|e| format!("Failed to create staging directory: {e}"))?;
+
+    let result = stage_archive(file, &staging).and_then(|staged| {
+        merge_staged_archive(
+            app,
+            &staging,
+            staged,
+            overwrite,
+            &projects,
+            target_worktree_id,
+        )
+    });
+
+    if let Err(e) = fs::remove_dir_all(&staging) {
+        log::warn!("Failed to remove import staging directory: {e}");
+    }
+    result
+}
+
+/// Move staged sessions into the store and link them into their local indexes
+fn merge_staged_archive(
+    app: &AppHandle,
+    staging: &Path,
+    staged: StagedArchive,
+    overwrite: bool,
+    projects: &ProjectsData,
+    target_worktree_id: Option<&str>,
+) -> Result<ImportSummary, String> {
+    let data_dir = get_data_dir(app)?;
+    let mut summary = ImportSummary {
+        imported: 0,
+        skipped: 0,
+        worktrees: 0,
+        unmatched: 0,
+    };
+
+    let total = staged
+        .indexes
+        .iter()
+        .flat_map(|(_, index)| &index.sessions)
+        .filter(|entry| staged.sessions.contains_key(&entry.id))
+        .count();
+    let mut progress = ProgressTracker::new(total, app_reporter(app, IMPORT_PROGRESS_EVENT));
+
+    for (index_file, index) in &staged.indexes {
+        let Some(destination) = resolve_import_target(
+            index_file,
+            index,
+            &staged.origins,
+            projects,
+            target_worktree_id,
+        ) else {
+            for entry in &index.sessions {
+                if staged.sessions.contains_key(&entry.id) {
+                    log::warn!(
+                        "No local worktree matches {index_file}, skipping session {}",
+                        entry.id
+                    );
+                    summary.unmatched += 1;
+                    progress.advance(&entry.name);
+                }
+            }
+            continue;
+        };
+
+        let mut imported = Vec::new();
+        for entry in &index.sessions {
+            let Some(metadata) = staged.sessions.get(&entry.id) else {
+                continue;
+            };
+
+            if !overwrite && data_dir.join(&entry.id).join("metadata.json").exists() {
+                summary.skipped += 1;
+                progress.advance(&entry.name);
+                continue;
+            }
+
+            // Also replaces a leftover directory without metadata (e.g. an empty
+            // session); the local data is only removed once the import is in place
+            replace_session_data(app, &entry.id, &staging.join("data").join(&entry.id))
+                .map_err(|e| format!("Failed to import session {}: {e}", entry.id))?;
+            imported.push(metadata.clone());
+            progress.advance(&entry.name);
+        }
+
+        if imported.is_empty() {
+            continue;
+        }
+
+        let merge = |live: &mut WorktreeIndex, rebuild: bool| {
+            // Overwritten sessions get a fresh entry from their imported metadata
+            live.sessions
+                .retain(|e| !imported.iter().any(|m| m.id == e.id));
+            relink_sessions(live, &imported, rebuild);
+            Ok::<_, String>(live.worktree_id.clone())
+        };
+        let worktree_id = match &destination {
+            ImportTarget::Worktree(id) => {
+                let rebuild = !get_index_path(app, id)?.exists();
+                with_index_mut(app, id, |live| merge(live, rebuild))?
+            }
+            ImportTarget::PreservedBase(project_id) => {
+                with_base_index_mut(app, project_id, |live| merge(live, false))?
+            }
+        };
+
+        // Point the sessions at their new worktree. Preserved base sessions get
+        // the preserved index's ID, like those preserved locally.
+        for metadata in &mut imported {
+            if metadata.worktree_id != worktree_id {
+                metadata.worktree_id = worktree_id.clone();
+                save_metadata(app, metadata)?;
+            }
+        }
+
+        summary.imported += imported.len();
+        summary.worktrees += 1;
+    }
+    progress.finish();
+
+    log::info!(
+        "Imported {} sessions into {} worktrees ({} skipped, {} unmatched)",
+        summary.imported,
+        summary.worktrees,
+        summary.skipped,
+        summary.unmatched
+    );
+    Ok(summary)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_progress_advances_to_completion() {
+        let sessions = ["Session 1", "Session 2", "Session 3"];
+        let mut events = Vec::new();
+
+        let mut tracker = ProgressTracker::new(sessions.len(), |p| events.push(p.clone()));
+        for name in sessions {
+            tracker.advance(name);
+        }
+        tracker.finish();
+
+        let processed: Vec<usize> = events.iter().map(|e| e.processed).collect();
+        assert_eq!(processed, vec![0, 1, 2, 3, 3]);
+        assert!(events.iter().all(|e| e.total == 3));
+        assert_eq!(events[2].current_session.as_deref(), Some("Session 2"));
+
+        let last = events.last().unwrap();
+        assert_eq!(last.processed, last.total);
+        assert_eq!(last.current_session, None);
+    }
+
+    #[test]
+    fn test_progress_serializes_snake_case() {
+        let progress = TransferProgress {
+            processed: 1,
+            total: 2,
+            current_session: Some("Session 1".to_string()),
+        };
+        let json = serde_json::to_value(&progress).unwrap();
+        assert_eq!(json["current_session"], "Session 1");
+        assert_eq!(json["processed"], 1);
+    }
+
+    fn write_store(root: &Path) -> (Vec<PathBuf>, Vec<(String, PathBuf)>) {
+        let mut index = WorktreeIndex::new("wt-1".to_string());
+        index.sessions.clear();
+        let mut session_dirs = Vec::new();
+        for (n, id) in ["sess-a", "sess-b"].iter().enumerate() {
+            let metadata = SessionMetadata::new(
+                id.to_string(),
+                "wt-1".to_string(),
+                format!("Session {}", n + 1),
+                n as u32,
+            );
+            index.sessions.push(metadata.to_index_entry());
+
+            let dir = root.join("data").join(id);
+            fs::create_dir_all(&dir).unwrap();
+            fs::write(
+                dir.join("metadata.json"),
+                serde_json::to_string(&metadata).unwrap(),
+            )
+            .unwrap();
+            fs::write(dir.join("run-1.jsonl"), "{\"type\":\"result\"}\n").unwrap();
+            fs::write(dir.join("metadata.tmp"), "partial").unwrap();
+            session_dirs.push((id.to_string(), dir));
+        }
+
+        let index_path = root.join("wt-1.json");
+        fs::write(&index_path, serde_json::to_string(&index).unwrap()).unwrap();
+        (vec![index_path], session_dirs)
+    }
+
+    #[test]
+    fn test_archive_round_trip() {
+        let store = tempfile::tempdir().unwrap();
+        let (index_files, session_dirs) = write_store(store.path());
+
+        let mut archive = Vec::new();
+        let mut exported = Vec::new();
+        let origin = IndexOrigin {
+            index_file: "wt-1.json".to_string(),
+            project_name: "app".to_string(),
+            project_path: "/src/app".to_string(),
+            is_base: false,
+            branch: Some("feature".to_string()),
+            path: Some("/jean/app/feature".to_string()),
+        };
+        write_archive(
+            &mut archive,
+            &index_files,
+            vec![origin.clone()],
+            &session_dirs,
+            |id| exported.push(id.to_string()),
+        )
+        .unwrap();
+        assert_eq!(exported, vec!["sess-a", "sess-b"]);
+
+        let staging = tempfile::tempdir().unwrap();
+        let staged = stage_archive(archive.as_slice(), staging.path()).unwrap();
+        assert_eq!(staged.indexes.len(), 1);
+        assert_eq!(staged.indexes[0].0, "wt-1.json");
+        assert_eq!(staged.indexes[0].1.worktree_id, "wt-1");
+        assert_eq!(staged.origins, vec![origin]);
+        assert_eq!(staged.sessions.len(), 2);
+        assert_eq!(staged.sessions["sess-b"].name, "Session 2");
+
+        let run_log = staging.path().join("data/sess-a/run-1.jsonl");
+        assert_eq!(
+            fs::read_to_string(run_log).unwrap(),
+            "{\"type\":\"result\"}\n"
+        );
+        // Temp files are not exported
+        assert!(!staging.path().join("data/sess-a/metadata.tmp").exists());
+    }
+
+    /// A project at `/local/app` with a base session and a `feature` worktree
+    fn local_projects(base_open: bool) -> ProjectsData {
+        let mut worktrees = vec![serde_json::json!({
+            "id": "local-wt", "project_id": "local-proj", "name": "feature",
+            "path": "/local/jean/app/feature", "branch": "feature", "created_at": 0,
+        })];
+        if base_open {
+            worktrees.push(serde_json::json!({
+                "id": "local-base", "project_id": "local-proj", "name": "main",
+                "path": "/local/app", "branch": "main", "created_at": 0,
+                "session_type": "base",
+            }));
+        }
+        serde_json::from_value(serde_json::json!({
+            "projects": [{
+                "id": "local-proj", "name": "app", "path": "/local/app",
+                "default_branch": "main", "added_at": 0,
+            }],
+            "worktrees": worktrees,
+        }))
+        .unwrap()
+    }
+
+    fn origin(index_file: &str, is_base: bool, branch: Option<&str>) -> IndexOrigin {
+        IndexOrigin {
+            index_file: index_file.to_string(),
+            project_name: "app".to_string(),
+            project_path: "/remote/app".to_string(),
+            is_base,
+            branch: branch.map(str::to_string),
+            path: branch.map(|b| format!("/remote/jean/app/{b}")),
+        }
+    }
+
+    #[test]
+    fn test_resolve_import_target_maps_to_local_worktrees() {
+        let projects = local_projects(false);
+        let origins = vec![
+            origin("remote-wt.json", false, Some("feature")),
+            origin("remote-other.json", false, Some("other")),
+            origin("base-remote-proj.json", true, None),
+        ];
+        let resolve = |file: &str, worktree_id: &str, target: Option<&str>| {
+            let index = WorktreeIndex::new(worktree_id.to_string());
+            resolve_import_target(file, &index, &origins, &projects, target)
+        };
+
+        // Same project by name, same branch
+        assert_eq!(
+            resolve("remote-wt.json", "remote-wt", None),
+            Some(ImportTarget::Worktree("local-wt".to_string()))
+        );
+        // No local worktree on that branch
+        assert_eq!(resolve("remote-other.json", "remote-other", None), None);
+        // Preserved base index goes to the local project's closed base session
+        assert_eq!(
+            resolve("base-remote-proj.json", "remote-base", None),
+            Some(ImportTarget::PreservedBase("local-proj".to_string()))
+        );
+        // ...or its open one
+        let open_base = local_projects(true);
+        let index = WorktreeIndex::new("remote-base".to_string());
+        assert_eq!(
+            resolve_import_target("base-remote-proj.json", &index, &origins, &open_base, None),
+            Some(ImportTarget::Worktree("local-base".to_string()))
+        );
+
+        // Restoring on the same machine keeps the worktree
+        assert_eq!(
+            resolve("local-wt.json", "local-wt", None),
+            Some(ImportTarget::Worktree("local-wt".to_string()))
+        );
+        // Unknown worktree from an archive without origins
+        assert_eq!(resolve("gone.json", "gone", None), None);
+        // An explicit target wins
+        assert_eq!(
+            resolve("remote-other.json", "remote-other", Some("local-wt")),
+            Some(ImportTarget::Worktree("local-wt".to_string()))
+        );
+    }
+
+    #[test]
+    fn test_classify_entry_rejects_paths_outside_layout() {
+        assert_eq!(
+            classify_entry(Path::new("manifest.json")),
+            Ok(EntryKind::Manifest)
+        );
+        assert_eq!(
+            classify_entry(Path::new("index/wt-1.json")),
+            Ok(EntryKind::Index)
+        );
+        assert_eq!(
+            classify_entry(Path::new("data/sess-a/run-1.jsonl")),
+            Ok(EntryKind::SessionFile {
+                session_id: "sess-a".to_string()
+            })
+        );
+
+        assert!(classify_entry(Path::new("../evil.json")).is_err());
+        assert!(classify_entry(Path::new("/etc/passwd")).is_err());
+        assert!(classify_entry(Path::new("data/../../evil")).is_err());
+        assert!(classify_entry(Path::new("data/sess-a/nested/file")).is_err());
+        assert!(classify_entry(Path::new("index/notes.txt")).is_err());
+    }
+
+    #[test]
+    fn test_stage_archive_rejects_traversal_and_missing_manifest() {
+        // Hand-craft a header, since tar::Builder refuses ".." in paths
+        let mut builder = tar::Builder::new(GzEncoder::new(Vec::new(), Compression::default()));
+        let mut header = tar::Header::new_old();
+        header.as_old_mut().name[..13].copy_from_slice(b"../escape.txt");
+        header.set_size(4);
+        header.set_entry_type(tar::EntryType::Regular);
+        header.set_cksum();
+        builder.append(&header, "evil".as_bytes()).unwrap();
+        let archive = builder.into_inner().unwrap().finish().unwrap();
+
+        let staging = tempfile::tempdir().unwrap();
+        assert!(stage_archive(archive.as_slice(), staging.path()).is_err());
+        assert!(!staging.path().parent().unwrap().join("escape.txt").exists());
+
+        // A well-formed archive without a manifest is rejected too
+        let builder = tar::Builder::new(GzEncoder::new(Vec::new(), Compression::default()));
+        let archive = builder.into_inner().unwrap().finish().unwrap();
+        let err = stage_archive(archive.as_slice(), staging.path())
+            .err()
+            .unwrap();
+        assert!(err.contains("manifest"));
+    }
+}
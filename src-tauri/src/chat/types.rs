@@ -1,6 +1,8 @@
 use serde::{Deserialize, Serialize};
 use std::collections::HashMap;
 
+use crate::ai_cli::types::AiCliProvider;
+
 // ============================================================================
 // Compaction Types
 // ============================================================================
@@ -85,6 +87,112 @@ impl ThinkingLevel {
     }
 }
 
+/// Provider-independent tool kind
+///
+/// Each CLI names its tools differently (Kimi `WriteFile`, Gemini `write_file`,
+/// Codex `file_change` items). Event processors normalize raw names through
+/// `ToolKind::normalize` so the frontend only has to know the canonical names,
+/// which match Claude's tool names. Unknown tools keep their raw name.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum ToolKind {
+    Read,
+    Write,
+    Edit,
+    Bash,
+    Glob,
+    Grep,
+    WebFetch,
+    WebSearch,
+    Task,
+    Other(String),
+}
+
+impl ToolKind {
+    /// Map a provider's raw tool name to its canonical kind
+    ///
+    /// For Codex, `raw` is the item type (`command_execution`) or the
+    /// `change_type` of a `file_change` item.
+    pub fn normalize(provider: AiCliProvider, raw: &str) -> ToolKind {
+        let kind = match provider {
+            AiCliProvider::Claude => None,
+            AiCliProvider::Kimi => match raw {
+                "WriteFile" | "CreateFile" => Some(ToolKind::Write),
+                "ReadFile" => Some(ToolKind::Read),
+                "EditFile" | "PatchFile" => Some(ToolKind::Edit),
+                "RunCommand" | "Shell" => Some(ToolKind::Bash),
+                "ListDirectory" | "ListDir" | "DeleteFile" => Some(ToolKind::Bash),
+                "SearchFiles" | "GlobTool" => Some(ToolKind::Glob),
+                "GrepTool" | "SearchContent" => Some(ToolKind::Grep),
+                _ => None,
+            },
+            AiCliProvider::Gemini => match raw {
+                "read_file" | "read_many_files" => Some(ToolKind::Read),
+                "write_file" => Some(ToolKind::Write),
+                "replace" => Some(ToolKind::Edit),
+                "run_shell_command" => Some(ToolKind::Bash),
+                "glob" => Some(ToolKind::Glob),
+                "search_file_content" => Some(ToolKind::Grep),
+                "web_fetch" => Some(ToolKind::WebFetch),
+                "google_web_search" => Some(ToolKind::WebSearch),
+                _ => None,
+            },
+            AiCliProvider::Codex => match raw {
+                "command_execution" => Some(ToolKind::Bash),
+                "create" => Some(ToolKind::Write),
+                "delete" => Some(ToolKind::Bash),
+                "edit" | "update" | "rename" => Some(ToolKind::Edit),
+                _ => None,
+            },
+        };
+
+        kind.unwrap_or_else(|| ToolKind::from_canonical(raw))
+    }
+
+    /// Parse a canonical (Claude-style) tool name
+    fn from_canonical(name: &str) -> ToolKind {
+        match name {
+            "Read" => ToolKind::Read,
+            "Write" => ToolKind::Write,
+            "Edit" => ToolKind::Edit,
+            "Bash" => ToolKind::Bash,
+            "Glob" => ToolKind::Glob,
+            "Grep" => ToolKind::Grep,
+            "WebFetch" => ToolKind::WebFetch,
+            "WebSearch" => ToolKind::WebSearch,
+            "Task" => ToolKind::Task,
+            other => ToolKind::Other(other.to_string()),
+        }
+    }
+
+    /// Canonical tool name sent to the frontend
+    pub fn as_str(&self) -> &str {
+        match self {
+            ToolKind::Read => "Read",
+            ToolKind::Write => "Write",
+            ToolKind::Edit => "Edit",
+            ToolKind::Bash => "Bash",
+            ToolKind::Glob => "Glob",
+            ToolKind::Grep => "Grep",
+            ToolKind::WebFetch => "WebFetch",
+            ToolKind::WebSearch => "WebSearch",
+            ToolKind::Task => "Task",
+            ToolKind::Other(name) => name,
+        }
+    }
+}
+
+impl std::fmt::Display for ToolKind {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.write_str(self.as_str())
+    }
+}
+
+impl Serialize for ToolKind {
+    fn serialize<S: serde::Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        serializer.serialize_str(self.as_str())
+    }
+}
+
 /// A tool call made by Claude during a response
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct ToolCall {
@@ -134,6 +242,15 @@ pub enum ContentBlock {
     Thinking { thinking: String },
 }
 
+/// Output format for run transcript export
+#[derive(Debug, Clone, Copy, Serialize, Deserialize, PartialEq)]
+#[serde(rename_all = "lowercase")]
+pub enum TranscriptFormat {
+    Markdown,
+    Text,
+    Json,
+}
+
 /// A single chat message
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct ChatMessage {
@@ -245,6 +362,19 @@ pub struct ReadTextResponse {
     pub size: usize,
 }
 
+/// A local file attached to a session as additional context
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct FileAttachment {
+    /// Path the user attached the file from
+    pub path: String,
+    /// Copy of the file in the session's data directory
+    pub stored_path: String,
+    /// Size in bytes
+    pub size: u64,
+    /// Unix timestamp when the file was attached
+    pub attached_at: u64,
+}
+
 // ============================================================================
 // TodoWrite Types
 // ============================================================================
@@ -306,6 +436,15 @@ pub struct DelegatedTask {
     pub output: Option<String>,
 }
 
+/// Event emitted when a run finished and the worktree has changes to review
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq, Eq)]
+pub struct ReviewReadyEvent {
+    pub session_id: String,
+    pub worktree_id: String,
+    /// Number of changed files reported by `git status --porcelain`
+    pub changed_files: u32,
+}
+
 /// Event emitted when a delegated task starts execution
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct DelegationTaskStartedEvent {
@@ -452,6 +591,9 @@ pub struct Session {
     /// Selected thinking level for this session
     #[serde(default)]
     pub selected_thinking_level: Option<ThinkingLevel>,
+    /// MCP config file passed to Claude/Codex for this session
+    #[serde(default)]
+    pub mcp_config_path: Option<String>,
     /// Whether session naming has been attempted for this session
     /// Prevents re-triggering on app restart
     #[serde(default)]
@@ -507,6 +649,7 @@ impl Session {
             selected_provider: None,
             selected_model: None,
             selected_thinking_level: None,
+            mcp_config_path: None,
             session_naming_completed: false,
             archived_at: None,
             // Session-specific UI state
@@ -563,6 +706,12 @@ pub struct WorktreeIndex {
     /// Prevents re-triggering on app restart
     #[serde(default)]
     pub branch_naming_completed: bool,
+    /// Model new sessions in this worktree start with
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub default_model: Option<String>,
+    /// Thinking level new sessions in this worktree start with
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub default_thinking_level: Option<ThinkingLevel>,
 }
 
 impl Default for WorktreeIndex {
@@ -580,6 +729,8 @@ impl Default for WorktreeIndex {
             }],
             version: 1,
             branch_naming_completed: false,
+            default_model: None,
+            default_thinking_level: None,
         }
     }
 }
@@ -600,6 +751,8 @@ impl WorktreeIndex {
             }],
             version: 1,
             branch_naming_completed: false,
+            default_model: None,
+            default_thinking_level: None,
         }
     }
 
@@ -641,6 +794,7 @@ impl SessionMetadata {
             selected_provider: self.selected_provider.clone(),
             selected_model: self.selected_model.clone(),
             selected_thinking_level: self.selected_thinking_level.clone(),
+            mcp_config_path: self.mcp_config_path.clone(),
             session_naming_completed: self.session_naming_completed,
             archived_at: self.archived_at,
             answered_questions: self.answered_questions.clone(),
@@ -662,6 +816,7 @@ impl SessionMetadata {
         self.selected_provider = session.selected_provider.clone();
         self.selected_model = session.selected_model.clone();
         self.selected_thinking_level = session.selected_thinking_level.clone();
+        self.mcp_config_path = session.mcp_config_path.clone();
         self.session_naming_completed = session.session_naming_completed;
         self.archived_at = session.archived_at;
         self.answered_questions = session.answered_questions.clone();
@@ -690,9 +845,12 @@ pub struct WorktreeSessions {
     /// ID of the active/displayed session tab
     #[serde(default)]
     pub active_session_id: Option<String>,
-    /// Default model for new sessions in this worktree (unused, kept for compatibility)
+    /// Default model for new sessions in this worktree
     #[serde(default)]
     pub default_model: Option<String>,
+    /// Default thinking level for new sessions in this worktree
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub default_thinking_level: Option<ThinkingLevel>,
     /// Storage format version for migrations
     #[serde(default = "default_version")]
     pub version: u32,
@@ -710,6 +868,7 @@ impl Default for WorktreeSessions {
             sessions: vec![session],
             active_session_id: Some(session_id),
             default_model: None,
+            default_thinking_level: None,
             version: 1,
             branch_naming_completed: false,
         }
@@ -786,6 +945,42 @@ pub struct SaveContextResponse {
     pub size: u64,
 }
 
+/// A saved context matching a full-text search
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct ContextSearchHit {
+    /// Filename (stable identifier, also the key for custom names)
+    pub filename: String,
+    /// Full path to the saved context file
+    pub path: String,
+    /// Optional custom display name (from metadata file)
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub name: Option<String>,
+    /// Number of matches in the file
+    pub match_count: usize,
+    /// Excerpt around the first match
+    pub snippet: String,
+    /// UTF-16 ranges `[start, end)` of matches within `snippet`
+    pub highlights: Vec<(usize, usize)>,
+}
+
+/// A session whose transcript matches a full-text search
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct TranscriptHit {
+    pub session_id: String,
+    pub worktree_id: String,
+    pub session_name: String,
+    /// Most recent run containing a match (the snippet's source)
+    pub run_id: String,
+    /// Unix timestamp when that run started
+    pub timestamp: u64,
+    /// Number of matches across the session's runs
+    pub match_count: usize,
+    /// Excerpt around the first match in `run_id`
+    pub snippet: String,
+    /// UTF-16 ranges `[start, end)` of matches within `snippet`
+    pub highlights: Vec<(usize, usize)>,
+}
+
 // ============================================================================
 // All Sessions Types (for loading sessions across all worktrees)
 // ============================================================================
@@ -836,6 +1031,10 @@ pub struct RunEntry {
     pub user_message_id: String,
     /// Content of the user message
     pub user_message: String,
+    /// AI provider that produced this run's output (None for runs logged
+    /// before the provider was recorded)
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub provider: Option<AiCliProvider>,
     /// Model used for this run
     #[serde(default, skip_serializing_if = "Option::is_none")]
     pub model: Option<String>,
@@ -870,6 +1069,15 @@ pub struct RunEntry {
     /// Token usage for this run (captured from Claude CLI result)
     #[serde(default, skip_serializing_if = "Option::is_none")]
     pub usage: Option<UsageData>,
+    /// HEAD commit of the worktree when the run started
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub start_head: Option<String>,
+    /// Git tree snapshot of the working directory when the run started
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub start_tree: Option<String>,
+    /// Git tree snapshot of the working directory when the run ended
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub end_tree: Option<String>,
 }
 
 /// Session metadata - single source of truth for session data and run history
@@ -900,6 +1108,9 @@ pub struct SessionMetadata {
     /// Selected thinking level for this session
     #[serde(default, skip_serializing_if = "Option::is_none")]
     pub selected_thinking_level: Option<ThinkingLevel>,
+    /// MCP config file passed to Claude/Codex for this session
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub mcp_config_path: Option<String>,
     /// Whether session naming has been attempted
     #[serde(default)]
     pub session_naming_completed: bool,
@@ -932,6 +1143,9 @@ pub struct SessionMetadata {
     /// Message IDs whose plans have been approved
     #[serde(default)]
     pub approved_plan_message_ids: Vec<String>,
+    /// Files attached since the last message, included in the next prompt
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub attached_files: Vec<FileAttachment>,
 
     /// Run history - each entry corresponds to one Claude CLI execution
     #[serde(default)]
@@ -988,6 +1202,19 @@ pub struct SessionDebugInfo {
     pub total_usage: UsageData,
 }
 
+// ============================================================================
+// Session Control Types
+// ============================================================================
+
+/// Result of stopping every running session
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+pub struct StopReport {
+    /// Number of sessions whose process was stopped
+    pub stopped: u32,
+    /// Session IDs whose process was still alive after being force-killed
+    pub refused: Vec<String>,
+}
+
 impl SessionMetadata {
     /// Create a new metadata for a session
     pub fn new(session_id: String, worktree_id: String, session_name: String, order: u32) -> Self {
@@ -1004,6 +1231,7 @@ impl SessionMetadata {
             selected_provider: None,
             selected_model: None,
             selected_thinking_level: None,
+            mcp_config_path: None,
             session_naming_completed: false,
             archived_at: None,
             answered_questions: vec![],
@@ -1014,6 +1242,7 @@ impl SessionMetadata {
             is_reviewing: false,
             waiting_for_input: false,
             approved_plan_message_ids: vec![],
+            attached_files: vec![],
             runs: vec![],
             version: 1,
         }
@@ -1072,6 +1301,72 @@ impl SessionMetadata {
 mod tests {
     use super::*;
 
+    // ========================================================================
+    // ToolKind Tests
+    // ========================================================================
+
+    #[test]
+    fn test_tool_kind_kimi_names() {
+        let kimi = |raw| ToolKind::normalize(AiCliProvider::Kimi, raw);
+        assert_eq!(kimi("WriteFile"), ToolKind::Write);
+        assert_eq!(kimi("CreateFile"), ToolKind::Write);
+        assert_eq!(kimi("ReadFile"), ToolKind::Read);
+        assert_eq!(kimi("PatchFile"), ToolKind::Edit);
+        assert_eq!(kimi("Shell"), ToolKind::Bash);
+        assert_eq!(kimi("Bash"), ToolKind::Bash);
+        assert_eq!(kimi("SearchFiles"), ToolKind::Glob);
+        assert_eq!(kimi("GrepTool"), ToolKind::Grep);
+        assert_eq!(
+            kimi("SetTodoList"),
+            ToolKind::Other("SetTodoList".to_string())
+        );
+    }
+
+    #[test]
+    fn test_tool_kind_gemini_names() {
+        let gemini = |raw| ToolKind::normalize(AiCliProvider::Gemini, raw);
+        assert_eq!(gemini("read_file"), ToolKind::Read);
+        assert_eq!(gemini("write_file"), ToolKind::Write);
+        assert_eq!(gemini("replace"), ToolKind::Edit);
+        assert_eq!(gemini("run_shell_command"), ToolKind::Bash);
+        assert_eq!(gemini("glob"), ToolKind::Glob);
+        assert_eq!(gemini("search_file_content"), ToolKind::Grep);
+        assert_eq!(gemini("web_fetch"), ToolKind::WebFetch);
+        assert_eq!(gemini("google_web_search"), ToolKind::WebSearch);
+    }
+
+    #[test]
+    fn test_tool_kind_codex_and_claude_names() {
+        let codex = |raw| ToolKind::normalize(AiCliProvider::Codex, raw);
+        assert_eq!(codex("command_execution"), ToolKind::Bash);
+        assert_eq!(codex("create"), ToolKind::Write);
+        assert_eq!(codex("edit"), ToolKind::Edit);
+        assert_eq!(codex("delete"), ToolKind::Bash);
+        assert_eq!(codex("rename"), ToolKind::Edit);
+
+        // Claude names are already canonical
+        assert_eq!(
+            ToolKind::normalize(AiCliProvider::Claude, "Task"),
+            ToolKind::Task
+        );
+        assert_eq!(
+            ToolKind::normalize(AiCliProvider::Claude, "TodoWrite").as_str(),
+            "TodoWrite"
+        );
+    }
+
+    #[test]
+    fn test_tool_kind_serializes_to_canonical_name() {
+        assert_eq!(
+            serde_json::to_string(&ToolKind::WebFetch).unwrap(),
+            "\"WebFetch\""
+        );
+        assert_eq!(
+            serde_json::to_string(&ToolKind::Other("mcp__linear".to_string())).unwrap(),
+            "\"mcp__linear\""
+        );
+    }
+
     // ========================================================================
     // ThinkingLevel tests
     // ========================================================================
@@ -1359,6 +1654,7 @@ mod tests {
         );
 
         metadata.runs.push(RunEntry {
+            provider: None,
             run_id: "run-1".to_string(),
             user_message_id: "msg-1".to_string(),
             user_message: "Hello".to_string(),
@@ -1374,6 +1670,9 @@ mod tests {
             claude_session_id: None,
             pid: Some(12345),
             usage: None,
+            start_head: None,
+            start_tree: None,
+            end_tree: None,
         });
 
         assert!(metadata.find_run("run-1").is_some());
@@ -1394,6 +1693,7 @@ mod tests {
 
         // Add run without claude_session_id
         metadata.runs.push(RunEntry {
+            provider: None,
             run_id: "run-1".to_string(),
             user_message_id: "msg-1".to_string(),
             user_message: "First".to_string(),
@@ -1409,12 +1709,16 @@ mod tests {
             claude_session_id: None,
             pid: None,
             usage: None,
+            start_head: None,
+            start_tree: None,
+            end_tree: None,
         });
 
         assert!(metadata.latest_claude_session_id().is_none());
 
         // Add run with claude_session_id
         metadata.runs.push(RunEntry {
+            provider: None,
             run_id: "run-2".to_string(),
             user_message_id: "msg-2".to_string(),
             user_message: "Second".to_string(),
@@ -1430,8 +1734,26 @@ mod tests {
             claude_session_id: Some("claude-sess-abc".to_string()),
             pid: None,
             usage: None,
+            start_head: None,
+            start_tree: None,
+            end_tree: None,
         });
 
         assert_eq!(metadata.latest_claude_session_id(), Some("claude-sess-abc"));
     }
+
+    // ========================================================================
+    // Worktree Default Model Tests
+    // ========================================================================
+
+    #[test]
+    fn test_worktree_index_without_defaults_deserializes() {
+        let index: WorktreeIndex =
+            serde_json::from_str(r#"{"worktree_id": "wt-1", "sessions": []}"#).unwrap();
+        assert_eq!(index.default_model, None);
+        assert_eq!(index.default_thinking_level, None);
+
+        let json = serde_json::to_value(&index).unwrap();
+        assert!(json.get("default_model").is_none());
+    }
 }
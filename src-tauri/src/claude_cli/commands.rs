@@ -6,27 +6,7 @@ use std::io::Write;
 use tauri::{AppHandle, Emitter};
 
 use super::config::{ensure_cli_dir, get_cli_binary_path};
-
-/// Extract semver version number from a version string
-/// Handles formats like: "1.0.28", "v1.0.28", "Claude CLI 1.0.28"
-fn extract_version_number(version_str: &str) -> String {
-    // Try to find a semver-like pattern (digits.digits.digits)
-    for word in version_str.split_whitespace() {
-        let trimmed = word.trim_start_matches('v');
-        // Check if it looks like a version number (starts with digit, contains dots)
-        if trimmed
-            .chars()
-            .next()
-            .map(|c| c.is_ascii_digit())
-            .unwrap_or(false)
-            && trimmed.contains('.')
-        {
-            return trimmed.to_string();
-        }
-    }
-    // Fallback: return original string
-    version_str.to_string()
-}
+use crate::ai_cli::updates::extract_version;
 
 /// Base URL for Claude CLI binary distribution
 const CLAUDE_DIST_BUCKET: &str =
@@ -92,9 +72,9 @@ pub async fn check_claude_cli_installed(app: AppHandle) -> Result<ClaudeCliStatu
                 log::trace!("Claude CLI raw version output: {}", version_str);
                 // claude --version returns just the version number like "1.0.28"
                 // but handle any prefix like "v1.0.28" or "Claude CLI 1.0.28"
-                let version = extract_version_number(&version_str);
-                log::trace!("Claude CLI parsed version: {}", version);
-                Some(version)
+                let version = extract_version(&version_str).map(str::to_string);
+                log::trace!("Claude CLI parsed version: {:?}", version);
+                version
             } else {
                 log::warn!("Failed to get Claude CLI version");
                 None
@@ -195,7 +175,7 @@ pub async fn get_available_cli_versions() -> Result<Vec<ReleaseInfo>, String> {
 }
 
 /// Fetch the latest version string from the distribution bucket
-async fn fetch_latest_version() -> Result<String, String> {
+pub async fn fetch_latest_version() -> Result<String, String> {
     let url = format!("{CLAUDE_DIST_BUCKET}/latest");
     log::trace!("Fetching latest version from {url}");
 
@@ -298,6 +278,7 @@ fn verify_checksum(data: &[u8], expected: &str) -> Result<(), String> {
 #[tauri::command]
 pub async fn install_claude_cli(app: AppHandle, version: Option<String>) -> Result<(), String> {
     log::trace!("Installing Claude CLI, version: {:?}", version);
+    let _install = crate::ai_cli::InstallLock::acquire("Claude CLI")?;
 
     // Check if any Claude processes are running - cannot replace binary while in use
     let running_sessions = crate::chat::registry::get_running_sessions();
@@ -495,3 +476,11 @@ fn emit_progress(app: &AppHandle, stage: &str, message: &str, percent: u8) {
         log::warn!("Failed to emit install progress: {}", e);
     }
 }
+
+/// List installed WSL distributions (Windows only; empty elsewhere)
+///
+/// Used to pick the distribution Claude CLI runs in (`wsl_distro` preference).
+#[tauri::command]
+pub async fn list_wsl_distributions() -> Result<Vec<String>, String> {
+    crate::platform::list_wsl_distributions()
+}
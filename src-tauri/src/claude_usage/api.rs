@@ -1,8 +1,10 @@
-use reqwest::header::{HeaderMap, HeaderValue, AUTHORIZATION, USER_AGENT};
+use chrono::{DateTime, Utc};
+use reqwest::header::{HeaderMap, HeaderValue, AUTHORIZATION, RETRY_AFTER, USER_AGENT};
 use std::sync::Mutex;
+use std::time::Duration;
 
-use super::credentials::get_oauth_token;
-use super::types::{CachedUsageLimits, UsageLimits, UsageLimitsApiResponse};
+use super::credentials::{auth_status, get_oauth_credentials, now_ms};
+use super::types::{CachedUsageLimits, UsageLimits, UsageLimitsApiResponse, UsageLimitsError};
 
 /// API endpoint for usage limits
 const USAGE_API_URL: &str = "https://api.anthropic.com/api/oauth/usage";
@@ -14,6 +16,15 @@ const ANTHROPIC_BETA_VALUE: &str = "oauth-2025-04-20";
 /// User agent to match Claude Code
 const CLAUDE_CODE_USER_AGENT: &str = "claude-code/2.0.31";
 
+/// Total attempts for 429 and 5xx responses
+const MAX_ATTEMPTS: u32 = 3;
+
+/// First retry delay when the response has no Retry-After, doubled per attempt
+const BASE_RETRY_DELAY: Duration = Duration::from_millis(500);
+
+/// Longer Retry-After waits are reported as rate limited rather than waited out
+const MAX_RETRY_DELAY: Duration = Duration::from_secs(10);
+
 /// Global cache for usage limits (1 minute TTL)
 static USAGE_LIMITS_CACHE: Mutex<Option<CachedUsageLimits>> = Mutex::new(None);
 
@@ -21,7 +32,7 @@ static USAGE_LIMITS_CACHE: Mutex<Option<CachedUsageLimits>> = Mutex::new(None);
 ///
 /// Uses a 60-second cache to avoid excessive API calls.
 /// Returns cached data if available and valid.
-pub async fn fetch_usage_limits() -> Result<UsageLimits, String> {
+pub async fn fetch_usage_limits() -> Result<UsageLimits, UsageLimitsError> {
     // Check cache first
     {
         let cache = USAGE_LIMITS_CACHE.lock().map_err(|e| format!("Cache lock error: {e}"))?;
@@ -45,8 +56,12 @@ pub async fn fetch_usage_limits() -> Result<UsageLimits, String> {
 }
 
 /// Fetch usage limits without caching
-async fn fetch_usage_limits_uncached() -> Result<UsageLimits, String> {
-    let token = get_oauth_token().await?;
+pub async fn fetch_usage_limits_uncached() -> Result<UsageLimits, UsageLimitsError> {
+    let creds = get_oauth_credentials().await?;
+    if !auth_status(&creds, now_ms()).valid {
+        return Err(UsageLimitsError::CredentialsExpired);
+    }
+    let token = creds.access_token;
 
     let mut headers = HeaderMap::new();
     headers.insert(
@@ -61,21 +76,44 @@ async fn fetch_usage_limits_uncached() -> Result<UsageLimits, String> {
     headers.insert(USER_AGENT, HeaderValue::from_static(CLAUDE_CODE_USER_AGENT));
 
     let client = reqwest::Client::new();
-    let response = client
-        .get(USAGE_API_URL)
-        .headers(headers)
-        .send()
-        .await
-        .map_err(|e| format!("Failed to fetch usage limits: {e}"))?;
+    let mut attempt = 1;
+    let response = loop {
+        let response = client
+            .get(USAGE_API_URL)
+            .headers(headers.clone())
+            .send()
+            .await
+            .map_err(|e| format!("Failed to fetch usage limits: {e}"))?;
 
-    if !response.status().is_success() {
         let status = response.status();
-        let body = response
-            .text()
-            .await
-            .unwrap_or_else(|_| "Unknown error".to_string());
-        return Err(format!("API error {status}: {body}"));
-    }
+        // Tokens can be revoked or expire without a recorded expiry
+        if status.as_u16() == 401 {
+            return Err(UsageLimitsError::CredentialsExpired);
+        }
+        if status.is_success() {
+            break response;
+        }
+
+        let rate_limited = status.as_u16() == 429;
+        let delay = retry_delay(response.headers(), attempt);
+        let give_up = attempt >= MAX_ATTEMPTS || delay > MAX_RETRY_DELAY;
+        if rate_limited && give_up {
+            return Err(UsageLimitsError::RateLimited {
+                resets_at: rate_limit_reset(response.headers(), Utc::now()),
+            });
+        }
+        if !(rate_limited || status.is_server_error()) || give_up {
+            let body = response
+                .text()
+                .await
+                .unwrap_or_else(|_| "Unknown error".to_string());
+            return Err(format!("API error {status}: {body}").into());
+        }
+
+        log::debug!("Usage limits request returned {status}, retrying in {delay:?}");
+        tokio::time::sleep(delay).await;
+        attempt += 1;
+    };
 
     let api_response: UsageLimitsApiResponse = response
         .json()
@@ -85,6 +123,44 @@ async fn fetch_usage_limits_uncached() -> Result<UsageLimits, String> {
     Ok(api_response.into())
 }
 
+/// Delay before retrying: Retry-After if present, else exponential backoff
+fn retry_delay(headers: &HeaderMap, attempt: u32) -> Duration {
+    headers
+        .get(RETRY_AFTER)
+        .and_then(|v| v.to_str().ok())
+        .and_then(|v| v.trim().parse::<u64>().ok())
+        .map(Duration::from_secs)
+        .unwrap_or(BASE_RETRY_DELAY * 2u32.pow(attempt.saturating_sub(1)))
+}
+
+/// When a rate limit resets, as an ISO timestamp
+///
+/// Uses the earliest `anthropic-ratelimit-*-reset` header, falling back to
+/// Retry-After seconds from `now`.
+fn rate_limit_reset(headers: &HeaderMap, now: DateTime<Utc>) -> Option<String> {
+    let earliest_reset = headers
+        .iter()
+        .filter(|(name, _)| {
+            let name = name.as_str();
+            name.starts_with("anthropic-ratelimit-") && name.ends_with("-reset")
+        })
+        .filter_map(|(_, value)| DateTime::parse_from_rfc3339(value.to_str().ok()?).ok())
+        .map(|reset| reset.with_timezone(&Utc))
+        .min();
+    if let Some(reset) = earliest_reset {
+        return Some(reset.to_rfc3339());
+    }
+
+    let seconds: i64 = headers
+        .get(RETRY_AFTER)?
+        .to_str()
+        .ok()?
+        .trim()
+        .parse()
+        .ok()?;
+    Some((now + chrono::Duration::seconds(seconds)).to_rfc3339())
+}
+
 /// Clear the usage limits cache (useful for testing or force refresh)
 #[allow(dead_code)]
 pub fn clear_cache() {
@@ -103,4 +179,58 @@ mod tests {
         let cache = USAGE_LIMITS_CACHE.lock().unwrap();
         assert!(cache.is_none());
     }
+
+    #[test]
+    fn test_retry_delay() {
+        let mut headers = HeaderMap::new();
+        assert_eq!(retry_delay(&headers, 1), Duration::from_millis(500));
+        assert_eq!(retry_delay(&headers, 2), Duration::from_secs(1));
+
+        headers.insert(RETRY_AFTER, HeaderValue::from_static("3"));
+        assert_eq!(retry_delay(&headers, 1), Duration::from_secs(3));
+    }
+
+    #[test]
+    fn test_rate_limit_reset() {
+        let now = DateTime::parse_from_rfc3339("2026-01-01T00:00:00Z")
+            .unwrap()
+            .with_timezone(&Utc);
+        let mut headers = HeaderMap::new();
+        assert_eq!(rate_limit_reset(&headers, now), None);
+
+        headers.insert(RETRY_AFTER, HeaderValue::from_static("30"));
+        assert_eq!(
+            rate_limit_reset(&headers, now).as_deref(),
+            Some("2026-01-01T00:00:30+00:00")
+        );
+
+        // Rate limit headers win over Retry-After, earliest reset first
+        headers.insert(
+            "anthropic-ratelimit-requests-reset",
+            HeaderValue::from_static("2026-01-01T00:05:00Z"),
+        );
+        headers.insert(
+            "anthropic-ratelimit-tokens-reset",
+            HeaderValue::from_static("2026-01-01T00:01:00Z"),
+        );
+        assert_eq!(
+            rate_limit_reset(&headers, now).as_deref(),
+            Some("2026-01-01T00:01:00+00:00")
+        );
+    }
+
+    #[test]
+    fn test_usage_limits_error_serialization() {
+        let error = UsageLimitsError::RateLimited {
+            resets_at: Some("2026-01-01T00:01:00+00:00".to_string()),
+        };
+        assert_eq!(
+            serde_json::to_value(&error).unwrap(),
+            serde_json::json!({ "kind": "rate_limited", "resetsAt": "2026-01-01T00:01:00+00:00" })
+        );
+        assert_eq!(
+            serde_json::to_value(UsageLimitsError::CredentialsExpired).unwrap(),
+            serde_json::json!({ "kind": "credentials_expired" })
+        );
+    }
 }
@@ -1,16 +1,22 @@
+use std::collections::HashMap;
+
 use tauri::AppHandle;
 
 use super::api::fetch_usage_limits;
+use super::context_hook::HookContextData;
 use super::credentials::has_oauth_credentials;
-use super::types::{SessionUsage, UsageLimits};
-use crate::chat::storage::{load_metadata, load_sessions};
+use super::hook_installer::{HookEvent, HookHealth, SettingsWriteResult};
+use super::types::{ClaudeAuthStatus, SessionUsage, UsageLimits, UsageLimitsError, UsageSummary};
+use crate::chat::registry::get_running_sessions;
+use crate::chat::storage::{list_all_session_ids, load_metadata, load_sessions};
 
 /// Get Claude usage limits (5-hour and 7-day windows)
 ///
 /// Returns current utilization percentages and reset times.
 /// Uses a 60-second cache to avoid excessive API calls.
+/// Fails with `credentials_expired` when the user needs to log in again.
 #[tauri::command]
-pub async fn get_claude_usage_limits() -> Result<UsageLimits, String> {
+pub async fn get_claude_usage_limits() -> Result<UsageLimits, UsageLimitsError> {
     // Check if credentials are available first
     if !has_oauth_credentials().await {
         return Ok(UsageLimits::default());
@@ -37,6 +43,11 @@ pub async fn get_session_usage(
 
     // Load session metadata to get run info
     let metadata = load_metadata(&app, &session_id)?;
+    let context_window = context_window_override(
+        &app,
+        metadata.as_ref().and_then(|m| m.selected_model.as_deref()),
+    )
+    .await;
 
     // For cost: sum all tokens from all runs
     // For context: use last run's tokens (represents current context window usage)
@@ -68,7 +79,7 @@ pub async fn get_session_usage(
         None => (0, 0, 0, 0, 0, 0, 0),
     };
 
-    Ok(SessionUsage::from_tokens_with_context(
+    let usage = SessionUsage::from_tokens_with_context(
         total_input,
         total_output,
         total_cache_read,
@@ -76,7 +87,74 @@ pub async fn get_session_usage(
         last_input,
         last_cache_read,
         last_cache_creation,
-    ))
+    );
+
+    Ok(match context_window {
+        Some(max_tokens) => usage.with_context_window(max_tokens),
+        None => usage,
+    })
+}
+
+/// Look up the user's context window override for a model
+///
+/// Falls back to the globally selected model when the session has none.
+pub(crate) async fn context_window_override(app: &AppHandle, model: Option<&str>) -> Option<u64> {
+    let prefs = crate::load_preferences(app.clone()).await.ok()?;
+    context_window_for(&prefs, model)
+}
+
+/// The context window override in `prefs` for a model (see `context_window_override`)
+fn context_window_for(prefs: &crate::AppPreferences, model: Option<&str>) -> Option<u64> {
+    let model = model.unwrap_or(&prefs.selected_model);
+    prefs
+        .context_window_overrides
+        .get(model)
+        .copied()
+        .filter(|&tokens| tokens > 0)
+}
+
+/// Apply the user's window size override for `model` on top of the hook's default
+fn with_context_window_override(
+    data: HookContextData,
+    prefs: Option<&crate::AppPreferences>,
+    model: Option<&str>,
+) -> HookContextData {
+    match prefs.and_then(|prefs| context_window_for(prefs, model)) {
+        Some(max_tokens) => data.with_context_window(max_tokens),
+        None => data,
+    }
+}
+
+/// Selected model of each Jean session, keyed by the Claude Code session ID it uses
+fn models_by_claude_session(app: &AppHandle) -> HashMap<String, Option<String>> {
+    list_all_session_ids(app)
+        .unwrap_or_default()
+        .into_iter()
+        .filter_map(|session_id| load_metadata(app, &session_id).ok().flatten())
+        .filter_map(|metadata| Some((metadata.claude_session_id?, metadata.selected_model)))
+        .collect()
+}
+
+/// Selected model of the Jean session that uses a Claude Code session ID
+///
+/// Running sessions are checked first, since the hook writes while a run
+/// finishes; the rest are only read until the session is found.
+pub(crate) fn claude_session_model(app: &AppHandle, claude_session_id: &str) -> Option<String> {
+    let running = get_running_sessions();
+    let idle = list_all_session_ids(app)
+        .unwrap_or_default()
+        .into_iter()
+        .filter(|session_id| !running.contains(session_id));
+    running
+        .iter()
+        .cloned()
+        .chain(idle)
+        .find_map(|session_id| {
+            let metadata = load_metadata(app, &session_id).ok()??;
+            (metadata.claude_session_id.as_deref() == Some(claude_session_id))
+                .then_some(metadata.selected_model)
+        })
+        .flatten()
 }
 
 /// Check if OAuth credentials are available
@@ -87,16 +165,19 @@ pub async fn has_claude_credentials() -> bool {
     has_oauth_credentials().await
 }
 
+/// Check whether the Claude OAuth token is valid or needs a re-login
+#[tauri::command]
+pub async fn check_claude_auth() -> ClaudeAuthStatus {
+    super::credentials::check_claude_auth().await
+}
+
 /// Get context data from the Jean hook
 ///
 /// Returns context data written by the context-writer hook script.
 /// This provides accurate context percentage from Claude Code directly.
 /// Uses Claude Code's session ID from Jean's session metadata.
 #[tauri::command]
-pub fn get_hook_context_data(
-    app: AppHandle,
-    session_id: String,
-) -> Option<super::context_hook::HookContextData> {
+pub async fn get_hook_context_data(app: AppHandle, session_id: String) -> Option<HookContextData> {
     // Load session metadata to get Claude Code's session ID
     let metadata = load_metadata(&app, &session_id).ok()??;
 
@@ -104,7 +185,73 @@ pub fn get_hook_context_data(
     let claude_session_id = metadata.claude_session_id.as_ref()?;
 
     // Read hook data using Claude Code's session ID
-    super::context_hook::read_hook_context_data(claude_session_id)
+    let data = super::context_hook::read_hook_context_data(claude_session_id)?;
+
+    let prefs = crate::load_preferences(app).await.ok();
+    Some(with_context_window_override(
+        data,
+        prefs.as_ref(),
+        metadata.selected_model.as_deref(),
+    ))
+}
+
+/// Get the latest context data the hook wrote for a Claude Code session ID
+///
+/// Returns `None` if the hook hasn't written (complete) data for it yet. The
+/// context window override of the Jean session using it is applied, as in
+/// `get_hook_context_data`.
+#[tauri::command]
+pub async fn get_session_context_data(
+    app: AppHandle,
+    session_id: String,
+) -> Result<Option<HookContextData>, String> {
+    let Some(data) = super::context_hook::get_session_context_data(&session_id)? else {
+        return Ok(None);
+    };
+    let prefs = crate::load_preferences(app.clone()).await.ok();
+    tokio::task::spawn_blocking(move || {
+        let model = claude_session_model(&app, &session_id);
+        with_context_window_override(data, prefs.as_ref(), model.as_deref())
+    })
+    .await
+    .map(Some)
+    .map_err(|e| format!("Failed to read context data: {e}"))
+}
+
+/// Get the latest context data of every session the hook has seen, newest first
+///
+/// Each entry gets its Jean session's context window override, as in
+/// `get_hook_context_data`.
+#[tauri::command]
+pub async fn list_all_context_data(app: AppHandle) -> Result<Vec<HookContextData>, String> {
+    let prefs = crate::load_preferences(app.clone()).await.ok();
+    tokio::task::spawn_blocking(move || {
+        let data = super::context_hook::list_all_context_data()?;
+        let models = models_by_claude_session(&app);
+        Ok(data
+            .into_iter()
+            .map(|item| {
+                let model = models.get(&item.session_id).cloned().flatten();
+                with_context_window_override(item, prefs.as_ref(), model.as_deref())
+            })
+            .collect())
+    })
+    .await
+    .map_err(|e| format!("Failed to read context data: {e}"))?
+}
+
+/// Get usage totals across all sessions and projects, bucketed per day
+///
+/// Reads the context hook's data files, so it works without the Anthropic API.
+/// `since` and `until` are RFC 3339 timestamps.
+#[tauri::command]
+pub async fn get_claude_usage_summary(
+    since: String,
+    until: String,
+) -> Result<UsageSummary, String> {
+    tokio::task::spawn_blocking(move || super::context_hook::get_usage_summary(&since, &until))
+        .await
+        .map_err(|e| format!("Failed to summarize usage: {e}"))?
 }
 
 /// Check if the context tracking hook is installed
@@ -113,14 +260,28 @@ pub fn is_context_hook_installed() -> bool {
     super::hook_installer::is_hook_installed()
 }
 
+/// Get the Claude Code events the context tracking hook is installed for
+#[tauri::command]
+pub fn get_context_hook_events() -> Vec<HookEvent> {
+    super::hook_installer::installed_hook_events()
+}
+
+/// Verify the context tracking hook can run (script, Bun/Node.js/Deno, data directory)
+#[tauri::command]
+pub fn verify_context_hook() -> Result<HookHealth, String> {
+    super::hook_installer::verify_hook()
+}
+
 /// Install the context tracking hook in Claude Code settings
+///
+/// Registers for `Stop` only unless other events are requested.
 #[tauri::command]
-pub fn install_context_hook() -> Result<(), String> {
-    super::hook_installer::install_hook()
+pub fn install_context_hook(events: Option<Vec<HookEvent>>) -> Result<SettingsWriteResult, String> {
+    super::hook_installer::install_hook(events.unwrap_or_default())
 }
 
 /// Uninstall the context tracking hook from Claude Code settings
 #[tauri::command]
-pub fn uninstall_context_hook() -> Result<(), String> {
+pub fn uninstall_context_hook() -> Result<SettingsWriteResult, String> {
     super::hook_installer::uninstall_hook()
 }
@@ -3,9 +3,13 @@
 //! Reads context data written by Jean's context-writer hook.
 //! The hook writes to ~/.jean/context-data/{session_id}.json after each response.
 
+use chrono::{DateTime, FixedOffset};
 use serde::{Deserialize, Serialize};
+use std::collections::BTreeMap;
 use std::fs;
-use std::path::PathBuf;
+use std::path::{Path, PathBuf};
+
+use super::types::{UsageDay, UsageSummary};
 
 /// Context data written by the hook script
 #[derive(Debug, Clone, Serialize, Deserialize)]
@@ -20,8 +24,19 @@ pub struct HookContextData {
     pub timestamp: String,
 }
 
+impl HookContextData {
+    /// Re-measure the context percentage against a different window size
+    pub fn with_context_window(mut self, max_context_tokens: u64) -> Self {
+        self.context_max_tokens = max_context_tokens;
+        self.context_percentage =
+            super::types::context_percentage(self.context_tokens, max_context_tokens).round()
+                as u64;
+        self
+    }
+}
+
 /// Get the directory for context data files
-fn get_context_data_dir() -> Option<PathBuf> {
+pub fn get_context_data_dir() -> Option<PathBuf> {
     let home = dirs::home_dir()?;
     Some(home.join(".jean").join("context-data"))
 }
@@ -44,8 +59,44 @@ pub fn read_hook_context_data(session_id: &str) -> Option<HookContextData> {
     serde_json::from_str(&content).ok()
 }
 
+/// Read the latest context data for a Claude Code session ID
+///
+/// `Ok(None)` when the hook hasn't written data for the session yet, or the
+/// file is partially written (the hook fails silently mid-write).
+pub fn get_session_context_data(session_id: &str) -> Result<Option<HookContextData>, String> {
+    if session_id.is_empty() || session_id.contains(['/', '\\']) || session_id.contains("..") {
+        return Err(format!("Invalid session ID: {session_id}"));
+    }
+    get_context_data_dir().ok_or("Could not determine home directory")?;
+    Ok(read_hook_context_data(session_id))
+}
+
+/// Latest context data of every session, most recent first
+///
+/// Unreadable or partially written files are skipped.
+pub fn list_all_context_data() -> Result<Vec<HookContextData>, String> {
+    let dir = get_context_data_dir().ok_or("Could not determine home directory")?;
+    let (data, _skipped) = read_all_context_data(&dir);
+    Ok(latest_per_session(data))
+}
+
+/// Keep the newest entry per session ID, sorted newest first
+fn latest_per_session(data: Vec<HookContextData>) -> Vec<HookContextData> {
+    let mut latest: BTreeMap<String, HookContextData> = BTreeMap::new();
+    for item in data {
+        match latest.get(&item.session_id) {
+            Some(existing) if existing.timestamp >= item.timestamp => {}
+            _ => {
+                latest.insert(item.session_id.clone(), item);
+            }
+        }
+    }
+    let mut entries: Vec<HookContextData> = latest.into_values().collect();
+    entries.sort_by(|a, b| b.timestamp.cmp(&a.timestamp));
+    entries
+}
+
 /// Ensure the context data directory exists
-#[allow(dead_code)]
 pub fn ensure_context_data_dir() -> Result<PathBuf, String> {
     let dir = get_context_data_dir().ok_or("Could not determine home directory")?;
     fs::create_dir_all(&dir).map_err(|e| format!("Failed to create context data directory: {e}"))?;
@@ -81,3 +132,178 @@ pub fn cleanup_old_context_data() -> Result<u32, String> {
 
     Ok(removed)
 }
+
+/// Read every context data file in a directory
+///
+/// Returns the parsed entries and how many files were skipped as malformed.
+fn read_all_context_data(dir: &Path) -> (Vec<HookContextData>, u32) {
+    let Ok(entries) = fs::read_dir(dir) else {
+        return (Vec::new(), 0);
+    };
+
+    let mut data = Vec::new();
+    let mut skipped = 0;
+    for entry in entries.flatten() {
+        let path = entry.path();
+        if path.extension().is_none_or(|ext| ext != "json") {
+            continue;
+        }
+        match fs::read_to_string(&path)
+            .ok()
+            .and_then(|content| serde_json::from_str(&content).ok())
+        {
+            Some(item) => data.push(item),
+            None => skipped += 1,
+        }
+    }
+    (data, skipped)
+}
+
+/// Sum usage in `[since, until)`, bucketed by day in the given timezone
+///
+/// Entries with an unparseable timestamp are counted as skipped.
+fn summarize_usage(
+    data: &[HookContextData],
+    since: DateTime<FixedOffset>,
+    until: DateTime<FixedOffset>,
+    offset: FixedOffset,
+) -> UsageSummary {
+    let mut days: BTreeMap<String, UsageDay> = BTreeMap::new();
+    let mut skipped_files = 0;
+
+    for item in data {
+        let Ok(timestamp) = DateTime::parse_from_rfc3339(&item.timestamp) else {
+            skipped_files += 1;
+            continue;
+        };
+        if timestamp < since || timestamp >= until {
+            continue;
+        }
+
+        let date = timestamp
+            .with_timezone(&offset)
+            .format("%Y-%m-%d")
+            .to_string();
+        let day = days.entry(date.clone()).or_insert_with(|| UsageDay {
+            date,
+            sessions: 0,
+            cost_usd: 0.0,
+            context_tokens: 0,
+        });
+        day.sessions += 1;
+        day.cost_usd += item.cost_usd;
+        day.context_tokens += item.context_tokens;
+    }
+
+    let days: Vec<UsageDay> = days.into_values().collect();
+    UsageSummary {
+        session_count: days.iter().map(|d| d.sessions).sum(),
+        total_cost_usd: days.iter().map(|d| d.cost_usd).sum(),
+        total_context_tokens: days.iter().map(|d| d.context_tokens).sum(),
+        days,
+        skipped_files,
+    }
+}
+
+/// Summarize usage across all sessions from the hook's context data files
+///
+/// `since` and `until` are RFC 3339 timestamps; days are bucketed in the
+/// local timezone.
+pub fn get_usage_summary(since: &str, until: &str) -> Result<UsageSummary, String> {
+    let since =
+        DateTime::parse_from_rfc3339(since).map_err(|e| format!("Invalid since timestamp: {e}"))?;
+    let until =
+        DateTime::parse_from_rfc3339(until).map_err(|e| format!("Invalid until timestamp: {e}"))?;
+    let dir = get_context_data_dir().ok_or("Could not determine home directory")?;
+
+    let (data, unreadable) = read_all_context_data(&dir);
+    let offset = *chrono::Local::now().offset();
+    let mut summary = summarize_usage(&data, since, until, offset);
+    summary.skipped_files += unreadable;
+    Ok(summary)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn entry(session_id: &str, cost_usd: f64, timestamp: &str) -> HookContextData {
+        HookContextData {
+            session_id: session_id.to_string(),
+            cost_usd,
+            duration_ms: 0,
+            context_tokens: 1000,
+            context_max_tokens: 200_000,
+            context_percentage: 0,
+            timestamp: timestamp.to_string(),
+        }
+    }
+
+    #[test]
+    fn test_read_all_context_data_skips_malformed_files() {
+        let dir = tempfile::tempdir().unwrap();
+        let good = serde_json::to_string(&entry("a", 1.0, "2026-01-01T10:00:00Z")).unwrap();
+        fs::write(dir.path().join("a.json"), good).unwrap();
+        fs::write(dir.path().join("b.json"), r#"{"sessionId": "b""#).unwrap();
+        fs::write(dir.path().join("notes.txt"), "ignored").unwrap();
+
+        let (data, skipped) = read_all_context_data(dir.path());
+        assert_eq!(data.len(), 1);
+        assert_eq!(data[0].session_id, "a");
+        assert_eq!(skipped, 1);
+    }
+
+    #[test]
+    fn test_latest_per_session_keeps_newest_entry() {
+        let data = vec![
+            entry("a", 1.0, "2026-01-01T10:00:00.000Z"),
+            entry("b", 2.0, "2026-01-02T10:00:00.000Z"),
+            entry("a", 3.0, "2026-01-03T10:00:00.000Z"),
+            entry("a", 0.5, "2026-01-02T10:00:00.000Z"),
+        ];
+
+        let latest = latest_per_session(data);
+        assert_eq!(latest.len(), 2);
+        assert_eq!(latest[0].session_id, "a");
+        assert_eq!(latest[0].cost_usd, 3.0);
+        assert_eq!(latest[1].session_id, "b");
+    }
+
+    #[test]
+    fn test_get_session_context_data_rejects_paths() {
+        assert!(get_session_context_data("").is_err());
+        assert!(get_session_context_data("../settings").is_err());
+        assert!(get_session_context_data("a/b").is_err());
+    }
+
+    #[test]
+    fn test_summarize_usage_buckets_by_day() {
+        let data = vec![
+            entry("a", 1.5, "2026-01-01T10:00:00Z"),
+            entry("b", 0.5, "2026-01-01T23:00:00Z"),
+            entry("c", 2.0, "2026-01-02T08:00:00Z"),
+            entry("old", 9.0, "2025-12-31T12:00:00Z"),
+            entry("bad", 9.0, "yesterday"),
+        ];
+        let utc = FixedOffset::east_opt(0).unwrap();
+        let since = DateTime::parse_from_rfc3339("2026-01-01T00:00:00Z").unwrap();
+        let until = DateTime::parse_from_rfc3339("2026-01-03T00:00:00Z").unwrap();
+
+        let summary = summarize_usage(&data, since, until, utc);
+        assert_eq!(summary.session_count, 3);
+        assert_eq!(summary.total_cost_usd, 4.0);
+        assert_eq!(summary.total_context_tokens, 3000);
+        assert_eq!(summary.skipped_files, 1);
+        assert_eq!(summary.days.len(), 2);
+        assert_eq!(summary.days[0].date, "2026-01-01");
+        assert_eq!(summary.days[0].sessions, 2);
+        assert_eq!(summary.days[0].cost_usd, 2.0);
+
+        // Buckets follow the given timezone: 23:00 UTC is the next day at UTC+2
+        let plus_two = FixedOffset::east_opt(2 * 3600).unwrap();
+        let summary = summarize_usage(&data, since, until, plus_two);
+        assert_eq!(summary.days[0].sessions, 1);
+        assert_eq!(summary.days[1].date, "2026-01-02");
+        assert_eq!(summary.days[1].sessions, 2);
+    }
+}
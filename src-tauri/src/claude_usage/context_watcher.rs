@@ -0,0 +1,180 @@
+//! Live context updates from the context-writer hook
+//!
+//! Watches `~/.jean/context-data/` for filesystem notifications and emits a
+//! `context:update` event with the new `HookContextData` whenever the hook
+//! rewrites a session's file, so the context gauge doesn't have to poll.
+
+use std::collections::HashMap;
+use std::fs;
+use std::path::{Path, PathBuf};
+use std::sync::mpsc::{self, RecvTimeoutError};
+use std::thread;
+use std::time::{Duration, Instant};
+
+use notify::{Event, EventKind, RecursiveMode, Watcher};
+use tauri::{AppHandle, Emitter};
+
+use super::context_hook::{ensure_context_data_dir, HookContextData};
+
+/// A changed file must stay unchanged this long before it's read, so rapid
+/// successive writes produce one event
+const DEBOUNCE: Duration = Duration::from_millis(300);
+
+/// Changed context data files waiting out the debounce
+#[derive(Default)]
+struct PendingChanges {
+    /// When each file last changed
+    changed: HashMap<PathBuf, Instant>,
+}
+
+impl PendingChanges {
+    /// Record the `.json` files a filesystem event touched
+    fn record(&mut self, event: &Event, now: Instant) {
+        if matches!(event.kind, EventKind::Access(_) | EventKind::Remove(_)) {
+            return;
+        }
+        for path in &event.paths {
+            if path.extension().is_some_and(|ext| ext == "json") {
+                self.changed.insert(path.clone(), now);
+            }
+        }
+    }
+
+    /// Files whose changes have settled, removed from the pending set
+    fn take_settled(&mut self, now: Instant) -> Vec<PathBuf> {
+        let mut ready: Vec<PathBuf> = self
+            .changed
+            .iter()
+            .filter(|(_, since)| now.duration_since(**since) >= DEBOUNCE)
+            .map(|(path, _)| path.clone())
+            .collect();
+        for path in &ready {
+            self.changed.remove(path);
+        }
+        ready.sort();
+        ready
+    }
+
+    /// How long until the next pending file settles (None when nothing is pending)
+    fn next_wait(&self, now: Instant) -> Option<Duration> {
+        self.changed
+            .values()
+            .map(|since| (*since + DEBOUNCE).saturating_duration_since(now))
+            .min()
+    }
+}
+
+/// Read a settled file and emit it, with the user's context window override applied
+///
+/// Partially written files are skipped; the hook's next complete write is
+/// picked up as a new change.
+fn emit_context_update(app: &AppHandle, path: &Path) {
+    let Some(data) = fs::read_to_string(path)
+        .ok()
+        .and_then(|content| serde_json::from_str::<HookContextData>(&content).ok())
+    else {
+        return;
+    };
+
+    let model = super::commands::claude_session_model(app, &data.session_id);
+    let data = match tauri::async_runtime::block_on(super::commands::context_window_override(
+        app,
+        model.as_deref(),
+    )) {
+        Some(max_tokens) => data.with_context_window(max_tokens),
+        None => data,
+    };
+    if let Err(e) = app.emit("context:update", &data) {
+        log::error!("Failed to emit context:update event: {e}");
+    }
+}
+
+/// Start the background watcher that emits `context:update` events
+///
+/// The payload is the hook data, keyed by Claude Code's session ID.
+pub fn start_context_watcher(app: AppHandle) {
+    thread::spawn(move || {
+        // Create it up front: a directory that doesn't exist can't be watched
+        let dir = match ensure_context_data_dir() {
+            Ok(dir) => dir,
+            Err(e) => {
+                log::warn!("Context watcher disabled: {e}");
+                return;
+            }
+        };
+
+        let (tx, rx) = mpsc::channel();
+        let mut watcher = match notify::recommended_watcher(tx) {
+            Ok(watcher) => watcher,
+            Err(e) => {
+                log::warn!("Context watcher disabled: failed to create watcher: {e}");
+                return;
+            }
+        };
+        if let Err(e) = watcher.watch(&dir, RecursiveMode::NonRecursive) {
+            log::warn!(
+                "Context watcher disabled: failed to watch {}: {e}",
+                dir.display()
+            );
+            return;
+        }
+        log::trace!("Context watcher started for {}", dir.display());
+
+        let mut pending = PendingChanges::default();
+        loop {
+            // Sleep until an event arrives, or until a pending change settles
+            let received = match pending.next_wait(Instant::now()) {
+                Some(wait) => rx.recv_timeout(wait),
+                None => rx.recv().map_err(|_| RecvTimeoutError::Disconnected),
+            };
+            match received {
+                Ok(Ok(event)) => pending.record(&event, Instant::now()),
+                Ok(Err(e)) => log::warn!("Context watcher error: {e}"),
+                Err(RecvTimeoutError::Timeout) => {}
+                Err(RecvTimeoutError::Disconnected) => {
+                    log::warn!("Context watcher stopped: watcher disconnected");
+                    return;
+                }
+            }
+
+            for path in pending.take_settled(Instant::now()) {
+                emit_context_update(&app, &path);
+            }
+        }
+    });
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use notify::event::{AccessKind, CreateKind, ModifyKind};
+
+    #[test]
+    fn test_pending_changes_report_settled_files_once() {
+        let start = Instant::now();
+        let file = PathBuf::from("/ctx/session.json");
+        let mut pending = PendingChanges::default();
+        assert_eq!(pending.next_wait(start), None);
+
+        let created = Event::new(EventKind::Create(CreateKind::File))
+            .add_path(file.clone())
+            .add_path(PathBuf::from("/ctx/notes.txt"));
+        pending.record(&created, start);
+        let read = Event::new(EventKind::Access(AccessKind::Any)).add_path(file.clone());
+        pending.record(&read, start + DEBOUNCE);
+
+        // Still inside the debounce window
+        assert!(pending.take_settled(start).is_empty());
+        assert_eq!(pending.next_wait(start), Some(DEBOUNCE));
+
+        // Another write restarts the window
+        let modified = Event::new(EventKind::Modify(ModifyKind::Any)).add_path(file.clone());
+        pending.record(&modified, start + DEBOUNCE / 2);
+        assert!(pending.take_settled(start + DEBOUNCE).is_empty());
+
+        let settled = start + DEBOUNCE / 2 + DEBOUNCE;
+        assert_eq!(pending.take_settled(settled), vec![file]);
+        assert!(pending.take_settled(settled + DEBOUNCE).is_empty());
+        assert_eq!(pending.next_wait(settled), None);
+    }
+}
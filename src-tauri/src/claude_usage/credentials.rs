@@ -1,34 +1,61 @@
+use std::env;
 use std::fs;
-use std::path::PathBuf;
+use std::path::{Path, PathBuf};
+use std::time::{SystemTime, UNIX_EPOCH};
 
-use super::types::ClaudeCredentials;
+use super::types::{ClaudeAuthStatus, ClaudeCredentials, OAuthCredentials};
 
 #[cfg(target_os = "macos")]
 use std::process::Command;
 
-/// Get the OAuth access token from Claude Code credentials
+/// Tokens expiring within this window are reported as needing a refresh
+const REFRESH_MARGIN_MS: u64 = 5 * 60 * 1000;
+
+/// Env var naming a credentials file to use before the platform defaults,
+/// for headless Linux, CI or remote machines where the token lives elsewhere
+pub const CREDENTIALS_PATH_ENV: &str = "JEAN_CLAUDE_CREDENTIALS_PATH";
+
+/// Get the OAuth credentials from Claude Code
+///
+/// Tries, in order:
+/// 1. The file named by `JEAN_CLAUDE_CREDENTIALS_PATH`, if set
+/// 2. On macOS: the Keychain, via the `security` CLI
+/// 3. `$CLAUDE_CONFIG_DIR/.credentials.json`, if set
+/// 4. `~/.claude/.credentials.json`
 ///
-/// On macOS: Reads from Keychain using `security` CLI
-/// On other platforms: Falls back to ~/.claude/.credentials.json file
-pub async fn get_oauth_token() -> Result<String, String> {
+/// If none has credentials, the error lists every location checked.
+pub async fn get_oauth_credentials() -> Result<OAuthCredentials, String> {
+    let mut checked = Vec::new();
+
+    if let Some(path) = env::var_os(CREDENTIALS_PATH_ENV).filter(|p| !p.is_empty()) {
+        match read_credentials_file(Path::new(&path)) {
+            Ok(creds) => return Ok(creds),
+            Err(e) => checked.push(format!("${CREDENTIALS_PATH_ENV}: {e}")),
+        }
+    }
+
     #[cfg(target_os = "macos")]
     {
-        // Try Keychain first on macOS
         match get_macos_keychain_token().await {
             Ok(token) => return Ok(token),
-            Err(_) => {
-                // Fall back to file-based credentials
-            }
+            Err(e) => checked.push(format!("macOS Keychain: {e}")),
         }
     }
 
-    // Try file-based credentials
-    get_file_credentials().await
+    let config_dir = env::var_os("CLAUDE_CONFIG_DIR")
+        .filter(|p| !p.is_empty())
+        .map(PathBuf::from);
+    find_file_credentials(&default_credentials_paths(config_dir), &mut checked).ok_or_else(|| {
+        format!(
+            "No Claude credentials found. Checked: {}",
+            checked.join("; ")
+        )
+    })
 }
 
 /// Get OAuth token from macOS Keychain
 #[cfg(target_os = "macos")]
-async fn get_macos_keychain_token() -> Result<String, String> {
+async fn get_macos_keychain_token() -> Result<OAuthCredentials, String> {
     let output = Command::new("security")
         .args(["find-generic-password", "-s", "Claude Code-credentials", "-w"])
         .output()
@@ -44,34 +71,93 @@ async fn get_macos_keychain_token() -> Result<String, String> {
     parse_credentials_json(&json_str)
 }
 
-/// Get OAuth token from credentials file
-async fn get_file_credentials() -> Result<String, String> {
-    let credentials_path = get_credentials_file_path()?;
-
-    if !credentials_path.exists() {
-        return Err(format!(
-            "Credentials file not found at {}",
-            credentials_path.display()
-        ));
+/// Get OAuth token from a credentials file
+fn read_credentials_file(path: &Path) -> Result<OAuthCredentials, String> {
+    if !path.exists() {
+        return Err(format!("Credentials file not found at {}", path.display()));
     }
 
-    let content = fs::read_to_string(&credentials_path)
-        .map_err(|e| format!("Failed to read credentials file: {e}"))?;
+    let content = fs::read_to_string(path)
+        .map_err(|e| format!("Failed to read credentials file {}: {e}", path.display()))?;
+
+    parse_credentials_json(&content).map_err(|e| format!("{e} ({})", path.display()))
+}
+
+/// Default credentials file locations, most specific first
+fn default_credentials_paths(config_dir: Option<PathBuf>) -> Vec<PathBuf> {
+    let mut paths: Vec<PathBuf> = config_dir
+        .map(|dir| dir.join(".credentials.json"))
+        .into_iter()
+        .collect();
+    if let Ok(path) = get_credentials_file_path() {
+        if !paths.contains(&path) {
+            paths.push(path);
+        }
+    }
+    paths
+}
 
-    parse_credentials_json(&content)
+/// First credentials file in `paths` that parses, recording each failure in `checked`
+fn find_file_credentials(paths: &[PathBuf], checked: &mut Vec<String>) -> Option<OAuthCredentials> {
+    if paths.is_empty() {
+        checked.push("Could not determine home directory".to_string());
+    }
+    for path in paths {
+        match read_credentials_file(path) {
+            Ok(creds) => return Some(creds),
+            Err(e) => checked.push(e),
+        }
+    }
+    None
 }
 
-/// Parse credentials JSON and extract access token
-fn parse_credentials_json(json_str: &str) -> Result<String, String> {
+/// Parse credentials JSON and extract the OAuth credentials
+fn parse_credentials_json(json_str: &str) -> Result<OAuthCredentials, String> {
     let creds: ClaudeCredentials =
         serde_json::from_str(json_str.trim()).map_err(|e| format!("Failed to parse credentials JSON: {e}"))?;
 
     creds
         .claude_ai_oauth
-        .map(|oauth| oauth.access_token)
         .ok_or_else(|| "No OAuth credentials found in credentials".to_string())
 }
 
+/// Compute the auth status of credentials at `now_ms` (Unix milliseconds)
+pub fn auth_status(creds: &OAuthCredentials, now_ms: u64) -> ClaudeAuthStatus {
+    let Some(expires_at) = creds.expires_at else {
+        return ClaudeAuthStatus {
+            valid: true,
+            expires_at: None,
+            needs_refresh: false,
+        };
+    };
+
+    ClaudeAuthStatus {
+        valid: now_ms < expires_at,
+        expires_at: Some(expires_at),
+        needs_refresh: now_ms.saturating_add(REFRESH_MARGIN_MS) >= expires_at,
+    }
+}
+
+/// Current time as Unix milliseconds
+pub fn now_ms() -> u64 {
+    SystemTime::now()
+        .duration_since(UNIX_EPOCH)
+        .map(|d| d.as_millis() as u64)
+        .unwrap_or(0)
+}
+
+/// Check whether Claude OAuth credentials exist and are still valid
+pub async fn check_claude_auth() -> ClaudeAuthStatus {
+    match get_oauth_credentials().await {
+        Ok(creds) => auth_status(&creds, now_ms()),
+        Err(_) => ClaudeAuthStatus {
+            valid: false,
+            expires_at: None,
+            needs_refresh: false,
+        },
+    }
+}
+
 /// Get the path to the credentials file
 fn get_credentials_file_path() -> Result<PathBuf, String> {
     let home = dirs::home_dir().ok_or("Could not determine home directory")?;
@@ -80,7 +166,7 @@ fn get_credentials_file_path() -> Result<PathBuf, String> {
 
 /// Check if OAuth credentials are available (without returning the token)
 pub async fn has_oauth_credentials() -> bool {
-    get_oauth_token().await.is_ok()
+    get_oauth_credentials().await.is_ok()
 }
 
 #[cfg(test)]
@@ -99,7 +185,38 @@ mod tests {
 
         let result = parse_credentials_json(json);
         assert!(result.is_ok());
-        assert_eq!(result.unwrap(), "test-token-123");
+        assert_eq!(result.unwrap().access_token, "test-token-123");
+    }
+
+    #[test]
+    fn test_auth_status_expired_token() {
+        let creds = parse_credentials_json(
+            r#"{"claudeAiOauth": {"accessToken": "t", "expiresAt": 1700000000000}}"#,
+        )
+        .unwrap();
+
+        let status = auth_status(&creds, 1700000000001);
+        assert!(!status.valid);
+        assert!(status.needs_refresh);
+        assert_eq!(status.expires_at, Some(1700000000000));
+
+        // Valid, but inside the refresh margin
+        let status = auth_status(&creds, 1700000000000 - 60_000);
+        assert!(status.valid);
+        assert!(status.needs_refresh);
+
+        let status = auth_status(&creds, 1700000000000 - 60 * 60_000);
+        assert!(status.valid);
+        assert!(!status.needs_refresh);
+    }
+
+    #[test]
+    fn test_auth_status_without_expiry() {
+        let creds = parse_credentials_json(r#"{"claudeAiOauth": {"accessToken": "t"}}"#).unwrap();
+        let status = auth_status(&creds, now_ms());
+        assert!(status.valid);
+        assert!(!status.needs_refresh);
+        assert_eq!(status.expires_at, None);
     }
 
     #[test]
@@ -109,6 +226,35 @@ mod tests {
         assert!(result.is_err());
     }
 
+    #[test]
+    fn test_find_file_credentials_lists_every_location_checked() {
+        let dir = tempfile::tempdir().unwrap();
+        let missing = dir.path().join("missing.json");
+        let broken = dir.path().join("broken.json");
+        let good = dir.path().join("good.json");
+        fs::write(&broken, "{ not json").unwrap();
+        fs::write(&good, r#"{"claudeAiOauth": {"accessToken": "from-file"}}"#).unwrap();
+
+        let mut checked = Vec::new();
+        let creds = find_file_credentials(&[missing.clone(), broken.clone()], &mut checked);
+        assert!(creds.is_none());
+        assert_eq!(checked.len(), 2);
+        assert!(checked[0].contains("not found") && checked[0].contains("missing.json"));
+        assert!(checked[1].contains("broken.json"));
+
+        let mut checked = Vec::new();
+        let creds = find_file_credentials(&[missing, good], &mut checked).unwrap();
+        assert_eq!(creds.access_token, "from-file");
+        assert_eq!(checked.len(), 1);
+    }
+
+    #[test]
+    fn test_default_credentials_paths_prefer_config_dir() {
+        let paths = default_credentials_paths(Some(PathBuf::from("/srv/claude")));
+        assert_eq!(paths[0], PathBuf::from("/srv/claude/.credentials.json"));
+        assert_eq!(paths.last(), get_credentials_file_path().ok().as_ref());
+    }
+
     #[test]
     fn test_get_credentials_file_path() {
         let path = get_credentials_file_path();
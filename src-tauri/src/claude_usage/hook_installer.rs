@@ -5,7 +5,11 @@
 use serde::{Deserialize, Serialize};
 use serde_json::Value;
 use std::fs;
-use std::path::PathBuf;
+use std::path::{Path, PathBuf};
+use std::process::Command;
+use std::time::{SystemTime, UNIX_EPOCH};
+
+use super::context_hook::get_context_data_dir;
 
 /// The hook script content (Bun/TypeScript)
 const HOOK_SCRIPT: &str = r#"#!/usr/bin/env bun
@@ -13,8 +17,9 @@ const HOOK_SCRIPT: &str = r#"#!/usr/bin/env bun
 /**
  * Jean context-writer hook for Claude Code
  *
- * This hook runs on the "Stop" event (after each assistant response)
- * and writes context window data for Jean to read.
+ * This hook runs on the "Stop" event (after each assistant response),
+ * and optionally on "PostToolUse" (after each tool call), and writes
+ * context window data for Jean to read.
  */
 
 import { writeFile, mkdir } from "node:fs/promises";
@@ -97,16 +102,177 @@ async function main() {
 main();
 "#;
 
+/// The hook script for Node.js and Deno (plain ES module, no TypeScript or Bun APIs)
+const HOOK_SCRIPT_NODE: &str = r#"#!/usr/bin/env node
+
+/**
+ * Jean context-writer hook for Claude Code (Node.js / Deno variant)
+ *
+ * Same as the Bun script: runs on "Stop" (and optionally "PostToolUse")
+ * and writes context window data for Jean to read.
+ */
+
+import { writeFile, mkdir } from "node:fs/promises";
+import { join } from "node:path";
+import { homedir } from "node:os";
+import process from "node:process";
+
+const DATA_DIR = join(homedir(), ".jean", "context-data");
+
+async function readStdinJson() {
+  const chunks = [];
+  for await (const chunk of process.stdin) {
+    chunks.push(typeof chunk === "string" ? chunk : new TextDecoder().decode(chunk));
+  }
+  return JSON.parse(chunks.join(""));
+}
+
+async function main() {
+  try {
+    const input = await readStdinJson();
+
+    // Ensure data directory exists
+    await mkdir(DATA_DIR, { recursive: true });
+
+    // Extract context data
+    const contextWindow = input.context_window;
+    const currentUsage = contextWindow?.current_usage;
+
+    let contextTokens = 0;
+    if (currentUsage) {
+      contextTokens =
+        (currentUsage.input_tokens || 0) +
+        (currentUsage.cache_creation_input_tokens || 0) +
+        (currentUsage.cache_read_input_tokens || 0);
+    }
+
+    const maxTokens = contextWindow?.context_window_size || 200000;
+    const contextPercentage = Math.min(100, Math.round((contextTokens / maxTokens) * 100));
+
+    const data = {
+      sessionId: input.session_id,
+      costUsd: input.cost.total_cost_usd,
+      durationMs: input.cost.total_duration_ms,
+      contextTokens: contextTokens,
+      contextMaxTokens: maxTokens,
+      contextPercentage: contextPercentage,
+      timestamp: new Date().toISOString(),
+    };
+
+    // Write to session-specific file
+    const filePath = join(DATA_DIR, `${input.session_id}.json`);
+    await writeFile(filePath, JSON.stringify(data, null, 2));
+
+  } catch (error) {
+    // Fail silently - don't disrupt Claude Code
+    console.error("Jean context-writer error:", error);
+  }
+}
+
+main();
+"#;
+
+/// JavaScript runtime the context-writer hook runs with
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum HookRuntime {
+    /// Runs the TypeScript script directly
+    Bun,
+    /// Runs the `.mjs` variant, so no TypeScript loader (tsx) is needed
+    Node,
+    /// Runs the `.mjs` variant with the permissions it needs
+    Deno,
+}
+
+impl HookRuntime {
+    /// Detection order: the first runtime found on PATH is used
+    const PREFERENCE: [HookRuntime; 3] = [HookRuntime::Bun, HookRuntime::Node, HookRuntime::Deno];
+
+    fn executable(self) -> &'static str {
+        match self {
+            HookRuntime::Bun => "bun",
+            HookRuntime::Node => "node",
+            HookRuntime::Deno => "deno",
+        }
+    }
+
+    fn display_name(self) -> &'static str {
+        match self {
+            HookRuntime::Bun => "Bun",
+            HookRuntime::Node => "Node.js",
+            HookRuntime::Deno => "Deno",
+        }
+    }
+
+    /// Command prefix the script path is appended to
+    fn command_prefix(self) -> &'static str {
+        match self {
+            HookRuntime::Bun => "bun",
+            HookRuntime::Node => "node",
+            HookRuntime::Deno => "deno run --allow-env --allow-read --allow-write --allow-sys",
+        }
+    }
+
+    fn script_name(self) -> &'static str {
+        match self {
+            HookRuntime::Bun => "context-writer.ts",
+            HookRuntime::Node | HookRuntime::Deno => "context-writer.mjs",
+        }
+    }
+
+    fn script(self) -> &'static str {
+        match self {
+            HookRuntime::Bun => HOOK_SCRIPT,
+            HookRuntime::Node | HookRuntime::Deno => HOOK_SCRIPT_NODE,
+        }
+    }
+
+    /// First available runtime on PATH (Bun, then Node.js, then Deno)
+    fn detect() -> Option<Self> {
+        Self::PREFERENCE
+            .into_iter()
+            .find(|runtime| crate::platform::find_executable(runtime.executable()).is_some())
+    }
+
+    /// Runtime a hook command runs with, judged by its first word
+    fn from_command(command: &str) -> Option<Self> {
+        let program = command.split_whitespace().next()?;
+        let program = Path::new(program.trim_matches('"')).file_stem()?.to_str()?;
+        Self::PREFERENCE
+            .into_iter()
+            .find(|runtime| runtime.executable() == program)
+    }
+}
+
 /// Get the path to Jean's hooks directory
 fn get_jean_hooks_dir() -> Option<PathBuf> {
     let home = dirs::home_dir()?;
     Some(home.join(".jean").join("hooks"))
 }
 
-/// Get the path to the hook script
-fn get_hook_script_path() -> Option<PathBuf> {
+/// Get the path to the hook script for a runtime
+fn get_hook_script_path(runtime: HookRuntime) -> Option<PathBuf> {
     let dir = get_jean_hooks_dir()?;
-    Some(dir.join("context-writer.ts"))
+    Some(dir.join(runtime.script_name()))
+}
+
+/// File recording which runtime the hook was installed for
+fn get_runtime_file_path() -> Option<PathBuf> {
+    let dir = get_jean_hooks_dir()?;
+    Some(dir.join("runtime.json"))
+}
+
+/// Runtime recorded at install time, if any
+fn load_installed_runtime() -> Option<HookRuntime> {
+    let content = fs::read_to_string(get_runtime_file_path()?).ok()?;
+    serde_json::from_str(&content).ok()
+}
+
+fn save_installed_runtime(runtime: HookRuntime) -> Result<(), String> {
+    let path = get_runtime_file_path().ok_or("Could not determine hooks directory")?;
+    let content = serde_json::to_string(&runtime)
+        .map_err(|e| format!("Failed to serialize hook runtime: {e}"))?;
+    fs::write(&path, content).map_err(|e| format!("Failed to save hook runtime: {e}"))
 }
 
 /// Get the path to Claude Code's settings.json
@@ -149,37 +315,358 @@ pub struct HookCommand {
     pub command: String,
 }
 
-/// Check if the Jean hook is installed in Claude Code settings
-pub fn is_hook_installed() -> bool {
-    let settings_path = match get_claude_settings_path() {
-        Some(p) => p,
-        None => return false,
+/// Marker identifying Jean's hook command in Claude Code settings
+/// (matches both the `.ts` and `.mjs` scripts)
+const HOOK_SCRIPT_MARKER: &str = ".jean/hooks/context-writer.";
+
+/// Directory of the hook scripts, relative to the home directory
+const HOOKS_DIR: &str = ".jean/hooks";
+
+/// Context data written within this window counts as recent
+const RECENT_DATA_SECS: u64 = 24 * 60 * 60;
+
+/// Claude Code hook events the context-writer can be registered for
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+pub enum HookEvent {
+    /// After each assistant response
+    Stop,
+    /// After each tool call, for mid-turn updates during long runs
+    PostToolUse,
+}
+
+impl HookEvent {
+    const ALL: [HookEvent; 2] = [HookEvent::Stop, HookEvent::PostToolUse];
+
+    /// Key of the event's array under `hooks` in settings.json
+    fn settings_key(self) -> &'static str {
+        match self {
+            HookEvent::Stop => "Stop",
+            HookEvent::PostToolUse => "PostToolUse",
+        }
+    }
+}
+
+/// Build the hook command for the current platform
+fn hook_command(runtime: HookRuntime) -> String {
+    hook_command_for(runtime, cfg!(windows), dirs::home_dir().as_deref())
+}
+
+/// Build the hook command
+///
+/// On Unix, `$HOME` is left for the shell Claude Code runs hooks in, so the
+/// command keeps working if the home directory moves. Windows has no `$HOME`,
+/// so the path is resolved now, with forward slashes so the marker matches.
+fn hook_command_for(runtime: HookRuntime, windows: bool, home: Option<&Path>) -> String {
+    let prefix = runtime.command_prefix();
+    let script = runtime.script_name();
+    match home.filter(|_| windows) {
+        Some(home) => {
+            let home = home.to_string_lossy().replace('\\', "/");
+            format!("{prefix} \"{home}/{HOOKS_DIR}/{script}\"")
+        }
+        None => format!("{prefix} \"$HOME/{HOOKS_DIR}/{script}\""),
+    }
+}
+
+/// Check whether a hook command runs Jean's context-writer script
+fn is_jean_command(cmd: &Value) -> bool {
+    cmd.get("command")
+        .and_then(|c| c.as_str())
+        .map(|c| c.replace('\\', "/").contains(HOOK_SCRIPT_MARKER))
+        .unwrap_or(false)
+}
+
+/// Check whether a hook matcher entry runs Jean's context-writer script
+fn is_jean_hook(entry: &Value) -> bool {
+    entry
+        .get("hooks")
+        .and_then(|hooks| hooks.as_array())
+        .map(|hooks| hooks.iter().any(is_jean_command))
+        .unwrap_or(false)
+}
+
+/// Runtime referenced by Jean's hook command in the given settings
+fn runtime_in_settings(settings: &Value) -> Option<HookRuntime> {
+    settings
+        .get("hooks")?
+        .as_object()?
+        .values()
+        .filter_map(|entries| entries.as_array())
+        .flatten()
+        .filter_map(|entry| entry.get("hooks").and_then(|h| h.as_array()))
+        .flatten()
+        .filter(|cmd| is_jean_command(cmd))
+        .find_map(|cmd| HookRuntime::from_command(cmd.get("command")?.as_str()?))
+}
+
+/// Events Jean's hook is registered for in the given settings
+fn events_in_settings(settings: &Value) -> Vec<HookEvent> {
+    HookEvent::ALL
+        .into_iter()
+        .filter(|event| {
+            settings
+                .get("hooks")
+                .and_then(|hooks| hooks.get(event.settings_key()))
+                .and_then(|entries| entries.as_array())
+                .is_some_and(|entries| entries.iter().any(is_jean_hook))
+        })
+        .collect()
+}
+
+/// Register the hook command under an event
+///
+/// An existing registration has its command rewritten, which migrates hooks
+/// installed with an absolute home path.
+fn add_hook_to_settings(
+    settings: &mut Value,
+    event: HookEvent,
+    hook_command: &str,
+) -> Result<(), String> {
+    let hooks = settings
+        .as_object_mut()
+        .ok_or("Settings is not an object")?
+        .entry("hooks")
+        .or_insert(serde_json::json!({}));
+
+    let event_hooks = hooks
+        .as_object_mut()
+        .ok_or("Hooks is not an object")?
+        .entry(event.settings_key())
+        .or_insert(serde_json::json!([]));
+
+    let event_array = event_hooks
+        .as_array_mut()
+        .ok_or_else(|| format!("{} is not an array", event.settings_key()))?;
+
+    let mut found = false;
+    for cmd in event_array
+        .iter_mut()
+        .filter_map(|entry| entry.get_mut("hooks").and_then(|h| h.as_array_mut()))
+        .flatten()
+        .filter(|cmd| is_jean_command(cmd))
+    {
+        cmd["command"] = Value::String(hook_command.to_string());
+        found = true;
+    }
+
+    if !found {
+        event_array.push(serde_json::json!({
+            "matcher": "",
+            "hooks": [{
+                "type": "command",
+                "command": hook_command
+            }]
+        }));
+    }
+
+    Ok(())
+}
+
+/// Remove Jean's hook from every event except those in `keep`
+///
+/// Scans all event arrays, not just known ones, so hooks registered by other
+/// Jean versions are cleaned up too. Arrays left empty are removed.
+fn remove_hooks_from_settings(settings: &mut Value, keep: &[HookEvent]) {
+    let Some(hooks) = settings.get_mut("hooks").and_then(|h| h.as_object_mut()) else {
+        return;
     };
 
-    if !settings_path.exists() {
-        return false;
+    let kept_keys: Vec<&str> = keep.iter().map(|e| e.settings_key()).collect();
+    hooks.retain(|key, entries| {
+        if kept_keys.contains(&key.as_str()) {
+            return true;
+        }
+        let Some(array) = entries.as_array_mut() else {
+            return true;
+        };
+        let before = array.len();
+        array.retain(|h| !is_jean_hook(h));
+        !(array.is_empty() && before > 0)
+    });
+}
+
+/// Outcome of rewriting Claude Code settings
+#[derive(Debug, Clone, Default, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct SettingsWriteResult {
+    /// Backup of the original file, made when rewriting would drop comments
+    pub backup_path: Option<PathBuf>,
+    /// Shown to the user when the rewrite changed more than the hooks
+    pub warning: Option<String>,
+}
+
+/// Claude Code settings as read from disk
+#[derive(Debug)]
+struct LoadedSettings {
+    value: Value,
+    /// The file only parsed as JSONC (comments or trailing commas)
+    is_jsonc: bool,
+}
+
+/// Remove `//` and `/* */` comments and trailing commas, leaving strings intact
+fn strip_jsonc(content: &str) -> String {
+    let mut out = String::with_capacity(content.len());
+    let mut chars = content.chars().peekable();
+    let mut in_string = false;
+
+    while let Some(c) = chars.next() {
+        if in_string {
+            out.push(c);
+            match c {
+                '\\' => out.extend(chars.next()),
+                '"' => in_string = false,
+                _ => {}
+            }
+            continue;
+        }
+        match (c, chars.peek()) {
+            ('"', _) => {
+                in_string = true;
+                out.push(c);
+            }
+            ('/', Some('/')) => {
+                for n in chars.by_ref() {
+                    if n == '\n' {
+                        out.push(n);
+                        break;
+                    }
+                }
+            }
+            ('/', Some('*')) => {
+                chars.next();
+                let mut prev = ' ';
+                for n in chars.by_ref() {
+                    if prev == '*' && n == '/' {
+                        break;
+                    }
+                    prev = n;
+                }
+            }
+            (']' | '}', _) => {
+                // Drop a trailing comma before the closing bracket
+                let trimmed = out.trim_end().len();
+                if out[..trimmed].ends_with(',') {
+                    out.truncate(trimmed - 1);
+                }
+                out.push(c);
+            }
+            _ => out.push(c),
+        }
     }
 
-    let content = match fs::read_to_string(&settings_path) {
-        Ok(c) => c,
-        Err(_) => return false,
+    out
+}
+
+/// Parse settings content, accepting JSONC when it isn't strict JSON
+fn parse_claude_settings(content: &str) -> Result<LoadedSettings, String> {
+    let (value, is_jsonc) = match serde_json::from_str::<Value>(content) {
+        Ok(value) => (value, false),
+        Err(strict_err) => match serde_json::from_str::<Value>(&strip_jsonc(content)) {
+            Ok(value) => (value, true),
+            Err(_) => return Err(format!("Failed to parse Claude settings: {strict_err}")),
+        },
     };
 
-    // Check if our hook command is in the file
-    content.contains(".jean/hooks/context-writer.ts")
+    if !value.is_object() {
+        return Err("Claude settings must be a JSON object".to_string());
+    }
+    Ok(LoadedSettings { value, is_jsonc })
+}
+
+/// Read Claude Code settings, or an empty object if there are none yet
+fn read_claude_settings(settings_path: &Path) -> Result<LoadedSettings, String> {
+    if !settings_path.exists() {
+        return Ok(LoadedSettings {
+            value: serde_json::json!({}),
+            is_jsonc: false,
+        });
+    }
+    let content = fs::read_to_string(settings_path)
+        .map_err(|e| format!("Failed to read Claude settings: {e}"))?;
+    parse_claude_settings(&content).map_err(|e| format!("{e} ({})", settings_path.display()))
+}
+
+/// Write Claude Code settings back to disk
+///
+/// Comments can't survive the rewrite, so JSONC files are first copied to
+/// `settings.json.bak` and a warning is returned.
+fn write_claude_settings(
+    settings_path: &Path,
+    settings: &LoadedSettings,
+) -> Result<SettingsWriteResult, String> {
+    let mut result = SettingsWriteResult::default();
+    if settings.is_jsonc {
+        let backup_path = settings_path.with_extension("json.bak");
+        fs::copy(settings_path, &backup_path)
+            .map_err(|e| format!("Failed to back up Claude settings: {e}"))?;
+        log::warn!(
+            "Claude settings contained comments; original backed up to {}",
+            backup_path.display()
+        );
+        result.warning = Some(format!(
+            "Comments in {} were removed. The original was backed up to {}",
+            settings_path.display(),
+            backup_path.display()
+        ));
+        result.backup_path = Some(backup_path);
+    }
+
+    let output = serde_json::to_string_pretty(&settings.value)
+        .map_err(|e| format!("Failed to serialize settings: {e}"))?;
+    fs::write(settings_path, output)
+        .map_err(|e| format!("Failed to write Claude settings: {e}"))?;
+    Ok(result)
+}
+
+/// Get the events the Jean hook is installed for in Claude Code settings
+pub fn installed_hook_events() -> Vec<HookEvent> {
+    get_claude_settings_path()
+        .and_then(|path| read_claude_settings(&path).ok())
+        .map(|settings| events_in_settings(&settings.value))
+        .unwrap_or_default()
 }
 
-/// Install the Jean hook in Claude Code settings
-pub fn install_hook() -> Result<(), String> {
+/// Check if the Jean hook is installed for any event in Claude Code settings
+pub fn is_hook_installed() -> bool {
+    !installed_hook_events().is_empty()
+}
+
+/// Install the Jean hook in Claude Code settings for the given events
+///
+/// Defaults to `Stop` only when no events are given. The hook is removed from
+/// any event not in the list, so reinstalling with fewer events narrows it.
+/// The script runs with the first of Bun, Node.js or Deno found on PATH.
+pub fn install_hook(events: Vec<HookEvent>) -> Result<SettingsWriteResult, String> {
+    let events = if events.is_empty() {
+        vec![HookEvent::Stop]
+    } else {
+        events
+    };
+
+    let runtime = HookRuntime::detect()
+        .ok_or("No JavaScript runtime found for the context hook. Install Bun, Node.js or Deno")?;
+    log::trace!("Installing context hook for {}", runtime.display_name());
+
     // 1. Create the hook script
     let hooks_dir = get_jean_hooks_dir().ok_or("Could not determine home directory")?;
     fs::create_dir_all(&hooks_dir)
         .map_err(|e| format!("Failed to create hooks directory: {e}"))?;
 
-    let script_path = get_hook_script_path().ok_or("Could not determine hook script path")?;
-    fs::write(&script_path, HOOK_SCRIPT)
+    let script_path =
+        get_hook_script_path(runtime).ok_or("Could not determine hook script path")?;
+    fs::write(&script_path, runtime.script())
         .map_err(|e| format!("Failed to write hook script: {e}"))?;
 
+    // Drop the other variant left by an install with a different runtime
+    for other in HookRuntime::PREFERENCE {
+        if other.script_name() != runtime.script_name() {
+            if let Some(path) = get_hook_script_path(other) {
+                let _ = fs::remove_file(path);
+            }
+        }
+    }
+    save_installed_runtime(runtime)?;
+
     // Make script executable
     #[cfg(unix)]
     {
@@ -193,122 +680,201 @@ pub fn install_hook() -> Result<(), String> {
     }
 
     // 2. Update Claude Code settings
-    let settings_path = get_claude_settings_path().ok_or("Could not determine Claude settings path")?;
-
-    // Read existing settings or create new
-    let mut settings: Value = if settings_path.exists() {
-        let content = fs::read_to_string(&settings_path)
-            .map_err(|e| format!("Failed to read Claude settings: {e}"))?;
-        serde_json::from_str(&content)
-            .map_err(|e| format!("Failed to parse Claude settings: {e}"))?
-    } else {
-        // Ensure .claude directory exists
-        if let Some(parent) = settings_path.parent() {
-            fs::create_dir_all(parent)
-                .map_err(|e| format!("Failed to create .claude directory: {e}"))?;
-        }
-        serde_json::json!({})
-    };
+    let settings_path =
+        get_claude_settings_path().ok_or("Could not determine Claude settings path")?;
 
-    // Get or create hooks object
-    let hooks = settings
-        .as_object_mut()
-        .ok_or("Settings is not an object")?
-        .entry("hooks")
-        .or_insert(serde_json::json!({}));
+    // Ensure .claude directory exists
+    if let Some(parent) = settings_path.parent() {
+        fs::create_dir_all(parent)
+            .map_err(|e| format!("Failed to create .claude directory: {e}"))?;
+    }
+    let mut settings = read_claude_settings(&settings_path)?;
 
-    // Get or create Stop array
-    let stop_hooks = hooks
-        .as_object_mut()
-        .ok_or("Hooks is not an object")?
-        .entry("Stop")
-        .or_insert(serde_json::json!([]));
+    let hook_command = hook_command(runtime);
+    remove_hooks_from_settings(&mut settings.value, &events);
+    for event in events {
+        add_hook_to_settings(&mut settings.value, event, &hook_command)?;
+    }
 
-    let stop_array = stop_hooks
-        .as_array_mut()
-        .ok_or("Stop is not an array")?;
-
-    // Check if our hook already exists
-    let hook_command = format!("bun {}/.jean/hooks/context-writer.ts", std::env::var("HOME").unwrap_or_default());
-    let hook_exists = stop_array.iter().any(|h| {
-        h.get("hooks")
-            .and_then(|hooks| hooks.as_array())
-            .map(|hooks| hooks.iter().any(|cmd| {
-                cmd.get("command")
-                    .and_then(|c| c.as_str())
-                    .map(|c| c.contains(".jean/hooks/context-writer.ts"))
-                    .unwrap_or(false)
-            }))
-            .unwrap_or(false)
-    });
+    write_claude_settings(&settings_path, &settings)
+}
 
-    if !hook_exists {
-        // Add our hook
-        let new_hook = serde_json::json!({
-            "matcher": "",
-            "hooks": [{
-                "type": "command",
-                "command": hook_command
-            }]
-        });
-        stop_array.push(new_hook);
+/// Uninstall the Jean hook from all events in Claude Code settings
+pub fn uninstall_hook() -> Result<SettingsWriteResult, String> {
+    let settings_path =
+        get_claude_settings_path().ok_or("Could not determine Claude settings path")?;
+
+    if !settings_path.exists() {
+        return Ok(SettingsWriteResult::default()); // Nothing to uninstall
     }
 
-    // Write settings back
-    let output = serde_json::to_string_pretty(&settings)
-        .map_err(|e| format!("Failed to serialize settings: {e}"))?;
-    fs::write(&settings_path, output)
-        .map_err(|e| format!("Failed to write Claude settings: {e}"))?;
+    let mut settings = read_claude_settings(&settings_path)?;
+    remove_hooks_from_settings(&mut settings.value, &[]);
+    let result = write_claude_settings(&settings_path, &settings)?;
 
-    Ok(())
+    // Remove the script for the runtime it was installed with, or every
+    // variant if that wasn't recorded (installs from older versions)
+    let runtimes = match load_installed_runtime() {
+        Some(runtime) => vec![runtime],
+        None => HookRuntime::PREFERENCE.to_vec(),
+    };
+    for runtime in runtimes {
+        if let Some(script_path) = get_hook_script_path(runtime) {
+            let _ = fs::remove_file(script_path); // Ignore errors
+        }
+    }
+    if let Some(runtime_file) = get_runtime_file_path() {
+        let _ = fs::remove_file(runtime_file);
+    }
+
+    Ok(result)
 }
 
-/// Uninstall the Jean hook from Claude Code settings
-pub fn uninstall_hook() -> Result<(), String> {
-    let settings_path = get_claude_settings_path().ok_or("Could not determine Claude settings path")?;
+/// Result of a single hook health check
+#[derive(Debug, Clone, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct HookCheck {
+    /// Check identifier: "registered", "script", "runtime" or "context_dir"
+    pub id: String,
+    pub ok: bool,
+    /// What was found, or what's missing
+    pub message: String,
+}
 
-    if !settings_path.exists() {
-        return Ok(()); // Nothing to uninstall
+impl HookCheck {
+    fn new(id: &str, result: Result<String, String>) -> Self {
+        let (ok, message) = match result {
+            Ok(message) => (true, message),
+            Err(message) => (false, message),
+        };
+        Self {
+            id: id.to_string(),
+            ok,
+            message,
+        }
     }
+}
 
-    let content = fs::read_to_string(&settings_path)
-        .map_err(|e| format!("Failed to read Claude settings: {e}"))?;
+/// Health of the installed context-writer hook
+#[derive(Debug, Clone, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct HookHealth {
+    /// Every check passed
+    pub healthy: bool,
+    pub checks: Vec<HookCheck>,
+    /// When the hook last wrote context data (Unix seconds), if ever
+    pub last_data_written_at: Option<u64>,
+    /// Context data was written within the last day
+    pub recently_written: bool,
+}
 
-    let mut settings: Value = serde_json::from_str(&content)
-        .map_err(|e| format!("Failed to parse Claude settings: {e}"))?;
-
-    // Navigate to hooks.Stop array
-    if let Some(hooks) = settings.get_mut("hooks") {
-        if let Some(stop_hooks) = hooks.get_mut("Stop") {
-            if let Some(stop_array) = stop_hooks.as_array_mut() {
-                // Remove any hooks containing our script
-                stop_array.retain(|h| {
-                    !h.get("hooks")
-                        .and_then(|hooks| hooks.as_array())
-                        .map(|hooks| hooks.iter().any(|cmd| {
-                            cmd.get("command")
-                                .and_then(|c| c.as_str())
-                                .map(|c| c.contains(".jean/hooks/context-writer.ts"))
-                                .unwrap_or(false)
-                        }))
-                        .unwrap_or(false)
-                });
-            }
+fn check_registered() -> Result<String, String> {
+    let events = installed_hook_events();
+    if events.is_empty() {
+        return Err("Hook is not registered in ~/.claude/settings.json".to_string());
+    }
+    let names: Vec<&str> = events.iter().map(|e| e.settings_key()).collect();
+    Ok(format!("Registered for {}", names.join(", ")))
+}
+
+fn check_script(script_path: &Path) -> Result<String, String> {
+    let metadata = fs::metadata(script_path)
+        .map_err(|_| format!("Hook script not found at {}", script_path.display()))?;
+    if !metadata.is_file() {
+        return Err(format!("{} is not a file", script_path.display()));
+    }
+
+    #[cfg(unix)]
+    {
+        use std::os::unix::fs::PermissionsExt;
+        if metadata.permissions().mode() & 0o111 == 0 {
+            return Err(format!(
+                "Hook script at {} is not executable",
+                script_path.display()
+            ));
         }
     }
 
-    // Write settings back
-    let output = serde_json::to_string_pretty(&settings)
-        .map_err(|e| format!("Failed to serialize settings: {e}"))?;
-    fs::write(&settings_path, output)
-        .map_err(|e| format!("Failed to write Claude settings: {e}"))?;
+    Ok(format!("Hook script found at {}", script_path.display()))
+}
+
+fn check_runtime(runtime: HookRuntime) -> Result<String, String> {
+    let name = runtime.display_name();
+    let executable = runtime.executable();
+    let path = crate::platform::find_executable(executable)
+        .ok_or_else(|| format!("{name} not found on PATH"))?;
+    let output = Command::new(&path)
+        .arg("--version")
+        .output()
+        .map_err(|e| format!("Failed to run {executable}: {e}"))?;
 
-    // Optionally remove the script file
-    if let Some(script_path) = get_hook_script_path() {
-        let _ = fs::remove_file(script_path); // Ignore errors
+    if !output.status.success() {
+        let stderr = String::from_utf8_lossy(&output.stderr);
+        return Err(format!("{executable} --version failed: {}", stderr.trim()));
     }
+    // `deno --version` prints several lines; the first has the version
+    let stdout = String::from_utf8_lossy(&output.stdout);
+    let version = stdout.lines().next().unwrap_or_default().trim();
+    Ok(format!("{name} {version}"))
+}
 
-    Ok(())
+fn check_context_dir(dir: &Path) -> Result<String, String> {
+    fs::create_dir_all(dir).map_err(|e| format!("Cannot create {}: {e}", dir.display()))?;
+
+    let probe = dir.join(".write-test");
+    fs::write(&probe, b"").map_err(|e| format!("{} is not writable: {e}", dir.display()))?;
+    let _ = fs::remove_file(&probe);
+
+    Ok(format!("{} is writable", dir.display()))
+}
+
+/// Modification time (Unix seconds) of the newest context data file
+fn last_data_written_at(dir: &Path) -> Option<u64> {
+    fs::read_dir(dir)
+        .ok()?
+        .flatten()
+        .filter(|entry| entry.path().extension().is_some_and(|ext| ext == "json"))
+        .filter_map(|entry| entry.metadata().ok()?.modified().ok())
+        .filter_map(|modified| modified.duration_since(UNIX_EPOCH).ok())
+        .map(|d| d.as_secs())
+        .max()
+}
+
+/// Verify the installed hook can actually run and write context data
+///
+/// The runtime checked is the one the registered command references, falling
+/// back to the one recorded at install time (Bun for older installs).
+/// Each check reports separately so the UI can say exactly what's missing.
+pub fn verify_hook() -> Result<HookHealth, String> {
+    let runtime = get_claude_settings_path()
+        .and_then(|path| read_claude_settings(&path).ok())
+        .and_then(|settings| runtime_in_settings(&settings.value))
+        .or_else(load_installed_runtime)
+        .unwrap_or(HookRuntime::Bun);
+    let script_path =
+        get_hook_script_path(runtime).ok_or("Could not determine hook script path")?;
+    let data_dir = get_context_data_dir().ok_or("Could not determine home directory")?;
+
+    let checks = vec![
+        HookCheck::new("registered", check_registered()),
+        HookCheck::new("script", check_script(&script_path)),
+        HookCheck::new("runtime", check_runtime(runtime)),
+        HookCheck::new("context_dir", check_context_dir(&data_dir)),
+    ];
+
+    let last_data_written_at = last_data_written_at(&data_dir);
+    let now = SystemTime::now()
+        .duration_since(UNIX_EPOCH)
+        .map(|d| d.as_secs())
+        .unwrap_or(0);
+
+    Ok(HookHealth {
+        healthy: checks.iter().all(|c| c.ok),
+        checks,
+        last_data_written_at,
+        recently_written: last_data_written_at
+            .is_some_and(|t| now.saturating_sub(t) < RECENT_DATA_SECS),
+    })
 }
 
 #[cfg(test)]
@@ -322,4 +888,141 @@ mod tests {
         assert!(HOOK_SCRIPT.contains("Bun.stdin.json()"));
         assert!(HOOK_SCRIPT.contains("contextPercentage"));
     }
+
+    #[test]
+    fn test_install_and_remove_hook_events() {
+        let command = "bun /home/me/.jean/hooks/context-writer.ts";
+        let other = serde_json::json!({
+            "matcher": "",
+            "hooks": [{ "type": "command", "command": "echo done" }]
+        });
+        let mut settings = serde_json::json!({ "hooks": { "Stop": [other.clone()] } });
+
+        add_hook_to_settings(&mut settings, HookEvent::Stop, command).unwrap();
+        add_hook_to_settings(&mut settings, HookEvent::PostToolUse, command).unwrap();
+        // Adding twice doesn't duplicate
+        add_hook_to_settings(&mut settings, HookEvent::PostToolUse, command).unwrap();
+        assert_eq!(
+            settings["hooks"]["PostToolUse"].as_array().unwrap().len(),
+            1
+        );
+        assert_eq!(
+            events_in_settings(&settings),
+            vec![HookEvent::Stop, HookEvent::PostToolUse]
+        );
+
+        // Narrowing to Stop drops the now-empty PostToolUse array
+        remove_hooks_from_settings(&mut settings, &[HookEvent::Stop]);
+        assert_eq!(events_in_settings(&settings), vec![HookEvent::Stop]);
+        assert!(settings["hooks"].get("PostToolUse").is_none());
+
+        // Uninstalling keeps other tools' hooks
+        remove_hooks_from_settings(&mut settings, &[]);
+        assert!(events_in_settings(&settings).is_empty());
+        assert_eq!(settings["hooks"]["Stop"], serde_json::json!([other]));
+    }
+
+    #[test]
+    fn test_check_script_and_context_dir() {
+        let dir = tempfile::tempdir().unwrap();
+        let script = dir.path().join("context-writer.ts");
+
+        assert!(check_script(&script).unwrap_err().contains("not found"));
+
+        fs::write(&script, HOOK_SCRIPT).unwrap();
+        #[cfg(unix)]
+        {
+            use std::os::unix::fs::PermissionsExt;
+            fs::set_permissions(&script, fs::Permissions::from_mode(0o644)).unwrap();
+            let err = check_script(&script).unwrap_err();
+            assert!(err.contains("not executable"));
+            fs::set_permissions(&script, fs::Permissions::from_mode(0o755)).unwrap();
+        }
+        assert!(check_script(&script).is_ok());
+
+        let data_dir = dir.path().join("context-data");
+        assert!(check_context_dir(&data_dir).is_ok());
+        assert_eq!(last_data_written_at(&data_dir), None);
+
+        fs::write(data_dir.join("session.json"), "{}").unwrap();
+        assert!(last_data_written_at(&data_dir).is_some());
+    }
+
+    #[test]
+    fn test_hook_command_has_no_baked_in_home() {
+        let home = Path::new("/home/me");
+        let command = hook_command_for(HookRuntime::Bun, false, Some(home));
+        assert_eq!(command, "bun \"$HOME/.jean/hooks/context-writer.ts\"");
+        assert!(!command.contains("/home/me"));
+
+        let command = hook_command_for(HookRuntime::Bun, true, Some(Path::new(r"C:\Users\me")));
+        assert_eq!(command, "bun \"C:/Users/me/.jean/hooks/context-writer.ts\"");
+    }
+
+    #[test]
+    fn test_hook_command_per_runtime_round_trips() {
+        let command = hook_command_for(HookRuntime::Node, false, None);
+        assert_eq!(command, "node \"$HOME/.jean/hooks/context-writer.mjs\"");
+        assert!(HOOK_SCRIPT_NODE.contains("process.stdin"));
+        assert!(!HOOK_SCRIPT_NODE.contains("Bun."));
+
+        for runtime in HookRuntime::PREFERENCE {
+            let command = hook_command_for(runtime, false, None);
+            assert!(is_jean_command(&serde_json::json!({ "command": command })));
+            assert_eq!(HookRuntime::from_command(&command), Some(runtime));
+
+            let mut settings = serde_json::json!({});
+            add_hook_to_settings(&mut settings, HookEvent::Stop, &command).unwrap();
+            assert_eq!(runtime_in_settings(&settings), Some(runtime));
+        }
+
+        // Absolute runtime paths count too
+        assert_eq!(
+            HookRuntime::from_command("/usr/local/bin/node /x/.jean/hooks/context-writer.mjs"),
+            Some(HookRuntime::Node)
+        );
+        assert_eq!(HookRuntime::from_command("python3 script.py"), None);
+    }
+
+    #[test]
+    fn test_reinstall_migrates_absolute_hook_command() {
+        let mut settings = serde_json::json!({ "hooks": { "Stop": [{
+            "matcher": "",
+            "hooks": [{ "type": "command", "command": "bun /home/me/.jean/hooks/context-writer.ts" }]
+        }] } });
+
+        let command = hook_command_for(HookRuntime::Bun, false, None);
+        add_hook_to_settings(&mut settings, HookEvent::Stop, &command).unwrap();
+        assert_eq!(
+            settings["hooks"]["Stop"],
+            serde_json::json!([{
+                "matcher": "",
+                "hooks": [{ "type": "command", "command": command }]
+            }])
+        );
+    }
+
+    #[test]
+    fn test_parse_jsonc_settings() {
+        let content = r#"{
+            // Model override
+            "model": "opus", /* inline */
+            "url": "https://example.com/a//b",
+            "quote": "say \"hi\" // not a comment",
+            "hooks": { "Stop": [], },
+        }"#;
+        let settings = parse_claude_settings(content).unwrap();
+        assert!(settings.is_jsonc);
+        assert_eq!(settings.value["url"], "https://example.com/a//b");
+        assert_eq!(settings.value["quote"], "say \"hi\" // not a comment");
+        assert_eq!(settings.value["hooks"]["Stop"], serde_json::json!([]));
+
+        let strict = parse_claude_settings(r#"{"model": "opus"}"#).unwrap();
+        assert!(!strict.is_jsonc);
+
+        assert!(parse_claude_settings("[1, 2]")
+            .unwrap_err()
+            .contains("must be a JSON object"));
+        assert!(parse_claude_settings("{ not json").is_err());
+    }
 }
@@ -5,10 +5,12 @@
 //! - Usage limits from Anthropic API (5-hour and 7-day windows)
 //! - Session usage aggregation (tokens, cost, context percentage)
 //! - Context hook for accurate context window tracking
+//! - Live `context:update` events when the hook writes new data
 
 pub mod api;
 pub mod commands;
 pub mod context_hook;
+pub mod context_watcher;
 pub mod credentials;
 pub mod hook_installer;
 pub mod types;
@@ -1,5 +1,7 @@
 use serde::{Deserialize, Serialize};
 
+use crate::provider_usage::types::DEFAULT_PRICING;
+
 /// A single usage limit (5-hour or 7-day)
 #[derive(Debug, Clone, Serialize, Deserialize)]
 #[serde(rename_all = "camelCase")]
@@ -48,6 +50,60 @@ impl From<UsageLimitsApiResponse> for UsageLimits {
     }
 }
 
+/// Error from fetching usage limits
+///
+/// Expired credentials and rate limiting get their own variants so the UI can
+/// prompt a re-login or say when data will be back, instead of showing a
+/// generic network error.
+#[derive(Debug, Clone, PartialEq, Serialize)]
+#[serde(tag = "kind", rename_all = "snake_case")]
+pub enum UsageLimitsError {
+    CredentialsExpired,
+    RateLimited {
+        /// ISO timestamp when the API accepts requests again, if known
+        #[serde(rename = "resetsAt")]
+        resets_at: Option<String>,
+    },
+    Other {
+        message: String,
+    },
+}
+
+impl std::fmt::Display for UsageLimitsError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Self::CredentialsExpired => {
+                write!(
+                    f,
+                    "Claude credentials expired. Run /login in Claude Code to re-authenticate"
+                )
+            }
+            Self::RateLimited {
+                resets_at: Some(resets_at),
+            } => write!(f, "Usage API rate limited until {resets_at}"),
+            Self::RateLimited { resets_at: None } => write!(f, "Usage API rate limited"),
+            Self::Other { message } => write!(f, "{message}"),
+        }
+    }
+}
+
+impl From<String> for UsageLimitsError {
+    fn from(message: String) -> Self {
+        Self::Other { message }
+    }
+}
+
+/// Default context window size used when no per-model override is configured
+pub const DEFAULT_CONTEXT_WINDOW_TOKENS: u64 = 200_000;
+
+/// Compute the context percentage (0-100) for a token count and window size
+pub fn context_percentage(context_tokens: u64, max_context_tokens: u64) -> f64 {
+    if max_context_tokens == 0 {
+        return 0.0;
+    }
+    ((context_tokens as f64 / max_context_tokens as f64) * 100.0).min(100.0)
+}
+
 /// Session usage summary for a specific session
 #[derive(Debug, Clone, Serialize, Deserialize)]
 #[serde(rename_all = "camelCase")]
@@ -58,7 +114,11 @@ pub struct SessionUsage {
     pub total_output_tokens: u64,
     /// Total cache tokens (read + creation)
     pub total_cache_tokens: u64,
-    /// Context percentage (0-100) based on 200k max
+    /// Tokens in the current context window (last run's input + cache)
+    pub context_tokens: u64,
+    /// Context window size the percentage is measured against
+    pub context_max_tokens: u64,
+    /// Context percentage (0-100) of `context_max_tokens`
     pub context_percentage: f64,
     /// Estimated cost in USD
     pub estimated_cost_usd: f64,
@@ -78,13 +138,8 @@ impl SessionUsage {
         context_cache_read_tokens: u64,
         context_cache_creation_tokens: u64,
     ) -> Self {
-        const MAX_CONTEXT_TOKENS: f64 = 200_000.0;
-        // Sonnet 3.5 pricing (adjust for other models if needed)
-        const INPUT_COST_PER_1M: f64 = 3.0;
-        const OUTPUT_COST_PER_1M: f64 = 15.0;
-        // Cache tokens are cheaper
-        const CACHE_READ_COST_PER_1M: f64 = 0.30;
-        const CACHE_CREATION_COST_PER_1M: f64 = 3.75;
+        // Sonnet pricing (adjust for other models if needed)
+        let pricing = DEFAULT_PRICING;
 
         let total_cache_tokens = total_cache_read_tokens + total_cache_creation_tokens;
 
@@ -92,23 +147,60 @@ impl SessionUsage {
         // This matches Claude Code's calculation: input + cache_read + cache_creation
         let context_tokens =
             context_input_tokens + context_cache_read_tokens + context_cache_creation_tokens;
-        let context_percentage = ((context_tokens as f64 / MAX_CONTEXT_TOKENS) * 100.0).min(100.0);
+        let context_percentage = context_percentage(context_tokens, DEFAULT_CONTEXT_WINDOW_TOKENS);
 
         // Cost = sum of all tokens across all runs
-        let estimated_cost_usd = (total_input_tokens as f64 * INPUT_COST_PER_1M
-            + total_output_tokens as f64 * OUTPUT_COST_PER_1M
-            + total_cache_read_tokens as f64 * CACHE_READ_COST_PER_1M
-            + total_cache_creation_tokens as f64 * CACHE_CREATION_COST_PER_1M)
+        let estimated_cost_usd = (total_input_tokens as f64 * pricing.input_per_1m
+            + total_output_tokens as f64 * pricing.output_per_1m
+            + total_cache_read_tokens as f64 * pricing.cache_read_per_1m
+            + total_cache_creation_tokens as f64 * pricing.cache_creation_per_1m)
             / 1_000_000.0;
 
         Self {
             total_input_tokens,
             total_output_tokens,
             total_cache_tokens,
+            context_tokens,
+            context_max_tokens: DEFAULT_CONTEXT_WINDOW_TOKENS,
             context_percentage,
             estimated_cost_usd,
         }
     }
+
+    /// Re-measure the context percentage against a different window size
+    pub fn with_context_window(mut self, max_context_tokens: u64) -> Self {
+        self.context_max_tokens = max_context_tokens;
+        self.context_percentage = context_percentage(self.context_tokens, max_context_tokens);
+        self
+    }
+}
+
+/// Usage totals for one day
+#[derive(Debug, Clone, PartialEq, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct UsageDay {
+    /// Local date (YYYY-MM-DD)
+    pub date: String,
+    pub sessions: u32,
+    pub cost_usd: f64,
+    pub context_tokens: u64,
+}
+
+/// Usage across all sessions and projects, from the context hook's data files
+///
+/// Each session is counted once, on the day it was last updated, with its
+/// cumulative cost at that point.
+#[derive(Debug, Clone, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct UsageSummary {
+    pub session_count: u32,
+    pub total_cost_usd: f64,
+    /// Sum of each session's last context window size
+    pub total_context_tokens: u64,
+    /// Per-day buckets, oldest first
+    pub days: Vec<UsageDay>,
+    /// Malformed or partial data files that were ignored
+    pub skipped_files: u32,
 }
 
 /// Cached usage limits with timestamp
@@ -145,6 +237,42 @@ pub struct OAuthCredentials {
     pub access_token: String,
     #[allow(dead_code)]
     pub refresh_token: Option<String>,
-    #[allow(dead_code)]
+    /// Expiry as a Unix timestamp in milliseconds
     pub expires_at: Option<u64>,
 }
+
+/// Validity of the stored Claude OAuth credentials
+#[derive(Debug, Clone, PartialEq, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct ClaudeAuthStatus {
+    /// Credentials exist and have not expired
+    pub valid: bool,
+    /// Token expiry as a Unix timestamp in milliseconds, if known
+    pub expires_at: Option<u64>,
+    /// The token has expired or is about to, so the user should log in again
+    pub needs_refresh: bool,
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_context_window_override_changes_percentage() {
+        // 150k tokens in context for the last run
+        let usage = SessionUsage::from_tokens_with_context(0, 0, 0, 0, 50_000, 100_000, 0);
+        assert_eq!(usage.context_tokens, 150_000);
+        assert_eq!(usage.context_max_tokens, DEFAULT_CONTEXT_WINDOW_TOKENS);
+        assert_eq!(usage.context_percentage, 75.0);
+
+        let usage = usage.with_context_window(1_000_000);
+        assert_eq!(usage.context_max_tokens, 1_000_000);
+        assert_eq!(usage.context_percentage, 15.0);
+    }
+
+    #[test]
+    fn test_context_percentage_clamps_and_handles_zero() {
+        assert_eq!(context_percentage(300_000, 200_000), 100.0);
+        assert_eq!(context_percentage(1_000, 0), 0.0);
+    }
+}
@@ -5,7 +5,8 @@ use tauri::{AppHandle, Emitter};
 
 use super::config::{ensure_gh_cli_dir, get_gh_cli_binary_path};
 
-/// GitHub API URL for releases
+/// GitHub API URL for releases (gh is always downloaded from github.com, even for
+/// GitHub Enterprise users)
 const GITHUB_RELEASES_API: &str = "https://api.github.com/repos/cli/cli/releases";
 
 /// Status of the GitHub CLI installation
@@ -199,6 +200,7 @@ fn get_gh_platform() -> Result<(&'static str, &'static str), String> {
 #[tauri::command]
 pub async fn install_gh_cli(app: AppHandle, version: Option<String>) -> Result<(), String> {
     log::trace!("Installing GitHub CLI, version: {:?}", version);
+    let _install = crate::ai_cli::InstallLock::acquire("GitHub CLI")?;
 
     // Check if any Claude processes are running - Claude may use gh for GitHub operations
     let running_sessions = crate::chat::registry::get_running_sessions();
@@ -505,6 +507,26 @@ pub struct GhAuthStatus {
     pub authenticated: bool,
     /// Error message if authentication check failed
     pub error: Option<String>,
+    /// GitHub host (github.com or a GitHub Enterprise host)
+    pub host: Option<String>,
+}
+
+/// Pick the active host from `gh auth status` output
+///
+/// gh lists every host it is logged in to ("Logged in to github.com account ...").
+/// `preferred` (the configured self-hosted instance) wins when it is among them.
+/// glab uses the same wording, so its auth check shares this parser.
+pub(crate) fn parse_auth_host(output: &str, preferred: Option<&str>) -> Option<String> {
+    let hosts: Vec<&str> = output
+        .lines()
+        .filter_map(|line| line.split("Logged in to ").nth(1))
+        .filter_map(|rest| rest.split_whitespace().next())
+        .collect();
+    hosts
+        .iter()
+        .find(|host| Some(**host) == preferred)
+        .or_else(|| hosts.first())
+        .map(|host| host.to_string())
 }
 
 /// Check if GitHub CLI is authenticated by running `gh auth status`
@@ -518,6 +540,7 @@ pub async fn check_gh_cli_auth(app: AppHandle) -> Result<GhAuthStatus, String> {
         return Ok(GhAuthStatus {
             authenticated: false,
             error: Some("GitHub CLI not installed".to_string()),
+            host: None,
         });
     }
 
@@ -533,9 +556,16 @@ pub async fn check_gh_cli_auth(app: AppHandle) -> Result<GhAuthStatus, String> {
     if output.status.success() {
         let stdout = String::from_utf8_lossy(&output.stdout).trim().to_string();
         log::trace!("GitHub CLI auth check successful: {}", stdout);
+
+        // Older gh versions print the status to stderr
+        let stderr = String::from_utf8_lossy(&output.stderr);
+        let enterprise_host = crate::projects::git::github_enterprise_host();
+        let host = parse_auth_host(&format!("{stdout}\n{stderr}"), enterprise_host.as_deref());
+
         Ok(GhAuthStatus {
             authenticated: true,
             error: None,
+            host,
         })
     } else {
         let stderr = String::from_utf8_lossy(&output.stderr).trim().to_string();
@@ -543,6 +573,7 @@ pub async fn check_gh_cli_auth(app: AppHandle) -> Result<GhAuthStatus, String> {
         Ok(GhAuthStatus {
             authenticated: false,
             error: Some(stderr),
+            host: None,
         })
     }
 }
@@ -685,3 +716,24 @@ pub async fn list_github_repos(
     log::trace!("Found {} GitHub repositories", remote_repos.len());
     Ok(remote_repos)
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_parse_auth_host() {
+        let output = "github.com\n  ✓ Logged in to github.com account octocat (keyring)\n\
+                      github.mycorp.com\n  ✓ Logged in to github.mycorp.com account octocat (keyring)";
+        assert_eq!(parse_auth_host(output, None).as_deref(), Some("github.com"));
+        assert_eq!(
+            parse_auth_host(output, Some("github.mycorp.com")).as_deref(),
+            Some("github.mycorp.com")
+        );
+
+        // Older gh output format
+        let legacy = "github.com\n  ✓ Logged in to github.com as octocat (oauth_token)";
+        assert_eq!(parse_auth_host(legacy, None).as_deref(), Some("github.com"));
+        assert_eq!(parse_auth_host("not logged in", None), None);
+    }
+}
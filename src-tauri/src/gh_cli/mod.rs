@@ -7,3 +7,4 @@ mod commands;
 mod config;
 
 pub use commands::*;
+pub use config::get_gh_cli_binary_path;
@@ -221,6 +221,7 @@ fn get_glab_platform() -> Result<(&'static str, &'static str), String> {
 #[tauri::command]
 pub async fn install_glab_cli(app: AppHandle, version: Option<String>) -> Result<(), String> {
     log::trace!("Installing GitLab CLI, version: {:?}", version);
+    let _install = crate::ai_cli::InstallLock::acquire("GitLab CLI")?;
 
     // Check if any Claude processes are running
     let running_sessions = crate::chat::registry::get_running_sessions();
@@ -534,16 +535,12 @@ pub async fn check_glab_cli_auth(app: AppHandle) -> Result<GlabAuthStatus, Strin
         let stdout = String::from_utf8_lossy(&output.stdout).trim().to_string();
         log::trace!("GitLab CLI auth check successful: {}", stdout);
 
-        // Try to extract host from output (e.g., "Logged in to gitlab.com as username")
-        let host = stdout
-            .lines()
-            .find(|line| line.contains("Logged in to"))
-            .and_then(|line| {
-                line.split("Logged in to ")
-                    .nth(1)
-                    .and_then(|s| s.split_whitespace().next())
-                    .map(|s| s.to_string())
-            });
+        // Try to extract host from output (e.g., "Logged in to gitlab.com as username").
+        // glab prints the status to stderr, so check both streams.
+        let stderr = String::from_utf8_lossy(&output.stderr);
+        let self_hosted = crate::projects::git::gitlab_self_hosted_host();
+        let host =
+            crate::gh_cli::parse_auth_host(&format!("{stdout}\n{stderr}"), self_hosted.as_deref());
 
         Ok(GlabAuthStatus {
             authenticated: true,
@@ -7,3 +7,4 @@ mod commands;
 mod config;
 
 pub use commands::*;
+pub use config::get_glab_cli_binary_path;
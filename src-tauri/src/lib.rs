@@ -20,6 +20,7 @@ mod glab_cli;
 mod platform;
 mod projects;
 mod terminal;
+mod util;
 
 // Validation functions
 fn validate_filename(filename: &str) -> Result<(), String> {
@@ -143,6 +144,26 @@ pub struct AppPreferences {
     pub default_ai_provider: String, // Default AI CLI provider: claude, gemini, codex
     #[serde(default = "default_show_usage_status_bar")]
     pub show_usage_status_bar: bool, // Show Claude usage status bar (cost, context, limits)
+    #[serde(default)]
+    pub context_window_overrides: std::collections::HashMap<String, u64>, // Per-model context window size overrides (model -> tokens)
+    #[serde(default)]
+    pub wsl_distro: Option<String>, // WSL distribution to run Claude in on Windows (None = default distro)
+    #[serde(default)]
+    pub gemini_plain_text_output: bool, // Run Gemini with `-o text` instead of stream-json (for incompatible versions)
+    #[serde(default = "default_tail_poll_interval_ms")]
+    pub tail_poll_interval_ms: u64, // How often Codex/Kimi output is polled in ms (10-200, backs off while idle)
+    #[serde(default = "default_compress_run_logs")]
+    pub compress_run_logs: bool, // Gzip finished run logs older than a week on startup
+    #[serde(default)]
+    pub auto_archive_idle_days: u32, // Archive sessions idle this many days on startup (0 = disabled)
+    #[serde(default)]
+    pub load_jean_env: bool, // Pass the worktree's .jean.env to AI CLIs
+    #[serde(default)]
+    pub load_project_dotenv: bool, // Also pass the worktree's .env (not just .jean.env) to AI CLIs
+    #[serde(default)]
+    pub github_host: String, // GitHub Enterprise host (empty = GH_HOST env var, else github.com only)
+    #[serde(default)]
+    pub gitlab_host: String, // Self-hosted GitLab host (empty = GITLAB_HOST env var, else gitlab.com only)
 }
 
 fn default_auto_branch_naming() -> bool {
@@ -165,6 +186,10 @@ fn default_session_grouping_enabled() -> bool {
     true // Enabled by default
 }
 
+fn default_compress_run_logs() -> bool {
+    true // Enabled by default
+}
+
 fn default_session_naming_model() -> String {
     "haiku".to_string() // Use Haiku by default for fast, cheap session name generation
 }
@@ -205,6 +230,10 @@ fn default_remote_poll_interval() -> u64 {
     60 // 1 minute default for remote API calls (PR status, etc.)
 }
 
+fn default_tail_poll_interval_ms() -> u64 {
+    50 // 20 polls/second while output is streaming
+}
+
 fn default_keybindings() -> std::collections::HashMap<String, String> {
     let mut map = std::collections::HashMap::new();
     map.insert("focus_chat_input".to_string(), "mod+l".to_string());
@@ -542,6 +571,16 @@ impl Default for AppPreferences {
             workspace_folder: String::new(),
             default_ai_provider: default_ai_provider(),
             show_usage_status_bar: default_show_usage_status_bar(),
+            context_window_overrides: std::collections::HashMap::new(),
+            wsl_distro: None,
+            gemini_plain_text_output: false,
+            tail_poll_interval_ms: default_tail_poll_interval_ms(),
+            compress_run_logs: default_compress_run_logs(),
+            auto_archive_idle_days: 0,
+            load_jean_env: false,
+            load_project_dotenv: false,
+            github_host: String::new(),
+            gitlab_host: String::new(),
         }
     }
 }
@@ -712,6 +751,8 @@ async fn save_preferences(app: AppHandle, preferences: AppPreferences) -> Result
         format!("Failed to finalize preferences file: {e}")
     })?;
 
+    projects::git::set_host_preferences(&preferences.github_host, &preferences.gitlab_host);
+
     log::trace!("Successfully saved preferences to {prefs_path:?}");
     Ok(())
 }
@@ -1172,6 +1213,12 @@ pub fn run() {
                 app.package_info().name
             );
 
+            // Self-hosted GitHub/GitLab hosts, needed before any remote URL is parsed
+            let prefs = tauri::async_runtime::block_on(load_preferences(app.handle().clone()));
+            if let Ok(prefs) = prefs {
+                projects::git::set_host_preferences(&prefs.github_host, &prefs.gitlab_host);
+            }
+
             // Recover any incomplete runs from previous session (crash recovery)
             let app_handle = app.handle().clone();
             match chat::run_log::recover_incomplete_runs(&app_handle) {
@@ -1192,6 +1239,29 @@ pub fn run() {
                 }
             }
 
+            // Gzip old run logs in the background (after recovery, which may still need them)
+            let app_handle = app.handle().clone();
+            tauri::async_runtime::spawn(async move {
+                let enabled = load_preferences(app_handle.clone())
+                    .await
+                    .map(|prefs| prefs.compress_run_logs)
+                    .unwrap_or(true);
+                if !enabled {
+                    log::trace!("Run log compression is disabled");
+                    return;
+                }
+                let result = tauri::async_runtime::spawn_blocking(move || {
+                    chat::run_log::compress_completed_runs(
+                        &app_handle,
+                        chat::run_log::COMPRESS_RUNS_AFTER,
+                    )
+                })
+                .await;
+                if let Ok(Err(e)) = result {
+                    log::warn!("Failed to compress run logs: {e}");
+                }
+            });
+
             #[cfg(target_os = "macos")]
             {
                 log::trace!("Creating macOS app menu");
@@ -1281,6 +1351,9 @@ pub fn run() {
             app.manage(task_manager);
             log::trace!("Background task manager initialized");
 
+            // Push context hook updates to the frontend
+            claude_usage::context_watcher::start_context_watcher(app.handle().clone());
+
             Ok(())
         })
         .invoke_handler(tauri::generate_handler![
@@ -1304,6 +1377,10 @@ pub fn run() {
             projects::get_worktree,
             projects::create_worktree,
             projects::create_worktree_from_existing_branch,
+            projects::create_worktree_for_issue,
+            projects::preview_branch_name,
+            projects::post_pr_comment,
+            projects::validate_branch_name,
             projects::checkout_pr,
             projects::delete_worktree,
             projects::create_base_session,
@@ -1338,8 +1415,14 @@ pub fn run() {
             projects::rebase_worktree,
             projects::has_uncommitted_changes,
             projects::get_git_diff,
+            projects::get_worktree_status_batch,
             projects::git_pull,
             projects::git_push,
+            projects::git_stash_push,
+            projects::git_stash_pop,
+            projects::git_stash_list,
+            projects::prune_stale_worktrees,
+            projects::remove_stale_worktrees,
             projects::merge_worktree_to_base,
             projects::get_merge_conflicts,
             projects::fetch_and_merge_base,
@@ -1353,6 +1436,8 @@ pub fn run() {
             projects::list_github_issues,
             projects::search_github_issues,
             projects::get_github_issue,
+            projects::get_github_issue_summary,
+            projects::get_github_issue_comments,
             projects::load_issue_context,
             projects::list_loaded_issue_contexts,
             projects::remove_issue_context,
@@ -1365,6 +1450,7 @@ pub fn run() {
             projects::remove_pr_context,
             projects::get_pr_context_content,
             projects::get_issue_context_content,
+            projects::refresh_all_contexts,
             // Saved context commands
             projects::attach_saved_context,
             projects::remove_saved_context,
@@ -1387,6 +1473,12 @@ pub fn run() {
             terminal::stop_terminal,
             terminal::get_active_terminals,
             terminal::has_active_terminal,
+            terminal::list_terminals,
+            terminal::get_terminal_scrollback,
+            terminal::capture_terminal_output,
+            terminal::signal_terminal,
+            terminal::spawn_ai_terminal,
+            terminal::get_terminal_recording_path,
             terminal::get_run_script,
             terminal::kill_all_terminals,
             // Chat commands - Session management
@@ -1395,7 +1487,9 @@ pub fn run() {
             chat::get_session,
             chat::create_session,
             chat::rename_session,
+            chat::regenerate_session_name,
             chat::update_session_state,
+            chat::clear_pending_denials,
             chat::close_session,
             chat::archive_session,
             chat::unarchive_session,
@@ -1408,9 +1502,16 @@ pub fn run() {
             // Chat commands - Session-based messaging
             chat::send_chat_message,
             chat::clear_session_history,
+            chat::get_run_diff,
+            chat::revert_run_changes,
             chat::set_session_model,
+            chat::set_session_mcp_config,
             chat::set_session_thinking_level,
+            chat::set_worktree_default_model,
             chat::cancel_chat_message,
+            chat::cancel_session,
+            chat::stop_all_sessions,
+            chat::cancel_worktree_sessions,
             chat::has_running_sessions,
             chat::save_cancelled_message,
             chat::mark_plan_approved,
@@ -1420,6 +1521,8 @@ pub fn run() {
             chat::delete_pasted_image,
             // Chat commands - Text paste handling
             chat::save_pasted_text,
+            chat::attach_file_context,
+            chat::estimate_prompt_tokens,
             chat::delete_pasted_text,
             chat::read_pasted_text,
             // Chat commands - Plan file handling
@@ -1434,16 +1537,27 @@ pub fn run() {
             chat::read_context_file,
             chat::delete_context_file,
             chat::rename_saved_context,
+            chat::search_saved_contexts,
+            chat::search_transcripts,
+            chat::transfer::export_sessions_archive,
+            chat::transfer::import_sessions_archive,
+            chat::claude_import::import_claude_session,
             chat::generate_context_from_session,
             // Chat commands - Session digest (context recall)
             chat::generate_session_digest,
             // Chat commands - Debug info
             chat::get_session_debug_info,
+            chat::run_log::export_run_transcript,
             // Usage commands
             usage::get_usage_overview,
             // Chat commands - Session resume (detached process recovery)
             chat::resume_session,
+            chat::resume_session_stream,
             chat::check_resumable_sessions,
+            chat::recover_orphaned_sessions,
+            chat::cleanup_archived_sessions,
+            chat::auto_archive_idle_sessions,
+            chat::duplicate_session,
             // Chat commands - Multi-model delegation
             chat::execute_delegated_tasks,
             // Chat commands - Claude Orchestrator (intelligent delegation)
@@ -1452,6 +1566,7 @@ pub fn run() {
             // Claude CLI management commands
             claude_cli::check_claude_cli_installed,
             claude_cli::check_claude_cli_auth,
+            claude_cli::list_wsl_distributions,
             claude_cli::get_available_cli_versions,
             claude_cli::install_claude_cli,
             // GitHub CLI management commands
@@ -1480,6 +1595,10 @@ pub fn run() {
             ai_cli::kimi::commands::check_kimi_cli_installed,
             ai_cli::kimi::commands::check_kimi_cli_auth,
             ai_cli::kimi::commands::install_kimi_cli,
+            ai_cli::updates::check_all_cli_updates,
+            ai_cli::health::test_provider,
+            ai_cli::detect::check_all_clis,
+            ai_cli::models::list_models,
             // GitLab issues/MRs commands
             projects::list_gitlab_issues,
             projects::get_gitlab_issue,
@@ -1504,17 +1623,27 @@ pub fn run() {
             background_tasks::commands::set_remote_poll_interval,
             background_tasks::commands::get_remote_poll_interval,
             background_tasks::commands::trigger_immediate_remote_poll,
+            background_tasks::commands::get_github_rate_limit,
             // Claude usage commands
             claude_usage::commands::get_claude_usage_limits,
             claude_usage::commands::get_session_usage,
             claude_usage::commands::has_claude_credentials,
+            claude_usage::commands::check_claude_auth,
             claude_usage::commands::get_hook_context_data,
+            claude_usage::commands::get_session_context_data,
+            claude_usage::commands::list_all_context_data,
+            claude_usage::commands::get_claude_usage_summary,
             claude_usage::commands::is_context_hook_installed,
+            claude_usage::commands::get_context_hook_events,
+            claude_usage::commands::verify_context_hook,
             claude_usage::commands::install_context_hook,
             claude_usage::commands::uninstall_context_hook,
             // Multi-provider usage commands
             provider_usage::commands::get_provider_usage,
             provider_usage::commands::get_all_providers_usage,
+            provider_usage::history::get_usage_history,
+            provider_usage::alerts::set_usage_alert,
+            provider_usage::alerts::get_usage_alerts,
         ])
         .build(tauri::generate_context!())
         .expect("error building tauri application")
@@ -2,6 +2,11 @@
 
 #[cfg(windows)]
 use std::process::Command;
+use std::thread;
+use std::time::{Duration, Instant};
+
+/// How often to check whether a terminating process has exited
+const EXIT_POLL_INTERVAL: Duration = Duration::from_millis(50);
 
 /// Check if a process is still alive
 /// - Unix: Uses kill(pid, 0) to check
@@ -140,3 +145,249 @@ pub fn terminate_process(pid: u32) -> Result<(), String> {
     // Windows doesn't have SIGTERM, use TerminateProcess
     kill_process(pid)
 }
+
+/// Ask a process tree to shut down so it can clean up
+/// - Unix: Sends SIGTERM to the process group (falls back to the process)
+/// - Windows: Sends CTRL_BREAK to the process group
+#[cfg(unix)]
+pub fn request_process_tree_exit(pid: u32) -> Result<(), String> {
+    let result = unsafe { libc::kill(-(pid as i32), libc::SIGTERM) };
+    if result == 0 {
+        Ok(())
+    } else {
+        terminate_process(pid)
+    }
+}
+
+#[cfg(windows)]
+pub fn request_process_tree_exit(pid: u32) -> Result<(), String> {
+    use windows_sys::Win32::System::Console::{GenerateConsoleCtrlEvent, CTRL_BREAK_EVENT};
+
+    // Only reaches processes spawned with CREATE_NEW_PROCESS_GROUP (group id == pid)
+    let result = unsafe { GenerateConsoleCtrlEvent(CTRL_BREAK_EVENT, pid) };
+    if result != 0 {
+        Ok(())
+    } else {
+        Err(format!(
+            "Failed to send CTRL_BREAK to process group {}: {}",
+            pid,
+            std::io::Error::last_os_error()
+        ))
+    }
+}
+
+/// Send a signal to every process in a process group (Unix only)
+#[cfg(unix)]
+fn signal_process_group(pgid: u32, signal: libc::c_int) -> Result<(), String> {
+    let result = unsafe { libc::kill(-(pgid as i32), signal) };
+    if result == 0 {
+        Ok(())
+    } else {
+        Err(format!(
+            "Failed to signal process group {}: {}",
+            pgid,
+            std::io::Error::last_os_error()
+        ))
+    }
+}
+
+/// Check if a process group still has any members (Unix only)
+#[cfg(unix)]
+fn is_process_group_alive(pgid: u32) -> bool {
+    let result = unsafe { libc::killpg(pgid as i32, 0) };
+    if result == 0 {
+        return true;
+    }
+    let errno = std::io::Error::last_os_error().raw_os_error().unwrap_or(0);
+    errno == libc::EPERM
+}
+
+/// Check if a process, or anything left in its process group, is still alive
+///
+/// The group is `pgid` if given, else the one led by `pid`. A CLI can exit on
+/// SIGTERM while a subprocess that ignores it lingers in the group.
+fn is_tree_alive(pid: u32, pgid: Option<u32>) -> bool {
+    if is_process_alive(pid) {
+        return true;
+    }
+    #[cfg(unix)]
+    {
+        is_process_group_alive(pgid.unwrap_or(pid))
+    }
+    #[cfg(not(unix))]
+    {
+        let _ = pgid;
+        false
+    }
+}
+
+/// Terminate a process tree gracefully, escalating to a forced kill
+///
+/// Requests a clean exit, waits up to `grace` for the process and its group to
+/// go away, and only then kills the tree. Returns true if the forced kill was needed.
+///
+/// `pgid` is the process group the process was started in, if it differs from
+/// `pid` (e.g. a shell pipeline started with its own group). Both signals go to
+/// the whole group so subprocesses aren't orphaned. Ignored on Windows, where
+/// the CTRL_BREAK/taskkill tree already covers children.
+pub fn terminate_process_tree_with_grace(
+    pid: u32,
+    pgid: Option<u32>,
+    grace: Duration,
+) -> Result<bool, String> {
+    request_exit(pid, pgid);
+
+    let deadline = Instant::now() + grace;
+    while Instant::now() < deadline {
+        if !is_tree_alive(pid, pgid) {
+            return Ok(false);
+        }
+        thread::sleep(EXIT_POLL_INTERVAL);
+    }
+    if !is_tree_alive(pid, pgid) {
+        return Ok(false);
+    }
+
+    log::trace!("pid={pid} still alive after {grace:?}, killing process tree");
+    force_kill(pid, pgid)?;
+    Ok(true)
+}
+
+/// Ask a process tree (and its separate group, if any) to exit cleanly
+fn request_exit(pid: u32, pgid: Option<u32>) {
+    #[cfg(unix)]
+    if let Some(pgid) = pgid {
+        if let Err(e) = signal_process_group(pgid, libc::SIGTERM) {
+            log::trace!("SIGTERM to process group {pgid} failed: {e}");
+        }
+    }
+    #[cfg(not(unix))]
+    let _ = pgid;
+
+    if let Err(e) = request_process_tree_exit(pid) {
+        log::trace!("Graceful shutdown request for pid={pid} failed: {e}");
+    }
+}
+
+/// Kill a process tree (and its separate group, if any)
+fn force_kill(pid: u32, pgid: Option<u32>) -> Result<(), String> {
+    #[cfg(unix)]
+    if let Some(pgid) = pgid {
+        if let Err(e) = signal_process_group(pgid, libc::SIGKILL) {
+            log::trace!("SIGKILL to process group {pgid} failed: {e}");
+        }
+    }
+    #[cfg(not(unix))]
+    let _ = pgid;
+
+    match kill_process_tree(pid) {
+        // The process itself already exited; only its group needed killing
+        Err(_) if !is_process_alive(pid) => Ok(()),
+        result => result,
+    }
+}
+
+/// Terminate process trees without blocking the caller
+///
+/// Every `(pid, pgid)` is asked to exit right away; a background thread then
+/// waits up to `grace` once for all of them and force-kills the survivors.
+pub fn terminate_processes_in_background(processes: Vec<(u32, Option<u32>)>, grace: Duration) {
+    if processes.is_empty() {
+        return;
+    }
+    for &(pid, pgid) in &processes {
+        request_exit(pid, pgid);
+    }
+
+    thread::spawn(move || {
+        let deadline = Instant::now() + grace;
+        let mut alive = processes;
+        loop {
+            alive.retain(|&(pid, pgid)| is_tree_alive(pid, pgid));
+            if alive.is_empty() || Instant::now() >= deadline {
+                break;
+            }
+            thread::sleep(EXIT_POLL_INTERVAL);
+        }
+        for (pid, pgid) in alive {
+            log::warn!("Process {pid} ignored termination, force-killing after {grace:?}");
+            if let Err(e) = force_kill(pid, pgid) {
+                log::error!("Failed to kill process tree for pid={pid}: {e}");
+            }
+        }
+    });
+}
+
+#[cfg(all(test, unix))]
+mod tests {
+    use super::*;
+    use std::os::unix::process::{CommandExt, ExitStatusExt};
+    use std::process::Command;
+
+    #[test]
+    fn test_terminate_escalates_when_sigterm_ignored() {
+        let mut child = Command::new("/bin/sh")
+            .args(["-c", "trap '' TERM; sleep 30"])
+            .process_group(0)
+            .spawn()
+            .unwrap();
+        // Give the shell time to install the trap
+        thread::sleep(Duration::from_millis(200));
+
+        let escalated =
+            terminate_process_tree_with_grace(child.id(), None, Duration::from_millis(300))
+                .unwrap();
+        assert!(escalated);
+
+        let status = child.wait().unwrap();
+        assert_eq!(status.signal(), Some(libc::SIGKILL));
+    }
+
+    #[test]
+    fn test_terminate_escalates_when_group_outlives_process() {
+        // A subprocess in the run's group that ignores SIGTERM
+        let mut survivor = Command::new("/bin/sh")
+            .args(["-c", "trap '' TERM; sleep 30"])
+            .process_group(0)
+            .spawn()
+            .unwrap();
+        thread::sleep(Duration::from_millis(200));
+
+        // The CLI itself has already exited (and been reaped)
+        let mut cli = Command::new("/bin/sh")
+            .args(["-c", "exit 0"])
+            .spawn()
+            .unwrap();
+        let cli_pid = cli.id();
+        cli.wait().unwrap();
+
+        let escalated = terminate_process_tree_with_grace(
+            cli_pid,
+            Some(survivor.id()),
+            Duration::from_millis(300),
+        )
+        .unwrap();
+        assert!(escalated);
+
+        let status = survivor.wait().unwrap();
+        assert_eq!(status.signal(), Some(libc::SIGKILL));
+    }
+
+    #[test]
+    fn test_terminate_in_background_returns_before_escalating() {
+        let mut child = Command::new("/bin/sh")
+            .args(["-c", "trap '' TERM; sleep 30"])
+            .process_group(0)
+            .spawn()
+            .unwrap();
+        thread::sleep(Duration::from_millis(200));
+
+        let start = Instant::now();
+        terminate_processes_in_background(vec![(child.id(), None)], Duration::from_millis(300));
+        assert!(start.elapsed() < Duration::from_millis(300));
+
+        // The background thread kills it once the grace period is up
+        let status = child.wait().unwrap();
+        assert_eq!(status.signal(), Some(libc::SIGKILL));
+    }
+}
@@ -128,21 +128,58 @@ pub fn is_wsl_available() -> bool {
 /// Convert a Windows path to WSL path format
 /// C:\Users\foo\file.txt -> /mnt/c/Users/foo/file.txt
 #[cfg(windows)]
-pub fn windows_to_wsl_path(win_path: &str) -> String {
-    let path = win_path.replace('\\', "/");
-    if path.len() >= 2 && path.chars().nth(1) == Some(':') {
-        let drive = path.chars().next().unwrap().to_ascii_lowercase();
-        format!("/mnt/{}{}", drive, &path[2..])
-    } else {
-        path
-    }
+pub fn windows_to_wsl_path(win_path: &str) -> Result<String, String> {
+    translate_windows_path(win_path)
 }
 
 #[cfg(not(windows))]
 #[allow(dead_code)]
-pub fn windows_to_wsl_path(path: &str) -> String {
+pub fn windows_to_wsl_path(path: &str) -> Result<String, String> {
     // On non-Windows, just return the path as-is
-    path.to_string()
+    Ok(path.to_string())
+}
+
+/// Translate a Windows path into the path WSL sees
+///
+/// - Drive paths (any letter case) map to `/mnt/<lowercase drive>/...`
+/// - `\\wsl$\<distro>\...` and `\\wsl.localhost\<distro>\...` map to the path
+///   inside the distribution
+/// - Other UNC paths (network shares) are rejected, since `/mnt` only covers
+///   local and mapped drives
+#[cfg_attr(not(windows), allow(dead_code))]
+fn translate_windows_path(win_path: &str) -> Result<String, String> {
+    // Strip the extended-length prefix (\\?\C:\... or \\?\UNC\server\...)
+    let path = if let Some(rest) = win_path.strip_prefix(r"\\?\UNC\") {
+        format!(r"\\{rest}")
+    } else {
+        win_path
+            .strip_prefix(r"\\?\")
+            .unwrap_or(win_path)
+            .to_string()
+    };
+    let path = path.replace('\\', "/");
+
+    if let Some(unc) = path.strip_prefix("//") {
+        let mut parts = unc.splitn(3, '/');
+        let host = parts.next().unwrap_or("");
+        let distro = parts.next().unwrap_or("");
+        if (host.eq_ignore_ascii_case("wsl$") || host.eq_ignore_ascii_case("wsl.localhost"))
+            && !distro.is_empty()
+        {
+            return Ok(format!("/{}", parts.next().unwrap_or("")));
+        }
+        return Err(format!(
+            "WSL cannot access network path {win_path}. Move the worktree to a local drive."
+        ));
+    }
+
+    let mut chars = path.chars();
+    match (chars.next(), chars.next()) {
+        (Some(drive), Some(':')) if drive.is_ascii_alphabetic() => {
+            Ok(format!("/mnt/{}{}", drive.to_ascii_lowercase(), &path[2..]))
+        }
+        _ => Ok(path),
+    }
 }
 
 /// Create a Command that runs through WSL (Windows only)
@@ -165,3 +202,93 @@ pub fn wsl_shell_command(cmd: &str) -> Result<Command, String> {
     // On Unix, just use regular shell
     Ok(shell_command(cmd))
 }
+
+/// List installed WSL distribution names (Windows only)
+#[cfg(windows)]
+pub fn list_wsl_distributions() -> Result<Vec<String>, String> {
+    let output = Command::new("wsl")
+        .args(["-l", "-q"])
+        .output()
+        .map_err(|e| format!("Failed to run wsl: {e}"))?;
+    if !output.status.success() {
+        return Err(format!(
+            "wsl -l -q failed: {}",
+            parse_wsl_list_output(&output.stderr).join(" ")
+        ));
+    }
+    Ok(parse_wsl_list_output(&output.stdout))
+}
+
+#[cfg(not(windows))]
+pub fn list_wsl_distributions() -> Result<Vec<String>, String> {
+    Ok(Vec::new())
+}
+
+/// Parse `wsl -l -q` output into distribution names
+///
+/// wsl.exe writes UTF-16LE (sometimes with a BOM); fall back to UTF-8 when the
+/// output doesn't look like UTF-16.
+#[cfg_attr(not(windows), allow(dead_code))]
+fn parse_wsl_list_output(bytes: &[u8]) -> Vec<String> {
+    let text = if bytes.len().is_multiple_of(2) && bytes.chunks(2).any(|pair| pair[1] == 0) {
+        let units: Vec<u16> = bytes
+            .chunks(2)
+            .map(|pair| u16::from_le_bytes([pair[0], pair[1]]))
+            .collect();
+        String::from_utf16_lossy(&units)
+    } else {
+        String::from_utf8_lossy(bytes).to_string()
+    };
+
+    text.lines()
+        .map(|line| line.trim_matches(|c: char| c == '\u{feff}' || c == '\0' || c.is_whitespace()))
+        .filter(|line| !line.is_empty())
+        .map(|line| line.to_string())
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_translate_windows_path() {
+        assert_eq!(
+            translate_windows_path(r"C:\Users\x").unwrap(),
+            "/mnt/c/Users/x"
+        );
+        assert_eq!(translate_windows_path(r"d:\proj").unwrap(), "/mnt/d/proj");
+        assert_eq!(translate_windows_path(r"D:\").unwrap(), "/mnt/d/");
+        assert_eq!(
+            translate_windows_path(r"\\?\C:\Users\x").unwrap(),
+            "/mnt/c/Users/x"
+        );
+        assert_eq!(
+            translate_windows_path(r"\\wsl$\Ubuntu\home\me\proj").unwrap(),
+            "/home/me/proj"
+        );
+        assert_eq!(
+            translate_windows_path(r"\\wsl.localhost\Ubuntu\home").unwrap(),
+            "/home"
+        );
+
+        let err = translate_windows_path(r"\\server\share\proj").unwrap_err();
+        assert!(err.contains("WSL cannot access network path"));
+        assert!(translate_windows_path(r"\\?\UNC\server\share").is_err());
+    }
+
+    #[test]
+    fn test_parse_wsl_list_output() {
+        let utf16: Vec<u8> = "\u{feff}Ubuntu-22.04\r\ndocker-desktop\r\n\r\n"
+            .encode_utf16()
+            .flat_map(|unit| unit.to_le_bytes())
+            .collect();
+        assert_eq!(
+            parse_wsl_list_output(&utf16),
+            vec!["Ubuntu-22.04", "docker-desktop"]
+        );
+
+        assert_eq!(parse_wsl_list_output(b"Debian\n"), vec!["Debian"]);
+        assert!(parse_wsl_list_output(b"").is_empty());
+    }
+}
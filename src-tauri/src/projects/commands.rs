@@ -12,21 +12,23 @@ use uuid::Uuid;
 use super::git;
 use super::git::get_repo_identifier;
 use super::github_issues::{
-    add_issue_reference, add_pr_reference, format_issue_context_markdown,
-    format_pr_context_markdown, generate_branch_name_from_issue, generate_branch_name_from_pr,
-    get_github_contexts_dir, get_github_pr, get_pr_diff, IssueContext, PullRequestContext,
+    add_issue_reference, add_pr_reference, cleanup_issue_contexts_for_worktree,
+    format_issue_context_markdown, format_pr_context_markdown, generate_branch_name_from_issue,
+    generate_branch_name_from_pr, get_github_contexts_dir, get_github_issue, get_github_pr,
+    get_pr_diff, load_issue_context, post_github_pr_comment, IssueContext, PullRequestContext,
 };
 use super::gitlab_issues::{
-    format_gitlab_mr_context_markdown, generate_branch_name_from_gitlab_mr, get_gitlab_mr,
-    get_mr_diff, GitLabMergeRequestContext,
+    format_gitlab_mr_context_markdown, generate_branch_name_from_gitlab_issue,
+    generate_branch_name_from_gitlab_mr, get_gitlab_issue, get_gitlab_mr, get_mr_diff,
+    load_gitlab_issue_context, post_gitlab_mr_note, GitLabMergeRequestContext,
 };
 use super::names::generate_unique_workspace_name;
 use super::storage::{get_project_worktrees_dir, load_projects_data, save_projects_data};
 use super::types::{
-    MergeType, Project, SessionType, Worktree, WorktreeArchivedEvent, WorktreeBranchExistsEvent,
-    WorktreeCreateErrorEvent, WorktreeCreatedEvent, WorktreeCreatingEvent,
-    WorktreeDeleteErrorEvent, WorktreeDeletedEvent, WorktreeDeletingEvent, WorktreePathExistsEvent,
-    WorktreePermanentlyDeletedEvent, WorktreeUnarchivedEvent,
+    MergeType, Project, ProjectsData, SessionType, Worktree, WorktreeArchivedEvent,
+    WorktreeBranchExistsEvent, WorktreeCreateErrorEvent, WorktreeCreatedEvent,
+    WorktreeCreatingEvent, WorktreeDeleteErrorEvent, WorktreeDeletedEvent, WorktreeDeletingEvent,
+    WorktreePathExistsEvent, WorktreePermanentlyDeletedEvent, WorktreeUnarchivedEvent,
 };
 use crate::claude_cli::get_cli_binary_path;
 
@@ -523,34 +525,10 @@ pub async fn create_worktree(
         custom
     } else if let Some(ref ctx) = pr_context {
         let pr_branch = generate_branch_name_from_pr(ctx.number, &ctx.title);
-        // Check if this branch name already exists, if so, add a suffix
-        if data.worktree_name_exists(&project_id, &pr_branch) {
-            let mut counter = 2;
-            loop {
-                let candidate = format!("{pr_branch}-{counter}");
-                if !data.worktree_name_exists(&project_id, &candidate) {
-                    break candidate;
-                }
-                counter += 1;
-            }
-        } else {
-            pr_branch
-        }
+        unique_worktree_name(&data, &project_id, &project.path, &pr_branch)
     } else if let Some(ref ctx) = issue_context {
         let issue_branch = generate_branch_name_from_issue(ctx.number, &ctx.title);
-        // Check if this branch name already exists, if so, add a suffix
-        if data.worktree_name_exists(&project_id, &issue_branch) {
-            let mut counter = 2;
-            loop {
-                let candidate = format!("{issue_branch}-{counter}");
-                if !data.worktree_name_exists(&project_id, &candidate) {
-                    break candidate;
-                }
-                counter += 1;
-            }
-        } else {
-            issue_branch
-        }
+        unique_worktree_name(&data, &project_id, &project.path, &issue_branch)
     } else {
         generate_unique_workspace_name(|n| data.worktree_name_exists(&project_id, n))
     };
@@ -1355,6 +1333,238 @@ pub async fn create_worktree_from_existing_branch(
     Ok(pending_worktree)
 }
 
+/// Append `-2`, `-3`, ... to `name` until neither a worktree nor a local or
+/// remote git branch uses it
+fn unique_worktree_name(
+    data: &ProjectsData,
+    project_id: &str,
+    repo_path: &str,
+    name: &str,
+) -> String {
+    let branches = git::all_branch_names(repo_path);
+    git::next_free_name(name, |n| {
+        data.worktree_name_exists(project_id, n) || branches.iter().any(|b| b == n)
+    })
+}
+
+/// Generate the branch name for an issue using the provider's naming scheme
+fn issue_branch_name(provider: &str, issue_iid: u32, title: &str) -> Result<String, String> {
+    match provider {
+        "github" => Ok(generate_branch_name_from_issue(issue_iid, title)),
+        "gitlab" => Ok(generate_branch_name_from_gitlab_issue(issue_iid, title)),
+        other => Err(format!("Unsupported issue provider: {other}")),
+    }
+}
+
+/// Preview the branch/worktree name `create_worktree_for_issue` would use
+///
+/// Runs the same generator and collision check without creating anything, so
+/// the UI can show the name in an editable field before creation.
+#[tauri::command]
+pub async fn preview_branch_name(
+    app: AppHandle,
+    project_path: String,
+    provider: String,
+    iid: u32,
+    title: String,
+) -> Result<String, String> {
+    let base_name = issue_branch_name(&provider, iid, &title)?;
+
+    let data = load_projects_data(&app)?;
+    let name = match data.projects.iter().find(|p| p.path == project_path) {
+        Some(project) => unique_worktree_name(&data, &project.id, &project.path, &base_name),
+        None => git::unique_branch_name(&project_path, &base_name),
+    };
+    Ok(name)
+}
+
+/// Check a user-edited branch name against git's ref-name rules
+#[tauri::command]
+pub async fn validate_branch_name(name: String) -> Result<(), String> {
+    git::validate_branch_name(&name)
+}
+
+/// Post a comment, such as an AI review, on a GitHub PR or GitLab MR
+///
+/// The body is passed to the CLI on stdin. Returns the comment URL when the
+/// CLI reports one (`gh` does, `glab` doesn't).
+#[tauri::command]
+pub async fn post_pr_comment(
+    project_path: String,
+    provider: String,
+    pr_number: u32,
+    body: String,
+) -> Result<Option<String>, String> {
+    match provider.as_str() {
+        "github" => post_github_pr_comment(&project_path, pr_number, &body),
+        "gitlab" => post_gitlab_mr_note(&project_path, pr_number, &body),
+        other => Err(format!("Unsupported PR provider: {other}")),
+    }
+}
+
+/// Create a worktree for a GitHub or GitLab issue in one step
+///
+/// Unlike `create_worktree`, this runs to completion before returning:
+/// 1. Fetches the issue and generates a branch name from its title
+/// 2. Runs `git worktree add -b <branch>` from the project's default branch
+/// 3. Loads the issue context into the shared git-context directory
+/// 4. Runs the jean.json setup script, if any, and saves the worktree
+///
+/// If any step after the worktree is created fails, the worktree, its branch
+/// and the context reference are removed again.
+///
+/// Events emitted:
+/// - `worktree:created` - Emitted when worktree is ready
+#[tauri::command]
+pub async fn create_worktree_for_issue(
+    app: AppHandle,
+    project_path: String,
+    issue_iid: u32,
+    provider: String,
+) -> Result<Worktree, String> {
+    log::trace!("Creating worktree for {provider} issue {issue_iid} in {project_path}");
+
+    let data = load_projects_data(&app)?;
+    let project = data
+        .projects
+        .iter()
+        .find(|p| p.path == project_path)
+        .ok_or_else(|| format!("Project not found for path: {project_path}"))?
+        .clone();
+
+    let title = match provider.as_str() {
+        "github" => {
+            let issue = get_github_issue(project_path.clone(), issue_iid).await?;
+            issue.title
+        }
+        "gitlab" => {
+            let issue = get_gitlab_issue(project_path.clone(), issue_iid).await?;
+            issue.title
+        }
+        other => return Err(format!("Unsupported issue provider: {other}")),
+    };
+    let issue_branch = issue_branch_name(&provider, issue_iid, &title)?;
+    let name = unique_worktree_name(&data, &project.id, &project.path, &issue_branch);
+    let base = git::get_valid_base_branch(&project.path, &project.default_branch)?;
+
+    let worktree_path = get_project_worktrees_dir(&project.name)?.join(&name);
+    let worktree_path_str = worktree_path
+        .to_str()
+        .ok_or_else(|| "Invalid worktree path".to_string())?
+        .to_string();
+    if worktree_path.exists() {
+        return Err(format!("Directory already exists: {worktree_path_str}"));
+    }
+
+    git::create_worktree(&project.path, &worktree_path_str, &name, &base)?;
+
+    let worktree_id = Uuid::new_v4().to_string();
+    let result = async {
+        match provider.as_str() {
+            "github" => {
+                load_issue_context(
+                    app.clone(),
+                    worktree_id.clone(),
+                    issue_iid,
+                    project_path.clone(),
+                )
+                .await?;
+            }
+            _ => {
+                load_gitlab_issue_context(
+                    app.clone(),
+                    worktree_id.clone(),
+                    issue_iid,
+                    project_path.clone(),
+                )
+                .await?;
+            }
+        }
+
+        let setup = git::read_jean_config(&project.path).and_then(|config| config.scripts.setup);
+        let (setup_output, setup_script) = match setup {
+            Some(script) => {
+                let output =
+                    git::run_setup_script(&worktree_path_str, &project.path, &name, &script)
+                        .map_err(|e| format!("Setup script failed: {e}"))?;
+                (Some(output), Some(script))
+            }
+            None => (None, None),
+        };
+
+        let mut data = load_projects_data(&app)?;
+        let max_order = data
+            .worktrees
+            .iter()
+            .filter(|w| w.project_id == project.id)
+            .map(|w| w.order)
+            .max()
+            .unwrap_or(0);
+
+        let worktree = Worktree {
+            id: worktree_id.clone(),
+            project_id: project.id.clone(),
+            name: name.clone(),
+            path: worktree_path_str.clone(),
+            branch: name.clone(),
+            created_at: now(),
+            setup_output,
+            setup_script,
+            session_type: SessionType::Worktree,
+            pr_number: None,
+            pr_url: None,
+            cached_pr_status: None,
+            cached_check_status: None,
+            cached_behind_count: None,
+            cached_ahead_count: None,
+            cached_status_at: None,
+            cached_uncommitted_added: None,
+            cached_uncommitted_removed: None,
+            cached_branch_diff_added: None,
+            cached_branch_diff_removed: None,
+            cached_base_branch_ahead_count: None,
+            cached_base_branch_behind_count: None,
+            cached_worktree_ahead_count: None,
+            cached_unpushed_count: None,
+            order: max_order + 1,
+            archived_at: None,
+            ai_provider: None,
+            ai_model: None,
+        };
+
+        data.add_worktree(worktree.clone());
+        save_projects_data(&app, &data)?;
+        Ok::<_, String>(worktree)
+    }
+    .await;
+
+    match result {
+        Ok(worktree) => {
+            log::trace!("Worktree created for issue {issue_iid}: {}", worktree.name);
+            let created_event = WorktreeCreatedEvent {
+                worktree: worktree.clone(),
+            };
+            if let Err(e) = app.emit("worktree:created", &created_event) {
+                log::error!("Failed to emit worktree:created event: {e}");
+            }
+            Ok(worktree)
+        }
+        Err(e) => {
+            log::error!("Failed to create worktree for issue {issue_iid}, cleaning up: {e}");
+            if let Err(cleanup_err) = cleanup_issue_contexts_for_worktree(&app, &worktree_id) {
+                log::warn!("Failed to remove issue context reference: {cleanup_err}");
+            }
+            if let Err(cleanup_err) = git::remove_worktree(&project.path, &worktree_path_str) {
+                log::warn!("Failed to remove worktree {worktree_path_str}: {cleanup_err}");
+            }
+            if let Err(cleanup_err) = git::delete_branch(&project.path, &name) {
+                log::warn!("Failed to delete branch {name}: {cleanup_err}");
+            }
+            Err(e)
+        }
+    }
+}
+
 /// Checkout a GitHub PR to a new worktree
 ///
 /// This command:
@@ -1402,19 +1612,9 @@ pub async fn checkout_pr(
     // Generate worktree name from PR (for the directory/worktree name, not the branch)
     let worktree_name = generate_branch_name_from_pr(pr_number, &pr_detail.title);
 
-    // Check if worktree name already exists, add suffix if needed
-    let final_worktree_name = if data.worktree_name_exists(&project_id, &worktree_name) {
-        let mut counter = 2;
-        loop {
-            let candidate = format!("{worktree_name}-{counter}");
-            if !data.worktree_name_exists(&project_id, &candidate) {
-                break candidate;
-            }
-            counter += 1;
-        }
-    } else {
-        worktree_name
-    };
+    // Add a suffix if a worktree or branch already uses this name
+    let final_worktree_name =
+        unique_worktree_name(&data, &project_id, &project.path, &worktree_name);
 
     // Generate a temporary branch name for worktree creation
     // This will be replaced by the actual PR branch after gh pr checkout
@@ -1772,19 +1972,9 @@ pub async fn checkout_gitlab_mr(
     // Generate worktree name from MR (for the directory/worktree name, not the branch)
     let worktree_name = generate_branch_name_from_gitlab_mr(mr_iid, &mr_detail.title);
 
-    // Check if worktree name already exists, add suffix if needed
-    let final_worktree_name = if data.worktree_name_exists(&project_id, &worktree_name) {
-        let mut counter = 2;
-        loop {
-            let candidate = format!("{worktree_name}-{counter}");
-            if !data.worktree_name_exists(&project_id, &candidate) {
-                break candidate;
-            }
-            counter += 1;
-        }
-    } else {
-        worktree_name
-    };
+    // Add a suffix if a worktree or branch already uses this name
+    let final_worktree_name =
+        unique_worktree_name(&data, &project_id, &project.path, &worktree_name);
 
     // Generate a temporary branch name for worktree creation
     // This will be replaced by the actual MR branch after glab mr checkout
@@ -3806,6 +3996,23 @@ pub async fn get_git_diff(
     super::git_status::get_git_diff(&worktree_path, &diff_type, base_branch.as_deref())
 }
 
+/// Get a compact status summary (branch, ahead/behind, dirty files) for many worktrees
+///
+/// Runs one `git status --porcelain=v2 --branch` per worktree on a bounded
+/// thread pool. Failures are reported per worktree in `error`.
+#[tauri::command]
+pub async fn get_worktree_status_batch(
+    paths: Vec<String>,
+) -> Result<Vec<super::git_status::WorktreeStatus>, String> {
+    log::trace!("Getting status for {} worktrees", paths.len());
+
+    tauri::async_runtime::spawn_blocking(move || {
+        super::git_status::get_worktree_status_batch(&paths)
+    })
+    .await
+    .map_err(|e| format!("Status task failed: {e}"))
+}
+
 /// Reorder projects in the sidebar
 #[tauri::command]
 pub async fn reorder_projects(app: AppHandle, project_ids: Vec<String>) -> Result<(), String> {
@@ -4784,6 +4991,50 @@ pub async fn git_push(worktree_path: String) -> Result<String, String> {
     git::git_push(&worktree_path)
 }
 
+/// Stash uncommitted changes (including untracked files)
+/// Returns the stash commit hash for a targeted `git_stash_pop`
+#[tauri::command]
+pub async fn git_stash_push(
+    project_path: String,
+    message: Option<String>,
+) -> Result<String, String> {
+    log::trace!("Stashing changes in: {project_path}");
+    git::git_stash_push(&project_path, message.as_deref())
+}
+
+/// Apply and drop a stash made on the current branch (its latest if no ref is given)
+#[tauri::command]
+pub async fn git_stash_pop(
+    project_path: String,
+    stash_ref: Option<String>,
+) -> Result<String, String> {
+    log::trace!("Popping stash {stash_ref:?} in: {project_path}");
+    git::git_stash_pop(&project_path, stash_ref.as_deref())
+}
+
+/// Report stale worktrees without changing anything (dry run)
+///
+/// Includes worktrees whose directory is missing and worktrees whose branch
+/// has been deleted. Use `remove_stale_worktrees` to prune them.
+#[tauri::command]
+pub async fn prune_stale_worktrees(project_path: String) -> Result<Vec<String>, String> {
+    log::trace!("Checking for stale worktrees in: {project_path}");
+    git::find_stale_worktrees(&project_path)
+}
+
+/// Prune worktrees whose directory is missing, returning the removed paths
+#[tauri::command]
+pub async fn remove_stale_worktrees(project_path: String) -> Result<Vec<String>, String> {
+    log::trace!("Pruning stale worktrees in: {project_path}");
+    git::prune_worktrees(&project_path)
+}
+
+/// List the stashes made on the worktree's current branch (newest first)
+#[tauri::command]
+pub async fn git_stash_list(project_path: String) -> Result<Vec<git::StashEntry>, String> {
+    git::git_stash_list(&project_path)
+}
+
 // =============================================================================
 // Local Merge
 // =============================================================================
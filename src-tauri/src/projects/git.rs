@@ -1,21 +1,144 @@
 use std::path::Path;
 use std::process::Command;
+use std::sync::Mutex;
 
+use once_cell::sync::Lazy;
 use serde::{Deserialize, Serialize};
 
 use super::types::{JeanConfig, MergeType};
 
+/// Public GitHub host
+pub const GITHUB_HOST: &str = "github.com";
+
+/// Public GitLab host
+pub const GITLAB_HOST: &str = "gitlab.com";
+
 /// Repository identifier extracted from GitHub remote URL
 #[derive(Debug, Clone, Serialize, Deserialize, PartialEq, Eq)]
 pub struct RepoIdentifier {
+    /// Host the repository lives on (e.g. "github.com" or "github.mycorp.com")
+    #[serde(default)]
+    pub host: String,
     pub owner: String,
     pub repo: String,
 }
 
 impl RepoIdentifier {
     /// Create a key string for use in file naming: "{owner}-{repo}"
+    ///
+    /// Repos on self-hosted instances get an "@{host}" suffix so their contexts
+    /// don't collide with a same-named repo on github.com or gitlab.com.
     pub fn to_key(&self) -> String {
-        format!("{}-{}", self.owner, self.repo)
+        if self.host.is_empty() || self.host == GITHUB_HOST || self.host == GITLAB_HOST {
+            format!("{}-{}", self.owner, self.repo)
+        } else {
+            format!("{}-{}@{}", self.owner, self.repo, self.host)
+        }
+    }
+
+    /// The key this repo had before self-hosted keys got the "@{host}" suffix
+    ///
+    /// None when it's the same as `to_key`, i.e. there's nothing to migrate.
+    pub fn legacy_key(&self) -> Option<String> {
+        let legacy_key = format!("{}-{}", self.owner, self.repo);
+        (self.to_key() != legacy_key).then_some(legacy_key)
+    }
+}
+
+/// Self-hosted GitHub and GitLab hosts from preferences (empty = not set)
+#[derive(Debug, Clone, Default)]
+struct HostPreferences {
+    github: String,
+    gitlab: String,
+}
+
+/// Set from `AppPreferences` at startup and whenever preferences are saved
+static HOST_PREFERENCES: Lazy<Mutex<HostPreferences>> =
+    Lazy::new(|| Mutex::new(HostPreferences::default()));
+
+/// Use the `github_host`/`gitlab_host` preferences for self-hosted instances
+pub fn set_host_preferences(github: &str, gitlab: &str) {
+    let mut hosts = HOST_PREFERENCES.lock().unwrap();
+    hosts.github = github.to_string();
+    hosts.gitlab = gitlab.to_string();
+}
+
+/// Resolve a self-hosted instance host from its preference, falling back to
+/// the environment variable's value when the preference is empty
+///
+/// Accepts a bare host or a URL ("https://github.mycorp.com/"). Returns None
+/// when neither is set or when it names the public host.
+fn configured_host(
+    preference: &str,
+    env_value: Option<String>,
+    public_host: &str,
+) -> Option<String> {
+    let value = if preference.trim().is_empty() {
+        env_value?
+    } else {
+        preference.to_string()
+    };
+    let host = value
+        .trim()
+        .trim_start_matches("https://")
+        .trim_start_matches("http://")
+        .trim_end_matches('/')
+        .to_lowercase();
+    (!host.is_empty() && host != public_host).then_some(host)
+}
+
+/// GitHub Enterprise host, from the `github_host` preference or else `GH_HOST`
+/// (the variable gh itself reads)
+pub fn github_enterprise_host() -> Option<String> {
+    let preference = HOST_PREFERENCES.lock().unwrap().github.clone();
+    configured_host(&preference, std::env::var("GH_HOST").ok(), GITHUB_HOST)
+}
+
+/// Self-hosted GitLab host, from the `gitlab_host` preference or else
+/// `GITLAB_HOST` (the variable glab itself reads)
+pub fn gitlab_self_hosted_host() -> Option<String> {
+    let preference = HOST_PREFERENCES.lock().unwrap().gitlab.clone();
+    configured_host(&preference, std::env::var("GITLAB_HOST").ok(), GITLAB_HOST)
+}
+
+/// Split a git remote URL into (host, path)
+///
+/// Handles `git@host:path`, `ssh://git@host[:port]/path` and
+/// `https://[user@]host/path`. The `.git` suffix is removed from the path.
+pub fn parse_remote_url(remote_url: &str) -> Option<(String, String)> {
+    let (host, path) = if let Some(rest) = remote_url
+        .strip_prefix("https://")
+        .or_else(|| remote_url.strip_prefix("http://"))
+        .or_else(|| remote_url.strip_prefix("ssh://"))
+    {
+        let (authority, path) = rest.split_once('/')?;
+        let host = authority.rsplit('@').next()?;
+        // Drop an SSH/HTTPS port (ssh://git@host:2222/owner/repo)
+        (host.split(':').next()?, path)
+    } else {
+        let (user_host, path) = remote_url.split_once(':')?;
+        (user_host.rsplit('@').next()?, path)
+    };
+
+    let path = path.trim_end_matches('/').trim_end_matches(".git");
+    if host.is_empty() || path.is_empty() {
+        return None;
+    }
+    Some((host.to_lowercase(), path.to_string()))
+}
+
+/// Convert a remote URL to an HTTPS GitHub URL, if it points at github.com or `enterprise_host`
+fn github_url_from_remote(
+    remote_url: &str,
+    enterprise_host: Option<&str>,
+) -> Result<String, String> {
+    match parse_remote_url(remote_url) {
+        Some((host, path)) if host == GITHUB_HOST || Some(host.as_str()) == enterprise_host => {
+            Ok(format!("https://{host}/{path}"))
+        }
+        _ => Err(format!(
+            "Remote URL is not a GitHub repository: {remote_url}"
+        )),
     }
 }
 
@@ -27,9 +150,10 @@ impl RepoIdentifier {
 pub fn get_repo_identifier(repo_path: &str) -> Result<RepoIdentifier, String> {
     let github_url = get_github_url(repo_path)?;
 
-    // Parse owner/repo from URL: https://github.com/owner/repo
-    let url_without_prefix = github_url
-        .strip_prefix("https://github.com/")
+    // Parse owner/repo from URL: https://{host}/owner/repo
+    let (host, url_without_prefix) = github_url
+        .strip_prefix("https://")
+        .and_then(|rest| rest.split_once('/'))
         .ok_or_else(|| format!("Invalid GitHub URL format: {github_url}"))?;
 
     let parts: Vec<&str> = url_without_prefix.split('/').collect();
@@ -40,6 +164,7 @@ pub fn get_repo_identifier(repo_path: &str) -> Result<RepoIdentifier, String> {
     }
 
     Ok(RepoIdentifier {
+        host: host.to_string(),
         owner: parts[0].to_string(),
         repo: parts[1].to_string(),
     })
@@ -168,38 +293,14 @@ pub fn get_repo_name(path: &str) -> Result<String, String> {
 
 /// Get the GitHub URL for a repository
 ///
-/// Converts git remote URLs to HTTPS GitHub URLs
+/// Converts git remote URLs to HTTPS GitHub URLs. Remotes on the GitHub
+/// Enterprise host (`github_host` preference or `GH_HOST`) are accepted
+/// alongside github.com.
+/// - git@github.com:user/repo.git -> https://github.com/user/repo
+/// - https://github.mycorp.com/user/repo.git -> https://github.mycorp.com/user/repo
 pub fn get_github_url(repo_path: &str) -> Result<String, String> {
-    let output = Command::new("git")
-        .args(["remote", "get-url", "origin"])
-        .current_dir(repo_path)
-        .output()
-        .map_err(|e| format!("Failed to get remote URL: {e}"))?;
-
-    if !output.status.success() {
-        let stderr = String::from_utf8_lossy(&output.stderr);
-        return Err(format!("Failed to get remote URL: {stderr}"));
-    }
-
-    let remote_url = String::from_utf8_lossy(&output.stdout).trim().to_string();
-
-    // Convert SSH URL to HTTPS URL if needed
-    // git@github.com:user/repo.git -> https://github.com/user/repo
-    // https://github.com/user/repo.git -> https://github.com/user/repo
-    let github_url = if remote_url.starts_with("git@github.com:") {
-        remote_url
-            .replace("git@github.com:", "https://github.com/")
-            .trim_end_matches(".git")
-            .to_string()
-    } else if remote_url.starts_with("https://github.com/") {
-        remote_url.trim_end_matches(".git").to_string()
-    } else {
-        return Err(format!(
-            "Remote URL is not a GitHub repository: {remote_url}"
-        ));
-    };
-
-    Ok(github_url)
+    let remote_url = get_remote_url(repo_path)?;
+    github_url_from_remote(&remote_url, github_enterprise_host().as_deref())
 }
 
 /// Git hosting provider
@@ -230,10 +331,15 @@ pub fn get_remote_url(repo_path: &str) -> Result<String, String> {
 /// Detect the git hosting provider from the remote URL
 pub fn detect_git_provider(repo_path: &str) -> Result<GitProvider, String> {
     let remote_url = get_remote_url(repo_path)?;
+    let host = parse_remote_url(&remote_url).map(|(host, _)| host);
+    let is_host = |configured: Option<String>| configured.is_some() && configured == host;
 
-    if remote_url.contains("github.com") {
+    if remote_url.contains("github.com") || is_host(github_enterprise_host()) {
         Ok(GitProvider::GitHub)
-    } else if remote_url.contains("gitlab.com") || remote_url.contains("gitlab.") {
+    } else if remote_url.contains("gitlab.com")
+        || remote_url.contains("gitlab.")
+        || is_host(gitlab_self_hosted_host())
+    {
         Ok(GitProvider::GitLab)
     } else {
         // Check for .gitlab-ci.yml as a fallback for self-hosted GitLab
@@ -250,47 +356,24 @@ pub fn detect_git_provider(repo_path: &str) -> Result<GitProvider, String> {
 /// Supports both SSH and HTTPS URLs.
 /// - git@gitlab.com:user/repo.git -> https://gitlab.com/user/repo
 /// - https://gitlab.com/user/repo.git -> https://gitlab.com/user/repo
+///
+/// Self-hosted instances are recognized by a "gitlab" host name or by
+/// matching the `gitlab_host` preference or `GITLAB_HOST` environment variable.
 pub fn get_gitlab_url(repo_path: &str) -> Result<String, String> {
     let remote_url = get_remote_url(repo_path)?;
+    gitlab_url_from_remote(&remote_url, gitlab_self_hosted_host().as_deref())
+}
 
-    // Convert SSH URL to HTTPS URL if needed
-    let gitlab_url = if remote_url.starts_with("git@gitlab.com:") {
-        remote_url
-            .replace("git@gitlab.com:", "https://gitlab.com/")
-            .trim_end_matches(".git")
-            .to_string()
-    } else if remote_url.starts_with("https://gitlab.com/") {
-        remote_url.trim_end_matches(".git").to_string()
-    } else if remote_url.contains("gitlab") {
-        // Handle self-hosted GitLab instances
-        // Pattern: git@gitlab.example.com:user/repo.git or https://gitlab.example.com/user/repo.git
-        if remote_url.starts_with("git@") {
-            // git@gitlab.example.com:user/repo.git -> https://gitlab.example.com/user/repo
-            let without_git = remote_url.trim_start_matches("git@");
-            let parts: Vec<&str> = without_git.splitn(2, ':').collect();
-            if parts.len() == 2 {
-                format!(
-                    "https://{}/{}",
-                    parts[0],
-                    parts[1].trim_end_matches(".git")
-                )
-            } else {
-                return Err(format!("Invalid GitLab SSH URL format: {remote_url}"));
-            }
-        } else if remote_url.starts_with("https://") {
-            remote_url.trim_end_matches(".git").to_string()
-        } else {
-            return Err(format!(
-                "Remote URL is not a GitLab repository: {remote_url}"
-            ));
+/// Convert a remote URL to an HTTPS GitLab URL, if it points at a GitLab instance
+fn gitlab_url_from_remote(remote_url: &str, self_hosted: Option<&str>) -> Result<String, String> {
+    match parse_remote_url(remote_url) {
+        Some((host, path)) if host.contains("gitlab") || Some(host.as_str()) == self_hosted => {
+            Ok(format!("https://{host}/{path}"))
         }
-    } else {
-        return Err(format!(
+        _ => Err(format!(
             "Remote URL is not a GitLab repository: {remote_url}"
-        ));
-    };
-
-    Ok(gitlab_url)
+        )),
+    }
 }
 
 /// Extract repository path from a GitLab repository's remote
@@ -300,19 +383,13 @@ pub fn get_gitlab_repo_identifier(repo_path: &str) -> Result<RepoIdentifier, Str
     let gitlab_url = get_gitlab_url(repo_path)?;
 
     // Parse project path from URL: https://gitlab.com/owner/repo or https://gitlab.com/group/subgroup/repo
+    // (self-hosted: https://gitlab.example.com/owner/repo)
     // We treat it as owner/repo for simplicity (owner = first part, repo = rest joined)
-    let url_without_prefix = if gitlab_url.starts_with("https://gitlab.com/") {
-        gitlab_url.strip_prefix("https://gitlab.com/").unwrap()
-    } else {
-        // Self-hosted: https://gitlab.example.com/owner/repo
-        // Find the path after the host
-        gitlab_url
-            .split("//")
-            .nth(1)
-            .and_then(|s| s.split_once('/'))
-            .map(|(_, path)| path)
-            .ok_or_else(|| format!("Could not parse GitLab URL: {gitlab_url}"))?
-    };
+    let (host, url_without_prefix) = gitlab_url
+        .split("//")
+        .nth(1)
+        .and_then(|s| s.split_once('/'))
+        .ok_or_else(|| format!("Could not parse GitLab URL: {gitlab_url}"))?;
 
     let parts: Vec<&str> = url_without_prefix.split('/').collect();
     if parts.len() < 2 {
@@ -329,7 +406,11 @@ pub fn get_gitlab_repo_identifier(repo_path: &str) -> Result<RepoIdentifier, Str
     };
     let repo = parts.last().unwrap().to_string();
 
-    Ok(RepoIdentifier { owner, repo })
+    Ok(RepoIdentifier {
+        host: host.to_string(),
+        owner,
+        repo,
+    })
 }
 
 /// Get the current branch name (HEAD) for a repository
@@ -438,20 +519,8 @@ pub fn rename_branch(repo_path: &str, new_name: &str) -> Result<String, String>
         return Ok(old_name);
     }
 
-    // Check if target branch name already exists
-    let branch_exists = Command::new("git")
-        .args(["rev-parse", "--verify", &format!("refs/heads/{}", new_name)])
-        .current_dir(repo_path)
-        .output()
-        .map(|o| o.status.success())
-        .unwrap_or(false);
-
-    let final_name = if branch_exists {
-        // Append suffix to make unique
-        find_unique_branch_name(repo_path, new_name)?
-    } else {
-        new_name.to_string()
-    };
+    // Append -2, -3, ... if a local or remote branch already uses the name
+    let final_name = unique_branch_name(repo_path, new_name);
 
     // Perform the rename
     let output = Command::new("git")
@@ -469,34 +538,6 @@ pub fn rename_branch(repo_path: &str, new_name: &str) -> Result<String, String>
     Ok(final_name)
 }
 
-/// Find a unique branch name by appending a random 4-char suffix
-fn find_unique_branch_name(repo_path: &str, base_name: &str) -> Result<String, String> {
-    use rand::Rng;
-
-    for _ in 0..10 {
-        let suffix: String = rand::thread_rng()
-            .sample_iter(&rand::distributions::Alphanumeric)
-            .take(4)
-            .map(char::from)
-            .map(|c| c.to_ascii_lowercase())
-            .collect();
-
-        let candidate = format!("{base_name}-{suffix}");
-        let exists = Command::new("git")
-            .args(["rev-parse", "--verify", &format!("refs/heads/{candidate}")])
-            .current_dir(repo_path)
-            .output()
-            .map(|o| o.status.success())
-            .unwrap_or(false);
-
-        if !exists {
-            log::trace!("Using unique branch name: {candidate}");
-            return Ok(candidate);
-        }
-    }
-    Err("Could not find unique branch name after 10 attempts".to_string())
-}
-
 /// Get list of local branches for a repository
 pub fn get_branches(repo_path: &str) -> Result<Vec<String>, String> {
     let output = Command::new("git")
@@ -686,6 +727,76 @@ pub fn get_remote_branches(repo_path: &str) -> Result<Vec<String>, String> {
     Ok(branches)
 }
 
+/// Local and remote branch names, with the `origin/` prefix stripped
+///
+/// Listing failures are treated as "no branches" so callers can still pick a name.
+pub fn all_branch_names(repo_path: &str) -> Vec<String> {
+    let mut names = get_branches(repo_path).unwrap_or_default();
+    names.extend(get_remote_branches(repo_path).unwrap_or_default());
+    names
+}
+
+/// Return `base_name`, or the first of `base_name-2`, `base_name-3`, ... not taken
+pub fn next_free_name(base_name: &str, taken: impl Fn(&str) -> bool) -> String {
+    if !taken(base_name) {
+        return base_name.to_string();
+    }
+    let mut counter = 2;
+    loop {
+        let candidate = format!("{base_name}-{counter}");
+        if !taken(&candidate) {
+            return candidate;
+        }
+        counter += 1;
+    }
+}
+
+/// Pick a branch name based on `base_name` that no local or remote branch uses yet
+pub fn unique_branch_name(repo_path: &str, base_name: &str) -> String {
+    let existing = all_branch_names(repo_path);
+    next_free_name(base_name, |n| existing.iter().any(|b| b == n))
+}
+
+/// Check a branch name against git's ref-name rules (see `git check-ref-format`)
+pub fn validate_branch_name(name: &str) -> Result<(), String> {
+    if name.is_empty() {
+        return Err("Branch name cannot be empty".to_string());
+    }
+    if name == "@" {
+        return Err("Branch name cannot be '@'".to_string());
+    }
+    if name.starts_with('-') {
+        return Err("Branch name cannot start with '-'".to_string());
+    }
+    if let Some(c) = name
+        .chars()
+        .find(|c| c.is_ascii_control() || " ~^:?*[\\".contains(*c))
+    {
+        return Err(format!("Branch name cannot contain {c:?}"));
+    }
+    if name.contains("..") {
+        return Err("Branch name cannot contain '..'".to_string());
+    }
+    if name.contains("@{") {
+        return Err("Branch name cannot contain '@{'".to_string());
+    }
+    if name.ends_with('.') {
+        return Err("Branch name cannot end with '.'".to_string());
+    }
+    for component in name.split('/') {
+        if component.is_empty() {
+            return Err("Branch name cannot start or end with '/' or contain '//'".to_string());
+        }
+        if component.starts_with('.') {
+            return Err("Branch name components cannot start with '.'".to_string());
+        }
+        if component.ends_with(".lock") {
+            return Err("Branch name components cannot end with '.lock'".to_string());
+        }
+    }
+    Ok(())
+}
+
 /// Create a new git worktree
 ///
 /// # Arguments
@@ -931,6 +1042,137 @@ pub fn list_worktrees(repo_path: &str) -> Result<Vec<String>, String> {
     Ok(worktrees)
 }
 
+/// A worktree entry from `git worktree list --porcelain`
+#[derive(Debug, Clone, Default, PartialEq)]
+struct WorktreeListEntry {
+    path: String,
+    /// Checked-out branch name (None for a detached HEAD or bare repo)
+    branch: Option<String>,
+    /// Set when git considers the entry prunable (its directory is gone)
+    prunable: bool,
+    locked: bool,
+}
+
+/// Parse `git worktree list --porcelain` output (blank-line separated records)
+fn parse_worktree_list(output: &str) -> Vec<WorktreeListEntry> {
+    let mut entries = Vec::new();
+    let mut current: Option<WorktreeListEntry> = None;
+
+    for line in output.lines() {
+        if let Some(path) = line.strip_prefix("worktree ") {
+            entries.extend(current.take());
+            current = Some(WorktreeListEntry {
+                path: path.to_string(),
+                ..Default::default()
+            });
+            continue;
+        }
+        let Some(entry) = current.as_mut() else {
+            continue;
+        };
+        if let Some(branch) = line.strip_prefix("branch ") {
+            let branch = branch.strip_prefix("refs/heads/").unwrap_or(branch);
+            entry.branch = Some(branch.to_string());
+        } else if line == "prunable" || line.starts_with("prunable ") {
+            entry.prunable = true;
+        } else if line == "locked" || line.starts_with("locked ") {
+            entry.locked = true;
+        }
+    }
+    entries.extend(current);
+
+    entries
+}
+
+fn list_worktree_entries(repo_path: &str) -> Result<Vec<WorktreeListEntry>, String> {
+    let output = Command::new("git")
+        .args(["worktree", "list", "--porcelain"])
+        .current_dir(repo_path)
+        .output()
+        .map_err(|e| format!("Failed to run git worktree list: {e}"))?;
+
+    if !output.status.success() {
+        let stderr = String::from_utf8_lossy(&output.stderr);
+        return Err(format!("Failed to list worktrees: {stderr}"));
+    }
+
+    let stdout = String::from_utf8_lossy(&output.stdout);
+    Ok(parse_worktree_list(&stdout))
+}
+
+/// Paths of worktrees git would prune (`git worktree prune --dry-run`):
+/// registered, unlocked, and with their directory missing
+fn prunable_worktrees(entries: &[WorktreeListEntry]) -> Vec<String> {
+    entries
+        .iter()
+        .filter(|e| e.prunable && !e.locked)
+        .map(|e| e.path.clone())
+        .collect()
+}
+
+/// Find stale worktrees without changing anything
+///
+/// Reports worktrees whose directory is missing (prunable), plus worktrees whose
+/// directory still exists but whose branch has been deleted. The main worktree
+/// (listed first) is never reported.
+pub fn find_stale_worktrees(repo_path: &str) -> Result<Vec<String>, String> {
+    let entries = list_worktree_entries(repo_path)?;
+    let mut stale = prunable_worktrees(&entries);
+
+    for entry in entries.iter().skip(1).filter(|e| !e.prunable) {
+        let Some(branch) = &entry.branch else {
+            continue;
+        };
+        let branch_ref = format!("refs/heads/{branch}");
+        let exists = Command::new("git")
+            .args(["show-ref", "--verify", "--quiet", &branch_ref])
+            .current_dir(repo_path)
+            .status()
+            .map(|s| s.success())
+            .unwrap_or(true);
+        if !exists {
+            log::trace!("Worktree {} is on deleted branch {branch}", entry.path);
+            stale.push(entry.path.clone());
+        }
+    }
+
+    Ok(stale)
+}
+
+/// Prune worktrees whose directory is missing, returning the removed paths
+///
+/// Worktrees on a deleted branch are left alone: their directory may still
+/// hold uncommitted work, so they should be removed explicitly.
+pub fn prune_worktrees(repo_path: &str) -> Result<Vec<String>, String> {
+    let before = prunable_worktrees(&list_worktree_entries(repo_path)?);
+    if before.is_empty() {
+        return Ok(Vec::new());
+    }
+
+    let output = Command::new("git")
+        .args(["worktree", "prune"])
+        .current_dir(repo_path)
+        .output()
+        .map_err(|e| format!("Failed to run git worktree prune: {e}"))?;
+
+    if !output.status.success() {
+        let stderr = String::from_utf8_lossy(&output.stderr);
+        return Err(format!("Failed to prune worktrees: {}", stderr.trim()));
+    }
+
+    let remaining: Vec<String> = list_worktree_entries(repo_path)?
+        .into_iter()
+        .map(|e| e.path)
+        .collect();
+    let removed: Vec<String> = before
+        .into_iter()
+        .filter(|path| !remaining.contains(path))
+        .collect();
+
+    log::trace!("Pruned {} stale worktrees in {repo_path}", removed.len());
+    Ok(removed)
+}
+
 /// Commit staged changes with a message
 ///
 /// # Arguments
@@ -1324,6 +1566,265 @@ pub fn glab_mr_checkout(worktree_path: &str, mr_iid: u32) -> Result<String, Stri
     Ok(branch_name)
 }
 
+// =============================================================================
+// Stash
+// =============================================================================
+
+/// A stash entry from `git stash list`
+#[derive(Debug, Clone, Serialize, PartialEq)]
+pub struct StashEntry {
+    /// Positional ref (e.g., "stash@{0}"), shifts as stashes are pushed/popped
+    pub stash_ref: String,
+    /// Stash commit hash, stable for the lifetime of the stash
+    pub commit: String,
+    /// Branch the stash was created on (None for a detached HEAD)
+    pub branch: Option<String>,
+    /// Stash message (user-provided, or "WIP" subject line)
+    pub message: String,
+    /// Unix timestamp when the stash was created
+    pub created_at: u64,
+}
+
+/// Field separator for `git stash list --format`
+const STASH_FIELD_SEPARATOR: char = '\x1f';
+
+/// Parse `git stash list --format=%gd%x1f%H%x1f%ct%x1f%gs` output
+///
+/// The reflog subject is "On {branch}: {message}" for stashes with a message
+/// and "WIP on {branch}: {sha} {subject}" otherwise.
+fn parse_stash_list(output: &str) -> Vec<StashEntry> {
+    output
+        .lines()
+        .filter_map(|line| {
+            let mut fields = line.splitn(4, STASH_FIELD_SEPARATOR);
+            let stash_ref = fields.next()?.to_string();
+            let commit = fields.next()?.to_string();
+            let created_at = fields.next()?.parse().unwrap_or(0);
+            let subject = fields.next()?;
+
+            let (branch, message) = match subject
+                .strip_prefix("WIP on ")
+                .or_else(|| subject.strip_prefix("On "))
+                .and_then(|rest| rest.split_once(": "))
+            {
+                Some((branch, message)) if branch != "(no branch)" => {
+                    (Some(branch.to_string()), message.to_string())
+                }
+                Some((_, message)) => (None, message.to_string()),
+                None => (None, subject.to_string()),
+            };
+
+            Some(StashEntry {
+                stash_ref,
+                commit,
+                branch,
+                message,
+                created_at,
+            })
+        })
+        .collect()
+}
+
+/// Shortest commit hash prefix `git_stash_pop` accepts, so a short or empty
+/// prefix can't match an arbitrary stash
+const MIN_STASH_COMMIT_PREFIX_LEN: usize = 7;
+
+/// Keep only the stashes created on `branch` (None for a detached HEAD)
+///
+/// The stash is shared by every worktree of a repository, so without this a
+/// worktree would list, and could pop, stashes made on other branches.
+fn stashes_on_branch(entries: Vec<StashEntry>, branch: Option<&str>) -> Vec<StashEntry> {
+    entries
+        .into_iter()
+        .filter(|entry| entry.branch.as_deref() == branch)
+        .collect()
+}
+
+/// Find the stash `stash_ref` refers to among `entries` (the latest if None)
+fn find_stash<'a>(
+    entries: &'a [StashEntry],
+    stash_ref: Option<&str>,
+) -> Result<&'a StashEntry, String> {
+    match stash_ref {
+        None => entries
+            .first()
+            .ok_or_else(|| "No stashes on this branch".to_string()),
+        Some(r) if r.starts_with("stash@{") => entries
+            .iter()
+            .find(|entry| entry.stash_ref == r)
+            .ok_or_else(|| format!("Stash not found on this branch: {r}")),
+        Some(commit) => {
+            if commit.len() < MIN_STASH_COMMIT_PREFIX_LEN
+                || !commit.chars().all(|c| c.is_ascii_hexdigit())
+            {
+                return Err(format!(
+                    "Invalid stash commit '{commit}': expected at least {MIN_STASH_COMMIT_PREFIX_LEN} hex characters"
+                ));
+            }
+            entries
+                .iter()
+                .find(|entry| entry.commit.starts_with(commit))
+                .ok_or_else(|| format!("Stash not found on this branch: {commit}"))
+        }
+    }
+}
+
+/// List the stashes made on the current branch (newest first)
+pub fn git_stash_list(repo_path: &str) -> Result<Vec<StashEntry>, String> {
+    let output = Command::new("git")
+        .args(["stash", "list", "--format=%gd%x1f%H%x1f%ct%x1f%gs"])
+        .current_dir(repo_path)
+        .output()
+        .map_err(|e| format!("Failed to run git stash list: {e}"))?;
+
+    if !output.status.success() {
+        let stderr = String::from_utf8_lossy(&output.stderr);
+        return Err(format!("Failed to list stashes: {}", stderr.trim()));
+    }
+
+    let entries = parse_stash_list(&String::from_utf8_lossy(&output.stdout));
+    let branch = get_current_branch(repo_path)?;
+    // `rev-parse --abbrev-ref HEAD` prints "HEAD" when detached
+    let branch = (branch != "HEAD").then_some(branch.as_str());
+    Ok(stashes_on_branch(entries, branch))
+}
+
+/// Stash all uncommitted changes (including untracked files)
+///
+/// Returns the stash commit hash, which `git_stash_pop` accepts and which,
+/// unlike "stash@{N}", keeps pointing at this stash as others are added.
+pub fn git_stash_push(repo_path: &str, message: Option<&str>) -> Result<String, String> {
+    log::trace!("Stashing changes in {repo_path}");
+
+    if !has_uncommitted_changes(repo_path) {
+        return Err("Nothing to stash: the working tree is clean".to_string());
+    }
+
+    let mut args = vec!["stash", "push", "--include-untracked"];
+    if let Some(message) = message.filter(|m| !m.trim().is_empty()) {
+        args.extend(["--message", message]);
+    }
+
+    let output = Command::new("git")
+        .args(&args)
+        .current_dir(repo_path)
+        .output()
+        .map_err(|e| format!("Failed to run git stash push: {e}"))?;
+
+    if !output.status.success() {
+        let stderr = String::from_utf8_lossy(&output.stderr);
+        log::error!("Failed to stash changes: {stderr}");
+        return Err(format!("Failed to stash changes: {}", stderr.trim()));
+    }
+
+    let rev = Command::new("git")
+        .args(["rev-parse", "stash@{0}"])
+        .current_dir(repo_path)
+        .output()
+        .map_err(|e| format!("Failed to run git rev-parse: {e}"))?;
+
+    if !rev.status.success() {
+        return Err("Changes were stashed but the stash could not be found".to_string());
+    }
+
+    let commit = String::from_utf8_lossy(&rev.stdout).trim().to_string();
+    log::trace!("Stashed changes as {commit}");
+    Ok(commit)
+}
+
+/// Apply and drop a stash made on the current branch (its latest if
+/// `stash_ref` is None)
+///
+/// `stash_ref` may be a positional ref ("stash@{1}") or a stash commit hash
+/// as returned by `git_stash_push`. On conflicts the stash is kept.
+///
+/// The stash is resolved to its commit up front and applied and dropped by
+/// that commit, so a stash pushed or dropped meanwhile (from any worktree of
+/// the repository) can't shift "stash@{N}" onto a different entry.
+pub fn git_stash_pop(repo_path: &str, stash_ref: Option<&str>) -> Result<String, String> {
+    let entries = git_stash_list(repo_path)?;
+    let entry = find_stash(&entries, stash_ref)?;
+    let (stash_ref, commit) = (entry.stash_ref.clone(), entry.commit.clone());
+    log::trace!("Popping {stash_ref} ({commit}) in {repo_path}");
+
+    let output = Command::new("git")
+        .args(["stash", "apply", &commit])
+        .current_dir(repo_path)
+        .output()
+        .map_err(|e| format!("Failed to run git stash apply: {e}"))?;
+
+    let stdout = String::from_utf8_lossy(&output.stdout).to_string();
+    if output.status.success() {
+        drop_stash_commit(repo_path, &commit)?;
+        log::trace!("Successfully popped {stash_ref} ({commit})");
+        return Ok(stdout);
+    }
+
+    // Conflicts are reported on stdout; git keeps the stash in that case
+    if stdout.contains("CONFLICT") {
+        let conflicts = Command::new("git")
+            .args(["diff", "--name-only", "--diff-filter=U"])
+            .current_dir(repo_path)
+            .output()
+            .map(|o| {
+                String::from_utf8_lossy(&o.stdout)
+                    .trim()
+                    .replace('\n', ", ")
+            })
+            .unwrap_or_default();
+
+        log::warn!("Conflicts popping {stash_ref}: {conflicts}");
+        return Err(format!(
+            "Conflicts applying {stash_ref} in: {conflicts}. The stash was kept; resolve the conflicts, then run 'git stash drop'"
+        ));
+    }
+
+    let stderr = String::from_utf8_lossy(&output.stderr);
+    let error = if stderr.trim().is_empty() {
+        stdout.trim().to_string()
+    } else {
+        stderr.trim().to_string()
+    };
+    if error.contains("No stash entries found") || error.contains("is not a valid reference") {
+        return Err(format!("Stash not found: {stash_ref}"));
+    }
+    log::error!("Failed to pop {stash_ref}: {error}");
+    Err(error)
+}
+
+/// Drop the stash entry whose commit is `commit`, wherever it is in the list now
+fn drop_stash_commit(repo_path: &str, commit: &str) -> Result<(), String> {
+    let output = Command::new("git")
+        .args(["stash", "list", "--format=%H"])
+        .current_dir(repo_path)
+        .output()
+        .map_err(|e| format!("Failed to run git stash list: {e}"))?;
+    if !output.status.success() {
+        let stderr = String::from_utf8_lossy(&output.stderr);
+        return Err(format!("Failed to list stashes: {}", stderr.trim()));
+    }
+
+    let index = String::from_utf8_lossy(&output.stdout)
+        .lines()
+        .position(|line| line.trim() == commit)
+        .ok_or_else(|| format!("Stash {commit} was applied but is no longer in the stash list"))?;
+    let stash_ref = format!("stash@{{{index}}}");
+
+    let output = Command::new("git")
+        .args(["stash", "drop", &stash_ref])
+        .current_dir(repo_path)
+        .output()
+        .map_err(|e| format!("Failed to run git stash drop: {e}"))?;
+    if !output.status.success() {
+        let stderr = String::from_utf8_lossy(&output.stderr);
+        return Err(format!(
+            "Stash {commit} was applied but could not be dropped: {}",
+            stderr.trim()
+        ));
+    }
+    Ok(())
+}
+
 // =============================================================================
 // PR Context Generation
 // =============================================================================
@@ -1981,6 +2482,174 @@ fn handle_merge_failure(repo_path: &str, stdout: &[u8], stderr: &[u8]) -> MergeR
 mod tests {
     use super::*;
 
+    // ========================================================================
+    // Branch naming tests
+    // ========================================================================
+
+    #[test]
+    fn test_next_free_name_skips_existing_branches() {
+        let existing = ["main".to_string(), "mr-123-slug".to_string()];
+        let taken = |n: &str| existing.iter().any(|b| b == n);
+
+        assert_eq!(next_free_name("mr-123-slug", taken), "mr-123-slug-2");
+        assert_eq!(next_free_name("mr-124-other", taken), "mr-124-other");
+
+        let existing = ["mr-123-slug".to_string(), "mr-123-slug-2".to_string()];
+        assert_eq!(
+            next_free_name("mr-123-slug", |n| existing.iter().any(|b| b == n)),
+            "mr-123-slug-3"
+        );
+    }
+
+    #[test]
+    fn test_validate_branch_name() {
+        for ok in ["main", "feature/login-fix", "123-fix-the-bug", "user/v1.2"] {
+            assert!(validate_branch_name(ok).is_ok(), "{ok} should be valid");
+        }
+        for bad in [
+            "",
+            "@",
+            "-leading-dash",
+            "has space",
+            "a..b",
+            "topic.lock",
+            "dir/x.lock/y",
+            "ends-with.",
+            "/leading",
+            "trailing/",
+            "double//slash",
+            ".hidden",
+            "feature/.hidden",
+            "ref@{1}",
+            "what?",
+            "tab\there",
+        ] {
+            assert!(
+                validate_branch_name(bad).is_err(),
+                "{bad:?} should be rejected"
+            );
+        }
+    }
+
+    // ========================================================================
+    // Worktree list tests
+    // ========================================================================
+
+    #[test]
+    fn test_parse_worktree_list_flags_prunable_and_locked() {
+        let output = "worktree /repo\nHEAD b2af6f0\nbranch refs/heads/main\n\n\
+                      worktree /wt/gone\nHEAD b2af6f0\nbranch refs/heads/feature/a\n\
+                      prunable gitdir file points to non-existent location\n\n\
+                      worktree /wt/locked\nHEAD b2af6f0\ndetached\nlocked busy\nprunable\n";
+
+        let entries = parse_worktree_list(output);
+        assert_eq!(entries.len(), 3);
+        assert_eq!(entries[0].branch.as_deref(), Some("main"));
+        assert_eq!(
+            entries[1],
+            WorktreeListEntry {
+                path: "/wt/gone".to_string(),
+                branch: Some("feature/a".to_string()),
+                prunable: true,
+                locked: false,
+            }
+        );
+        assert_eq!(entries[2].branch, None);
+        assert!(entries[2].locked);
+
+        // Locked worktrees are never pruned
+        assert_eq!(prunable_worktrees(&entries), vec!["/wt/gone"]);
+    }
+
+    // ========================================================================
+    // Stash tests
+    // ========================================================================
+
+    #[test]
+    fn test_parse_stash_list() {
+        let output = "stash@{0}\x1fabc123\x1f1760000000\x1fOn feature/login: half-done form\n\
+                      stash@{1}\x1fdef456\x1f1759990000\x1fWIP on main: 1a2b3c4 Fix: typo\n\
+                      stash@{2}\x1f789abc\x1f1759980000\x1fOn (no branch): detached\n";
+
+        let entries = parse_stash_list(output);
+        assert_eq!(entries.len(), 3);
+        assert_eq!(
+            entries[0],
+            StashEntry {
+                stash_ref: "stash@{0}".to_string(),
+                commit: "abc123".to_string(),
+                branch: Some("feature/login".to_string()),
+                message: "half-done form".to_string(),
+                created_at: 1760000000,
+            }
+        );
+        assert_eq!(entries[1].branch.as_deref(), Some("main"));
+        assert_eq!(entries[1].message, "1a2b3c4 Fix: typo");
+        assert_eq!(entries[2].branch, None);
+        assert!(parse_stash_list("").is_empty());
+    }
+
+    #[test]
+    fn test_stashes_on_branch_and_find_stash() {
+        let output = "stash@{0}\x1fabc1234aaaa\x1f1760000000\x1fOn other: theirs\n\
+                      stash@{1}\x1fdef4567bbbb\x1f1759990000\x1fWIP on main: 1a2b3c4 Fix\n\
+                      stash@{2}\x1fabc1234cccc\x1f1759980000\x1fOn main: mine\n";
+        let entries = stashes_on_branch(parse_stash_list(output), Some("main"));
+        let refs: Vec<_> = entries.iter().map(|e| e.stash_ref.as_str()).collect();
+        assert_eq!(refs, vec!["stash@{1}", "stash@{2}"]);
+
+        // Latest on this branch, not the repository-wide stash@{0}
+        assert_eq!(find_stash(&entries, None).unwrap().stash_ref, "stash@{1}");
+        assert!(find_stash(&entries, Some("stash@{0}")).is_err());
+        assert_eq!(
+            find_stash(&entries, Some("abc1234")).unwrap().stash_ref,
+            "stash@{2}"
+        );
+
+        // Empty, short and non-hex prefixes are rejected
+        assert!(find_stash(&entries, Some("")).is_err());
+        assert!(find_stash(&entries, Some("abc")).is_err());
+        assert!(find_stash(&entries, Some("main..x")).is_err());
+        assert!(find_stash(&[], None).is_err());
+    }
+
+    #[test]
+    fn test_git_stash_pop_by_commit_survives_newer_stashes() {
+        let dir = tempfile::tempdir().unwrap();
+        let repo = dir.path().to_str().unwrap();
+        let git = |args: &[&str]| {
+            let output = Command::new("git")
+                .args(args)
+                .current_dir(repo)
+                .output()
+                .unwrap();
+            assert!(output.status.success(), "git {args:?} failed");
+        };
+        git(&["init", "-q", "-b", "main"]);
+        git(&["config", "user.name", "Test"]);
+        git(&["config", "user.email", "test@example.com"]);
+        std::fs::write(dir.path().join("file.txt"), "base\n").unwrap();
+        git(&["add", "."]);
+        git(&["commit", "-q", "-m", "base"]);
+
+        std::fs::write(dir.path().join("first.txt"), "first\n").unwrap();
+        let first = git_stash_push(repo, Some("first")).unwrap();
+        // Pushing another stash moves the first one from stash@{0} to stash@{1}
+        std::fs::write(dir.path().join("second.txt"), "second\n").unwrap();
+        let second = git_stash_push(repo, Some("second")).unwrap();
+
+        git_stash_pop(repo, Some(&first)).unwrap();
+        assert!(dir.path().join("first.txt").exists());
+        assert!(!dir.path().join("second.txt").exists());
+
+        let remaining: Vec<_> = git_stash_list(repo)
+            .unwrap()
+            .into_iter()
+            .map(|entry| entry.commit)
+            .collect();
+        assert_eq!(remaining, vec![second]);
+    }
+
     // ========================================================================
     // get_repo_name tests
     // ========================================================================
@@ -2041,18 +2710,102 @@ mod tests {
     #[test]
     fn test_repo_identifier_to_key() {
         let id = RepoIdentifier {
+            host: GITHUB_HOST.to_string(),
             owner: "heyandras".to_string(),
             repo: "jean".to_string(),
         };
         assert_eq!(id.to_key(), "heyandras-jean");
+        assert_eq!(id.legacy_key(), None);
     }
 
     #[test]
     fn test_repo_identifier_to_key_with_hyphen_in_name() {
         let id = RepoIdentifier {
+            host: GITHUB_HOST.to_string(),
             owner: "my-org".to_string(),
             repo: "my-project".to_string(),
         };
         assert_eq!(id.to_key(), "my-org-my-project");
     }
+
+    #[test]
+    fn test_repo_identifier_to_key_on_self_hosted_instance() {
+        let id = RepoIdentifier {
+            host: "github.mycorp.com".to_string(),
+            owner: "heyandras".to_string(),
+            repo: "jean".to_string(),
+        };
+        assert_eq!(id.to_key(), "heyandras-jean@github.mycorp.com");
+        assert_eq!(id.legacy_key().as_deref(), Some("heyandras-jean"));
+    }
+
+    #[test]
+    fn test_parse_remote_url() {
+        let parsed = |url: &str| parse_remote_url(url).map(|(h, p)| format!("{h} {p}"));
+        assert_eq!(
+            parsed("git@github.com:user/repo.git").as_deref(),
+            Some("github.com user/repo")
+        );
+        assert_eq!(
+            parsed("https://GitHub.com/user/repo.git").as_deref(),
+            Some("github.com user/repo")
+        );
+        assert_eq!(
+            parsed("https://token@github.mycorp.com/user/repo").as_deref(),
+            Some("github.mycorp.com user/repo")
+        );
+        assert_eq!(
+            parsed("ssh://git@gitlab.example.com:2222/group/sub/repo.git").as_deref(),
+            Some("gitlab.example.com group/sub/repo")
+        );
+        assert_eq!(parsed("not a url"), None);
+    }
+
+    #[test]
+    fn test_github_url_from_remote_accepts_enterprise_host() {
+        assert_eq!(
+            github_url_from_remote("git@github.com:user/repo.git", None).unwrap(),
+            "https://github.com/user/repo"
+        );
+
+        let remote = "git@github.mycorp.com:user/repo.git";
+        assert!(github_url_from_remote(remote, None).is_err());
+        assert_eq!(
+            github_url_from_remote(remote, Some("github.mycorp.com")).unwrap(),
+            "https://github.mycorp.com/user/repo"
+        );
+    }
+
+    #[test]
+    fn test_configured_host_prefers_preference_over_env() {
+        let env = Some("https://github.env.com/".to_string());
+        assert_eq!(
+            configured_host("GitHub.MyCorp.com", env.clone(), GITHUB_HOST).as_deref(),
+            Some("github.mycorp.com")
+        );
+        assert_eq!(
+            configured_host("  ", env, GITHUB_HOST).as_deref(),
+            Some("github.env.com")
+        );
+        assert_eq!(configured_host("", None, GITHUB_HOST), None);
+        assert_eq!(
+            configured_host("https://github.com", None, GITHUB_HOST),
+            None
+        );
+    }
+
+    #[test]
+    fn test_gitlab_url_from_remote() {
+        assert_eq!(
+            gitlab_url_from_remote("git@gitlab.example.com:group/repo.git", None).unwrap(),
+            "https://gitlab.example.com/group/repo"
+        );
+
+        let remote = "https://git.mycorp.com/group/repo.git";
+        assert!(gitlab_url_from_remote(remote, None).is_err());
+        assert_eq!(
+            gitlab_url_from_remote(remote, Some("git.mycorp.com")).unwrap(),
+            "https://git.mycorp.com/group/repo"
+        );
+    }
 }
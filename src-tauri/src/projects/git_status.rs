@@ -410,7 +410,7 @@ pub struct DiffFile {
 /// Complete diff response
 #[derive(Debug, Clone, Serialize)]
 pub struct GitDiff {
-    /// Type of diff: "uncommitted" or "branch"
+    /// Type of diff: "uncommitted", "branch" or "run"
     pub diff_type: String,
     /// Base ref (e.g., "origin/main" or "HEAD")
     pub base_ref: String,
@@ -491,6 +491,40 @@ pub fn get_git_diff(
     }
 
     let stdout = String::from_utf8_lossy(&output.stdout);
+    let mut files = parse_diff_files(&stdout);
+
+    // Build raw patch - start with git diff output
+    let mut raw_patch = stdout.to_string();
+
+    // For uncommitted diffs, also include untracked (new) files
+    if diff_type == "uncommitted" {
+        let untracked_files = get_untracked_files_diff(repo_path);
+        files.extend(untracked_files);
+
+        // Add raw patch for untracked files
+        let untracked_patch = get_untracked_files_raw_patch(repo_path);
+        if !untracked_patch.is_empty() {
+            raw_patch.push_str(&untracked_patch);
+        }
+    }
+
+    // Calculate totals
+    let total_additions: u32 = files.iter().map(|f| f.additions).sum();
+    let total_deletions: u32 = files.iter().map(|f| f.deletions).sum();
+
+    Ok(GitDiff {
+        diff_type: diff_type.to_string(),
+        base_ref,
+        target_ref,
+        total_additions,
+        total_deletions,
+        files,
+        raw_patch,
+    })
+}
+
+/// Parse `git diff` unified output into per-file hunks
+fn parse_diff_files(stdout: &str) -> Vec<DiffFile> {
     let mut files: Vec<DiffFile> = Vec::new();
     let mut current_file: Option<DiffFile> = None;
     let mut current_hunk: Option<DiffHunk> = None;
@@ -619,28 +653,226 @@ pub fn get_git_diff(
         files.push(file);
     }
 
-    // Build raw patch - start with git diff output
-    let mut raw_patch = stdout.to_string();
+    files
+}
 
-    // For uncommitted diffs, also include untracked (new) files
-    if diff_type == "uncommitted" {
-        let untracked_files = get_untracked_files_diff(repo_path);
-        files.extend(untracked_files);
+// ============================================================================
+// Worktree Snapshots
+// ============================================================================
 
-        // Add raw patch for untracked files
-        let untracked_patch = get_untracked_files_raw_patch(repo_path);
-        if !untracked_patch.is_empty() {
-            raw_patch.push_str(&untracked_patch);
+/// Snapshot the full working tree (tracked + untracked, respecting .gitignore)
+/// as a git tree object, without touching the real index or working directory.
+///
+/// Uses a throwaway index seeded from the real one so `git add -A` only has to
+/// re-hash files that changed. Returns the tree hash.
+pub fn snapshot_worktree_tree(repo_path: &str) -> Result<String, String> {
+    let git_path = |name: &str| -> Result<std::path::PathBuf, String> {
+        let output = Command::new("git")
+            .args(["rev-parse", "--git-path", name])
+            .current_dir(repo_path)
+            .output()
+            .map_err(|e| format!("Failed to run git rev-parse: {e}"))?;
+        if !output.status.success() {
+            let stderr = String::from_utf8_lossy(&output.stderr);
+            return Err(format!("Not a git repository: {stderr}"));
         }
+        let path = String::from_utf8_lossy(&output.stdout).trim().to_string();
+        Ok(std::path::Path::new(repo_path).join(path))
+    };
+
+    let real_index = git_path("index")?;
+    let temp_index = git_path(&format!("jean-snapshot-{}.index", uuid::Uuid::new_v4()))?;
+    if real_index.exists() {
+        std::fs::copy(&real_index, &temp_index)
+            .map_err(|e| format!("Failed to copy git index: {e}"))?;
     }
 
-    // Calculate totals
+    let run_with_temp_index = |args: &[&str]| {
+        Command::new("git")
+            .args(args)
+            .env("GIT_INDEX_FILE", &temp_index)
+            .current_dir(repo_path)
+            .output()
+            .map_err(|e| format!("Failed to run git {}: {e}", args[0]))
+    };
+
+    let result = run_with_temp_index(&["add", "-A"]).and_then(|output| {
+        if !output.status.success() {
+            let stderr = String::from_utf8_lossy(&output.stderr);
+            return Err(format!("Failed to stage snapshot: {stderr}"));
+        }
+        let output = run_with_temp_index(&["write-tree"])?;
+        if !output.status.success() {
+            let stderr = String::from_utf8_lossy(&output.stderr);
+            return Err(format!("Failed to write snapshot tree: {stderr}"));
+        }
+        Ok(String::from_utf8_lossy(&output.stdout).trim().to_string())
+    });
+
+    let _ = std::fs::remove_file(&temp_index);
+    result
+}
+
+/// Ref that keeps one of a run's snapshots from being pruned by `git gc`
+///
+/// `which` is "start" or "end". Keyed by session too, since duplicated
+/// sessions share run IDs.
+fn run_snapshot_ref(session_id: &str, run_id: &str, which: &str) -> String {
+    format!("refs/jean/runs/{session_id}/{run_id}/{which}")
+}
+
+/// Pin a snapshot tree so it survives `git gc`
+///
+/// Nothing else references the tree, so it's wrapped in a parentless commit
+/// and pointed to by a `refs/jean/runs/...` ref.
+pub fn pin_run_snapshot(
+    repo_path: &str,
+    session_id: &str,
+    run_id: &str,
+    which: &str,
+    tree: &str,
+) -> Result<(), String> {
+    let output = Command::new("git")
+        .args([
+            "commit-tree",
+            tree,
+            "-m",
+            &format!("Jean run {run_id} {which}"),
+        ])
+        .env("GIT_AUTHOR_NAME", "Jean")
+        .env("GIT_AUTHOR_EMAIL", "jean@localhost")
+        .env("GIT_COMMITTER_NAME", "Jean")
+        .env("GIT_COMMITTER_EMAIL", "jean@localhost")
+        .current_dir(repo_path)
+        .output()
+        .map_err(|e| format!("Failed to run git commit-tree: {e}"))?;
+    if !output.status.success() {
+        let stderr = String::from_utf8_lossy(&output.stderr);
+        return Err(format!("Failed to pin snapshot: {stderr}"));
+    }
+    let commit = String::from_utf8_lossy(&output.stdout).trim().to_string();
+
+    let git_ref = run_snapshot_ref(session_id, run_id, which);
+    let output = Command::new("git")
+        .args(["update-ref", &git_ref, &commit])
+        .current_dir(repo_path)
+        .output()
+        .map_err(|e| format!("Failed to run git update-ref: {e}"))?;
+    if !output.status.success() {
+        let stderr = String::from_utf8_lossy(&output.stderr);
+        return Err(format!("Failed to pin snapshot: {stderr}"));
+    }
+    Ok(())
+}
+
+/// Resolve one of a run's snapshots to its tree hash
+///
+/// Prefers the pinned ref, falling back to the tree recorded in the run
+/// manifest (runs from before snapshots were pinned). Errors when neither is
+/// in the repository any more, e.g. after `git gc` pruned it.
+pub fn resolve_run_snapshot(
+    repo_path: &str,
+    session_id: &str,
+    run_id: &str,
+    which: &str,
+    recorded_tree: &str,
+) -> Result<String, String> {
+    let pinned = format!("{}^{{tree}}", run_snapshot_ref(session_id, run_id, which));
+    let recorded = format!("{recorded_tree}^{{tree}}");
+    for candidate in [pinned, recorded] {
+        let output = Command::new("git")
+            .args(["rev-parse", "--verify", "--quiet", &candidate])
+            .current_dir(repo_path)
+            .output()
+            .map_err(|e| format!("Failed to run git rev-parse: {e}"))?;
+        if output.status.success() {
+            return Ok(String::from_utf8_lossy(&output.stdout).trim().to_string());
+        }
+    }
+    Err(format!(
+        "The {which} snapshot of this run is no longer available in the repository"
+    ))
+}
+
+/// Delete the refs pinning a session's run snapshots
+pub fn delete_run_snapshots(repo_path: &str, session_id: &str) -> Result<(), String> {
+    let output = Command::new("git")
+        .args([
+            "for-each-ref",
+            "--format=%(refname)",
+            &format!("refs/jean/runs/{session_id}/"),
+        ])
+        .current_dir(repo_path)
+        .output()
+        .map_err(|e| format!("Failed to run git for-each-ref: {e}"))?;
+    if !output.status.success() {
+        let stderr = String::from_utf8_lossy(&output.stderr);
+        return Err(format!("Failed to list snapshot refs: {stderr}"));
+    }
+
+    for git_ref in String::from_utf8_lossy(&output.stdout).lines() {
+        let output = Command::new("git")
+            .args(["update-ref", "-d", git_ref])
+            .current_dir(repo_path)
+            .output()
+            .map_err(|e| format!("Failed to run git update-ref: {e}"))?;
+        if !output.status.success() {
+            let stderr = String::from_utf8_lossy(&output.stderr);
+            return Err(format!("Failed to delete {git_ref}: {stderr}"));
+        }
+    }
+    Ok(())
+}
+
+/// Get the HEAD commit hash (None for repos without commits)
+pub fn get_head_sha(repo_path: &str) -> Option<String> {
+    let output = Command::new("git")
+        .args(["rev-parse", "HEAD"])
+        .current_dir(repo_path)
+        .output()
+        .ok()?;
+    if !output.status.success() {
+        return None;
+    }
+    Some(String::from_utf8_lossy(&output.stdout).trim().to_string())
+}
+
+/// Get the diff between two worktree snapshots
+///
+/// When `target_tree` is None, the current working tree is snapshotted and used.
+/// Untracked files are included because snapshots stage everything.
+pub fn get_snapshot_diff(
+    repo_path: &str,
+    base_tree: &str,
+    target_tree: Option<&str>,
+) -> Result<GitDiff, String> {
+    let (target_tree, target_ref) = match target_tree {
+        Some(tree) => (tree.to_string(), "run end".to_string()),
+        None => (
+            snapshot_worktree_tree(repo_path)?,
+            "working directory".to_string(),
+        ),
+    };
+
+    let output = Command::new("git")
+        .args(["diff", "--unified=3", base_tree, &target_tree])
+        .current_dir(repo_path)
+        .output()
+        .map_err(|e| format!("Failed to run git diff: {e}"))?;
+
+    if !output.status.success() {
+        let stderr = String::from_utf8_lossy(&output.stderr);
+        return Err(format!("Git diff failed: {stderr}"));
+    }
+
+    let raw_patch = String::from_utf8_lossy(&output.stdout).to_string();
+    let files = parse_diff_files(&raw_patch);
     let total_additions: u32 = files.iter().map(|f| f.additions).sum();
     let total_deletions: u32 = files.iter().map(|f| f.deletions).sum();
 
     Ok(GitDiff {
-        diff_type: diff_type.to_string(),
-        base_ref,
+        diff_type: "run".to_string(),
+        base_ref: "run start".to_string(),
         target_ref,
         total_additions,
         total_deletions,
@@ -733,10 +965,200 @@ pub fn get_branch_status(info: &ActiveWorktreeInfo) -> Result<GitBranchStatus, S
     })
 }
 
+/// Restore the working directory from `end_tree` back to `start_tree`
+///
+/// Refuses when the working directory no longer matches `end_tree` (i.e. someone
+/// changed files after the snapshot), so only the snapshotted changes are undone.
+/// Applies the reverse patch to the working directory only: the index and any
+/// commits made in between are left untouched.
+pub fn revert_to_snapshot(repo_path: &str, start_tree: &str, end_tree: &str) -> Result<(), String> {
+    let current_tree = snapshot_worktree_tree(repo_path)?;
+    if current_tree != end_tree {
+        return Err(
+            "The worktree has changed since the run ended. Refusing to revert over manual changes."
+                .to_string(),
+        );
+    }
+
+    let diff = Command::new("git")
+        .args(["diff", "--binary", end_tree, start_tree])
+        .current_dir(repo_path)
+        .output()
+        .map_err(|e| format!("Failed to run git diff: {e}"))?;
+    if !diff.status.success() {
+        let stderr = String::from_utf8_lossy(&diff.stderr);
+        return Err(format!("Git diff failed: {stderr}"));
+    }
+    if diff.stdout.is_empty() {
+        return Ok(());
+    }
+
+    let mut apply = Command::new("git")
+        .args(["apply", "--whitespace=nowarn", "-"])
+        .current_dir(repo_path)
+        .stdin(std::process::Stdio::piped())
+        .stdout(std::process::Stdio::null())
+        .stderr(std::process::Stdio::piped())
+        .spawn()
+        .map_err(|e| format!("Failed to run git apply: {e}"))?;
+    if let Some(mut stdin) = apply.stdin.take() {
+        use std::io::Write;
+        stdin
+            .write_all(&diff.stdout)
+            .map_err(|e| format!("Failed to write patch to git apply: {e}"))?;
+    }
+    let output = apply
+        .wait_with_output()
+        .map_err(|e| format!("Failed to wait for git apply: {e}"))?;
+    if !output.status.success() {
+        let stderr = String::from_utf8_lossy(&output.stderr);
+        return Err(format!("Failed to revert changes: {stderr}"));
+    }
+
+    Ok(())
+}
+
+/// Upper bound on concurrent `git status` processes in a batch
+const MAX_STATUS_THREADS: usize = 8;
+
+/// Compact working tree summary for dashboards polling many worktrees
+#[derive(Debug, Clone, Default, Serialize, PartialEq)]
+pub struct WorktreeStatus {
+    pub worktree_path: String,
+    /// Checked out branch (None when HEAD is detached)
+    pub current_branch: Option<String>,
+    /// Upstream tracking branch, if configured
+    pub upstream: Option<String>,
+    /// Commits ahead of the upstream branch
+    pub ahead_count: u32,
+    /// Commits behind the upstream branch
+    pub behind_count: u32,
+    /// Changed, staged, conflicted and untracked files
+    pub dirty_count: u32,
+    /// Set if `git status` failed for this worktree
+    pub error: Option<String>,
+}
+
+/// Parse `git status --porcelain=v2 --branch` output
+fn parse_porcelain_v2_status(worktree_path: &str, output: &str) -> WorktreeStatus {
+    let mut status = WorktreeStatus {
+        worktree_path: worktree_path.to_string(),
+        ..Default::default()
+    };
+
+    for line in output.lines() {
+        if let Some(head) = line.strip_prefix("# branch.head ") {
+            status.current_branch = (head != "(detached)").then(|| head.to_string());
+        } else if let Some(upstream) = line.strip_prefix("# branch.upstream ") {
+            status.upstream = Some(upstream.to_string());
+        } else if let Some(ab) = line.strip_prefix("# branch.ab ") {
+            // "+<ahead> -<behind>"
+            for part in ab.split_whitespace() {
+                if let Some(ahead) = part.strip_prefix('+') {
+                    status.ahead_count = ahead.parse().unwrap_or(0);
+                } else if let Some(behind) = part.strip_prefix('-') {
+                    status.behind_count = behind.parse().unwrap_or(0);
+                }
+            }
+        } else if ["1 ", "2 ", "u ", "? "]
+            .iter()
+            .any(|prefix| line.starts_with(prefix))
+        {
+            status.dirty_count += 1;
+        }
+    }
+
+    status
+}
+
+/// Summarize a worktree with a single `git status --porcelain=v2 --branch` call
+///
+/// `--no-optional-locks` keeps polling from contending with git commands the
+/// user (or an agent) is running in the same worktree.
+pub fn get_worktree_status(worktree_path: &str) -> WorktreeStatus {
+    let output = Command::new("git")
+        .args([
+            "--no-optional-locks",
+            "status",
+            "--porcelain=v2",
+            "--branch",
+        ])
+        .current_dir(worktree_path)
+        .output();
+
+    let error = match output {
+        Ok(output) if output.status.success() => {
+            let stdout = String::from_utf8_lossy(&output.stdout);
+            return parse_porcelain_v2_status(worktree_path, &stdout);
+        }
+        Ok(output) => format!(
+            "git status failed: {}",
+            String::from_utf8_lossy(&output.stderr).trim()
+        ),
+        Err(e) => format!("Failed to run git status: {e}"),
+    };
+
+    WorktreeStatus {
+        worktree_path: worktree_path.to_string(),
+        error: Some(error),
+        ..Default::default()
+    }
+}
+
+/// Summarize many worktrees in parallel, in the same order as `paths`
+///
+/// At most `MAX_STATUS_THREADS` git processes run at a time.
+pub fn get_worktree_status_batch(paths: &[String]) -> Vec<WorktreeStatus> {
+    crate::util::parallel_map(paths, MAX_STATUS_THREADS, |path| get_worktree_status(path))
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
 
+    #[test]
+    fn test_parse_porcelain_v2_status() {
+        let output = "\
+# branch.oid 1234567890abcdef1234567890abcdef12345678
+# branch.head feature/login
+# branch.upstream origin/feature/login
+# branch.ab +2 -5
+1 .M N... 100644 100644 100644 aaaaaaa bbbbbbb src/main.rs
+2 R. N... 100644 100644 100644 aaaaaaa bbbbbbb R100 new.rs\told.rs
+u UU N... 100644 100644 100644 100644 aaaaaaa bbbbbbb ccccccc conflict.rs
+? notes.txt
+! target/
+";
+        let status = parse_porcelain_v2_status("/tmp/wt", output);
+        assert_eq!(status.worktree_path, "/tmp/wt");
+        assert_eq!(status.current_branch.as_deref(), Some("feature/login"));
+        assert_eq!(status.upstream.as_deref(), Some("origin/feature/login"));
+        assert_eq!(status.ahead_count, 2);
+        assert_eq!(status.behind_count, 5);
+        // Ignored files don't count as dirty
+        assert_eq!(status.dirty_count, 4);
+        assert!(status.error.is_none());
+    }
+
+    #[test]
+    fn test_parse_porcelain_v2_status_detached_without_upstream() {
+        let output = "# branch.oid 1234567\n# branch.head (detached)\n";
+        let status = parse_porcelain_v2_status("/tmp/wt", output);
+        assert_eq!(status.current_branch, None);
+        assert_eq!(status.upstream, None);
+        assert_eq!((status.ahead_count, status.behind_count), (0, 0));
+        assert_eq!(status.dirty_count, 0);
+    }
+
+    #[test]
+    fn test_worktree_status_batch_reports_errors_in_order() {
+        let missing = std::env::temp_dir().join("jean-missing-worktree-for-status-test");
+        let paths = vec![missing.to_string_lossy().to_string(); 3];
+        let statuses = get_worktree_status_batch(&paths);
+        assert_eq!(statuses.len(), 3);
+        assert!(statuses.iter().all(|s| s.error.is_some()));
+    }
+
     #[test]
     fn test_git_branch_status_serialization() {
         let status = GitBranchStatus {
@@ -903,4 +1325,135 @@ mod tests {
         assert!(json.contains("\"old_start\":1"));
         assert!(json.contains("\"new_lines\":7"));
     }
+
+    /// Create a repo with two staged files, snapshot it, then simulate a run
+    /// that edits a tracked file and creates an untracked one.
+    /// Returns (tempdir, start_tree, end_tree).
+    fn repo_with_simulated_run() -> (tempfile::TempDir, String, String) {
+        let dir = tempfile::tempdir().unwrap();
+        let repo = dir.path().to_str().unwrap();
+        let status = Command::new("git")
+            .args(["init", "-q"])
+            .current_dir(repo)
+            .status()
+            .unwrap();
+        assert!(status.success());
+        std::fs::write(dir.path().join("kept.txt"), "one\n").unwrap();
+        std::fs::write(dir.path().join("edited.txt"), "before\n").unwrap();
+        let status = Command::new("git")
+            .args(["add", "-A"])
+            .current_dir(repo)
+            .status()
+            .unwrap();
+        assert!(status.success());
+
+        let start = snapshot_worktree_tree(repo).unwrap();
+        std::fs::write(dir.path().join("edited.txt"), "after\n").unwrap();
+        std::fs::write(dir.path().join("created.txt"), "new\n").unwrap();
+        let end = snapshot_worktree_tree(repo).unwrap();
+
+        (dir, start, end)
+    }
+
+    #[test]
+    fn test_snapshot_diff_includes_changed_and_untracked_files() {
+        let (dir, start, end) = repo_with_simulated_run();
+        let repo = dir.path().to_str().unwrap();
+
+        let diff = get_snapshot_diff(repo, &start, Some(&end)).unwrap();
+        let paths: Vec<&str> = diff.files.iter().map(|f| f.path.as_str()).collect();
+        assert_eq!(paths, vec!["created.txt", "edited.txt"]);
+        assert_eq!(diff.files[0].status, "added");
+        assert_eq!(diff.files[1].status, "modified");
+        assert_eq!(diff.total_additions, 2);
+        assert_eq!(diff.total_deletions, 1);
+
+        // Snapshots must not stage anything in the real index
+        let output = Command::new("git")
+            .args(["status", "--porcelain"])
+            .current_dir(repo)
+            .output()
+            .unwrap();
+        assert!(String::from_utf8_lossy(&output.stdout).contains("?? created.txt"));
+    }
+
+    fn git_gc(repo: &str) {
+        let status = Command::new("git")
+            .args(["-c", "gc.reflogExpire=now", "gc", "-q", "--prune=now"])
+            .current_dir(repo)
+            .status()
+            .unwrap();
+        assert!(status.success());
+    }
+
+    #[test]
+    fn test_pinned_snapshots_survive_gc_until_deleted() {
+        let (dir, start, end) = repo_with_simulated_run();
+        let repo = dir.path().to_str().unwrap();
+
+        pin_run_snapshot(repo, "s1", "r1", "start", &start).unwrap();
+        pin_run_snapshot(repo, "s1", "r1", "end", &end).unwrap();
+        git_gc(repo);
+
+        assert_eq!(
+            resolve_run_snapshot(repo, "s1", "r1", "start", &start).unwrap(),
+            start
+        );
+        let end_tree = resolve_run_snapshot(repo, "s1", "r1", "end", &end).unwrap();
+        let diff = get_snapshot_diff(repo, &start, Some(&end_tree)).unwrap();
+        assert_eq!(diff.files.len(), 2);
+
+        delete_run_snapshots(repo, "s1").unwrap();
+        git_gc(repo);
+        let result = resolve_run_snapshot(repo, "s1", "r1", "end", &end);
+        assert!(result.unwrap_err().contains("no longer available"));
+    }
+
+    #[test]
+    fn test_revert_to_snapshot_restores_pre_run_state() {
+        let (dir, start, end) = repo_with_simulated_run();
+        let repo = dir.path().to_str().unwrap();
+
+        revert_to_snapshot(repo, &start, &end).unwrap();
+
+        assert_eq!(
+            std::fs::read_to_string(dir.path().join("edited.txt")).unwrap(),
+            "before\n"
+        );
+        assert!(!dir.path().join("created.txt").exists());
+        assert_eq!(snapshot_worktree_tree(repo).unwrap(), start);
+    }
+
+    #[test]
+    fn test_revert_to_pinned_snapshot_after_gc() {
+        let (dir, start, end) = repo_with_simulated_run();
+        let repo = dir.path().to_str().unwrap();
+
+        pin_run_snapshot(repo, "s1", "r1", "start", &start).unwrap();
+        pin_run_snapshot(repo, "s1", "r1", "end", &end).unwrap();
+        git_gc(repo);
+
+        let start = resolve_run_snapshot(repo, "s1", "r1", "start", &start).unwrap();
+        let end = resolve_run_snapshot(repo, "s1", "r1", "end", &end).unwrap();
+        revert_to_snapshot(repo, &start, &end).unwrap();
+        assert!(!dir.path().join("created.txt").exists());
+    }
+
+    #[test]
+    fn test_revert_to_snapshot_refuses_manual_changes() {
+        let (dir, start, end) = repo_with_simulated_run();
+        let repo = dir.path().to_str().unwrap();
+
+        // User edits a file after the run ended
+        std::fs::write(dir.path().join("kept.txt"), "manual\n").unwrap();
+
+        let result = revert_to_snapshot(repo, &start, &end);
+        assert!(result.unwrap_err().contains("Refusing"));
+        // Nothing was touched
+        assert_eq!(
+            std::fs::read_to_string(dir.path().join("edited.txt")).unwrap(),
+            "after\n"
+        );
+        assert!(dir.path().join("created.txt").exists());
+    }
 }
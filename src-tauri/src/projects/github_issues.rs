@@ -1,10 +1,17 @@
+use once_cell::sync::Lazy;
 use serde::{Deserialize, Serialize};
-use std::path::PathBuf;
-use std::process::Command;
+use sha2::{Digest, Sha256};
+use std::io::Write;
+use std::path::{Path, PathBuf};
+use std::process::{Command, Output, Stdio};
+use std::sync::Mutex;
 use std::time::{SystemTime, UNIX_EPOCH};
 use tauri::Manager;
 
-use super::git::get_repo_identifier;
+use super::git::{get_repo_identifier, RepoIdentifier};
+use super::gitlab_issues::{
+    gitlab_reference_key, migrate_legacy_gitlab_keys, parse_gitlab_context_key,
+};
 
 // =============================================================================
 // GitHub Types
@@ -60,6 +67,26 @@ pub struct GitHubIssueDetail {
     pub comments: Vec<GitHubComment>,
 }
 
+/// One page of an issue's comments, oldest first
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct GitHubCommentPage {
+    pub comments: Vec<GitHubComment>,
+    /// 1-based page number
+    pub page: u32,
+    pub has_more: bool,
+}
+
+/// Comment as returned by the REST API (`gh api .../comments`)
+#[derive(Debug, Deserialize)]
+struct RestComment {
+    #[serde(default)]
+    body: Option<String>,
+    /// Null for deleted accounts
+    user: Option<GitHubAuthor>,
+    created_at: String,
+}
+
 /// Issue context to pass when creating a worktree
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct IssueContext {
@@ -172,27 +199,121 @@ pub async fn search_github_issues(
     Ok(issues)
 }
 
+/// Comments fetched per page by `get_github_issue_comments`
+const COMMENTS_PER_PAGE: u32 = 50;
+
 /// Get detailed information about a specific GitHub issue
 ///
-/// Uses `gh issue view` to fetch the issue with comments.
+/// Uses `gh issue view` to fetch the issue with all of its comments. Used by
+/// the worktree-context flow; the UI uses `get_github_issue_summary` and
+/// loads comments on demand.
 #[tauri::command]
 pub async fn get_github_issue(
     project_path: String,
     issue_number: u32,
 ) -> Result<GitHubIssueDetail, String> {
     log::trace!("Getting GitHub issue #{issue_number} for {project_path}");
+    view_github_issue(
+        &project_path,
+        issue_number,
+        "number,title,body,state,labels,createdAt,author,comments",
+    )
+}
+
+/// Get a GitHub issue without its comments
+///
+/// Fast even for issues with long threads; `comments` is left empty. Use
+/// `get_github_issue_comments` to page through the thread.
+#[tauri::command]
+pub async fn get_github_issue_summary(
+    project_path: String,
+    issue_number: u32,
+) -> Result<GitHubIssueDetail, String> {
+    log::trace!("Getting GitHub issue #{issue_number} summary for {project_path}");
+    view_github_issue(
+        &project_path,
+        issue_number,
+        "number,title,body,state,labels,createdAt,author",
+    )
+}
+
+/// Get one page of a GitHub issue's comments (1-based `page`, default 1)
+///
+/// Uses `gh api` on the REST comments endpoint, oldest comments first.
+#[tauri::command]
+pub async fn get_github_issue_comments(
+    project_path: String,
+    issue_number: u32,
+    page: Option<u32>,
+) -> Result<GitHubCommentPage, String> {
+    let page = page.unwrap_or(1).max(1);
+    log::trace!("Getting comments page {page} of GitHub issue #{issue_number} for {project_path}");
 
-    // Run gh issue view
     let output = Command::new("gh")
         .args([
-            "issue",
-            "view",
-            &issue_number.to_string(),
-            "--json",
-            "number,title,body,state,labels,createdAt,author,comments",
+            "api",
+            &format!(
+                "repos/{{owner}}/{{repo}}/issues/{issue_number}/comments?per_page={COMMENTS_PER_PAGE}&page={page}"
+            ),
         ])
         .current_dir(&project_path)
         .output()
+        .map_err(|e| format!("Failed to run gh api: {e}"))?;
+
+    if !output.status.success() {
+        let stderr = String::from_utf8_lossy(&output.stderr);
+        if stderr.contains("gh auth login") || stderr.contains("authentication") {
+            return Err("GitHub CLI not authenticated. Run 'gh auth login' first.".to_string());
+        }
+        if stderr.contains("Not Found") {
+            return Err(format!("Issue #{issue_number} not found"));
+        }
+        return Err(format!("gh api issue comments failed: {stderr}"));
+    }
+
+    let page = parse_comment_page(&String::from_utf8_lossy(&output.stdout), page)?;
+    log::trace!(
+        "Got {} comments for issue #{issue_number}",
+        page.comments.len()
+    );
+    Ok(page)
+}
+
+/// Parse a REST comments response into a page of comments
+fn parse_comment_page(json: &str, page: u32) -> Result<GitHubCommentPage, String> {
+    let comments: Vec<RestComment> =
+        serde_json::from_str(json).map_err(|e| format!("Failed to parse gh response: {e}"))?;
+
+    // A full page means there may be more; an exact multiple costs one empty fetch
+    let has_more = comments.len() as u32 >= COMMENTS_PER_PAGE;
+    let comments = comments
+        .into_iter()
+        .map(|c| GitHubComment {
+            body: c.body.unwrap_or_default(),
+            author: c.user.unwrap_or_else(|| GitHubAuthor {
+                login: "ghost".to_string(),
+            }),
+            created_at: c.created_at,
+        })
+        .collect();
+
+    Ok(GitHubCommentPage {
+        comments,
+        page,
+        has_more,
+    })
+}
+
+/// Run `gh issue view` with the given `--json` fields
+fn view_github_issue(
+    project_path: &str,
+    issue_number: u32,
+    fields: &str,
+) -> Result<GitHubIssueDetail, String> {
+    let output = Command::new("gh")
+        .args(["issue", "view", &issue_number.to_string(), "--json", fields])
+        .current_dir(project_path)
+        .output()
         .map_err(|e| format!("Failed to run gh issue view: {e}"))?;
 
     if !output.status.success() {
@@ -299,6 +420,9 @@ pub struct LoadedIssueContext {
     pub comment_count: usize,
     pub repo_owner: String,
     pub repo_name: String,
+    /// Whether the issue changed since the context was loaded (only set when checked)
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub stale: Option<bool>,
 }
 
 // =============================================================================
@@ -310,6 +434,48 @@ pub struct LoadedIssueContext {
 pub struct ContextRef {
     pub worktrees: Vec<String>,
     pub orphaned_at: Option<u64>,
+    /// SHA-256 of the markdown last written by a context loader
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub content_hash: Option<String>,
+    /// Cheap summary of the PR when it was loaded (see `get_pr_fingerprint`),
+    /// compared instead of re-fetching the whole PR and its diff
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub fingerprint: Option<String>,
+}
+
+impl ContextRef {
+    /// Add a worktree reference, clearing any orphaned status
+    fn add_worktree(&mut self, worktree_id: &str) {
+        if !self.worktrees.iter().any(|w| w == worktree_id) {
+            self.worktrees.push(worktree_id.to_string());
+        }
+        self.orphaned_at = None;
+    }
+
+    /// Fold in the reference tracked under an old key for the same context
+    pub fn merge_from(&mut self, other: ContextRef) {
+        for worktree in other.worktrees {
+            if !self.worktrees.contains(&worktree) {
+                self.worktrees.push(worktree);
+            }
+        }
+        self.orphaned_at = if self.worktrees.is_empty() {
+            self.orphaned_at.or(other.orphaned_at)
+        } else {
+            None
+        };
+        self.content_hash = self.content_hash.take().or(other.content_hash);
+        self.fingerprint = self.fingerprint.take().or(other.fingerprint);
+    }
+}
+
+/// Which reference map a shared context file is tracked in
+///
+/// GitLab issues and MRs share the issue and PR maps under `gitlab-` keys.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum ContextKind {
+    Issue,
+    Pr,
 }
 
 /// Tracks which worktrees reference which shared context files
@@ -319,6 +485,41 @@ pub struct ContextReferences {
     pub prs: std::collections::HashMap<String, ContextRef>,
 }
 
+impl ContextReferences {
+    /// The map tracking contexts of `kind`
+    fn map_mut(&mut self, kind: ContextKind) -> &mut std::collections::HashMap<String, ContextRef> {
+        match kind {
+            ContextKind::Issue => &mut self.issues,
+            ContextKind::Pr => &mut self.prs,
+        }
+    }
+
+    /// Remove a worktree's reference to a context
+    /// Returns true if the context is now orphaned (no more references)
+    fn remove_reference(&mut self, kind: ContextKind, key: &str, worktree_id: &str) -> bool {
+        let Some(entry) = self.map_mut(kind).get_mut(key) else {
+            return false;
+        };
+        entry.worktrees.retain(|w| w != worktree_id);
+        if entry.worktrees.is_empty() && entry.orphaned_at.is_none() {
+            entry.orphaned_at = Some(
+                SystemTime::now()
+                    .duration_since(UNIX_EPOCH)
+                    .unwrap_or_default()
+                    .as_secs(),
+            );
+            true
+        } else {
+            false
+        }
+    }
+}
+
+/// Global mutex to prevent concurrent read-modify-write races on references.json.
+/// Without it, two worktrees loading the same issue at once can lose a reference
+/// and have the shared file deleted as orphaned.
+static REFERENCES_LOCK: Lazy<Mutex<()>> = Lazy::new(|| Mutex::new(()));
+
 /// Get the directory for shared GitHub contexts
 pub fn get_github_contexts_dir(app: &tauri::AppHandle) -> Result<PathBuf, String> {
     let app_data_dir = app
@@ -333,30 +534,190 @@ pub fn get_references_path(app: &tauri::AppHandle) -> Result<PathBuf, String> {
     Ok(get_github_contexts_dir(app)?.join("references.json"))
 }
 
-/// Load context references from disk
-pub fn load_context_references(app: &tauri::AppHandle) -> Result<ContextReferences, String> {
-    let path = get_references_path(app)?;
+/// Load context references from disk (internal, no locking)
+///
+/// Legacy GitLab keys are migrated in memory; returns whether any were.
+fn load_references_internal(path: &Path) -> Result<(ContextReferences, bool), String> {
     if !path.exists() {
-        return Ok(ContextReferences::default());
+        return Ok((ContextReferences::default(), false));
     }
-    let content = std::fs::read_to_string(&path)
+    let content = std::fs::read_to_string(path)
         .map_err(|e| format!("Failed to read references.json: {e}"))?;
-    serde_json::from_str(&content).map_err(|e| format!("Failed to parse references.json: {e}"))
+    let mut refs: ContextReferences = serde_json::from_str(&content)
+        .map_err(|e| format!("Failed to parse references.json: {e}"))?;
+
+    // Rewrite GitLab keys from the old "{repo_key}-{iid}" format
+    let migrated = migrate_legacy_gitlab_keys(&mut refs);
+    if migrated {
+        log::debug!("Migrated legacy GitLab context reference keys");
+    }
+
+    Ok((refs, migrated))
 }
 
-/// Save context references to disk
-pub fn save_context_references(
-    app: &tauri::AppHandle,
-    refs: &ContextReferences,
-) -> Result<(), String> {
-    let dir = get_github_contexts_dir(app)?;
-    std::fs::create_dir_all(&dir)
-        .map_err(|e| format!("Failed to create git-context directory: {e}"))?;
+/// Save context references to disk (internal, no locking; atomic write: temp file + rename)
+fn save_references_internal(path: &Path, refs: &ContextReferences) -> Result<(), String> {
+    if let Some(dir) = path.parent() {
+        std::fs::create_dir_all(dir)
+            .map_err(|e| format!("Failed to create git-context directory: {e}"))?;
+    }
 
-    let path = dir.join("references.json");
     let content = serde_json::to_string_pretty(refs)
         .map_err(|e| format!("Failed to serialize references: {e}"))?;
-    std::fs::write(&path, content).map_err(|e| format!("Failed to write references.json: {e}"))
+    let temp_path = path.with_extension("tmp");
+    std::fs::write(&temp_path, content)
+        .map_err(|e| format!("Failed to write references.json: {e}"))?;
+    std::fs::rename(&temp_path, path)
+        .map_err(|e| format!("Failed to finalize references.json: {e}"))
+}
+
+/// Atomically load, modify, and save the references file at `path`
+fn with_references_at<F, T>(path: &Path, f: F) -> Result<T, String>
+where
+    F: FnOnce(&mut ContextReferences) -> Result<T, String>,
+{
+    let _guard = REFERENCES_LOCK.lock().unwrap();
+
+    let (mut refs, _) = load_references_internal(path)?;
+    let result = f(&mut refs)?;
+    save_references_internal(path, &refs)?;
+
+    Ok(result)
+}
+
+/// Atomically load, modify, and save context references.
+/// This prevents race conditions by holding a lock for the entire operation.
+pub fn with_references_mut<F, T>(app: &tauri::AppHandle, f: F) -> Result<T, String>
+where
+    F: FnOnce(&mut ContextReferences) -> Result<T, String>,
+{
+    with_references_at(&get_references_path(app)?, f)
+}
+
+/// Load context references from disk (read-only snapshot)
+pub fn load_context_references(app: &tauri::AppHandle) -> Result<ContextReferences, String> {
+    let path = get_references_path(app)?;
+    let _guard = REFERENCES_LOCK.lock().unwrap();
+
+    let (refs, migrated) = load_references_internal(&path)?;
+    if migrated {
+        save_references_internal(&path, &refs)?;
+    }
+    Ok(refs)
+}
+
+/// Move references stored under a repo's host-less `legacy_repo_key` to `repo_key`
+///
+/// Only GitLab (`gitlab-` prefixed) or only GitHub keys are moved, per `gitlab`.
+/// Returns the (old, new) context filenames of the moved references.
+fn migrate_host_less_references(
+    refs: &mut ContextReferences,
+    legacy_repo_key: &str,
+    repo_key: &str,
+    gitlab: bool,
+) -> Vec<(String, String)> {
+    let mut renames = Vec::new();
+
+    for (kind, map) in [("issue", &mut refs.issues), ("pr", &mut refs.prs)] {
+        let moves: Vec<(String, String)> = map
+            .keys()
+            .filter_map(|key| {
+                let number = match key.strip_prefix("gitlab-") {
+                    Some(gitlab_key) if gitlab => {
+                        let (key_repo, iid) = parse_gitlab_context_key(gitlab_key)?;
+                        (key_repo == legacy_repo_key).then_some(iid)?
+                    }
+                    None if !gitlab => key
+                        .strip_prefix(legacy_repo_key)?
+                        .strip_prefix('-')?
+                        .parse::<u32>()
+                        .ok()?,
+                    _ => return None,
+                };
+                let new_key = if gitlab {
+                    gitlab_reference_key(repo_key, number)
+                } else {
+                    format!("{repo_key}-{number}")
+                };
+                Some((key.clone(), new_key))
+            })
+            .collect();
+
+        for (legacy_key, new_key) in moves {
+            let Some(legacy_ref) = map.remove(&legacy_key) else {
+                continue;
+            };
+            map.entry(new_key.clone())
+                .or_default()
+                .merge_from(legacy_ref);
+            if let (Some(old_file), Some(new_file)) = (
+                context_filename_for_key(&legacy_key, kind),
+                context_filename_for_key(&new_key, kind),
+            ) {
+                renames.push((old_file, new_file));
+            }
+        }
+    }
+
+    renames
+}
+
+/// Move a repo's contexts from its host-less key in `contexts_dir`
+///
+/// Files already present under the new key win; the old copy is dropped.
+fn migrate_host_less_contexts_at(
+    contexts_dir: &Path,
+    legacy_repo_key: &str,
+    repo_key: &str,
+    gitlab: bool,
+) -> Result<usize, String> {
+    let path = contexts_dir.join("references.json");
+    let _guard = REFERENCES_LOCK.lock().unwrap();
+
+    let (mut refs, legacy_migrated) = load_references_internal(&path)?;
+    let renames = migrate_host_less_references(&mut refs, legacy_repo_key, repo_key, gitlab);
+    if renames.is_empty() && !legacy_migrated {
+        return Ok(0);
+    }
+
+    for (old_file, new_file) in &renames {
+        let old_path = contexts_dir.join(old_file);
+        let new_path = contexts_dir.join(new_file);
+        let result = if new_path.exists() {
+            std::fs::remove_file(&old_path)
+        } else {
+            std::fs::rename(&old_path, &new_path)
+        };
+        if let Err(e) = result {
+            if e.kind() != std::io::ErrorKind::NotFound {
+                log::warn!("Failed to migrate context file {old_file}: {e}");
+            }
+        }
+    }
+
+    save_references_internal(&path, &refs)?;
+    Ok(renames.len())
+}
+
+/// Move a self-hosted repo's contexts to its "@{host}" key
+///
+/// Contexts loaded before keys carried the host are otherwise missed when
+/// loading, listing, or removing them. A no-op for github.com/gitlab.com
+/// repos and once everything is migrated; failures are only logged.
+pub fn migrate_host_less_contexts(app: &tauri::AppHandle, repo_id: &RepoIdentifier, gitlab: bool) {
+    let Some(legacy_repo_key) = repo_id.legacy_key() else {
+        return;
+    };
+    let repo_key = repo_id.to_key();
+
+    let result = get_github_contexts_dir(app).and_then(|contexts_dir| {
+        migrate_host_less_contexts_at(&contexts_dir, &legacy_repo_key, &repo_key, gitlab)
+    });
+    match result {
+        Ok(0) => {}
+        Ok(count) => log::debug!("Migrated {count} contexts from {legacy_repo_key} to {repo_key}"),
+        Err(e) => log::warn!("Failed to migrate contexts for {repo_key}: {e}"),
+    }
 }
 
 /// Add a worktree reference to an issue context
@@ -367,17 +728,22 @@ pub fn add_issue_reference(
     issue_number: u32,
     worktree_id: &str,
 ) -> Result<(), String> {
-    let mut refs = load_context_references(app)?;
-    let key = format!("{repo_key}-{issue_number}");
-
-    let entry = refs.issues.entry(key).or_default();
-    if !entry.worktrees.contains(&worktree_id.to_string()) {
-        entry.worktrees.push(worktree_id.to_string());
-    }
-    // Clear orphaned status when a reference is added
-    entry.orphaned_at = None;
+    add_issue_reference_key(app, &format!("{repo_key}-{issue_number}"), worktree_id)
+}
 
-    save_context_references(app, &refs)
+/// Add a worktree reference to an issue context using a pre-built key
+pub fn add_issue_reference_key(
+    app: &tauri::AppHandle,
+    key: &str,
+    worktree_id: &str,
+) -> Result<(), String> {
+    with_references_mut(app, |refs| {
+        refs.issues
+            .entry(key.to_string())
+            .or_default()
+            .add_worktree(worktree_id);
+        Ok(())
+    })
 }
 
 /// Add a worktree reference to a PR context
@@ -388,81 +754,125 @@ pub fn add_pr_reference(
     pr_number: u32,
     worktree_id: &str,
 ) -> Result<(), String> {
-    let mut refs = load_context_references(app)?;
-    let key = format!("{repo_key}-{pr_number}");
+    add_pr_reference_key(app, &format!("{repo_key}-{pr_number}"), worktree_id)
+}
 
-    let entry = refs.prs.entry(key).or_default();
-    if !entry.worktrees.contains(&worktree_id.to_string()) {
-        entry.worktrees.push(worktree_id.to_string());
-    }
-    // Clear orphaned status when a reference is added
-    entry.orphaned_at = None;
+/// Add a worktree reference to a PR context using a pre-built key
+pub fn add_pr_reference_key(
+    app: &tauri::AppHandle,
+    key: &str,
+    worktree_id: &str,
+) -> Result<(), String> {
+    with_references_mut(app, |refs| {
+        refs.prs
+            .entry(key.to_string())
+            .or_default()
+            .add_worktree(worktree_id);
+        Ok(())
+    })
+}
 
-    save_context_references(app, &refs)
+/// SHA-256 of a context file's markdown, hex-encoded
+pub fn context_hash(content: &str) -> String {
+    Sha256::digest(content.as_bytes())
+        .iter()
+        .map(|b| format!("{b:02x}"))
+        .collect()
 }
 
-/// Remove a worktree reference from an issue context
-/// Returns true if the context is now orphaned (no more references)
-pub fn remove_issue_reference(
+/// Write a shared context file and track the worktree's reference to it
+///
+/// The write is skipped when the file already holds this content, so
+/// reloading an unchanged issue doesn't touch the disk. The content hash (and
+/// `fingerprint`, when the loader has one) is stored with the reference for
+/// staleness checks. Returns whether the file was written.
+///
+/// The file is checked and written under the references lock, so it can't be
+/// deleted by `release_context_file` between the check and the new reference.
+pub fn store_context_file(
     app: &tauri::AppHandle,
-    repo_key: &str,
-    issue_number: u32,
+    kind: ContextKind,
+    key: &str,
     worktree_id: &str,
+    path: &Path,
+    content: &str,
+    fingerprint: Option<String>,
 ) -> Result<bool, String> {
-    let mut refs = load_context_references(app)?;
-    let key = format!("{repo_key}-{issue_number}");
-
-    let orphaned = if let Some(entry) = refs.issues.get_mut(&key) {
-        entry.worktrees.retain(|w| w != worktree_id);
-        if entry.worktrees.is_empty() && entry.orphaned_at.is_none() {
-            entry.orphaned_at = Some(
-                SystemTime::now()
-                    .duration_since(UNIX_EPOCH)
-                    .unwrap_or_default()
-                    .as_secs(),
-            );
-            true
+    with_references_mut(app, |refs| {
+        let hash = context_hash(content);
+        let unchanged =
+            std::fs::read_to_string(path).is_ok_and(|existing| context_hash(&existing) == hash);
+        if unchanged {
+            log::trace!("Context file {path:?} unchanged, skipping write");
         } else {
-            false
+            std::fs::write(path, content)
+                .map_err(|e| format!("Failed to write context file {}: {e}", path.display()))?;
         }
-    } else {
-        false
-    };
 
-    save_context_references(app, &refs)?;
-    Ok(orphaned)
+        let entry = refs.map_mut(kind).entry(key.to_string()).or_default();
+        entry.add_worktree(worktree_id);
+        entry.content_hash = Some(hash);
+        entry.fingerprint = fingerprint;
+        Ok(!unchanged)
+    })
+}
+
+/// Whether freshly fetched markdown differs from what was loaded
+///
+/// Compares against the hash stored by the loader, falling back to the file
+/// on disk for contexts written before hashes were tracked.
+pub fn is_context_stale(
+    entry: Option<&ContextRef>,
+    file_content: &str,
+    fresh_content: &str,
+) -> bool {
+    let loaded_hash = entry
+        .and_then(|e| e.content_hash.clone())
+        .unwrap_or_else(|| context_hash(file_content));
+    loaded_hash != context_hash(fresh_content)
 }
 
-/// Remove a worktree reference from a PR context
-/// Returns true if the context is now orphaned (no more references)
-pub fn remove_pr_reference(
+/// Whether a PR's current fingerprint differs from the one stored at load
+///
+/// None when no fingerprint was stored (contexts loaded before they were
+/// tracked), since the answer would need a full re-fetch.
+pub fn is_fingerprint_stale(entry: Option<&ContextRef>, fresh_fingerprint: &str) -> Option<bool> {
+    let loaded = entry?.fingerprint.as_deref()?;
+    Some(loaded != fresh_fingerprint)
+}
+
+/// Remove a worktree's reference to a shared context file, deleting the file
+/// once nothing references it
+///
+/// The delete happens in the same locked update that drops the last reference,
+/// so a concurrent load can't re-reference the file in between and be left
+/// pointing at a deleted file. Returns true if the file was deleted.
+pub fn release_context_file(
     app: &tauri::AppHandle,
-    repo_key: &str,
-    pr_number: u32,
+    kind: ContextKind,
+    key: &str,
     worktree_id: &str,
+    path: &Path,
 ) -> Result<bool, String> {
-    let mut refs = load_context_references(app)?;
-    let key = format!("{repo_key}-{pr_number}");
+    release_context_file_at(&get_references_path(app)?, kind, key, worktree_id, path)
+}
 
-    let orphaned = if let Some(entry) = refs.prs.get_mut(&key) {
-        entry.worktrees.retain(|w| w != worktree_id);
-        if entry.worktrees.is_empty() && entry.orphaned_at.is_none() {
-            entry.orphaned_at = Some(
-                SystemTime::now()
-                    .duration_since(UNIX_EPOCH)
-                    .unwrap_or_default()
-                    .as_secs(),
-            );
-            true
-        } else {
-            false
+fn release_context_file_at(
+    refs_path: &Path,
+    kind: ContextKind,
+    key: &str,
+    worktree_id: &str,
+    path: &Path,
+) -> Result<bool, String> {
+    with_references_at(refs_path, |refs| {
+        if !refs.remove_reference(kind, key, worktree_id) || !path.exists() {
+            return Ok(false);
         }
-    } else {
-        false
-    };
-
-    save_context_references(app, &refs)?;
-    Ok(orphaned)
+        std::fs::remove_file(path)
+            .map_err(|e| format!("Failed to remove context file {}: {e}", path.display()))?;
+        log::trace!("Deleted orphaned context file {path:?}");
+        Ok(true)
+    })
 }
 
 /// Get all issue keys referenced by a worktree
@@ -501,33 +911,33 @@ pub fn remove_all_worktree_references(
     app: &tauri::AppHandle,
     worktree_id: &str,
 ) -> Result<(Vec<String>, Vec<String>), String> {
-    let mut refs = load_context_references(app)?;
-    let now = SystemTime::now()
-        .duration_since(UNIX_EPOCH)
-        .unwrap_or_default()
-        .as_secs();
-
-    let mut orphaned_issues = Vec::new();
-    let mut orphaned_prs = Vec::new();
-
-    for (key, entry) in refs.issues.iter_mut() {
-        entry.worktrees.retain(|w| w != worktree_id);
-        if entry.worktrees.is_empty() && entry.orphaned_at.is_none() {
-            entry.orphaned_at = Some(now);
-            orphaned_issues.push(key.clone());
+    with_references_mut(app, |refs| {
+        let now = SystemTime::now()
+            .duration_since(UNIX_EPOCH)
+            .unwrap_or_default()
+            .as_secs();
+
+        let mut orphaned_issues = Vec::new();
+        let mut orphaned_prs = Vec::new();
+
+        for (key, entry) in refs.issues.iter_mut() {
+            entry.worktrees.retain(|w| w != worktree_id);
+            if entry.worktrees.is_empty() && entry.orphaned_at.is_none() {
+                entry.orphaned_at = Some(now);
+                orphaned_issues.push(key.clone());
+            }
         }
-    }
 
-    for (key, entry) in refs.prs.iter_mut() {
-        entry.worktrees.retain(|w| w != worktree_id);
-        if entry.worktrees.is_empty() && entry.orphaned_at.is_none() {
-            entry.orphaned_at = Some(now);
-            orphaned_prs.push(key.clone());
+        for (key, entry) in refs.prs.iter_mut() {
+            entry.worktrees.retain(|w| w != worktree_id);
+            if entry.worktrees.is_empty() && entry.orphaned_at.is_none() {
+                entry.orphaned_at = Some(now);
+                orphaned_prs.push(key.clone());
+            }
         }
-    }
 
-    save_context_references(app, &refs)?;
-    Ok((orphaned_issues, orphaned_prs))
+        Ok((orphaned_issues, orphaned_prs))
+    })
 }
 
 /// Parse a context key into (repo_owner, repo_name, number)
@@ -543,91 +953,96 @@ fn parse_context_key(key: &str) -> Option<(String, String, u32)> {
     Some((owner.to_string(), repo.to_string(), number))
 }
 
+/// Map a reference key to its context filename
+///
+/// GitHub: "{repo_key}-{number}" -> "{repo_key}-{kind}-{number}.md"
+/// GitLab: "gitlab-{repo_key}#{iid}" -> "{repo_key}-gitlab-{kind}-{iid}.md"
+/// where kind is "issue" or "pr" (GitLab MRs use "mr").
+fn context_filename_for_key(key: &str, kind: &str) -> Option<String> {
+    if let Some(gitlab_key) = key.strip_prefix("gitlab-") {
+        let (repo_key, iid) = parse_gitlab_context_key(gitlab_key)?;
+        let gitlab_kind = if kind == "pr" { "mr" } else { kind };
+        return Some(format!("{repo_key}-gitlab-{gitlab_kind}-{iid}.md"));
+    }
+
+    let (repo_key, number) = key.rsplit_once('-')?;
+    Some(format!("{repo_key}-{kind}-{number}.md"))
+}
+
 /// Clean up orphaned context files older than retention_days
 /// Returns the number of files deleted
 pub fn cleanup_orphaned_contexts(
     app: &tauri::AppHandle,
     retention_days: u64,
 ) -> Result<u32, String> {
-    let mut refs = load_context_references(app)?;
-    let now = SystemTime::now()
-        .duration_since(UNIX_EPOCH)
-        .unwrap_or_default()
-        .as_secs();
-    let retention_secs = retention_days * 24 * 60 * 60;
-
-    let contexts_dir = get_github_contexts_dir(app)?;
-    let mut deleted_count = 0u32;
-
-    // Clean up orphaned issues
-    let issues_to_remove: Vec<String> = refs
-        .issues
-        .iter()
-        .filter_map(|(key, entry)| {
-            if let Some(orphaned_at) = entry.orphaned_at {
-                if orphaned_at + retention_secs < now {
-                    return Some(key.clone());
+    with_references_mut(app, |refs| {
+        let now = SystemTime::now()
+            .duration_since(UNIX_EPOCH)
+            .unwrap_or_default()
+            .as_secs();
+        let retention_secs = retention_days * 24 * 60 * 60;
+
+        let contexts_dir = get_github_contexts_dir(app)?;
+        let mut deleted_count = 0u32;
+
+        // Clean up orphaned issues
+        let issues_to_remove: Vec<String> = refs
+            .issues
+            .iter()
+            .filter_map(|(key, entry)| {
+                if let Some(orphaned_at) = entry.orphaned_at {
+                    if orphaned_at + retention_secs < now {
+                        return Some(key.clone());
+                    }
                 }
-            }
-            None
-        })
-        .collect();
-
-    for key in &issues_to_remove {
-        // File format: {repo_key}-issue-{number}.md
-        // Key format: {repo_key}-{number}
-        // We need to transform key to filename
-        if let Some(last_dash) = key.rfind('-') {
-            let repo_key = &key[..last_dash];
-            let number = &key[last_dash + 1..];
-            let filename = format!("{repo_key}-issue-{number}.md");
-            let file_path = contexts_dir.join(&filename);
-            if file_path.exists() {
-                if let Err(e) = std::fs::remove_file(&file_path) {
-                    log::warn!("Failed to remove orphaned issue context {filename}: {e}");
-                } else {
-                    deleted_count += 1;
+                None
+            })
+            .collect();
+
+        for key in &issues_to_remove {
+            if let Some(filename) = context_filename_for_key(key, "issue") {
+                let file_path = contexts_dir.join(&filename);
+                if file_path.exists() {
+                    if let Err(e) = std::fs::remove_file(&file_path) {
+                        log::warn!("Failed to remove orphaned issue context {filename}: {e}");
+                    } else {
+                        deleted_count += 1;
+                    }
                 }
             }
+            refs.issues.remove(key);
         }
-        refs.issues.remove(key);
-    }
 
-    // Clean up orphaned PRs
-    let prs_to_remove: Vec<String> = refs
-        .prs
-        .iter()
-        .filter_map(|(key, entry)| {
-            if let Some(orphaned_at) = entry.orphaned_at {
-                if orphaned_at + retention_secs < now {
-                    return Some(key.clone());
+        // Clean up orphaned PRs
+        let prs_to_remove: Vec<String> = refs
+            .prs
+            .iter()
+            .filter_map(|(key, entry)| {
+                if let Some(orphaned_at) = entry.orphaned_at {
+                    if orphaned_at + retention_secs < now {
+                        return Some(key.clone());
+                    }
                 }
-            }
-            None
-        })
-        .collect();
-
-    for key in &prs_to_remove {
-        // File format: {repo_key}-pr-{number}.md
-        // Key format: {repo_key}-{number}
-        if let Some(last_dash) = key.rfind('-') {
-            let repo_key = &key[..last_dash];
-            let number = &key[last_dash + 1..];
-            let filename = format!("{repo_key}-pr-{number}.md");
-            let file_path = contexts_dir.join(&filename);
-            if file_path.exists() {
-                if let Err(e) = std::fs::remove_file(&file_path) {
-                    log::warn!("Failed to remove orphaned PR context {filename}: {e}");
-                } else {
-                    deleted_count += 1;
+                None
+            })
+            .collect();
+
+        for key in &prs_to_remove {
+            if let Some(filename) = context_filename_for_key(key, "pr") {
+                let file_path = contexts_dir.join(&filename);
+                if file_path.exists() {
+                    if let Err(e) = std::fs::remove_file(&file_path) {
+                        log::warn!("Failed to remove orphaned PR context {filename}: {e}");
+                    } else {
+                        deleted_count += 1;
+                    }
                 }
             }
+            refs.prs.remove(key);
         }
-        refs.prs.remove(key);
-    }
 
-    save_context_references(app, &refs)?;
-    Ok(deleted_count)
+        Ok(deleted_count)
+    })
 }
 
 /// Load/refresh issue context for a worktree by fetching data from GitHub
@@ -645,18 +1060,11 @@ pub async fn load_issue_context(
 
     // Get repo identifier for shared storage
     let repo_id = get_repo_identifier(&project_path)?;
+    migrate_host_less_contexts(&app, &repo_id, false);
     let repo_key = repo_id.to_key();
 
     // Fetch issue data from GitHub
-    let issue = get_github_issue(project_path, issue_number).await?;
-
-    // Create issue context
-    let ctx = IssueContext {
-        number: issue.number,
-        title: issue.title.clone(),
-        body: issue.body,
-        comments: issue.comments,
-    };
+    let ctx = fetch_issue_context(project_path, issue_number).await?;
 
     // Write to shared git-context directory
     let contexts_dir = get_github_contexts_dir(&app)?;
@@ -667,11 +1075,16 @@ pub async fn load_issue_context(
     let context_file = contexts_dir.join(format!("{repo_key}-issue-{issue_number}.md"));
     let context_content = format_issue_context_markdown(&ctx);
 
-    std::fs::write(&context_file, context_content)
-        .map_err(|e| format!("Failed to write issue context file: {e}"))?;
-
-    // Add reference tracking
-    add_issue_reference(&app, &repo_key, issue_number, &worktree_id)?;
+    // Write (if changed) and add reference tracking
+    store_context_file(
+        &app,
+        ContextKind::Issue,
+        &format!("{repo_key}-{issue_number}"),
+        &worktree_id,
+        &context_file,
+        &context_content,
+        None,
+    )?;
 
     log::trace!(
         "Issue context loaded successfully for issue #{} ({} comments)",
@@ -680,22 +1093,140 @@ pub async fn load_issue_context(
     );
 
     Ok(LoadedIssueContext {
-        number: issue.number,
-        title: issue.title,
+        number: ctx.number,
         comment_count: ctx.comments.len(),
+        title: ctx.title,
         repo_owner: repo_id.owner,
         repo_name: repo_id.repo,
+        stale: Some(false),
     })
 }
 
+/// Fetch an issue with its comments as context
+async fn fetch_issue_context(
+    project_path: String,
+    issue_number: u32,
+) -> Result<IssueContext, String> {
+    let issue = get_github_issue(project_path, issue_number).await?;
+    Ok(IssueContext {
+        number: issue.number,
+        title: issue.title,
+        body: issue.body,
+        comments: issue.comments,
+    })
+}
+
+/// Most contexts re-fetched at once when checking loaded contexts for changes
+const STALE_CHECK_CONCURRENCY: usize = 4;
+
+/// A context file loaded into a worktree, read from disk
+struct StoredContext {
+    key: String,
+    owner: String,
+    repo: String,
+    number: u32,
+    content: String,
+}
+
+/// Read the stored context files behind `keys` ("{owner}-{repo}-{number}")
+///
+/// `kind` is the file name infix ("issue" or "pr"). Missing files are skipped.
+fn read_stored_contexts(contexts_dir: &Path, keys: Vec<String>, kind: &str) -> Vec<StoredContext> {
+    keys.into_iter()
+        .filter_map(|key| {
+            let (owner, repo, number) = parse_context_key(&key)?;
+            let file = contexts_dir.join(format!("{owner}-{repo}-{kind}-{number}.md"));
+            let content = std::fs::read_to_string(file).ok()?;
+            Some(StoredContext {
+                key,
+                owner,
+                repo,
+                number,
+                content,
+            })
+        })
+        .collect()
+}
+
+/// Fetch the current state of the contexts from `repo_key` concurrently
+///
+/// Returns one entry per stored context, None for those from other
+/// repositories (or all of them without a `project_path`) and for failures,
+/// which are logged.
+async fn fetch_current_state<T: Send + 'static>(
+    stored: &[StoredContext],
+    project_path: Option<String>,
+    repo_key: Option<String>,
+    label: &'static str,
+    fetch: fn(&str, u32) -> Result<T, String>,
+) -> Vec<Option<T>> {
+    let (Some(project_path), Some(repo_key)) = (project_path, repo_key) else {
+        return std::iter::repeat_with(|| None).take(stored.len()).collect();
+    };
+    let numbers = stored
+        .iter()
+        .map(|c| (format!("{}-{}", c.owner, c.repo) == repo_key).then_some(c.number))
+        .collect();
+    fetch_states_concurrently(project_path, numbers, label, fetch).await
+}
+
+/// Run `fetch` for each number concurrently, off the async runtime
+///
+/// Entries without a number, and failures (which are logged), come back None.
+pub async fn fetch_states_concurrently<T: Send + 'static>(
+    project_path: String,
+    numbers: Vec<Option<u32>>,
+    label: &'static str,
+    fetch: fn(&str, u32) -> Result<T, String>,
+) -> Vec<Option<T>> {
+    let count = numbers.len();
+    tauri::async_runtime::spawn_blocking(move || {
+        crate::util::parallel_map(&numbers, STALE_CHECK_CONCURRENCY, |number| {
+            let number = (*number)?;
+            match fetch(&project_path, number) {
+                Ok(state) => Some(state),
+                Err(e) => {
+                    log::warn!("Failed to check {label} #{number} for changes: {e}");
+                    None
+                }
+            }
+        })
+    })
+    .await
+    .unwrap_or_else(|e| {
+        log::warn!("Failed to check {label} contexts for changes: {e}");
+        std::iter::repeat_with(|| None).take(count).collect()
+    })
+}
+
+/// Re-fetch an issue rendered as context markdown, for staleness checks
+fn fetch_issue_markdown(project_path: &str, issue_number: u32) -> Result<String, String> {
+    let ctx = tauri::async_runtime::block_on(fetch_issue_context(
+        project_path.to_string(),
+        issue_number,
+    ))?;
+    Ok(format_issue_context_markdown(&ctx))
+}
+
 /// List all loaded issue contexts for a worktree
+///
+/// When `project_path` is given, that repository's issues are re-fetched
+/// concurrently and compared to the loaded content to fill in `stale`.
 #[tauri::command]
 pub async fn list_loaded_issue_contexts(
     app: tauri::AppHandle,
     worktree_id: String,
+    project_path: Option<String>,
 ) -> Result<Vec<LoadedIssueContext>, String> {
     log::trace!("Listing loaded issue contexts for worktree {worktree_id}");
 
+    let repo_id = project_path
+        .as_deref()
+        .and_then(|path| get_repo_identifier(path).ok());
+    if let Some(repo_id) = &repo_id {
+        migrate_host_less_contexts(&app, repo_id, false);
+    }
+
     // Get issue refs for this worktree from reference tracking
     let issue_keys = get_worktree_issue_refs(&app, &worktree_id)?;
 
@@ -704,38 +1235,53 @@ pub async fn list_loaded_issue_contexts(
     }
 
     let contexts_dir = get_github_contexts_dir(&app)?;
-    let mut contexts = Vec::new();
+    let refs = load_context_references(&app)?;
+    let check_repo_key = repo_id.map(|repo_id| repo_id.to_key());
+
+    let stored = read_stored_contexts(&contexts_dir, issue_keys, "issue");
+    let fresh = fetch_current_state(
+        &stored,
+        project_path,
+        check_repo_key,
+        "issue",
+        fetch_issue_markdown,
+    )
+    .await;
 
-    for key in issue_keys {
-        // Parse key format: "{owner}-{repo}-{number}"
-        if let Some((owner, repo, number)) = parse_context_key(&key) {
-            let repo_key = format!("{owner}-{repo}");
-            let context_file = contexts_dir.join(format!("{repo_key}-issue-{number}.md"));
-
-            if let Ok(content) = std::fs::read_to_string(&context_file) {
-                // Parse title from first line: "# GitHub Issue #123: Title"
-                let title = content
-                    .lines()
-                    .next()
-                    .and_then(|line| {
-                        line.strip_prefix("# GitHub Issue #")
-                            .and_then(|rest| rest.split_once(": "))
-                            .map(|(_, title)| title.to_string())
-                    })
-                    .unwrap_or_else(|| format!("Issue #{number}"));
-
-                // Count comments by counting "### @" headers
-                let comment_count = content.matches("### @").count();
-
-                contexts.push(LoadedIssueContext {
-                    number,
-                    title,
-                    comment_count,
-                    repo_owner: owner,
-                    repo_name: repo,
-                });
-            }
-        }
+    let mut contexts = Vec::new();
+    for (context, fresh) in stored.into_iter().zip(fresh) {
+        let StoredContext {
+            key,
+            owner,
+            repo,
+            number,
+            content,
+        } = context;
+
+        // Parse title from first line: "# GitHub Issue #123: Title"
+        let title = content
+            .lines()
+            .next()
+            .and_then(|line| {
+                line.strip_prefix("# GitHub Issue #")
+                    .and_then(|rest| rest.split_once(": "))
+                    .map(|(_, title)| title.to_string())
+            })
+            .unwrap_or_else(|| format!("Issue #{number}"));
+
+        // Count comments by counting "### @" headers
+        let comment_count = content.matches("### @").count();
+
+        let stale = fresh.map(|fresh| is_context_stale(refs.issues.get(&key), &content, &fresh));
+
+        contexts.push(LoadedIssueContext {
+            number,
+            title,
+            comment_count,
+            repo_owner: owner,
+            repo_name: repo,
+            stale,
+        });
     }
 
     // Sort by issue number
@@ -779,22 +1325,19 @@ pub async fn remove_issue_context(
 
     // Get repo identifier
     let repo_id = get_repo_identifier(&project_path)?;
+    migrate_host_less_contexts(&app, &repo_id, false);
     let repo_key = repo_id.to_key();
 
-    // Remove reference
-    let is_orphaned = remove_issue_reference(&app, &repo_key, issue_number, &worktree_id)?;
-
-    // If orphaned, delete the shared file immediately
-    if is_orphaned {
-        let contexts_dir = get_github_contexts_dir(&app)?;
-        let context_file = contexts_dir.join(format!("{repo_key}-issue-{issue_number}.md"));
-
-        if context_file.exists() {
-            std::fs::remove_file(&context_file)
-                .map_err(|e| format!("Failed to remove issue context file: {e}"))?;
-            log::trace!("Deleted orphaned issue context file");
-        }
-    }
+    // Remove the reference, and the shared file if nothing else uses it
+    let context_file =
+        get_github_contexts_dir(&app)?.join(format!("{repo_key}-issue-{issue_number}.md"));
+    release_context_file(
+        &app,
+        ContextKind::Issue,
+        &format!("{repo_key}-{issue_number}"),
+        &worktree_id,
+        &context_file,
+    )?;
 
     log::trace!("Issue context removed successfully");
     Ok(())
@@ -876,6 +1419,9 @@ pub struct LoadedPullRequestContext {
     pub review_count: usize,
     pub repo_owner: String,
     pub repo_name: String,
+    /// Whether the PR changed since the context was loaded (only set when checked)
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub stale: Option<bool>,
 }
 
 /// List GitHub pull requests for a repository
@@ -1022,6 +1568,87 @@ pub async fn get_github_pr(
     Ok(pr)
 }
 
+/// GitHub rejects comments longer than this many characters
+const GITHUB_COMMENT_MAX_CHARS: usize = 65_536;
+
+/// Check a PR/MR comment body before posting it
+pub fn validate_comment_body(body: &str, max_chars: usize) -> Result<(), String> {
+    if body.trim().is_empty() {
+        return Err("Comment body is empty".to_string());
+    }
+    let chars = body.chars().count();
+    if chars > max_chars {
+        return Err(format!(
+            "Comment is too long ({chars} characters, the limit is {max_chars})"
+        ));
+    }
+    Ok(())
+}
+
+/// Run a command with `input` on stdin, so long bodies don't hit argument length limits
+pub fn output_with_stdin(mut command: Command, input: &str) -> std::io::Result<Output> {
+    let mut child = command
+        .stdin(Stdio::piped())
+        .stdout(Stdio::piped())
+        .stderr(Stdio::piped())
+        .spawn()?;
+
+    // Write on another thread so a CLI that prints before reading can't deadlock us.
+    // If it exits without reading (e.g. not authenticated), its own error is what matters.
+    let mut stdin = child.stdin.take().expect("stdin is piped");
+    let input = input.to_string();
+    let writer = std::thread::spawn(move || stdin.write_all(input.as_bytes()));
+    let output = child.wait_with_output()?;
+    let _ = writer.join();
+    Ok(output)
+}
+
+/// The comment URL a CLI printed after posting, if any
+fn comment_url(stdout: &str) -> Option<String> {
+    stdout
+        .lines()
+        .map(str::trim)
+        .rfind(|line| line.starts_with("https://") || line.starts_with("http://"))
+        .map(str::to_string)
+}
+
+/// Post a comment on a GitHub PR using `gh pr comment`
+///
+/// Returns the URL of the new comment, which `gh` prints on success.
+pub fn post_github_pr_comment(
+    project_path: &str,
+    pr_number: u32,
+    body: &str,
+) -> Result<Option<String>, String> {
+    validate_comment_body(body, GITHUB_COMMENT_MAX_CHARS)?;
+    log::trace!("Posting comment on PR #{pr_number} in {project_path}");
+
+    let mut command = Command::new("gh");
+    command
+        .args(["pr", "comment", &pr_number.to_string(), "--body-file", "-"])
+        .current_dir(project_path);
+    let output = output_with_stdin(command, body)
+        .map_err(|e| format!("Failed to run gh pr comment: {e}"))?;
+
+    if !output.status.success() {
+        let stderr = String::from_utf8_lossy(&output.stderr);
+        if stderr.contains("gh auth login") || stderr.contains("authentication") {
+            return Err("GitHub CLI not authenticated. Run 'gh auth login' first.".to_string());
+        }
+        if stderr.contains("Could not resolve")
+            || stderr.contains("not found")
+            || stderr.contains("no pull requests found")
+        {
+            return Err(format!("PR #{pr_number} not found"));
+        }
+        return Err(format!("gh pr comment failed: {stderr}"));
+    }
+
+    let url = comment_url(&String::from_utf8_lossy(&output.stdout));
+    log::debug!("Posted comment on PR #{pr_number}: {url:?}");
+    Ok(url)
+}
+
 /// Generate a branch name from a PR
 /// e.g., PR #123 "Fix the login bug" -> "pr-123-fix-the-login-bug"
 pub fn generate_branch_name_from_pr(pr_number: u32, title: &str) -> String {
@@ -1085,18 +1712,7 @@ pub fn format_pr_context_markdown(ctx: &PullRequestContext) -> String {
         }
     }
 
-    // Add diff section if available
-    if let Some(diff) = &ctx.diff {
-        if !diff.is_empty() {
-            content.push_str("## Changes (Diff)\n\n");
-            content.push_str("```diff\n");
-            content.push_str(diff);
-            if !diff.ends_with('\n') {
-                content.push('\n');
-            }
-            content.push_str("```\n\n");
-        }
-    }
+    push_diff_section(&mut content, ctx.diff.as_deref());
 
     content.push_str("---\n\n");
     content.push_str("*Review this pull request and provide feedback or make changes.*\n");
@@ -1104,6 +1720,45 @@ pub fn format_pr_context_markdown(ctx: &PullRequestContext) -> String {
     content
 }
 
+/// Diffs embedded in PR/MR context files are truncated past this many bytes
+const MAX_DIFF_SIZE: usize = 100_000;
+
+/// Clean up `gh`/`glab` diff output for a context file
+///
+/// Escape codes are stripped (`--color never` isn't honored by every pager
+/// setup), and diffs over 100KB are cut at a char boundary with a note naming
+/// `full_diff_command`, which shows the whole diff.
+pub fn prepare_diff(diff: &str, full_diff_command: &str) -> String {
+    let diff = crate::util::strip_ansi(diff);
+    if diff.len() <= MAX_DIFF_SIZE {
+        return diff;
+    }
+
+    let mut end = MAX_DIFF_SIZE;
+    while !diff.is_char_boundary(end) {
+        end -= 1;
+    }
+    format!(
+        "{}...\n\n[Diff truncated at 100KB - {} bytes total. Run `{full_diff_command}` to see the full diff.]",
+        &diff[..end],
+        diff.len()
+    )
+}
+
+/// Append a PR/MR diff as a fenced `diff` block, if there is one
+pub fn push_diff_section(content: &mut String, diff: Option<&str>) {
+    let Some(diff) = diff.filter(|d| !d.is_empty()) else {
+        return;
+    };
+    content.push_str("## Changes (Diff)\n\n");
+    content.push_str("```diff\n");
+    content.push_str(diff);
+    if !diff.ends_with('\n') {
+        content.push('\n');
+    }
+    content.push_str("```\n\n");
+}
+
 /// Get the diff for a PR using `gh pr diff`
 ///
 /// Returns the diff as a string, truncated to 100KB if too large.
@@ -1123,21 +1778,10 @@ pub fn get_pr_diff(project_path: &str, pr_number: u32) -> Result<String, String>
         return Ok(String::new());
     }
 
-    let diff = String::from_utf8_lossy(&output.stdout).to_string();
+    let diff = String::from_utf8_lossy(&output.stdout);
     log::debug!("Got diff for PR #{pr_number}: {} bytes", diff.len());
 
-    // Truncate if > 100KB
-    const MAX_DIFF_SIZE: usize = 100_000;
-    if diff.len() > MAX_DIFF_SIZE {
-        Ok(format!(
-            "{}...\n\n[Diff truncated at 100KB - {} bytes total. Run `gh pr diff {}` to see the full diff.]",
-            &diff[..MAX_DIFF_SIZE],
-            diff.len(),
-            pr_number
-        ))
-    } else {
-        Ok(diff)
-    }
+    Ok(prepare_diff(&diff, &format!("gh pr diff {pr_number}")))
 }
 
 /// Load/refresh PR context for a worktree by fetching data from GitHub
@@ -1155,25 +1799,17 @@ pub async fn load_pr_context(
 
     // Get repo identifier for shared storage
     let repo_id = get_repo_identifier(&project_path)?;
+    migrate_host_less_contexts(&app, &repo_id, false);
     let repo_key = repo_id.to_key();
 
-    // Fetch PR data from GitHub
-    let pr = get_github_pr(project_path.clone(), pr_number).await?;
+    // Fingerprint first: if the PR changes while it's being fetched, the
+    // context is reported stale rather than the change being missed
+    let fingerprint = get_pr_fingerprint(&project_path, pr_number)
+        .map_err(|e| log::warn!("Failed to fingerprint PR #{pr_number}: {e}"))
+        .ok();
 
-    // Fetch the diff
-    let diff = get_pr_diff(&project_path, pr_number).ok();
-
-    // Create PR context
-    let ctx = PullRequestContext {
-        number: pr.number,
-        title: pr.title.clone(),
-        body: pr.body,
-        head_ref_name: pr.head_ref_name,
-        base_ref_name: pr.base_ref_name,
-        comments: pr.comments,
-        reviews: pr.reviews.clone(),
-        diff,
-    };
+    // Fetch PR data and diff from GitHub
+    let ctx = fetch_pr_context(project_path, pr_number).await?;
 
     // Write to shared git-context directory
     let contexts_dir = get_github_contexts_dir(&app)?;
@@ -1184,11 +1820,16 @@ pub async fn load_pr_context(
     let context_file = contexts_dir.join(format!("{repo_key}-pr-{pr_number}.md"));
     let context_content = format_pr_context_markdown(&ctx);
 
-    std::fs::write(&context_file, context_content)
-        .map_err(|e| format!("Failed to write PR context file: {e}"))?;
-
-    // Add reference tracking
-    add_pr_reference(&app, &repo_key, pr_number, &worktree_id)?;
+    // Write (if changed) and add reference tracking
+    store_context_file(
+        &app,
+        ContextKind::Pr,
+        &format!("{repo_key}-{pr_number}"),
+        &worktree_id,
+        &context_file,
+        &context_content,
+        fingerprint,
+    )?;
 
     log::debug!(
         "PR context loaded successfully for PR #{} ({} comments, {} reviews, diff: {} bytes)",
@@ -1199,23 +1840,81 @@ pub async fn load_pr_context(
     );
 
     Ok(LoadedPullRequestContext {
-        number: pr.number,
-        title: pr.title,
+        number: ctx.number,
         comment_count: ctx.comments.len(),
-        review_count: pr.reviews.len(),
+        review_count: ctx.reviews.len(),
+        title: ctx.title,
         repo_owner: repo_id.owner,
         repo_name: repo_id.repo,
+        stale: Some(false),
+    })
+}
+
+/// Fetch a PR with its comments, reviews and diff as context
+async fn fetch_pr_context(
+    project_path: String,
+    pr_number: u32,
+) -> Result<PullRequestContext, String> {
+    let pr = get_github_pr(project_path.clone(), pr_number).await?;
+    let diff = get_pr_diff(&project_path, pr_number).ok();
+    Ok(PullRequestContext {
+        number: pr.number,
+        title: pr.title,
+        body: pr.body,
+        head_ref_name: pr.head_ref_name,
+        base_ref_name: pr.base_ref_name,
+        comments: pr.comments,
+        reviews: pr.reviews,
+        diff,
     })
 }
 
+/// Summarize a PR's current state in one lightweight `gh pr view` call
+///
+/// Covers the last update, the head commit and the comment and review counts,
+/// so it changes whenever the PR's context file would.
+pub fn get_pr_fingerprint(project_path: &str, pr_number: u32) -> Result<String, String> {
+    let output = Command::new("gh")
+        .args([
+            "pr",
+            "view",
+            &pr_number.to_string(),
+            "--json",
+            "updatedAt,headRefOid,comments,reviews",
+            "--jq",
+            r#""\(.updatedAt)|\(.headRefOid)|\(.comments | length)|\(.reviews | length)""#,
+        ])
+        .current_dir(project_path)
+        .output()
+        .map_err(|e| format!("Failed to run gh pr view: {e}"))?;
+
+    if !output.status.success() {
+        let stderr = String::from_utf8_lossy(&output.stderr);
+        return Err(format!("gh pr view failed: {stderr}"));
+    }
+    Ok(String::from_utf8_lossy(&output.stdout).trim().to_string())
+}
+
 /// List all loaded PR contexts for a worktree
+///
+/// When `project_path` is given, that repository's PRs are fingerprinted
+/// concurrently and compared to the fingerprints stored at load to fill in
+/// `stale`. PRs loaded before fingerprints were stored report None.
 #[tauri::command]
 pub async fn list_loaded_pr_contexts(
     app: tauri::AppHandle,
     worktree_id: String,
+    project_path: Option<String>,
 ) -> Result<Vec<LoadedPullRequestContext>, String> {
     log::trace!("Listing loaded PR contexts for worktree {worktree_id}");
 
+    let repo_id = project_path
+        .as_deref()
+        .and_then(|path| get_repo_identifier(path).ok());
+    if let Some(repo_id) = &repo_id {
+        migrate_host_less_contexts(&app, repo_id, false);
+    }
+
     // Get PR refs for this worktree from reference tracking
     let pr_keys = get_worktree_pr_refs(&app, &worktree_id)?;
 
@@ -1224,54 +1923,69 @@ pub async fn list_loaded_pr_contexts(
     }
 
     let contexts_dir = get_github_contexts_dir(&app)?;
-    let mut contexts = Vec::new();
+    let refs = load_context_references(&app)?;
+    let check_repo_key = repo_id.map(|repo_id| repo_id.to_key());
+
+    let stored = read_stored_contexts(&contexts_dir, pr_keys, "pr");
+    let fingerprints = fetch_current_state(
+        &stored,
+        project_path,
+        check_repo_key,
+        "PR",
+        get_pr_fingerprint,
+    )
+    .await;
 
-    for key in pr_keys {
-        // Parse key format: "{owner}-{repo}-{number}"
-        if let Some((owner, repo, number)) = parse_context_key(&key) {
-            let repo_key = format!("{owner}-{repo}");
-            let context_file = contexts_dir.join(format!("{repo_key}-pr-{number}.md"));
-
-            if let Ok(content) = std::fs::read_to_string(&context_file) {
-                // Parse title from first line: "# GitHub Pull Request #123: Title"
-                let title = content
-                    .lines()
-                    .next()
-                    .and_then(|line| {
-                        line.strip_prefix("# GitHub Pull Request #")
-                            .and_then(|rest| rest.split_once(": "))
-                            .map(|(_, title)| title.to_string())
-                    })
-                    .unwrap_or_else(|| format!("PR #{number}"));
-
-                // Count comments by counting "### @" headers in Comments section
-                let comment_count = content
+    let mut contexts = Vec::new();
+    for (context, fingerprint) in stored.into_iter().zip(fingerprints) {
+        let StoredContext {
+            key,
+            owner,
+            repo,
+            number,
+            content,
+        } = context;
+
+        // Parse title from first line: "# GitHub Pull Request #123: Title"
+        let title = content
+            .lines()
+            .next()
+            .and_then(|line| {
+                line.strip_prefix("# GitHub Pull Request #")
+                    .and_then(|rest| rest.split_once(": "))
+                    .map(|(_, title)| title.to_string())
+            })
+            .unwrap_or_else(|| format!("PR #{number}"));
+
+        // Count comments by counting "### @" headers in Comments section
+        let comment_count = content
+            .find("## Comments")
+            .map(|start| content[start..].matches("### @").count())
+            .unwrap_or(0);
+
+        // Count reviews by counting "### @" headers in Reviews section
+        let review_count = content
+            .find("## Reviews")
+            .map(|start| {
+                let reviews_section = &content[start..];
+                let end = reviews_section
                     .find("## Comments")
-                    .map(|start| content[start..].matches("### @").count())
-                    .unwrap_or(0);
-
-                // Count reviews by counting "### @" headers in Reviews section
-                let review_count = content
-                    .find("## Reviews")
-                    .map(|start| {
-                        let reviews_section = &content[start..];
-                        let end = reviews_section
-                            .find("## Comments")
-                            .unwrap_or(reviews_section.len());
-                        reviews_section[..end].matches("### @").count()
-                    })
-                    .unwrap_or(0);
-
-                contexts.push(LoadedPullRequestContext {
-                    number,
-                    title,
-                    comment_count,
-                    review_count,
-                    repo_owner: owner,
-                    repo_name: repo,
-                });
-            }
-        }
+                    .unwrap_or(reviews_section.len());
+                reviews_section[..end].matches("### @").count()
+            })
+            .unwrap_or(0);
+
+        let stale = fingerprint.and_then(|fresh| is_fingerprint_stale(refs.prs.get(&key), &fresh));
+
+        contexts.push(LoadedPullRequestContext {
+            number,
+            title,
+            comment_count,
+            review_count,
+            repo_owner: owner,
+            repo_name: repo,
+            stale,
+        });
     }
 
     // Sort by PR number
@@ -1306,22 +2020,18 @@ pub async fn remove_pr_context(
 
     // Get repo identifier
     let repo_id = get_repo_identifier(&project_path)?;
+    migrate_host_less_contexts(&app, &repo_id, false);
     let repo_key = repo_id.to_key();
 
-    // Remove reference
-    let is_orphaned = remove_pr_reference(&app, &repo_key, pr_number, &worktree_id)?;
-
-    // If orphaned, delete the shared file immediately
-    if is_orphaned {
-        let contexts_dir = get_github_contexts_dir(&app)?;
-        let context_file = contexts_dir.join(format!("{repo_key}-pr-{pr_number}.md"));
-
-        if context_file.exists() {
-            std::fs::remove_file(&context_file)
-                .map_err(|e| format!("Failed to remove PR context file: {e}"))?;
-            log::trace!("Deleted orphaned PR context file");
-        }
-    }
+    // Remove the reference, and the shared file if nothing else uses it
+    let context_file = get_github_contexts_dir(&app)?.join(format!("{repo_key}-pr-{pr_number}.md"));
+    release_context_file(
+        &app,
+        ContextKind::Pr,
+        &format!("{repo_key}-{pr_number}"),
+        &worktree_id,
+        &context_file,
+    )?;
 
     log::trace!("PR context removed successfully");
     Ok(())
@@ -1337,6 +2047,7 @@ pub async fn get_issue_context_content(
 ) -> Result<String, String> {
     // Get repo identifier
     let repo_id = get_repo_identifier(&project_path)?;
+    migrate_host_less_contexts(&app, &repo_id, false);
     let repo_key = repo_id.to_key();
 
     // Verify this worktree has a reference to this context
@@ -1371,6 +2082,7 @@ pub async fn get_pr_context_content(
 ) -> Result<String, String> {
     // Get repo identifier
     let repo_id = get_repo_identifier(&project_path)?;
+    migrate_host_less_contexts(&app, &repo_id, false);
     let repo_key = repo_id.to_key();
 
     // Verify this worktree has a reference to this context
@@ -1391,10 +2103,284 @@ pub async fn get_pr_context_content(
         .map_err(|e| format!("Failed to read PR context file: {e}"))
 }
 
+// =============================================================================
+// Bulk Context Refresh
+// =============================================================================
+
+/// A context that failed to refresh during a bulk refresh
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct RefreshFailure {
+    /// Context kind: "issue", "pr", "gitlab-issue", or "gitlab-mr"
+    pub kind: String,
+    pub number: u32,
+    pub error: String,
+}
+
+/// Summary of a bulk context refresh for a worktree
+#[derive(Debug, Clone, Serialize, Deserialize, Default)]
+#[serde(rename_all = "camelCase")]
+pub struct RefreshSummary {
+    pub refreshed: u32,
+    pub failed: u32,
+    pub failures: Vec<RefreshFailure>,
+}
+
+impl RefreshSummary {
+    fn record<T>(&mut self, kind: &str, number: u32, result: Result<T, String>) {
+        match result {
+            Ok(_) => self.refreshed += 1,
+            Err(error) => {
+                log::warn!("Failed to refresh {kind} {number}: {error}");
+                self.failed += 1;
+                self.failures.push(RefreshFailure {
+                    kind: kind.to_string(),
+                    number,
+                    error,
+                });
+            }
+        }
+    }
+}
+
+/// Collect the numbers of references that belong to the given repo key
+///
+/// Keys for other repositories are skipped so a refresh never writes
+/// another repo's issue into this project's context files.
+fn numbers_for_repo(
+    keys: &[String],
+    repo_key: &str,
+    parse: impl Fn(&str) -> Option<(String, u32)>,
+) -> Vec<u32> {
+    let mut numbers: Vec<u32> = keys
+        .iter()
+        .filter_map(|key| parse(key))
+        .filter(|(key_repo, _)| key_repo == repo_key)
+        .map(|(_, number)| number)
+        .collect();
+    numbers.sort_unstable();
+    numbers
+}
+
+/// Re-fetch every loaded GitHub/GitLab issue and PR/MR context for a worktree
+///
+/// Continues past individual failures (e.g., a deleted issue) and reports
+/// them in the returned summary instead of aborting.
+#[tauri::command]
+pub async fn refresh_all_contexts(
+    app: tauri::AppHandle,
+    worktree_id: String,
+    project_path: String,
+) -> Result<RefreshSummary, String> {
+    use super::git::get_gitlab_repo_identifier;
+    use super::gitlab_issues::{
+        get_worktree_gitlab_issue_refs, get_worktree_gitlab_mr_refs, load_gitlab_issue_context,
+        load_gitlab_mr_context,
+    };
+
+    log::trace!("Refreshing all contexts for worktree {worktree_id}");
+
+    let mut summary = RefreshSummary::default();
+
+    let parse_github_key = |key: &str| {
+        if key.starts_with("gitlab-") {
+            return None;
+        }
+        parse_context_key(key).map(|(owner, repo, number)| (format!("{owner}-{repo}"), number))
+    };
+
+    // GitHub issues and PRs
+    if let Ok(repo_id) = get_repo_identifier(&project_path) {
+        migrate_host_less_contexts(&app, &repo_id, false);
+        let repo_key = repo_id.to_key();
+
+        let issue_keys = get_worktree_issue_refs(&app, &worktree_id)?;
+        for number in numbers_for_repo(&issue_keys, &repo_key, parse_github_key) {
+            let result = load_issue_context(
+                app.clone(),
+                worktree_id.clone(),
+                number,
+                project_path.clone(),
+            )
+            .await;
+            summary.record("issue", number, result);
+        }
+
+        let pr_keys = get_worktree_pr_refs(&app, &worktree_id)?;
+        for number in numbers_for_repo(&pr_keys, &repo_key, parse_github_key) {
+            let result = load_pr_context(
+                app.clone(),
+                worktree_id.clone(),
+                number,
+                project_path.clone(),
+            )
+            .await;
+            summary.record("pr", number, result);
+        }
+    }
+
+    // GitLab issues and MRs
+    if let Ok(repo_id) = get_gitlab_repo_identifier(&project_path) {
+        migrate_host_less_contexts(&app, &repo_id, true);
+        let repo_key = repo_id.to_key();
+
+        let issue_keys = get_worktree_gitlab_issue_refs(&app, &worktree_id)?;
+        for iid in numbers_for_repo(&issue_keys, &repo_key, parse_gitlab_context_key) {
+            let result = load_gitlab_issue_context(
+                app.clone(),
+                worktree_id.clone(),
+                iid,
+                project_path.clone(),
+            )
+            .await;
+            summary.record("gitlab-issue", iid, result);
+        }
+
+        let mr_keys = get_worktree_gitlab_mr_refs(&app, &worktree_id)?;
+        for iid in numbers_for_repo(&mr_keys, &repo_key, parse_gitlab_context_key) {
+            let result =
+                load_gitlab_mr_context(app.clone(), worktree_id.clone(), iid, project_path.clone())
+                    .await;
+            summary.record("gitlab-mr", iid, result);
+        }
+    }
+
+    log::trace!(
+        "Refreshed {} contexts for worktree {worktree_id} ({} failed)",
+        summary.refreshed,
+        summary.failed
+    );
+
+    Ok(summary)
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
 
+    #[test]
+    fn test_prepare_diff_strips_ansi_and_truncates() {
+        let colored = "\x1b[1mdiff --git a/x b/x\x1b[0m\n\x1b[32m+added\x1b[m\n";
+        assert_eq!(
+            prepare_diff(colored, "gh pr diff 1"),
+            "diff --git a/x b/x\n+added\n"
+        );
+
+        // Multi-byte characters straddling the limit must not split
+        let long = format!("+{}", "é".repeat(MAX_DIFF_SIZE));
+        let truncated = prepare_diff(&long, "gh pr diff 7");
+        assert!(truncated.contains("bytes total. Run `gh pr diff 7` to see the full diff."));
+        assert!(truncated.len() < MAX_DIFF_SIZE + 200);
+    }
+
+    #[test]
+    fn test_validate_comment_body() {
+        assert!(validate_comment_body("LGTM", 10).is_ok());
+        assert_eq!(
+            validate_comment_body(" \n\t", 10).unwrap_err(),
+            "Comment body is empty"
+        );
+        // The limit counts characters, not bytes
+        assert!(validate_comment_body(&"é".repeat(10), 10).is_ok());
+        assert!(validate_comment_body(&"é".repeat(11), 10)
+            .unwrap_err()
+            .starts_with("Comment is too long"));
+    }
+
+    #[test]
+    fn test_comment_url() {
+        assert_eq!(
+            comment_url("https://github.com/o/r/pull/7#issuecomment-42\n"),
+            Some("https://github.com/o/r/pull/7#issuecomment-42".to_string())
+        );
+        assert_eq!(comment_url(""), None);
+    }
+
+    #[cfg(unix)]
+    #[test]
+    fn test_output_with_stdin() {
+        let body = "line one\n".repeat(50_000);
+        let output = output_with_stdin(Command::new("cat"), &body).unwrap();
+        assert!(output.status.success());
+        assert_eq!(output.stdout.len(), body.len());
+    }
+
+    #[test]
+    fn test_push_diff_section() {
+        let mut content = String::new();
+        push_diff_section(&mut content, Some("+line"));
+        assert_eq!(content, "## Changes (Diff)\n\n```diff\n+line\n```\n\n");
+
+        let mut content = String::new();
+        push_diff_section(&mut content, Some(""));
+        push_diff_section(&mut content, None);
+        assert!(content.is_empty());
+    }
+
+    #[test]
+    fn test_context_staleness_uses_stored_hash() {
+        let loaded = "# GitHub Issue #1: Title\n";
+        let updated = "# GitHub Issue #1: Title\n\n### @octocat\n";
+
+        // References written before hashes were tracked fall back to the file content
+        let legacy: ContextRef =
+            serde_json::from_str(r#"{"worktrees": ["wt-1"], "orphaned_at": null}"#).unwrap();
+        assert_eq!(legacy.content_hash, None);
+        assert!(!is_context_stale(Some(&legacy), loaded, loaded));
+        assert!(is_context_stale(None, loaded, updated));
+
+        // The stored hash wins over a file edited on disk
+        let tracked = ContextRef {
+            content_hash: Some(context_hash(updated)),
+            ..Default::default()
+        };
+        assert!(!is_context_stale(Some(&tracked), loaded, updated));
+        assert_eq!(context_hash(loaded).len(), 64);
+    }
+
+    #[test]
+    fn test_pr_staleness_uses_stored_fingerprint() {
+        let loaded = "2026-01-01T00:00:00Z|abc123|2|1";
+        let tracked = ContextRef {
+            fingerprint: Some(loaded.to_string()),
+            ..Default::default()
+        };
+        assert_eq!(is_fingerprint_stale(Some(&tracked), loaded), Some(false));
+        assert_eq!(
+            is_fingerprint_stale(Some(&tracked), "2026-01-02T00:00:00Z|def456|2|1"),
+            Some(true)
+        );
+
+        // Loaded before fingerprints were stored: unknown, not stale
+        let legacy = ContextRef {
+            content_hash: Some(context_hash("# GitHub Pull Request #1: Title\n")),
+            ..Default::default()
+        };
+        assert_eq!(is_fingerprint_stale(Some(&legacy), loaded), None);
+        assert_eq!(is_fingerprint_stale(None, loaded), None);
+    }
+
+    #[test]
+    fn test_parse_comment_page() {
+        let json = r#"[
+            {"body": "First", "user": {"login": "octocat", "id": 1}, "created_at": "2026-01-01T00:00:00Z"},
+            {"body": null, "user": null, "created_at": "2026-01-02T00:00:00Z"}
+        ]"#;
+        let page = parse_comment_page(json, 2).unwrap();
+        assert_eq!(page.page, 2);
+        assert!(!page.has_more);
+        assert_eq!(page.comments.len(), 2);
+        assert_eq!(page.comments[0].author.login, "octocat");
+        assert_eq!(page.comments[1].body, "");
+        assert_eq!(page.comments[1].author.login, "ghost");
+
+        let full: Vec<_> = (0..COMMENTS_PER_PAGE)
+            .map(|_| serde_json::json!({"body": "x", "user": {"login": "a"}, "created_at": "t"}))
+            .collect();
+        let page = parse_comment_page(&serde_json::to_string(&full).unwrap(), 1).unwrap();
+        assert!(page.has_more);
+    }
+
     #[test]
     fn test_slugify_issue_title() {
         assert_eq!(
@@ -1452,4 +2438,150 @@ mod tests {
         assert_eq!(parse_context_key("repo-abc"), None);
         assert_eq!(parse_context_key("single"), None);
     }
+
+    #[test]
+    fn test_numbers_for_repo_filters_other_repos() {
+        let keys = vec![
+            "foo-2024#5".to_string(),
+            "foo-2024#2".to_string(),
+            "other-repo#9".to_string(),
+            "garbage".to_string(),
+        ];
+        assert_eq!(
+            numbers_for_repo(&keys, "foo-2024", parse_gitlab_context_key),
+            vec![2, 5]
+        );
+    }
+
+    #[test]
+    fn test_refresh_summary_records_failures() {
+        let mut summary = RefreshSummary::default();
+        summary.record("issue", 1, Ok::<(), String>(()));
+        summary.record::<()>("issue", 2, Err("Issue #2 not found".to_string()));
+        summary.record("pr", 3, Ok::<(), String>(()));
+
+        assert_eq!(summary.refreshed, 2);
+        assert_eq!(summary.failed, 1);
+        assert_eq!(summary.failures[0].number, 2);
+        assert_eq!(summary.failures[0].kind, "issue");
+    }
+
+    #[test]
+    fn test_concurrent_reference_adds_are_not_lost() {
+        let dir = tempfile::tempdir().unwrap();
+        let path = dir.path().join("references.json");
+
+        std::thread::scope(|scope| {
+            for worktree_id in ["wt-a", "wt-b"] {
+                let path = &path;
+                scope.spawn(move || {
+                    for _ in 0..20 {
+                        with_references_at(path, |refs| {
+                            refs.issues
+                                .entry("owner-repo-1".to_string())
+                                .or_default()
+                                .add_worktree(worktree_id);
+                            Ok(())
+                        })
+                        .unwrap();
+                    }
+                });
+            }
+        });
+
+        let (refs, _) = load_references_internal(&path).unwrap();
+        let mut worktrees = refs.issues["owner-repo-1"].worktrees.clone();
+        worktrees.sort();
+        assert_eq!(worktrees, vec!["wt-a", "wt-b"]);
+    }
+
+    #[test]
+    fn test_release_context_file_deletes_with_last_reference() {
+        let dir = tempfile::tempdir().unwrap();
+        let refs_path = dir.path().join("references.json");
+        let file = dir.path().join("owner-repo-pr-7.md");
+        std::fs::write(&file, "# PR 7").unwrap();
+        with_references_at(&refs_path, |refs| {
+            let entry = refs.prs.entry("owner-repo-7".to_string()).or_default();
+            entry.add_worktree("wt-a");
+            entry.add_worktree("wt-b");
+            Ok(())
+        })
+        .unwrap();
+
+        // Still referenced by wt-b: the file stays
+        let deleted =
+            release_context_file_at(&refs_path, ContextKind::Pr, "owner-repo-7", "wt-a", &file)
+                .unwrap();
+        assert!(!deleted);
+        assert!(file.exists());
+
+        let deleted =
+            release_context_file_at(&refs_path, ContextKind::Pr, "owner-repo-7", "wt-b", &file)
+                .unwrap();
+        assert!(deleted);
+        assert!(!file.exists());
+        let (refs, _) = load_references_internal(&refs_path).unwrap();
+        assert!(refs.prs["owner-repo-7"].orphaned_at.is_some());
+    }
+
+    #[test]
+    fn test_migrate_host_less_contexts_moves_only_that_repo() {
+        let dir = tempfile::tempdir().unwrap();
+        let refs_path = dir.path().join("references.json");
+        let host_key = "acme-app@gitlab.acme.dev";
+        with_references_at(&refs_path, |refs| {
+            refs.issues
+                .entry("gitlab-acme-app#3".to_string())
+                .or_default()
+                .add_worktree("wt-a");
+            refs.prs
+                .entry("gitlab-acme-app#4".to_string())
+                .or_default()
+                .add_worktree("wt-a");
+            // Reloaded since the upgrade: both keys exist
+            refs.prs
+                .entry(format!("gitlab-{host_key}#4"))
+                .or_default()
+                .add_worktree("wt-b");
+            // Same repo name on GitHub stays put
+            refs.issues
+                .entry("acme-app-3".to_string())
+                .or_default()
+                .add_worktree("wt-a");
+            Ok(())
+        })
+        .unwrap();
+        for (file, content) in [
+            ("acme-app-gitlab-issue-3.md", "old issue"),
+            ("acme-app-gitlab-mr-4.md", "old mr"),
+            ("acme-app@gitlab.acme.dev-gitlab-mr-4.md", "new mr"),
+            ("acme-app-issue-3.md", "github issue"),
+        ] {
+            std::fs::write(dir.path().join(file), content).unwrap();
+        }
+
+        let migrated = migrate_host_less_contexts_at(dir.path(), "acme-app", host_key, true);
+        assert_eq!(migrated.unwrap(), 2);
+
+        let (refs, _) = load_references_internal(&refs_path).unwrap();
+        let issue = &refs.issues[&format!("gitlab-{host_key}#3")];
+        assert_eq!(issue.worktrees, vec!["wt-a"]);
+        let mr = &refs.prs[&format!("gitlab-{host_key}#4")];
+        assert_eq!(mr.worktrees, vec!["wt-b", "wt-a"]);
+        assert!(!refs.issues.contains_key("gitlab-acme-app#3"));
+        assert!(refs.issues.contains_key("acme-app-3"));
+
+        let read = |file: &str| std::fs::read_to_string(dir.path().join(file)).ok();
+        let issue_file = read("acme-app@gitlab.acme.dev-gitlab-issue-3.md");
+        assert_eq!(issue_file.as_deref(), Some("old issue"));
+        let mr_file = read("acme-app@gitlab.acme.dev-gitlab-mr-4.md");
+        assert_eq!(mr_file.as_deref(), Some("new mr"));
+        assert_eq!(read("acme-app-gitlab-mr-4.md"), None);
+        assert_eq!(read("acme-app-issue-3.md").as_deref(), Some("github issue"));
+
+        // Second pass is a no-op
+        let migrated = migrate_host_less_contexts_at(dir.path(), "acme-app", host_key, true);
+        assert_eq!(migrated.unwrap(), 0);
+    }
 }
@@ -8,8 +8,10 @@ use std::process::Command;
 
 use super::git::get_gitlab_repo_identifier;
 use super::github_issues::{
-    add_issue_reference, add_pr_reference, get_github_contexts_dir, remove_issue_reference,
-    remove_pr_reference,
+    fetch_states_concurrently, get_github_contexts_dir, is_fingerprint_stale,
+    load_context_references, migrate_host_less_contexts, output_with_stdin, prepare_diff,
+    push_diff_section, release_context_file, store_context_file, validate_comment_body,
+    ContextKind, ContextReferences,
 };
 
 // =============================================================================
@@ -135,6 +137,9 @@ pub struct LoadedGitLabIssueContext {
     pub title: String,
     pub note_count: usize,
     pub project_path: String,
+    /// Whether the issue changed since the context was loaded (only set when checked)
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub stale: Option<bool>,
 }
 
 /// Loaded MR context info returned to frontend
@@ -145,6 +150,9 @@ pub struct LoadedGitLabMergeRequestContext {
     pub title: String,
     pub note_count: usize,
     pub project_path: String,
+    /// Whether the MR changed since the context was loaded (only set when checked)
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub stale: Option<bool>,
 }
 
 // =============================================================================
@@ -491,18 +499,7 @@ pub fn format_gitlab_mr_context_markdown(ctx: &GitLabMergeRequestContext) -> Str
         }
     }
 
-    // Add diff section if available
-    if let Some(diff) = &ctx.diff {
-        if !diff.is_empty() {
-            content.push_str("## Changes (Diff)\n\n");
-            content.push_str("```diff\n");
-            content.push_str(diff);
-            if !diff.ends_with('\n') {
-                content.push('\n');
-            }
-            content.push_str("```\n\n");
-        }
-    }
+    push_diff_section(&mut content, ctx.diff.as_deref());
 
     content.push_str("---\n\n");
     content.push_str("*Review this merge request and provide feedback or make changes.*\n");
@@ -529,21 +526,58 @@ pub fn get_mr_diff(project_path: &str, mr_iid: u32) -> Result<String, String> {
         return Ok(String::new());
     }
 
-    let diff = String::from_utf8_lossy(&output.stdout).to_string();
+    let diff = String::from_utf8_lossy(&output.stdout);
     log::debug!("Got diff for MR !{mr_iid}: {} bytes", diff.len());
 
-    // Truncate if > 100KB
-    const MAX_DIFF_SIZE: usize = 100_000;
-    if diff.len() > MAX_DIFF_SIZE {
-        Ok(format!(
-            "{}...\n\n[Diff truncated at 100KB - {} bytes total. Run `glab mr diff {}` to see the full diff.]",
-            &diff[..MAX_DIFF_SIZE],
-            diff.len(),
-            mr_iid
-        ))
-    } else {
-        Ok(diff)
+    Ok(prepare_diff(&diff, &format!("glab mr diff {mr_iid}")))
+}
+
+/// GitLab rejects notes longer than this many characters
+const GITLAB_NOTE_MAX_CHARS: usize = 1_000_000;
+
+/// Post a note on a GitLab MR
+///
+/// `glab mr note` only takes the message as an argument, so the notes API is
+/// called through `glab api` with the body on stdin instead. The API response
+/// has no web URL, so this always returns `None`.
+pub fn post_gitlab_mr_note(
+    project_path: &str,
+    mr_iid: u32,
+    body: &str,
+) -> Result<Option<String>, String> {
+    validate_comment_body(body, GITLAB_NOTE_MAX_CHARS)?;
+    log::trace!("Posting note on MR !{mr_iid} in {project_path}");
+
+    let mut command = Command::new("glab");
+    command
+        .args([
+            "api",
+            "--method",
+            "POST",
+            &format!("projects/:id/merge_requests/{mr_iid}/notes"),
+            "--header",
+            "Content-Type: application/json",
+            "--input",
+            "-",
+        ])
+        .current_dir(project_path);
+    let request = serde_json::json!({ "body": body }).to_string();
+    let output =
+        output_with_stdin(command, &request).map_err(|e| format!("Failed to run glab api: {e}"))?;
+
+    if !output.status.success() {
+        let stderr = String::from_utf8_lossy(&output.stderr);
+        if stderr.contains("glab auth login") || stderr.contains("authentication") {
+            return Err("GitLab CLI not authenticated. Run 'glab auth login' first.".to_string());
+        }
+        if stderr.contains("not found") || stderr.contains("404") {
+            return Err(format!("MR !{mr_iid} not found"));
+        }
+        return Err(format!("glab api failed: {stderr}"));
     }
+
+    log::debug!("Posted note on MR !{mr_iid}");
+    Ok(None)
 }
 
 // =============================================================================
@@ -562,18 +596,17 @@ pub async fn load_gitlab_issue_context(
 
     // Get repo identifier for shared storage
     let repo_id = get_gitlab_repo_identifier(&project_path)?;
+    migrate_host_less_contexts(&app, &repo_id, true);
     let repo_key = repo_id.to_key();
 
-    // Fetch issue data from GitLab
-    let issue = get_gitlab_issue(project_path.clone(), issue_iid).await?;
+    // Fingerprint first: if the issue changes while it's being fetched, the
+    // context is reported stale rather than the change being missed
+    let fingerprint = get_gitlab_issue_fingerprint(&project_path, issue_iid)
+        .map_err(|e| log::warn!("Failed to fingerprint issue !{issue_iid}: {e}"))
+        .ok();
 
-    // Create issue context
-    let ctx = GitLabIssueContext {
-        iid: issue.iid,
-        title: issue.title.clone(),
-        description: issue.description,
-        notes: issue.notes,
-    };
+    // Fetch issue data from GitLab
+    let ctx = fetch_gitlab_issue_context(project_path, issue_iid).await?;
 
     // Write to shared git-context directory
     let contexts_dir = get_github_contexts_dir(&app)?;
@@ -584,11 +617,17 @@ pub async fn load_gitlab_issue_context(
     let context_file = contexts_dir.join(format!("{repo_key}-gitlab-issue-{issue_iid}.md"));
     let context_content = format_gitlab_issue_context_markdown(&ctx);
 
-    std::fs::write(&context_file, context_content)
-        .map_err(|e| format!("Failed to write issue context file: {e}"))?;
-
-    // Add reference tracking (reuse GitHub's tracking with gitlab prefix in key)
-    add_issue_reference(&app, &format!("gitlab-{repo_key}"), issue_iid, &worktree_id)?;
+    // Write (if changed) and add reference tracking
+    // (reuse GitHub's tracking with gitlab prefix in key)
+    store_context_file(
+        &app,
+        ContextKind::Issue,
+        &gitlab_reference_key(&repo_key, issue_iid),
+        &worktree_id,
+        &context_file,
+        &context_content,
+        fingerprint,
+    )?;
 
     log::trace!(
         "GitLab issue context loaded successfully for issue !{} ({} notes)",
@@ -597,10 +636,25 @@ pub async fn load_gitlab_issue_context(
     );
 
     Ok(LoadedGitLabIssueContext {
-        iid: issue.iid,
-        title: issue.title,
+        iid: ctx.iid,
         note_count: ctx.notes.len(),
+        title: ctx.title,
         project_path: repo_key,
+        stale: Some(false),
+    })
+}
+
+/// Fetch an issue with its notes as context
+async fn fetch_gitlab_issue_context(
+    project_path: String,
+    issue_iid: u32,
+) -> Result<GitLabIssueContext, String> {
+    let issue = get_gitlab_issue(project_path, issue_iid).await?;
+    Ok(GitLabIssueContext {
+        iid: issue.iid,
+        title: issue.title,
+        description: issue.description,
+        notes: issue.notes,
     })
 }
 
@@ -616,24 +670,16 @@ pub async fn load_gitlab_mr_context(
 
     // Get repo identifier for shared storage
     let repo_id = get_gitlab_repo_identifier(&project_path)?;
+    migrate_host_less_contexts(&app, &repo_id, true);
     let repo_key = repo_id.to_key();
 
-    // Fetch MR data from GitLab
-    let mr = get_gitlab_mr(project_path.clone(), mr_iid).await?;
+    // Fingerprint first, as for issues
+    let fingerprint = get_gitlab_mr_fingerprint(&project_path, mr_iid)
+        .map_err(|e| log::warn!("Failed to fingerprint MR !{mr_iid}: {e}"))
+        .ok();
 
-    // Fetch the diff
-    let diff = get_mr_diff(&project_path, mr_iid).ok();
-
-    // Create MR context
-    let ctx = GitLabMergeRequestContext {
-        iid: mr.iid,
-        title: mr.title.clone(),
-        description: mr.description,
-        source_branch: mr.source_branch,
-        target_branch: mr.target_branch,
-        notes: mr.notes.clone(),
-        diff,
-    };
+    // Fetch MR data and diff from GitLab
+    let ctx = fetch_gitlab_mr_context(project_path, mr_iid).await?;
 
     // Write to shared git-context directory
     let contexts_dir = get_github_contexts_dir(&app)?;
@@ -644,11 +690,16 @@ pub async fn load_gitlab_mr_context(
     let context_file = contexts_dir.join(format!("{repo_key}-gitlab-mr-{mr_iid}.md"));
     let context_content = format_gitlab_mr_context_markdown(&ctx);
 
-    std::fs::write(&context_file, context_content)
-        .map_err(|e| format!("Failed to write MR context file: {e}"))?;
-
-    // Add reference tracking
-    add_pr_reference(&app, &format!("gitlab-{repo_key}"), mr_iid, &worktree_id)?;
+    // Write (if changed) and add reference tracking
+    store_context_file(
+        &app,
+        ContextKind::Pr,
+        &gitlab_reference_key(&repo_key, mr_iid),
+        &worktree_id,
+        &context_file,
+        &context_content,
+        fingerprint,
+    )?;
 
     log::debug!(
         "GitLab MR context loaded successfully for MR !{} ({} notes, diff: {} bytes)",
@@ -658,13 +709,81 @@ pub async fn load_gitlab_mr_context(
     );
 
     Ok(LoadedGitLabMergeRequestContext {
+        iid: ctx.iid,
+        note_count: ctx.notes.len(),
+        title: ctx.title,
+        project_path: repo_key,
+        stale: Some(false),
+    })
+}
+
+/// Fetch an MR with its notes and diff as context
+async fn fetch_gitlab_mr_context(
+    project_path: String,
+    mr_iid: u32,
+) -> Result<GitLabMergeRequestContext, String> {
+    let mr = get_gitlab_mr(project_path.clone(), mr_iid).await?;
+    let diff = get_mr_diff(&project_path, mr_iid).ok();
+    Ok(GitLabMergeRequestContext {
         iid: mr.iid,
         title: mr.title,
-        note_count: mr.notes.len(),
-        project_path: repo_key,
+        description: mr.description,
+        source_branch: mr.source_branch,
+        target_branch: mr.target_branch,
+        notes: mr.notes,
+        diff,
     })
 }
 
+/// Fields of `glab issue view` / `glab mr view` JSON that change whenever the
+/// context file would
+#[derive(Debug, Deserialize)]
+struct GitLabFingerprintFields {
+    updated_at: String,
+    #[serde(default)]
+    user_notes_count: u64,
+    /// Head commit (MRs only)
+    #[serde(default)]
+    sha: Option<String>,
+}
+
+/// Summarize an issue or MR from its `glab ... view` JSON
+fn parse_gitlab_fingerprint(json: &str) -> Result<String, String> {
+    let fields: GitLabFingerprintFields =
+        serde_json::from_str(json).map_err(|e| format!("Failed to parse glab response: {e}"))?;
+    Ok(format!(
+        "{}|{}|{}",
+        fields.updated_at,
+        fields.sha.unwrap_or_default(),
+        fields.user_notes_count
+    ))
+}
+
+/// Fingerprint an issue or MR with one `glab view` call, without notes or diff
+fn get_gitlab_fingerprint(project_path: &str, kind: &str, iid: u32) -> Result<String, String> {
+    let output = Command::new("glab")
+        .args([kind, "view", &iid.to_string(), "--output", "json"])
+        .current_dir(project_path)
+        .output()
+        .map_err(|e| format!("Failed to run glab {kind} view: {e}"))?;
+
+    if !output.status.success() {
+        let stderr = String::from_utf8_lossy(&output.stderr);
+        return Err(format!("glab {kind} view failed: {stderr}"));
+    }
+    parse_gitlab_fingerprint(&String::from_utf8_lossy(&output.stdout))
+}
+
+/// Summarize an issue's current state (last update and note count)
+pub fn get_gitlab_issue_fingerprint(project_path: &str, issue_iid: u32) -> Result<String, String> {
+    get_gitlab_fingerprint(project_path, "issue", issue_iid)
+}
+
+/// Summarize an MR's current state (last update, head commit and note count)
+pub fn get_gitlab_mr_fingerprint(project_path: &str, mr_iid: u32) -> Result<String, String> {
+    get_gitlab_fingerprint(project_path, "mr", mr_iid)
+}
+
 /// Remove a loaded GitLab issue context for a worktree
 #[tauri::command]
 pub async fn remove_gitlab_issue_context(
@@ -677,28 +796,20 @@ pub async fn remove_gitlab_issue_context(
 
     // Get repo identifier
     let repo_id = get_gitlab_repo_identifier(&project_path)?;
+    migrate_host_less_contexts(&app, &repo_id, true);
     let repo_key = repo_id.to_key();
 
-    // Remove reference
-    let is_orphaned = remove_issue_reference(
+    // Remove the reference, and the shared file if nothing else uses it
+    let context_file =
+        get_github_contexts_dir(&app)?.join(format!("{repo_key}-gitlab-issue-{issue_iid}.md"));
+    release_context_file(
         &app,
-        &format!("gitlab-{repo_key}"),
-        issue_iid,
+        ContextKind::Issue,
+        &gitlab_reference_key(&repo_key, issue_iid),
         &worktree_id,
+        &context_file,
     )?;
 
-    // If orphaned, delete the shared file immediately
-    if is_orphaned {
-        let contexts_dir = get_github_contexts_dir(&app)?;
-        let context_file = contexts_dir.join(format!("{repo_key}-gitlab-issue-{issue_iid}.md"));
-
-        if context_file.exists() {
-            std::fs::remove_file(&context_file)
-                .map_err(|e| format!("Failed to remove issue context file: {e}"))?;
-            log::trace!("Deleted orphaned GitLab issue context file");
-        }
-    }
-
     log::trace!("GitLab issue context removed successfully");
     Ok(())
 }
@@ -715,23 +826,19 @@ pub async fn remove_gitlab_mr_context(
 
     // Get repo identifier
     let repo_id = get_gitlab_repo_identifier(&project_path)?;
+    migrate_host_less_contexts(&app, &repo_id, true);
     let repo_key = repo_id.to_key();
 
-    // Remove reference
-    let is_orphaned =
-        remove_pr_reference(&app, &format!("gitlab-{repo_key}"), mr_iid, &worktree_id)?;
-
-    // If orphaned, delete the shared file immediately
-    if is_orphaned {
-        let contexts_dir = get_github_contexts_dir(&app)?;
-        let context_file = contexts_dir.join(format!("{repo_key}-gitlab-mr-{mr_iid}.md"));
-
-        if context_file.exists() {
-            std::fs::remove_file(&context_file)
-                .map_err(|e| format!("Failed to remove MR context file: {e}"))?;
-            log::trace!("Deleted orphaned GitLab MR context file");
-        }
-    }
+    // Remove the reference, and the shared file if nothing else uses it
+    let context_file =
+        get_github_contexts_dir(&app)?.join(format!("{repo_key}-gitlab-mr-{mr_iid}.md"));
+    release_context_file(
+        &app,
+        ContextKind::Pr,
+        &gitlab_reference_key(&repo_key, mr_iid),
+        &worktree_id,
+        &context_file,
+    )?;
 
     log::trace!("GitLab MR context removed successfully");
     Ok(())
@@ -742,7 +849,7 @@ pub async fn remove_gitlab_mr_context(
 // =============================================================================
 
 /// Get GitLab issue refs for a worktree from reference tracking
-fn get_worktree_gitlab_issue_refs(
+pub fn get_worktree_gitlab_issue_refs(
     app: &tauri::AppHandle,
     worktree_id: &str,
 ) -> Result<Vec<String>, String> {
@@ -766,7 +873,7 @@ fn get_worktree_gitlab_issue_refs(
 }
 
 /// Get GitLab MR refs for a worktree from reference tracking
-fn get_worktree_gitlab_mr_refs(
+pub fn get_worktree_gitlab_mr_refs(
     app: &tauri::AppHandle,
     worktree_id: &str,
 ) -> Result<Vec<String>, String> {
@@ -789,28 +896,140 @@ fn get_worktree_gitlab_mr_refs(
     Ok(keys)
 }
 
-/// Parse context key format: "{repo_key}-{iid}"
-fn parse_gitlab_context_key(key: &str) -> Option<(String, u32)> {
-    // Key format: "{owner}-{repo}-{iid}" where owner-repo is the repo_key
-    let parts: Vec<&str> = key.rsplitn(2, '-').collect();
-    if parts.len() != 2 {
+/// Build the reference-tracking key for a GitLab issue/MR
+///
+/// Key format: "gitlab-{repo_key}#{iid}". The `#` separator can't appear in a
+/// repo key, so the iid is never confused with a numeric repo name suffix.
+pub fn gitlab_reference_key(repo_key: &str, iid: u32) -> String {
+    format!("gitlab-{repo_key}#{iid}")
+}
+
+/// Parse a context key (without the "gitlab-" prefix) into (repo_key, iid)
+///
+/// Accepts the current "{repo_key}#{iid}" format and the legacy
+/// "{repo_key}-{iid}" format written by older versions.
+pub fn parse_gitlab_context_key(key: &str) -> Option<(String, u32)> {
+    let (repo_key, iid_str) = match key.rsplit_once('#') {
+        Some(parts) => parts,
+        None => key.rsplit_once('-')?,
+    };
+
+    if repo_key.is_empty() {
         return None;
     }
+    let iid: u32 = iid_str.parse().ok()?;
 
-    let iid: u32 = parts[0].parse().ok()?;
-    let repo_key = parts[1].to_string();
+    Some((repo_key.to_string(), iid))
+}
 
-    Some((repo_key, iid))
+/// Rewrite legacy "gitlab-{repo_key}-{iid}" reference keys to the `#` format
+///
+/// Returns true if any key was migrated (caller should persist the result).
+pub fn migrate_legacy_gitlab_keys(refs: &mut ContextReferences) -> bool {
+    let mut migrated = false;
+
+    for map in [&mut refs.issues, &mut refs.prs] {
+        let legacy_keys: Vec<String> = map
+            .keys()
+            .filter(|k| k.starts_with("gitlab-") && !k.contains('#'))
+            .cloned()
+            .collect();
+
+        for legacy_key in legacy_keys {
+            let Some((repo_key, iid)) = legacy_key
+                .strip_prefix("gitlab-")
+                .and_then(parse_gitlab_context_key)
+            else {
+                continue;
+            };
+            let Some(legacy_ref) = map.remove(&legacy_key) else {
+                continue;
+            };
+
+            map.entry(gitlab_reference_key(&repo_key, iid))
+                .or_default()
+                .merge_from(legacy_ref);
+            migrated = true;
+        }
+    }
+
+    migrated
+}
+
+/// A GitLab context file loaded into a worktree, read from disk
+struct StoredGitLabContext {
+    key: String,
+    repo_key: String,
+    iid: u32,
+    content: String,
+}
+
+/// Read the context files behind `keys`
+///
+/// `kind` is the file name infix ("issue" or "mr"). Missing files are skipped.
+fn read_stored_gitlab_contexts(
+    contexts_dir: &std::path::Path,
+    keys: Vec<String>,
+    kind: &str,
+) -> Vec<StoredGitLabContext> {
+    keys.into_iter()
+        .filter_map(|key| {
+            // Parse key format: "{repo_key}#{iid}"
+            let (repo_key, iid) = parse_gitlab_context_key(&key)?;
+            let file = contexts_dir.join(format!("{repo_key}-gitlab-{kind}-{iid}.md"));
+            let content = std::fs::read_to_string(file).ok()?;
+            Some(StoredGitLabContext {
+                key,
+                repo_key,
+                iid,
+                content,
+            })
+        })
+        .collect()
+}
+
+/// Fingerprint the contexts from `project_path`'s repository concurrently
+///
+/// `check_repo_key` is that repository's key. Returns one entry per stored
+/// context, None for those from other repositories (or all of them without a
+/// `project_path`) and for failures.
+async fn fetch_gitlab_fingerprints(
+    stored: &[StoredGitLabContext],
+    project_path: Option<String>,
+    check_repo_key: Option<String>,
+    label: &'static str,
+    fetch: fn(&str, u32) -> Result<String, String>,
+) -> Vec<Option<String>> {
+    let (Some(project_path), Some(check_repo_key)) = (project_path, check_repo_key) else {
+        return std::iter::repeat_with(|| None).take(stored.len()).collect();
+    };
+    let iids = stored
+        .iter()
+        .map(|c| (c.repo_key == check_repo_key).then_some(c.iid))
+        .collect();
+    fetch_states_concurrently(project_path, iids, label, fetch).await
 }
 
 /// List all loaded GitLab issue contexts for a worktree
+///
+/// When `project_path` is given, that repository's issues are fingerprinted
+/// concurrently and compared to the fingerprints stored at load to fill in
+/// `stale`. Issues loaded before fingerprints were stored report None.
 #[tauri::command]
 pub async fn list_loaded_gitlab_issue_contexts(
     app: tauri::AppHandle,
     worktree_id: String,
+    project_path: Option<String>,
 ) -> Result<Vec<LoadedGitLabIssueContext>, String> {
     log::trace!("Listing loaded GitLab issue contexts for worktree {worktree_id}");
 
+    let repo_id = project_path
+        .as_deref()
+        .and_then(|path| get_gitlab_repo_identifier(path).ok());
+    if let Some(repo_id) = &repo_id {
+        migrate_host_less_contexts(&app, repo_id, true);
+    }
+
     // Get GitLab issue refs for this worktree from reference tracking
     let issue_keys = get_worktree_gitlab_issue_refs(&app, &worktree_id)?;
 
@@ -819,36 +1038,50 @@ pub async fn list_loaded_gitlab_issue_contexts(
     }
 
     let contexts_dir = get_github_contexts_dir(&app)?;
-    let mut contexts = Vec::new();
+    let refs = load_context_references(&app)?;
+    let stored = read_stored_gitlab_contexts(&contexts_dir, issue_keys, "issue");
+    let fingerprints = fetch_gitlab_fingerprints(
+        &stored,
+        project_path,
+        repo_id.map(|repo_id| repo_id.to_key()),
+        "GitLab issue",
+        get_gitlab_issue_fingerprint,
+    )
+    .await;
 
-    for key in issue_keys {
-        // Parse key format: "{repo_key}-{iid}"
-        if let Some((repo_key, iid)) = parse_gitlab_context_key(&key) {
-            let context_file = contexts_dir.join(format!("{repo_key}-gitlab-issue-{iid}.md"));
-
-            if let Ok(content) = std::fs::read_to_string(&context_file) {
-                // Parse title from first line: "# GitLab Issue !123: Title"
-                let title = content
-                    .lines()
-                    .next()
-                    .and_then(|line| {
-                        line.strip_prefix("# GitLab Issue !")
-                            .and_then(|rest| rest.split_once(": "))
-                            .map(|(_, title)| title.to_string())
-                    })
-                    .unwrap_or_else(|| format!("Issue !{iid}"));
-
-                // Count notes by counting "### @" headers
-                let note_count = content.matches("### @").count();
-
-                contexts.push(LoadedGitLabIssueContext {
-                    iid,
-                    title,
-                    note_count,
-                    project_path: repo_key,
-                });
-            }
-        }
+    let mut contexts = Vec::new();
+    for (context, fingerprint) in stored.into_iter().zip(fingerprints) {
+        let StoredGitLabContext {
+            key,
+            repo_key,
+            iid,
+            content,
+        } = context;
+
+        // Parse title from first line: "# GitLab Issue !123: Title"
+        let title = content
+            .lines()
+            .next()
+            .and_then(|line| {
+                line.strip_prefix("# GitLab Issue !")
+                    .and_then(|rest| rest.split_once(": "))
+                    .map(|(_, title)| title.to_string())
+            })
+            .unwrap_or_else(|| format!("Issue !{iid}"));
+
+        // Count notes by counting "### @" headers
+        let note_count = content.matches("### @").count();
+
+        let stale =
+            fingerprint.and_then(|fresh| is_fingerprint_stale(refs.issues.get(&key), &fresh));
+
+        contexts.push(LoadedGitLabIssueContext {
+            iid,
+            title,
+            note_count,
+            project_path: repo_key,
+            stale,
+        });
     }
 
     // Sort by issue IID
@@ -859,13 +1092,25 @@ pub async fn list_loaded_gitlab_issue_contexts(
 }
 
 /// List all loaded GitLab MR contexts for a worktree
+///
+/// When `project_path` is given, that repository's MRs are fingerprinted
+/// concurrently and compared to the fingerprints stored at load to fill in
+/// `stale`. MRs loaded before fingerprints were stored report None.
 #[tauri::command]
 pub async fn list_loaded_gitlab_mr_contexts(
     app: tauri::AppHandle,
     worktree_id: String,
+    project_path: Option<String>,
 ) -> Result<Vec<LoadedGitLabMergeRequestContext>, String> {
     log::trace!("Listing loaded GitLab MR contexts for worktree {worktree_id}");
 
+    let repo_id = project_path
+        .as_deref()
+        .and_then(|path| get_gitlab_repo_identifier(path).ok());
+    if let Some(repo_id) = &repo_id {
+        migrate_host_less_contexts(&app, repo_id, true);
+    }
+
     // Get GitLab MR refs for this worktree from reference tracking
     let mr_keys = get_worktree_gitlab_mr_refs(&app, &worktree_id)?;
 
@@ -874,39 +1119,52 @@ pub async fn list_loaded_gitlab_mr_contexts(
     }
 
     let contexts_dir = get_github_contexts_dir(&app)?;
-    let mut contexts = Vec::new();
+    let refs = load_context_references(&app)?;
+    let stored = read_stored_gitlab_contexts(&contexts_dir, mr_keys, "mr");
+    let fingerprints = fetch_gitlab_fingerprints(
+        &stored,
+        project_path,
+        repo_id.map(|repo_id| repo_id.to_key()),
+        "GitLab MR",
+        get_gitlab_mr_fingerprint,
+    )
+    .await;
 
-    for key in mr_keys {
-        // Parse key format: "{repo_key}-{iid}"
-        if let Some((repo_key, iid)) = parse_gitlab_context_key(&key) {
-            let context_file = contexts_dir.join(format!("{repo_key}-gitlab-mr-{iid}.md"));
-
-            if let Ok(content) = std::fs::read_to_string(&context_file) {
-                // Parse title from first line: "# GitLab Merge Request !123: Title"
-                let title = content
-                    .lines()
-                    .next()
-                    .and_then(|line| {
-                        line.strip_prefix("# GitLab Merge Request !")
-                            .and_then(|rest| rest.split_once(": "))
-                            .map(|(_, title)| title.to_string())
-                    })
-                    .unwrap_or_else(|| format!("MR !{iid}"));
-
-                // Count notes by counting "### @" headers in Notes section
-                let note_count = content
-                    .find("## Notes")
-                    .map(|start| content[start..].matches("### @").count())
-                    .unwrap_or(0);
-
-                contexts.push(LoadedGitLabMergeRequestContext {
-                    iid,
-                    title,
-                    note_count,
-                    project_path: repo_key,
-                });
-            }
-        }
+    let mut contexts = Vec::new();
+    for (context, fingerprint) in stored.into_iter().zip(fingerprints) {
+        let StoredGitLabContext {
+            key,
+            repo_key,
+            iid,
+            content,
+        } = context;
+
+        // Parse title from first line: "# GitLab Merge Request !123: Title"
+        let title = content
+            .lines()
+            .next()
+            .and_then(|line| {
+                line.strip_prefix("# GitLab Merge Request !")
+                    .and_then(|rest| rest.split_once(": "))
+                    .map(|(_, title)| title.to_string())
+            })
+            .unwrap_or_else(|| format!("MR !{iid}"));
+
+        // Count notes by counting "### @" headers in Notes section
+        let note_count = content
+            .find("## Notes")
+            .map(|start| content[start..].matches("### @").count())
+            .unwrap_or(0);
+
+        let stale = fingerprint.and_then(|fresh| is_fingerprint_stale(refs.prs.get(&key), &fresh));
+
+        contexts.push(LoadedGitLabMergeRequestContext {
+            iid,
+            title,
+            note_count,
+            project_path: repo_key,
+            stale,
+        });
     }
 
     // Sort by MR IID
@@ -926,11 +1184,12 @@ pub async fn get_gitlab_issue_context_content(
 ) -> Result<String, String> {
     // Get repo identifier
     let repo_id = get_gitlab_repo_identifier(&project_path)?;
+    migrate_host_less_contexts(&app, &repo_id, true);
     let repo_key = repo_id.to_key();
 
     // Verify this worktree has a reference to this context
     let refs = get_worktree_gitlab_issue_refs(&app, &worktree_id)?;
-    let expected_key = format!("{repo_key}-{issue_iid}");
+    let expected_key = format!("{repo_key}#{issue_iid}");
     if !refs.contains(&expected_key) {
         return Err(format!(
             "Worktree does not have GitLab issue !{issue_iid} loaded"
@@ -960,11 +1219,12 @@ pub async fn get_gitlab_mr_context_content(
 ) -> Result<String, String> {
     // Get repo identifier
     let repo_id = get_gitlab_repo_identifier(&project_path)?;
+    migrate_host_less_contexts(&app, &repo_id, true);
     let repo_key = repo_id.to_key();
 
     // Verify this worktree has a reference to this context
     let refs = get_worktree_gitlab_mr_refs(&app, &worktree_id)?;
-    let expected_key = format!("{repo_key}-{mr_iid}");
+    let expected_key = format!("{repo_key}#{mr_iid}");
     if !refs.contains(&expected_key) {
         return Err(format!(
             "Worktree does not have GitLab MR !{mr_iid} loaded"
@@ -1007,6 +1267,77 @@ mod tests {
         );
     }
 
+    #[test]
+    fn test_parse_gitlab_context_key() {
+        assert_eq!(
+            parse_gitlab_context_key("foo-2024#5"),
+            Some(("foo-2024".to_string(), 5))
+        );
+        assert_eq!(
+            parse_gitlab_context_key("team-42-backend#7"),
+            Some(("team-42-backend".to_string(), 7))
+        );
+        // Legacy format still parses
+        assert_eq!(
+            parse_gitlab_context_key("group-project-12"),
+            Some(("group-project".to_string(), 12))
+        );
+        assert_eq!(parse_gitlab_context_key("foo-2024#abc"), None);
+        assert_eq!(parse_gitlab_context_key("#5"), None);
+        assert_eq!(parse_gitlab_context_key("single"), None);
+    }
+
+    #[test]
+    fn test_gitlab_reference_key_roundtrip() {
+        let key = gitlab_reference_key("foo-2024", 5);
+        assert_eq!(key, "gitlab-foo-2024#5");
+        assert_eq!(
+            key.strip_prefix("gitlab-")
+                .and_then(parse_gitlab_context_key),
+            Some(("foo-2024".to_string(), 5))
+        );
+    }
+
+    #[test]
+    fn test_migrate_legacy_gitlab_keys() {
+        use super::super::github_issues::ContextRef;
+
+        let mut refs = ContextReferences::default();
+        refs.issues.insert(
+            "gitlab-foo-2024-5".to_string(),
+            ContextRef {
+                worktrees: vec!["wt-1".to_string()],
+                orphaned_at: None,
+                ..Default::default()
+            },
+        );
+        refs.issues.insert(
+            "gitlab-foo-2024#5".to_string(),
+            ContextRef {
+                worktrees: vec!["wt-2".to_string()],
+                orphaned_at: None,
+                ..Default::default()
+            },
+        );
+        refs.issues.insert(
+            "owner-repo-3".to_string(),
+            ContextRef {
+                worktrees: vec!["wt-1".to_string()],
+                orphaned_at: None,
+                ..Default::default()
+            },
+        );
+
+        assert!(migrate_legacy_gitlab_keys(&mut refs));
+        assert!(!refs.issues.contains_key("gitlab-foo-2024-5"));
+        assert!(refs.issues.contains_key("owner-repo-3"));
+        let merged = &refs.issues["gitlab-foo-2024#5"];
+        assert_eq!(merged.worktrees, vec!["wt-2", "wt-1"]);
+
+        // Second pass is a no-op
+        assert!(!migrate_legacy_gitlab_keys(&mut refs));
+    }
+
     #[test]
     fn test_generate_branch_name_from_gitlab_mr() {
         assert_eq!(
@@ -1014,4 +1345,22 @@ mod tests {
             "mr-456-fix-authentication"
         );
     }
+
+    #[test]
+    fn test_parse_gitlab_fingerprint() {
+        let mr = r#"{"iid": 7, "updated_at": "2026-01-02T00:00:00Z", "user_notes_count": 3, "sha": "abc123"}"#;
+        assert_eq!(
+            parse_gitlab_fingerprint(mr).unwrap(),
+            "2026-01-02T00:00:00Z|abc123|3"
+        );
+
+        // Issues have no head commit
+        let issue = r#"{"iid": 7, "updated_at": "2026-01-02T00:00:00Z", "user_notes_count": 0}"#;
+        assert_eq!(
+            parse_gitlab_fingerprint(issue).unwrap(),
+            "2026-01-02T00:00:00Z||0"
+        );
+
+        assert!(parse_gitlab_fingerprint("{}").is_err());
+    }
 }
@@ -1,8 +1,19 @@
+use std::collections::HashMap;
 use std::process::Command;
+use std::sync::Mutex;
 use std::time::{SystemTime, UNIX_EPOCH};
 
+use once_cell::sync::Lazy;
 use serde::{Deserialize, Serialize};
 
+/// Below this many remaining REST requests (or GraphQL points), polling serves
+/// cached statuses
+const RATE_LIMIT_LOW_WATERMARK: u64 = 100;
+
+/// Cached statuses older than this are refetched even when the ETags match,
+/// since review decisions and legacy commit statuses aren't covered by them
+const MAX_CACHE_AGE_SECS: u64 = 300;
+
 /// PR state from GitHub API
 #[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
 #[serde(rename_all = "snake_case")]
@@ -60,6 +71,7 @@ struct GhPrViewResponse {
     review_decision: Option<String>,
     status_check_rollup: Option<Vec<StatusCheck>>,
     mergeable: Option<String>,
+    head_ref_oid: Option<String>,
 }
 
 #[derive(Debug, Clone, Deserialize)]
@@ -68,6 +80,84 @@ struct StatusCheck {
     status: Option<String>,
 }
 
+/// Subset of the REST pull request response needed to probe check runs
+#[derive(Debug, Clone, Deserialize)]
+struct GhPullResponse {
+    head: GhPullHead,
+}
+
+#[derive(Debug, Clone, Deserialize)]
+struct GhPullHead {
+    sha: String,
+}
+
+/// `gh api rate_limit` response, of which only the GraphQL budget is used
+#[derive(Debug, Clone, Deserialize)]
+struct GhRateLimitResponse {
+    resources: GhRateLimitResources,
+}
+
+#[derive(Debug, Clone, Deserialize)]
+struct GhRateLimitResources {
+    graphql: GhRateLimitResource,
+}
+
+#[derive(Debug, Clone, Deserialize)]
+struct GhRateLimitResource {
+    limit: u64,
+    remaining: u64,
+    reset: u64,
+}
+
+/// GitHub REST rate limit budget, as reported by the last API response
+#[derive(Debug, Clone, Serialize)]
+pub struct RateLimitBudget {
+    pub limit: u64,
+    pub remaining: u64,
+    /// Unix timestamp when the budget resets
+    pub reset_at: u64,
+    /// Whether PR polling is backing off to cached statuses
+    pub low: bool,
+    pub checked_at: u64,
+}
+
+/// Last known status of a PR along with the ETags it was fetched under
+#[derive(Debug, Clone)]
+struct CachedPrStatus {
+    pr_etag: Option<String>,
+    checks_etag: Option<String>,
+    head_sha: Option<String>,
+    status: PrStatus,
+    fetched_at: u64,
+}
+
+/// Result of the conditional requests made before a full fetch
+#[derive(Debug, Default)]
+struct ChangeProbe {
+    pr_etag: Option<String>,
+    checks_etag: Option<String>,
+    head_sha: Option<String>,
+    unchanged: bool,
+}
+
+/// Parsed `gh api --include` output
+#[derive(Debug)]
+struct ApiResponse {
+    status: u16,
+    etag: Option<String>,
+    rate_limit: Option<(u64, u64, u64)>,
+    body: String,
+}
+
+/// PR statuses keyed by (repo path, PR number)
+static PR_STATUS_CACHE: Lazy<Mutex<HashMap<(String, u32), CachedPrStatus>>> =
+    Lazy::new(|| Mutex::new(HashMap::new()));
+
+static RATE_LIMIT: Lazy<Mutex<Option<RateLimitBudget>>> = Lazy::new(|| Mutex::new(None));
+
+/// GraphQL budget, which `gh pr view` spends, from the last `gh api rate_limit`
+static GRAPHQL_RATE_LIMIT: Lazy<Mutex<Option<RateLimitBudget>>> = Lazy::new(|| Mutex::new(None));
+
 /// Processed PR status for frontend consumption
 #[derive(Debug, Clone, Serialize)]
 pub struct PrStatus {
@@ -83,13 +173,281 @@ pub struct PrStatus {
     pub checked_at: u64,
 }
 
-/// Fetch PR status using gh CLI
+fn now_secs() -> u64 {
+    SystemTime::now()
+        .duration_since(UNIX_EPOCH)
+        .map(|d| d.as_secs())
+        .unwrap_or(0)
+}
+
+/// Get PR status, reusing the cached status when GitHub reports no changes
+///
+/// The pull request and its head commit's check runs are probed with
+/// `If-None-Match`; 304 responses don't count against the rate limit. While the
+/// remaining REST or GraphQL budget is low, the cached status is returned
+/// without probing or fetching.
 pub fn get_pr_status(
     repo_path: &str,
     pr_number: u32,
     pr_url: &str,
     worktree_id: &str,
 ) -> Result<PrStatus, String> {
+    let key = (repo_path.to_string(), pr_number);
+    let now = now_secs();
+    let cached = PR_STATUS_CACHE.lock().unwrap().get(&key).cloned();
+
+    if let Some(cached) = &cached {
+        if is_rate_limited(RATE_LIMIT.lock().unwrap().as_ref(), now)
+            || is_rate_limited(GRAPHQL_RATE_LIMIT.lock().unwrap().as_ref(), now)
+        {
+            log::debug!("GitHub rate limit low, using cached status for PR #{pr_number}");
+            return Ok(restamp(&cached.status, pr_url, worktree_id, now));
+        }
+    }
+
+    let fresh = cached
+        .clone()
+        .filter(|c| now.saturating_sub(c.fetched_at) < MAX_CACHE_AGE_SECS);
+    let probe = probe_pr_changes(repo_path, pr_number, fresh.as_ref()).unwrap_or_else(|e| {
+        log::debug!("PR #{pr_number} change probe failed, doing a full fetch: {e}");
+        ChangeProbe::default()
+    });
+
+    if probe.unchanged {
+        if let Some(fresh) = fresh {
+            log::trace!("PR #{pr_number} unchanged (304), using cached status");
+            return Ok(restamp(&fresh.status, pr_url, worktree_id, now));
+        }
+    }
+
+    // `gh pr view` spends GraphQL points, which the REST headers above don't cover
+    if let Some(cached) = &cached {
+        match check_graphql_budget(repo_path, now) {
+            Ok(budget) if is_rate_limited(Some(&budget), now) => {
+                log::debug!(
+                    "GitHub GraphQL rate limit low, using cached status for PR #{pr_number}"
+                );
+                return Ok(restamp(&cached.status, pr_url, worktree_id, now));
+            }
+            Ok(_) => {}
+            Err(e) => log::debug!("Failed to check GitHub GraphQL rate limit: {e}"),
+        }
+    }
+
+    let (status, head_sha) = fetch_pr_status(repo_path, pr_number, pr_url, worktree_id)?;
+    PR_STATUS_CACHE.lock().unwrap().insert(
+        key,
+        CachedPrStatus {
+            pr_etag: probe.pr_etag,
+            checks_etag: probe.checks_etag,
+            head_sha: probe.head_sha.or(head_sha),
+            status: status.clone(),
+            fetched_at: now,
+        },
+    );
+    Ok(status)
+}
+
+/// Get the last known GitHub rate limit budget
+///
+/// This is the REST budget, unless the GraphQL budget is the one running low.
+pub fn get_rate_limit_budget() -> Option<RateLimitBudget> {
+    let now = now_secs();
+    let rest = RATE_LIMIT.lock().unwrap().clone();
+    let graphql = GRAPHQL_RATE_LIMIT.lock().unwrap().clone();
+    let budget = match (rest, graphql) {
+        (_, Some(graphql)) if is_rate_limited(Some(&graphql), now) => graphql,
+        (Some(rest), _) => rest,
+        (None, graphql) => graphql?,
+    };
+    let low = is_rate_limited(Some(&budget), now);
+    Some(RateLimitBudget { low, ..budget })
+}
+
+/// Fetch and record the GraphQL budget (`gh api rate_limit` doesn't spend any)
+fn check_graphql_budget(repo_path: &str, now: u64) -> Result<RateLimitBudget, String> {
+    let output = Command::new("gh")
+        .args(["api", "rate_limit"])
+        .current_dir(repo_path)
+        .output()
+        .map_err(|e| format!("Failed to run gh api rate_limit: {e}"))?;
+    if !output.status.success() {
+        let stderr = String::from_utf8_lossy(&output.stderr);
+        return Err(format!("gh api rate_limit failed: {stderr}"));
+    }
+
+    let budget = parse_graphql_budget(&String::from_utf8_lossy(&output.stdout), now)?;
+    *GRAPHQL_RATE_LIMIT.lock().unwrap() = Some(budget.clone());
+    Ok(budget)
+}
+
+/// Read `resources.graphql` from a `gh api rate_limit` response
+fn parse_graphql_budget(body: &str, now: u64) -> Result<RateLimitBudget, String> {
+    let response: GhRateLimitResponse = serde_json::from_str(body)
+        .map_err(|e| format!("Failed to parse gh api rate_limit response: {e}"))?;
+    let graphql = response.resources.graphql;
+    Ok(RateLimitBudget {
+        limit: graphql.limit,
+        remaining: graphql.remaining,
+        reset_at: graphql.reset,
+        low: false,
+        checked_at: now,
+    })
+}
+
+fn is_rate_limited(budget: Option<&RateLimitBudget>, now: u64) -> bool {
+    budget.is_some_and(|b| b.remaining < RATE_LIMIT_LOW_WATERMARK && now < b.reset_at)
+}
+
+/// Copy a cached status for the current worktree, marked as checked now
+fn restamp(status: &PrStatus, pr_url: &str, worktree_id: &str, now: u64) -> PrStatus {
+    PrStatus {
+        worktree_id: worktree_id.to_string(),
+        pr_url: pr_url.to_string(),
+        checked_at: now,
+        ..status.clone()
+    }
+}
+
+/// Probe the PR and its head commit's check runs with conditional requests
+fn probe_pr_changes(
+    repo_path: &str,
+    pr_number: u32,
+    cached: Option<&CachedPrStatus>,
+) -> Result<ChangeProbe, String> {
+    let pr = conditional_get(
+        repo_path,
+        &format!("repos/{{owner}}/{{repo}}/pulls/{pr_number}"),
+        cached.and_then(|c| c.pr_etag.as_deref()),
+    )?;
+    let pr_unchanged = pr.status == 304;
+    let pr_etag = pr.etag.or_else(|| {
+        cached
+            .filter(|_| pr_unchanged)
+            .and_then(|c| c.pr_etag.clone())
+    });
+
+    let head_sha = if pr_unchanged {
+        cached.and_then(|c| c.head_sha.clone())
+    } else {
+        serde_json::from_str::<GhPullResponse>(&pr.body)
+            .ok()
+            .map(|p| p.head.sha)
+    };
+    let Some(head_sha) = head_sha else {
+        return Ok(ChangeProbe {
+            pr_etag,
+            ..Default::default()
+        });
+    };
+
+    // The checks ETag only applies while the head commit is unchanged
+    let checks_etag = cached
+        .filter(|c| c.head_sha.as_deref() == Some(head_sha.as_str()))
+        .and_then(|c| c.checks_etag.as_deref());
+    let checks = conditional_get(
+        repo_path,
+        &format!("repos/{{owner}}/{{repo}}/commits/{head_sha}/check-runs"),
+        checks_etag,
+    )?;
+    let checks_unchanged = checks.status == 304;
+
+    Ok(ChangeProbe {
+        pr_etag,
+        checks_etag: checks
+            .etag
+            .or_else(|| checks_etag.filter(|_| checks_unchanged).map(String::from)),
+        head_sha: Some(head_sha),
+        unchanged: pr_unchanged && checks_unchanged,
+    })
+}
+
+/// Run `gh api --include`, sending `If-None-Match` when an ETag is known
+fn conditional_get(
+    repo_path: &str,
+    endpoint: &str,
+    etag: Option<&str>,
+) -> Result<ApiResponse, String> {
+    let mut cmd = Command::new("gh");
+    cmd.args(["api", "--include", endpoint])
+        .current_dir(repo_path);
+    if let Some(etag) = etag {
+        cmd.args(["-H", &format!("If-None-Match: {etag}")]);
+    }
+    let output = cmd
+        .output()
+        .map_err(|e| format!("Failed to run gh api: {e}"))?;
+
+    // gh exits non-zero on 304, so parse the response regardless of exit status
+    let stdout = String::from_utf8_lossy(&output.stdout);
+    let Some(response) = parse_api_response(&stdout) else {
+        let stderr = String::from_utf8_lossy(&output.stderr);
+        return Err(format!("gh api {endpoint} failed: {stderr}"));
+    };
+
+    if let Some((limit, remaining, reset_at)) = response.rate_limit {
+        *RATE_LIMIT.lock().unwrap() = Some(RateLimitBudget {
+            limit,
+            remaining,
+            reset_at,
+            low: false,
+            checked_at: now_secs(),
+        });
+    }
+
+    match response.status {
+        200 | 304 => Ok(response),
+        status => Err(format!("gh api {endpoint} returned HTTP {status}")),
+    }
+}
+
+/// Parse the status line, ETag, rate limit headers and body of an HTTP response
+fn parse_api_response(raw: &str) -> Option<ApiResponse> {
+    let raw = raw.replace("\r\n", "\n");
+    let (head, body) = raw.split_once("\n\n").unwrap_or((raw.as_str(), ""));
+    let mut lines = head.lines();
+    let status = lines
+        .next()?
+        .strip_prefix("HTTP/")?
+        .split_whitespace()
+        .nth(1)?
+        .parse()
+        .ok()?;
+
+    let mut headers = HashMap::new();
+    for line in lines {
+        if let Some((name, value)) = line.split_once(':') {
+            headers.insert(name.trim().to_lowercase(), value.trim().to_string());
+        }
+    }
+    let number = |name: &str| headers.get(name).and_then(|v| v.parse::<u64>().ok());
+    let rate_limit = match (
+        number("x-ratelimit-limit"),
+        number("x-ratelimit-remaining"),
+        number("x-ratelimit-reset"),
+    ) {
+        (Some(limit), Some(remaining), Some(reset)) => Some((limit, remaining, reset)),
+        _ => None,
+    };
+
+    Some(ApiResponse {
+        status,
+        etag: headers.get("etag").cloned(),
+        rate_limit,
+        body: body.to_string(),
+    })
+}
+
+/// Fetch PR status using gh CLI
+///
+/// Also returns the head commit SHA, fetched in the same call, for the
+/// next check-runs probe.
+fn fetch_pr_status(
+    repo_path: &str,
+    pr_number: u32,
+    pr_url: &str,
+    worktree_id: &str,
+) -> Result<(PrStatus, Option<String>), String> {
     log::trace!("Fetching PR status for #{pr_number} in {repo_path}");
 
     // Run gh pr view
@@ -99,7 +457,7 @@ pub fn get_pr_status(
             "view",
             &pr_number.to_string(),
             "--json",
-            "state,isDraft,reviewDecision,statusCheckRollup,mergeable",
+            "state,isDraft,reviewDecision,statusCheckRollup,mergeable,headRefOid",
         ])
         .current_dir(repo_path)
         .output()
@@ -134,12 +492,9 @@ pub fn get_pr_status(
         .as_ref()
         .and_then(|s| parse_mergeable_status(s));
 
-    let checked_at = SystemTime::now()
-        .duration_since(UNIX_EPOCH)
-        .map(|d| d.as_secs())
-        .unwrap_or(0);
+    let checked_at = now_secs();
 
-    Ok(PrStatus {
+    let status = PrStatus {
         worktree_id: worktree_id.to_string(),
         pr_number,
         pr_url: pr_url.to_string(),
@@ -150,7 +505,8 @@ pub fn get_pr_status(
         display_status,
         mergeable,
         checked_at,
-    })
+    };
+    Ok((status, response.head_ref_oid))
 }
 
 fn parse_pr_state(s: &str) -> PrState {
@@ -308,4 +664,54 @@ mod tests {
         );
         assert_eq!(parse_mergeable_status("other"), None);
     }
+
+    #[test]
+    fn test_parse_api_response() {
+        let raw = "HTTP/2.0 304 Not Modified\r\nEtag: W/\"abc123\"\r\nX-Ratelimit-Limit: 5000\r\nX-Ratelimit-Remaining: 42\r\nX-Ratelimit-Reset: 1700000000\r\n\r\n";
+        let response = parse_api_response(raw).unwrap();
+        assert_eq!(response.status, 304);
+        assert_eq!(response.etag.as_deref(), Some("W/\"abc123\""));
+        assert_eq!(response.rate_limit, Some((5000, 42, 1700000000)));
+        assert!(response.body.is_empty());
+
+        let raw =
+            "HTTP/2.0 200 OK\nContent-Type: application/json\n\n{\"head\":{\"sha\":\"deadbeef\"}}";
+        let response = parse_api_response(raw).unwrap();
+        assert_eq!(response.status, 200);
+        assert_eq!(response.etag, None);
+        assert_eq!(response.rate_limit, None);
+        let pull: GhPullResponse = serde_json::from_str(&response.body).unwrap();
+        assert_eq!(pull.head.sha, "deadbeef");
+
+        assert!(parse_api_response("gh: Not Found").is_none());
+    }
+
+    #[test]
+    fn test_parse_graphql_budget() {
+        let body = r#"{"resources":{"core":{"limit":5000,"used":1,"remaining":4999,"reset":1700000100},"graphql":{"limit":5000,"used":4950,"remaining":50,"reset":1700000200}},"rate":{}}"#;
+        let budget = parse_graphql_budget(body, 1700000000).unwrap();
+        assert_eq!(budget.limit, 5000);
+        assert_eq!(budget.remaining, 50);
+        assert_eq!(budget.reset_at, 1700000200);
+        assert_eq!(budget.checked_at, 1700000000);
+        assert!(is_rate_limited(Some(&budget), 1700000000));
+
+        assert!(parse_graphql_budget("{\"message\":\"Bad credentials\"}", 0).is_err());
+    }
+
+    #[test]
+    fn test_is_rate_limited() {
+        let budget = |remaining| RateLimitBudget {
+            limit: 5000,
+            remaining,
+            reset_at: 2000,
+            low: false,
+            checked_at: 1000,
+        };
+        assert!(!is_rate_limited(None, 1000));
+        assert!(!is_rate_limited(Some(&budget(4000)), 1000));
+        assert!(is_rate_limited(Some(&budget(10)), 1000));
+        // Budget has reset since it was recorded
+        assert!(!is_rate_limited(Some(&budget(10)), 2000));
+    }
 }
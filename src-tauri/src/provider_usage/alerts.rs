@@ -0,0 +1,254 @@
+//! Budget alerts for provider usage
+//!
+//! Thresholds are stored in `{app_data}/usage-alerts.json`. Each time usage is
+//! polled, every rate window is compared against its provider's threshold and a
+//! `usage:alert` event is emitted the first time it crosses within a reset
+//! period. The alert re-arms once the window's `resets_at` changes.
+
+use once_cell::sync::Lazy;
+use std::collections::HashMap;
+use std::fs;
+use std::path::PathBuf;
+use std::sync::Mutex;
+use tauri::{AppHandle, Emitter, Manager};
+
+use super::types::{ProviderUsageSnapshot, RateWindow, UsageAlert, UsageAlertConfig};
+
+/// Fired alerts: "{provider_id}:{window}" -> resets_at of the period it fired in.
+/// Kept in memory, so an alert may fire once more after an app restart.
+static FIRED_ALERTS: Lazy<Mutex<HashMap<String, Option<String>>>> =
+    Lazy::new(|| Mutex::new(HashMap::new()));
+
+/// Get the path to the usage alerts config file
+fn get_alerts_path(app: &AppHandle) -> Result<PathBuf, String> {
+    let app_data_dir = app
+        .path()
+        .app_data_dir()
+        .map_err(|e| format!("Failed to get app data directory: {e}"))?;
+    Ok(app_data_dir.join("usage-alerts.json"))
+}
+
+/// Load alert thresholds from disk (defaults to none configured)
+pub fn load_alert_config(app: &AppHandle) -> Result<UsageAlertConfig, String> {
+    let path = get_alerts_path(app)?;
+    if !path.exists() {
+        return Ok(UsageAlertConfig::default());
+    }
+    let content =
+        fs::read_to_string(&path).map_err(|e| format!("Failed to read usage alerts: {e}"))?;
+    serde_json::from_str(&content).map_err(|e| format!("Failed to parse usage alerts: {e}"))
+}
+
+/// Save alert thresholds to disk (atomic write: temp file + rename)
+fn save_alert_config(app: &AppHandle, config: &UsageAlertConfig) -> Result<(), String> {
+    let path = get_alerts_path(app)?;
+    if let Some(parent) = path.parent() {
+        fs::create_dir_all(parent)
+            .map_err(|e| format!("Failed to create app data directory: {e}"))?;
+    }
+
+    let content = serde_json::to_string_pretty(config)
+        .map_err(|e| format!("Failed to serialize usage alerts: {e}"))?;
+    let temp_path = path.with_extension("tmp");
+    fs::write(&temp_path, content).map_err(|e| format!("Failed to write usage alerts: {e}"))?;
+    fs::rename(&temp_path, &path).map_err(|e| format!("Failed to finalize usage alerts: {e}"))
+}
+
+/// Check a snapshot's windows against a threshold
+///
+/// `fired` tracks which windows already alerted in their current reset period.
+/// A window alerts again once its `resets_at` changes, or (when the reset time
+/// is unknown or only estimated) once usage drops back below the threshold.
+fn check_windows(
+    snapshot: &ProviderUsageSnapshot,
+    threshold_percent: f64,
+    fired: &mut HashMap<String, Option<String>>,
+) -> Vec<UsageAlert> {
+    let windows: [(&str, Option<&RateWindow>); 2] = [
+        ("primary", snapshot.primary.as_ref()),
+        ("secondary", snapshot.secondary.as_ref()),
+    ];
+
+    let mut alerts = Vec::new();
+    for (name, window) in windows {
+        let Some(window) = window else {
+            continue;
+        };
+        let key = format!("{}:{name}", snapshot.provider_id);
+        // Estimated resets move with every poll, so they can't identify a period
+        let period = window.resets_at.clone().filter(|_| !window.reset_estimated);
+
+        if window.used_percent < threshold_percent {
+            if period.is_none() {
+                fired.remove(&key);
+            }
+            continue;
+        }
+
+        if fired.get(&key) == Some(&period) {
+            continue;
+        }
+
+        fired.insert(key, period);
+        alerts.push(UsageAlert {
+            provider_id: snapshot.provider_id.clone(),
+            window: name.to_string(),
+            used_percent: window.used_percent,
+            threshold_percent,
+            resets_at: window.resets_at.clone(),
+        });
+    }
+
+    alerts
+}
+
+/// Emit `usage:alert` for any window of the snapshot that crossed its threshold
+pub fn check_usage_alerts(app: &AppHandle, snapshot: &ProviderUsageSnapshot) {
+    if !snapshot.available {
+        return;
+    }
+
+    let config = match load_alert_config(app) {
+        Ok(config) => config,
+        Err(e) => {
+            log::warn!("Failed to load usage alerts: {e}");
+            return;
+        }
+    };
+    let Some(&threshold) = config.thresholds.get(&snapshot.provider_id) else {
+        return;
+    };
+
+    let alerts = {
+        let mut fired = FIRED_ALERTS.lock().unwrap();
+        check_windows(snapshot, threshold, &mut fired)
+    };
+
+    for alert in alerts {
+        log::debug!(
+            "Usage alert: {} {} window at {:.1}% (threshold {:.1}%)",
+            alert.provider_id,
+            alert.window,
+            alert.used_percent,
+            alert.threshold_percent
+        );
+        if let Err(e) = app.emit("usage:alert", &alert) {
+            log::warn!("Failed to emit usage:alert event: {e}");
+        }
+    }
+}
+
+/// Set the budget alert threshold for a provider
+///
+/// A threshold of 0 removes the alert for that provider.
+#[tauri::command]
+pub async fn set_usage_alert(
+    app: AppHandle,
+    provider_id: String,
+    threshold_percent: f64,
+) -> Result<(), String> {
+    if !(0.0..=100.0).contains(&threshold_percent) {
+        return Err(format!(
+            "Threshold must be between 0 and 100, got {threshold_percent}"
+        ));
+    }
+
+    let mut config = load_alert_config(&app)?;
+    if threshold_percent == 0.0 {
+        config.thresholds.remove(&provider_id);
+    } else {
+        config
+            .thresholds
+            .insert(provider_id.clone(), threshold_percent);
+    }
+    save_alert_config(&app, &config)?;
+
+    // Re-arm alerts for this provider so the new threshold applies immediately
+    FIRED_ALERTS
+        .lock()
+        .unwrap()
+        .retain(|key, _| !key.starts_with(&format!("{provider_id}:")));
+
+    log::trace!("Set usage alert for {provider_id} at {threshold_percent}%");
+    Ok(())
+}
+
+/// Get all configured usage alert thresholds
+#[tauri::command]
+pub async fn get_usage_alerts(app: AppHandle) -> Result<UsageAlertConfig, String> {
+    load_alert_config(&app)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn snapshot(used: f64, resets_at: Option<&str>) -> ProviderUsageSnapshot {
+        ProviderUsageSnapshot {
+            provider_id: "claude".to_string(),
+            primary: Some(RateWindow {
+                used_percent: used,
+                resets_at: resets_at.map(|s| s.to_string()),
+                ..Default::default()
+            }),
+            available: true,
+            ..Default::default()
+        }
+    }
+
+    #[test]
+    fn test_alert_fires_once_per_reset_period() {
+        let mut fired = HashMap::new();
+        let period_1 = Some("2026-01-01T05:00:00Z");
+
+        assert!(check_windows(&snapshot(50.0, period_1), 80.0, &mut fired).is_empty());
+
+        let alerts = check_windows(&snapshot(85.0, period_1), 80.0, &mut fired);
+        assert_eq!(alerts.len(), 1);
+        assert_eq!(alerts[0].window, "primary");
+        assert_eq!(alerts[0].used_percent, 85.0);
+
+        // Still above threshold in the same period: debounced
+        assert!(check_windows(&snapshot(90.0, period_1), 80.0, &mut fired).is_empty());
+
+        // Window reset: alert re-arms and fires again on the next crossing
+        let period_2 = Some("2026-01-01T10:00:00Z");
+        assert!(check_windows(&snapshot(10.0, period_2), 80.0, &mut fired).is_empty());
+        assert_eq!(
+            check_windows(&snapshot(81.0, period_2), 80.0, &mut fired).len(),
+            1
+        );
+    }
+
+    #[test]
+    fn test_alert_rearms_below_threshold_without_reset_time() {
+        let mut fired = HashMap::new();
+
+        assert_eq!(
+            check_windows(&snapshot(95.0, None), 90.0, &mut fired).len(),
+            1
+        );
+        assert!(check_windows(&snapshot(96.0, None), 90.0, &mut fired).is_empty());
+        assert!(check_windows(&snapshot(5.0, None), 90.0, &mut fired).is_empty());
+        assert_eq!(
+            check_windows(&snapshot(91.0, None), 90.0, &mut fired).len(),
+            1
+        );
+    }
+
+    #[test]
+    fn test_estimated_reset_does_not_rearm_alert() {
+        let mut fired = HashMap::new();
+        let estimated = |used: f64, resets_at: &str| {
+            let mut snapshot = snapshot(used, Some(resets_at));
+            snapshot.primary.as_mut().unwrap().reset_estimated = true;
+            snapshot
+        };
+
+        let first = estimated(95.0, "2026-01-01T05:00:00Z");
+        assert_eq!(check_windows(&first, 90.0, &mut fired).len(), 1);
+        // The estimate moved, but it's the same period
+        let second = estimated(96.0, "2026-01-01T05:01:00Z");
+        assert!(check_windows(&second, 90.0, &mut fired).is_empty());
+    }
+}
@@ -0,0 +1,207 @@
+//! Anthropic API-key usage fetcher
+//!
+//! For teams using a raw Anthropic API key (not Claude OAuth). The key is read
+//! from `ANTHROPIC_API_KEY`, falling back to `~/.jean/anthropic-api.json`
+//! (`{ "apiKey": "sk-ant-..." }`). Usage comes from the `anthropic-ratelimit-*`
+//! response headers: requests map to the primary window, tokens to the secondary.
+
+use chrono::Utc;
+use reqwest::header::HeaderMap;
+use serde::Deserialize;
+use std::fs;
+use std::path::PathBuf;
+use std::time::Duration;
+
+use super::types::{ProviderUsageSnapshot, RateWindow};
+
+/// Lightweight endpoint used to read rate-limit headers
+const MODELS_API_URL: &str = "https://api.anthropic.com/v1/models?limit=1";
+
+/// API version header required by the Anthropic API
+const ANTHROPIC_VERSION: &str = "2023-06-01";
+
+/// Provider ID used in snapshots
+const PROVIDER_ID: &str = "anthropic-api";
+
+/// Config file structure from ~/.jean/anthropic-api.json
+#[derive(Debug, Deserialize)]
+#[serde(rename_all = "camelCase")]
+struct AnthropicApiConfig {
+    api_key: Option<String>,
+}
+
+/// Get the path to the API key config file
+fn get_config_path() -> Option<PathBuf> {
+    let home = dirs::home_dir()?;
+    Some(home.join(".jean").join("anthropic-api.json"))
+}
+
+/// Read the API key from the environment or the config file
+fn get_api_key() -> Option<String> {
+    if let Ok(key) = std::env::var("ANTHROPIC_API_KEY") {
+        if !key.trim().is_empty() {
+            return Some(key.trim().to_string());
+        }
+    }
+
+    let content = fs::read_to_string(get_config_path()?).ok()?;
+    let config: AnthropicApiConfig = serde_json::from_str(&content).ok()?;
+    config
+        .api_key
+        .map(|k| k.trim().to_string())
+        .filter(|k| !k.is_empty())
+}
+
+/// Fetch Anthropic API usage data
+pub async fn fetch_anthropic_api_usage() -> ProviderUsageSnapshot {
+    let now = Utc::now();
+
+    let Some(api_key) = get_api_key() else {
+        return ProviderUsageSnapshot {
+            provider_id: PROVIDER_ID.to_string(),
+            available: false,
+            error: Some(
+                "No Anthropic API key. Set ANTHROPIC_API_KEY or add apiKey to ~/.jean/anthropic-api.json"
+                    .to_string(),
+            ),
+            updated_at: now.to_rfc3339(),
+            ..Default::default()
+        };
+    };
+
+    match fetch_rate_limits(&api_key).await {
+        Ok((None, None)) => ProviderUsageSnapshot {
+            provider_id: PROVIDER_ID.to_string(),
+            available: false,
+            error: Some("No rate limit headers in API response".to_string()),
+            plan_type: Some("api".to_string()),
+            updated_at: now.to_rfc3339(),
+            ..Default::default()
+        },
+        Ok((primary, secondary)) => ProviderUsageSnapshot {
+            provider_id: PROVIDER_ID.to_string(),
+            available: true,
+            error: None,
+            primary,
+            secondary,
+            plan_type: Some("api".to_string()),
+            updated_at: now.to_rfc3339(),
+            ..Default::default()
+        },
+        Err(e) => ProviderUsageSnapshot {
+            provider_id: PROVIDER_ID.to_string(),
+            available: false,
+            error: Some(e),
+            updated_at: now.to_rfc3339(),
+            ..Default::default()
+        },
+    }
+}
+
+async fn fetch_rate_limits(
+    api_key: &str,
+) -> Result<(Option<RateWindow>, Option<RateWindow>), String> {
+    let client = reqwest::Client::builder()
+        .timeout(Duration::from_secs(10))
+        .build()
+        .map_err(|e| format!("Failed to create HTTP client: {e}"))?;
+
+    let response = client
+        .get(MODELS_API_URL)
+        .header("x-api-key", api_key)
+        .header("anthropic-version", ANTHROPIC_VERSION)
+        .send()
+        .await
+        .map_err(|e| format!("Failed to reach Anthropic API: {e}"))?;
+
+    let status = response.status();
+    if status.as_u16() == 401 {
+        return Err("Anthropic API key is invalid".to_string());
+    }
+    // 429 still carries rate-limit headers, which is exactly what we want
+    if !status.is_success() && status.as_u16() != 429 {
+        let body = response
+            .text()
+            .await
+            .unwrap_or_else(|_| "Unknown error".to_string());
+        return Err(format!("API error {status}: {body}"));
+    }
+
+    let headers = response.headers();
+    Ok((
+        window_from_headers(headers, "requests"),
+        window_from_headers(headers, "tokens"),
+    ))
+}
+
+/// Build a rate window from `anthropic-ratelimit-{kind}-{limit,remaining,reset}` headers
+fn window_from_headers(headers: &HeaderMap, kind: &str) -> Option<RateWindow> {
+    let header = |suffix: &str| {
+        headers
+            .get(format!("anthropic-ratelimit-{kind}-{suffix}"))
+            .and_then(|v| v.to_str().ok())
+            .map(|s| s.to_string())
+    };
+
+    let limit: f64 = header("limit")?.parse().ok()?;
+    let remaining: f64 = header("remaining")?.parse().ok()?;
+    if limit <= 0.0 {
+        return None;
+    }
+
+    let used_percent = (((limit - remaining) / limit) * 100.0).clamp(0.0, 100.0);
+    let resets_at = header("reset");
+
+    Some(RateWindow {
+        used_percent,
+        // Anthropic rate limits are per-minute
+        window_minutes: Some(1),
+        reset_description: resets_at.as_deref().map(format_reset_time),
+        resets_at,
+        reset_estimated: false,
+    })
+}
+
+fn format_reset_time(iso_string: &str) -> String {
+    match chrono::DateTime::parse_from_rfc3339(iso_string) {
+        Ok(reset_date) => {
+            let seconds = reset_date.signed_duration_since(Utc::now()).num_seconds();
+            if seconds <= 0 {
+                "Resets soon".to_string()
+            } else {
+                format!("Resets in {seconds}s")
+            }
+        }
+        Err(_) => "Unknown".to_string(),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use reqwest::header::HeaderValue;
+
+    #[test]
+    fn test_window_from_headers() {
+        let mut headers = HeaderMap::new();
+        headers.insert(
+            "anthropic-ratelimit-requests-limit",
+            HeaderValue::from_static("50"),
+        );
+        headers.insert(
+            "anthropic-ratelimit-requests-remaining",
+            HeaderValue::from_static("40"),
+        );
+        headers.insert(
+            "anthropic-ratelimit-requests-reset",
+            HeaderValue::from_static("2026-01-01T00:00:30Z"),
+        );
+
+        let window = window_from_headers(&headers, "requests").unwrap();
+        assert_eq!(window.used_percent, 20.0);
+        assert_eq!(window.resets_at.as_deref(), Some("2026-01-01T00:00:30Z"));
+
+        // Missing headers produce no window
+        assert!(window_from_headers(&headers, "tokens").is_none());
+    }
+}
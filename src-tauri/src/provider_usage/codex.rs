@@ -3,15 +3,27 @@
 //! Fetches usage data from OpenAI Codex CLI using RPC or session logs.
 //! The Codex CLI stores credentials in ~/.codex/auth.json
 
-use chrono::Utc;
+use chrono::{DateTime, Utc};
+use once_cell::sync::Lazy;
 use serde::Deserialize;
 use std::fs;
-use std::io::{BufRead, BufReader, Write};
-use std::process::{Command, Stdio};
-use std::time::Duration;
+use std::io::{BufRead, BufReader, Read, Write};
+use std::path::Path;
+use std::process::{Child, Command, Stdio};
+use std::sync::mpsc::{self, Receiver, RecvTimeoutError};
+use std::sync::Mutex;
+use std::time::{Duration, Instant};
 
 use super::types::{ProviderUsageSnapshot, RateWindow};
 
+/// Upper bound for a single JSON-RPC request to the Codex app-server.
+/// Each request also stops at the caller's deadline, whichever comes first.
+const RPC_TIMEOUT: Duration = Duration::from_secs(10);
+
+/// Long-lived Codex app-server, reused across usage polls.
+/// Restarted only when the process died or stopped answering.
+static APP_SERVER: Lazy<Mutex<Option<AppServer>>> = Lazy::new(|| Mutex::new(None));
+
 /// Auth file structure from ~/.codex/auth.json
 #[derive(Debug, Deserialize)]
 struct AuthFile {
@@ -24,12 +36,6 @@ struct Tokens {
     id_token: Option<String>,
 }
 
-/// RPC response for rate limits
-#[derive(Debug, Deserialize)]
-struct RpcRateLimitsResponse {
-    result: Option<RateLimitsResult>,
-}
-
 #[derive(Debug, Deserialize)]
 struct RateLimitsResult {
     #[serde(rename = "rateLimits")]
@@ -63,12 +69,6 @@ struct RpcCredits {
     balance: Option<String>,
 }
 
-/// RPC response for account info
-#[derive(Debug, Deserialize)]
-struct RpcAccountResponse {
-    result: Option<AccountResult>,
-}
-
 #[derive(Debug, Deserialize)]
 struct AccountResult {
     account: Option<AccountDetails>,
@@ -83,11 +83,11 @@ enum AccountDetails {
     ApiKey,
 }
 
-/// Fetch Codex usage data
-pub async fn fetch_codex_usage() -> ProviderUsageSnapshot {
+/// Fetch Codex usage data, giving up once `deadline` passes
+pub async fn fetch_codex_usage(deadline: Instant) -> ProviderUsageSnapshot {
     let now = Utc::now();
 
-    match fetch_codex_usage_inner().await {
+    match fetch_codex_usage_inner(deadline).await {
         Ok(snapshot) => snapshot,
         Err(e) => ProviderUsageSnapshot {
             provider_id: "codex".to_string(),
@@ -99,11 +99,11 @@ pub async fn fetch_codex_usage() -> ProviderUsageSnapshot {
     }
 }
 
-async fn fetch_codex_usage_inner() -> Result<ProviderUsageSnapshot, String> {
+async fn fetch_codex_usage_inner(deadline: Instant) -> Result<ProviderUsageSnapshot, String> {
     let now = Utc::now();
 
     // Try RPC approach first
-    match fetch_via_rpc().await {
+    match fetch_via_rpc(deadline).await {
         Ok(snapshot) => return Ok(snapshot),
         Err(e) => {
             log::debug!("Codex RPC failed, will return error: {e}");
@@ -130,39 +130,20 @@ async fn fetch_codex_usage_inner() -> Result<ProviderUsageSnapshot, String> {
     })
 }
 
-async fn fetch_via_rpc() -> Result<ProviderUsageSnapshot, String> {
+async fn fetch_via_rpc(deadline: Instant) -> Result<ProviderUsageSnapshot, String> {
     let now = Utc::now();
 
     // Find codex binary
     let codex_path = which::which("codex")
         .map_err(|_| "Codex CLI not installed")?;
 
-    // Start codex app-server process
-    let mut child = Command::new(&codex_path)
-        .args(["-s", "read-only", "-a", "untrusted", "app-server"])
-        .stdin(Stdio::piped())
-        .stdout(Stdio::piped())
-        .stderr(Stdio::null())
-        .spawn()
-        .map_err(|e| format!("Failed to start Codex: {e}"))?;
-
-    let stdin = child.stdin.take().ok_or("Failed to get stdin")?;
-    let stdout = child.stdout.take().ok_or("Failed to get stdout")?;
-
-    // Wrap in async task with timeout
-    let result = tokio::time::timeout(
-        Duration::from_secs(10),
-        tokio::task::spawn_blocking(move || {
-            rpc_communication(stdin, stdout)
-        }),
-    )
-    .await;
-
-    // Kill child process
-    let _ = child.kill();
-
-    let (limits, account) = result
-        .map_err(|_| "RPC timeout".to_string())?
+    // The blocking read can't be aborted, but every request in it stops at the
+    // same deadline, so the thread winds down on its own shortly after this
+    let task =
+        tokio::task::spawn_blocking(move || read_usage_from_app_server(&codex_path, deadline));
+    let (limits, account) = tokio::time::timeout_at(deadline.into(), task)
+        .await
+        .map_err(|_| "RPC timed out".to_string())?
         .map_err(|e| format!("RPC task error: {e}"))??;
 
     // Extract account info
@@ -172,31 +153,14 @@ async fn fetch_via_rpc() -> Result<ProviderUsageSnapshot, String> {
     };
 
     // Build rate windows
-    let primary = limits.primary.map(|w| RateWindow {
-        used_percent: w.used_percent,
-        window_minutes: w.window_duration_mins,
-        resets_at: w.resets_at.map(|ts| {
-            chrono::DateTime::from_timestamp(ts, 0)
-                .map(|dt| dt.to_rfc3339())
-                .unwrap_or_default()
-        }),
-        reset_description: w.resets_at.and_then(|ts| {
-            chrono::DateTime::from_timestamp(ts, 0).map(|dt| format_reset_time(&dt))
-        }),
-    });
-
-    let secondary = limits.secondary.map(|w| RateWindow {
-        used_percent: w.used_percent,
-        window_minutes: w.window_duration_mins,
-        resets_at: w.resets_at.map(|ts| {
-            chrono::DateTime::from_timestamp(ts, 0)
-                .map(|dt| dt.to_rfc3339())
-                .unwrap_or_default()
-        }),
-        reset_description: w.resets_at.and_then(|ts| {
-            chrono::DateTime::from_timestamp(ts, 0).map(|dt| format_reset_time(&dt))
-        }),
-    });
+    let primary = limits
+        .primary
+        .as_ref()
+        .map(|w| rate_window(w, limits.secondary.as_ref(), now));
+    let secondary = limits
+        .secondary
+        .as_ref()
+        .map(|w| rate_window(w, limits.primary.as_ref(), now));
 
     let is_available = primary.is_some() || secondary.is_some();
 
@@ -212,80 +176,245 @@ async fn fetch_via_rpc() -> Result<ProviderUsageSnapshot, String> {
     })
 }
 
-fn rpc_communication(
-    mut stdin: std::process::ChildStdin,
-    stdout: std::process::ChildStdout,
+/// Read usage via the shared app-server, restarting it if it died or is unhealthy
+fn read_usage_from_app_server(
+    codex_path: &Path,
+    deadline: Instant,
 ) -> Result<(RateLimitSnapshot, Option<AccountDetails>), String> {
-    let mut reader = BufReader::new(stdout);
-
-    // Send initialize request
-    let init_req = r#"{"id":1,"method":"initialize","params":{"clientInfo":{"name":"jean","version":"1.0"}}}"#;
-    writeln!(stdin, "{init_req}").map_err(|e| format!("Write error: {e}"))?;
-
-    // Read until we get a response with id: 1
-    let mut line = String::new();
-    loop {
-        line.clear();
-        if reader.read_line(&mut line).map_err(|e| format!("Read error: {e}"))? == 0 {
-            return Err("EOF while waiting for initialize response".to_string());
-        }
-        if let Ok(json) = serde_json::from_str::<serde_json::Value>(&line) {
-            if json.get("id") == Some(&serde_json::json!(1)) {
-                break;
+    let Ok(mut guard) = APP_SERVER.try_lock() else {
+        // Another poll is using the shared server; don't queue behind it
+        return AppServer::start(codex_command(codex_path), deadline)?.read_usage(deadline);
+    };
+
+    if let Some(server) = guard.as_mut() {
+        if server.is_alive() {
+            match server.read_usage(deadline) {
+                Ok(usage) => return Ok(usage),
+                Err(e) => log::debug!("Codex app-server unhealthy, restarting: {e}"),
             }
+        } else {
+            log::debug!("Codex app-server exited, restarting");
         }
     }
 
-    // Send initialized notification
-    let initialized = r#"{"method":"initialized","params":{}}"#;
-    writeln!(stdin, "{initialized}").map_err(|e| format!("Write error: {e}"))?;
+    // Drop (and kill) the old server before spawning a fresh one
+    *guard = None;
+    let mut server = AppServer::start(codex_command(codex_path), deadline)?;
+    let usage = server.read_usage(deadline)?;
+    *guard = Some(server);
+    Ok(usage)
+}
 
-    // Request rate limits
-    let limits_req = r#"{"id":2,"method":"account/rateLimits/read","params":{}}"#;
-    writeln!(stdin, "{limits_req}").map_err(|e| format!("Write error: {e}"))?;
+fn codex_command(codex_path: &Path) -> Command {
+    let mut command = Command::new(codex_path);
+    command.args(["-s", "read-only", "-a", "untrusted", "app-server"]);
+    command
+}
+
+/// A running `codex app-server` and its JSON-RPC connection
+struct AppServer {
+    child: Child,
+    rpc: RpcConnection,
+}
 
-    // Read rate limits response
-    let limits: RateLimitSnapshot = loop {
-        line.clear();
-        if reader.read_line(&mut line).map_err(|e| format!("Read error: {e}"))? == 0 {
-            return Err("EOF while waiting for rate limits".to_string());
+impl AppServer {
+    /// Spawn the server and perform the initialize handshake
+    fn start(mut command: Command, deadline: Instant) -> Result<Self, String> {
+        // No point spawning a process that has no time left to answer
+        if Instant::now() >= deadline {
+            return Err("RPC timeout before starting Codex".to_string());
         }
-        if let Ok(json) = serde_json::from_str::<serde_json::Value>(&line) {
-            if json.get("id") == Some(&serde_json::json!(2)) {
-                let response: RpcRateLimitsResponse = serde_json::from_str(&line)
-                    .map_err(|e| format!("Parse error: {e}"))?;
-                break response
-                    .result
-                    .and_then(|r| r.rate_limits)
-                    .ok_or("No rate limits in response")?;
+
+        let mut child = command
+            .stdin(Stdio::piped())
+            .stdout(Stdio::piped())
+            .stderr(Stdio::null())
+            .spawn()
+            .map_err(|e| format!("Failed to start Codex: {e}"))?;
+
+        let stdin = child.stdin.take().ok_or("Failed to get stdin")?;
+        let stdout = child.stdout.take().ok_or("Failed to get stdout")?;
+
+        let mut rpc = RpcConnection::new(Box::new(stdin), stdout);
+        rpc.initialize(deadline)?;
+
+        Ok(Self { child, rpc })
+    }
+
+    fn is_alive(&mut self) -> bool {
+        matches!(self.child.try_wait(), Ok(None))
+    }
+
+    fn read_usage(
+        &mut self,
+        deadline: Instant,
+    ) -> Result<(RateLimitSnapshot, Option<AccountDetails>), String> {
+        self.rpc.read_usage(deadline)
+    }
+}
+
+impl Drop for AppServer {
+    fn drop(&mut self) {
+        let _ = self.child.kill();
+        let _ = self.child.wait();
+    }
+}
+
+/// Server identity from the `initialize` response
+#[derive(Debug, Default, Deserialize)]
+#[serde(rename_all = "camelCase")]
+struct InitializeResult {
+    protocol_version: Option<serde_json::Value>,
+    server_info: Option<ServerInfo>,
+}
+
+#[derive(Debug, Deserialize)]
+struct ServerInfo {
+    name: Option<String>,
+    version: Option<String>,
+}
+
+/// Line-delimited JSON-RPC connection to the app-server
+struct RpcConnection {
+    writer: Box<dyn Write + Send>,
+    /// Lines from the server, forwarded by a reader thread so reads can time out
+    lines: Receiver<String>,
+    /// Next JSON-RPC request id (persists across polls)
+    next_id: u64,
+}
+
+impl RpcConnection {
+    fn new<R: Read + Send + 'static>(writer: Box<dyn Write + Send>, reader: R) -> Self {
+        let (tx, lines) = mpsc::channel();
+        std::thread::spawn(move || {
+            for line in BufReader::new(reader).lines() {
+                let Ok(line) = line else { break };
+                if tx.send(line).is_err() {
+                    break;
+                }
             }
+        });
+
+        Self {
+            writer,
+            lines,
+            next_id: 1,
         }
-    };
+    }
 
-    // Request account info
-    let account_req = r#"{"id":3,"method":"account/read","params":{}}"#;
-    writeln!(stdin, "{account_req}").map_err(|e| format!("Write error: {e}"))?;
-
-    // Read account response (optional, don't fail if it errors)
-    let account: Option<AccountDetails> = loop {
-        line.clear();
-        match reader.read_line(&mut line) {
-            Ok(0) => break None,
-            Err(_) => break None,
-            Ok(_) => {
-                if let Ok(json) = serde_json::from_str::<serde_json::Value>(&line) {
-                    if json.get("id") == Some(&serde_json::json!(3)) {
-                        if let Ok(response) = serde_json::from_str::<RpcAccountResponse>(&line) {
-                            break response.result.and_then(|r| r.account);
-                        }
-                        break None;
-                    }
-                }
+    /// Perform the initialize handshake and log what the server reports
+    fn initialize(&mut self, deadline: Instant) -> Result<InitializeResult, String> {
+        let result = self.request(
+            "initialize",
+            serde_json::json!({"clientInfo": {"name": "jean", "version": "1.0"}}),
+            deadline,
+        )?;
+        let info: InitializeResult = serde_json::from_value(result).unwrap_or_default();
+        log::debug!(
+            "Codex app-server initialized: server={} version={} protocol={}",
+            info.server_info
+                .as_ref()
+                .and_then(|s| s.name.as_deref())
+                .unwrap_or("unknown"),
+            info.server_info
+                .as_ref()
+                .and_then(|s| s.version.as_deref())
+                .unwrap_or("unknown"),
+            info.protocol_version
+                .as_ref()
+                .map(|v| v.to_string())
+                .unwrap_or_else(|| "unspecified".to_string()),
+        );
+
+        self.send(&serde_json::json!({"method": "initialized", "params": {}}))?;
+        Ok(info)
+    }
+
+    fn send(&mut self, message: &serde_json::Value) -> Result<(), String> {
+        writeln!(self.writer, "{message}").map_err(|e| format!("Write error: {e}"))?;
+        self.writer.flush().map_err(|e| format!("Write error: {e}"))
+    }
+
+    /// Send a request and wait for the response with the matching id
+    ///
+    /// Waits at most [`RPC_TIMEOUT`], and never past `deadline`. Returns the
+    /// `result` value, or a descriptive error if the server answered with an
+    /// `error` object (e.g. the method was renamed in a newer Codex).
+    fn request(
+        &mut self,
+        method: &str,
+        params: serde_json::Value,
+        deadline: Instant,
+    ) -> Result<serde_json::Value, String> {
+        let id = self.next_id;
+        self.next_id += 1;
+        self.send(&serde_json::json!({"id": id, "method": method, "params": params}))?;
+
+        let deadline = deadline.min(Instant::now() + RPC_TIMEOUT);
+        loop {
+            let remaining = deadline.saturating_duration_since(Instant::now());
+            let line = self.lines.recv_timeout(remaining).map_err(|e| match e {
+                RecvTimeoutError::Timeout => format!("RPC timeout waiting for {method}"),
+                RecvTimeoutError::Disconnected => format!("EOF while waiting for {method}"),
+            })?;
+            // Skip notifications and stale responses from earlier timed-out requests
+            let Ok(mut json) = serde_json::from_str::<serde_json::Value>(&line) else {
+                continue;
+            };
+            if json.get("id") != Some(&serde_json::json!(id)) {
+                continue;
+            }
+            if let Some(error) = json.get("error") {
+                return Err(describe_rpc_error(method, error));
             }
+            return Ok(json
+                .get_mut("result")
+                .map(serde_json::Value::take)
+                .unwrap_or(serde_json::Value::Null));
         }
-    };
+    }
+
+    fn read_usage(
+        &mut self,
+        deadline: Instant,
+    ) -> Result<(RateLimitSnapshot, Option<AccountDetails>), String> {
+        let result = self.request("account/rateLimits/read", serde_json::json!({}), deadline)?;
+        let limits = serde_json::from_value::<RateLimitsResult>(result)
+            .map_err(|e| format!("Parse error: {e}"))?
+            .rate_limits
+            .ok_or("No rate limits in response")?;
+
+        // Account info is optional, don't fail if it errors
+        let account = match self.request("account/read", serde_json::json!({}), deadline) {
+            Ok(result) => serde_json::from_value::<AccountResult>(result)
+                .ok()
+                .and_then(|r| r.account),
+            Err(e) => {
+                log::debug!("Codex account info unavailable: {e}");
+                None
+            }
+        };
 
-    Ok((limits, account))
+        Ok((limits, account))
+    }
+}
+
+/// Turn a JSON-RPC `error` object into a readable message
+fn describe_rpc_error(method: &str, error: &serde_json::Value) -> String {
+    let code = error.get("code").and_then(|c| c.as_i64());
+    let message = error
+        .get("message")
+        .and_then(|m| m.as_str())
+        .unwrap_or("unknown error");
+
+    match code {
+        // JSON-RPC "method not found": the app-server protocol changed
+        Some(-32601) => format!(
+            "Codex app-server does not support {method} ({message}). The installed Codex version may be incompatible."
+        ),
+        Some(code) => format!("Codex app-server rejected {method}: {message} (code {code})"),
+        None => format!("Codex app-server rejected {method}: {message}"),
+    }
 }
 
 fn get_account_info() -> (Option<String>, Option<String>) {
@@ -353,6 +482,44 @@ fn parse_jwt_claims(token: &str) -> (Option<String>, Option<String>) {
     (email, plan)
 }
 
+/// When a window resets, and whether that time is an estimate
+///
+/// Codex sometimes omits `resetsAt` (often reporting it only on the secondary
+/// window). A missing reset is taken from the other window when that reset is
+/// no later than this window could possibly last, and otherwise assumed to be
+/// a full window duration from now.
+fn infer_resets_at(
+    window: &RpcWindow,
+    other: Option<&RpcWindow>,
+    now: DateTime<Utc>,
+) -> Option<(DateTime<Utc>, bool)> {
+    if let Some(ts) = window.resets_at {
+        return DateTime::from_timestamp(ts, 0).map(|dt| (dt, false));
+    }
+
+    let latest_possible = window
+        .window_duration_mins
+        .map(|mins| now + chrono::Duration::minutes(i64::from(mins)));
+    let from_other = other
+        .and_then(|w| w.resets_at)
+        .and_then(|ts| DateTime::from_timestamp(ts, 0))
+        .filter(|dt| *dt > now && latest_possible.is_none_or(|latest| *dt <= latest));
+
+    from_other.or(latest_possible).map(|dt| (dt, true))
+}
+
+/// Convert an RPC window, filling in a missing reset from `other` or the window duration
+fn rate_window(window: &RpcWindow, other: Option<&RpcWindow>, now: DateTime<Utc>) -> RateWindow {
+    let reset = infer_resets_at(window, other, now);
+    RateWindow {
+        used_percent: window.used_percent,
+        window_minutes: window.window_duration_mins,
+        resets_at: reset.map(|(dt, _)| dt.to_rfc3339()),
+        reset_description: reset.map(|(dt, _)| format_reset_time(&dt)),
+        reset_estimated: reset.is_some_and(|(_, estimated)| estimated),
+    }
+}
+
 fn format_reset_time(dt: &chrono::DateTime<Utc>) -> String {
     let now = Utc::now();
     let diff = *dt - now;
@@ -370,3 +537,155 @@ fn format_reset_time(dt: &chrono::DateTime<Utc>) -> String {
         format!("Resets in {minutes}m")
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /// Fake app-server that answers every request with its own id
+    #[cfg(unix)]
+    fn echo_server() -> Command {
+        let mut command = Command::new("sh");
+        command.args([
+            "-c",
+            r#"while IFS= read -r line; do
+                id=$(printf '%s' "$line" | sed -n 's/.*"id":\([0-9]*\).*/\1/p')
+                [ -n "$id" ] && printf '{"id":%s,"result":{}}\n' "$id"
+            done"#,
+        ]);
+        command
+    }
+
+    #[cfg(unix)]
+    #[test]
+    fn test_app_server_reuses_process_and_request_ids() {
+        let deadline = Instant::now() + RPC_TIMEOUT;
+        let mut server = AppServer::start(echo_server(), deadline).unwrap();
+        assert!(server.is_alive());
+
+        // initialize used id 1; later requests keep counting on the same process
+        server
+            .rpc
+            .request("ping", serde_json::json!({}), deadline)
+            .unwrap();
+        server
+            .rpc
+            .request("ping", serde_json::json!({}), deadline)
+            .unwrap();
+        assert_eq!(server.rpc.next_id, 4);
+
+        // A dead server is detected so it can be restarted
+        server.child.kill().unwrap();
+        server.child.wait().unwrap();
+        assert!(!server.is_alive());
+        assert!(server
+            .rpc
+            .request("ping", serde_json::json!({}), deadline)
+            .is_err());
+    }
+
+    #[cfg(unix)]
+    #[test]
+    fn test_request_stops_at_caller_deadline() {
+        // A server that never answers
+        let mut silent = Command::new("sleep")
+            .arg("30")
+            .stdout(Stdio::piped())
+            .spawn()
+            .unwrap();
+        let stdout = silent.stdout.take().unwrap();
+        let mut rpc = RpcConnection::new(Box::new(std::io::sink()), stdout);
+
+        let started = Instant::now();
+        let err = rpc
+            .request(
+                "ping",
+                serde_json::json!({}),
+                started + Duration::from_millis(100),
+            )
+            .unwrap_err();
+        assert!(err.contains("RPC timeout"), "{err}");
+        assert!(started.elapsed() < RPC_TIMEOUT);
+
+        // No time left: don't even spawn the server
+        assert!(AppServer::start(echo_server(), Instant::now()).is_err());
+
+        let _ = silent.kill();
+        let _ = silent.wait();
+    }
+
+    fn rpc_window(duration_mins: Option<i32>, resets_at: Option<i64>) -> RpcWindow {
+        RpcWindow {
+            used_percent: 40.0,
+            window_duration_mins: duration_mins,
+            resets_at,
+        }
+    }
+
+    #[test]
+    fn test_missing_reset_inferred_from_window_duration() {
+        let now = Utc::now();
+        let window = rate_window(&rpc_window(Some(300), None), None, now);
+
+        let expected = now + chrono::Duration::minutes(300);
+        assert_eq!(window.resets_at, Some(expected.to_rfc3339()));
+        assert!(window.reset_estimated);
+        assert!(window
+            .reset_description
+            .as_deref()
+            .is_some_and(|d| d.starts_with("Resets in 4h") || d.starts_with("Resets in 5h")));
+    }
+
+    #[test]
+    fn test_missing_reset_inferred_from_other_window() {
+        let now = DateTime::from_timestamp(1_000_000, 0).unwrap();
+        let reported = rpc_window(Some(300), Some(1_000_000 + 3600));
+        assert_eq!(
+            infer_resets_at(&reported, None, now),
+            Some((now + chrono::Duration::hours(1), false))
+        );
+
+        // The other window's reset is used when it fits within this window
+        let missing = rpc_window(Some(300), None);
+        assert_eq!(
+            infer_resets_at(&missing, Some(&reported), now),
+            Some((now + chrono::Duration::hours(1), true))
+        );
+
+        // A weekly reset can't be the 5-hour window's, so fall back to the duration
+        let weekly = rpc_window(Some(10080), Some(1_000_000 + 3 * 86400));
+        assert_eq!(
+            infer_resets_at(&missing, Some(&weekly), now),
+            Some((now + chrono::Duration::minutes(300), true))
+        );
+
+        // Nothing to infer from
+        assert_eq!(infer_resets_at(&rpc_window(None, None), None, now), None);
+    }
+
+    #[test]
+    fn test_rpc_error_response_is_reported() {
+        let responses = concat!(
+            r#"{"id":1,"result":{"protocolVersion":"2","serverInfo":{"name":"codex","version":"9.0.0"}}}"#,
+            "\n",
+            r#"{"method":"some/notification","params":{}}"#,
+            "\n",
+            r#"{"id":2,"error":{"code":-32601,"message":"Method not found"}}"#,
+            "\n",
+        );
+        let mut rpc = RpcConnection::new(
+            Box::new(std::io::sink()),
+            std::io::Cursor::new(responses.as_bytes().to_vec()),
+        );
+
+        let deadline = Instant::now() + RPC_TIMEOUT;
+        let info = rpc.initialize(deadline).unwrap();
+        assert_eq!(info.server_info.unwrap().version.as_deref(), Some("9.0.0"));
+
+        let err = rpc.read_usage(deadline).unwrap_err();
+        assert!(
+            err.contains("does not support account/rateLimits/read"),
+            "{err}"
+        );
+    }
+}
@@ -1,31 +1,108 @@
 //! Tauri commands for multi-provider usage tracking
 
+use super::alerts::check_usage_alerts;
+use super::anthropic_api::fetch_anthropic_api_usage;
 use super::codex::fetch_codex_usage;
+use super::history::record_snapshot;
 use super::types::{AllProvidersUsage, ProviderUsageSnapshot, RateWindow};
 use crate::claude_usage::api::fetch_usage_limits as fetch_claude_limits;
 use crate::claude_usage::credentials::has_oauth_credentials;
 use chrono::Utc;
+use std::time::{Duration, Instant};
+use tauri::async_runtime::{spawn, JoinHandle};
+use tauri::AppHandle;
+
+/// Overall deadline for a usage fetch. Codex RPCs are bounded by the time left
+/// before it, and anything still running when it fires is aborted.
+const FETCH_DEADLINE: Duration = Duration::from_secs(15);
 
 /// Get usage for a specific provider
 #[tauri::command]
-pub async fn get_provider_usage(provider: String) -> Result<ProviderUsageSnapshot, String> {
-    match provider.as_str() {
-        "claude" => Ok(fetch_claude_usage().await),
-        "codex" => Ok(fetch_codex_usage().await),
-        _ => Err(format!("Unknown provider: {provider}")),
-    }
+pub async fn get_provider_usage(
+    app: AppHandle,
+    provider: String,
+) -> Result<ProviderUsageSnapshot, String> {
+    let deadline = Instant::now() + FETCH_DEADLINE;
+    let task = match provider.as_str() {
+        "claude" => ("claude", spawn(fetch_claude_usage())),
+        "codex" => ("codex", spawn(fetch_codex_usage(deadline))),
+        "anthropic-api" => ("anthropic-api", spawn(fetch_anthropic_api_usage())),
+        _ => return Err(format!("Unknown provider: {provider}")),
+    };
+    let snapshot = collect_with_deadline(vec![task], deadline).await.remove(0);
+
+    record_snapshot(&app, &snapshot);
+    check_usage_alerts(&app, &snapshot);
+    Ok(snapshot)
 }
 
 /// Get usage for all providers
 #[tauri::command]
-pub async fn get_all_providers_usage() -> AllProvidersUsage {
-    // Fetch all providers sequentially (simpler, avoids tokio::join! issues)
-    let claude = fetch_claude_usage().await;
-    let codex = fetch_codex_usage().await;
-
-    AllProvidersUsage {
-        claude: Some(claude),
-        codex: Some(codex),
+pub async fn get_all_providers_usage(app: AppHandle) -> AllProvidersUsage {
+    let mut usage = AllProvidersUsage::default();
+
+    for snapshot in fetch_all_provider_usage().await {
+        record_snapshot(&app, &snapshot);
+        check_usage_alerts(&app, &snapshot);
+
+        match snapshot.provider_id.as_str() {
+            "claude" => usage.claude = Some(snapshot),
+            "codex" => usage.codex = Some(snapshot),
+            "anthropic-api" => usage.anthropic_api = Some(snapshot),
+            _ => {}
+        }
+    }
+
+    usage
+}
+
+/// Fetch usage from all providers concurrently
+///
+/// Every provider runs in its own task under a shared deadline, so one wedged
+/// CLI can't stall the dashboard. Providers that miss the deadline are returned
+/// as unavailable with a "timed out" error.
+pub async fn fetch_all_provider_usage() -> Vec<ProviderUsageSnapshot> {
+    let deadline = Instant::now() + FETCH_DEADLINE;
+    let tasks = vec![
+        ("claude", spawn(fetch_claude_usage())),
+        ("codex", spawn(fetch_codex_usage(deadline))),
+        ("anthropic-api", spawn(fetch_anthropic_api_usage())),
+    ];
+
+    collect_with_deadline(tasks, deadline).await
+}
+
+/// Await spawned provider fetches until the deadline, aborting any stragglers
+async fn collect_with_deadline(
+    tasks: Vec<(&'static str, JoinHandle<ProviderUsageSnapshot>)>,
+    deadline: Instant,
+) -> Vec<ProviderUsageSnapshot> {
+    let deadline = tokio::time::Instant::from_std(deadline);
+    let mut snapshots = Vec::with_capacity(tasks.len());
+
+    for (provider_id, mut handle) in tasks {
+        let snapshot = match tokio::time::timeout_at(deadline, &mut handle).await {
+            Ok(Ok(snapshot)) => snapshot,
+            Ok(Err(e)) => unavailable_snapshot(provider_id, format!("Usage fetch failed: {e}")),
+            Err(_) => {
+                log::warn!("Usage fetch for {provider_id} timed out");
+                handle.abort();
+                unavailable_snapshot(provider_id, "timed out".to_string())
+            }
+        };
+        snapshots.push(snapshot);
+    }
+
+    snapshots
+}
+
+fn unavailable_snapshot(provider_id: &str, error: String) -> ProviderUsageSnapshot {
+    ProviderUsageSnapshot {
+        provider_id: provider_id.to_string(),
+        available: false,
+        error: Some(error),
+        updated_at: Utc::now().to_rfc3339(),
+        ..Default::default()
     }
 }
 
@@ -52,6 +129,7 @@ async fn fetch_claude_usage() -> ProviderUsageSnapshot {
                 window_minutes: Some(300), // 5 hours
                 resets_at: l.resets_at.clone(),
                 reset_description: l.resets_at.as_ref().map(|r| format_reset_time(r)),
+                reset_estimated: false,
             });
 
             let secondary = limits.seven_day.as_ref().map(|l| RateWindow {
@@ -59,6 +137,7 @@ async fn fetch_claude_usage() -> ProviderUsageSnapshot {
                 window_minutes: Some(10080), // 7 days
                 resets_at: l.resets_at.clone(),
                 reset_description: l.resets_at.as_ref().map(|r| format_reset_time(r)),
+                reset_estimated: false,
             });
 
             ProviderUsageSnapshot {
@@ -75,7 +154,7 @@ async fn fetch_claude_usage() -> ProviderUsageSnapshot {
         Err(e) => ProviderUsageSnapshot {
             provider_id: "claude".to_string(),
             available: false,
-            error: Some(e),
+            error: Some(e.to_string()),
             updated_at: now.to_rfc3339(),
             ..Default::default()
         },
@@ -103,3 +182,35 @@ fn format_reset_time(iso_string: &str) -> String {
         "Unknown".to_string()
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_collect_with_deadline_marks_stragglers_timed_out() {
+        let snapshots = tauri::async_runtime::block_on(async {
+            let tasks = vec![
+                (
+                    "fast",
+                    spawn(async { unavailable_snapshot("fast", "done".to_string()) }),
+                ),
+                (
+                    "wedged",
+                    spawn(async {
+                        tokio::time::sleep(Duration::from_secs(60)).await;
+                        ProviderUsageSnapshot::default()
+                    }),
+                ),
+            ];
+            collect_with_deadline(tasks, Instant::now() + Duration::from_millis(50)).await
+        });
+
+        assert_eq!(snapshots.len(), 2);
+        assert_eq!(snapshots[0].provider_id, "fast");
+        assert_eq!(snapshots[0].error.as_deref(), Some("done"));
+        assert_eq!(snapshots[1].provider_id, "wedged");
+        assert!(!snapshots[1].available);
+        assert_eq!(snapshots[1].error.as_deref(), Some("timed out"));
+    }
+}
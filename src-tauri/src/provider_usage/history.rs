@@ -0,0 +1,235 @@
+//! Usage history persistence
+//!
+//! Each successful provider snapshot is appended to a per-provider JSONL file
+//! under `{app_data}/usage-history/{provider_id}.jsonl` so the UI can chart
+//! usage over time without re-querying providers. Entries older than
+//! `RETENTION_DAYS` are dropped on every write.
+
+use chrono::{DateTime, Duration, Utc};
+use once_cell::sync::Lazy;
+use std::fs;
+use std::path::{Path, PathBuf};
+use std::sync::Mutex;
+use tauri::{AppHandle, Manager};
+
+use super::types::ProviderUsageSnapshot;
+
+/// How long snapshots are kept
+const RETENTION_DAYS: i64 = 30;
+
+/// Minimum spacing between recorded snapshots, bounds the file size when
+/// the UI polls frequently (30 days at 5 minutes is ~8.6k lines)
+const MIN_RECORD_INTERVAL_SECS: i64 = 5 * 60;
+
+/// Serializes read-modify-write cycles on history files
+static HISTORY_LOCK: Lazy<Mutex<()>> = Lazy::new(|| Mutex::new(()));
+
+/// Get the usage history directory in app data
+fn get_history_dir(app: &AppHandle) -> Result<PathBuf, String> {
+    let app_data_dir = app
+        .path()
+        .app_data_dir()
+        .map_err(|e| format!("Failed to get app data directory: {e}"))?;
+    Ok(app_data_dir.join("usage-history"))
+}
+
+/// Path of a provider's history file
+fn history_path(dir: &Path, provider_id: &str) -> PathBuf {
+    let safe_id: String = provider_id
+        .chars()
+        .map(|c| {
+            if c.is_alphanumeric() || c == '-' || c == '_' {
+                c
+            } else {
+                '-'
+            }
+        })
+        .collect();
+    dir.join(format!("{safe_id}.jsonl"))
+}
+
+fn parse_timestamp(snapshot: &ProviderUsageSnapshot) -> Option<DateTime<Utc>> {
+    DateTime::parse_from_rfc3339(&snapshot.updated_at)
+        .ok()
+        .map(|dt| dt.with_timezone(&Utc))
+}
+
+/// Read all snapshots from a history file, skipping malformed lines
+fn read_snapshots(path: &Path) -> Vec<ProviderUsageSnapshot> {
+    let Ok(content) = fs::read_to_string(path) else {
+        return vec![];
+    };
+    content
+        .lines()
+        .filter(|line| !line.trim().is_empty())
+        .filter_map(|line| serde_json::from_str(line).ok())
+        .collect()
+}
+
+/// Append a snapshot to the provider's history in `dir`
+///
+/// Returns false if the snapshot was skipped (unavailable, or too close to
+/// the previous recorded snapshot).
+fn append_snapshot_in(
+    dir: &Path,
+    snapshot: &ProviderUsageSnapshot,
+    now: DateTime<Utc>,
+) -> Result<bool, String> {
+    if !snapshot.available {
+        return Ok(false);
+    }
+
+    let _lock = HISTORY_LOCK.lock().unwrap();
+
+    fs::create_dir_all(dir)
+        .map_err(|e| format!("Failed to create usage history directory: {e}"))?;
+    let path = history_path(dir, &snapshot.provider_id);
+
+    let cutoff = now - Duration::days(RETENTION_DAYS);
+    let mut snapshots: Vec<ProviderUsageSnapshot> = read_snapshots(&path)
+        .into_iter()
+        .filter(|s| parse_timestamp(s).is_some_and(|ts| ts >= cutoff))
+        .collect();
+
+    let recent = snapshots
+        .last()
+        .and_then(parse_timestamp)
+        .zip(parse_timestamp(snapshot))
+        .is_some_and(|(last, current)| (current - last).num_seconds() < MIN_RECORD_INTERVAL_SECS);
+    if recent {
+        return Ok(false);
+    }
+
+    snapshots.push(snapshot.clone());
+
+    let mut content = String::new();
+    for s in &snapshots {
+        let line = serde_json::to_string(s)
+            .map_err(|e| format!("Failed to serialize usage snapshot: {e}"))?;
+        content.push_str(&line);
+        content.push('\n');
+    }
+
+    // Atomic write: temp file + rename
+    let temp_path = path.with_extension("tmp");
+    fs::write(&temp_path, content).map_err(|e| format!("Failed to write usage history: {e}"))?;
+    fs::rename(&temp_path, &path).map_err(|e| format!("Failed to finalize usage history: {e}"))?;
+
+    Ok(true)
+}
+
+/// Read a provider's history from `dir`, optionally only entries at or after `since`
+fn read_history_in(
+    dir: &Path,
+    provider_id: &str,
+    since: Option<DateTime<Utc>>,
+) -> Vec<ProviderUsageSnapshot> {
+    let _lock = HISTORY_LOCK.lock().unwrap();
+
+    read_snapshots(&history_path(dir, provider_id))
+        .into_iter()
+        .filter(|s| match since {
+            Some(since) => parse_timestamp(s).is_some_and(|ts| ts >= since),
+            None => true,
+        })
+        .collect()
+}
+
+/// Record a snapshot in the provider's history (errors are logged, not returned)
+pub fn record_snapshot(app: &AppHandle, snapshot: &ProviderUsageSnapshot) {
+    let result =
+        get_history_dir(app).and_then(|dir| append_snapshot_in(&dir, snapshot, Utc::now()));
+    if let Err(e) = result {
+        log::warn!(
+            "Failed to record {} usage history: {e}",
+            snapshot.provider_id
+        );
+    }
+}
+
+/// Get recorded usage snapshots for a provider
+///
+/// `since` is an optional RFC 3339 timestamp; only snapshots at or after it are returned.
+#[tauri::command]
+pub async fn get_usage_history(
+    app: AppHandle,
+    provider_id: String,
+    since: Option<String>,
+) -> Result<Vec<ProviderUsageSnapshot>, String> {
+    let since = since
+        .map(|s| {
+            DateTime::parse_from_rfc3339(&s)
+                .map(|dt| dt.with_timezone(&Utc))
+                .map_err(|e| format!("Invalid 'since' timestamp: {e}"))
+        })
+        .transpose()?;
+
+    let dir = get_history_dir(&app)?;
+    Ok(read_history_in(&dir, &provider_id, since))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn snapshot_at(ts: DateTime<Utc>, used: f64) -> ProviderUsageSnapshot {
+        ProviderUsageSnapshot {
+            provider_id: "codex".to_string(),
+            primary: Some(super::super::types::RateWindow {
+                used_percent: used,
+                ..Default::default()
+            }),
+            updated_at: ts.to_rfc3339(),
+            available: true,
+            ..Default::default()
+        }
+    }
+
+    #[test]
+    fn test_append_and_read_history() {
+        let dir = tempfile::tempdir().unwrap();
+        let now = Utc::now();
+
+        let first = snapshot_at(now - Duration::hours(2), 10.0);
+        let second = snapshot_at(now - Duration::hours(1), 20.0);
+        assert!(append_snapshot_in(dir.path(), &first, now).unwrap());
+        assert!(append_snapshot_in(dir.path(), &second, now).unwrap());
+
+        let all = read_history_in(dir.path(), "codex", None);
+        assert_eq!(all.len(), 2);
+
+        let recent = read_history_in(dir.path(), "codex", Some(now - Duration::minutes(90)));
+        assert_eq!(recent.len(), 1);
+        assert_eq!(recent[0].primary.as_ref().unwrap().used_percent, 20.0);
+    }
+
+    #[test]
+    fn test_append_skips_unavailable_and_too_frequent() {
+        let dir = tempfile::tempdir().unwrap();
+        let now = Utc::now();
+
+        let mut unavailable = snapshot_at(now, 0.0);
+        unavailable.available = false;
+        assert!(!append_snapshot_in(dir.path(), &unavailable, now).unwrap());
+
+        assert!(append_snapshot_in(dir.path(), &snapshot_at(now, 1.0), now).unwrap());
+        let soon = snapshot_at(now + Duration::minutes(1), 2.0);
+        assert!(!append_snapshot_in(dir.path(), &soon, now).unwrap());
+
+        assert_eq!(read_history_in(dir.path(), "codex", None).len(), 1);
+    }
+
+    #[test]
+    fn test_append_prunes_old_entries() {
+        let dir = tempfile::tempdir().unwrap();
+        let now = Utc::now();
+
+        let old = snapshot_at(now - Duration::days(RETENTION_DAYS + 1), 5.0);
+        append_snapshot_in(dir.path(), &old, now - Duration::days(RETENTION_DAYS + 1)).unwrap();
+        append_snapshot_in(dir.path(), &snapshot_at(now, 50.0), now).unwrap();
+
+        let all = read_history_in(dir.path(), "codex", None);
+        assert_eq!(all.len(), 1);
+        assert_eq!(all[0].primary.as_ref().unwrap().used_percent, 50.0);
+    }
+}
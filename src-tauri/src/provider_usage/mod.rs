@@ -5,9 +5,13 @@
 //! - Codex (via RPC or session logs)
 //! - Gemini (via Google Cloud API)
 //! - Kimi (via Kimi API)
+//! - Anthropic API key (via rate-limit headers)
 
+pub mod alerts;
+pub mod anthropic_api;
 pub mod commands;
 pub mod gemini;
 pub mod codex;
+pub mod history;
 pub mod kimi;
 pub mod types;
@@ -14,6 +14,9 @@ pub struct RateWindow {
     pub resets_at: Option<String>,
     /// Human-readable reset description
     pub reset_description: Option<String>,
+    /// Whether `resets_at` was inferred rather than reported by the provider
+    #[serde(default)]
+    pub reset_estimated: bool,
 }
 
 impl RateWindow {
@@ -28,7 +31,7 @@ impl RateWindow {
 #[derive(Debug, Clone, Serialize, Deserialize, Default)]
 #[serde(rename_all = "camelCase")]
 pub struct ProviderUsageSnapshot {
-    /// Provider ID (claude, codex)
+    /// Provider ID (claude, codex, anthropic-api)
     pub provider_id: String,
     /// Primary rate window (usually session/5-hour)
     pub primary: Option<RateWindow>,
@@ -52,4 +55,152 @@ pub struct ProviderUsageSnapshot {
 pub struct AllProvidersUsage {
     pub claude: Option<ProviderUsageSnapshot>,
     pub codex: Option<ProviderUsageSnapshot>,
+    pub anthropic_api: Option<ProviderUsageSnapshot>,
+}
+
+/// Budget alert thresholds per provider, persisted in app data
+#[derive(Debug, Clone, Serialize, Deserialize, Default)]
+#[serde(rename_all = "camelCase")]
+pub struct UsageAlertConfig {
+    /// Provider ID -> threshold percentage (0-100) that triggers an alert
+    #[serde(default)]
+    pub thresholds: std::collections::HashMap<String, f64>,
+}
+
+/// Payload of the `usage:alert` event
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
+#[serde(rename_all = "camelCase")]
+pub struct UsageAlert {
+    /// Provider ID (claude, codex, anthropic-api)
+    pub provider_id: String,
+    /// Which window crossed the threshold ("primary" or "secondary")
+    pub window: String,
+    /// Current usage percentage of the window
+    pub used_percent: f64,
+    /// Configured threshold percentage
+    pub threshold_percent: f64,
+    /// When the window resets (if known)
+    pub resets_at: Option<String>,
+}
+
+/// Per-model token prices in USD per million tokens
+#[derive(Debug, Clone, Copy, Serialize, Deserialize, PartialEq)]
+#[serde(rename_all = "camelCase")]
+pub struct ModelPricing {
+    pub input_per_1m: f64,
+    pub output_per_1m: f64,
+    pub cache_read_per_1m: f64,
+    pub cache_creation_per_1m: f64,
+}
+
+impl ModelPricing {
+    const fn new(input: f64, output: f64, cache_read: f64, cache_creation: f64) -> Self {
+        Self {
+            input_per_1m: input,
+            output_per_1m: output,
+            cache_read_per_1m: cache_read,
+            cache_creation_per_1m: cache_creation,
+        }
+    }
+}
+
+/// Claude Sonnet pricing, also used for models missing from the table
+pub const DEFAULT_PRICING: ModelPricing = ModelPricing::new(3.0, 15.0, 0.30, 3.75);
+
+/// Approximate list prices, matched by substring of the model id or alias.
+/// More specific patterns come first.
+const PRICE_TABLE: &[(&str, ModelPricing)] = &[
+    ("opus", ModelPricing::new(5.0, 25.0, 0.50, 6.25)),
+    ("sonnet", DEFAULT_PRICING),
+    ("haiku", ModelPricing::new(1.0, 5.0, 0.10, 1.25)),
+    ("gpt-5.2", ModelPricing::new(1.75, 14.0, 0.175, 0.0)),
+    ("gpt-", ModelPricing::new(1.25, 10.0, 0.125, 0.0)),
+    ("gemini-3-pro", ModelPricing::new(2.0, 12.0, 0.20, 0.0)),
+    ("flash", ModelPricing::new(0.50, 3.0, 0.05, 0.0)),
+    ("kimi", ModelPricing::new(0.60, 2.50, 0.15, 0.0)),
+];
+
+/// Look up pricing for a model id or alias (e.g. "opus", "gpt-5.2-codex")
+pub fn model_pricing(model: &str) -> ModelPricing {
+    let model = model.to_lowercase();
+    PRICE_TABLE
+        .iter()
+        .find(|(pattern, _)| model.contains(pattern))
+        .map(|(_, pricing)| *pricing)
+        .unwrap_or(DEFAULT_PRICING)
+}
+
+/// Approximate tokens per attached image (Claude bills ~1600 for a 1092x1092 image)
+pub const IMAGE_TOKEN_ESTIMATE: u64 = 1_600;
+
+/// Rough token count for text, using the ~4 characters per token heuristic
+pub fn estimate_text_tokens(text: &str) -> u64 {
+    (text.chars().count() as u64).div_ceil(4)
+}
+
+/// Pre-send estimate of a prompt's input tokens and cost
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
+#[serde(rename_all = "camelCase")]
+pub struct TokenEstimate {
+    pub model: String,
+    /// Tokens in the typed message
+    pub text_tokens: u64,
+    /// Tokens in files attached to the session for the next message
+    pub attachment_tokens: u64,
+    pub image_count: u32,
+    pub image_tokens: u64,
+    /// Sum of text, attachment and image tokens
+    pub input_tokens: u64,
+    /// Input cost only; output length isn't known before sending
+    pub estimated_cost_usd: f64,
+}
+
+impl TokenEstimate {
+    pub fn new(model: &str, text_tokens: u64, attachment_tokens: u64, image_count: u32) -> Self {
+        let image_tokens = image_count as u64 * IMAGE_TOKEN_ESTIMATE;
+        let input_tokens = text_tokens + attachment_tokens + image_tokens;
+        let estimated_cost_usd =
+            input_tokens as f64 * model_pricing(model).input_per_1m / 1_000_000.0;
+        Self {
+            model: model.to_string(),
+            text_tokens,
+            attachment_tokens,
+            image_count,
+            image_tokens,
+            input_tokens,
+            estimated_cost_usd,
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_model_pricing_matches_aliases_and_ids() {
+        assert_eq!(model_pricing("sonnet"), DEFAULT_PRICING);
+        assert_eq!(model_pricing("claude-opus-4-5").input_per_1m, 5.0);
+        assert_eq!(model_pricing("Haiku").input_per_1m, 1.0);
+        assert_eq!(model_pricing("gpt-5.2-codex").input_per_1m, 1.75);
+        assert_eq!(model_pricing("gpt-5.1-codex-mini").input_per_1m, 1.25);
+        assert_eq!(model_pricing("gemini-3-flash-preview").input_per_1m, 0.50);
+        assert_eq!(
+            model_pricing("kimi-code/kimi-for-coding").input_per_1m,
+            0.60
+        );
+        assert_eq!(model_pricing("some-new-model"), DEFAULT_PRICING);
+    }
+
+    #[test]
+    fn test_token_estimate_sums_text_attachments_and_images() {
+        assert_eq!(estimate_text_tokens(""), 0);
+        assert_eq!(estimate_text_tokens("abcde"), 2);
+
+        let estimate = TokenEstimate::new("sonnet", 1_000, 9_000, 2);
+        assert_eq!(estimate.image_tokens, 3_200);
+        assert_eq!(estimate.input_tokens, 13_200);
+        // 13,200 tokens at $3 per million
+        assert!((estimate.estimated_cost_usd - 0.0396).abs() < 1e-9);
+    }
 }
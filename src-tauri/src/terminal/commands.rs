@@ -1,14 +1,40 @@
+use std::path::{Path, PathBuf};
+
 use tauri::AppHandle;
+use uuid::Uuid;
 
 use super::pty::{
-    kill_all_terminals as pty_kill_all_terminals, kill_terminal, resize_terminal, spawn_terminal,
+    get_recording_path, get_scrollback, kill_all_terminals as pty_kill_all_terminals,
+    kill_terminal, resize_terminal, signal_terminal as pty_signal_terminal, spawn_terminal,
     write_to_terminal,
 };
-use super::registry::{get_all_terminal_ids, has_terminal};
+use super::registry::{get_all_terminal_ids, has_terminal, list_terminal_info};
+use super::scrollback::{
+    plain_text_tail, ScrollbackBuffer, DEFAULT_SCROLLBACK_BYTES, DEFAULT_SCROLLBACK_LINES,
+    MAX_CAPTURE_BYTES,
+};
+use super::types::{TerminalInfo, TerminalSignal};
+use crate::ai_cli::codex::config::get_codex_cli_path;
+use crate::ai_cli::gemini::config::get_gemini_cli_path;
+use crate::ai_cli::kimi::config::get_kimi_cli_path;
+use crate::ai_cli::types::AiCliProvider;
+use crate::chat::detached::shell_escape;
+use crate::chat::env_file::load_worktree_env;
+use crate::claude_cli::get_cli_binary_path;
 use crate::projects::git::read_jean_config;
 
+/// Size of an AI CLI terminal until the UI attaches and fits it
+const AI_TERMINAL_COLS: u16 = 120;
+const AI_TERMINAL_ROWS: u16 = 40;
+
 /// Start a terminal
+///
+/// `scrollback_lines` / `scrollback_bytes` cap the output history kept for
+/// `get_terminal_scrollback` (defaults: 10k lines, 1MB). `record` writes the
+/// session to an asciinema cast file (see `get_terminal_recording_path`).
+/// `env` adds (or overrides) environment variables for the shell.
 #[tauri::command]
+#[allow(clippy::too_many_arguments)]
 pub async fn start_terminal(
     app: AppHandle,
     terminal_id: String,
@@ -16,6 +42,10 @@ pub async fn start_terminal(
     cols: u16,
     rows: u16,
     command: Option<String>,
+    env: Option<Vec<(String, String)>>,
+    scrollback_lines: Option<usize>,
+    scrollback_bytes: Option<usize>,
+    record: Option<bool>,
 ) -> Result<(), String> {
     log::trace!("start_terminal called for terminal: {terminal_id}");
 
@@ -24,7 +54,133 @@ pub async fn start_terminal(
         return Err("Terminal already exists".to_string());
     }
 
-    spawn_terminal(&app, terminal_id, worktree_path, cols, rows, command)
+    let scrollback = ScrollbackBuffer::new(
+        scrollback_lines.unwrap_or(DEFAULT_SCROLLBACK_LINES),
+        scrollback_bytes.unwrap_or(DEFAULT_SCROLLBACK_BYTES),
+    );
+    spawn_terminal(
+        &app,
+        terminal_id,
+        worktree_path,
+        cols,
+        rows,
+        command,
+        env.unwrap_or_default(),
+        scrollback,
+        record.unwrap_or(false),
+    )
+}
+
+/// Path of a provider's CLI, failing if it isn't installed
+fn ai_cli_path(app: &AppHandle, provider: &AiCliProvider) -> Result<PathBuf, String> {
+    let path = match provider {
+        AiCliProvider::Claude => get_cli_binary_path(app),
+        AiCliProvider::Gemini => get_gemini_cli_path(),
+        AiCliProvider::Codex => get_codex_cli_path(app),
+        AiCliProvider::Kimi => get_kimi_cli_path(),
+    }
+    .and_then(|path| {
+        if path.exists() {
+            Ok(path)
+        } else {
+            Err(format!("{} does not exist", path.display()))
+        }
+    });
+    path.map_err(|e| format!("{} not installed: {e}", provider.display_name()))
+}
+
+/// Shell command line starting a provider's CLI in interactive mode
+fn interactive_cli_command(
+    provider: &AiCliProvider,
+    cli_path: &Path,
+    model: Option<&str>,
+) -> Result<String, String> {
+    let cli_path = cli_path.to_str().ok_or("CLI path contains invalid UTF-8")?;
+    let mut parts = vec![shell_escape(cli_path)?];
+    if let Some(model) = model.filter(|m| !m.is_empty()) {
+        let flag = match provider {
+            AiCliProvider::Claude | AiCliProvider::Codex => "--model",
+            AiCliProvider::Gemini | AiCliProvider::Kimi => "-m",
+        };
+        parts.push(flag.to_string());
+        parts.push(shell_escape(model)?);
+    }
+    Ok(parts.join(" "))
+}
+
+/// Open a terminal running an AI CLI's interactive TUI in a worktree
+///
+/// Uses the same CLI binary and worktree env vars as chat runs. Returns the
+/// new terminal's ID; the UI attaches to it like a reconnected terminal and
+/// resizes it to fit. Fails if the provider's CLI isn't installed.
+#[tauri::command]
+pub async fn spawn_ai_terminal(
+    app: AppHandle,
+    worktree_path: String,
+    provider: String,
+    model: Option<String>,
+) -> Result<String, String> {
+    let cli = AiCliProvider::from_str(&provider)
+        .ok_or_else(|| format!("Unknown provider: {provider}"))?;
+    let cli_path = ai_cli_path(&app, &cli)?;
+    let command = interactive_cli_command(&cli, &cli_path, model.as_deref())?;
+
+    let (load_jean_env, load_project_dotenv) = crate::load_preferences(app.clone())
+        .await
+        .map(|prefs| (prefs.load_jean_env, prefs.load_project_dotenv))
+        .unwrap_or((false, false));
+    let env = load_worktree_env(
+        Path::new(&worktree_path),
+        load_jean_env,
+        load_project_dotenv,
+    );
+
+    let terminal_id = Uuid::new_v4().to_string();
+    log::trace!("Spawning {provider} terminal {terminal_id} in {worktree_path}");
+    spawn_terminal(
+        &app,
+        terminal_id.clone(),
+        worktree_path,
+        AI_TERMINAL_COLS,
+        AI_TERMINAL_ROWS,
+        Some(command),
+        env,
+        ScrollbackBuffer::new(DEFAULT_SCROLLBACK_LINES, DEFAULT_SCROLLBACK_BYTES),
+        false,
+    )?;
+    Ok(terminal_id)
+}
+
+/// Get the buffered output of a terminal so the UI can repaint it
+#[tauri::command]
+pub async fn get_terminal_scrollback(terminal_id: String) -> Result<String, String> {
+    get_scrollback(&terminal_id)
+}
+
+/// Get the last `last_n_lines` lines of a terminal's output as plain text
+///
+/// Used to paste terminal output (e.g. an error) into a chat prompt: escape
+/// codes are stripped and the result is capped at 64KB, keeping the end.
+#[tauri::command]
+pub async fn capture_terminal_output(
+    terminal_id: String,
+    last_n_lines: usize,
+) -> Result<String, String> {
+    if last_n_lines == 0 {
+        return Err("last_n_lines must be at least 1".to_string());
+    }
+    let scrollback = get_scrollback(&terminal_id)?;
+    Ok(plain_text_tail(
+        &scrollback,
+        last_n_lines,
+        MAX_CAPTURE_BYTES,
+    ))
+}
+
+/// Get the cast file path of a terminal started with `record`
+#[tauri::command]
+pub async fn get_terminal_recording_path(terminal_id: String) -> Result<String, String> {
+    get_recording_path(&terminal_id)
 }
 
 /// Get the run script from jean.json for a worktree
@@ -39,6 +195,13 @@ pub async fn terminal_write(terminal_id: String, data: String) -> Result<(), Str
     write_to_terminal(&terminal_id, &data)
 }
 
+/// Send a signal (e.g. Ctrl-C) to the command running in a terminal
+#[tauri::command]
+pub async fn signal_terminal(terminal_id: String, signal: TerminalSignal) -> Result<(), String> {
+    log::trace!("signal_terminal for {terminal_id}: {signal:?}");
+    pty_signal_terminal(&terminal_id, signal)
+}
+
 /// Resize a terminal
 #[tauri::command]
 pub async fn terminal_resize(terminal_id: String, cols: u16, rows: u16) -> Result<(), String> {
@@ -59,6 +222,12 @@ pub async fn get_active_terminals() -> Vec<String> {
     get_all_terminal_ids()
 }
 
+/// List all active terminal sessions, including ones the UI lost track of
+#[tauri::command]
+pub async fn list_terminals() -> Vec<TerminalInfo> {
+    list_terminal_info()
+}
+
 /// Check if a terminal exists
 #[tauri::command]
 pub async fn has_active_terminal(terminal_id: String) -> bool {
@@ -71,3 +240,25 @@ pub fn kill_all_terminals() -> usize {
     log::trace!("kill_all_terminals command invoked");
     pty_kill_all_terminals()
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_interactive_cli_command() {
+        let path = Path::new("/opt/my tools/claude");
+        assert_eq!(
+            interactive_cli_command(&AiCliProvider::Claude, path, Some("opus")).unwrap(),
+            "'/opt/my tools/claude' --model 'opus'"
+        );
+        assert_eq!(
+            interactive_cli_command(&AiCliProvider::Gemini, Path::new("gemini"), Some("")).unwrap(),
+            "'gemini'"
+        );
+        assert_eq!(
+            interactive_cli_command(&AiCliProvider::Kimi, Path::new("kimi"), Some("k2")).unwrap(),
+            "'kimi' -m 'k2'"
+        );
+    }
+}
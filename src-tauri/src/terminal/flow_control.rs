@@ -0,0 +1,191 @@
+//! Flow control for `terminal:output` events
+//!
+//! The PTY reader thread hands every read to an emitter thread, which
+//! coalesces reads arriving within `BATCH_WINDOW` (up to `BATCH_MAX_BYTES`)
+//! into a single event. Output past `THROTTLE_MAX_BYTES` per `THROTTLE_WINDOW`
+//! is dropped instead of emitted, with one "[output throttled]" marker, so a
+//! firehose like `yes` can't freeze the UI. The scrollback buffer is filled by
+//! the reader thread and still receives everything.
+
+use std::sync::mpsc::{Receiver, RecvTimeoutError};
+use std::time::{Duration, Instant};
+
+/// Maximum bytes coalesced into one output event (16KB)
+pub const BATCH_MAX_BYTES: usize = 16 * 1024;
+
+/// How long the emitter waits for more output before sending a batch
+pub const BATCH_WINDOW: Duration = Duration::from_millis(16);
+
+/// Window over which emitted output is rate limited
+pub const THROTTLE_WINDOW: Duration = Duration::from_secs(1);
+
+/// Maximum bytes emitted per throttle window (2MB)
+pub const THROTTLE_MAX_BYTES: usize = 2 * 1024 * 1024;
+
+/// Shown in place of the output dropped while throttled
+pub const THROTTLED_MARKER: &str = "\r\n\x1b[33m[output throttled]\x1b[0m\r\n";
+
+/// What to do with a batch of output
+#[derive(Debug, PartialEq, Eq)]
+pub enum ThrottleDecision {
+    /// Under the limit: emit the batch
+    Emit,
+    /// The batch went over the limit: emit the throttled marker instead
+    Throttle,
+    /// Already throttled in this window: drop the batch
+    Drop,
+}
+
+/// Rate limit on emitted output, measured over fixed windows
+pub struct OutputThrottle {
+    max_bytes: usize,
+    window: Duration,
+    window_start: Instant,
+    window_bytes: usize,
+    throttled: bool,
+}
+
+impl OutputThrottle {
+    pub fn new(max_bytes: usize, window: Duration, now: Instant) -> Self {
+        Self {
+            max_bytes,
+            window,
+            window_start: now,
+            window_bytes: 0,
+            throttled: false,
+        }
+    }
+
+    /// Count a batch of `len` bytes and decide whether it may be emitted
+    pub fn check(&mut self, len: usize, now: Instant) -> ThrottleDecision {
+        if now.duration_since(self.window_start) >= self.window {
+            self.window_start = now;
+            self.window_bytes = 0;
+            self.throttled = false;
+        }
+
+        self.window_bytes += len;
+        if self.window_bytes <= self.max_bytes {
+            ThrottleDecision::Emit
+        } else if self.throttled {
+            ThrottleDecision::Drop
+        } else {
+            self.throttled = true;
+            ThrottleDecision::Throttle
+        }
+    }
+}
+
+/// Decode the complete UTF-8 prefix of `bytes`, leaving a character cut off
+/// at the end in place so it can be completed by the next read
+fn take_utf8(bytes: &mut Vec<u8>) -> String {
+    let complete = match std::str::from_utf8(bytes) {
+        // `error_len` is None only for an incomplete sequence at the end
+        Err(e) if e.error_len().is_none() => e.valid_up_to(),
+        _ => bytes.len(),
+    };
+    let rest = bytes.split_off(complete);
+    let text = String::from_utf8_lossy(bytes).into_owned();
+    *bytes = rest;
+    text
+}
+
+/// Forward PTY output from `rx` to `emit` in batches, until the reader hangs up
+pub fn run_output_emitter(rx: Receiver<Vec<u8>>, mut emit: impl FnMut(String)) {
+    let mut throttle = OutputThrottle::new(THROTTLE_MAX_BYTES, THROTTLE_WINDOW, Instant::now());
+    let mut pending = Vec::new();
+
+    while let Ok(chunk) = rx.recv() {
+        pending.extend_from_slice(&chunk);
+
+        let deadline = Instant::now() + BATCH_WINDOW;
+        while pending.len() < BATCH_MAX_BYTES {
+            match rx.recv_timeout(deadline.saturating_duration_since(Instant::now())) {
+                Ok(chunk) => pending.extend_from_slice(&chunk),
+                Err(RecvTimeoutError::Timeout | RecvTimeoutError::Disconnected) => break,
+            }
+        }
+
+        match throttle.check(pending.len(), Instant::now()) {
+            ThrottleDecision::Emit => {
+                let data = take_utf8(&mut pending);
+                if !data.is_empty() {
+                    emit(data);
+                }
+            }
+            ThrottleDecision::Throttle => {
+                pending.clear();
+                emit(THROTTLED_MARKER.to_string());
+            }
+            ThrottleDecision::Drop => pending.clear(),
+        }
+    }
+
+    // A partial character left when the terminal closed
+    if !pending.is_empty() {
+        emit(String::from_utf8_lossy(&pending).into_owned());
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::sync::mpsc;
+
+    #[test]
+    fn test_throttle_drops_output_over_limit_until_next_window() {
+        let start = Instant::now();
+        let mut throttle = OutputThrottle::new(10, Duration::from_secs(1), start);
+
+        assert_eq!(throttle.check(6, start), ThrottleDecision::Emit);
+        assert_eq!(throttle.check(4, start), ThrottleDecision::Emit);
+        assert_eq!(throttle.check(1, start), ThrottleDecision::Throttle);
+        assert_eq!(throttle.check(1, start), ThrottleDecision::Drop);
+
+        let next_window = start + Duration::from_secs(1);
+        assert_eq!(throttle.check(10, next_window), ThrottleDecision::Emit);
+    }
+
+    #[test]
+    fn test_take_utf8_keeps_partial_char() {
+        let mut bytes = "abé".as_bytes().to_vec();
+        bytes.pop();
+        assert_eq!(take_utf8(&mut bytes), "ab");
+        assert_eq!(bytes, vec![0xc3]);
+
+        bytes.push(0xa9);
+        assert_eq!(take_utf8(&mut bytes), "é");
+        assert!(bytes.is_empty());
+    }
+
+    #[test]
+    fn test_emitter_coalesces_queued_reads() {
+        let (tx, rx) = mpsc::channel();
+        for chunk in ["one ", "two ", "three"] {
+            tx.send(chunk.as_bytes().to_vec()).unwrap();
+        }
+        drop(tx);
+
+        let mut events = Vec::new();
+        run_output_emitter(rx, |data| events.push(data));
+        assert_eq!(events, vec!["one two three".to_string()]);
+    }
+
+    #[test]
+    fn test_emitter_throttles_firehose_output() {
+        let (tx, rx) = mpsc::channel();
+        for _ in 0..(THROTTLE_MAX_BYTES * 2 / 4096) {
+            tx.send(vec![b'y'; 4096]).unwrap();
+        }
+        drop(tx);
+
+        let mut events = Vec::new();
+        run_output_emitter(rx, |data| events.push(data));
+
+        assert!(events.iter().all(|data| data.len() <= BATCH_MAX_BYTES));
+        let markers = events.iter().filter(|d| *d == THROTTLED_MARKER).count();
+        assert_eq!(markers, 1);
+        let emitted: usize = events.iter().map(String::len).sum();
+        assert!(emitted <= THROTTLE_MAX_BYTES + THROTTLED_MARKER.len());
+    }
+}
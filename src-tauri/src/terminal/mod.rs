@@ -1,6 +1,9 @@
 mod commands;
+mod flow_control;
 mod pty;
+mod recording;
 mod registry;
+mod scrollback;
 mod types;
 
 // Re-export commands for registration in lib.rs
@@ -1,20 +1,130 @@
 use portable_pty::{native_pty_system, CommandBuilder, PtySize};
 use std::io::Read;
-use std::sync::Mutex;
+use std::sync::{mpsc, Arc, Mutex};
 use std::thread;
 use tauri::{AppHandle, Emitter};
 
+use super::flow_control::run_output_emitter;
+use super::recording::{new_recording_path, CastRecorder};
 use super::registry::{register_terminal, unregister_terminal};
+use super::scrollback::ScrollbackBuffer;
 use super::types::{
-    TerminalOutputEvent, TerminalSession, TerminalStartedEvent, TerminalStoppedEvent,
+    TerminalOutputEvent, TerminalSession, TerminalSignal, TerminalStartedEvent,
+    TerminalStoppedEvent,
 };
 
+/// Shell used when the configured one can't be found
+#[cfg(unix)]
+const FALLBACK_SHELL: &str = "/bin/sh";
+#[cfg(windows)]
+const FALLBACK_SHELL: &str = "cmd.exe";
+
 /// Detect user's default shell (cross-platform)
-fn get_user_shell() -> String {
-    crate::platform::get_default_shell()
+///
+/// Returns the shell to launch, plus a warning when the configured shell is
+/// missing and the fallback is used instead.
+fn get_user_shell() -> Result<(String, Option<String>), String> {
+    resolve_shell(&crate::platform::get_default_shell(), FALLBACK_SHELL)
+}
+
+/// Check that `configured` exists and is executable, falling back if it isn't
+fn resolve_shell(configured: &str, fallback: &str) -> Result<(String, Option<String>), String> {
+    if which::which(configured).is_ok() {
+        return Ok((configured.to_string(), None));
+    }
+    if configured != fallback && which::which(fallback).is_ok() {
+        return Ok((
+            fallback.to_string(),
+            Some(format!(
+                "configured shell {configured} not found, falling back to {fallback}"
+            )),
+        ));
+    }
+    Err(format!(
+        "Configured shell {configured} not found and fallback shell {fallback} is unavailable"
+    ))
+}
+
+/// Wrap a command so the terminal stays open after it exits
+///
+/// The trailing `cat` keeps the shell alive for inspection; once it ends
+/// (Ctrl+D), the shell exits with the wrapped command's status so the real
+/// exit code reaches `terminal:stopped`. fish has no `$?`, so it gets its own
+/// syntax.
+fn wrap_run_command(shell: &str, run_command: &str) -> String {
+    let capture = if shell.ends_with("fish") {
+        "set __jean_status $status"
+    } else {
+        "__jean_status=$?"
+    };
+    format!(
+        "{run_command}; {capture}; echo ''; \
+         echo \"[Command exited with code $__jean_status. Press Ctrl+D to close]\"; \
+         cat; exit $__jean_status"
+    )
+}
+
+/// Map a child's exit status to a shell-style exit code
+///
+/// Processes killed by a signal report `128 + signal` like a shell would,
+/// falling back to portable-pty's generic code if the signal can't be identified.
+fn exit_code_from_status(status: &portable_pty::ExitStatus) -> i32 {
+    if status.success() {
+        return 0;
+    }
+    // portable-pty only exposes the signal through its Display impl
+    if let Some(signal) = status.to_string().strip_prefix("Terminated by ") {
+        if let Some(signum) = signal_number(signal) {
+            return 128 + signum;
+        }
+    }
+    status.exit_code() as i32
+}
+
+/// Recover a signal number from the description portable-pty produced with `strsignal`
+#[cfg(unix)]
+fn signal_number(description: &str) -> Option<i32> {
+    if let Some(num) = description.strip_prefix("Signal ") {
+        return num.trim().parse().ok();
+    }
+    (1..=64).find(|&signum| {
+        let name = unsafe { libc::strsignal(signum) };
+        !name.is_null()
+            && unsafe { std::ffi::CStr::from_ptr(name) }.to_string_lossy() == description
+    })
+}
+
+#[cfg(not(unix))]
+fn signal_number(_description: &str) -> Option<i32> {
+    None
+}
+
+/// Reject environment variables the OS can't represent
+fn validate_env_vars(env: &[(String, String)]) -> Result<(), String> {
+    for (key, value) in env {
+        if key.is_empty() {
+            return Err("Environment variable name cannot be empty".to_string());
+        }
+        if key.contains('=') || key.contains('\0') {
+            return Err(format!(
+                "Invalid environment variable name {key:?}: must not contain '=' or NUL"
+            ));
+        }
+        if value.contains('\0') {
+            return Err(format!(
+                "Invalid value for environment variable {key}: must not contain NUL"
+            ));
+        }
+    }
+    Ok(())
 }
 
 /// Spawn a terminal, optionally running a command
+///
+/// `scrollback` bounds the output history kept for `get_terminal_scrollback`.
+/// When `record` is set, output is also written to an asciinema cast file.
+/// `env` is applied after the built-in variables, so callers can override them.
+#[allow(clippy::too_many_arguments)]
 pub fn spawn_terminal(
     app: &AppHandle,
     terminal_id: String,
@@ -22,8 +132,19 @@ pub fn spawn_terminal(
     cols: u16,
     rows: u16,
     command: Option<String>,
+    env: Vec<(String, String)>,
+    scrollback: ScrollbackBuffer,
+    record: bool,
 ) -> Result<(), String> {
+    validate_env_vars(&env)?;
     log::trace!("Spawning terminal {terminal_id} at {worktree_path}");
+
+    // Resolve the shell before opening the PTY so a missing one fails fast
+    let (shell, shell_warning) = get_user_shell()?;
+    log::trace!("Using shell: {shell}");
+    if let Some(ref warning) = shell_warning {
+        log::warn!("Terminal {terminal_id}: {warning}");
+    }
     if let Some(ref cmd) = command {
         log::trace!("Running command: {cmd}");
     }
@@ -40,10 +161,6 @@ pub fn spawn_terminal(
         })
         .map_err(|e| format!("Failed to open PTY: {e}"))?;
 
-    // Get user's shell
-    let shell = get_user_shell();
-    log::trace!("Using shell: {shell}");
-
     // Build command - either run a specific command or start interactive shell
     let mut cmd = if let Some(ref run_command) = command {
         // Run the command in shell, then keep shell open for inspection
@@ -51,9 +168,7 @@ pub fn spawn_terminal(
         c.arg("-c");
         // Run the command; if it exits, show message and wait for user
         // Note: Caller is responsible for properly quoting paths with spaces
-        c.arg(format!(
-            "{run_command}; echo ''; echo '[Command finished. Press Ctrl+D to close]'; cat"
-        ));
+        c.arg(wrap_run_command(&shell, run_command));
         c
     } else {
         CommandBuilder::new(&shell)
@@ -62,6 +177,9 @@ pub fn spawn_terminal(
     cmd.env("TERM", "xterm-256color");
     cmd.env("COLORTERM", "truecolor");
     cmd.env("JEAN_WORKTREE_PATH", &worktree_path);
+    for (key, value) in &env {
+        cmd.env(key, value);
+    }
 
     // Spawn the shell
     let child = pair
@@ -83,7 +201,18 @@ pub fn spawn_terminal(
         .take_writer()
         .map_err(|e| format!("Failed to take writer: {e}"))?;
 
+    // Start recording before any output is read
+    let (mut recorder, recording_path) = if record {
+        let path = new_recording_path(app, &terminal_id)?;
+        let recorder = CastRecorder::create(&path, cols, rows)?;
+        log::trace!("Recording terminal {terminal_id} to {path:?}");
+        (Some(recorder), Some(path))
+    } else {
+        (None, None)
+    };
+
     // Register the session
+    let scrollback = Arc::new(Mutex::new(scrollback));
     let session = TerminalSession {
         terminal_id: terminal_id.clone(),
         master: pair.master,
@@ -91,6 +220,9 @@ pub fn spawn_terminal(
         child,
         cols,
         rows,
+        worktree_path: worktree_path.clone(),
+        scrollback: scrollback.clone(),
+        recording_path,
     };
     register_terminal(session);
 
@@ -104,6 +236,38 @@ pub fn spawn_terminal(
         log::error!("Failed to emit terminal:started event: {e}");
     }
 
+    // Tell the user their configured shell was replaced
+    if let Some(warning) = shell_warning {
+        let data = format!("\x1b[33m[{warning}]\x1b[0m\r\n");
+        if let Ok(mut scrollback) = scrollback.lock() {
+            scrollback.push(data.as_bytes());
+        }
+        let event = TerminalOutputEvent {
+            terminal_id: terminal_id.clone(),
+            data,
+        };
+        if let Err(e) = app.emit("terminal:output", &event) {
+            log::error!("Failed to emit terminal:output event: {e}");
+        }
+    }
+
+    // Output events are batched and throttled on their own thread, so a
+    // firehose can't flood the UI (see flow_control)
+    let (output_tx, output_rx) = mpsc::channel::<Vec<u8>>();
+    let emitter_app = app.clone();
+    let emitter_terminal_id = terminal_id.clone();
+    let emitter = thread::spawn(move || {
+        run_output_emitter(output_rx, |data| {
+            let event = TerminalOutputEvent {
+                terminal_id: emitter_terminal_id.clone(),
+                data,
+            };
+            if let Err(e) = emitter_app.emit("terminal:output", &event) {
+                log::error!("Failed to emit terminal:output event: {e}");
+            }
+        });
+    });
+
     // Spawn reader thread
     let app_clone = app.clone();
     let terminal_id_clone = terminal_id.clone();
@@ -117,15 +281,19 @@ pub fn spawn_terminal(
                     break;
                 }
                 Ok(n) => {
-                    // Convert bytes to string (lossy conversion for non-UTF8)
-                    let data = String::from_utf8_lossy(&buf[..n]).to_string();
-                    let event = TerminalOutputEvent {
-                        terminal_id: terminal_id_clone.clone(),
-                        data,
-                    };
-                    if let Err(e) = app_clone.emit("terminal:output", &event) {
-                        log::error!("Failed to emit terminal:output event: {e}");
+                    if let Ok(mut scrollback) = scrollback.lock() {
+                        scrollback.push(&buf[..n]);
+                    }
+
+                    if let Some(ref mut rec) = recorder {
+                        // Convert bytes to string (lossy conversion for non-UTF8)
+                        let data = String::from_utf8_lossy(&buf[..n]);
+                        if let Err(e) = rec.write_output(&data) {
+                            log::warn!("Stopping terminal recording: {e}");
+                            recorder = None;
+                        }
                     }
+                    let _ = output_tx.send(buf[..n].to_vec());
                 }
                 Err(e) => {
                     log::error!("Error reading from terminal: {e}");
@@ -134,16 +302,17 @@ pub fn spawn_terminal(
             }
         }
 
+        // Let the emitter flush the last batch before reporting the exit
+        drop(output_tx);
+        let _ = emitter.join();
+
         // Terminal has exited, get exit code and cleanup
         if let Some(mut session) = unregister_terminal(&terminal_id_clone) {
-            let exit_code = session.child.wait().ok().and_then(|s| {
-                if s.success() {
-                    Some(0)
-                } else {
-                    // portable-pty ExitStatus doesn't expose code directly
-                    None
-                }
-            });
+            let exit_code = session
+                .child
+                .wait()
+                .ok()
+                .map(|status| exit_code_from_status(&status));
 
             let stopped_event = TerminalStoppedEvent {
                 terminal_id: terminal_id_clone,
@@ -175,6 +344,64 @@ pub fn write_to_terminal(terminal_id: &str, data: &str) -> Result<(), String> {
     .ok_or_else(|| "Terminal not found".to_string())?
 }
 
+/// Send a signal to the command running in the foreground of a terminal
+///
+/// On Unix the signal goes to the PTY's foreground process group via
+/// `killpg`, which works even when the program has disabled the terminal's
+/// signal keys (raw mode). Otherwise Interrupt and Quit fall back to writing
+/// `Ctrl-C` / `Ctrl-\`, and Terminate fails since it has no control character.
+pub fn signal_terminal(terminal_id: &str, signal: TerminalSignal) -> Result<(), String> {
+    #[cfg(unix)]
+    {
+        let leader = super::registry::with_terminal(terminal_id, |session| {
+            session.master.process_group_leader()
+        })
+        .ok_or_else(|| "Terminal not found".to_string())?;
+
+        if let Some(pgid) = leader.filter(|&pgid| pgid > 0) {
+            if unsafe { libc::killpg(pgid, signal.signum()) } == 0 {
+                log::trace!("Sent {signal:?} to process group {pgid} of {terminal_id}");
+                return Ok(());
+            }
+            log::warn!(
+                "Failed to send {signal:?} to process group {pgid}: {}",
+                std::io::Error::last_os_error()
+            );
+        }
+    }
+
+    match signal.control_char() {
+        Some(control) => write_to_terminal(terminal_id, control),
+        None => Err(format!(
+            "Can't send {signal:?}: the terminal's foreground process is unknown"
+        )),
+    }
+}
+
+/// Get the buffered scrollback of a terminal
+pub fn get_scrollback(terminal_id: &str) -> Result<String, String> {
+    super::registry::with_terminal(terminal_id, |session| {
+        session
+            .scrollback
+            .lock()
+            .map(|scrollback| scrollback.contents())
+            .map_err(|e| format!("Failed to lock scrollback: {e}"))
+    })
+    .ok_or_else(|| "Terminal not found".to_string())?
+}
+
+/// Get the cast file path of a recorded terminal
+pub fn get_recording_path(terminal_id: &str) -> Result<String, String> {
+    super::registry::with_terminal(terminal_id, |session| {
+        session
+            .recording_path
+            .as_ref()
+            .map(|path| path.to_string_lossy().to_string())
+            .ok_or_else(|| "Terminal is not being recorded".to_string())
+    })
+    .ok_or_else(|| "Terminal not found".to_string())?
+}
+
 /// Resize a terminal
 pub fn resize_terminal(terminal_id: &str, cols: u16, rows: u16) -> Result<(), String> {
     super::registry::with_terminal(terminal_id, |session| {
@@ -204,13 +431,19 @@ pub fn kill_terminal(app: &AppHandle, terminal_id: &str) -> Result<bool, String>
             }
         }
 
-        // Wait for the process to exit
         let _ = session.child.kill();
 
+        // Wait for the process to exit so the real status is reported
+        let exit_code = session
+            .child
+            .wait()
+            .ok()
+            .map(|status| exit_code_from_status(&status));
+
         // Emit stopped event
         let stopped_event = TerminalStoppedEvent {
             terminal_id: terminal_id.to_string(),
-            exit_code: None,
+            exit_code,
         };
         if let Err(e) = app.emit("terminal:stopped", &stopped_event) {
             log::error!("Failed to emit terminal:stopped event: {e}");
@@ -251,3 +484,152 @@ pub fn kill_all_terminals() -> usize {
 
     count
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn env(pairs: &[(&str, &str)]) -> Vec<(String, String)> {
+        pairs
+            .iter()
+            .map(|(k, v)| (k.to_string(), v.to_string()))
+            .collect()
+    }
+
+    #[test]
+    fn test_validate_env_vars() {
+        assert!(validate_env_vars(&[]).is_ok());
+        assert!(validate_env_vars(&env(&[("NODE_ENV", "development"), ("TERM", "dumb")])).is_ok());
+        // Values may contain '='
+        assert!(validate_env_vars(&env(&[("OPTS", "a=b")])).is_ok());
+
+        assert!(validate_env_vars(&env(&[("", "x")])).is_err());
+        assert!(validate_env_vars(&env(&[("A=B", "x")])).is_err());
+        assert!(validate_env_vars(&env(&[("A\0", "x")])).is_err());
+        assert!(validate_env_vars(&env(&[("A", "x\0y")])).is_err());
+    }
+
+    #[test]
+    fn test_user_shell_falls_back_when_missing() {
+        let fallback = FALLBACK_SHELL;
+
+        let (shell, warning) = resolve_shell("/nonexistent/bin/fish", fallback).unwrap();
+        assert_eq!(shell, fallback);
+        assert_eq!(
+            warning.as_deref(),
+            Some(
+                format!(
+                    "configured shell /nonexistent/bin/fish not found, falling back to {fallback}"
+                )
+                .as_str()
+            )
+        );
+
+        let (shell, warning) = resolve_shell(fallback, fallback).unwrap();
+        assert_eq!(shell, fallback);
+        assert!(warning.is_none());
+
+        assert!(resolve_shell("/nonexistent/bin/fish", "/nonexistent/bin/sh").is_err());
+    }
+
+    #[test]
+    fn test_resolve_shell_falls_back_for_bogus_shell_env() {
+        // A $SHELL naming a missing binary, absolute or looked up on PATH
+        for configured in ["/nonexistent/bin/jean-shell", "jean-nonexistent-shell"] {
+            let (shell, warning) = resolve_shell(configured, FALLBACK_SHELL).unwrap();
+            assert_eq!(shell, FALLBACK_SHELL);
+            let warning = warning.expect("falling back should produce a warning");
+            assert!(warning.contains(configured), "{warning}");
+        }
+    }
+
+    #[test]
+    fn test_exit_code_from_status() {
+        use portable_pty::ExitStatus;
+
+        assert_eq!(exit_code_from_status(&ExitStatus::with_exit_code(0)), 0);
+        assert_eq!(exit_code_from_status(&ExitStatus::with_exit_code(2)), 2);
+        assert_eq!(
+            exit_code_from_status(&ExitStatus::with_signal("Signal 9")),
+            137
+        );
+    }
+
+    #[cfg(unix)]
+    #[test]
+    fn test_wrapped_command_exit_status() {
+        // stdin is closed, so `cat` returns immediately like Ctrl+D
+        let status = std::process::Command::new("/bin/sh")
+            .arg("-c")
+            .arg(wrap_run_command("/bin/sh", "sh -c 'exit 3'"))
+            .stdin(std::process::Stdio::null())
+            .stdout(std::process::Stdio::null())
+            .status()
+            .unwrap();
+        assert_eq!(status.code(), Some(3));
+
+        let status = portable_pty::ExitStatus::from(
+            std::process::Command::new("/bin/sh")
+                .arg("-c")
+                .arg("kill -TERM $$")
+                .status()
+                .unwrap(),
+        );
+        assert_eq!(exit_code_from_status(&status), 128 + libc::SIGTERM);
+    }
+
+    #[cfg(unix)]
+    #[test]
+    fn test_signal_terminal_interrupts_foreground_command() {
+        use super::super::registry::with_terminal;
+        use std::time::{Duration, Instant};
+
+        let pair = native_pty_system()
+            .openpty(PtySize {
+                rows: 24,
+                cols: 80,
+                pixel_width: 0,
+                pixel_height: 0,
+            })
+            .unwrap();
+        let mut cmd = CommandBuilder::new("sleep");
+        cmd.arg("30");
+        let child = pair.slave.spawn_command(cmd).unwrap();
+        let pid = child.process_id().map(|pid| pid as libc::pid_t);
+        let writer = pair.master.take_writer().unwrap();
+
+        let terminal_id = "test-signal-terminal".to_string();
+        register_terminal(TerminalSession {
+            terminal_id: terminal_id.clone(),
+            master: pair.master,
+            writer: Mutex::new(writer),
+            child,
+            cols: 80,
+            rows: 24,
+            worktree_path: String::new(),
+            scrollback: Arc::new(Mutex::new(ScrollbackBuffer::new(10, 1024))),
+            recording_path: None,
+        });
+
+        // The foreground group is only set once the child has started
+        let deadline = Instant::now() + Duration::from_secs(5);
+        while with_terminal(&terminal_id, |s| s.master.process_group_leader()).flatten() != pid {
+            assert!(Instant::now() < deadline, "child never became foreground");
+            thread::sleep(Duration::from_millis(10));
+        }
+
+        signal_terminal(&terminal_id, TerminalSignal::Terminate).unwrap();
+        let status = loop {
+            let status = with_terminal(&terminal_id, |s| s.child.try_wait().unwrap()).unwrap();
+            if let Some(status) = status {
+                break status;
+            }
+            assert!(Instant::now() < deadline, "child wasn't terminated");
+            thread::sleep(Duration::from_millis(10));
+        };
+        unregister_terminal(&terminal_id);
+
+        assert_eq!(exit_code_from_status(&status), 128 + libc::SIGTERM);
+        assert!(signal_terminal(&terminal_id, TerminalSignal::Interrupt).is_err());
+    }
+}
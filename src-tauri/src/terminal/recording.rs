@@ -0,0 +1,120 @@
+//! Terminal session recording in asciinema v2 `.cast` format
+//!
+//! The first line is a JSON header (`version`, `width`, `height`, `timestamp`),
+//! followed by one `[time, "o", data]` event per chunk of output, where `time`
+//! is seconds since the recording started.
+
+use std::fs::{self, File};
+use std::io::{BufWriter, Write};
+use std::path::{Path, PathBuf};
+use std::time::{Instant, SystemTime, UNIX_EPOCH};
+use tauri::{AppHandle, Manager};
+
+/// Writer for an asciinema v2 cast file
+pub struct CastRecorder {
+    writer: BufWriter<File>,
+    started: Instant,
+}
+
+impl CastRecorder {
+    /// Create the cast file and write its header
+    pub fn create(path: &Path, cols: u16, rows: u16) -> Result<Self, String> {
+        if let Some(parent) = path.parent() {
+            fs::create_dir_all(parent)
+                .map_err(|e| format!("Failed to create recordings directory: {e}"))?;
+        }
+        let file =
+            File::create(path).map_err(|e| format!("Failed to create recording file: {e}"))?;
+
+        let mut recorder = Self {
+            writer: BufWriter::new(file),
+            started: Instant::now(),
+        };
+        let timestamp = SystemTime::now()
+            .duration_since(UNIX_EPOCH)
+            .map(|d| d.as_secs())
+            .unwrap_or(0);
+        let header = serde_json::json!({
+            "version": 2,
+            "width": cols,
+            "height": rows,
+            "timestamp": timestamp,
+            "env": { "TERM": "xterm-256color" },
+        });
+        recorder.write_line(&header)?;
+
+        Ok(recorder)
+    }
+
+    /// Append an output event timed relative to the start of the recording
+    pub fn write_output(&mut self, data: &str) -> Result<(), String> {
+        let elapsed = self.started.elapsed().as_secs_f64();
+        self.write_line(&serde_json::json!([elapsed, "o", data]))
+    }
+
+    fn write_line(&mut self, value: &serde_json::Value) -> Result<(), String> {
+        writeln!(self.writer, "{value}").map_err(|e| format!("Failed to write recording: {e}"))?;
+        // Flush every event so the file is usable while the terminal is still running
+        self.writer
+            .flush()
+            .map_err(|e| format!("Failed to flush recording: {e}"))
+    }
+}
+
+/// Path for a new recording of `terminal_id` in `{app_data}/terminal-recordings/`
+pub fn new_recording_path(app: &AppHandle, terminal_id: &str) -> Result<PathBuf, String> {
+    let app_data_dir = app
+        .path()
+        .app_data_dir()
+        .map_err(|e| format!("Failed to get app data directory: {e}"))?;
+    let safe_id: String = terminal_id
+        .chars()
+        .map(|c| {
+            if c.is_alphanumeric() || c == '-' || c == '_' {
+                c
+            } else {
+                '-'
+            }
+        })
+        .collect();
+    let timestamp = SystemTime::now()
+        .duration_since(UNIX_EPOCH)
+        .map(|d| d.as_secs())
+        .unwrap_or(0);
+
+    Ok(app_data_dir
+        .join("terminal-recordings")
+        .join(format!("{safe_id}-{timestamp}.cast")))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_cast_file_format() {
+        let dir = tempfile::tempdir().unwrap();
+        let path = dir.path().join("nested").join("session.cast");
+
+        let mut recorder = CastRecorder::create(&path, 120, 40).unwrap();
+        recorder.write_output("$ ls\r\n").unwrap();
+        recorder.write_output("\u{1b}[32mok\u{1b}[0m").unwrap();
+
+        let content = fs::read_to_string(&path).unwrap();
+        let lines: Vec<serde_json::Value> = content
+            .lines()
+            .map(|l| serde_json::from_str(l).unwrap())
+            .collect();
+        assert_eq!(lines.len(), 3);
+
+        assert_eq!(lines[0]["version"], 2);
+        assert_eq!(lines[0]["width"], 120);
+        assert_eq!(lines[0]["height"], 40);
+        assert!(lines[0]["timestamp"].as_u64().unwrap() > 0);
+
+        assert_eq!(lines[1][1], "o");
+        assert_eq!(lines[1][2], "$ ls\r\n");
+        assert_eq!(lines[2][2], "\u{1b}[32mok\u{1b}[0m");
+        assert!(lines[2][0].as_f64().unwrap() >= lines[1][0].as_f64().unwrap());
+    }
+}
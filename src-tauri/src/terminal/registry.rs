@@ -2,7 +2,7 @@ use once_cell::sync::Lazy;
 use std::collections::HashMap;
 use std::sync::Mutex;
 
-use super::types::TerminalSession;
+use super::types::{TerminalInfo, TerminalSession};
 
 /// Global registry of active terminal sessions (terminal_id -> session)
 pub static TERMINAL_SESSIONS: Lazy<Mutex<HashMap<String, TerminalSession>>> =
@@ -32,6 +32,24 @@ pub fn get_all_terminal_ids() -> Vec<String> {
     sessions.keys().cloned().collect()
 }
 
+/// Get a summary of every active terminal session
+pub fn list_terminal_info() -> Vec<TerminalInfo> {
+    let sessions = TERMINAL_SESSIONS.lock().unwrap();
+    sessions
+        .values()
+        .map(|session| TerminalInfo {
+            terminal_id: session.terminal_id.clone(),
+            cols: session.cols,
+            rows: session.rows,
+            worktree_path: session.worktree_path.clone(),
+            alive: session
+                .child
+                .process_id()
+                .is_some_and(crate::platform::is_process_alive),
+        })
+        .collect()
+}
+
 /// Execute a function with mutable access to a terminal session
 pub fn with_terminal<F, R>(terminal_id: &str, f: F) -> Option<R>
 where
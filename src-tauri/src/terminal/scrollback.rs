@@ -0,0 +1,157 @@
+use std::collections::VecDeque;
+
+use crate::util::strip_ansi;
+
+/// Default maximum number of lines kept per terminal
+pub const DEFAULT_SCROLLBACK_LINES: usize = 10_000;
+
+/// Default maximum number of bytes kept per terminal (1MB)
+pub const DEFAULT_SCROLLBACK_BYTES: usize = 1024 * 1024;
+
+/// Maximum bytes of output returned by `capture_terminal_output` (64KB)
+pub const MAX_CAPTURE_BYTES: usize = 64 * 1024;
+
+/// Bounded ring buffer of recent terminal output
+///
+/// Keeps at most `max_lines` lines and `max_bytes` bytes. When either cap is
+/// exceeded, whole lines are dropped from the front so the remaining output
+/// starts at a line boundary (avoids cutting escape sequences mid-line).
+pub struct ScrollbackBuffer {
+    data: VecDeque<u8>,
+    /// Number of '\n' bytes currently in `data`
+    newlines: usize,
+    max_lines: usize,
+    max_bytes: usize,
+}
+
+impl ScrollbackBuffer {
+    pub fn new(max_lines: usize, max_bytes: usize) -> Self {
+        Self {
+            data: VecDeque::new(),
+            newlines: 0,
+            max_lines: max_lines.max(1),
+            max_bytes: max_bytes.max(1),
+        }
+    }
+
+    /// Append output bytes, trimming the oldest lines if over either cap
+    pub fn push(&mut self, bytes: &[u8]) {
+        self.data.extend(bytes);
+        self.newlines += bytes.iter().filter(|&&b| b == b'\n').count();
+
+        while self.data.len() > self.max_bytes || self.newlines > self.max_lines {
+            match self.data.iter().position(|&b| b == b'\n') {
+                Some(pos) => {
+                    self.data.drain(..=pos);
+                    self.newlines -= 1;
+                }
+                None => {
+                    // A single line larger than the byte cap: keep its tail
+                    let excess = self.data.len() - self.max_bytes;
+                    self.data.drain(..excess);
+                    break;
+                }
+            }
+        }
+    }
+
+    /// Buffered output as a string (lossy for non-UTF8 bytes)
+    pub fn contents(&self) -> String {
+        let (front, back) = self.data.as_slices();
+        let mut bytes = Vec::with_capacity(self.data.len());
+        bytes.extend_from_slice(front);
+        bytes.extend_from_slice(back);
+        String::from_utf8_lossy(&bytes).to_string()
+    }
+}
+
+/// Last `n` lines of raw terminal output as plain text
+///
+/// Escape codes are stripped and carriage returns resolved, so a line redrawn
+/// with `\r` (progress bars, spinners) keeps only its final text. Trailing
+/// blank lines don't count towards `n`. Output over `max_bytes` keeps its tail,
+/// starting at a line boundary unless a single line is larger than the cap.
+pub fn plain_text_tail(raw: &str, n: usize, max_bytes: usize) -> String {
+    let text = strip_ansi(raw);
+    let lines: Vec<&str> = text
+        .split('\n')
+        .map(|line| {
+            let line = line.strip_suffix('\r').unwrap_or(line);
+            line.rsplit('\r').next().unwrap_or(line)
+        })
+        .collect();
+
+    let end = lines
+        .iter()
+        .rposition(|line| !line.trim().is_empty())
+        .map_or(0, |i| i + 1);
+    let mut tail = lines[end.saturating_sub(n)..end].join("\n");
+
+    if tail.len() > max_bytes {
+        let mut cut = tail.len() - max_bytes;
+        while !tail.is_char_boundary(cut) {
+            cut += 1;
+        }
+        if let Some(newline) = tail[cut..].find('\n') {
+            cut += newline + 1;
+        }
+        tail = tail.split_off(cut);
+    }
+    tail
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_keeps_last_lines() {
+        let mut buffer = ScrollbackBuffer::new(3, 1024);
+        buffer.push(b"one\ntwo\nthree\n");
+        buffer.push(b"four\nfi");
+        buffer.push(b"ve\n");
+
+        assert_eq!(buffer.contents(), "three\nfour\nfive\n");
+    }
+
+    #[test]
+    fn test_byte_cap_drops_whole_lines() {
+        let mut buffer = ScrollbackBuffer::new(100, 10);
+        buffer.push(b"aaaa\nbbbb\ncc");
+
+        // 12 bytes > 10: the first line is dropped entirely
+        assert_eq!(buffer.contents(), "bbbb\ncc");
+    }
+
+    #[test]
+    fn test_oversized_single_line_keeps_tail() {
+        let mut buffer = ScrollbackBuffer::new(100, 4);
+        buffer.push(b"abcdefgh");
+
+        assert_eq!(buffer.contents(), "efgh");
+    }
+
+    #[test]
+    fn test_plain_text_tail() {
+        let raw = "$ cargo build\r\n\x1b[1m\x1b[32m   Compiling\x1b[0m jean\r\n\
+                   \x1b(B\x1b[31merror[E0425]\x1b[0m: not found\r\n\
+                   Building [=>  ] 1/3\rBuilding [===] 3/3\r\n\x1b]0;title\x07$ \r\n\r\n";
+
+        assert_eq!(
+            plain_text_tail(raw, 3, MAX_CAPTURE_BYTES),
+            "error[E0425]: not found\nBuilding [===] 3/3\n$ "
+        );
+        assert_eq!(
+            plain_text_tail(raw, 100, MAX_CAPTURE_BYTES).lines().count(),
+            5
+        );
+        assert_eq!(plain_text_tail(raw, 0, MAX_CAPTURE_BYTES), "");
+    }
+
+    #[test]
+    fn test_plain_text_tail_caps_size_at_line_boundary() {
+        assert_eq!(plain_text_tail("aaaa\nbbbb\ncc", 10, 8), "bbbb\ncc");
+        // A single oversized line keeps its tail at a char boundary
+        assert_eq!(plain_text_tail("ééééé", 1, 5), "éé");
+    }
+}
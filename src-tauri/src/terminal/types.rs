@@ -1,7 +1,10 @@
 use portable_pty::{Child, MasterPty};
 use serde::{Deserialize, Serialize};
 use std::io::Write;
-use std::sync::Mutex;
+use std::path::PathBuf;
+use std::sync::{Arc, Mutex};
+
+use super::scrollback::ScrollbackBuffer;
 
 /// Event payload for terminal output
 #[derive(Clone, Serialize, Deserialize)]
@@ -25,6 +28,49 @@ pub struct TerminalStoppedEvent {
     pub exit_code: Option<i32>,
 }
 
+/// Summary of an active terminal session, for reconciling UI state
+#[derive(Clone, Serialize, Deserialize)]
+pub struct TerminalInfo {
+    pub terminal_id: String,
+    pub cols: u16,
+    pub rows: u16,
+    pub worktree_path: String,
+    /// Whether the child process is still running
+    pub alive: bool,
+}
+
+/// Signal for the command running in a terminal
+#[derive(Debug, Clone, Copy, Serialize, Deserialize, PartialEq, Eq)]
+#[serde(rename_all = "lowercase")]
+pub enum TerminalSignal {
+    /// SIGINT (`Ctrl-C`)
+    Interrupt,
+    /// SIGQUIT (`Ctrl-\`)
+    Quit,
+    /// SIGTERM (no key sends it)
+    Terminate,
+}
+
+impl TerminalSignal {
+    /// Control character the terminal turns into this signal, if any
+    pub fn control_char(self) -> Option<&'static str> {
+        match self {
+            Self::Interrupt => Some("\x03"),
+            Self::Quit => Some("\x1c"),
+            Self::Terminate => None,
+        }
+    }
+
+    #[cfg(unix)]
+    pub fn signum(self) -> libc::c_int {
+        match self {
+            Self::Interrupt => libc::SIGINT,
+            Self::Quit => libc::SIGQUIT,
+            Self::Terminate => libc::SIGTERM,
+        }
+    }
+}
+
 /// Active terminal session state
 pub struct TerminalSession {
     pub terminal_id: String,
@@ -33,4 +79,9 @@ pub struct TerminalSession {
     pub child: Box<dyn Child + Send + Sync>,
     pub cols: u16,
     pub rows: u16,
+    pub worktree_path: String,
+    /// Recent output, shared with the reader thread so the UI can repaint
+    pub scrollback: Arc<Mutex<ScrollbackBuffer>>,
+    /// Path of the asciinema cast file, if this session is being recorded
+    pub recording_path: Option<PathBuf>,
 }
@@ -0,0 +1,61 @@
+//! Small helpers shared across modules
+
+use std::sync::atomic::{AtomicUsize, Ordering};
+
+use once_cell::sync::Lazy;
+
+/// Map `f` over `items` on a bounded number of scoped threads.
+/// Results are returned in the same order as `items`.
+pub fn parallel_map<I, T, F>(items: &[I], max_threads: usize, f: F) -> Vec<T>
+where
+    I: Sync,
+    T: Send,
+    F: Fn(&I) -> T + Sync,
+{
+    let threads = max_threads.min(items.len());
+    if threads <= 1 {
+        return items.iter().map(f).collect();
+    }
+
+    let next = AtomicUsize::new(0);
+    let mut results: Vec<(usize, T)> = std::thread::scope(|scope| {
+        let workers: Vec<_> = (0..threads)
+            .map(|_| {
+                scope.spawn(|| {
+                    let mut local = Vec::new();
+                    loop {
+                        let i = next.fetch_add(1, Ordering::Relaxed);
+                        let Some(item) = items.get(i) else {
+                            break;
+                        };
+                        local.push((i, f(item)));
+                    }
+                    local
+                })
+            })
+            .collect();
+
+        workers
+            .into_iter()
+            .flat_map(|worker| worker.join().unwrap())
+            .collect()
+    });
+
+    results.sort_by_key(|(i, _)| *i);
+    results.into_iter().map(|(_, result)| result).collect()
+}
+
+/// ANSI escape sequences: CSI (colors, cursor movement), OSC (hyperlinks, titles)
+/// and the short ones shells emit (charset selection, keypad mode, cursor save)
+static ANSI_ESCAPE: Lazy<regex::Regex> = Lazy::new(|| {
+    regex::Regex::new(concat!(
+        r"\x1b\[[0-9;?]*[ -/]*[@-~]|\x1b\][^\x07\x1b]*(?:\x07|\x1b\\)",
+        r"|\x1b[()][0-9A-Za-z]|\x1b[=>78]"
+    ))
+    .expect("valid ANSI regex")
+});
+
+/// Remove ANSI escape sequences from CLI or terminal output
+pub fn strip_ansi(text: &str) -> String {
+    ANSI_ESCAPE.replace_all(text, "").into_owned()
+}
@@ -103,6 +103,26 @@ function App() {
       logger.warn('Failed to cleanup old recovery files', { error })
     })
 
+    // Re-link sessions whose worktree index entry was lost (e.g. crash mid-write)
+    interface RecoveredSession {
+      session_id: string
+      worktree_id: string
+      name: string
+      index_rebuilt: boolean
+    }
+    invoke<RecoveredSession[]>('recover_orphaned_sessions')
+      .then(recovered => {
+        if (recovered.length > 0) {
+          logger.info('Recovered orphaned sessions', {
+            count: recovered.length,
+            sessions: recovered.map(s => s.session_id),
+          })
+        }
+      })
+      .catch(error => {
+        logger.warn('Failed to recover orphaned sessions', { error })
+      })
+
     // Check for and resume any detached Claude sessions that are still running
     interface ResumableSession {
       session_id: string
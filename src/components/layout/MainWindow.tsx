@@ -30,6 +30,8 @@ import { useUIStatePersistence } from '@/hooks/useUIStatePersistence'
 import { useSessionStatePersistence } from '@/hooks/useSessionStatePersistence'
 import { useRestoreLastArchived } from '@/hooks/useRestoreLastArchived'
 import { useArchiveCleanup } from '@/hooks/useArchiveCleanup'
+import { useIdleSessionArchive } from '@/hooks/useIdleSessionArchive'
+import { useAiCliUpdateNotifications } from '@/hooks/useAiCliUpdateNotifications'
 import {
   useAppFocusTracking,
   useGitStatusEvents,
@@ -135,6 +137,12 @@ export function MainWindow() {
   // Auto-cleanup old archived items on startup
   useArchiveCleanup()
 
+  // Archive sessions that have been idle too long
+  useIdleSessionArchive()
+
+  // Notify about outdated AI CLIs on startup
+  useAiCliUpdateNotifications()
+
   // Track app focus state for background task manager
   useAppFocusTracking()
 
@@ -49,7 +49,9 @@ import {
   editorOptions,
   gitPollIntervalOptions,
   remotePollIntervalOptions,
+  tailPollIntervalOptions,
   archiveRetentionOptions,
+  idleArchiveOptions,
   notificationSoundOptions,
   aiProviderOptions,
   getModelOptionsForProvider,
@@ -272,6 +274,38 @@ export const GeneralPane: React.FC = () => {
     }
   }
 
+  const handleTailPollIntervalChange = (value: string) => {
+    const ms = parseInt(value, 10)
+    if (preferences && !isNaN(ms)) {
+      savePreferences.mutate({ ...preferences, tail_poll_interval_ms: ms })
+    }
+  }
+
+  const handleLoadJeanEnvChange = (checked: boolean) => {
+    if (preferences) {
+      savePreferences.mutate({ ...preferences, load_jean_env: checked })
+    }
+  }
+
+  const handleLoadProjectDotenvChange = (checked: boolean) => {
+    if (preferences) {
+      savePreferences.mutate({ ...preferences, load_project_dotenv: checked })
+    }
+  }
+
+  const handleCompressRunLogsChange = (checked: boolean) => {
+    if (preferences) {
+      savePreferences.mutate({ ...preferences, compress_run_logs: checked })
+    }
+  }
+
+  const handleIdleArchiveChange = (value: string) => {
+    const days = parseInt(value, 10)
+    if (preferences && !isNaN(days)) {
+      savePreferences.mutate({ ...preferences, auto_archive_idle_days: days })
+    }
+  }
+
   const handleArchiveRetentionChange = (value: string) => {
     const days = parseInt(value, 10)
     if (preferences && !isNaN(days)) {
@@ -329,7 +363,11 @@ export const GeneralPane: React.FC = () => {
       const result = await queryClient.fetchQuery<GhAuthStatus>({ queryKey: ghCliQueryKeys.auth() })
 
       if (result?.authenticated) {
-        toast.success('GitHub CLI is already authenticated')
+        toast.success(
+          result.host
+            ? `GitHub CLI is already authenticated on ${result.host}`
+            : 'GitHub CLI is already authenticated'
+        )
         return
       }
     } finally {
@@ -360,7 +398,11 @@ export const GeneralPane: React.FC = () => {
       const result = await queryClient.fetchQuery<GlabAuthStatus>({ queryKey: glabCliQueryKeys.auth() })
 
       if (result?.authenticated) {
-        toast.success('GitLab CLI is already authenticated')
+        toast.success(
+          result.host
+            ? `GitLab CLI is already authenticated on ${result.host}`
+            : 'GitLab CLI is already authenticated'
+        )
         return
       }
     } finally {
@@ -840,6 +882,64 @@ export const GeneralPane: React.FC = () => {
               </SelectContent>
             </Select>
           </InlineField>
+
+          <InlineField
+            label="Load .jean.env"
+            description="Pass the worktree's .jean.env to AI tools (trusted repos only)"
+          >
+            <Switch
+              checked={preferences?.load_jean_env ?? false}
+              onCheckedChange={handleLoadJeanEnvChange}
+            />
+          </InlineField>
+
+          <InlineField
+            label="Load project .env"
+            description="Pass the worktree's .env to AI tools, not just .jean.env"
+          >
+            <Switch
+              checked={preferences?.load_project_dotenv ?? false}
+              onCheckedChange={handleLoadProjectDotenvChange}
+            />
+          </InlineField>
+
+          <InlineField
+            label="GitHub host"
+            description="GitHub Enterprise host (defaults to GH_HOST)"
+          >
+            <Input
+              className="w-40"
+              placeholder="github.com"
+              value={preferences?.github_host ?? ''}
+              onChange={e => {
+                if (preferences) {
+                  savePreferences.mutate({
+                    ...preferences,
+                    github_host: e.target.value,
+                  })
+                }
+              }}
+            />
+          </InlineField>
+
+          <InlineField
+            label="GitLab host"
+            description="Self-hosted GitLab host (defaults to GITLAB_HOST)"
+          >
+            <Input
+              className="w-40"
+              placeholder="gitlab.com"
+              value={preferences?.gitlab_host ?? ''}
+              onChange={e => {
+                if (preferences) {
+                  savePreferences.mutate({
+                    ...preferences,
+                    gitlab_host: e.target.value,
+                  })
+                }
+              }}
+            />
+          </InlineField>
         </div>
       </SettingsSection>
 
@@ -1035,6 +1135,27 @@ export const GeneralPane: React.FC = () => {
             </Select>
           </InlineField>
 
+          <InlineField
+            label="Output poll interval"
+            description="How often Codex and Kimi output is read while streaming"
+          >
+            <Select
+              value={String(preferences?.tail_poll_interval_ms ?? 50)}
+              onValueChange={handleTailPollIntervalChange}
+            >
+              <SelectTrigger className="w-40">
+                <SelectValue />
+              </SelectTrigger>
+              <SelectContent>
+                {tailPollIntervalOptions.map(option => (
+                  <SelectItem key={option.value} value={String(option.value)}>
+                    {option.label}
+                  </SelectItem>
+                ))}
+              </SelectContent>
+            </Select>
+          </InlineField>
+
         </div>
       </SettingsSection>
 
@@ -1130,6 +1251,37 @@ export const GeneralPane: React.FC = () => {
             </Select>
           </InlineField>
 
+          <InlineField
+            label="Auto-archive idle sessions"
+            description="On startup, archive sessions with no activity for this long"
+          >
+            <Select
+              value={String(preferences?.auto_archive_idle_days ?? 0)}
+              onValueChange={handleIdleArchiveChange}
+            >
+              <SelectTrigger className="w-48">
+                <SelectValue />
+              </SelectTrigger>
+              <SelectContent>
+                {idleArchiveOptions.map(option => (
+                  <SelectItem key={option.value} value={String(option.value)}>
+                    {option.label}
+                  </SelectItem>
+                ))}
+              </SelectContent>
+            </Select>
+          </InlineField>
+
+          <InlineField
+            label="Compress old transcripts"
+            description="Gzip session transcripts older than a week to save disk space"
+          >
+            <Switch
+              checked={preferences?.compress_run_logs ?? true}
+              onCheckedChange={handleCompressRunLogsChange}
+            />
+          </InlineField>
+
           <InlineField
             label="Delete all archives"
             description="Permanently delete all archived worktrees and sessions"
@@ -1,5 +1,5 @@
 import { memo } from 'react'
-import { Activity } from 'lucide-react'
+import { Activity, KeyRound } from 'lucide-react'
 import {
   Tooltip,
   TooltipContent,
@@ -12,6 +12,8 @@ import {
   formatResetTime,
   formatPacingDelta,
   getPacingDeltaColor,
+  isCredentialsExpiredError,
+  getRateLimitedResetsAt,
 } from '@/services/claude-usage'
 
 /**
@@ -21,6 +23,31 @@ import {
 export const ClaudeUsageSidebar = memo(function ClaudeUsageSidebar() {
   const { data: limits, isLoading, error } = useClaudeUsageLimits()
 
+  // Rate limited: say when usage data will be back
+  const rateLimitedResetsAt = getRateLimitedResetsAt(error)
+  if (rateLimitedResetsAt !== undefined) {
+    return (
+      <div className="flex items-center gap-1.5 px-2 py-1.5 text-xs text-muted-foreground">
+        <Activity className="size-3 shrink-0" />
+        <span>
+          {rateLimitedResetsAt
+            ? `Usage available in ${formatResetTime(rateLimitedResetsAt)}`
+            : 'Usage temporarily unavailable'}
+        </span>
+      </div>
+    )
+  }
+
+  // Expired login: prompt a re-auth instead of hiding the limits silently
+  if (isCredentialsExpiredError(error)) {
+    return (
+      <div className="flex items-center gap-1.5 px-2 py-1.5 text-xs text-yellow-500">
+        <KeyRound className="size-3 shrink-0" />
+        <span>Claude login expired. Run /login in Claude Code</span>
+      </div>
+    )
+  }
+
   // Don't render if loading, error, or no limits data
   if (isLoading || error || !limits) {
     return null
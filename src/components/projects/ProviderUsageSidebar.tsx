@@ -211,7 +211,12 @@ function WindowBadge({ window: w, label }: WindowBadgeProps) {
         <div className="space-y-0.5">
           <div className="font-medium capitalize">{label}</div>
           <div>Used: {w.usedPercent.toFixed(1)}%</div>
-          {w.resetDescription && <div>Resets: {w.resetDescription}</div>}
+          {w.resetDescription && (
+            <div>
+              Resets: {w.resetDescription}
+              {w.resetEstimated && ' (estimated)'}
+            </div>
+          )}
         </div>
       </TooltipContent>
     </Tooltip>
@@ -0,0 +1,47 @@
+import { useEffect, useRef } from 'react'
+import { toast } from 'sonner'
+import { useCliUpdates } from '@/services/ai-cli'
+import { logger } from '@/lib/logger'
+import type { AiCliProvider } from '@/types/ai-cli'
+
+const PROVIDER_NAMES: Record<AiCliProvider, string> = {
+  claude: 'Claude CLI',
+  gemini: 'Gemini CLI',
+  codex: 'Codex CLI',
+  kimi: 'Kimi CLI',
+}
+
+/**
+ * Hook that checks the installed AI CLIs for updates on startup.
+ *
+ * Shows one toast per outdated CLI. Claude is skipped: useCliVersionCheck
+ * already offers its update with an Update button.
+ */
+export function useAiCliUpdateNotifications() {
+  const { data: updates } = useCliUpdates()
+  const notifiedRef = useRef<Set<string>>(new Set())
+
+  useEffect(() => {
+    if (!updates) return
+
+    for (const update of updates) {
+      if (
+        update.provider === 'claude' ||
+        !update.updateAvailable ||
+        !update.latestVersion
+      ) {
+        continue
+      }
+
+      const key = `${update.provider}:${update.installedVersion}→${update.latestVersion}`
+      if (notifiedRef.current.has(key)) continue
+      notifiedRef.current.add(key)
+
+      logger.info('AI CLI update available', { update })
+      toast.info(`${PROVIDER_NAMES[update.provider]} update available`, {
+        id: `cli-update-${update.provider}`,
+        description: `v${update.installedVersion} → v${update.latestVersion}`,
+      })
+    }
+  }, [updates])
+}
@@ -0,0 +1,56 @@
+import { useEffect, useRef } from 'react'
+import { invoke } from '@tauri-apps/api/core'
+import { useQueryClient } from '@tanstack/react-query'
+import { usePreferences } from '@/services/preferences'
+import { chatQueryKeys } from '@/services/chat'
+import { logger } from '@/lib/logger'
+import { isTauri } from '@/services/projects'
+
+/**
+ * Hook to archive idle sessions on app startup.
+ *
+ * Runs based on the auto_archive_idle_days preference.
+ * Set to 0 to disable idle session archiving.
+ */
+export function useIdleSessionArchive() {
+  const queryClient = useQueryClient()
+  const { data: preferences } = usePreferences()
+  const hasRunRef = useRef(false)
+
+  useEffect(() => {
+    // Only run once on startup, and only when preferences are loaded
+    if (!preferences || hasRunRef.current || !isTauri()) {
+      return
+    }
+
+    hasRunRef.current = true
+
+    const idleDays = preferences.auto_archive_idle_days ?? 0
+    if (idleDays === 0) {
+      logger.debug('Idle session archiving is disabled (idle_days = 0)')
+      return
+    }
+
+    const runArchive = async () => {
+      try {
+        const archived = await invoke<string[]>('auto_archive_idle_sessions', {
+          idleDays,
+        })
+
+        if (archived.length > 0) {
+          queryClient.invalidateQueries({
+            queryKey: [...chatQueryKeys.all, 'sessions'],
+          })
+          queryClient.invalidateQueries({ queryKey: ['all-archived-sessions'] })
+          logger.info('Archived idle sessions', { count: archived.length })
+        } else {
+          logger.debug('No idle sessions to archive')
+        }
+      } catch (error) {
+        logger.error('Idle session archiving failed', { error: String(error) })
+      }
+    }
+
+    runArchive()
+  }, [preferences, queryClient])
+}
@@ -159,7 +159,11 @@ export async function attachToContainer(
       const ptyExists = await invoke<boolean>('has_active_terminal', { terminalId })
 
       if (ptyExists) {
-        // PTY exists - just resize and mark as running
+        // PTY exists - repaint buffered output, resize and mark as running
+        const scrollback = await invoke<string>('get_terminal_scrollback', {
+          terminalId,
+        }).catch(() => '')
+        if (scrollback) terminal.write(scrollback)
         useTerminalStore.getState().setTerminalRunning(terminalId, true)
         await invoke('terminal_resize', { terminalId, cols, rows }).catch(console.error)
       } else {
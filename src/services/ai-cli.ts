@@ -7,7 +7,17 @@
 
 import { invoke } from '@tauri-apps/api/core'
 import { useQuery, useMutation, useQueryClient } from '@tanstack/react-query'
-import type { AiCliStatus, AiCliAuthStatus } from '@/types/ai-cli'
+import { logger } from '@/lib/logger'
+import type {
+  AiCliProvider,
+  AllCliStatus,
+  AiCliStatus,
+  AiCliAuthStatus,
+  CliStatus,
+  CliUpdateStatus,
+  ModelInfo,
+  ProviderHealth,
+} from '@/types/ai-cli'
 
 // =============================================================================
 // Query Keys
@@ -16,34 +26,84 @@ import type { AiCliStatus, AiCliAuthStatus } from '@/types/ai-cli'
 export const aiCliQueryKeys = {
   all: ['ai-cli'] as const,
   gemini: {
-    status: ['ai-cli', 'gemini', 'status'] as const,
     auth: ['ai-cli', 'gemini', 'auth'] as const,
   },
   codex: {
-    status: ['ai-cli', 'codex', 'status'] as const,
     auth: ['ai-cli', 'codex', 'auth'] as const,
   },
   kimi: {
-    status: ['ai-cli', 'kimi', 'status'] as const,
     auth: ['ai-cli', 'kimi', 'auth'] as const,
   },
+  updates: ['ai-cli', 'updates'] as const,
+  allStatus: ['ai-cli', 'all', 'status'] as const,
+  models: (provider: string) => ['ai-cli', provider, 'models'] as const,
 }
 
 // =============================================================================
-// Gemini CLI Hooks
+// All CLIs
 // =============================================================================
 
-/** Check if Gemini CLI is installed */
-export function useGeminiCliStatus() {
+// Check if running in Tauri context (vs plain browser)
+const isTauri = () =>
+  typeof window !== 'undefined' && '__TAURI_INTERNALS__' in window
+
+const notInstalled: CliStatus = {
+  installed: false,
+  version: null,
+  path: null,
+  source: null,
+}
+
+const noneInstalled: AllCliStatus = {
+  gh: notInstalled,
+  glab: notInstalled,
+  claude: notInstalled,
+  codex: notInstalled,
+  gemini: notInstalled,
+  kimi: notInstalled,
+}
+
+/**
+ * Check which CLIs (gh, glab and the AI CLIs) are installed, in one call
+ *
+ * The per-CLI status hooks select from this query, so settings and onboarding
+ * share a single probe. After installing a CLI, invalidate
+ * `aiCliQueryKeys.allStatus`.
+ */
+export function useAllCliStatus<T = AllCliStatus>(
+  select?: (status: AllCliStatus) => T
+) {
   return useQuery({
-    queryKey: aiCliQueryKeys.gemini.status,
-    queryFn: async (): Promise<AiCliStatus> => {
-      return await invoke<AiCliStatus>('check_gemini_cli_installed')
+    queryKey: aiCliQueryKeys.allStatus,
+    queryFn: async (): Promise<AllCliStatus> => {
+      if (!isTauri()) {
+        logger.debug('Not in Tauri context, returning mock CLI status')
+        return noneInstalled
+      }
+
+      try {
+        return await invoke<AllCliStatus>('check_all_clis')
+      } catch (error) {
+        logger.error('Failed to check CLI installations', { error })
+        return noneInstalled
+      }
     },
-    staleTime: 60 * 1000, // 1 minute
+    select,
+    staleTime: 1000 * 60 * 5, // 5 minutes
+    gcTime: 1000 * 60 * 10, // 10 minutes
+    refetchInterval: 1000 * 60 * 60, // Re-check every hour
   })
 }
 
+// =============================================================================
+// Gemini CLI Hooks
+// =============================================================================
+
+/** Check if Gemini CLI is installed */
+export function useGeminiCliStatus() {
+  return useAllCliStatus((status): AiCliStatus => status.gemini)
+}
+
 /** Check if Gemini CLI is authenticated */
 export function useGeminiCliAuth(enabled = true) {
   return useQuery({
@@ -65,7 +125,7 @@ export function useInstallGeminiCli() {
       return await invoke<string>('install_gemini_cli')
     },
     onSuccess: () => {
-      queryClient.invalidateQueries({ queryKey: aiCliQueryKeys.gemini.status })
+      queryClient.invalidateQueries({ queryKey: aiCliQueryKeys.allStatus })
       queryClient.invalidateQueries({ queryKey: aiCliQueryKeys.gemini.auth })
     },
   })
@@ -77,13 +137,7 @@ export function useInstallGeminiCli() {
 
 /** Check if Codex CLI is installed */
 export function useCodexCliStatus() {
-  return useQuery({
-    queryKey: aiCliQueryKeys.codex.status,
-    queryFn: async (): Promise<AiCliStatus> => {
-      return await invoke<AiCliStatus>('check_codex_cli_installed')
-    },
-    staleTime: 60 * 1000, // 1 minute
-  })
+  return useAllCliStatus((status): AiCliStatus => status.codex)
 }
 
 /** Check if Codex CLI is authenticated */
@@ -107,7 +161,7 @@ export function useInstallCodexCli() {
       return await invoke<string>('install_codex_cli')
     },
     onSuccess: () => {
-      queryClient.invalidateQueries({ queryKey: aiCliQueryKeys.codex.status })
+      queryClient.invalidateQueries({ queryKey: aiCliQueryKeys.allStatus })
       queryClient.invalidateQueries({ queryKey: aiCliQueryKeys.codex.auth })
     },
   })
@@ -119,13 +173,7 @@ export function useInstallCodexCli() {
 
 /** Check if Kimi CLI is installed */
 export function useKimiCliStatus() {
-  return useQuery({
-    queryKey: aiCliQueryKeys.kimi.status,
-    queryFn: async (): Promise<AiCliStatus> => {
-      return await invoke<AiCliStatus>('check_kimi_cli_installed')
-    },
-    staleTime: 60 * 1000, // 1 minute
-  })
+  return useAllCliStatus((status): AiCliStatus => status.kimi)
 }
 
 /** Check if Kimi CLI is authenticated */
@@ -149,8 +197,54 @@ export function useInstallKimiCli() {
       return await invoke<string>('install_kimi_cli')
     },
     onSuccess: () => {
-      queryClient.invalidateQueries({ queryKey: aiCliQueryKeys.kimi.status })
+      queryClient.invalidateQueries({ queryKey: aiCliQueryKeys.allStatus })
       queryClient.invalidateQueries({ queryKey: aiCliQueryKeys.kimi.auth })
     },
   })
 }
+
+// =============================================================================
+// Update Checks
+// =============================================================================
+
+/** Check all installed AI CLIs for available updates (latest versions cached daily) */
+export function useCliUpdates() {
+  return useQuery({
+    queryKey: aiCliQueryKeys.updates,
+    queryFn: async (): Promise<CliUpdateStatus[]> => {
+      return await invoke<CliUpdateStatus[]>('check_all_cli_updates')
+    },
+    staleTime: 60 * 60 * 1000, // 1 hour
+  })
+}
+
+// =============================================================================
+// Model Catalog
+// =============================================================================
+
+/** Known models for a provider, with context window, vision and cost tier */
+export function useModelCatalog(provider: AiCliProvider) {
+  return useQuery({
+    queryKey: aiCliQueryKeys.models(provider),
+    queryFn: async (): Promise<ModelInfo[]> => {
+      return await invoke<ModelInfo[]>('list_models', { provider })
+    },
+    staleTime: Infinity, // Static table, only changes with an app update
+  })
+}
+
+// =============================================================================
+// Health Checks
+// =============================================================================
+
+/**
+ * Test a provider with a minimal real request (usage fetch or one-line prompt).
+ * Unlike the auth checks, this catches revoked keys and expired logins.
+ */
+export function useTestProvider() {
+  return useMutation({
+    mutationFn: async (provider: AiCliProvider): Promise<ProviderHealth> => {
+      return await invoke<ProviderHealth>('test_provider', { provider })
+    },
+  })
+}
@@ -17,6 +17,10 @@ import type {
   QuestionAnswer,
   ThinkingLevel,
   ExecutionMode,
+  TranscriptFormat,
+  ImportSummary,
+  CleanupReport,
+  TranscriptHit,
 } from '@/types/chat'
 import {
   isTauri,
@@ -24,6 +28,7 @@ import {
   useArchiveWorktree,
   useCloseBaseSessionClean,
 } from '@/services/projects'
+import { isChatError } from '@/types/chat'
 import { useChatStore } from '@/store/chat-store'
 import { useProjectsStore } from '@/store/projects-store'
 import type { Worktree } from '@/types/projects'
@@ -313,6 +318,46 @@ export function useCreateSession() {
   })
 }
 
+/**
+ * Hook to duplicate a session, branching its conversation into a new tab
+ */
+export function useDuplicateSession() {
+  const queryClient = useQueryClient()
+
+  return useMutation({
+    mutationFn: async ({
+      worktreeId,
+      sessionId,
+    }: {
+      worktreeId: string
+      sessionId: string
+    }): Promise<Session> => {
+      if (!isTauri()) {
+        throw new Error('Not in Tauri context')
+      }
+
+      logger.debug('Duplicating session', { worktreeId, sessionId })
+      const session = await invoke<Session>('duplicate_session', {
+        worktreeId,
+        sessionId,
+      })
+      logger.info('Session duplicated', { sessionId: session.id })
+      return session
+    },
+    onSuccess: (_, { worktreeId }) => {
+      queryClient.invalidateQueries({
+        queryKey: chatQueryKeys.sessions(worktreeId),
+      })
+    },
+    onError: error => {
+      const message =
+        error instanceof Error ? error.message : 'Unknown error occurred'
+      logger.error('Failed to duplicate session', { error })
+      toast.error('Failed to duplicate session', { description: message })
+    },
+  })
+}
+
 /**
  * Hook to rename a session tab
  */
@@ -358,6 +403,49 @@ export function useRenameSession() {
   })
 }
 
+/**
+ * Hook to regenerate a session's name from its transcript
+ * Useful when the conversation has drifted from its first message
+ */
+export function useRegenerateSessionName() {
+  const queryClient = useQueryClient()
+
+  return useMutation({
+    mutationFn: async ({
+      worktreeId,
+      sessionId,
+    }: {
+      worktreeId: string
+      sessionId: string
+    }): Promise<string> => {
+      if (!isTauri()) {
+        throw new Error('Not in Tauri context')
+      }
+
+      logger.debug('Regenerating session name', { sessionId })
+      const name = await invoke<string>('regenerate_session_name', {
+        worktreeId,
+        sessionId,
+      })
+      logger.info('Session name regenerated', { sessionId, name })
+      return name
+    },
+    onSuccess: (_, { worktreeId }) => {
+      queryClient.invalidateQueries({
+        queryKey: chatQueryKeys.sessions(worktreeId),
+      })
+    },
+    onError: error => {
+      const message =
+        error instanceof Error ? error.message : 'Unknown error occurred'
+      logger.error('Failed to regenerate session name', { error })
+      toast.error('Failed to regenerate session name', {
+        description: message,
+      })
+    },
+  })
+}
+
 /**
  * Hook to update session-specific UI state
  * Persists answered questions, fixed findings, permission denials, etc. to the session file
@@ -432,6 +520,45 @@ export function useUpdateSessionState() {
   })
 }
 
+/**
+ * Hook to discard a session's pending permission denials
+ * Unblocks a session waiting on a denied action the user no longer wants to answer
+ */
+export function useClearPendingDenials() {
+  const queryClient = useQueryClient()
+
+  return useMutation({
+    mutationFn: async ({
+      worktreeId,
+      sessionId,
+    }: {
+      worktreeId: string
+      sessionId: string
+    }): Promise<void> => {
+      if (!isTauri()) {
+        throw new Error('Not in Tauri context')
+      }
+
+      logger.debug('Clearing pending permission denials', { sessionId })
+      await invoke('clear_pending_denials', { worktreeId, sessionId })
+    },
+    onSuccess: (_, { worktreeId, sessionId }) => {
+      queryClient.invalidateQueries({
+        queryKey: chatQueryKeys.sessions(worktreeId),
+      })
+      queryClient.invalidateQueries({
+        queryKey: chatQueryKeys.session(sessionId),
+      })
+    },
+    onError: error => {
+      const message =
+        error instanceof Error ? error.message : 'Unknown error occurred'
+      logger.error('Failed to clear pending permission denials', { error })
+      toast.error('Failed to discard denied action', { description: message })
+    },
+  })
+}
+
 /**
  * Hook to close/delete a session tab
  * Returns the new active session ID (if any)
@@ -1057,6 +1184,7 @@ export function useSendMessage() {
       parallelExecutionPromptEnabled,
       aiLanguage,
       allowedTools,
+      kimiMaxIterations,
     }: {
       sessionId: string
       worktreeId: string
@@ -1070,6 +1198,8 @@ export function useSendMessage() {
       parallelExecutionPromptEnabled?: boolean
       aiLanguage?: string
       allowedTools?: string[]
+      /** Cap on Kimi's Ralph-loop iterations: -1 = unlimited or a positive count (default: derived from thinking level) */
+      kimiMaxIterations?: number
     }): Promise<ChatMessage> => {
       if (!isTauri()) {
         throw new Error('Not in Tauri context')
@@ -1089,6 +1219,7 @@ export function useSendMessage() {
         parallelExecutionPromptEnabled,
         aiLanguage,
         allowedTools,
+        kimiMaxIterations,
       })
       const response = await invoke<ChatMessage>('send_chat_message', {
         sessionId,
@@ -1103,6 +1234,7 @@ export function useSendMessage() {
         parallelExecutionPromptEnabled,
         aiLanguage,
         allowedTools,
+        kimiMaxIterations,
       })
       logger.info('Chat message sent', { responseId: response.id })
       return response
@@ -1230,7 +1362,8 @@ export function useSendMessage() {
       // Check for cancellation - Tauri errors may not be Error instances
       // so we check both the stringified error and the message property
       const errorStr = String(error)
-      const errorMessage = error instanceof Error ? error.message : ''
+      const errorMessage =
+        error instanceof Error || isChatError(error) ? error.message : ''
       const isCancellation =
         errorStr.includes('cancelled') || errorMessage.includes('cancelled')
 
@@ -1423,6 +1556,54 @@ export function useSetSessionModel() {
   })
 }
 
+/**
+ * Hook to set or clear the MCP config file for a session
+ */
+export function useSetSessionMcpConfig() {
+  const queryClient = useQueryClient()
+
+  return useMutation({
+    mutationFn: async ({
+      worktreeId,
+      worktreePath,
+      sessionId,
+      mcpConfigPath,
+    }: {
+      worktreeId: string
+      worktreePath: string
+      sessionId: string
+      mcpConfigPath: string | null
+    }): Promise<void> => {
+      if (!isTauri()) {
+        throw new Error('Not in Tauri context')
+      }
+
+      logger.debug('Setting session MCP config', { sessionId, mcpConfigPath })
+      await invoke('set_session_mcp_config', {
+        worktreeId,
+        worktreePath,
+        sessionId,
+        mcpConfigPath,
+      })
+      logger.info('Session MCP config saved')
+    },
+    onSuccess: (_, { sessionId, worktreeId }) => {
+      queryClient.invalidateQueries({
+        queryKey: chatQueryKeys.session(sessionId),
+      })
+      queryClient.invalidateQueries({
+        queryKey: chatQueryKeys.sessions(worktreeId),
+      })
+    },
+    onError: error => {
+      const message =
+        error instanceof Error ? error.message : 'Unknown error occurred'
+      logger.error('Failed to save MCP config', { error })
+      toast.error('Failed to save MCP config', { description: message })
+    },
+  })
+}
+
 /**
  * Hook to set the selected thinking level for a session
  */
@@ -1474,6 +1655,52 @@ export function useSetSessionThinkingLevel() {
   })
 }
 
+/**
+ * Hook to set the model and thinking level new sessions in a worktree start with
+ * Pass undefined to clear, falling back to the app preferences
+ */
+export function useSetWorktreeDefaultModel() {
+  const queryClient = useQueryClient()
+
+  return useMutation({
+    mutationFn: async ({
+      worktreeId,
+      model,
+      thinkingLevel,
+    }: {
+      worktreeId: string
+      model?: string
+      thinkingLevel?: ThinkingLevel
+    }): Promise<void> => {
+      if (!isTauri()) {
+        throw new Error('Not in Tauri context')
+      }
+
+      logger.debug('Setting worktree default model', {
+        worktreeId,
+        model,
+        thinkingLevel,
+      })
+      await invoke('set_worktree_default_model', {
+        worktreeId,
+        model,
+        thinkingLevel,
+      })
+    },
+    onSuccess: (_, { worktreeId }) => {
+      queryClient.invalidateQueries({
+        queryKey: chatQueryKeys.sessions(worktreeId),
+      })
+    },
+    onError: error => {
+      const message =
+        error instanceof Error ? error.message : 'Unknown error occurred'
+      logger.error('Failed to save worktree default model', { error })
+      toast.error('Failed to save default model', { description: message })
+    },
+  })
+}
+
 /**
  * Hook to set the selected model for a worktree (legacy)
  * @deprecated Use useSetSessionModel instead
@@ -1597,6 +1824,175 @@ export async function cancelChatMessage(
   }
 }
 
+/**
+ * Cancel a running chat request, giving the CLI `graceMs` to exit cleanly
+ * before it is force-killed (backend default: 3s)
+ */
+export async function cancelSession(
+  sessionId: string,
+  worktreeId: string,
+  graceMs?: number
+): Promise<boolean> {
+  if (!isTauri()) {
+    return false
+  }
+
+  try {
+    logger.debug('Cancelling session', { sessionId, worktreeId, graceMs })
+    return await invoke<boolean>('cancel_session', {
+      sessionId,
+      worktreeId,
+      graceMs,
+    })
+  } catch (error) {
+    logger.error('Failed to cancel session', { error, sessionId })
+    return false
+  }
+}
+
+/**
+ * Cancel every running chat session of a worktree, giving each CLI `graceMs`
+ * to exit cleanly before it is force-killed. Returns the number stopped.
+ */
+export async function cancelWorktreeSessions(
+  worktreeId: string,
+  graceMs?: number
+): Promise<number> {
+  if (!isTauri()) {
+    return 0
+  }
+
+  logger.debug('Cancelling worktree sessions', { worktreeId, graceMs })
+  return await invoke<number>('cancel_worktree_sessions', {
+    worktreeId,
+    graceMs,
+  })
+}
+
+/** Result of stopping every running session */
+export interface StopReport {
+  /** Number of sessions whose process was stopped */
+  stopped: number
+  /** Session IDs whose process was still alive after being force-killed */
+  refused: string[]
+}
+
+/**
+ * Stop every running chat session (e.g. before quitting or installing a CLI),
+ * giving each CLI `graceMs` to exit cleanly before it is force-killed
+ */
+export async function stopAllSessions(graceMs?: number): Promise<StopReport> {
+  if (!isTauri()) {
+    return { stopped: 0, refused: [] }
+  }
+
+  logger.debug('Stopping all sessions', { graceMs })
+  return await invoke<StopReport>('stop_all_sessions', { graceMs })
+}
+
+/**
+ * Attach a local file to a session as context. Its path and (truncated)
+ * content are included in the next message sent in that session.
+ * Binary files and files over 1MB are rejected.
+ */
+export async function attachFileContext(
+  sessionId: string,
+  filePath: string
+): Promise<void> {
+  if (!isTauri()) {
+    throw new Error('Not in Tauri context')
+  }
+
+  logger.debug('Attaching file context', { sessionId, filePath })
+  await invoke('attach_file_context', { sessionId, filePath })
+}
+
+/**
+ * Render a single run as a transcript (nothing is written to disk)
+ */
+export async function exportRunTranscript(
+  sessionId: string,
+  runId: string,
+  format: TranscriptFormat
+): Promise<string> {
+  return invoke<string>('export_run_transcript', { sessionId, runId, format })
+}
+
+/**
+ * Permanently delete sessions archived more than olderThanDays ago
+ * With dryRun, reports what would be deleted without deleting anything
+ */
+export async function cleanupArchivedSessions(
+  olderThanDays: number,
+  dryRun = false
+): Promise<CleanupReport> {
+  return invoke<CleanupReport>('cleanup_archived_sessions', {
+    olderThanDays,
+    dryRun,
+  })
+}
+
+/**
+ * Search all session transcripts (prompts and assistant text) for a substring
+ * Returns matching sessions, most recently active first
+ */
+export async function searchTranscripts(
+  query: string,
+  limit?: number
+): Promise<TranscriptHit[]> {
+  return invoke<TranscriptHit[]>('search_transcripts', { query, limit })
+}
+
+/**
+ * Re-attach to a session's detached run that is still running
+ * Replays its output as chat events, then keeps streaming until it exits.
+ * No-op if the run already finished or is already streaming.
+ */
+export async function resumeSessionStream(sessionId: string): Promise<void> {
+  return invoke<void>('resume_session_stream', { sessionId })
+}
+
+/**
+ * Bundle sessions into a .tar.gz archive (all worktrees when worktreeIds is omitted)
+ * Returns the path of the written archive
+ */
+export async function exportSessionsArchive(
+  worktreeIds?: string[]
+): Promise<string> {
+  return invoke<string>('export_sessions_archive', { worktreeIds })
+}
+
+/**
+ * Import a session archive, skipping sessions that already exist unless overwrite is set
+ * Sessions go to the local worktrees matching the ones they were exported from,
+ * or all into targetWorktreeId when given
+ */
+export async function importSessionsArchive(
+  path: string,
+  overwrite = false,
+  targetWorktreeId?: string
+): Promise<ImportSummary> {
+  return invoke<ImportSummary>('import_sessions_archive', {
+    path,
+    overwrite,
+    targetWorktreeId,
+  })
+}
+
+/**
+ * Import a session started in the Claude CLI as a new session in a worktree
+ * The new session resumes the same Claude conversation
+ */
+export async function importClaudeSession(
+  worktreeId: string,
+  claudeSessionId: string
+): Promise<Session> {
+  return invoke<Session>('import_claude_session', {
+    worktreeId,
+    claudeSessionId,
+  })
+}
+
 /**
  * Save a cancelled message to disk
  * Called when a streaming response is cancelled mid-stream
@@ -11,6 +11,7 @@ import { listen } from '@tauri-apps/api/event'
 import { toast } from 'sonner'
 import { useCallback, useEffect, useState } from 'react'
 import { logger } from '@/lib/logger'
+import { aiCliQueryKeys, useAllCliStatus } from '@/services/ai-cli'
 import type {
   ClaudeCliStatus,
   ClaudeAuthStatus,
@@ -25,7 +26,6 @@ const isTauri = () =>
 // Query keys for Claude CLI
 export const claudeCliQueryKeys = {
   all: ['claude-cli'] as const,
-  status: () => [...claudeCliQueryKeys.all, 'status'] as const,
   auth: () => [...claudeCliQueryKeys.all, 'auth'] as const,
   versions: () => [...claudeCliQueryKeys.all, 'versions'] as const,
 }
@@ -34,30 +34,7 @@ export const claudeCliQueryKeys = {
  * Hook to check if Claude CLI is installed and get its status
  */
 export function useClaudeCliStatus() {
-  return useQuery({
-    queryKey: claudeCliQueryKeys.status(),
-    queryFn: async (): Promise<ClaudeCliStatus> => {
-      if (!isTauri()) {
-        logger.debug('Not in Tauri context, returning mock CLI status')
-        return { installed: false, version: null, path: null }
-      }
-
-      try {
-        logger.debug('Checking Claude CLI installation status')
-        const status = await invoke<ClaudeCliStatus>(
-          'check_claude_cli_installed'
-        )
-        logger.info('Claude CLI status', { status })
-        return status
-      } catch (error) {
-        logger.error('Failed to check Claude CLI status', { error })
-        return { installed: false, version: null, path: null }
-      }
-    },
-    staleTime: 1000 * 60 * 5, // 5 minutes
-    gcTime: 1000 * 60 * 10, // 10 minutes
-    refetchInterval: 1000 * 60 * 60, // Re-check every hour
-  })
+  return useAllCliStatus((status): ClaudeCliStatus => status.claude)
 }
 
 /**
@@ -151,7 +128,7 @@ export function useInstallClaudeCli() {
     retry: false,
     onSuccess: () => {
       // Invalidate status to refetch
-      queryClient.invalidateQueries({ queryKey: claudeCliQueryKeys.status() })
+      queryClient.invalidateQueries({ queryKey: aiCliQueryKeys.allStatus })
       logger.info('Claude CLI installed successfully')
       toast.success('Claude CLI installed successfully')
     },
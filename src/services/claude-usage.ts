@@ -6,8 +6,10 @@
  * - Session usage (tokens, cost, context percentage) from local data
  */
 
+import { useEffect } from 'react'
 import { invoke } from '@tauri-apps/api/core'
-import { useQuery } from '@tanstack/react-query'
+import { listen } from '@tauri-apps/api/event'
+import { useQuery, useQueryClient } from '@tanstack/react-query'
 
 // ============================================================================
 // Types
@@ -23,14 +25,59 @@ export interface UsageLimits {
   sevenDay: UsageLimit | null
 }
 
+/**
+ * Validity of the stored Claude OAuth credentials
+ */
+export interface ClaudeAuthStatus {
+  valid: boolean
+  /** Token expiry as Unix milliseconds, if known */
+  expiresAt: number | null
+  /** Token has expired or is about to; the user should log in again */
+  needsRefresh: boolean
+}
+
+/**
+ * Error returned by get_claude_usage_limits
+ */
+export type UsageLimitsError =
+  | { kind: 'credentials_expired' }
+  | { kind: 'rate_limited'; resetsAt: string | null }
+  | { kind: 'other'; message: string }
+
 export interface SessionUsage {
   totalInputTokens: number
   totalOutputTokens: number
   totalCacheTokens: number
+  contextTokens: number
+  contextMaxTokens: number
   contextPercentage: number
   estimatedCostUsd: number
 }
 
+/**
+ * Usage totals for one local day
+ */
+export interface UsageDay {
+  /** YYYY-MM-DD */
+  date: string
+  sessions: number
+  costUsd: number
+  contextTokens: number
+}
+
+/**
+ * Usage across all sessions and projects, from the context hook's data files.
+ * Each session counts once, on the day it was last updated.
+ */
+export interface UsageSummary {
+  sessionCount: number
+  totalCostUsd: number
+  totalContextTokens: number
+  /** Oldest first */
+  days: UsageDay[]
+  skippedFiles: number
+}
+
 /**
  * Context data from Jean's Claude Code hook
  * Provides accurate context window tracking
@@ -45,6 +92,43 @@ export interface HookContextData {
   timestamp: string
 }
 
+/**
+ * Claude Code hook events the context-writer hook can run on
+ * - Stop: after each assistant response
+ * - PostToolUse: after each tool call (mid-turn updates for long runs)
+ */
+export type HookEvent = 'Stop' | 'PostToolUse'
+
+/**
+ * Result of rewriting ~/.claude/settings.json
+ * Settings with comments are backed up first, since the rewrite drops them
+ */
+export interface SettingsWriteResult {
+  backupPath: string | null
+  warning: string | null
+}
+
+/**
+ * Result of a single context hook health check
+ */
+export interface HookCheck {
+  id: 'registered' | 'script' | 'runtime' | 'context_dir'
+  ok: boolean
+  /** What was found, or what's missing (e.g. "Node.js not found on PATH") */
+  message: string
+}
+
+/**
+ * Health of the installed context-writer hook
+ */
+export interface HookHealth {
+  healthy: boolean
+  checks: HookCheck[]
+  /** Unix seconds when context data was last written, if ever */
+  lastDataWrittenAt: number | null
+  recentlyWritten: boolean
+}
+
 // ============================================================================
 // Query Keys
 // ============================================================================
@@ -53,11 +137,16 @@ export const claudeUsageQueryKeys = {
   all: ['claude-usage'] as const,
   limits: () => [...claudeUsageQueryKeys.all, 'limits'] as const,
   credentials: () => [...claudeUsageQueryKeys.all, 'credentials'] as const,
+  authStatus: () => [...claudeUsageQueryKeys.all, 'auth-status'] as const,
   session: (sessionId: string) =>
     [...claudeUsageQueryKeys.all, 'session', sessionId] as const,
   hookContext: (sessionId: string) =>
     [...claudeUsageQueryKeys.all, 'hook-context', sessionId] as const,
   hookInstalled: () => [...claudeUsageQueryKeys.all, 'hook-installed'] as const,
+  hookEvents: () => [...claudeUsageQueryKeys.all, 'hook-events'] as const,
+  hookHealth: () => [...claudeUsageQueryKeys.all, 'hook-health'] as const,
+  summary: (since: string, until: string) =>
+    [...claudeUsageQueryKeys.all, 'summary', since, until] as const,
 }
 
 // ============================================================================
@@ -94,6 +183,41 @@ export async function hasClaudeCredentials(): Promise<boolean> {
   return invoke<boolean>('has_claude_credentials')
 }
 
+/**
+ * Check whether the Claude OAuth token is valid or needs a re-login
+ */
+export async function checkClaudeAuth(): Promise<ClaudeAuthStatus> {
+  return invoke<ClaudeAuthStatus>('check_claude_auth')
+}
+
+/**
+ * Whether an error from getClaudeUsageLimits means the login expired
+ */
+export function isCredentialsExpiredError(error: unknown): boolean {
+  return (
+    typeof error === 'object' &&
+    error !== null &&
+    (error as UsageLimitsError).kind === 'credentials_expired'
+  )
+}
+
+/**
+ * When usage data will be available again, if the error is a rate limit
+ * Returns undefined for other errors and null when the reset time is unknown
+ */
+export function getRateLimitedResetsAt(
+  error: unknown
+): string | null | undefined {
+  if (
+    typeof error === 'object' &&
+    error !== null &&
+    (error as UsageLimitsError).kind === 'rate_limited'
+  ) {
+    return (error as { resetsAt: string | null }).resetsAt
+  }
+  return undefined
+}
+
 /**
  * Get context data from Jean's hook (if installed)
  * Returns null if hook is not installed or data not available
@@ -104,6 +228,39 @@ export async function getHookContextData(
   return invoke<HookContextData | null>('get_hook_context_data', { sessionId })
 }
 
+/**
+ * Get the latest hook context data for a Claude Code session ID (not Jean's).
+ * Returns null if nothing (complete) has been written for it yet.
+ */
+export async function getSessionContextData(
+  claudeSessionId: string
+): Promise<HookContextData | null> {
+  return invoke<HookContextData | null>('get_session_context_data', {
+    sessionId: claudeSessionId,
+  })
+}
+
+/**
+ * Get the latest hook context data of every session, newest first
+ */
+export async function listAllContextData(): Promise<HookContextData[]> {
+  return invoke<HookContextData[]>('list_all_context_data')
+}
+
+/**
+ * Get usage totals across all sessions, bucketed per day
+ * Reads local hook data, so it doesn't depend on the Anthropic API
+ *
+ * @param since - RFC 3339 start of the window (inclusive)
+ * @param until - RFC 3339 end of the window (exclusive)
+ */
+export async function getClaudeUsageSummary(
+  since: string,
+  until: string
+): Promise<UsageSummary> {
+  return invoke<UsageSummary>('get_claude_usage_summary', { since, until })
+}
+
 /**
  * Check if the context tracking hook is installed
  */
@@ -111,18 +268,37 @@ export async function isContextHookInstalled(): Promise<boolean> {
   return invoke<boolean>('is_context_hook_installed')
 }
 
+/**
+ * Get the Claude Code events the context tracking hook is installed for
+ */
+export async function getContextHookEvents(): Promise<HookEvent[]> {
+  return invoke<HookEvent[]>('get_context_hook_events')
+}
+
+/**
+ * Verify the context tracking hook can run (script, Bun/Node.js/Deno, data directory)
+ */
+export async function verifyContextHook(): Promise<HookHealth> {
+  return invoke<HookHealth>('verify_context_hook')
+}
+
 /**
  * Install the context tracking hook in Claude Code settings
+ * Registers for Stop only unless other events are given
  */
-export async function installContextHook(): Promise<void> {
-  return invoke<void>('install_context_hook')
+export async function installContextHook(
+  events?: HookEvent[]
+): Promise<SettingsWriteResult> {
+  return invoke<SettingsWriteResult>('install_context_hook', {
+    events: events ?? null,
+  })
 }
 
 /**
  * Uninstall the context tracking hook from Claude Code settings
  */
-export async function uninstallContextHook(): Promise<void> {
-  return invoke<void>('uninstall_context_hook')
+export async function uninstallContextHook(): Promise<SettingsWriteResult> {
+  return invoke<SettingsWriteResult>('uninstall_context_hook')
 }
 
 // ============================================================================
@@ -156,6 +332,19 @@ export function useHasClaudeCredentials() {
   })
 }
 
+/**
+ * Hook to check whether the Claude login has expired
+ */
+export function useClaudeAuthStatus() {
+  return useQuery({
+    queryKey: claudeUsageQueryKeys.authStatus(),
+    queryFn: checkClaudeAuth,
+    staleTime: 60_000, // 1 minute
+    refetchInterval: 5 * 60_000, // Poll every 5 minutes
+    retry: false,
+  })
+}
+
 /**
  * Hook to get session usage (tokens, cost, context percentage)
  *
@@ -187,8 +376,27 @@ export function useSessionUsage(
  *
  * This provides accurate context percentage when the hook is installed.
  * Returns null if hook is not set up or no data available.
+ * Refetches when the backend emits `context:update` after the hook writes.
  */
 export function useHookContextData(sessionId: string | null) {
+  const queryClient = useQueryClient()
+
+  useEffect(() => {
+    if (!sessionId) return
+
+    // The event is keyed by Claude's session ID, so just refetch; the query
+    // maps Jean's session ID and applies any context window override
+    const unlisten = listen<HookContextData>('context:update', () => {
+      queryClient.invalidateQueries({
+        queryKey: claudeUsageQueryKeys.hookContext(sessionId),
+      })
+    })
+
+    return () => {
+      unlisten.then(fn => fn())
+    }
+  }, [queryClient, sessionId])
+
   return useQuery({
     queryKey: sessionId
       ? claudeUsageQueryKeys.hookContext(sessionId)
@@ -201,7 +409,6 @@ export function useHookContextData(sessionId: string | null) {
     },
     enabled: !!sessionId,
     staleTime: 5_000, // 5 seconds - hook data updates after each response
-    refetchInterval: 10_000, // Poll every 10 seconds
   })
 }
 
@@ -216,6 +423,29 @@ export function useIsContextHookInstalled() {
   })
 }
 
+/**
+ * Hook to get usage totals across all sessions in a time window
+ */
+export function useClaudeUsageSummary(since: string, until: string) {
+  return useQuery({
+    queryKey: claudeUsageQueryKeys.summary(since, until),
+    queryFn: () => getClaudeUsageSummary(since, until),
+    staleTime: 60_000, // 1 minute
+  })
+}
+
+/**
+ * Hook to check whether the installed context hook can actually run
+ */
+export function useContextHookHealth(enabled = true) {
+  return useQuery({
+    queryKey: claudeUsageQueryKeys.hookHealth(),
+    queryFn: verifyContextHook,
+    enabled,
+    staleTime: 60_000, // 1 minute
+  })
+}
+
 // ============================================================================
 // Formatters
 // ============================================================================
@@ -11,6 +11,7 @@ import { listen } from '@tauri-apps/api/event'
 import { toast } from 'sonner'
 import { useCallback, useEffect, useState } from 'react'
 import { logger } from '@/lib/logger'
+import { aiCliQueryKeys, useAllCliStatus } from '@/services/ai-cli'
 import type {
   GhCliStatus,
   GhAuthStatus,
@@ -25,7 +26,6 @@ const isTauri = () =>
 // Query keys for GitHub CLI
 export const ghCliQueryKeys = {
   all: ['gh-cli'] as const,
-  status: () => [...ghCliQueryKeys.all, 'status'] as const,
   auth: () => [...ghCliQueryKeys.all, 'auth'] as const,
   versions: () => [...ghCliQueryKeys.all, 'versions'] as const,
 }
@@ -34,28 +34,7 @@ export const ghCliQueryKeys = {
  * Hook to check if GitHub CLI is installed and get its status
  */
 export function useGhCliStatus() {
-  return useQuery({
-    queryKey: ghCliQueryKeys.status(),
-    queryFn: async (): Promise<GhCliStatus> => {
-      if (!isTauri()) {
-        logger.debug('Not in Tauri context, returning mock gh CLI status')
-        return { installed: false, version: null, path: null }
-      }
-
-      try {
-        logger.debug('Checking GitHub CLI installation status')
-        const status = await invoke<GhCliStatus>('check_gh_cli_installed')
-        logger.info('GitHub CLI status', { status })
-        return status
-      } catch (error) {
-        logger.error('Failed to check GitHub CLI status', { error })
-        return { installed: false, version: null, path: null }
-      }
-    },
-    staleTime: 1000 * 60 * 5, // 5 minutes
-    gcTime: 1000 * 60 * 10, // 10 minutes
-    refetchInterval: 1000 * 60 * 60, // Re-check every hour
-  })
+  return useAllCliStatus((status): GhCliStatus => status.gh)
 }
 
 /**
@@ -67,7 +46,7 @@ export function useGhCliAuth(options?: { enabled?: boolean }) {
     queryFn: async (): Promise<GhAuthStatus> => {
       if (!isTauri()) {
         logger.debug('Not in Tauri context, returning mock gh auth status')
-        return { authenticated: false, error: 'Not in Tauri context' }
+        return { authenticated: false, error: 'Not in Tauri context', host: null }
       }
 
       try {
@@ -80,6 +59,7 @@ export function useGhCliAuth(options?: { enabled?: boolean }) {
         return {
           authenticated: false,
           error: error instanceof Error ? error.message : String(error),
+          host: null,
         }
       }
     },
@@ -149,7 +129,7 @@ export function useInstallGhCli() {
     retry: false,
     onSuccess: () => {
       // Invalidate status to refetch
-      queryClient.invalidateQueries({ queryKey: ghCliQueryKeys.status() })
+      queryClient.invalidateQueries({ queryKey: aiCliQueryKeys.allStatus })
       logger.info('GitHub CLI installed successfully')
       toast.success('GitHub CLI installed successfully')
     },
@@ -10,10 +10,13 @@ import {
   getGitPollInterval,
   triggerImmediateGitPoll,
   gitPull,
+  gitStashPush,
+  gitStashPop,
   setRemotePollInterval,
   getRemotePollInterval,
   triggerImmediateRemotePoll,
   getGitDiff,
+  getWorktreeStatusBatch,
   useGitStatus,
   type WorktreePollingInfo,
 } from './git-status'
@@ -209,6 +212,19 @@ describe('git-status service', () => {
     })
   })
 
+  describe('gitStashPush / gitStashPop', () => {
+    it('returns the stash commit and pops it by ref', async () => {
+      mockInvoke.mockResolvedValueOnce('dbf3ee69f3f0')
+      mockInvoke.mockResolvedValueOnce('')
+
+      const commit = await gitStashPush('/path/to/repo', 'wip')
+      await gitStashPop('/path/to/repo', commit)
+
+      expect(mockInvoke).toHaveBeenCalledWith('git_stash_push', { projectPath: '/path/to/repo', message: 'wip' })
+      expect(mockInvoke).toHaveBeenCalledWith('git_stash_pop', { projectPath: '/path/to/repo', stashRef: 'dbf3ee69f3f0' })
+    })
+  })
+
   describe('setRemotePollInterval', () => {
     it('calls invoke with seconds', async () => {
       mockInvoke.mockResolvedValueOnce(undefined)
@@ -285,6 +301,38 @@ describe('git-status service', () => {
     })
   })
 
+  describe('getWorktreeStatusBatch', () => {
+    it('calls invoke with worktree paths', async () => {
+      const mockStatuses = [
+        {
+          worktree_path: '/path/a',
+          current_branch: 'main',
+          upstream: 'origin/main',
+          ahead_count: 1,
+          behind_count: 0,
+          dirty_count: 3,
+          error: null,
+        },
+      ]
+      mockInvoke.mockResolvedValueOnce(mockStatuses)
+
+      const result = await getWorktreeStatusBatch(['/path/a'])
+
+      expect(result).toEqual(mockStatuses)
+      expect(mockInvoke).toHaveBeenCalledWith('get_worktree_status_batch', {
+        paths: ['/path/a'],
+      })
+    })
+
+    it('returns empty list when not in Tauri', async () => {
+      const { isTauri } = vi.mocked(await import('@/services/projects'))
+      isTauri.mockReturnValue(false)
+
+      await expect(getWorktreeStatusBatch(['/path/a'])).resolves.toEqual([])
+      expect(mockInvoke).not.toHaveBeenCalledWith('get_worktree_status_batch', expect.anything())
+    })
+  })
+
   describe('useGitStatus', () => {
     it('returns null data when no worktree ID', async () => {
       const { result } = renderHook(() => useGitStatus(null), {
@@ -46,6 +46,25 @@ export interface GitStatusEvent {
   unpushed_count: number
 }
 
+/**
+ * Compact status summary from get_worktree_status_batch
+ */
+export interface WorktreeStatus {
+  worktree_path: string
+  /** Checked out branch (null when HEAD is detached) */
+  current_branch: string | null
+  /** Upstream tracking branch, if configured */
+  upstream: string | null
+  /** Commits ahead of the upstream branch */
+  ahead_count: number
+  /** Commits behind the upstream branch */
+  behind_count: number
+  /** Changed, staged, conflicted and untracked files */
+  dirty_count: number
+  /** Set if git status failed for this worktree */
+  error: string | null
+}
+
 /**
  * Information needed to set up polling for a worktree
  */
@@ -59,6 +78,21 @@ export interface WorktreePollingInfo {
   prUrl?: string
 }
 
+/**
+ * A stash entry from `git stash list` (newest first)
+ */
+export interface StashEntry {
+  /** Positional ref (e.g. "stash@{0}"), shifts as stashes are pushed/popped */
+  stash_ref: string
+  /** Stash commit hash, stable for the lifetime of the stash */
+  commit: string
+  /** Branch the stash was created on (null for a detached HEAD) */
+  branch: string | null
+  message: string
+  /** Unix timestamp */
+  created_at: number
+}
+
 // ============================================================================
 // Commands
 // ============================================================================
@@ -158,6 +192,53 @@ export async function gitPush(worktreePath: string): Promise<string> {
   return invoke<string>('git_push', { worktreePath })
 }
 
+/**
+ * Stash uncommitted changes, including untracked files.
+ * Rejects when the working tree is clean.
+ *
+ * @param projectPath - Path to the worktree/repository
+ * @param message - Optional stash message
+ * @returns The stash commit hash, usable with gitStashPop
+ */
+export async function gitStashPush(
+  projectPath: string,
+  message?: string
+): Promise<string> {
+  if (!isTauri()) {
+    throw new Error('Git stash only available in Tauri')
+  }
+  return invoke<string>('git_stash_push', { projectPath, message })
+}
+
+/**
+ * Apply and drop a stash. Rejects on conflicts, keeping the stash.
+ * Only stashes made on the current branch can be popped.
+ *
+ * @param projectPath - Path to the worktree/repository
+ * @param stashRef - "stash@{n}" or stash commit hash, at least 7 characters
+ *   (defaults to the branch's latest)
+ * @returns Output from git stash pop
+ */
+export async function gitStashPop(
+  projectPath: string,
+  stashRef?: string
+): Promise<string> {
+  if (!isTauri()) {
+    throw new Error('Git stash only available in Tauri')
+  }
+  return invoke<string>('git_stash_pop', { projectPath, stashRef })
+}
+
+/**
+ * List the stashes made on the worktree's current branch (newest first).
+ */
+export async function gitStashList(projectPath: string): Promise<StashEntry[]> {
+  if (!isTauri()) {
+    return []
+  }
+  return invoke<StashEntry[]>('git_stash_list', { projectPath })
+}
+
 /**
  * Fetch git status for all worktrees in a project.
  *
@@ -227,6 +308,55 @@ export async function getGitDiff(
   })
 }
 
+/**
+ * Get compact status summaries for many worktrees at once.
+ *
+ * Results are returned in the same order as `worktreePaths`.
+ */
+export async function getWorktreeStatusBatch(
+  worktreePaths: string[]
+): Promise<WorktreeStatus[]> {
+  if (!isTauri()) {
+    return []
+  }
+  return invoke<WorktreeStatus[]>('get_worktree_status_batch', {
+    paths: worktreePaths,
+  })
+}
+
+/**
+ * Get the diff of everything a single run changed in its worktree.
+ *
+ * Compares the snapshot taken at run start with the one taken at run end
+ * (or the current working directory while the run is still in progress).
+ */
+export async function getRunDiff(
+  sessionId: string,
+  runId: string
+): Promise<GitDiff> {
+  if (!isTauri()) {
+    throw new Error('Run diff only available in Tauri')
+  }
+  return invoke<GitDiff>('get_run_diff', { sessionId, runId })
+}
+
+/**
+ * Revert everything a run changed, restoring the worktree to its pre-run state.
+ *
+ * Fails if files were changed after the run ended (manual edits are never
+ * discarded). `force` must be true to confirm the revert.
+ */
+export async function revertRunChanges(
+  sessionId: string,
+  runId: string,
+  force: boolean
+): Promise<void> {
+  if (!isTauri()) {
+    throw new Error('Run revert only available in Tauri')
+  }
+  await invoke('revert_run_changes', { sessionId, runId, force })
+}
+
 // ============================================================================
 // Query Keys
 // ============================================================================
@@ -2,6 +2,7 @@ import { useQuery } from '@tanstack/react-query'
 import { invoke } from '@tauri-apps/api/core'
 import { logger } from '@/lib/logger'
 import type {
+  GitHubCommentPage,
   GitHubIssue,
   GitHubIssueDetail,
   GitHubPullRequest,
@@ -19,6 +20,10 @@ export const githubQueryKeys = {
     [...githubQueryKeys.all, 'issues', projectPath, state] as const,
   issue: (projectPath: string, issueNumber: number) =>
     [...githubQueryKeys.all, 'issue', projectPath, issueNumber] as const,
+  issueSummary: (projectPath: string, issueNumber: number) =>
+    [...githubQueryKeys.all, 'issue-summary', projectPath, issueNumber] as const,
+  issueComments: (projectPath: string, issueNumber: number, page: number) =>
+    [...githubQueryKeys.all, 'issue-comments', projectPath, issueNumber, page] as const,
   loadedContexts: (worktreeId: string) =>
     [...githubQueryKeys.all, 'loaded-contexts', worktreeId] as const,
   prs: (projectPath: string, state: string) =>
@@ -102,6 +107,81 @@ export function useGitHubIssue(projectPath: string | null, issueNumber: number |
   })
 }
 
+/**
+ * Hook to get a GitHub issue without its comments (fast for long threads)
+ *
+ * @param projectPath - Path to the git repository
+ * @param issueNumber - Issue number to fetch
+ */
+export function useGitHubIssueSummary(projectPath: string | null, issueNumber: number | null) {
+  return useQuery({
+    queryKey: githubQueryKeys.issueSummary(projectPath ?? '', issueNumber ?? 0),
+    queryFn: async (): Promise<GitHubIssueDetail> => {
+      if (!isTauri() || !projectPath || !issueNumber) {
+        throw new Error('Missing required parameters')
+      }
+
+      try {
+        logger.debug('Fetching GitHub issue summary', { projectPath, issueNumber })
+        return await invoke<GitHubIssueDetail>('get_github_issue_summary', {
+          projectPath,
+          issueNumber,
+        })
+      } catch (error) {
+        logger.error('Failed to load GitHub issue summary', { error, projectPath, issueNumber })
+        throw error
+      }
+    },
+    enabled: !!projectPath && !!issueNumber,
+    staleTime: 1000 * 60 * 5, // 5 minutes
+    gcTime: 1000 * 60 * 15, // 15 minutes
+  })
+}
+
+/**
+ * Hook to load one page of a GitHub issue's comments on demand
+ *
+ * @param projectPath - Path to the git repository
+ * @param issueNumber - Issue number whose comments to fetch
+ * @param page - 1-based page number
+ * @param enabled - Set once the user opens the thread
+ */
+export function useGitHubIssueComments(
+  projectPath: string | null,
+  issueNumber: number | null,
+  page = 1,
+  enabled = true
+) {
+  return useQuery({
+    queryKey: githubQueryKeys.issueComments(projectPath ?? '', issueNumber ?? 0, page),
+    queryFn: async (): Promise<GitHubCommentPage> => {
+      if (!isTauri() || !projectPath || !issueNumber) {
+        throw new Error('Missing required parameters')
+      }
+
+      try {
+        logger.debug('Fetching GitHub issue comments', { projectPath, issueNumber, page })
+        return await invoke<GitHubCommentPage>('get_github_issue_comments', {
+          projectPath,
+          issueNumber,
+          page,
+        })
+      } catch (error) {
+        logger.error('Failed to load GitHub issue comments', {
+          error,
+          projectPath,
+          issueNumber,
+          page,
+        })
+        throw error
+      }
+    },
+    enabled: enabled && !!projectPath && !!issueNumber,
+    staleTime: 1000 * 60 * 5, // 5 minutes
+    gcTime: 1000 * 60 * 15, // 15 minutes
+  })
+}
+
 /**
  * Filter issues by search query (number, title, or body)
  *
@@ -395,6 +475,25 @@ export async function getPRContextContent(
   })
 }
 
+/**
+ * Post a comment (e.g. an AI review) on a GitHub PR or GitLab MR
+ *
+ * Returns the comment URL when the CLI reports one (GitHub only).
+ */
+export async function postPRComment(
+  projectPath: string,
+  provider: 'github' | 'gitlab',
+  prNumber: number,
+  body: string
+): Promise<string | null> {
+  return invoke<string | null>('post_pr_comment', {
+    projectPath,
+    provider,
+    prNumber,
+    body,
+  })
+}
+
 /**
  * Filter PRs by search query (number, title, or body)
  *
@@ -11,6 +11,7 @@ import { listen } from '@tauri-apps/api/event'
 import { toast } from 'sonner'
 import { useCallback, useEffect, useState } from 'react'
 import { logger } from '@/lib/logger'
+import { aiCliQueryKeys, useAllCliStatus } from '@/services/ai-cli'
 import type {
   GlabCliStatus,
   GlabAuthStatus,
@@ -25,7 +26,6 @@ const isTauri = () =>
 // Query keys for GitLab CLI
 export const glabCliQueryKeys = {
   all: ['glab-cli'] as const,
-  status: () => [...glabCliQueryKeys.all, 'status'] as const,
   auth: () => [...glabCliQueryKeys.all, 'auth'] as const,
   versions: () => [...glabCliQueryKeys.all, 'versions'] as const,
 }
@@ -34,28 +34,7 @@ export const glabCliQueryKeys = {
  * Hook to check if GitLab CLI is installed and get its status
  */
 export function useGlabCliStatus() {
-  return useQuery({
-    queryKey: glabCliQueryKeys.status(),
-    queryFn: async (): Promise<GlabCliStatus> => {
-      if (!isTauri()) {
-        logger.debug('Not in Tauri context, returning mock glab CLI status')
-        return { installed: false, version: null, path: null }
-      }
-
-      try {
-        logger.debug('Checking GitLab CLI installation status')
-        const status = await invoke<GlabCliStatus>('check_glab_cli_installed')
-        logger.info('GitLab CLI status', { status })
-        return status
-      } catch (error) {
-        logger.error('Failed to check GitLab CLI status', { error })
-        return { installed: false, version: null, path: null }
-      }
-    },
-    staleTime: 1000 * 60 * 5, // 5 minutes
-    gcTime: 1000 * 60 * 10, // 10 minutes
-    refetchInterval: 1000 * 60 * 60, // Re-check every hour
-  })
+  return useAllCliStatus((status): GlabCliStatus => status.glab)
 }
 
 /**
@@ -150,7 +129,7 @@ export function useInstallGlabCli() {
     retry: false,
     onSuccess: () => {
       // Invalidate status to refetch
-      queryClient.invalidateQueries({ queryKey: glabCliQueryKeys.status() })
+      queryClient.invalidateQueries({ queryKey: aiCliQueryKeys.allStatus })
       logger.info('GitLab CLI installed successfully')
       toast.success('GitLab CLI installed successfully')
     },
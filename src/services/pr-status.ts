@@ -5,12 +5,13 @@
  * Rust backend and cache them using TanStack Query.
  */
 
+import { invoke } from '@tauri-apps/api/core'
 import { listen, type UnlistenFn } from '@tauri-apps/api/event'
 import { useEffect } from 'react'
 import { useQuery, useQueryClient } from '@tanstack/react-query'
 
 import { isTauri, updateWorktreeCachedStatus } from '@/services/projects'
-import type { PrStatusEvent } from '@/types/pr-status'
+import type { PrStatusEvent, RateLimitBudget } from '@/types/pr-status'
 
 // ============================================================================
 // Query Keys
@@ -20,6 +21,7 @@ export const prStatusQueryKeys = {
   all: ['pr-status'] as const,
   worktree: (worktreeId: string) =>
     [...prStatusQueryKeys.all, worktreeId] as const,
+  rateLimit: () => [...prStatusQueryKeys.all, 'rate-limit'] as const,
 }
 
 // ============================================================================
@@ -96,3 +98,18 @@ export function usePrStatus(worktreeId: string | null) {
     staleTime: Infinity, // Never refetch automatically; data comes from events
   })
 }
+
+/**
+ * Hook to get the GitHub REST rate limit budget used by PR status polling.
+ *
+ * When `low` is true, polling serves cached PR statuses until the budget resets.
+ * Returns null until the backend has made its first GitHub API request.
+ */
+export function useGitHubRateLimit() {
+  return useQuery({
+    queryKey: prStatusQueryKeys.rateLimit(),
+    queryFn: () => invoke<RateLimitBudget | null>('get_github_rate_limit'),
+    enabled: isTauri(),
+    refetchInterval: 1000 * 60, // 1 minute
+  })
+}
@@ -104,6 +104,16 @@ describe('preferences service', () => {
         workspace_folder: '',
         default_ai_provider: 'claude',
         show_usage_status_bar: true,
+        context_window_overrides: {},
+        wsl_distro: null,
+        gemini_plain_text_output: false,
+        tail_poll_interval_ms: 50,
+        compress_run_logs: true,
+        auto_archive_idle_days: 0,
+        load_jean_env: false,
+        load_project_dotenv: false,
+        github_host: '',
+        gitlab_host: '',
       }
       vi.mocked(invoke).mockResolvedValueOnce(mockPreferences)
 
@@ -184,6 +194,16 @@ describe('preferences service', () => {
         workspace_folder: '',
         default_ai_provider: 'claude',
         show_usage_status_bar: true,
+        context_window_overrides: {},
+        wsl_distro: null,
+        gemini_plain_text_output: false,
+        tail_poll_interval_ms: 50,
+        compress_run_logs: true,
+        auto_archive_idle_days: 0,
+        load_jean_env: false,
+        load_project_dotenv: false,
+        github_host: '',
+        gitlab_host: '',
       }
       vi.mocked(invoke).mockResolvedValueOnce(prefsWithOldBinding)
 
@@ -239,6 +259,16 @@ describe('preferences service', () => {
         workspace_folder: '',
         default_ai_provider: 'claude',
         show_usage_status_bar: true,
+        context_window_overrides: {},
+        wsl_distro: null,
+        gemini_plain_text_output: false,
+        tail_poll_interval_ms: 50,
+        compress_run_logs: true,
+        auto_archive_idle_days: 0,
+        load_jean_env: false,
+        load_project_dotenv: false,
+        github_host: '',
+        gitlab_host: '',
       }
 
       const { result } = renderHook(() => useSavePreferences(), {
@@ -292,6 +322,16 @@ describe('preferences service', () => {
         workspace_folder: '',
         default_ai_provider: 'claude',
         show_usage_status_bar: true,
+        context_window_overrides: {},
+        wsl_distro: null,
+        gemini_plain_text_output: false,
+        tail_poll_interval_ms: 50,
+        compress_run_logs: true,
+        auto_archive_idle_days: 0,
+        load_jean_env: false,
+        load_project_dotenv: false,
+        github_host: '',
+        gitlab_host: '',
       }
 
       const { result } = renderHook(() => useSavePreferences(), {
@@ -345,6 +385,16 @@ describe('preferences service', () => {
         workspace_folder: '',
         default_ai_provider: 'claude',
         show_usage_status_bar: true,
+        context_window_overrides: {},
+        wsl_distro: null,
+        gemini_plain_text_output: false,
+        tail_poll_interval_ms: 50,
+        compress_run_logs: true,
+        auto_archive_idle_days: 0,
+        load_jean_env: false,
+        load_project_dotenv: false,
+        github_host: '',
+        gitlab_host: '',
       }
 
       const { result } = renderHook(() => useSavePreferences(), {
@@ -398,6 +448,16 @@ describe('preferences service', () => {
         workspace_folder: '',
         default_ai_provider: 'claude',
         show_usage_status_bar: true,
+        context_window_overrides: {},
+        wsl_distro: null,
+        gemini_plain_text_output: false,
+        tail_poll_interval_ms: 50,
+        compress_run_logs: true,
+        auto_archive_idle_days: 0,
+        load_jean_env: false,
+        load_project_dotenv: false,
+        github_host: '',
+        gitlab_host: '',
       }
 
       const { result } = renderHook(() => useSavePreferences(), {
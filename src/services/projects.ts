@@ -477,6 +477,106 @@ export function useCreateWorktreeFromExistingBranch() {
   })
 }
 
+/**
+ * Preview the branch/worktree name an issue would get, including the
+ * -2, -3, ... suffix when the name is already taken. Creates nothing.
+ */
+export async function previewBranchName(
+  projectPath: string,
+  provider: 'github' | 'gitlab',
+  iid: number,
+  title: string
+): Promise<string> {
+  if (!isTauri()) {
+    throw new Error('Not in Tauri context')
+  }
+
+  return invoke<string>('preview_branch_name', {
+    projectPath,
+    provider,
+    iid,
+    title,
+  })
+}
+
+/**
+ * Validate a user-edited branch name against git's ref-name rules.
+ * Resolves to null when valid, or the reason it is invalid.
+ */
+export async function validateBranchName(
+  name: string
+): Promise<string | null> {
+  if (!isTauri()) return null
+
+  try {
+    await invoke('validate_branch_name', { name })
+    return null
+  } catch (error) {
+    return typeof error === 'string' ? error : String(error)
+  }
+}
+
+/**
+ * Hook to create a worktree for a GitHub or GitLab issue in one step
+ *
+ * Unlike useCreateWorktree, the backend finishes creating the branch, worktree
+ * and issue context before resolving, and cleans up if any step fails.
+ */
+export function useCreateWorktreeForIssue() {
+  const queryClient = useQueryClient()
+
+  return useMutation({
+    mutationFn: async ({
+      projectId,
+      projectPath,
+      issueIid,
+      provider,
+    }: {
+      projectId: string
+      projectPath: string
+      issueIid: number
+      provider: 'github' | 'gitlab'
+    }): Promise<Worktree> => {
+      if (!isTauri()) {
+        throw new Error('Not in Tauri context')
+      }
+
+      logger.debug('Creating worktree for issue', { projectId, issueIid, provider })
+      return invoke<Worktree>('create_worktree_for_issue', {
+        projectPath,
+        issueIid,
+        provider,
+      })
+    },
+    onSuccess: (worktree, { projectId }) => {
+      logger.info('Worktree created for issue', {
+        id: worktree.id,
+        name: worktree.name,
+      })
+
+      queryClient.invalidateQueries({
+        queryKey: projectsQueryKeys.worktrees(projectId),
+      })
+
+      const { expandProject, selectWorktree } = useProjectsStore.getState()
+      expandProject(projectId)
+      selectWorktree(worktree.id)
+    },
+    onError: error => {
+      let message: string
+      if (error instanceof Error) {
+        message = error.message
+      } else if (typeof error === 'string') {
+        message = error
+      } else {
+        message = String(error)
+      }
+      logger.error('Failed to create worktree for issue', { error, message })
+      toast.error('Failed to create worktree', { description: message })
+    },
+  })
+}
+
 /**
  * Hook to handle the CMD+N keybinding for creating a new worktree.
  *
@@ -1618,6 +1718,37 @@ export async function updateWorktreeCachedStatus(
   })
 }
 
+/**
+ * Report stale git worktrees for a project without changing anything:
+ * worktrees whose directory is missing, or whose branch was deleted.
+ */
+export async function findStaleWorktrees(
+  projectPath: string
+): Promise<string[]> {
+  if (!isTauri()) return []
+
+  return invoke<string[]>('prune_stale_worktrees', { projectPath })
+}
+
+/**
+ * Prune worktrees whose directory is missing.
+ * Returns the paths that were removed.
+ */
+export async function removeStaleWorktrees(
+  projectPath: string
+): Promise<string[]> {
+  if (!isTauri()) {
+    throw new Error('Not in Tauri context')
+  }
+
+  logger.debug('Pruning stale worktrees', { projectPath })
+  const removed = await invoke<string[]>('remove_stale_worktrees', {
+    projectPath,
+  })
+  logger.info('Pruned stale worktrees', { count: removed.length })
+  return removed
+}
+
 // ============================================================================
 // Project Settings
 // ============================================================================
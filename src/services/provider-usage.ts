@@ -6,6 +6,7 @@
  * - Codex (via OpenAI RPC)
  * - Gemini (via Google Cloud API)
  * - Kimi (via Kimi API)
+ * - Anthropic API key (via rate-limit headers)
  */
 
 import { invoke } from '@tauri-apps/api/core'
@@ -20,6 +21,8 @@ export interface RateWindow {
   windowMinutes: number | null
   resetsAt: string | null
   resetDescription: string | null
+  /** True when resetsAt was inferred rather than reported by the provider */
+  resetEstimated: boolean
 }
 
 export interface ProviderUsageSnapshot {
@@ -38,9 +41,22 @@ export interface AllProvidersUsage {
   codex: ProviderUsageSnapshot | null
   gemini: ProviderUsageSnapshot | null
   kimi: ProviderUsageSnapshot | null
+  anthropicApi: ProviderUsageSnapshot | null
 }
 
-export type ProviderId = 'claude' | 'codex' | 'gemini' | 'kimi'
+export type ProviderId = 'claude' | 'codex' | 'gemini' | 'kimi' | 'anthropic-api'
+
+/** Pre-send estimate of a prompt's input tokens and cost */
+export interface TokenEstimate {
+  model: string
+  textTokens: number
+  attachmentTokens: number
+  imageCount: number
+  imageTokens: number
+  inputTokens: number
+  /** Input cost only; output length isn't known before sending */
+  estimatedCostUsd: number
+}
 
 // ============================================================================
 // Query Keys
@@ -73,6 +89,22 @@ export async function getAllProvidersUsage(): Promise<AllProvidersUsage> {
   return invoke<AllProvidersUsage>('get_all_providers_usage')
 }
 
+/**
+ * Estimate input tokens and cost for a message before sending it.
+ * Includes pasted images and, with a sessionId, files attached to the session.
+ */
+export async function estimatePromptTokens(
+  text: string,
+  model: string,
+  sessionId?: string
+): Promise<TokenEstimate> {
+  return invoke<TokenEstimate>('estimate_prompt_tokens', {
+    text,
+    model,
+    sessionId: sessionId ?? null,
+  })
+}
+
 // ============================================================================
 // Hooks
 // ============================================================================
@@ -3,6 +3,7 @@ import { invoke } from '@tauri-apps/api/core'
 import { logger } from '@/lib/logger'
 import { extractErrorMessage } from '@/lib/errors'
 import { isTauri } from '@/services/projects'
+import { useTerminalStore } from '@/store/terminal-store'
 
 // Query keys for terminal operations
 export const terminalQueryKeys = {
@@ -33,3 +34,53 @@ export function useStopTerminal() {
     },
   })
 }
+
+/** Signal for the command running in a terminal */
+export type TerminalSignal = 'interrupt' | 'quit' | 'terminate'
+
+/**
+ * Send a signal (e.g. Ctrl-C) to the command running in a terminal
+ *
+ * Works even when the program has disabled the terminal's signal keys.
+ */
+export async function signalTerminal(
+  terminalId: string,
+  signal: TerminalSignal
+): Promise<void> {
+  await invoke('signal_terminal', { terminalId, signal })
+}
+
+/**
+ * Get the last lines of a terminal's output as plain text, e.g. to paste an
+ * error into the next chat prompt
+ *
+ * Escape codes are stripped and the result is capped at 64KB.
+ */
+export async function captureTerminalOutput(
+  terminalId: string,
+  lastNLines: number
+): Promise<string> {
+  return invoke<string>('capture_terminal_output', { terminalId, lastNLines })
+}
+
+/**
+ * Open a terminal running an AI CLI's interactive TUI in a worktree
+ *
+ * The backend starts the CLI; the terminal panel then attaches to it like a
+ * reconnected terminal. Rejects if the CLI isn't installed.
+ */
+export async function openAiTerminal(
+  worktreeId: string,
+  worktreePath: string,
+  provider: 'claude' | 'codex' | 'gemini' | 'kimi',
+  model?: string
+): Promise<string> {
+  const terminalId = await invoke<string>('spawn_ai_terminal', {
+    worktreePath,
+    provider,
+    model: model ?? null,
+  })
+  const { addTerminal } = useTerminalStore.getState()
+  addTerminal(worktreeId, null, provider, terminalId)
+  return terminalId
+}
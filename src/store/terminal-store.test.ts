@@ -97,6 +97,15 @@ describe('TerminalStore', () => {
       expect(terminals[0]?.label).toBe('Dev Server')
     })
 
+    it('adds terminal with an existing id', () => {
+      const { addTerminal, getTerminals } = useTerminalStore.getState()
+
+      const id = addTerminal('worktree-1', null, 'Claude', 'pty-1')
+
+      expect(id).toBe('pty-1')
+      expect(getTerminals('worktree-1')[0]?.id).toBe('pty-1')
+    })
+
     it('removes a terminal', () => {
       const { addTerminal, removeTerminal, getTerminals } = useTerminalStore.getState()
 
@@ -29,7 +29,13 @@ interface TerminalState {
   setTerminalHeight: (height: number) => void
 
   // Terminal instance management
-  addTerminal: (worktreeId: string, command?: string | null, label?: string) => string
+  // Pass `id` to add a terminal whose PTY the backend already started
+  addTerminal: (
+    worktreeId: string,
+    command?: string | null,
+    label?: string,
+    id?: string
+  ) => string
   removeTerminal: (worktreeId: string, terminalId: string) => void
   setActiveTerminal: (worktreeId: string, terminalId: string) => void
   getTerminals: (worktreeId: string) => TerminalInstance[]
@@ -90,8 +96,12 @@ export const useTerminalStore = create<TerminalState>((set, get) => ({
 
   setTerminalHeight: height => set({ terminalHeight: height }),
 
-  addTerminal: (worktreeId, command = null, label) => {
-    const id = generateTerminalId()
+  addTerminal: (
+    worktreeId,
+    command = null,
+    label,
+    id = generateTerminalId()
+  ) => {
     const terminal: TerminalInstance = {
       id,
       worktreeId,
@@ -47,3 +47,51 @@ export interface AiCliAuthStatus {
   authenticated: boolean
   error: string | null
 }
+
+/** Result of a real request to a provider (see test_provider) */
+export interface ProviderHealth {
+  provider: AiCliProvider
+  ok: boolean
+  latencyMs: number
+  /** The provider's own error when the request failed */
+  error: string | null
+}
+
+/** Installation status of any CLI Jean uses (see check_all_clis) */
+export interface CliStatus {
+  installed: boolean
+  version: string | null
+  path: string | null
+  /** Installed by Jean ('embedded') or found on the system ('global') */
+  source: 'embedded' | 'global' | null
+}
+
+/** Installation status of every CLI, checked in one call */
+export interface AllCliStatus {
+  gh: CliStatus
+  glab: CliStatus
+  claude: CliStatus
+  codex: CliStatus
+  gemini: CliStatus
+  kimi: CliStatus
+}
+
+/** Update status for an installed AI CLI */
+export interface CliUpdateStatus {
+  provider: AiCliProvider
+  installedVersion: string
+  latestVersion: string | null
+  updateAvailable: boolean
+}
+
+export type ModelCostTier = 'low' | 'medium' | 'high'
+
+/** A known model for a provider, from the backend model catalog */
+export interface ModelInfo {
+  id: string
+  provider: AiCliProvider
+  displayName: string
+  contextWindow: number
+  supportsVision: boolean
+  costTier: ModelCostTier
+}
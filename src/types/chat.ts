@@ -24,6 +24,11 @@ export type ExecutionMode = 'plan' | 'build' | 'yolo'
 /** Cycle order for execution modes (used by Shift+Tab cycling) */
 export const EXECUTION_MODE_CYCLE: ExecutionMode[] = ['plan', 'build', 'yolo']
 
+/**
+ * Output format for run transcript export
+ */
+export type TranscriptFormat = 'markdown' | 'text' | 'json'
+
 /**
  * A tool call made by Claude during a response
  */
@@ -120,6 +125,8 @@ export interface Session {
   selected_model?: string
   /** Selected thinking level for this session */
   selected_thinking_level?: ThinkingLevel
+  /** MCP config file passed to Claude/Codex for this session */
+  mcp_config_path?: string
   /** Whether session naming has been attempted for this session */
   session_naming_completed?: boolean
   /** Unix timestamp when session was archived (undefined = not archived) */
@@ -173,6 +180,8 @@ export interface WorktreeSessions {
   active_session_id: string | null
   /** Default model for new sessions in this worktree */
   default_model?: string
+  /** Default thinking level for new sessions in this worktree */
+  default_thinking_level?: ThinkingLevel
   /** Storage format version for migrations */
   version: number
   /** Whether branch naming has been attempted for this worktree */
@@ -237,6 +246,16 @@ export interface ChunkEvent {
   content: string
 }
 
+/**
+ * Event payload for Kimi Ralph-loop progress from Rust
+ */
+export interface ProgressEvent {
+  session_id: string
+  worktree_id: string
+  completed_iterations: number
+  max_iterations: number | null // -1 = unlimited, null = Kimi default
+}
+
 /**
  * Event payload for tool use from Rust
  */
@@ -269,6 +288,56 @@ export interface CompactedEvent {
   metadata: CompactMetadata
 }
 
+/** Periodic event while a CLI is alive but has produced no output for a while */
+export interface HeartbeatEvent {
+  session_id: string
+  worktree_id: string // Kept for backward compatibility
+  /** Seconds since the CLI last produced output (or was started) */
+  silent_secs: number
+}
+
+/** A file change Codex proposed during a plan mode (read-only) run */
+export interface PlannedChange {
+  file_path: string
+  change_type: 'create' | 'edit' | 'delete' | 'rename' | string
+  /** Source path of a rename */
+  old_path?: string
+  /** Unified diff of the change, when Codex provided one */
+  diff?: string
+}
+
+/** Proposed changes of a plan mode run, emitted when the turn completes */
+export interface PlanEvent {
+  session_id: string
+  worktree_id: string
+  changes: PlannedChange[]
+}
+
+/** Codes of structured chat execution errors (mirrors ChatError in Rust) */
+export type ChatErrorCode =
+  | 'cli_not_installed'
+  | 'not_authenticated'
+  | 'startup_timeout'
+  | 'process_crashed'
+  | 'unsupported'
+  | 'invalid_request'
+  | 'io'
+
+/** Error returned by send_chat_message (serialized ChatError) */
+export interface ChatError {
+  code: ChatErrorCode
+  message: string
+}
+
+export function isChatError(error: unknown): error is ChatError {
+  return (
+    typeof error === 'object' &&
+    error !== null &&
+    'code' in error &&
+    'message' in error
+  )
+}
+
 /**
  * Event payload for errors from Rust
  */
@@ -276,6 +345,8 @@ export interface ErrorEvent {
   session_id: string
   worktree_id: string // Kept for backward compatibility
   error: string
+  /** Set for errors raised through the structured error path */
+  code?: ChatErrorCode
 }
 
 /**
@@ -315,6 +386,7 @@ export interface ToolResultEvent {
   worktree_id: string // Kept for backward compatibility
   tool_use_id: string
   output: string
+  success: boolean // false when the tool reported a failure
 }
 
 // ============================================================================
@@ -757,6 +829,73 @@ export interface SavedContextsResponse {
   contexts: SavedContext[]
 }
 
+/**
+ * Result from search_saved_contexts Tauri command
+ */
+export interface ContextSearchHit {
+  /** Filename (stable identifier, also the key for custom names) */
+  filename: string
+  /** Full path to the saved context file */
+  path: string
+  /** Optional custom display name */
+  name?: string
+  /** Number of matches in the file */
+  match_count: number
+  /** Excerpt around the first match */
+  snippet: string
+  /** UTF-16 ranges [start, end) of matches within snippet (use with String.slice) */
+  highlights: [number, number][]
+}
+
+/**
+ * Result from search_transcripts Tauri command (one per matching session)
+ */
+export interface TranscriptHit {
+  session_id: string
+  worktree_id: string
+  session_name: string
+  /** Most recent run containing a match (the snippet's source) */
+  run_id: string
+  /** Unix timestamp when that run started */
+  timestamp: number
+  /** Number of matches across the session's runs */
+  match_count: number
+  /** Excerpt around the first match in run_id */
+  snippet: string
+  /** UTF-16 ranges [start, end) of matches within snippet (use with String.slice) */
+  highlights: [number, number][]
+}
+
+/**
+ * Result from cleanup_archived_sessions Tauri command
+ */
+export interface CleanupReport {
+  /** True if nothing was deleted (counts describe what would be deleted) */
+  dry_run: boolean
+  /** Archived sessions deleted */
+  sessions_deleted: number
+  /** Worktree indexes that had at least one session deleted */
+  worktrees_affected: number
+  /** Bytes of session data freed */
+  bytes_reclaimed: number
+  /** IDs of the deleted sessions */
+  session_ids: string[]
+}
+
+/**
+ * Result from import_sessions_archive Tauri command
+ */
+export interface ImportSummary {
+  /** Sessions written into the store */
+  imported: number
+  /** Sessions skipped because they already exist */
+  skipped: number
+  /** Worktree indexes that received sessions */
+  worktrees: number
+  /** Sessions left out because no local worktree matches the one they came from */
+  unmatched: number
+}
+
 /**
  * Response from save_context_file Tauri command
  */
@@ -22,6 +22,8 @@ export interface GhAuthStatus {
   authenticated: boolean
   /** Error message if authentication check failed */
   error: string | null
+  /** GitHub host (github.com or a GitHub Enterprise host) */
+  host: string | null
 }
 
 /**
@@ -50,8 +50,8 @@ export interface DiffFile {
 
 /** Complete diff response */
 export interface GitDiff {
-  /** Type of diff: "uncommitted" or "branch" */
-  diff_type: 'uncommitted' | 'branch'
+  /** Type of diff: "uncommitted", "branch" or "run" (changes made by one agent run) */
+  diff_type: 'uncommitted' | 'branch' | 'run'
   /** Base ref (e.g., "origin/main" or "HEAD") */
   base_ref: string
   /** Target ref (e.g., "HEAD" or "working directory") */
@@ -38,6 +38,14 @@ export interface GitHubIssueDetail extends GitHubIssue {
   comments: GitHubComment[]
 }
 
+/** One page of an issue's comments, oldest first */
+export interface GitHubCommentPage {
+  comments: GitHubComment[]
+  /** 1-based page number */
+  page: number
+  hasMore: boolean
+}
+
 /**
  * Issue context to pass when creating a worktree
  * Uses camelCase to match Rust backend expectations
@@ -58,6 +66,8 @@ export interface LoadedIssueContext {
   commentCount: number
   repoOwner: string
   repoName: string
+  /** Changed upstream since loaded; only set when listed with a projectPath */
+  stale?: boolean
 }
 
 // =============================================================================
@@ -113,6 +123,8 @@ export interface LoadedPullRequestContext {
   reviewCount: number
   repoOwner: string
   repoName: string
+  /** Changed upstream since loaded; only set when listed with a projectPath */
+  stale?: boolean
 }
 
 // =============================================================================
@@ -46,6 +46,8 @@ export interface LoadedGitLabIssueContext {
   title: string
   noteCount: number
   projectPath: string
+  /** Changed upstream since loaded; only set when listed with a projectPath */
+  stale?: boolean
 }
 
 // =============================================================================
@@ -91,4 +93,6 @@ export interface LoadedGitLabMergeRequestContext {
   title: string
   noteCount: number
   projectPath: string
+  /** Changed upstream since loaded; only set when listed with a projectPath */
+  stale?: boolean
 }
@@ -41,3 +41,17 @@ export interface PrStatusEvent {
   mergeable: MergeableStatus | null
   checked_at: number
 }
+
+/**
+ * GitHub rate limit budget seen by PR status polling: the REST budget, or
+ * the GraphQL budget when that is the one running low
+ */
+export interface RateLimitBudget {
+  limit: number
+  remaining: number
+  /** Unix timestamp when the budget resets */
+  reset_at: number
+  /** Whether polling is backing off to cached statuses */
+  low: boolean
+  checked_at: number
+}
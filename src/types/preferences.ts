@@ -269,6 +269,16 @@ export interface AppPreferences {
   workspace_folder: string // Base folder for worktrees (empty = default ~/jean/)
   default_ai_provider: AiCliProvider // Default AI CLI provider
   show_usage_status_bar: boolean // Show Claude usage status bar (cost, context, limits)
+  context_window_overrides: Record<string, number> // Per-model context window size overrides (model -> tokens)
+  wsl_distro: string | null // WSL distribution to run Claude in on Windows (null = default distro)
+  gemini_plain_text_output: boolean // Run Gemini with plain text output instead of stream-json
+  tail_poll_interval_ms: number // Codex/Kimi output polling interval in ms (10-200, backs off while idle)
+  compress_run_logs: boolean // Gzip finished run logs older than a week on startup
+  auto_archive_idle_days: number // Archive sessions idle this many days on startup (0 = disabled)
+  load_jean_env: boolean // Pass the worktree's .jean.env to AI CLIs
+  load_project_dotenv: boolean // Also pass the worktree's .env (not just .jean.env) to AI CLIs
+  github_host: string // GitHub Enterprise host (empty = GH_HOST env var, else github.com only)
+  gitlab_host: string // Self-hosted GitLab host (empty = GITLAB_HOST env var, else gitlab.com only)
 }
 
 export type FileEditMode = 'inline' | 'external'
@@ -469,6 +479,14 @@ export const remotePollIntervalOptions: { value: number; label: string }[] = [
   { value: 600, label: '10 minutes' },
 ]
 
+// Output poll interval options (milliseconds) - how often Codex/Kimi output is read
+export const tailPollIntervalOptions: { value: number; label: string }[] = [
+  { value: 20, label: '20 ms (snappier)' },
+  { value: 50, label: '50 ms' },
+  { value: 100, label: '100 ms' },
+  { value: 200, label: '200 ms (battery saver)' },
+]
+
 // Archive retention options (days) - how long to keep archived items
 export const archiveRetentionOptions: { value: number; label: string }[] = [
   { value: 0, label: 'Never (keep forever)' },
@@ -479,6 +497,15 @@ export const archiveRetentionOptions: { value: number; label: string }[] = [
   { value: 90, label: '90 days' },
 ]
 
+// Idle session auto-archive options (days without activity)
+export const idleArchiveOptions: { value: number; label: string }[] = [
+  { value: 0, label: 'Never' },
+  { value: 7, label: '7 days' },
+  { value: 14, label: '14 days' },
+  { value: 30, label: '30 days' },
+  { value: 60, label: '60 days' },
+]
+
 // Syntax highlighting themes (from shiki bundled themes)
 export type SyntaxTheme =
   | 'vitesse-black'
@@ -575,4 +602,14 @@ export const defaultPreferences: AppPreferences = {
   workspace_folder: '', // Default: empty means ~/jean/
   default_ai_provider: 'claude', // Default: Claude
   show_usage_status_bar: true, // Default: show usage status bar
+  context_window_overrides: {}, // Default: no overrides (200k window)
+  wsl_distro: null, // Default: WSL's default distribution
+  gemini_plain_text_output: false, // Default: stream-json
+  tail_poll_interval_ms: 50, // Default: 20 polls/second
+  compress_run_logs: true, // Default: gzip run logs older than a week
+  auto_archive_idle_days: 0, // Default: never auto-archive
+  load_jean_env: false, // Default: off, the repository may not be trusted
+  load_project_dotenv: false, // Default: only .jean.env, keep project secrets out
+  github_host: '', // Default: GH_HOST, if set
+  gitlab_host: '', // Default: GITLAB_HOST, if set
 }
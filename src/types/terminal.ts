@@ -9,6 +9,14 @@ export interface TerminalStartedEvent {
   rows: number
 }
 
+export interface TerminalInfo {
+  terminal_id: string
+  cols: number
+  rows: number
+  worktree_path: string
+  alive: boolean
+}
+
 export interface TerminalStoppedEvent {
   terminal_id: string
   exit_code: number | null